@@ -0,0 +1,99 @@
+//! Alerts
+//!
+//! Events worth hearing about while the TUI sits in a background pane can
+//! ring the terminal bell, flash the status bar, or both, each as set in
+//! `[alerts]`. The bell is rung by the event loop, which owns the terminal.
+
+use std::time::{Duration, Instant};
+
+use crate::app::{
+    thinking::{Level, ThinkingEntry},
+    AppState,
+};
+
+/// How long a flash keeps the status bar lit
+const FLASH: Duration = Duration::from_millis(800);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AlertEvent {
+    GenerationComplete,
+    Error,
+    Budget,
+}
+
+/// A bell waiting for the event loop, and the status bar's flash
+#[derive(Clone, Debug, Default)]
+pub struct Alerts {
+    pub bell: bool,
+    flash_until: Option<Instant>,
+}
+
+impl Alerts {
+    pub fn flashing(&self, now: Instant) -> bool {
+        self.flash_until.is_some_and(|until| now < until)
+    }
+}
+
+impl AppState {
+    /// Ring and/or flash, as `[alerts]` has it for `event`
+    pub fn alert(&mut self, event: AlertEvent) {
+        let config = &self.config.alerts;
+        let alert = match event {
+            AlertEvent::GenerationComplete => config.generation_complete,
+            AlertEvent::Error => config.error,
+            AlertEvent::Budget => config.budget,
+        };
+        if alert.bell() {
+            self.alerts.bell = true;
+        }
+        if alert.flash() {
+            self.alerts.flash_until = Some(Instant::now() + FLASH);
+        }
+    }
+
+    /// Add a response's cost to the session's spend, warning once it
+    /// passes `[alerts] budget_usd`
+    pub fn add_cost(&mut self, cost: f64) {
+        let before = self.total_cost;
+        self.total_cost += cost;
+        let Some(budget) = self.config.alerts.budget_usd else { return };
+        if before < budget && self.total_cost >= budget {
+            self.add_thinking(
+                ThinkingEntry::system(format!(
+                    "Session spend passed the ${:.2} budget (now ${:.4})",
+                    budget, self.total_cost
+                ))
+                .with_level(Level::Warning),
+            );
+            self.alert(AlertEvent::Budget);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::config::Alert;
+
+    #[test]
+    fn test_budget_alerts_once_when_crossed() {
+        let mut state = AppState::default();
+        state.config.alerts.budget = Alert::Both;
+        state.config.alerts.budget_usd = Some(0.05);
+
+        state.add_cost(0.03);
+        assert!(!state.alerts.bell && !state.alerts.flashing(Instant::now()));
+
+        state.add_cost(0.03);
+        assert!(state.alerts.bell && state.alerts.flashing(Instant::now()));
+        assert!(state.thinking_log.last().unwrap().text.contains("$0.05 budget"));
+
+        state.alerts.bell = false;
+        state.add_cost(0.03);
+        assert!(!state.alerts.bell);
+
+        // Off by default
+        state.alert(AlertEvent::Error);
+        assert!(!state.alerts.bell);
+    }
+}
@@ -0,0 +1,159 @@
+//! Generation Annotations
+//!
+//! Every response appended to the Generation pane is recorded as a region
+//! of `generated_code` together with the conversation turn that asked for
+//! it. The pane draws a gutter marking each region with its turn number;
+//! clicking the gutter, or `{` / `}` with the pane focused, selects a
+//! region and jumps to its prompt in the Thinking pane, highlighted.
+
+use std::ops::Range;
+
+use crate::app::{regeneration::RegenerationDiff, AppState};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GenerationRegion {
+    /// Byte range of `generated_code`
+    pub bytes: Range<usize>,
+    /// Index into `conversation`
+    pub turn: usize,
+    /// What changed from the previous response, when this one redoes it
+    pub changes: Option<RegenerationDiff>,
+}
+
+impl AppState {
+    /// Append a response to the Generation pane, remembering which turn
+    /// produced it
+    pub fn append_generation_for(&mut self, turn: usize, text: &str) {
+        let start = self.generated_code.len();
+        let changes = self
+            .generation_regions
+            .last()
+            .and_then(|previous| super::regeneration::diff(&self.generated_code[previous.bytes.clone()], text));
+        self.append_generation(text);
+        self.generation_regions.push(GenerationRegion {
+            bytes: start..self.generated_code.len(),
+            turn,
+            changes,
+        });
+    }
+
+    /// Forget the regions, for when the Generation pane is cleared or replaced
+    pub fn clear_generation_regions(&mut self) {
+        self.generation_regions.clear();
+        self.selected_region = None;
+        self.highlighted_entry = None;
+    }
+
+    /// Source lines (as `str::lines` counts them) each region covers
+    pub fn region_lines(&self) -> Vec<Range<usize>> {
+        let code = &self.generated_code;
+        let line_of = |byte: usize| code.as_bytes()[..byte.min(code.len())].iter().filter(|&&b| b == b'\n').count();
+        self.generation_regions
+            .iter()
+            .map(|region| {
+                let start = line_of(region.bytes.start);
+                // A trailing newline ends the region's last line
+                let end = line_of(region.bytes.end.saturating_sub(1)) + 1;
+                start..end.max(start + 1)
+            })
+            .collect()
+    }
+
+    /// Region covering source line `line`; the latest wins where a
+    /// response continued the previous one's last line
+    pub fn region_at_line(&self, line: usize) -> Option<usize> {
+        self.region_lines().iter().rposition(|lines| lines.contains(&line))
+    }
+
+    /// Select a region and highlight the prompt that produced it; the
+    /// Thinking log entry of that prompt, if it is still there
+    pub fn select_region(&mut self, index: usize) -> Option<usize> {
+        let region = self.generation_regions.get(index)?;
+        self.selected_region = Some(index);
+        self.highlighted_entry = self.conversation.get(region.turn).and_then(|t| t.prompt_entry);
+        self.highlighted_index()
+    }
+
+    /// Select the next (or previous) region, wrapping around
+    pub fn cycle_region(&mut self, forward: bool) -> Option<usize> {
+        let count = self.generation_regions.len();
+        if count == 0 {
+            return None;
+        }
+        let next = match (self.selected_region, forward) {
+            (None, true) => 0,
+            (None, false) => count - 1,
+            (Some(i), true) => (i + 1) % count,
+            (Some(i), false) => (i + count - 1) % count,
+        };
+        self.select_region(next);
+        Some(next)
+    }
+
+    /// Where the highlighted prompt is in the Thinking log, if it hasn't
+    /// been trimmed or cleared
+    pub fn highlighted_index(&self) -> Option<usize> {
+        let id = self.highlighted_entry?;
+        self.thinking_log.iter().position(|e| e.id == id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{thinking::ThinkingEntry, ConversationTurn};
+
+    /// Log `prompt` and add its turn
+    fn ask(state: &mut AppState, prompt: &str) {
+        state.add_thinking(ThinkingEntry::user(prompt.to_string()));
+        state.conversation.push(ConversationTurn {
+            response: Some(String::new()),
+            prompt_entry: state.thinking_log.last().map(|e| e.id),
+            ..ConversationTurn::new(prompt, "gpt-4o")
+        });
+    }
+
+    #[test]
+    fn test_regions_map_lines_to_prompts() {
+        let mut state = AppState::default();
+        for (i, prompt) in ["write a parser", "add tests", "write a parser"].iter().enumerate() {
+            ask(&mut state, prompt);
+            state.add_thinking(ThinkingEntry::system("Dispatching to IMS Core..."));
+            state.append_generation_for(i, &format!("// {}\nfn f{}() {{}}\n", prompt, i));
+        }
+
+        assert_eq!(state.region_lines(), [0..2, 2..4, 4..6]);
+        assert_eq!(state.region_at_line(3), Some(1));
+        assert_eq!(state.region_at_line(6), None);
+
+        // The repeated prompt resolves to its own entry
+        assert_eq!(state.select_region(2), Some(4));
+        assert_eq!(state.select_region(0), Some(0));
+        assert_eq!(state.cycle_region(false), Some(2));
+        assert_eq!(state.highlighted_index(), Some(4));
+
+        state.clear_generation_regions();
+        assert_eq!(state.cycle_region(true), None);
+    }
+
+    #[test]
+    fn test_highlight_follows_trimmed_log() {
+        let mut state = AppState::default();
+        ask(&mut state, "write a parser");
+        state.append_generation_for(0, "fn parse() {}\n");
+        for i in 0..1000 {
+            state.add_thinking(ThinkingEntry::system(format!("line {}", i)));
+        }
+        // The oldest 100 entries were trimmed, taking the prompt with them
+        assert_eq!(state.select_region(0), None);
+
+        ask(&mut state, "add tests");
+        state.append_generation_for(1, "#[test]\nfn t() {}\n");
+        let before = state.select_region(1).unwrap();
+        for i in 0..100 {
+            state.add_thinking(ThinkingEntry::system(format!("more {}", i)));
+        }
+        assert_eq!(state.highlighted_index(), Some(before - 100));
+        assert_eq!(state.thinking_log[before - 100].text, "add tests");
+    }
+}
@@ -0,0 +1,278 @@
+//! Typed API Errors
+//!
+//! Failed requests are classified from the HTTP status and the backend's
+//! error body (FastAPI `detail`, or a generic `error`/`message` field), so
+//! the message says what to do about it rather than just the status code.
+//! API client methods return these inside `anyhow::Error`; callers that
+//! need to react to a specific kind can `downcast_ref::<ApiError>()`.
+//!
+//! A 429's wait comes from the `Retry-After` header (seconds or an HTTP
+//! date), or failing that from the body: a `retry_after` field, or a
+//! vendor message like "Please try again in 20s".
+
+use regex::Regex;
+use reqwest::StatusCode;
+use serde_json::Value;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Longest raw body quoted when it isn't a recognised error shape
+const MAX_DETAIL: usize = 200;
+
+/// Longest wait taken from a 429; anything the server asks for beyond
+/// this is capped rather than trusted
+pub const MAX_RETRY_AFTER: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Clone, Debug, PartialEq, thiserror::Error)]
+pub enum ApiError {
+    #[error("unauthorized ({status}){}: set ADMIN_API_KEY to a valid admin key", detail_suffix(.detail))]
+    Unauthorized { status: u16, detail: String },
+    #[error("rate limited{}", match .retry_after {
+        Some(after) => format!(", retry in {}s", after.as_secs()),
+        None => ": wait a moment before retrying".to_string(),
+    })]
+    RateLimited { retry_after: Option<Duration> },
+    #[error("model not found{}: pick another from the model registry (m)", detail_suffix(.detail))]
+    ModelNotFound { detail: String },
+    #[error("invalid request: {detail}")]
+    Validation { detail: String },
+    #[error("IMS Core unreachable ({0}): is the backend running at the configured base_url?")]
+    Network(String),
+    #[error("request timed out: the backend or model is slow, try again or pick a faster model")]
+    Timeout,
+    #[error("server error ({status}){}", detail_suffix(.detail))]
+    Server { status: u16, detail: String },
+}
+
+fn detail_suffix(detail: &str) -> String {
+    if detail.is_empty() {
+        String::new()
+    } else {
+        format!(" - {}", detail)
+    }
+}
+
+impl ApiError {
+    /// Classify a non-success response; `model_request` marks endpoints
+    /// where a 404 means the model id is unknown
+    pub fn from_response(status: StatusCode, retry_after: Option<Duration>, body: &str, model_request: bool) -> Self {
+        let detail = error_detail(body);
+        match status.as_u16() {
+            401 | 403 => ApiError::Unauthorized {
+                status: status.as_u16(),
+                detail,
+            },
+            429 => ApiError::RateLimited {
+                retry_after: retry_after.or_else(|| body_retry_after(body)),
+            },
+            404 if model_request => ApiError::ModelNotFound { detail },
+            400 | 422 => ApiError::Validation {
+                detail: if detail.is_empty() { status.to_string() } else { detail },
+            },
+            _ => ApiError::Server {
+                status: status.as_u16(),
+                detail,
+            },
+        }
+    }
+
+    pub fn from_transport(error: &reqwest::Error) -> Self {
+        if error.is_timeout() {
+            ApiError::Timeout
+        } else {
+            ApiError::Network(transport_cause(error))
+        }
+    }
+}
+
+/// Parse a `Retry-After` header: delay seconds, or an HTTP date
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs).min(MAX_RETRY_AFTER));
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    // A date already past means "now"
+    let wait = (at.with_timezone(&chrono::Utc) - chrono::Utc::now()).to_std().unwrap_or_default();
+    Some(wait.min(MAX_RETRY_AFTER))
+}
+
+/// `secs` as a wait, capped at `MAX_RETRY_AFTER` (too big to represent
+/// counts as over the cap); `None` if negative or not a number
+fn capped_wait(secs: f64) -> Option<Duration> {
+    if secs.is_nan() || secs < 0.0 {
+        return None;
+    }
+    Some(Duration::try_from_secs_f64(secs).map_or(MAX_RETRY_AFTER, |wait| wait.min(MAX_RETRY_AFTER)))
+}
+
+/// "try again in 1.5s", "retry after 20 seconds", "try again in 300ms"
+fn retry_hint() -> &'static Regex {
+    static RETRY_HINT: OnceLock<Regex> = OnceLock::new();
+    RETRY_HINT.get_or_init(|| {
+        Regex::new(r"(?i)(?:try again|retry) (?:in|after) (\d+(?:\.\d+)?)\s*(ms|milliseconds?|s|secs?|seconds?|m|mins?|minutes?)?\b")
+            .expect("retry hint pattern is valid")
+    })
+}
+
+/// Wait suggested by a 429 body: a `retry_after` field (seconds), at the
+/// top level or under `detail`/`error`, or a retry hint in its message
+pub fn body_retry_after(body: &str) -> Option<Duration> {
+    if let Ok(value) = serde_json::from_str::<Value>(body) {
+        let field = [Some(&value), value.get("detail"), value.get("error")]
+            .into_iter()
+            .flatten()
+            .find_map(|v| v.get("retry_after"));
+        let secs = match field {
+            Some(Value::Number(n)) => n.as_f64(),
+            Some(Value::String(s)) => s.trim().parse().ok(),
+            _ => None,
+        };
+        if let Some(wait) = secs.and_then(capped_wait) {
+            return Some(wait);
+        }
+    }
+    let hint = retry_hint().captures(body)?;
+    let amount: f64 = hint[1].parse().ok()?;
+    let secs = match hint.get(2).map(|unit| unit.as_str().to_lowercase()) {
+        Some(unit) if unit.starts_with("ms") || unit.starts_with("milli") => amount / 1000.0,
+        Some(unit) if unit.starts_with('m') => amount * 60.0,
+        _ => amount,
+    };
+    capped_wait(secs)
+}
+
+/// Innermost cause of a transport error, leaving out the request URL
+/// (which may carry query secrets)
+fn transport_cause(error: &reqwest::Error) -> String {
+    let mut cause = None;
+    let mut source = std::error::Error::source(error);
+    while let Some(inner) = source {
+        cause = Some(inner.to_string());
+        source = inner.source();
+    }
+    cause.unwrap_or_else(|| {
+        if error.is_connect() {
+            "connection failed".to_string()
+        } else {
+            "request failed".to_string()
+        }
+    })
+}
+
+/// Human-readable detail from an error body
+pub fn error_detail(body: &str) -> String {
+    let Ok(value) = serde_json::from_str::<Value>(body) else {
+        return truncate(body.trim());
+    };
+    let field = ["detail", "error", "message"].iter().find_map(|key| value.get(*key));
+    match field {
+        Some(Value::String(s)) => s.clone(),
+        // FastAPI validation errors: [{"loc": ["body", "prompt"], "msg": "field required"}]
+        Some(Value::Array(items)) => items
+            .iter()
+            .map(|item| {
+                let msg = item.get("msg").and_then(Value::as_str).unwrap_or("invalid");
+                let loc: Vec<String> = item
+                    .get("loc")
+                    .and_then(Value::as_array)
+                    .map(|loc| {
+                        loc.iter()
+                            .filter(|part| part.as_str() != Some("body"))
+                            .map(|part| part.as_str().map(str::to_string).unwrap_or_else(|| part.to_string()))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                if loc.is_empty() {
+                    msg.to_string()
+                } else {
+                    format!("{}: {}", loc.join("."), msg)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("; "),
+        Some(Value::Object(inner)) => inner
+            .get("message")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| truncate(&Value::Object(inner.clone()).to_string())),
+        _ => truncate(body.trim()),
+    }
+}
+
+fn truncate(text: &str) -> String {
+    if text.chars().count() <= MAX_DETAIL {
+        text.to_string()
+    } else {
+        text.chars().take(MAX_DETAIL - 1).chain(['…']).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classifies_status_and_body() {
+        let err = ApiError::from_response(StatusCode::UNAUTHORIZED, None, r#"{"detail": "Invalid admin key"}"#, false);
+        assert_eq!(
+            err,
+            ApiError::Unauthorized {
+                status: 401,
+                detail: "Invalid admin key".to_string()
+            }
+        );
+        assert!(err.to_string().contains("ADMIN_API_KEY"));
+
+        let err = ApiError::from_response(StatusCode::TOO_MANY_REQUESTS, Some(Duration::from_secs(30)), "", false);
+        assert_eq!(err.to_string(), "rate limited, retry in 30s");
+        let err = ApiError::from_response(StatusCode::TOO_MANY_REQUESTS, None, r#"{"detail": {"retry_after": 12}}"#, false);
+        assert_eq!(err, ApiError::RateLimited { retry_after: Some(Duration::from_secs(12)) });
+
+        let err = ApiError::from_response(StatusCode::NOT_FOUND, None, r#"{"detail": "Model gpt-9 not found"}"#, true);
+        assert!(matches!(err, ApiError::ModelNotFound { .. }));
+        let err = ApiError::from_response(StatusCode::NOT_FOUND, None, "", false);
+        assert!(matches!(err, ApiError::Server { status: 404, .. }));
+    }
+
+    #[test]
+    fn test_fastapi_validation_detail() {
+        let body = r#"{"detail": [
+            {"loc": ["body", "prompt"], "msg": "field required", "type": "value_error.missing"},
+            {"loc": ["body", "temperature"], "msg": "ensure this value is less than or equal to 2"}
+        ]}"#;
+        let err = ApiError::from_response(StatusCode::UNPROCESSABLE_ENTITY, None, body, true);
+        assert_eq!(
+            err.to_string(),
+            "invalid request: prompt: field required; temperature: ensure this value is less than or equal to 2"
+        );
+
+        assert_eq!(error_detail(r#"{"error": {"message": "quota exceeded"}}"#), "quota exceeded");
+        assert_eq!(error_detail("Bad Gateway"), "Bad Gateway");
+    }
+
+    #[test]
+    fn test_retry_after_header_and_body_hints() {
+        assert_eq!(parse_retry_after(" 120 "), Some(Duration::from_secs(120)));
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), Some(Duration::ZERO));
+        let soon = (chrono::Utc::now() + chrono::Duration::seconds(90)).to_rfc2822();
+        let wait = parse_retry_after(&soon).unwrap();
+        assert!(wait > Duration::from_secs(85) && wait <= Duration::from_secs(90));
+        assert_eq!(parse_retry_after("soon"), None);
+
+        assert_eq!(body_retry_after(r#"{"retry_after": "2.5"}"#), Some(Duration::from_millis(2500)));
+        assert_eq!(
+            body_retry_after(r#"{"error": {"message": "Rate limit reached. Please try again in 20s."}}"#),
+            Some(Duration::from_secs(20))
+        );
+        assert_eq!(body_retry_after("Retry after 2 minutes"), Some(Duration::from_secs(120)));
+        assert_eq!(body_retry_after("try again in 300ms"), Some(Duration::from_millis(300)));
+        assert_eq!(body_retry_after(r#"{"error": "Rate limit exceeded: 100 per 1 minute"}"#), None);
+
+        // Whatever the server asks for, the wait is capped
+        assert_eq!(body_retry_after(r#"{"retry_after": 1e30}"#), Some(MAX_RETRY_AFTER));
+        assert_eq!(body_retry_after(&format!("try again in {}s", "9".repeat(400))), Some(MAX_RETRY_AFTER));
+        assert_eq!(parse_retry_after(&u64::MAX.to_string()), Some(MAX_RETRY_AFTER));
+        assert_eq!(body_retry_after(r#"{"retry_after": -3}"#), None);
+    }
+}
@@ -0,0 +1,128 @@
+//! Admin API Key Entry
+//!
+//! When IMS Core rejects a request as unauthorized (401/403), a masked
+//! input asks for the admin key. The key replaces the client's for the rest
+//! of the session (it is never written to disk) and the rejected requests
+//! are sent again with it.
+
+use crate::app::{
+    api::{ModelResponse, PricingUpdate},
+    overlay::Overlay,
+    registry::AdminAction,
+    AppState, InFlightPrompt,
+};
+
+/// A rejected request that can be sent again once a key is entered
+#[derive(Clone, Debug)]
+pub enum RetryRequest {
+    /// Resent with the same idempotency key
+    Prompt { request: InFlightPrompt, prompt_text: String },
+    /// `previous` is the registry entry before the optimistic update
+    Admin {
+        action: AdminAction,
+        pricing: Option<PricingUpdate>,
+        previous: ModelResponse,
+    },
+}
+
+impl RetryRequest {
+    pub fn label(&self) -> String {
+        match self {
+            RetryRequest::Prompt { request, .. } => format!("Prompt to {}", request.model_id),
+            RetryRequest::Admin { action, previous, .. } => format!("{}: {}", action.label(), previous.model_id),
+        }
+    }
+}
+
+/// Masked key input with the requests waiting on it
+#[derive(Clone, Debug, Default)]
+pub struct ApiKeyPrompt {
+    pub input: String,
+    /// Why the key is being asked for
+    pub error: String,
+    pub retries: Vec<RetryRequest>,
+}
+
+impl ApiKeyPrompt {
+    /// The input as shown: one bullet per character
+    pub fn masked(&self) -> String {
+        "•".repeat(self.input.chars().count())
+    }
+}
+
+impl AppState {
+    /// Ask for an admin key, queueing `retry` behind any request already waiting
+    pub fn request_api_key(&mut self, error: String, retry: RetryRequest) {
+        self.add_debug_log(format!("{} rejected: {}", retry.label(), error));
+        let prompt = self.api_key_prompt.get_or_insert_with(ApiKeyPrompt::default);
+        prompt.error = error;
+        prompt.retries.push(retry);
+        self.open_overlay(Overlay::ApiKey);
+    }
+
+    /// Ask for an admin key with nothing to retry, explaining why in `error`
+    pub fn ask_api_key(&mut self, error: String) {
+        self.api_key_prompt = Some(ApiKeyPrompt {
+            error,
+            ..Default::default()
+        });
+        self.open_overlay(Overlay::ApiKey);
+    }
+
+    /// Close the prompt, returning the entered key and the requests to resend
+    /// (`None` while the input is blank)
+    pub fn submit_api_key(&mut self) -> Option<(String, Vec<RetryRequest>)> {
+        let key = self.api_key_prompt.as_ref()?.input.trim().to_string();
+        if key.is_empty() {
+            return None;
+        }
+        let prompt = self.api_key_prompt.take()?;
+        self.close_overlay(Overlay::ApiKey);
+        Some((key, prompt.retries))
+    }
+
+    /// Run when the prompt is closed without a key: roll back waiting
+    /// admin changes
+    pub(super) fn cancel_api_key_prompt(&mut self) {
+        let Some(prompt) = self.api_key_prompt.take() else { return };
+        for retry in prompt.retries {
+            if let RetryRequest::Admin { previous, .. } = &retry {
+                self.model_registry.replace_model(previous.clone());
+            }
+            self.add_debug_log(format!("{} not retried (no API key entered)", retry.label()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prompt(model_id: &str) -> RetryRequest {
+        RetryRequest::Prompt {
+            request: InFlightPrompt::new("hello".to_string(), model_id.to_string()),
+            prompt_text: "hello".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_requests_queue_until_a_key_is_entered() {
+        let mut state = AppState::default();
+        state.request_api_key("unauthorized (401)".to_string(), prompt("gpt-4o"));
+        state.request_api_key("unauthorized (403)".to_string(), prompt("gemini-pro"));
+
+        let pending = state.api_key_prompt.as_mut().unwrap();
+        assert_eq!(pending.error, "unauthorized (403)");
+        pending.input = "  ".to_string();
+        assert!(state.submit_api_key().is_none());
+        assert!(state.api_key_prompt.is_some());
+
+        state.api_key_prompt.as_mut().unwrap().input = "s3cret ".to_string();
+        assert_eq!(state.api_key_prompt.as_ref().unwrap().masked(), "•••••••");
+        let (key, retries) = state.submit_api_key().unwrap();
+        assert_eq!(key, "s3cret");
+        assert_eq!(retries.len(), 2);
+        assert!(state.api_key_prompt.is_none());
+        assert_eq!(state.overlays.top(), None);
+    }
+}
@@ -0,0 +1,184 @@
+//! Audio
+//!
+//! Audio files attached from the Explorer (`+`) are sent base64-encoded in
+//! the request's `audio` for backends that transcribe or listen to them.
+//! They are checked when attached, so a file the backend would reject is
+//! never uploaded: a supported format whose header matches its extension,
+//! under `MAX_AUDIO_BYTES`. Upload progress is reported in the Thinking
+//! pane, and transcripts returned with the response are shown there too.
+//! Local model servers take no audio; it is left out for them.
+
+use anyhow::{bail, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::Path;
+
+use crate::app::{thinking::ThinkingEntry, AppState};
+
+/// Largest audio file sent (the usual transcription API limit)
+pub const MAX_AUDIO_BYTES: u64 = 25 * 1024 * 1024;
+
+/// Media type by file extension
+const FORMATS: &[(&str, &str)] = &[
+    ("mp3", "audio/mpeg"),
+    ("wav", "audio/wav"),
+    ("m4a", "audio/mp4"),
+    ("ogg", "audio/ogg"),
+    ("flac", "audio/flac"),
+    ("webm", "audio/webm"),
+];
+
+/// Audio extensions that are recognized but can't be sent
+const UNSUPPORTED: &[&str] = &["aac", "aiff", "amr", "wma", "opus", "mid", "midi"];
+
+/// The media type of an audio file, by extension
+pub fn media_type(path: &Path) -> Option<&'static str> {
+    let ext = extension(path)?;
+    FORMATS.iter().find(|(e, _)| *e == ext).map(|(_, media)| *media)
+}
+
+fn extension(path: &Path) -> Option<String> {
+    Some(path.extension()?.to_str()?.to_ascii_lowercase())
+}
+
+/// An audio file, supported or not
+pub fn is_audio(path: &Path) -> bool {
+    media_type(path).is_some() || extension(path).is_some_and(|ext| UNSUPPORTED.contains(&ext.as_str()))
+}
+
+/// Whether the first bytes of a file look like `media_type`
+fn header_matches(media_type: &str, header: &[u8]) -> bool {
+    match media_type {
+        "audio/mpeg" => header.starts_with(b"ID3") || (header.len() > 1 && header[0] == 0xFF && header[1] & 0xE0 == 0xE0),
+        "audio/wav" => header.starts_with(b"RIFF") && header.get(8..12) == Some(b"WAVE"),
+        "audio/mp4" => header.get(4..8) == Some(b"ftyp"),
+        "audio/ogg" => header.starts_with(b"OggS"),
+        "audio/flac" => header.starts_with(b"fLaC"),
+        "audio/webm" => header.starts_with(&[0x1A, 0x45, 0xDF, 0xA3]),
+        _ => false,
+    }
+}
+
+/// Check an audio file can be sent; its media type if so
+pub fn validate(path: &Path) -> Result<&'static str> {
+    let name = path.display();
+    let Some(media_type) = media_type(path) else {
+        let supported: Vec<_> = FORMATS.iter().map(|(ext, _)| *ext).collect();
+        bail!("{} is not a supported audio format ({})", name, supported.join(", "));
+    };
+    let size = std::fs::metadata(path).with_context(|| format!("Failed to read {}", name))?.len();
+    if size > MAX_AUDIO_BYTES {
+        bail!(
+            "{} is {} MB, over the {} MB limit",
+            name,
+            size / (1024 * 1024),
+            MAX_AUDIO_BYTES / (1024 * 1024)
+        );
+    }
+    let mut header = Vec::with_capacity(12);
+    std::fs::File::open(path)
+        .and_then(|file| file.take(12).read_to_end(&mut header))
+        .with_context(|| format!("Failed to read {}", name))?;
+    if !header_matches(media_type, &header) {
+        bail!("{} doesn't look like {} audio", name, media_type);
+    }
+    Ok(media_type)
+}
+
+/// An attached audio file as sent with a prompt
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AudioInput {
+    pub name: String,
+    pub media_type: String,
+    /// Base64 of the file
+    pub data: String,
+}
+
+impl AudioInput {
+    pub fn load(path: &Path) -> Result<Self> {
+        let media_type = validate(path)?;
+        let bytes = std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        Ok(Self {
+            name: path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default(),
+            media_type: media_type.to_string(),
+            data: STANDARD.encode(bytes),
+        })
+    }
+
+    /// Size of the file, before encoding
+    pub fn size(&self) -> usize {
+        let padding = self.data.bytes().rev().take_while(|b| *b == b'=').count();
+        self.data.len() / 4 * 3 - padding
+    }
+}
+
+/// What was said in an attached audio file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Transcript {
+    /// The file it came from
+    #[serde(default)]
+    pub name: String,
+    pub text: String,
+}
+
+/// `3.2 MB`, `640 KB`
+pub fn format_size(bytes: usize) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else {
+        format!("{} KB", bytes.div_ceil(1024))
+    }
+}
+
+impl AppState {
+    /// Show what was said in each attached audio file
+    pub fn record_transcripts(&mut self, transcripts: &[Transcript]) {
+        for transcript in transcripts {
+            let name = if transcript.name.is_empty() { "audio" } else { &transcript.name };
+            self.add_thinking(ThinkingEntry::agent(format!("🎙 Transcript of {}:\n{}", name, transcript.text.trim())));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validation() {
+        let dir = std::env::temp_dir().join(format!("ims-audio-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let write = |name: &str, bytes: &[u8]| {
+            let path = dir.join(name);
+            std::fs::write(&path, bytes).unwrap();
+            path
+        };
+
+        let wav = write("standup.WAV", b"RIFF\x24\x00\x00\x00WAVEfmt ");
+        assert_eq!(validate(&wav).unwrap(), "audio/wav");
+        let input = AudioInput::load(&wav).unwrap();
+        assert_eq!((input.name.as_str(), input.size()), ("standup.WAV", 16));
+
+        let renamed = write("notes.mp3", b"PK\x03\x04 not audio");
+        assert_eq!(
+            validate(&renamed).unwrap_err().to_string(),
+            format!("{} doesn't look like audio/mpeg audio", renamed.display())
+        );
+        let aac = write("memo.aac", b"\xFF\xF1");
+        assert!(is_audio(&aac));
+        assert!(validate(&aac).unwrap_err().to_string().contains("not a supported audio format"));
+        assert!(!is_audio(&dir.join("main.rs")));
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(format_size(3_355_443), "3.2 MB");
+        assert_eq!(format_size(1000), "1 KB");
+
+        let mut state = AppState::default();
+        state.record_transcripts(&[Transcript {
+            name: "standup.wav".to_string(),
+            text: "Ship it on Friday.\n".to_string(),
+        }]);
+        assert_eq!(state.thinking_log[0].text, "🎙 Transcript of standup.wav:\nShip it on Friday.");
+    }
+}
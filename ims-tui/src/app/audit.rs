@@ -0,0 +1,175 @@
+//! Audit Trail
+//!
+//! Every prompt sent to the Action Gateway appends one line to
+//! `.ims/audit.jsonl`: who sent it, when, to which model, a SHA-256 of
+//! the prompt as sent (never the prompt itself), whether policies were
+//! bypassed and what it cost. The file is only ever appended to, so teams
+//! sharing a terminal can account for AI usage after the fact; "Audit:
+//! View Trail" shows it.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, TimeZone};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::app::{
+    api::{ExecuteRequest, ExecuteResponse},
+    overlay::Overlay,
+    AppState,
+};
+
+/// Who sent a request without a `user_id`
+const UNKNOWN_USER: &str = "unknown";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    /// Unix seconds
+    pub timestamp: i64,
+    pub user_id: String,
+    pub model_id: String,
+    pub prompt_sha256: String,
+    pub bypass_policies: bool,
+    /// USD; zero for failed requests
+    pub cost: f64,
+    /// Why the request failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl AuditRecord {
+    pub fn new(request: &ExecuteRequest, outcome: &Result<ExecuteResponse>) -> Self {
+        Self {
+            timestamp: Local::now().timestamp(),
+            user_id: request.user_id.clone().unwrap_or_else(|| UNKNOWN_USER.to_string()),
+            model_id: request.model_id.clone(),
+            prompt_sha256: Sha256::digest(request.prompt.as_bytes())
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect(),
+            bypass_policies: request.bypass_policies,
+            cost: outcome.as_ref().map(|r| r.cost.total).unwrap_or(0.0),
+            error: outcome.as_ref().err().map(|e| format!("{:#}", e)),
+        }
+    }
+
+    pub fn time(&self) -> DateTime<Local> {
+        Local
+            .timestamp_opt(self.timestamp, 0)
+            .single()
+            .unwrap_or_else(Local::now)
+    }
+}
+
+/// The append-only audit file
+#[derive(Clone, Debug)]
+pub struct AuditLog {
+    path: PathBuf,
+}
+
+impl AuditLog {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Add `record` as one line at the end of the file
+    pub fn append(&self, record: &AuditRecord) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .with_context(|| format!("Failed to append to {}", self.path.display()))
+    }
+}
+
+/// Where the audit trail is kept
+pub fn audit_path() -> PathBuf {
+    crate::app::ims_dir().join("audit.jsonl")
+}
+
+/// Records from the audit file, oldest first; malformed lines are skipped
+pub fn load(path: &Path) -> Result<Vec<AuditRecord>> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    };
+    Ok(text
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Audit trail view
+#[derive(Clone, Debug, Default)]
+pub struct AuditView {
+    pub records: Vec<AuditRecord>,
+    pub scroll: usize,
+}
+
+impl AppState {
+    /// Load the audit trail and show it
+    pub fn open_audit(&mut self) {
+        match load(&audit_path()) {
+            Ok(records) => {
+                self.audit_view = AuditView { records, scroll: 0 };
+                self.open_overlay(Overlay::Audit);
+            }
+            Err(e) => self.add_debug_log(format!("Failed to load audit trail: {:#}", e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::api::ImsApiClient;
+
+    fn request(prompt: &str, bypass_policies: bool) -> ExecuteRequest {
+        ExecuteRequest {
+            prompt: prompt.to_string(),
+            model_id: "gpt-4o".to_string(),
+            max_tokens: None,
+            temperature: 0.7,
+            system_instruction: None,
+            user_id: None,
+            bypass_policies,
+            idempotency_key: None,
+            images: Vec::new(),
+            audio: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_every_request_is_appended() {
+        let path = std::env::temp_dir().join(format!("ims-tui-audit-{}", uuid::Uuid::new_v4())).join("audit.jsonl");
+        let client = ImsApiClient::new("http://localhost:8000".to_string(), None, true, &Default::default())
+            .unwrap()
+            .with_audit(AuditLog::new(path.clone()))
+            .with_user_id("dana".to_string());
+
+        client.execute_prompt(request("Explain lifetimes", false)).await.unwrap();
+        client.execute_prompt(request("Explain lifetimes", true)).await.unwrap();
+        let failed = AuditRecord::new(&request("hi", false), &Err(anyhow::anyhow!("timed out")));
+        AuditLog::new(path.clone()).append(&failed).unwrap();
+
+        let records = load(&path).unwrap();
+        std::fs::remove_dir_all(path.parent().unwrap()).ok();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].user_id, "dana");
+        assert_eq!(records[0].prompt_sha256, records[1].prompt_sha256);
+        assert_eq!(records[0].prompt_sha256.len(), 64);
+        assert!(!records[0].bypass_policies);
+        assert!(records[1].bypass_policies);
+        assert!(records[1].cost > 0.0);
+        assert_eq!(records[2].error.as_deref(), Some("timed out"));
+        assert_eq!(records[2].user_id, UNKNOWN_USER);
+    }
+}
@@ -0,0 +1,341 @@
+//! Batch Prompts
+//!
+//! Runs the prompt in the input box against every file marked in the
+//! Explorer (Space), one request per file using that file's model. Each
+//! file's request waits in the prompt queue at low priority, so prompts
+//! typed meanwhile go first, and at most `[batch] concurrency` of them are
+//! in flight; progress is applied to `AppState::batch` as each file starts
+//! and finishes, and a summary is logged once all are done. Each response
+//! is saved to History.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::app::{
+    api::ExecuteResponse,
+    history::{self, GenerationRecord},
+    metrics_store::RequestRecord,
+    prompt_queue::Priority,
+    thinking::{Level, ThinkingEntry},
+    AppState, FileNode, InFlightPrompt,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ItemStatus {
+    Pending,
+    Running,
+    Done { tokens: u32, cost: f64 },
+    Failed(String),
+}
+
+#[derive(Clone, Debug)]
+pub struct BatchItem {
+    pub path: PathBuf,
+    pub model_id: String,
+    pub status: ItemStatus,
+}
+
+/// Everything a batch run needs, captured when it is started
+#[derive(Clone, Debug)]
+pub struct BatchJob {
+    pub prompt: String,
+    /// (file, model) pairs
+    pub files: Vec<(PathBuf, String)>,
+    pub concurrency: usize,
+}
+
+/// Progress of the current (or last) batch run
+#[derive(Clone, Debug)]
+pub struct Batch {
+    pub prompt: String,
+    pub items: Vec<BatchItem>,
+    /// Most items in flight at once
+    pub concurrency: usize,
+    /// Request key of each item's prompt → its index
+    keys: HashMap<String, usize>,
+}
+
+impl Batch {
+    pub fn new(job: &BatchJob) -> Self {
+        Self {
+            prompt: job.prompt.clone(),
+            concurrency: job.concurrency.max(1),
+            keys: HashMap::new(),
+            items: job
+                .files
+                .iter()
+                .map(|(path, model_id)| BatchItem {
+                    path: path.clone(),
+                    model_id: model_id.clone(),
+                    status: ItemStatus::Pending,
+                })
+                .collect(),
+        }
+    }
+
+    /// Files that have finished, successfully or not
+    pub fn finished(&self) -> usize {
+        self.items
+            .iter()
+            .filter(|i| matches!(i.status, ItemStatus::Done { .. } | ItemStatus::Failed(_)))
+            .count()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.finished() < self.items.len()
+    }
+
+    /// Whether the prompt with request key `key` is one of the batch's
+    pub fn sent(&self, key: &str) -> bool {
+        self.keys.contains_key(key)
+    }
+
+    /// Whether as many items are in flight as may be
+    pub fn slots_full(&self) -> bool {
+        self.items.iter().filter(|i| i.status == ItemStatus::Running).count() >= self.concurrency
+    }
+
+    /// One-line result once every file has finished
+    pub fn summary(&self) -> String {
+        let (mut ok, mut tokens, mut cost) = (0, 0u64, 0.0);
+        for item in &self.items {
+            if let ItemStatus::Done { tokens: t, cost: c } = item.status {
+                ok += 1;
+                tokens += u64::from(t);
+                cost += c;
+            }
+        }
+        let failed = self.items.len() - ok;
+        format!(
+            "Batch finished: {} of {} files succeeded{}, {} tokens, ${:.4}",
+            ok,
+            self.items.len(),
+            if failed > 0 { format!(" ({} failed)", failed) } else { String::new() },
+            crate::app::tokens::format_token_count(tokens),
+            cost
+        )
+    }
+}
+
+/// The request text for one file: its contents (as attachments are sent), then the prompt
+pub fn request_text(prompt: &str, path: &Path, content: &str) -> String {
+    format!("{}{}", crate::app::context::file_block(path, content), prompt)
+}
+
+impl AppState {
+    /// Mark or unmark the selected Explorer entry for batch runs; a
+    /// directory marks (or, when all are marked, unmarks) every file in it
+    pub fn toggle_mark_selected(&mut self) -> Option<String> {
+        fn files(node: &FileNode, out: &mut Vec<PathBuf>) {
+            if node.is_dir {
+                node.children.iter().for_each(|child| files(child, out));
+            } else {
+                out.push(node.path.clone());
+            }
+        }
+
+        let node = self.get_selected_node()?;
+        let name = node.name.clone();
+        let mut paths = Vec::new();
+        files(node, &mut paths);
+        if paths.iter().all(|p| self.marked_files.contains(p)) {
+            self.marked_files.retain(|p| !paths.contains(p));
+            Some(format!("Unmarked {}", name))
+        } else {
+            for path in paths {
+                if !self.marked_files.contains(&path) {
+                    self.marked_files.push(path);
+                }
+            }
+            Some(format!("Marked {} ({} files marked)", name, self.marked_files.len()))
+        }
+    }
+
+    #[cfg(test)]
+    pub fn is_marked(&self, path: &Path) -> bool {
+        self.marked_files.iter().any(|p| p == path)
+    }
+
+    /// Files to batch with the model each one uses in the Explorer
+    pub fn batch_files(&self) -> Vec<(PathBuf, String)> {
+        let fallback = self.session.as_ref().map(|s| s.model_id.clone()).unwrap_or("gpt-4o".to_string());
+        self.marked_files
+            .iter()
+            .map(|path| {
+                let model = Self::find_node_recursive(&self.file_tree, &path.to_string_lossy())
+                    .map(|n| n.model.clone())
+                    .filter(|m| !m.is_empty())
+                    .unwrap_or_else(|| fallback.clone());
+                (path.clone(), model)
+            })
+            .collect()
+    }
+
+    /// Queue a request for every file of `job`
+    pub fn start_batch(&mut self, job: &BatchJob) {
+        self.add_thinking(ThinkingEntry::user(format!("(batch × {}) {}", job.files.len(), job.prompt)));
+        self.add_thinking(ThinkingEntry::system(format!(
+            "Queued a batch over {} files at low priority, {} at a time",
+            job.files.len(),
+            job.concurrency
+        )));
+        let mut batch = Batch::new(job);
+        for (index, (path, model_id)) in job.files.iter().enumerate() {
+            let mut request = InFlightPrompt::new(job.prompt.clone(), model_id.clone());
+            request.inline_file = Some(path.clone());
+            batch.keys.insert(request.key.clone(), index);
+            self.prompt_queue.push(request, job.prompt.clone(), Priority::Low);
+        }
+        self.batch = Some(batch);
+    }
+
+    /// Mark the batch item sent as `key` running; whether it is one
+    pub fn batch_prompt_started(&mut self, key: &str) -> bool {
+        let Some(batch) = self.batch.as_mut() else { return false };
+        let Some(&index) = batch.keys.get(key) else { return false };
+        if let Some(item) = batch.items.get_mut(index) {
+            item.status = ItemStatus::Running;
+        }
+        true
+    }
+
+    /// Record how the batch item sent as `key` went; whether it is one.
+    /// A cached response was saved and paid for when first generated
+    pub fn batch_prompt_finished(&mut self, key: &str, result: Result<&ExecuteResponse, String>, cached: bool) -> bool {
+        let Some(batch) = self.batch.as_mut() else { return false };
+        let Some(index) = batch.keys.remove(key) else { return false };
+        let prompt = batch.prompt.clone();
+        let Some(item) = batch.items.get_mut(index) else { return true };
+        let name = item.path.display().to_string();
+        match result {
+            Ok(_) if cached => item.status = ItemStatus::Done { tokens: 0, cost: 0.0 },
+            Ok(response) => {
+                item.status = ItemStatus::Done {
+                    tokens: response.tokens.total,
+                    cost: response.cost.total,
+                };
+                let generation = GenerationRecord::from_response(&format!("[{}] {}", name, prompt), response);
+                if let Err(e) = history::save(&history::generations_dir(), &generation) {
+                    self.add_debug_log(format!("Failed to save generation: {:#}", e));
+                }
+                self.record_request(RequestRecord::from_response(response));
+            }
+            Err(error) => {
+                item.status = ItemStatus::Failed(error.clone());
+                self.add_thinking(ThinkingEntry::system(format!("✗ {}: {}", name, error)).with_level(Level::Error));
+            }
+        }
+
+        if let Some(batch) = self.batch.as_ref().filter(|b| !b.is_running()) {
+            let summary = batch.summary();
+            self.add_debug_log(summary.clone());
+            self.add_thinking(ThinkingEntry::system(format!("{} (responses saved to History)", summary)));
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_text_puts_file_before_prompt() {
+        let text = request_text("Add docs", Path::new("src/lib.rs"), "fn a() {}\n");
+        assert_eq!(text, "File: src/lib.rs\n```\nfn a() {}\n```\n\nAdd docs");
+    }
+
+    #[test]
+    fn test_mark_directory_marks_its_files() {
+        let mut state = AppState::default();
+        let mut dir = FileNode::new_dir(PathBuf::from("src"));
+        dir.children = vec![
+            FileNode::new_file(PathBuf::from("src/a.rs")),
+            FileNode::new_file(PathBuf::from("src/b.rs")),
+        ];
+        state.file_tree = vec![dir];
+        state.tree_state.borrow_mut().select(vec!["src".to_string()]);
+
+        state.toggle_mark_selected();
+        assert!(state.is_marked(Path::new("src/a.rs")) && state.is_marked(Path::new("src/b.rs")));
+        assert_eq!(state.batch_files()[1], (PathBuf::from("src/b.rs"), "gpt-4o".to_string()));
+        state.toggle_mark_selected();
+        assert!(state.marked_files.is_empty());
+    }
+
+    #[test]
+    fn test_progress_and_summary() {
+        let mut state = AppState::default();
+        let job = BatchJob {
+            prompt: "Add docs".to_string(),
+            files: vec![
+                (PathBuf::from("a.rs"), "gpt-4o".to_string()),
+                (PathBuf::from("b.rs"), "gpt-4o".to_string()),
+            ],
+            concurrency: 2,
+        };
+        state.start_batch(&job);
+        let batch = state.batch.as_mut().unwrap();
+        batch.items[0].status = ItemStatus::Done { tokens: 30, cost: 0.5 };
+        assert!(batch.is_running());
+        batch.items[1].status = ItemStatus::Failed("timeout".to_string());
+
+        assert!(!batch.is_running());
+        assert_eq!(batch.summary(), "Batch finished: 1 of 2 files succeeded (1 failed), 30 tokens, $0.5000");
+    }
+
+    #[test]
+    fn test_items_wait_behind_typed_prompts_and_finish_from_responses() {
+        use crate::core::dispatch::reduce_api;
+        use crate::app::api::ApiEvent;
+
+        let mut state = AppState::default();
+        state.start_batch(&BatchJob {
+            prompt: "Add docs".to_string(),
+            files: vec![
+                (PathBuf::from("a.rs"), "gpt-4o".to_string()),
+                (PathBuf::from("b.rs"), "gpt-4o".to_string()),
+            ],
+            concurrency: 1,
+        });
+        let typed = InFlightPrompt::new("Explain this".to_string(), "gpt-4o".to_string());
+        state.prompt_queue.push(typed, "Explain this".to_string(), Priority::Normal);
+        assert_eq!(state.prompt_queue.pop().unwrap().prompt_text, "Explain this");
+
+        let first = state.prompt_queue.pop().unwrap().request;
+        assert_eq!(first.inline_file.as_deref(), Some(Path::new("a.rs")));
+        assert!(state.batch_prompt_started(&first.key));
+        assert!(state.batch.as_ref().unwrap().slots_full());
+
+        reduce_api(
+            &mut state,
+            ApiEvent::GenerationComplete {
+                key: first.key.clone(),
+                response: Box::new(crate::testing::response("gpt-4o", "Done")),
+                cached: true,
+                content: "Done".to_string(),
+                postprocess_failures: Vec::new(),
+            },
+        );
+        let batch = state.batch.as_ref().unwrap();
+        assert_eq!(batch.items[0].status, ItemStatus::Done { tokens: 0, cost: 0.0 });
+        assert!(!batch.slots_full());
+        // Not answered in the conversation
+        assert!(state.conversation.is_empty());
+
+        let second = state.prompt_queue.pop().unwrap().request;
+        state.batch_prompt_started(&second.key);
+        reduce_api(
+            &mut state,
+            ApiEvent::GenerationFailed {
+                key: second.key,
+                model_id: "gpt-4o".to_string(),
+                latency_ms: 10.0,
+                error: "timeout".to_string(),
+            },
+        );
+        let batch = state.batch.as_ref().unwrap();
+        assert_eq!(batch.items[1].status, ItemStatus::Failed("timeout".to_string()));
+        assert!(!batch.is_running());
+    }
+}
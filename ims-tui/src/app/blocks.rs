@@ -0,0 +1,191 @@
+//! Code Block Extraction
+//!
+//! Pulls fenced code blocks out of model responses so they can be copied,
+//! saved, inserted into the open file or diffed against it from the
+//! Blocks popup.
+
+use similar::{ChangeTag, TextDiff};
+
+/// A fenced block from a response
+#[derive(Clone, Debug, PartialEq)]
+pub struct CodeBlock {
+    /// First word of the fence info string (```rust → "rust")
+    pub language: Option<String>,
+    pub code: String,
+}
+
+impl CodeBlock {
+    pub fn label(&self) -> &str {
+        self.language.as_deref().unwrap_or("text")
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.code.lines().count()
+    }
+}
+
+/// Collect every ``` or ~~~ fenced block in `content`, in order.
+/// An unterminated fence runs to the end of the response.
+pub fn extract_blocks(content: &str) -> Vec<CodeBlock> {
+    let mut blocks = Vec::new();
+    let mut open: Option<(&str, CodeBlock)> = None;
+
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        match &mut open {
+            Some((fence, block)) => {
+                if trimmed.starts_with(*fence) && trimmed.trim_start_matches(['`', '~']).trim().is_empty() {
+                    blocks.push(open.take().unwrap().1);
+                } else {
+                    block.code.push_str(line);
+                    block.code.push('\n');
+                }
+            }
+            None => {
+                let fence = if trimmed.starts_with("```") {
+                    "```"
+                } else if trimmed.starts_with("~~~") {
+                    "~~~"
+                } else {
+                    continue;
+                };
+                let language = trimmed
+                    .trim_start_matches(fence.chars().next().unwrap())
+                    .split_whitespace()
+                    .next()
+                    .map(str::to_string);
+                open = Some((
+                    fence,
+                    CodeBlock {
+                        language,
+                        code: String::new(),
+                    },
+                ));
+            }
+        }
+    }
+
+    if let Some((_, block)) = open {
+        blocks.push(block);
+    }
+    blocks
+}
+
+/// File extension used when saving a block in `language`
+pub fn extension_for(language: Option<&str>) -> &'static str {
+    match language.map(str::to_ascii_lowercase).as_deref() {
+        Some("rust" | "rs") => "rs",
+        Some("python" | "py") => "py",
+        Some("javascript" | "js") => "js",
+        Some("typescript" | "ts") => "ts",
+        Some("tsx") => "tsx",
+        Some("go" | "golang") => "go",
+        Some("java") => "java",
+        Some("c") => "c",
+        Some("cpp" | "c++") => "cpp",
+        Some("sh" | "bash" | "shell" | "zsh") => "sh",
+        Some("json") => "json",
+        Some("toml") => "toml",
+        Some("yaml" | "yml") => "yaml",
+        Some("html") => "html",
+        Some("css") => "css",
+        Some("sql") => "sql",
+        Some("markdown" | "md") => "md",
+        _ => "txt",
+    }
+}
+
+/// Insert `code` before 1-based line `line` of `original` (past the end appends)
+pub fn insert_at_line(original: &str, line: usize, code: &str) -> String {
+    let mut lines: Vec<&str> = original.lines().collect();
+    let index = line.saturating_sub(1).min(lines.len());
+    lines.splice(index..index, code.lines());
+
+    let mut out = lines.join("\n");
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    out
+}
+
+/// One line of a unified diff
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiffLine {
+    pub tag: ChangeTag,
+    pub text: String,
+}
+
+/// Line diff from `old` (the open file) to `new` (the block)
+pub fn line_diff(old: &str, new: &str) -> Vec<DiffLine> {
+    TextDiff::from_lines(old, new)
+        .iter_all_changes()
+        .map(|change| DiffLine {
+            tag: change.tag(),
+            text: change.value().trim_end_matches('\n').to_string(),
+        })
+        .collect()
+}
+
+/// What the Blocks popup is currently showing
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum BlocksMode {
+    #[default]
+    List,
+    /// Typing the line to insert the selected block at
+    InsertLine(String),
+    Diff(Vec<DiffLine>),
+}
+
+/// Selection state of the Blocks popup
+#[derive(Clone, Debug, Default)]
+pub struct BlocksView {
+    pub selected: usize,
+    pub mode: BlocksMode,
+    pub diff_scroll: u16,
+}
+
+impl BlocksView {
+    pub fn select_next(&mut self, len: usize) {
+        if self.selected + 1 < len {
+            self.selected += 1;
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_blocks_with_languages() {
+        let content = "Intro\n\n```python\ndef f():\n    pass\n```\ntext\n~~~\nplain\n~~~\n```rust\nfn main() {}\n";
+        let blocks = extract_blocks(content);
+
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0].language.as_deref(), Some("python"));
+        assert_eq!(blocks[0].code, "def f():\n    pass\n");
+        assert_eq!(blocks[1].label(), "text");
+        assert_eq!(blocks[1].code, "plain\n");
+        // Unterminated fence still yields a block
+        assert_eq!(blocks[2].code, "fn main() {}\n");
+    }
+
+    #[test]
+    fn test_insert_at_line() {
+        assert_eq!(insert_at_line("a\nb\n", 2, "x\ny\n"), "a\nx\ny\nb\n");
+        assert_eq!(insert_at_line("a\n", 99, "z"), "a\nz\n");
+        assert_eq!(insert_at_line("", 1, "z\n"), "z\n");
+    }
+
+    #[test]
+    fn test_line_diff_tags_changes() {
+        let diff = line_diff("a\nb\n", "a\nc\n");
+        let tags: Vec<ChangeTag> = diff.iter().map(|l| l.tag).collect();
+        assert_eq!(tags, [ChangeTag::Equal, ChangeTag::Delete, ChangeTag::Insert]);
+        assert_eq!(diff[2].text, "c");
+    }
+}
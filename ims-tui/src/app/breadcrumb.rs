@@ -0,0 +1,181 @@
+//! Breadcrumbs
+//!
+//! A row above the center workspace shows where the session's file sits:
+//! workspace › directory › … › file. Clicking a directory's crumb drops
+//! down the files in it, and the file's crumb the files beside it; picking
+//! one opens it in place of the current file.
+
+use std::path::{Path, PathBuf};
+
+use ratatui::layout::{Position, Rect};
+
+use crate::app::{overlay::Overlay, AppState, FileNode};
+
+/// One step of the session file's path
+#[derive(Clone, Debug, PartialEq)]
+pub struct Crumb {
+    pub label: String,
+    /// Directory whose files the crumb drops down, relative to the
+    /// workspace (empty for the workspace itself)
+    pub dir: PathBuf,
+}
+
+/// Crumbs for the session's file: the workspace, each directory, the file
+pub fn crumbs(state: &AppState) -> Vec<Crumb> {
+    let Some(session) = &state.session else { return Vec::new() };
+    let path = session.file_path.strip_prefix(&state.workspace_root).unwrap_or(&session.file_path);
+    let workspace = state
+        .workspace_root
+        .file_name()
+        .map_or_else(|| state.workspace_root.display().to_string(), |n| n.to_string_lossy().into_owned());
+
+    let mut crumbs = vec![Crumb {
+        label: workspace,
+        dir: PathBuf::new(),
+    }];
+    let mut dir = PathBuf::new();
+    for part in path.parent().into_iter().flat_map(Path::iter) {
+        dir.push(part);
+        crumbs.push(Crumb {
+            label: part.to_string_lossy().into_owned(),
+            dir: dir.clone(),
+        });
+    }
+    if let Some(name) = path.file_name() {
+        crumbs.push(Crumb {
+            label: name.to_string_lossy().into_owned(),
+            dir,
+        });
+    }
+    crumbs
+}
+
+/// Files of one directory dropped down from its crumb
+#[derive(Clone, Debug)]
+pub struct FileDropdown {
+    /// Screen cell the list hangs from (its top-left corner)
+    pub x: u16,
+    pub y: u16,
+    /// (tree node id, name) of each file
+    pub files: Vec<(String, String)>,
+    pub selected: usize,
+}
+
+impl FileDropdown {
+    /// Popup rect, shifted to stay inside `screen`
+    pub fn area(&self, screen: Rect) -> Rect {
+        let name_width = self.files.iter().map(|(_, name)| name.chars().count()).max().unwrap_or(0);
+        let width = (name_width as u16 + 4).max(20).min(screen.width);
+        let height = (self.files.len() as u16 + 2).min(screen.height);
+        Rect {
+            x: self.x.min(screen.right().saturating_sub(width)),
+            y: self.y.min(screen.bottom().saturating_sub(height)),
+            width,
+            height,
+        }
+    }
+
+    /// File whose row is at screen row `y` (inside `area`)
+    pub fn file_at(&self, area: Rect, x: u16, y: u16) -> Option<usize> {
+        if !area.contains(Position { x, y }) {
+            return None;
+        }
+        let index = y.checked_sub(area.y + 1)? as usize;
+        (index < self.files.len()).then_some(index)
+    }
+
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1) % self.files.len().max(1);
+    }
+
+    pub fn select_prev(&mut self) {
+        let len = self.files.len().max(1);
+        self.selected = (self.selected + len - 1) % len;
+    }
+}
+
+impl AppState {
+    /// Files directly in `dir` (the workspace's top level when empty)
+    fn files_in(&self, dir: &Path) -> Vec<&FileNode> {
+        fn find<'a>(nodes: &'a [FileNode], dir: &Path) -> Option<&'a FileNode> {
+            nodes.iter().filter(|n| n.is_dir).find_map(|n| {
+                if n.path == dir {
+                    Some(n)
+                } else {
+                    find(&n.children, dir)
+                }
+            })
+        }
+        let nodes = if dir.as_os_str().is_empty() {
+            &self.file_tree[..]
+        } else {
+            match find(&self.file_tree, dir).or_else(|| find(&self.file_tree, &self.workspace_root.join(dir))) {
+                Some(node) => &node.children[..],
+                None => &[],
+            }
+        };
+        nodes.iter().filter(|n| !n.is_dir).collect()
+    }
+
+    /// Drop down the files of crumb `index`'s directory below `(x, y)`,
+    /// the session's file selected
+    pub fn open_file_dropdown(&mut self, index: usize, x: u16, y: u16) {
+        let Some(crumb) = crumbs(self).into_iter().nth(index) else { return };
+        let files: Vec<(String, String)> =
+            self.files_in(&crumb.dir).into_iter().map(|n| (n.id.clone(), n.name.clone())).collect();
+        if files.is_empty() {
+            self.add_debug_log(format!("No files in {}", crumb.label));
+            return;
+        }
+        let current = self.session.as_ref().map(|s| s.file_path.to_string_lossy().into_owned());
+        let selected = files.iter().position(|(id, _)| Some(id) == current.as_ref()).unwrap_or(0);
+        self.file_dropdown = Some(FileDropdown { x, y, files, selected });
+        self.open_overlay(Overlay::FileDropdown);
+    }
+
+    /// Open the dropdown's selected file, unless it is already open;
+    /// whether a new session started
+    pub fn open_dropdown_file(&mut self) -> bool {
+        let Some(dropdown) = self.file_dropdown.take() else { return false };
+        self.close_overlay(Overlay::FileDropdown);
+        let Some((id, _)) = dropdown.files.get(dropdown.selected) else { return false };
+        if self.session.as_ref().is_some_and(|s| s.file_path.to_string_lossy() == id.as_str()) {
+            return false;
+        }
+        self.open_file(id);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{vendors::Vendor, ActiveSession};
+
+    #[test]
+    fn test_crumbs_and_sibling_files() {
+        let mut src = FileNode::new_dir(PathBuf::from("src"));
+        src.children = vec![FileNode::new_file("src/main.rs".into()), FileNode::new_file("src/lib.rs".into())];
+        let mut state = AppState {
+            file_tree: vec![src, FileNode::new_file("README.md".into())],
+            workspace_root: PathBuf::from("/home/dev/crate"),
+            session: Some(ActiveSession::new("src/lib.rs".into(), &Vendor::unknown(), "gpt-4o".to_string())),
+            ..Default::default()
+        };
+
+        let labels: Vec<_> = crumbs(&state).into_iter().map(|c| c.label).collect();
+        assert_eq!(labels, ["crate", "src", "lib.rs"]);
+
+        state.open_file_dropdown(2, 4, 1);
+        let dropdown = state.file_dropdown.as_ref().unwrap();
+        assert_eq!(dropdown.files.iter().map(|(_, n)| n.as_str()).collect::<Vec<_>>(), ["main.rs", "lib.rs"]);
+        assert_eq!(dropdown.selected, 1);
+
+        state.file_dropdown.as_mut().unwrap().select_next();
+        assert!(state.open_dropdown_file());
+        assert_eq!(state.session.as_ref().unwrap().file_path, PathBuf::from("src/main.rs"));
+
+        state.open_file_dropdown(0, 0, 1);
+        assert_eq!(state.file_dropdown.as_ref().unwrap().files[0].1, "README.md");
+    }
+}
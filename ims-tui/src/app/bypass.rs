@@ -0,0 +1,120 @@
+//! Policy Bypass
+//!
+//! `bypass_policies` asks IMS Core to skip its routing and safety policies
+//! for a request. It is armed for the next prompt only, from the palette,
+//! and only after the admin key is typed into a confirmation prompt. While
+//! armed the session header shows a warning badge; the prompt it is spent
+//! on is logged as a policy bypass and flagged in the audit trail.
+
+use crate::app::{
+    overlay::Overlay,
+    thinking::{Level, ThinkingEntry},
+    AppState, InFlightPrompt,
+};
+
+/// "Bypass policies?" with the admin key being typed
+#[derive(Clone, Debug, Default)]
+pub struct BypassPrompt {
+    pub input: String,
+    /// Why the last key was refused
+    pub error: Option<String>,
+}
+
+impl BypassPrompt {
+    /// The input as shown: one bullet per character
+    pub fn masked(&self) -> String {
+        "•".repeat(self.input.chars().count())
+    }
+}
+
+impl AppState {
+    /// Ask for the admin key to arm a bypass, or disarm one already armed
+    pub fn toggle_bypass(&mut self) {
+        if self.bypass_armed {
+            self.bypass_armed = false;
+            self.add_debug_log("Policy bypass disarmed".to_string());
+        } else {
+            self.bypass_prompt = Some(BypassPrompt::default());
+            self.open_overlay(Overlay::Bypass);
+        }
+    }
+
+    /// Arm the bypass if the typed key is the admin key; a wrong key keeps
+    /// the prompt open with an error
+    pub fn confirm_bypass(&mut self) {
+        let Some(prompt) = self.bypass_prompt.as_mut() else { return };
+        let key = prompt.input.trim();
+        let error = match &self.api_client {
+            None => Some("API client not initialized"),
+            Some(client) if !client.has_admin_key() => Some("No admin key configured (set ADMIN_API_KEY)"),
+            Some(client) if !client.is_admin_key(key) => Some("Admin key does not match"),
+            Some(_) => None,
+        };
+        if let Some(error) = error {
+            prompt.input.clear();
+            prompt.error = Some(error.to_string());
+            self.add_debug_log(format!("Policy bypass refused: {}", error));
+            return;
+        }
+        self.close_overlay(Overlay::Bypass);
+        self.bypass_armed = true;
+        self.add_debug_log("Policy bypass armed for the next prompt".to_string());
+    }
+
+    /// Spend an armed bypass on `request`, logging it apart from ordinary
+    /// prompts
+    pub fn take_bypass(&mut self, request: &mut InFlightPrompt) {
+        if !std::mem::take(&mut self.bypass_armed) {
+            return;
+        }
+        request.bypass_policies = true;
+        tracing::warn!(
+            target: "ims_tui::audit",
+            model_id = %request.model_id,
+            request = %request.key,
+            "policy bypass"
+        );
+        self.add_debug_log(format!(
+            "AUDIT: policy bypass used for {} (request {})",
+            request.model_id, request.key
+        ));
+        self.add_thinking(
+            ThinkingEntry::system("⚠ Sending with IMS Core policies bypassed").with_level(Level::Warning),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::api::ImsApiClient;
+
+    #[test]
+    fn test_bypass_needs_the_admin_key_and_lasts_one_request() {
+        let client = ImsApiClient::new("http://localhost:8000".to_string(), Some("s3cret".to_string()), true, &Default::default()).unwrap();
+        let mut state = AppState {
+            api_client: Some(client),
+            ..Default::default()
+        };
+
+        state.toggle_bypass();
+        state.bypass_prompt.as_mut().unwrap().input = "guess".to_string();
+        state.confirm_bypass();
+        assert!(!state.bypass_armed);
+        assert_eq!(state.bypass_prompt.as_ref().unwrap().error.as_deref(), Some("Admin key does not match"));
+
+        state.bypass_prompt.as_mut().unwrap().input = "s3cret".to_string();
+        state.confirm_bypass();
+        assert!(state.bypass_armed);
+        assert!(state.bypass_prompt.is_none());
+
+        let mut first = InFlightPrompt::new("hi".to_string(), "gpt-4o".to_string());
+        state.take_bypass(&mut first);
+        assert!(first.bypass_policies);
+        assert!(state.debug_logs.last().unwrap().contains("AUDIT: policy bypass"));
+
+        let mut second = InFlightPrompt::new("hi again".to_string(), "gpt-4o".to_string());
+        state.take_bypass(&mut second);
+        assert!(!second.bypass_policies);
+    }
+}
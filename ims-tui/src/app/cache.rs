@@ -0,0 +1,218 @@
+//! Response Cache
+//!
+//! With `[cache] enabled`, a prompt sent again with the same text, model
+//! and parameters is answered from `.ims/cache` instead of the backend, so
+//! no tokens are spent. Entries are keyed by a SHA-256 of the request and
+//! expire after `[cache] ttl_secs`. "Cache: Clear Responses" empties it.
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::app::{
+    api::{ExecuteRequest, ExecuteResponse},
+    config::CacheConfig,
+    AppState,
+};
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    /// Unix seconds
+    created: i64,
+    response: ExecuteResponse,
+}
+
+#[derive(Clone, Debug)]
+pub struct ResponseCache {
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+/// `.ims/cache` under the current working directory
+pub fn cache_dir() -> PathBuf {
+    crate::app::ims_dir().join("cache")
+}
+
+/// Content hash of everything that affects the answer (not the user id or
+/// idempotency key)
+pub fn key(request: &ExecuteRequest) -> String {
+    let mut material = serde_json::json!([
+        request.model_id,
+        request.prompt,
+        request.max_tokens,
+        request.temperature,
+        request.system_instruction,
+        request.bypass_policies,
+    ]);
+    // Only when there are any, so text-only keys stay as they were
+    if let Some(parts) = material.as_array_mut().filter(|_| !request.images.is_empty()) {
+        parts.push(serde_json::json!(request.images));
+    }
+    if let Some(parts) = material.as_array_mut().filter(|_| !request.audio.is_empty()) {
+        parts.push(serde_json::json!(request.audio));
+    }
+    Sha256::digest(material.to_string().as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+impl ResponseCache {
+    pub fn new(dir: PathBuf, ttl: Duration) -> Self {
+        Self { dir, ttl }
+    }
+
+    /// The cache `config` asks for, if enabled
+    pub fn from_config(config: &CacheConfig) -> Option<Self> {
+        config
+            .enabled
+            .then(|| Self::new(cache_dir(), Duration::from_secs(config.ttl_secs)))
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", key))
+    }
+
+    /// A stored response younger than the TTL; expired entries are removed
+    pub fn get(&self, key: &str) -> Option<ExecuteResponse> {
+        let path = self.path(key);
+        let entry: Entry = serde_json::from_str(&std::fs::read_to_string(&path).ok()?).ok()?;
+        let age = Utc::now().timestamp().saturating_sub(entry.created);
+        if age < 0 || age as u64 >= self.ttl.as_secs() {
+            let _ = std::fs::remove_file(&path);
+            return None;
+        }
+        Some(entry.response)
+    }
+
+    pub fn put(&self, key: &str, response: &ExecuteResponse) -> Result<()> {
+        std::fs::create_dir_all(&self.dir).with_context(|| format!("Failed to create {}", self.dir.display()))?;
+        let entry = Entry {
+            created: Utc::now().timestamp(),
+            response: response.clone(),
+        };
+        let path = self.path(key);
+        std::fs::write(&path, serde_json::to_string(&entry)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// `get` on the blocking pool, for the prompt task
+    pub async fn fetch(&self, key: &str) -> Option<ExecuteResponse> {
+        let (cache, key) = (self.clone(), key.to_string());
+        tokio::task::spawn_blocking(move || cache.get(&key)).await.ok().flatten()
+    }
+
+    /// `put` on the blocking pool, for the prompt task
+    pub async fn store(&self, key: &str, response: &ExecuteResponse) -> Result<()> {
+        let (cache, key, response) = (self.clone(), key.to_string(), response.clone());
+        tokio::task::spawn_blocking(move || cache.put(&key, &response)).await?
+    }
+}
+
+/// Delete every cached response in `dir`, returning how many there were
+pub fn clear(dir: &Path) -> Result<usize> {
+    let Ok(entries) = std::fs::read_dir(dir) else { return Ok(0) };
+    let mut removed = 0;
+    for path in entries.filter_map(|e| e.ok().map(|e| e.path())) {
+        if path.extension().is_some_and(|ext| ext == "json") {
+            std::fs::remove_file(&path).with_context(|| format!("Failed to remove {}", path.display()))?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+impl AppState {
+    pub fn clear_response_cache(&mut self) {
+        match clear(&cache_dir()) {
+            Ok(removed) => self.add_debug_log(format!("Cleared {} cached response(s)", removed)),
+            Err(e) => self.add_debug_log(format!("Failed to clear response cache: {:#}", e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::api::{CostUsage, TokenUsage};
+
+    fn request(prompt: &str) -> ExecuteRequest {
+        ExecuteRequest {
+            prompt: prompt.to_string(),
+            model_id: "gpt-4o".to_string(),
+            max_tokens: Some(1024),
+            temperature: 0.7,
+            system_instruction: None,
+            user_id: Some("ims-tui-user".to_string()),
+            bypass_policies: false,
+            idempotency_key: Some("a".to_string()),
+            images: Vec::new(),
+            audio: Vec::new(),
+        }
+    }
+
+    fn response() -> ExecuteResponse {
+        ExecuteResponse {
+            content: "fn a() {}".to_string(),
+            model_id: "gpt-4o".to_string(),
+            tokens: TokenUsage {
+                input: 10,
+                output: 5,
+                total: 15,
+            },
+            cost: CostUsage {
+                input: 0.1,
+                output: 0.2,
+                total: 0.3,
+            },
+            latency_ms: 120.0,
+            tool_calls: Vec::new(),
+            images: Vec::new(),
+            transcripts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_key_ignores_idempotency_key() {
+        let mut retry = request("hello");
+        retry.idempotency_key = Some("b".to_string());
+        assert_eq!(key(&request("hello")), key(&retry));
+        assert_ne!(key(&request("hello")), key(&request("hello!")));
+        retry.temperature = 0.2;
+        assert_ne!(key(&request("hello")), key(&retry));
+    }
+
+    #[test]
+    fn test_round_trip_expiry_and_clear() {
+        let dir = std::env::temp_dir().join(format!("ims-cache-{}", uuid::Uuid::new_v4()));
+        let cache = ResponseCache::new(dir.clone(), Duration::from_secs(60));
+        let key = key(&request("hello"));
+        assert!(cache.get(&key).is_none());
+
+        cache.put(&key, &response()).unwrap();
+        assert_eq!(cache.get(&key).unwrap().content, "fn a() {}");
+
+        let expired = ResponseCache::new(dir.clone(), Duration::ZERO);
+        assert!(expired.get(&key).is_none());
+        assert!(!cache.path(&key).exists());
+
+        cache.put(&key, &response()).unwrap();
+        assert_eq!(clear(&dir).unwrap(), 1);
+        assert!(cache.get(&key).is_none());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_store_and_fetch_from_a_task() {
+        let dir = std::env::temp_dir().join(format!("ims-cache-{}", uuid::Uuid::new_v4()));
+        let cache = ResponseCache::new(dir.clone(), Duration::from_secs(60));
+        let key = key(&request("hello"));
+        assert!(cache.fetch(&key).await.is_none());
+        cache.store(&key, &response()).await.unwrap();
+        assert_eq!(cache.fetch(&key).await.unwrap().content, "fn a() {}");
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,109 @@
+//! Model Capabilities
+//!
+//! The session header shows what the active model can do, from its
+//! registry entry (fetched when the session opens): context window,
+//! function calling and capability tier. It also warns when the prompt
+//! being written needs something the model lacks: more context than the
+//! window holds, tool calls (the session has used them) on a model without
+//! function calling, or a model deactivated in the registry.
+
+use crate::app::{api::ModelResponse, context, tokens::format_token_count, AppState};
+
+/// A capability badge; `available` is false for one the model lacks
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Badge {
+    pub label: String,
+    pub available: bool,
+}
+
+/// Badges for `model`: context window, function calling, tier
+pub fn badges(model: &ModelResponse) -> Vec<Badge> {
+    vec![
+        Badge {
+            label: format!("{} ctx", format_token_count(model.context_window as u64)),
+            available: true,
+        },
+        Badge {
+            label: if model.function_call_support { "tools" } else { "no tools" }.to_string(),
+            available: model.function_call_support,
+        },
+        Badge {
+            label: model.capability_tier.replace('_', " "),
+            available: model.is_active,
+        },
+    ]
+}
+
+impl AppState {
+    /// What `prompt` (with the session's attachments and history) needs
+    /// that the session model lacks; empty while its registry entry is
+    /// still being fetched
+    pub fn capability_warnings(&self, prompt: &str) -> Vec<String> {
+        let Some(model) = self.session_model_info() else { return Vec::new() };
+        let mut warnings = Vec::new();
+        if let Some(over) = context::budget(self, prompt).overflow() {
+            warnings.push(format!(
+                "~{} tokens over the {} context window",
+                format_token_count(over as u64),
+                format_token_count(model.context_window as u64)
+            ));
+        }
+        let uses_tools = self.thinking_log.iter().any(|e| e.tool_call.is_some());
+        if uses_tools && !model.function_call_support {
+            warnings.push(format!("{} has no function calling", model.model_id));
+        }
+        if !model.is_active {
+            warnings.push(format!("{} is deactivated in the registry", model.model_id));
+        }
+        warnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{api::ToolCall, thinking::ThinkingEntry, vendors::Vendor, ActiveSession};
+
+    fn model(function_call_support: bool, is_active: bool) -> ModelResponse {
+        ModelResponse {
+            model_id: "o1-preview".to_string(),
+            vendor_id: "OpenAI".to_string(),
+            capability_tier: "Tier_1".to_string(),
+            context_window: 128_000,
+            cost_in_per_mil: 15.0,
+            cost_out_per_mil: 60.0,
+            function_call_support,
+            is_active,
+        }
+    }
+
+    #[test]
+    fn test_badges_and_missing_features() {
+        let labels: Vec<String> = badges(&model(false, true)).into_iter().map(|b| b.label).collect();
+        assert_eq!(labels, vec!["128.0k ctx", "no tools", "Tier 1"]);
+
+        let mut state = AppState {
+            session: Some(ActiveSession::new(
+                "src/main.rs".into(),
+                &Vendor::unknown(),
+                "o1-preview".to_string(),
+            )),
+            ..Default::default()
+        };
+        // Unknown until the registry entry arrives
+        assert!(state.capability_warnings("hi").is_empty());
+
+        state.model_catalog.insert("o1-preview".to_string(), model(false, true));
+        assert!(state.capability_warnings("hi").is_empty());
+        state.add_thinking(ThinkingEntry::tool_call(ToolCall {
+            id: None,
+            name: "read_file".to_string(),
+            arguments: serde_json::json!({}),
+            result: None,
+        }));
+        assert_eq!(state.capability_warnings("hi"), vec!["o1-preview has no function calling"]);
+
+        state.model_catalog.insert("o1-preview".to_string(), model(true, false));
+        assert_eq!(state.capability_warnings("hi"), vec!["o1-preview is deactivated in the registry"]);
+    }
+}
@@ -0,0 +1,260 @@
+//! Checkpoints and Session Tabs
+//!
+//! A checkpoint is a named snapshot of the active session's conversation,
+//! output and usage. Restoring one opens it in a new session tab so it can
+//! be compared with where the work went afterwards. Only the active tab's
+//! content lives in `AppState`; the others are parked as snapshots.
+
+use chrono::{DateTime, Local};
+
+use crate::app::{
+    annotations::GenerationRegion,
+    blocks::CodeBlock, input::TextInput, thinking::ThinkingEntry, ActiveSession, AppState, ConversationTurn,
+};
+
+/// Everything that belongs to one session tab
+#[derive(Clone, Debug, Default)]
+pub struct SessionSnapshot {
+    pub session: Option<ActiveSession>,
+    pub thinking_log: Vec<ThinkingEntry>,
+    pub generated_code: String,
+    pub generation_regions: Vec<GenerationRegion>,
+    pub conversation: Vec<ConversationTurn>,
+    pub code_blocks: Vec<CodeBlock>,
+    /// Usage totals when the snapshot was taken (informational, not restored)
+    pub tokens_used: u64,
+    pub cost: f64,
+}
+
+impl SessionSnapshot {
+    pub fn capture(state: &AppState) -> Self {
+        Self {
+            session: state.session.clone(),
+            thinking_log: state.thinking_log.clone(),
+            generated_code: state.generated_code.clone(),
+            generation_regions: state.generation_regions.clone(),
+            conversation: state.conversation.clone(),
+            code_blocks: state.code_blocks.clone(),
+            tokens_used: state.total_tokens_used,
+            cost: state.total_cost,
+        }
+    }
+
+    /// Move the active tab's content out of `state`, leaving it empty
+    fn take(state: &mut AppState) -> Self {
+        Self {
+            session: state.session.take(),
+            thinking_log: std::mem::take(&mut state.thinking_log),
+            generated_code: std::mem::take(&mut state.generated_code),
+            generation_regions: std::mem::take(&mut state.generation_regions),
+            conversation: std::mem::take(&mut state.conversation),
+            code_blocks: std::mem::take(&mut state.code_blocks),
+            tokens_used: state.total_tokens_used,
+            cost: state.total_cost,
+        }
+    }
+
+    /// Make this the active tab's content
+    fn restore(self, state: &mut AppState) {
+        state.session = self.session;
+        state.thinking_log = self.thinking_log;
+        state.generated_code = self.generated_code;
+        state.clear_generation_regions();
+        state.generation_regions = self.generation_regions;
+        state.conversation = self.conversation;
+        state.code_blocks = self.code_blocks;
+        state.selection = None;
+        state.maximized = None;
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Checkpoint {
+    pub name: String,
+    pub created: DateTime<Local>,
+    pub snapshot: SessionSnapshot,
+}
+
+impl Checkpoint {
+    /// e.g. "main.rs, 3 turns, 1.2K tokens, $0.0042"
+    pub fn summary(&self) -> String {
+        let file = self
+            .snapshot
+            .session
+            .as_ref()
+            .and_then(|s| s.file_path.file_name())
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "no file".to_string());
+        format!(
+            "{}, {} turns, {} tokens, ${:.4}",
+            file,
+            self.snapshot.conversation.len(),
+            crate::app::tokens::format_token_count(self.snapshot.tokens_used),
+            self.snapshot.cost
+        )
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SessionTab {
+    pub title: String,
+    /// Parked content; stale while this tab is the active one
+    pub snapshot: SessionSnapshot,
+}
+
+/// Open session tabs; there is always at least one
+#[derive(Clone, Debug)]
+pub struct SessionTabs {
+    pub tabs: Vec<SessionTab>,
+    pub active: usize,
+}
+
+impl Default for SessionTabs {
+    fn default() -> Self {
+        Self {
+            tabs: vec![SessionTab {
+                title: "Main".to_string(),
+                snapshot: SessionSnapshot::default(),
+            }],
+            active: 0,
+        }
+    }
+}
+
+impl SessionTabs {
+    pub fn len(&self) -> usize {
+        self.tabs.len()
+    }
+}
+
+/// Checkpoints modal
+#[derive(Clone, Debug, Default)]
+pub struct CheckpointsView {
+    pub selected: usize,
+    /// Name being typed for a new checkpoint
+    pub naming: Option<TextInput>,
+}
+
+impl AppState {
+    /// Snapshot the active session under `name`
+    pub fn create_checkpoint(&mut self, name: &str) {
+        let name = match name.trim() {
+            "" => format!("Checkpoint {}", self.checkpoints.len() + 1),
+            name => name.to_string(),
+        };
+        self.checkpoints.push(Checkpoint {
+            name: name.clone(),
+            created: Local::now(),
+            snapshot: SessionSnapshot::capture(self),
+        });
+        self.add_debug_log(format!("Saved checkpoint \"{}\"", name));
+    }
+
+    /// Open checkpoint `index` in a new tab and switch to it
+    pub fn restore_checkpoint(&mut self, index: usize) -> anyhow::Result<()> {
+        let checkpoint = self
+            .checkpoints
+            .get(index)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("No checkpoint {}", index + 1))?;
+        self.open_in_new_tab(checkpoint.name, checkpoint.snapshot)
+    }
+
+    /// Add a tab holding `snapshot` and switch to it
+    pub fn open_in_new_tab(&mut self, title: String, snapshot: SessionSnapshot) -> anyhow::Result<()> {
+        if self.generation_in_flight() {
+            anyhow::bail!("Wait for the running generation before opening another tab");
+        }
+        self.session_tabs.tabs.push(SessionTab { title, snapshot });
+        self.switch_tab(self.session_tabs.len() - 1)
+    }
+
+    /// Park the active tab and bring up tab `index`
+    pub fn switch_tab(&mut self, index: usize) -> anyhow::Result<()> {
+        if index >= self.session_tabs.len() {
+            anyhow::bail!("No tab {}", index + 1);
+        }
+        if index == self.session_tabs.active {
+            return Ok(());
+        }
+        // The response would land in whichever tab is active when it arrives
+        if self.generation_in_flight() {
+            anyhow::bail!("Wait for the running generation before switching tabs");
+        }
+        let parked = SessionSnapshot::take(self);
+        let active = self.session_tabs.active;
+        self.session_tabs.tabs[active].snapshot = parked;
+        let next = std::mem::take(&mut self.session_tabs.tabs[index].snapshot);
+        next.restore(self);
+        self.session_tabs.active = index;
+        Ok(())
+    }
+
+    /// Cycle through tabs (`forward` = to the right)
+    pub fn cycle_tab(&mut self, forward: bool) -> anyhow::Result<()> {
+        let len = self.session_tabs.len();
+        let active = self.session_tabs.active;
+        let next = if forward { (active + 1) % len } else { (active + len - 1) % len };
+        self.switch_tab(next)
+    }
+
+    /// Close the active tab, switching to its left neighbour
+    pub fn close_tab(&mut self) -> anyhow::Result<()> {
+        if self.session_tabs.len() <= 1 {
+            anyhow::bail!("The last tab can't be closed");
+        }
+        let closing = self.session_tabs.active;
+        self.switch_tab(if closing == 0 { 1 } else { closing - 1 })?;
+        self.session_tabs.tabs.remove(closing);
+        if self.session_tabs.active > closing {
+            self.session_tabs.active -= 1;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state_with_turn(prompt: &str) -> AppState {
+        let mut state = AppState::default();
+        state.conversation.push(ConversationTurn {
+            response: Some("ok".to_string()),
+            ..ConversationTurn::new(prompt, "gpt-4o")
+        });
+        state
+    }
+
+    #[test]
+    fn test_restore_opens_checkpoint_in_new_tab() {
+        let mut state = state_with_turn("before refactor");
+        state.create_checkpoint("before refactor");
+        state.conversation[0].prompt = "after".to_string();
+
+        state.restore_checkpoint(0).unwrap();
+        assert_eq!(state.session_tabs.len(), 2);
+        assert_eq!(state.conversation[0].prompt, "before refactor");
+
+        // The original tab kept its later work
+        state.switch_tab(0).unwrap();
+        assert_eq!(state.conversation[0].prompt, "after");
+
+        state.switch_tab(1).unwrap();
+        state.close_tab().unwrap();
+        assert_eq!(state.session_tabs.len(), 1);
+        assert_eq!(state.conversation[0].prompt, "after");
+    }
+
+    #[test]
+    fn test_switch_refused_while_generating() {
+        let mut state = state_with_turn("x");
+        state.create_checkpoint("");
+        assert_eq!(state.checkpoints[0].name, "Checkpoint 1");
+
+        state.conversation[0].response = None;
+        assert!(state.restore_checkpoint(0).is_err());
+        assert!(state.cycle_tab(true).is_ok()); // only one tab: no-op
+        assert_eq!(state.session_tabs.len(), 1);
+    }
+}
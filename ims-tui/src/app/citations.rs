@@ -0,0 +1,195 @@
+//! Citations
+//!
+//! Search results attached as context are sent numbered, `[1]` onwards,
+//! and the model is asked to cite them that way. Each turn keeps which
+//! chunk of which file each number stood for; once the response arrives
+//! its sources are listed under it in the Thinking pane, marking those it
+//! cited. Clicking one, or picking it from the Sources view (`o` in the
+//! Thinking pane), opens the file in `$EDITOR` at the chunk's first line.
+
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::LazyLock;
+
+use crate::app::{embeddings::SearchHit, external_editor, overlay::Overlay, thinking::ThinkingEntry, AppState};
+
+/// Asked of the model when numbered sources are sent
+pub const INSTRUCTION: &str = "Cite the numbered sources above as [1], [2], … where you use them.\n\n";
+
+/// A numbered chunk sent as context
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Citation {
+    pub number: usize,
+    pub path: PathBuf,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+impl Citation {
+    /// `[2] src/parser.rs:41-80`
+    pub fn label(&self) -> String {
+        format!("[{}] {}:{}-{}", self.number, self.path.display(), self.start_line, self.end_line)
+    }
+}
+
+/// `hits` numbered in the order they are sent
+pub fn number(hits: &[SearchHit]) -> Vec<Citation> {
+    hits.iter()
+        .enumerate()
+        .map(|(i, hit)| Citation {
+            number: i + 1,
+            path: hit.path.clone(),
+            start_line: hit.start_line,
+            end_line: hit.end_line,
+        })
+        .collect()
+}
+
+/// A `[n]` citation marker
+static MARKER: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\[(\d+)\]").expect("citation pattern is valid"));
+
+/// Source numbers `response` cites, in order, each once; indexing such as
+/// `args[1]` inside fenced code blocks isn't a citation
+pub fn cited(response: &str) -> Vec<usize> {
+    let mut numbers = Vec::new();
+    let mut in_code = false;
+    for line in response.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            continue;
+        }
+        for found in MARKER.captures_iter(line) {
+            if let Ok(n) = found[1].parse::<usize>() {
+                if !numbers.contains(&n) {
+                    numbers.push(n);
+                }
+            }
+        }
+    }
+    numbers
+}
+
+/// `command` opening `path` at `line`: `--goto path:line` for VS Code
+/// and its forks, `+line path` for everything else
+fn editor_at(command: &[String], path: &Path, line: usize) -> Vec<String> {
+    let mut args = command.to_vec();
+    let program = command
+        .first()
+        .and_then(|p| Path::new(p).file_stem())
+        .map(|p| p.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    if matches!(program.as_str(), "code" | "codium" | "cursor") {
+        args.extend(["--goto".to_string(), format!("{}:{}", path.display(), line)]);
+    } else {
+        args.extend([format!("+{}", line), path.display().to_string()]);
+    }
+    args
+}
+
+/// Open the cited file in the user's editor at its first line
+pub fn open(citation: &Citation) -> Result<()> {
+    let command = editor_at(&external_editor::editor_command(), &citation.path, citation.start_line);
+    let Some((program, args)) = command.split_first() else {
+        bail!("No editor command");
+    };
+    let status = Command::new(program)
+        .args(args)
+        .status()
+        .with_context(|| format!("Failed to run editor {}", program))?;
+    if !status.success() {
+        bail!("Editor {} exited with {}", program, status);
+    }
+    Ok(())
+}
+
+/// Sources view: the citations of the latest answered turn that has any
+#[derive(Clone, Debug, Default)]
+pub struct CitationsView {
+    pub selected: usize,
+}
+
+impl AppState {
+    /// List the sources of a response under it, marking those it cites
+    pub fn record_citations(&mut self, citations: &[Citation], response: &str) {
+        let cited = cited(response);
+        for citation in citations {
+            let cited = cited.contains(&citation.number);
+            self.add_thinking(ThinkingEntry::citation(citation.clone(), cited));
+        }
+    }
+
+    /// Citations of the latest answered turn with sources
+    pub fn latest_citations(&self) -> &[Citation] {
+        self.conversation
+            .iter()
+            .rev()
+            .find(|t| t.response.is_some() && !t.citations.is_empty())
+            .map_or(&[], |t| &t.citations)
+    }
+
+    pub fn open_citations(&mut self) {
+        if self.latest_citations().is_empty() {
+            self.add_debug_log("No response with cited sources yet".to_string());
+            return;
+        }
+        self.open_overlay(Overlay::Citations);
+        self.citations_view = CitationsView::default();
+    }
+
+    /// Open `citation` in the editor before the next frame
+    pub fn jump_to_citation(&mut self, citation: Citation) {
+        self.close_overlay(Overlay::Citations);
+        self.citation_jump = Some(citation);
+    }
+
+    pub fn finish_citation_jump(&mut self, citation: &Citation, result: Result<()>) {
+        match result {
+            Ok(()) => self.add_debug_log(format!("Opened {}", citation.label())),
+            Err(e) => self.add_debug_log(format!("{:#}", e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_citations_are_numbered_and_matched() {
+        let hit = |path: &str, start_line| SearchHit {
+            path: PathBuf::from(path),
+            start_line,
+            end_line: start_line + 39,
+            text: String::new(),
+            score: 0.5,
+        };
+        let citations = number(&[hit("src/parser.rs", 41), hit("src/lexer.rs", 1)]);
+        assert_eq!(citations[1].label(), "[2] src/lexer.rs:1-40");
+        assert_eq!(cited("Tokens come from the lexer [2], then [1] and [2] again; arr[i] isn't one."), [2, 1]);
+        assert_eq!(cited("Use the first argument [3]:\n```rust\nlet path = &args[1];\n```\nSee [1]."), [3, 1]);
+
+        let mut state = AppState::default();
+        state.record_citations(&citations, "See [2].");
+        let entries: Vec<_> = state.thinking_log.iter().map(|e| e.citation.as_ref().map(|c| c.number)).collect();
+        assert_eq!(entries, [Some(1), Some(2)]);
+        assert!(state.thinking_log[1].text.ends_with("(cited)"));
+        assert!(!state.thinking_log[0].text.ends_with("(cited)"));
+    }
+
+    #[test]
+    fn test_editor_opens_at_line() {
+        let path = Path::new("src/parser.rs");
+        let command = |c: &[&str]| c.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(editor_at(&command(&["nvim"]), path, 41), ["nvim", "+41", "src/parser.rs"]);
+        assert_eq!(
+            editor_at(&command(&["/usr/bin/code", "--wait"]), path, 41),
+            ["/usr/bin/code", "--wait", "--goto", "src/parser.rs:41"]
+        );
+    }
+}
@@ -0,0 +1,284 @@
+//! History Compaction
+//!
+//! Every prior turn is sent with each prompt, so a long session eventually
+//! fills the model's context window. Compacting has a cheap model
+//! (`[compaction] model`) summarize all but the latest `keep_recent` turns;
+//! the summary is kept on the last turn it covers and sent in place of it
+//! and every turn before. The turns themselves stay in the session, since
+//! annotations, ratings and exports point at them, and the Thinking pane
+//! marks where the history was compacted. What the summarizer is sent is
+//! cut to fit its own context window, oldest turns first, and what it
+//! costs counts towards the session's spend.
+
+use crate::app::{
+    api::{ApiEvent, ApiSender, ExecuteRequest, MAX_TOKENS},
+    context,
+    thinking::{Level, ThinkingEntry},
+    tokens::{count_tokens, Tokenizer},
+    AppState, ConversationTurn,
+};
+
+/// Context window assumed for a summarizing model the registry doesn't list
+const FALLBACK_WINDOW: u32 = 16_000;
+
+/// Asked of the summarizing model
+const INSTRUCTION: &str = "Summarize the conversation below so an assistant can carry it on without it. \
+Keep decisions made, code, file and function names discussed, open questions and the user's stated \
+preferences; leave out pleasantries. Write terse notes, not a dialogue.";
+
+/// The summary sent in place of the compacted turns, if any, and the
+/// turns after it, sent as they are
+pub fn history(conversation: &[ConversationTurn]) -> (Option<&str>, &[ConversationTurn]) {
+    match conversation.iter().rposition(|t| t.summary.is_some()) {
+        Some(last) => (conversation[last].summary.as_deref(), &conversation[last + 1..]),
+        None => (None, conversation),
+    }
+}
+
+/// Turns covered by the latest summary
+fn compacted(conversation: &[ConversationTurn]) -> usize {
+    conversation.len() - history(conversation).1.len()
+}
+
+/// A summary as sent in a request
+pub fn summary_block(summary: &str) -> String {
+    format!("Summary of the earlier conversation:\n{}\n\n", summary.trim_end())
+}
+
+/// What the summarizing model is sent for turns up to `through`: the
+/// summary of any turns compacted before, then the rest verbatim, leaving
+/// out the oldest of them past `limit` tokens; and how many were left out
+fn request_text(conversation: &[ConversationTurn], through: usize, limit: u32, tokenizer: Tokenizer) -> (String, usize) {
+    let (summary, turns) = history(&conversation[..=through]);
+    let summary = summary.map(summary_block).unwrap_or_default();
+    let blocks: Vec<String> = turns
+        .iter()
+        .filter_map(|turn| Some(context::turn_block(&turn.prompt, turn.response.as_ref()?)))
+        .collect();
+    let mut used = count_tokens(&summary, tokenizer);
+    // Newest first, so the turns kept are the latest
+    let kept = blocks
+        .iter()
+        .rev()
+        .take_while(|block| {
+            used = used.saturating_add(count_tokens(block, tokenizer));
+            used <= limit
+        })
+        .count();
+    let dropped = blocks.len() - kept;
+    (summary + &blocks[dropped..].concat(), dropped)
+}
+
+/// Start summarizing older turns in the background
+pub fn start(state: &mut AppState, api_tx: &ApiSender) {
+    let Some(client) = state.api_client.clone() else { return };
+    if state.compacting {
+        state.add_debug_log("The history is already being compacted".to_string());
+        return;
+    }
+    let config = state.config.compaction.clone();
+    let Some(through) = state.compactable() else {
+        state.add_debug_log(format!(
+            "Nothing to compact: the latest {} turns are always sent as they are",
+            config.keep_recent
+        ));
+        return;
+    };
+    state.compacting = true;
+    let turns = through + 1 - compacted(&state.conversation);
+    state.add_thinking(ThinkingEntry::system(format!(
+        "Compacting {} earlier turn(s) with {}…",
+        turns, config.model
+    )));
+
+    let window = state
+        .model_catalog
+        .get(&config.model)
+        .map_or(FALLBACK_WINDOW, |m| m.context_window);
+    let tokenizer = Tokenizer::for_model(&config.model);
+    let limit = window.saturating_sub(MAX_TOKENS + count_tokens(INSTRUCTION, tokenizer));
+    let (text, dropped) = request_text(&state.conversation, through, limit, tokenizer);
+    if dropped > 0 {
+        state.add_thinking(
+            ThinkingEntry::system(format!(
+                "{} of the oldest turn(s) don't fit in {}'s context window and are left out of the summary",
+                dropped, config.model
+            ))
+            .with_level(Level::Warning),
+        );
+    }
+
+    let prompt = state.conversation[through].prompt.clone();
+    let request = ExecuteRequest {
+        prompt: text,
+        model_id: config.model,
+        max_tokens: Some(MAX_TOKENS),
+        temperature: 0.2,
+        system_instruction: Some(INSTRUCTION.to_string()),
+        user_id: None,
+        bypass_policies: false,
+        idempotency_key: None,
+        images: Vec::new(),
+        audio: Vec::new(),
+    };
+    let tx = api_tx.clone();
+    tokio::spawn(async move {
+        let event = match client.execute_prompt(request).await {
+            Ok(response) => ApiEvent::HistoryCompacted {
+                through,
+                prompt,
+                summary: response.content.trim().to_string(),
+                model_id: response.model_id,
+                tokens: response.tokens.total,
+                cost: response.cost.total,
+            },
+            Err(e) => ApiEvent::CompactionFailed(format!("{:#}", e)),
+        };
+        let _ = tx.send(event);
+    });
+}
+
+/// Whether the latest response left the context full enough to compact
+pub fn needed(state: &AppState) -> bool {
+    let config = &state.config.compaction;
+    config.auto
+        && !state.compacting
+        && context::budget(state, "").ratio() >= config.threshold
+        && state.compactable().is_some()
+}
+
+impl AppState {
+    /// Last turn a compaction would summarize: every answered turn not yet
+    /// compacted, short of the latest `keep_recent`
+    fn compactable(&self) -> Option<usize> {
+        let from = compacted(&self.conversation);
+        let to = self.conversation.len().saturating_sub(self.config.compaction.keep_recent);
+        let answered = self
+            .conversation
+            .get(from..to)?
+            .iter()
+            .take_while(|t| t.response.is_some())
+            .count();
+        (answered > 0).then(|| from + answered - 1)
+    }
+
+    /// Keep `summary` on turn `through`, if the conversation still has the
+    /// turn it was written for
+    pub fn finish_compaction(&mut self, through: usize, prompt: &str, summary: String, model_id: &str) {
+        self.compacting = false;
+        let unchanged = self
+            .conversation
+            .get(through)
+            .is_some_and(|t| t.prompt == prompt && t.response.is_some() && t.summary.is_none());
+        if !unchanged || summary.is_empty() {
+            self.add_debug_log("The conversation changed while it was being compacted; summary dropped".to_string());
+            return;
+        }
+        let before = context::budget(self, "").history;
+        self.conversation[through].summary = Some(summary);
+        let after = context::budget(self, "").history;
+        self.add_thinking(ThinkingEntry::system(format!(
+            "🗜 Compacted history: turns 1–{} summarized by {} (history ~{} → ~{} tokens)",
+            through + 1,
+            model_id,
+            before,
+            after
+        )));
+    }
+
+    pub fn compaction_failed(&mut self, error: String) {
+        self.compacting = false;
+        self.add_thinking(
+            ThinkingEntry::system(format!("✗ Compacting the history failed: {}", error)).with_level(Level::Warning),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn turn(n: usize) -> ConversationTurn {
+        ConversationTurn {
+            response: Some(format!("answer {}", n)),
+            ..ConversationTurn::new(format!("question {}", n), "gpt-4o")
+        }
+    }
+
+    #[test]
+    fn test_summary_replaces_older_turns() {
+        let mut state = AppState {
+            conversation: (1..=6).map(turn).collect(),
+            ..Default::default()
+        };
+        assert_eq!(state.compactable(), Some(1));
+        let (request, dropped) = request_text(&state.conversation, 1, u32::MAX, Tokenizer::default());
+        assert!(request.contains("question 2") && !request.contains("question 3"));
+        assert_eq!(dropped, 0);
+
+        state.finish_compaction(1, "question 2", "Asked 1 and 2.".to_string(), "gpt-4o-mini");
+        let prompt = context::build_prompt(&state, "next");
+        assert!(prompt.starts_with("Summary of the earlier conversation:\nAsked 1 and 2.\n\nUser: question 3"));
+        assert!(!prompt.contains("question 1") && !prompt.contains("question 2"));
+        assert!(state.thinking_log.last().unwrap().text.contains("turns 1–2"));
+        assert_eq!(state.compactable(), None);
+
+        // Compacting again builds on the earlier summary
+        state.conversation.extend((7..=8).map(turn));
+        assert_eq!(state.compactable(), Some(3));
+        let (request, _) = request_text(&state.conversation, 3, u32::MAX, Tokenizer::default());
+        assert!(request.starts_with("Summary of the earlier conversation:\nAsked 1 and 2."));
+        assert!(!request.contains("question 2") && request.contains("question 4"));
+    }
+
+    #[test]
+    fn test_request_fits_the_summarizer_window() {
+        let conversation: Vec<_> = (1..=6).map(turn).collect();
+        let tokenizer = Tokenizer::default();
+        let one_turn = count_tokens(&context::turn_block("question 5", "answer 5"), tokenizer);
+        let (request, dropped) = request_text(&conversation, 4, one_turn * 2, tokenizer);
+        assert_eq!(dropped, 3);
+        assert!(request.starts_with("User: question 4") && request.contains("question 5"));
+        assert!(!request.contains("question 3"));
+    }
+
+    #[test]
+    fn test_compaction_usage_is_counted() {
+        let mut state = AppState {
+            conversation: (1..=6).map(turn).collect(),
+            compacting: true,
+            ..Default::default()
+        };
+        crate::core::dispatch::reduce_api(
+            &mut state,
+            ApiEvent::HistoryCompacted {
+                through: 1,
+                prompt: "question 2".to_string(),
+                summary: "Asked 1 and 2.".to_string(),
+                model_id: "gpt-4o-mini".to_string(),
+                tokens: 420,
+                cost: 0.0003,
+            },
+        );
+        assert_eq!(state.total_tokens_used, 420);
+        assert!((state.total_cost - 0.0003).abs() < 1e-12);
+        assert!(state.conversation[1].summary.is_some());
+    }
+
+    #[test]
+    fn test_summary_for_changed_conversation_is_dropped() {
+        let mut state = AppState {
+            conversation: (1..=6).map(turn).collect(),
+            compacting: true,
+            ..Default::default()
+        };
+        state.conversation[1].prompt = "edited".to_string();
+        state.finish_compaction(1, "question 2", "Asked 1 and 2.".to_string(), "gpt-4o-mini");
+        assert!(!state.compacting);
+        assert!(state.conversation.iter().all(|t| t.summary.is_none()));
+
+        // An unanswered turn ends what can be summarized
+        state.conversation[1].response = None;
+        assert_eq!(state.compactable(), Some(0));
+    }
+}
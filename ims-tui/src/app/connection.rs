@@ -0,0 +1,215 @@
+//! Backend Connection Management
+//!
+//! Owns the long-running pollers that depend on IMS Core being reachable
+//! and the manual reconnect flow: health checks retried with exponential
+//! backoff, after which the pollers are restarted. Polling can be paused
+//! from the palette; while paused nothing is restarted.
+
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+
+use crate::app::{
+    api::{self, ApiEvent, ApiSender, ImsApiClient},
+    config::{AppConfig, PollingConfig},
+};
+
+/// First retry delay; doubled per failed attempt
+pub const BASE_DELAY: Duration = Duration::from_secs(1);
+pub const MAX_DELAY: Duration = Duration::from_secs(60);
+/// Attempts before the reconnect gives up
+pub const MAX_ATTEMPTS: u32 = 10;
+
+/// Delay before retrying after failed attempt number `attempt` (1-based)
+pub fn backoff_delay(attempt: u32) -> Duration {
+    let factor = 1u32 << attempt.saturating_sub(1).min(16);
+    BASE_DELAY.saturating_mul(factor).min(MAX_DELAY)
+}
+
+/// Reconnect progress shown in the status bar
+#[derive(Clone, Debug, Default)]
+pub struct ReconnectStatus {
+    pub attempt: u32,
+    pub next_retry: Option<Instant>,
+    pub last_error: Option<String>,
+}
+
+impl ReconnectStatus {
+    /// Time left until the next attempt (`None` while one is running)
+    pub fn countdown(&self) -> Option<Duration> {
+        self.next_retry
+            .map(|at| at.saturating_duration_since(Instant::now()))
+    }
+}
+
+/// Poller change requested by the reducer, applied by the event loop
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PollerRequest {
+    /// The health checker saw the backend recover: see `Pollers::resume`
+    Resume,
+    /// A reconnect succeeded: restart both pollers
+    Restart,
+}
+
+/// Retry the health check with backoff until it succeeds or gives up
+pub async fn reconnector(client: ImsApiClient, tx: ApiSender) {
+    for attempt in 1..=MAX_ATTEMPTS {
+        let _ = tx.send(ApiEvent::ReconnectAttempt(attempt));
+        match client.health_check().await {
+            Ok(health) => {
+                let _ = tx.send(ApiEvent::Reconnected(health));
+                return;
+            }
+            Err(e) => {
+                let retry_in = (attempt < MAX_ATTEMPTS).then(|| backoff_delay(attempt));
+                let _ = tx.send(ApiEvent::ReconnectFailed {
+                    attempt,
+                    error: e.to_string(),
+                    retry_in,
+                });
+                if let Some(delay) = retry_in {
+                    tokio::time::sleep(delay).await;
+                }
+            }
+        }
+    }
+}
+
+/// Handles of the background tasks tied to the backend connection
+pub struct Pollers {
+    client: ImsApiClient,
+    tx: ApiSender,
+    shutdown: tokio::sync::watch::Receiver<bool>,
+    config: AppConfig,
+    health: Option<JoinHandle<()>>,
+    metrics: Option<JoinHandle<()>>,
+    reconnect: Option<JoinHandle<()>>,
+    paused: bool,
+}
+
+impl Pollers {
+    pub fn new(
+        client: ImsApiClient,
+        tx: ApiSender,
+        shutdown: tokio::sync::watch::Receiver<bool>,
+        config: AppConfig,
+    ) -> Self {
+        Self {
+            client,
+            tx,
+            shutdown,
+            config,
+            health: None,
+            metrics: None,
+            reconnect: None,
+            paused: false,
+        }
+    }
+
+    fn stop(&mut self) {
+        for handle in [self.health.take(), self.metrics.take()].into_iter().flatten() {
+            handle.abort();
+        }
+    }
+
+    /// (Re)start the health checker, plus the metrics poller when connected
+    pub fn spawn(&mut self, connected: bool) {
+        self.stop();
+        if self.paused {
+            return;
+        }
+        self.spawn_health();
+        if connected {
+            self.spawn_metrics();
+        }
+    }
+
+    /// The health checker saw the backend recover on its own
+    pub fn resume(&mut self) {
+        if let Some(handle) = self.reconnect.take() {
+            handle.abort();
+        }
+        if !self.paused && self.metrics.as_ref().is_none_or(|h| h.is_finished()) {
+            self.spawn_metrics();
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Stop or restart both pollers
+    pub fn set_paused(&mut self, paused: bool, connected: bool) {
+        self.paused = paused;
+        if paused {
+            self.stop();
+            tracing::info!("Paused background polling");
+        } else {
+            self.spawn(connected);
+        }
+    }
+
+    /// Apply new intervals, restarting the running pollers
+    pub fn set_intervals(&mut self, polling: PollingConfig, connected: bool) {
+        if self.config.polling != polling {
+            self.config.polling = polling;
+            self.spawn(connected);
+        }
+    }
+
+    fn spawn_health(&mut self) {
+        let client = self.client.clone();
+        let tx = self.tx.clone();
+        let shutdown = self.shutdown.clone();
+        let every = self.config.polling.health_interval();
+        self.health = Some(tokio::spawn(async move {
+            api::health_checker(client, tx, shutdown, every).await;
+        }));
+    }
+
+    fn spawn_metrics(&mut self) {
+        let client = self.client.clone();
+        let tx = self.tx.clone();
+        let shutdown = self.shutdown.clone();
+        let every = self.config.polling.metrics_interval();
+        let prometheus = self
+            .config
+            .prometheus
+            .enabled
+            .then(|| self.config.prometheus.clone());
+        self.metrics = Some(tokio::spawn(async move {
+            api::metrics_poller(client, tx, shutdown, every, prometheus).await;
+        }));
+        tracing::info!("Started metrics poller");
+    }
+
+    pub fn apply(&mut self, request: PollerRequest) {
+        match request {
+            PollerRequest::Resume => self.resume(),
+            PollerRequest::Restart => self.spawn(true),
+        }
+    }
+
+    /// Start a reconnect, replacing any already in progress
+    pub fn reconnect(&mut self) {
+        if let Some(handle) = self.reconnect.take() {
+            handle.abort();
+        }
+        let client = self.client.clone();
+        let tx = self.tx.clone();
+        self.reconnect = Some(tokio::spawn(reconnector(client, tx)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_and_caps() {
+        assert_eq!(backoff_delay(1), Duration::from_secs(1));
+        assert_eq!(backoff_delay(2), Duration::from_secs(2));
+        assert_eq!(backoff_delay(4), Duration::from_secs(8));
+        assert_eq!(backoff_delay(7), MAX_DELAY);
+        assert_eq!(backoff_delay(40), MAX_DELAY);
+    }
+}
@@ -0,0 +1,292 @@
+//! Prompt Context Assembly
+//!
+//! Builds the text actually dispatched for a prompt (attachments, then prior
+//! turns or their summary, then the new instruction) and estimates its size
+//! against the active model's context window. The estimate is redone only
+//! when what it counts changes, not on every redraw.
+
+use std::cell::RefCell;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use crate::app::{
+    citations, compaction, images,
+    overlay::Overlay,
+    tokens::{count_tokens, Tokenizer},
+    AppState, CostConfirm, InputMode,
+};
+
+/// Breakdown of estimated input tokens for the next request
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ContextBudget {
+    pub prompt: u32,
+    pub attachments: u32,
+    pub history: u32,
+    /// Context window of the selected model, if known
+    pub window: Option<u32>,
+    /// Vocabulary the prompt and history were counted with
+    pub tokenizer: Tokenizer,
+}
+
+impl ContextBudget {
+    pub fn total(&self) -> u32 {
+        self.prompt
+            .saturating_add(self.attachments)
+            .saturating_add(self.history)
+    }
+
+    /// Fraction of the context window used (0.0 when the window is unknown)
+    pub fn ratio(&self) -> f64 {
+        match self.window {
+            Some(window) if window > 0 => self.total() as f64 / window as f64,
+            _ => 0.0,
+        }
+    }
+
+    /// Tokens over the window, if the request would not fit
+    pub fn overflow(&self) -> Option<u32> {
+        let window = self.window?;
+        let total = self.total();
+        (total > window).then(|| total - window)
+    }
+
+    /// Human-readable truncation advice for an overflowing request
+    pub fn suggestion(&self) -> Option<String> {
+        let over = self.overflow()?;
+        let advice = if self.attachments >= self.history && self.attachments > 0 {
+            "detach large files"
+        } else if self.history > 0 {
+            "compact the history or open a fresh session"
+        } else {
+            "shorten the prompt"
+        };
+        Some(format!("over by ~{} tokens: {}", over, advice))
+    }
+}
+
+/// The last budget estimated, by a hash of what it was counted from
+pub type BudgetCache = RefCell<Option<(u64, ContextBudget)>>;
+
+/// Estimate the input size of `prompt` sent from the current state
+pub fn budget(state: &AppState, prompt: &str) -> ContextBudget {
+    let tokenizer = state
+        .session
+        .as_ref()
+        .map_or(Tokenizer::default(), |s| Tokenizer::for_model(&s.model_id));
+    let file_tokens: Vec<u32> = state.attachments.iter().map(|path| state.file_tokens(path)).collect();
+    let (summary, turns) = compaction::history(&state.conversation);
+    let window = state.session_model_info().map(|m| m.context_window);
+
+    // Hashing the inputs is far cheaper than counting their tokens
+    let mut hasher = DefaultHasher::new();
+    (prompt, tokenizer, &file_tokens, summary, window).hash(&mut hasher);
+    for hit in &state.search_context {
+        hit.text.hash(&mut hasher);
+    }
+    for turn in turns {
+        (&turn.prompt, &turn.response).hash(&mut hasher);
+    }
+    let key = hasher.finish();
+    if let Some((_, budget)) = state.budget_cache.borrow().filter(|(cached, _)| *cached == key) {
+        return budget;
+    }
+
+    let count = |text: &str| count_tokens(text, tokenizer);
+    let attachments = file_tokens
+        .into_iter()
+        .chain(state.search_context.iter().map(|hit| count(&hit.text)))
+        .fold(0u32, u32::saturating_add);
+    let history = turns
+        .iter()
+        .map(|turn| count(&turn.prompt).saturating_add(turn.response.as_deref().map(count).unwrap_or(0)))
+        .fold(summary.map_or(0, count), u32::saturating_add);
+
+    let budget = ContextBudget {
+        prompt: count(prompt),
+        attachments,
+        history,
+        window,
+        tokenizer,
+    };
+    *state.budget_cache.borrow_mut() = Some((key, budget));
+    budget
+}
+
+/// Estimated USD cost of a request of `budget` input tokens that uses its
+/// full output allowance (the vendor's `max_tokens`), at the session
+/// model's prices (if known)
+pub fn estimated_cost(state: &AppState, budget: &ContextBudget) -> Option<f64> {
+    let model = state.session_model_info()?;
+    let max_tokens = state.vendors.for_model(&model.model_id).max_tokens;
+    Some(
+        (f64::from(budget.total()) * model.cost_in_per_mil
+            + f64::from(max_tokens) * model.cost_out_per_mil)
+            / 1_000_000.0,
+    )
+}
+
+/// Hold `prompt` for confirmation if its estimate is over `[cost] confirm_above`
+pub fn confirmation_needed(state: &AppState, prompt: &str) -> Option<CostConfirm> {
+    let threshold = state.config.cost.confirm_above?;
+    let estimate = estimated_cost(state, &budget(state, prompt))?;
+    (estimate > threshold).then(|| CostConfirm {
+        prompt: prompt.to_string(),
+        estimate,
+        threshold,
+    })
+}
+
+impl AppState {
+    /// Hold a prompt until the user confirms it
+    pub fn ask_cost_confirm(&mut self, confirm: CostConfirm) {
+        self.cost_confirm = Some(confirm);
+        self.open_overlay(Overlay::CostConfirm);
+    }
+
+    /// The confirmed prompt, to send
+    pub fn take_cost_confirm(&mut self) -> Option<CostConfirm> {
+        let confirm = self.cost_confirm.take();
+        self.close_overlay(Overlay::CostConfirm);
+        confirm
+    }
+
+    /// Run when the confirmation is closed without sending: the prompt
+    /// goes back into the prompt box
+    pub(super) fn decline_cost(&mut self) {
+        let Some(confirm) = self.cost_confirm.take() else { return };
+        self.input_buffer.set(confirm.prompt);
+        self.input_mode = InputMode::Editing;
+    }
+}
+
+/// A file's contents as sent in a request
+pub fn file_block(path: &std::path::Path, content: &str) -> String {
+    format!("File: {}\n```\n{}\n```\n\n", path.display(), content.trim_end())
+}
+
+/// A prior exchange as sent in a request
+pub fn turn_block(prompt: &str, response: &str) -> String {
+    format!("User: {}\nAssistant: {}\n\n", prompt, response)
+}
+
+/// Assemble the full prompt text for dispatch
+pub fn build_prompt(state: &AppState, prompt: &str) -> String {
+    let mut out = String::new();
+
+    // Images and audio go in the request's `images` and `audio`, not the text
+    for path in state.attachments.iter().filter(|p| !images::is_media(p)) {
+        match std::fs::read_to_string(path) {
+            Ok(content) => out.push_str(&file_block(path, &content)),
+            Err(_) => {
+                out.push_str(&format!("File: {} (unreadable)\n\n", path.display()));
+            }
+        }
+    }
+
+    // Search results as they were indexed, not the whole file, numbered
+    // to be cited
+    for (i, hit) in state.search_context.iter().enumerate() {
+        out.push_str(&hit.context_block(i + 1));
+    }
+    if !state.search_context.is_empty() {
+        out.push_str(citations::INSTRUCTION);
+    }
+
+    let (summary, turns) = compaction::history(&state.conversation);
+    if let Some(summary) = summary {
+        out.push_str(&compaction::summary_block(summary));
+    }
+    for turn in turns {
+        if let Some(response) = &turn.response {
+            out.push_str(&turn_block(&turn.prompt, response));
+        }
+    }
+
+    out.push_str(prompt);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overflow_and_suggestion() {
+        let budget = ContextBudget {
+            prompt: 100,
+            attachments: 9_000,
+            history: 2_000,
+            window: Some(8_192),
+            ..Default::default()
+        };
+
+        assert_eq!(budget.overflow(), Some(2_908));
+        assert!(budget.suggestion().unwrap().contains("detach"));
+    }
+
+    #[test]
+    fn test_unknown_window_never_overflows() {
+        let budget = ContextBudget {
+            prompt: 1_000_000,
+            ..Default::default()
+        };
+
+        assert_eq!(budget.overflow(), None);
+        assert_eq!(budget.ratio(), 0.0);
+    }
+
+    #[test]
+    fn test_cost_estimate_and_confirmation() {
+        let mut state = AppState {
+            session: Some(crate::app::ActiveSession::new(
+                std::path::PathBuf::from("a.rs"),
+                &crate::app::vendors::Vendor::unknown(),
+                "gpt-4o".to_string(),
+            )),
+            ..Default::default()
+        };
+        let prompt = "word ".repeat(2000);
+        assert!(estimated_cost(&state, &budget(&state, &prompt)).is_none());
+
+        state.model_catalog.insert(
+            "gpt-4o".to_string(),
+            crate::app::api::ModelResponse {
+                model_id: "gpt-4o".to_string(),
+                vendor_id: "openai".to_string(),
+                capability_tier: "premium".to_string(),
+                context_window: 128_000,
+                cost_in_per_mil: 5.0,
+                cost_out_per_mil: 15.0,
+                function_call_support: true,
+                is_active: true,
+            },
+        );
+        let input = budget(&state, &prompt).total();
+        let cost = estimated_cost(&state, &budget(&state, &prompt)).unwrap();
+        assert!((cost - (f64::from(input) * 5.0 + 1024.0 * 15.0) / 1e6).abs() < 1e-12);
+
+        assert!(confirmation_needed(&state, &prompt).is_none());
+        state.config.cost.confirm_above = Some(0.02);
+        assert!(confirmation_needed(&state, "hi").is_none());
+        let held = confirmation_needed(&state, &prompt).unwrap();
+        assert_eq!(held.threshold, 0.02);
+        assert_eq!(held.estimate, cost);
+    }
+
+    #[test]
+    fn test_budget_is_counted_again_only_on_a_change() {
+        let state = AppState::default();
+        let first = budget(&state, "Explain the parser");
+        assert!(first.prompt > 0);
+
+        // Served from the cache while nothing changes
+        state.budget_cache.borrow_mut().as_mut().unwrap().1.prompt = 999;
+        assert_eq!(budget(&state, "Explain the parser").prompt, 999);
+        assert_eq!(budget(&state, "Explain the parser again").prompt, first.prompt + 1);
+    }
+
+    #[test]
+    fn test_build_prompt_without_context_is_verbatim() {
+        let state = AppState::default();
+        assert_eq!(build_prompt(&state, "Refactor this"), "Refactor this");
+    }
+}
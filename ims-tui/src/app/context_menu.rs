@@ -0,0 +1,159 @@
+//! Context Menus
+//!
+//! Small right-click popups anchored at the click position, offering the
+//! actions that apply to what was clicked.
+
+use std::path::PathBuf;
+
+use ratatui::layout::Rect;
+
+use crate::app::{overlay::Overlay, AppState};
+
+/// What the menu was opened on
+#[derive(Clone, Debug, PartialEq)]
+pub enum MenuTarget {
+    File(PathBuf),
+    Generation,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MenuAction {
+    Open,
+    Rename,
+    Delete,
+    Attach,
+    Copy,
+    Export,
+    Apply,
+}
+
+impl MenuAction {
+    pub fn label(self) -> &'static str {
+        match self {
+            MenuAction::Open => "Open",
+            MenuAction::Rename => "Rename",
+            MenuAction::Delete => "Delete",
+            MenuAction::Attach => "Attach to Prompt",
+            MenuAction::Copy => "Copy",
+            MenuAction::Export => "Export to File",
+            MenuAction::Apply => "Apply to Open File",
+        }
+    }
+}
+
+const FILE_ACTIONS: &[MenuAction] = &[
+    MenuAction::Open,
+    MenuAction::Rename,
+    MenuAction::Delete,
+    MenuAction::Attach,
+];
+
+const GENERATION_ACTIONS: &[MenuAction] = &[MenuAction::Copy, MenuAction::Export, MenuAction::Apply];
+
+#[derive(Clone, Debug)]
+pub struct ContextMenu {
+    pub target: MenuTarget,
+    /// Screen cell the menu is anchored at (its top-left corner)
+    pub x: u16,
+    pub y: u16,
+    pub selected: usize,
+}
+
+impl ContextMenu {
+    pub fn new(target: MenuTarget, x: u16, y: u16) -> Self {
+        Self {
+            target,
+            x,
+            y,
+            selected: 0,
+        }
+    }
+
+    pub fn actions(&self) -> &'static [MenuAction] {
+        match self.target {
+            MenuTarget::File(_) => FILE_ACTIONS,
+            MenuTarget::Generation => GENERATION_ACTIONS,
+        }
+    }
+
+    pub fn selected_action(&self) -> Option<MenuAction> {
+        self.actions().get(self.selected).copied()
+    }
+
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1) % self.actions().len();
+    }
+
+    pub fn select_prev(&mut self) {
+        let len = self.actions().len();
+        self.selected = (self.selected + len - 1) % len;
+    }
+
+    /// Popup rect, shifted to stay inside `screen`
+    pub fn area(&self, screen: Rect) -> Rect {
+        let label_width = self
+            .actions()
+            .iter()
+            .map(|a| a.label().chars().count())
+            .max()
+            .unwrap_or(0);
+        let width = (label_width as u16 + 4).max(24).min(screen.width);
+        let height = (self.actions().len() as u16 + 2).min(screen.height);
+
+        Rect {
+            x: self.x.min(screen.right().saturating_sub(width)),
+            y: self.y.min(screen.bottom().saturating_sub(height)),
+            width,
+            height,
+        }
+    }
+
+    /// Action whose row is at screen row `y` (inside `area`)
+    pub fn action_at(&self, area: Rect, x: u16, y: u16) -> Option<usize> {
+        if !area.contains(ratatui::layout::Position { x, y }) {
+            return None;
+        }
+        let index = y.checked_sub(area.y + 1)? as usize;
+        (index < self.actions().len()).then_some(index)
+    }
+}
+
+impl AppState {
+    pub fn open_context_menu(&mut self, menu: ContextMenu) {
+        self.context_menu = Some(menu);
+        self.open_overlay(Overlay::ContextMenu);
+    }
+
+    /// Take the menu, to run its selected action
+    pub fn take_context_menu(&mut self) -> Option<ContextMenu> {
+        let menu = self.context_menu.take();
+        self.close_overlay(Overlay::ContextMenu);
+        menu
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_menu_stays_on_screen() {
+        let screen = Rect::new(0, 0, 80, 24);
+        let menu = ContextMenu::new(MenuTarget::File(PathBuf::from("a.rs")), 78, 23);
+        let area = menu.area(screen);
+
+        assert_eq!(area.right(), 80);
+        assert_eq!(area.bottom(), 24);
+        assert_eq!(menu.action_at(area, area.x + 1, area.y + 2), Some(1));
+        assert_eq!(menu.action_at(area, area.x + 1, area.y), None);
+    }
+
+    #[test]
+    fn test_navigation_wraps() {
+        let mut menu = ContextMenu::new(MenuTarget::Generation, 0, 0);
+        menu.select_prev();
+        assert_eq!(menu.selected_action(), Some(MenuAction::Apply));
+        menu.select_next();
+        assert_eq!(menu.selected_action(), Some(MenuAction::Copy));
+    }
+}
@@ -0,0 +1,239 @@
+//! Demo Mode
+//!
+//! `ims-tui --demo [script.toml]` plays a scripted session without a
+//! backend, for screenshots and conference demos. Each step waits its
+//! `delay_ms` after the previous one and there is no randomness, so every
+//! run looks the same. Without a path the bundled `demo.toml` is played.
+//!
+//! ```toml
+//! [[steps]]
+//! kind = "prompt"
+//! text = "Add a greeting"
+//!
+//! [[steps]]
+//! kind = "tokens"
+//! delay_ms = 800
+//! token_ms = 40
+//! text = "fn main() {\n    println!(\"Hello\");\n}\n"
+//! ```
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use super::{
+    api::{CostUsage, ExecuteResponse, MetricsResponse, TokenUsage},
+    metrics_store::RequestRecord,
+    thinking::{Level, ThinkingEntry},
+    ActiveSession, AppState,
+};
+use crate::core::runtime::{request_mutation, EventSender};
+
+/// Played by `--demo` without a path
+const BUNDLED: &str = include_str!("../../demo.toml");
+
+const USAGE: &str = "usage: ims-tui [--demo [script.toml] | doctor]";
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct DemoScript {
+    pub steps: Vec<DemoStep>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct DemoStep {
+    /// Pause before this step
+    #[serde(default)]
+    pub delay_ms: u64,
+    #[serde(flatten)]
+    pub action: DemoAction,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DemoAction {
+    /// Open `file` in a session on `model`
+    Open { file: PathBuf, model: String },
+    /// The user's prompt, as the Thinking pane shows it
+    Prompt { text: String },
+    Thinking { text: String },
+    /// Stream `text` into the Generation pane a word at a time
+    Tokens {
+        text: String,
+        #[serde(default = "default_token_ms")]
+        token_ms: u64,
+    },
+    /// Backend counters for the Inspector
+    Metrics { models: u64, queries: u64, filters: u64 },
+    /// The generation finished; usage is counted as for a real response
+    Complete {
+        model: String,
+        input_tokens: u32,
+        output_tokens: u32,
+        cost: f64,
+        latency_ms: f64,
+    },
+    Error {
+        model: String,
+        message: String,
+        #[serde(default)]
+        latency_ms: f64,
+    },
+}
+
+fn default_token_ms() -> u64 {
+    30
+}
+
+impl DemoScript {
+    pub fn parse(text: &str) -> Result<Self> {
+        let script: DemoScript = config::Config::builder()
+            .add_source(config::File::from_str(text, config::FileFormat::Toml))
+            .build()?
+            .try_deserialize()?;
+        anyhow::ensure!(!script.steps.is_empty(), "no [[steps]] defined");
+        Ok(script)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("Invalid demo script {}", path.display()))
+    }
+
+    pub fn bundled() -> Self {
+        Self::parse(BUNDLED).expect("bundled demo.toml is valid")
+    }
+
+    /// The script `--demo` asks for; `args` exclude the program name
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Option<Self>> {
+        let mut args = args.into_iter();
+        let Some(flag) = args.next() else { return Ok(None) };
+        anyhow::ensure!(flag == "--demo", "Unknown argument {} ({})", flag, USAGE);
+        match (args.next(), args.next()) {
+            (None, _) => Ok(Some(Self::bundled())),
+            (Some(path), None) => Self::load(Path::new(&path)).map(Some),
+            (Some(_), Some(extra)) => anyhow::bail!("Unknown argument {} ({})", extra, USAGE),
+        }
+    }
+}
+
+impl DemoAction {
+    pub fn apply(&self, state: &mut AppState) {
+        match self {
+            DemoAction::Open { file, model } => {
+                state.session = Some(ActiveSession::new(file.clone(), state.vendors.for_model(model), model.clone()));
+                state.generated_code.clear();
+                state.clear_generation_regions();
+            }
+            DemoAction::Prompt { text } => state.add_thinking(ThinkingEntry::user(text.clone())),
+            DemoAction::Thinking { text } => state.add_thinking(ThinkingEntry::agent(text.clone())),
+            DemoAction::Tokens { text, .. } => state.append_generation(text),
+            DemoAction::Metrics { models, queries, filters } => state.backend_metrics.update(MetricsResponse {
+                total_models_registered: Some(*models),
+                total_model_queries: Some(*queries),
+                total_filter_queries: Some(*filters),
+            }),
+            DemoAction::Complete {
+                model,
+                input_tokens,
+                output_tokens,
+                cost,
+                latency_ms,
+            } => {
+                let response = ExecuteResponse {
+                    content: String::new(),
+                    model_id: model.clone(),
+                    tokens: TokenUsage {
+                        input: *input_tokens,
+                        output: *output_tokens,
+                        total: input_tokens + output_tokens,
+                    },
+                    cost: CostUsage {
+                        input: 0.0,
+                        output: 0.0,
+                        total: *cost,
+                    },
+                    latency_ms: *latency_ms,
+                    tool_calls: Vec::new(),
+                    images: Vec::new(),
+                    transcripts: Vec::new(),
+                };
+                state.add_thinking(ThinkingEntry::system(format!(
+                    "Finished in {:.2}ms. Tokens: {} (Cost: ${:.6})",
+                    response.latency_ms, response.tokens.total, response.cost.total
+                )));
+                state.total_tokens_used += response.tokens.total as u64;
+                state.total_cost += response.cost.total;
+                state.record_request(RequestRecord::from_response(&response));
+            }
+            DemoAction::Error { model, message, latency_ms } => {
+                state.add_debug_log(format!("API Error: Prompt failed: {}", message));
+                state.add_thinking(
+                    ThinkingEntry::system(format!("✗ Prompt failed: {}", message)).with_level(Level::Error),
+                );
+                state.record_request(RequestRecord::failed(model.clone(), *latency_ms, message.clone()));
+            }
+        }
+    }
+}
+
+/// Play `script` into the event loop, stopping early if it exits
+pub async fn play(script: DemoScript, tx: EventSender) {
+    for step in script.steps {
+        tokio::time::sleep(Duration::from_millis(step.delay_ms)).await;
+        match step.action {
+            DemoAction::Tokens { text, token_ms } => {
+                for (i, token) in text.split_inclusive(char::is_whitespace).enumerate() {
+                    if i > 0 {
+                        tokio::time::sleep(Duration::from_millis(token_ms)).await;
+                    }
+                    let token = token.to_string();
+                    request_mutation(&tx, move |state| state.append_generation(&token));
+                }
+            }
+            action => request_mutation(&tx, move |state| action.apply(state)),
+        }
+        if tx.is_closed() {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_script_plays_without_a_backend() {
+        let script = DemoScript::bundled();
+        let mut state = AppState::default();
+        for step in &script.steps {
+            step.action.apply(&mut state);
+        }
+        assert!(state.session.is_some());
+        assert!(!state.generated_code.is_empty());
+        assert!(state.total_tokens_used > 0);
+        assert!(state.backend_metrics.latest.is_some());
+        assert!(state.thinking_log.iter().any(|e| e.level == Level::Error));
+    }
+
+    #[test]
+    fn test_from_args() {
+        let args = |list: &[&str]| list.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+        assert!(DemoScript::from_args(args(&[])).unwrap().is_none());
+        assert!(DemoScript::from_args(args(&["--demo"])).unwrap().is_some());
+        assert!(DemoScript::from_args(args(&["--demo", "/no/such/script.toml"])).is_err());
+        assert!(DemoScript::from_args(args(&["--verbose"])).is_err());
+
+        let script = DemoScript::parse("[[steps]]\nkind = \"tokens\"\ndelay_ms = 5\ntext = \"a b\"").unwrap();
+        assert_eq!(script.steps[0].delay_ms, 5);
+        assert_eq!(
+            script.steps[0].action,
+            DemoAction::Tokens {
+                text: "a b".to_string(),
+                token_ms: 30
+            }
+        );
+    }
+}
@@ -0,0 +1,341 @@
+//! Environment Doctor
+//!
+//! `ims-tui doctor` (or "System: Run Doctor" from the palette) checks what
+//! the TUI depends on: a config file that parses, a backend that answers,
+//! an admin key it accepts, a terminal it can draw on and a `.ims/` it can
+//! write to. Each check passes, warns or fails, and a failure says what to
+//! do about it. The backend is asked directly, bypassing mock mode.
+
+use std::io::IsTerminal;
+use std::path::Path;
+
+use anyhow::Context;
+
+use crate::app::{
+    api::{ImsApiClient, RecommendationRequest},
+    api_error::ApiError,
+    config::AppConfig,
+    guardrails::Guardrails,
+    health::HealthLevel,
+    redact::Redactor,
+    thinking::{Level, ThinkingEntry},
+    vendors::VendorRegistry,
+    AppState,
+};
+use crate::ui::colors::{self, ColorDepth};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl Status {
+    pub fn label(self) -> &'static str {
+        match self {
+            Status::Pass => "PASS",
+            Status::Warn => "WARN",
+            Status::Fail => "FAIL",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: Status,
+    pub detail: String,
+    /// What to do about a warning or failure
+    pub hint: Option<String>,
+}
+
+impl Check {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Pass,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Warn,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Fail,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Report {
+    pub checks: Vec<Check>,
+}
+
+impl Report {
+    pub fn count(&self, status: Status) -> usize {
+        self.checks.iter().filter(|c| c.status == status).count()
+    }
+
+    pub fn passed(&self) -> bool {
+        self.count(Status::Fail) == 0
+    }
+
+    /// "All 5 checks passed", "1 failed, 2 warnings"
+    pub fn summary(&self) -> String {
+        let (failed, warned) = (self.count(Status::Fail), self.count(Status::Warn));
+        match (failed, warned) {
+            (0, 0) => format!("All {} checks passed", self.checks.len()),
+            (0, w) => format!("{} warning{}", w, if w == 1 { "" } else { "s" }),
+            (f, 0) => format!("{} failed", f),
+            (f, w) => format!("{} failed, {} warning{}", f, w, if w == 1 { "" } else { "s" }),
+        }
+    }
+
+    /// The report as printed by `ims-tui doctor`
+    pub fn render(&self) -> String {
+        let mut out = String::from("IMS-TUI doctor\n\n");
+        for check in &self.checks {
+            out.push_str(&format!("[{}] {:<9} {}\n", check.status.label(), check.name, check.detail));
+            if let Some(hint) = &check.hint {
+                out.push_str(&format!("{:17}-> {}\n", "", hint));
+            }
+        }
+        out.push_str(&format!("\n{}\n", self.summary()));
+        out
+    }
+}
+
+/// Parse the config at `path` and build everything derived from it
+pub fn check_config(path: &Path) -> (Check, Option<AppConfig>) {
+    const NAME: &str = "Config";
+    let hint = format!("Fix {} (config.example.toml documents every option)", path.display());
+    let config = match AppConfig::load_from(path) {
+        Ok(config) => config,
+        Err(e) => return (Check::fail(NAME, format!("{:#}", e), hint), None),
+    };
+    let derived = || -> anyhow::Result<()> {
+        config.api.endpoint().context("Invalid [api] config")?;
+        Redactor::new(&config.redaction).context("Invalid [redaction] config")?;
+        VendorRegistry::new(&config.vendors, &config.local).context("Invalid [[vendors]] config")?;
+        Guardrails::new(&config.guardrails).context("Invalid [guardrails] config")?;
+        Ok(())
+    };
+    let check = match derived() {
+        Err(e) => Check::fail(NAME, format!("{:#}", e), hint),
+        Ok(()) if path.exists() => Check::pass(NAME, format!("{} is valid", path.display())),
+        Ok(()) => Check::pass(NAME, format!("{} not found, using defaults", path.display())),
+    };
+    (check, Some(config))
+}
+
+/// Ask the backend for its health
+pub async fn check_backend(client: &ImsApiClient, base_url: &str) -> Check {
+    const NAME: &str = "Backend";
+    match client.health_check().await {
+        Ok(health) if HealthLevel::from_status(&health.status) == HealthLevel::Healthy => {
+            Check::pass(NAME, format!("IMS Core at {} is {}", base_url, health.status))
+        }
+        Ok(health) => Check::warn(
+            NAME,
+            format!("IMS Core at {} reports {} (database {}, cache {})", base_url, health.status, health.database, health.cache),
+            "Check the backend's own logs for the subsystem that is down",
+        ),
+        Err(e) => Check::fail(
+            NAME,
+            format!("{:#}", e),
+            "Start IMS Core, or point IMS_API_URL (or the [api] profile's base_url) at it",
+        ),
+    }
+}
+
+/// Try the admin key on an admin endpoint that changes nothing
+pub async fn check_auth(client: &ImsApiClient, key_var: &str, reachable: bool) -> Check {
+    const NAME: &str = "Auth";
+    if !client.has_admin_key() {
+        return Check::warn(
+            NAME,
+            format!("{} is not set; admin actions (activation, pricing, recommendations) are unavailable", key_var),
+            format!("export {}=<admin key>", key_var),
+        );
+    }
+    if !reachable {
+        return Check::warn(NAME, "Not checked: the backend is unreachable", "Fix the backend check first");
+    }
+    let criteria = RecommendationRequest {
+        min_capability_tier: None,
+        min_context_window: 0,
+        max_cost_per_mil: None,
+        strategy: "cost".to_string(),
+    };
+    match client.recommend_models(&criteria).await {
+        Ok(_) => Check::pass(NAME, format!("{} is accepted", key_var)),
+        Err(e) => match e.downcast_ref::<ApiError>() {
+            Some(ApiError::Unauthorized { status, .. }) => Check::fail(
+                NAME,
+                format!("{} was rejected ({})", key_var, status),
+                format!("Set {} to a valid admin key for this backend", key_var),
+            ),
+            _ => Check::warn(NAME, format!("Could not verify {}: {:#}", key_var, e), "Try again once the backend is healthy"),
+        },
+    }
+}
+
+/// Whether the TUI can draw here: a terminal of at least 80x24, and how
+/// many colors it has
+pub fn check_terminal(is_tty: bool, colorterm: Option<&str>, term: Option<&str>, size: Option<(u16, u16)>) -> Check {
+    const NAME: &str = "Terminal";
+    if !is_tty {
+        return Check::fail(NAME, "Not run from an interactive terminal", "Run ims-tui in a terminal, not with input redirected");
+    }
+    let Some(term) = term.filter(|t| !t.is_empty() && *t != "dumb") else {
+        return Check::fail(NAME, "TERM is unset or \"dumb\"", "Set TERM for your terminal, e.g. TERM=xterm-256color");
+    };
+    let depth = colors::detect(colorterm, Some(term));
+    let colors = match depth {
+        ColorDepth::TrueColor => "24-bit color",
+        ColorDepth::Ansi256 => "256 colors",
+        ColorDepth::Ansi16 => "16 colors",
+    };
+    let detail = match size {
+        Some((w, h)) => format!("{}, {}, {}x{}", term, colors, w, h),
+        None => format!("{}, {}", term, colors),
+    };
+    match size {
+        Some((w, h)) if w < 80 || h < 24 => {
+            Check::warn(NAME, detail, "Panes are cramped below 80x24; enlarge the window or use zen mode")
+        }
+        _ if depth == ColorDepth::Ansi16 => Check::warn(
+            NAME,
+            detail,
+            "Colors are approximated; set COLORTERM=truecolor or a *-256color TERM if the terminal supports it",
+        ),
+        _ => Check::pass(NAME, detail),
+    }
+}
+
+/// Write and remove a file in `dir`, and see whether `config` can be saved
+pub fn check_disk(dir: &Path, config: &Path) -> Check {
+    const NAME: &str = "Disk";
+    let probe = dir.join(".doctor");
+    let written = std::fs::create_dir_all(dir)
+        .and_then(|()| std::fs::write(&probe, b"ok"))
+        .and_then(|()| std::fs::remove_file(&probe));
+    if let Err(e) = written {
+        return Check::fail(
+            NAME,
+            format!("Can't write to {}: {}", dir.display(), e),
+            format!("Make {} writable, or run ims-tui from a directory you own", dir.display()),
+        );
+    }
+    let read_only = std::fs::metadata(config).is_ok_and(|m| m.permissions().readonly());
+    if read_only {
+        return Check::warn(
+            NAME,
+            format!("{} is read-only; Settings changes won't be saved", config.display()),
+            format!("chmod u+w {}", config.display()),
+        );
+    }
+    Check::pass(NAME, format!("{} is writable", dir.display()))
+}
+
+/// Every check, in order, for the config at `config_path`
+pub async fn run(config_path: &Path) -> Report {
+    let (config_check, config) = check_config(config_path);
+    let config = config.unwrap_or_default();
+    let mut checks = vec![config_check];
+
+    let endpoint = config.api.endpoint().unwrap_or_default();
+    let base_url = endpoint.base_url();
+    let key_var = endpoint.key_var().to_string();
+    let client = ImsApiClient::new(base_url.clone(), std::env::var(&key_var).ok(), false, &config.http)
+        .map(|client| client.with_endpoint(endpoint));
+    match client {
+        Ok(client) => {
+            let backend = check_backend(&client, &base_url).await;
+            let reachable = backend.status != Status::Fail;
+            checks.push(backend);
+            checks.push(check_auth(&client, &key_var, reachable).await);
+        }
+        Err(e) => checks.push(Check::fail("Backend", format!("{:#}", e), "Fix the [http] settings")),
+    }
+
+    let colorterm = std::env::var("COLORTERM").ok();
+    let term = std::env::var("TERM").ok();
+    let size = crossterm::terminal::size().ok();
+    checks.push(check_terminal(std::io::stdin().is_terminal(), colorterm.as_deref(), term.as_deref(), size));
+    checks.push(check_disk(&crate::app::ims_dir(), config_path));
+    Report { checks }
+}
+
+impl AppState {
+    /// List a doctor report in Thinking, one entry per check
+    pub fn show_doctor_report(&mut self, report: &Report) {
+        for check in &report.checks {
+            let level = match check.status {
+                Status::Pass => Level::Info,
+                Status::Warn => Level::Warning,
+                Status::Fail => Level::Error,
+            };
+            let text = match &check.hint {
+                Some(hint) => format!("Doctor: {} {} - {} ({})", check.status.label(), check.name, check.detail, hint),
+                None => format!("Doctor: {} {} - {}", check.status.label(), check.name, check.detail),
+            };
+            self.add_thinking(ThinkingEntry::system(text).with_level(level));
+        }
+        let level = if report.passed() { Level::Info } else { Level::Error };
+        self.add_thinking(ThinkingEntry::system(format!("Doctor: {}", report.summary())).with_level(level));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checks_and_report() {
+        let dir = std::env::temp_dir().join(format!("ims-tui-doctor-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+        std::fs::write(&path, "[api]\nprofile = \"azure\"\n").unwrap();
+        let (check, _) = check_config(&path);
+        assert_eq!(check.status, Status::Fail);
+        assert!(check.detail.contains("azure"), "{}", check.detail);
+
+        assert_eq!(check_terminal(false, None, Some("xterm"), None).status, Status::Fail);
+        let check = check_terminal(true, None, Some("xterm-256color"), Some((120, 40)));
+        assert_eq!((check.status, check.detail.as_str()), (Status::Pass, "xterm-256color, 256 colors, 120x40"));
+        assert_eq!(check_terminal(true, Some("truecolor"), Some("xterm"), Some((60, 20))).status, Status::Warn);
+        assert_eq!(check_disk(&dir.join(".ims"), &path).status, Status::Pass);
+        std::fs::remove_dir_all(&dir).ok();
+
+        let report = Report {
+            checks: vec![
+                Check::pass("Config", "config.toml is valid"),
+                Check::fail("Backend", "connection refused", "Start IMS Core"),
+            ],
+        };
+        assert!(!report.passed());
+        assert_eq!(report.summary(), "1 failed");
+        assert_eq!(
+            report.render(),
+            "IMS-TUI doctor\n\n\
+             [PASS] Config    config.toml is valid\n\
+             [FAIL] Backend   connection refused\n\
+             \x20                -> Start IMS Core\n\
+             \n1 failed\n"
+        );
+    }
+}
@@ -0,0 +1,128 @@
+//! Endpoint Profiles
+//!
+//! Organizations running IMS Core behind Azure OpenAI (API Management) or
+//! an AWS Bedrock API Gateway reach it through a gateway with its own URL,
+//! credential header and model names. An `[[api.profiles]]` entry describes
+//! such a gateway and `[api] profile` picks one; without one, requests go
+//! to IMS Core as-is (`X-Admin-Key`, `IMS_API_URL`, `ADMIN_API_KEY`).
+
+use anyhow::{bail, Result};
+
+use crate::app::config::{ApiConfig, AuthScheme, EndpointProfile};
+
+impl ApiConfig {
+    /// The selected profile, or IMS Core's own conventions when none is
+    pub fn endpoint(&self) -> Result<EndpointProfile> {
+        let Some(name) = &self.profile else { return Ok(EndpointProfile::default()) };
+        match self.profiles.iter().find(|p| &p.name == name) {
+            Some(profile) => Ok(profile.clone()),
+            None => bail!("no [[api.profiles]] entry named {:?}", name),
+        }
+    }
+}
+
+impl EndpointProfile {
+    /// Where IMS Core is reached: the profile's URL, else `IMS_API_URL`
+    pub fn base_url(&self) -> String {
+        match &self.base_url {
+            Some(url) => url.clone(),
+            None => std::env::var("IMS_API_URL").unwrap_or_else(|_| "http://localhost:8000".to_string()),
+        }
+    }
+
+    /// Environment variable the key is read from
+    pub fn key_var(&self) -> &str {
+        self.key_env.as_deref().unwrap_or("ADMIN_API_KEY")
+    }
+
+    /// Present `key` the way the gateway expects
+    pub fn authorize(&self, request: reqwest::RequestBuilder, key: &str) -> reqwest::RequestBuilder {
+        match self.auth {
+            AuthScheme::Header => request.header(self.key_header.as_str(), key),
+            AuthScheme::Bearer => request.bearer_auth(key),
+        }
+    }
+
+    /// Add the profile's fixed headers and query parameters
+    pub fn decorate(&self, mut request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        for (name, value) in &self.headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+        if !self.query.is_empty() {
+            request = request.query(&self.query);
+        }
+        request
+    }
+
+    /// The gateway's name for `model_id`
+    pub fn deployment<'a>(&'a self, model_id: &'a str) -> &'a str {
+        self.deployments
+            .iter()
+            .find(|d| d.model == model_id)
+            .map_or(model_id, |d| d.deployment.as_str())
+    }
+
+    /// The model id the gateway's `deployment` stands for
+    pub fn model<'a>(&'a self, deployment: &'a str) -> &'a str {
+        self.deployments
+            .iter()
+            .find(|d| d.deployment == deployment)
+            .map_or(deployment, |d| d.model.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::config::Deployment;
+
+    #[test]
+    fn test_profile_shapes_requests() {
+        let azure = EndpointProfile {
+            name: "azure".to_string(),
+            base_url: Some("https://contoso.azure-api.net/ims".to_string()),
+            key_header: "api-key".to_string(),
+            key_env: Some("AZURE_OPENAI_KEY".to_string()),
+            query: [("api-version".to_string(), "2024-06-01".to_string())].into(),
+            deployments: vec![Deployment {
+                model: "gpt-4o".to_string(),
+                deployment: "contoso-gpt4o".to_string(),
+            }],
+            ..Default::default()
+        };
+        let config = ApiConfig {
+            profile: Some("azure".to_string()),
+            profiles: vec![azure],
+            ..Default::default()
+        };
+        let azure = config.endpoint().unwrap();
+        assert_eq!(azure.key_var(), "AZURE_OPENAI_KEY");
+        assert_eq!(azure.deployment("gpt-4o"), "contoso-gpt4o");
+        assert_eq!(azure.model("contoso-gpt4o"), "gpt-4o");
+        assert_eq!(azure.deployment("claude-3-haiku"), "claude-3-haiku");
+
+        let client = reqwest::Client::new();
+        let request = azure
+            .decorate(azure.authorize(client.get("https://contoso.azure-api.net/ims/health"), "k1"))
+            .build()
+            .unwrap();
+        assert_eq!(request.headers()["api-key"], "k1");
+        assert_eq!(request.url().query(), Some("api-version=2024-06-01"));
+
+        let bedrock = EndpointProfile {
+            auth: AuthScheme::Bearer,
+            ..Default::default()
+        };
+        let request = bedrock.authorize(client.get("https://example.com"), "k2").build().unwrap();
+        assert_eq!(request.headers()["authorization"], "Bearer k2");
+
+        // IMS Core's own conventions without a profile
+        let direct = ApiConfig::default().endpoint().unwrap();
+        assert_eq!((direct.key_header.as_str(), direct.key_var()), ("X-Admin-Key", "ADMIN_API_KEY"));
+        let missing = ApiConfig {
+            profile: Some("bedrock".to_string()),
+            ..Default::default()
+        };
+        assert!(missing.endpoint().is_err());
+    }
+}
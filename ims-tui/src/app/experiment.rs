@@ -0,0 +1,356 @@
+//! A/B Experiments
+//!
+//! "Experiment: Start A/B Test" splits the prompts typed in the prompt box
+//! between the two `[experiment] models`: alternately, or `share` of them
+//! to the second. Each arm counts its requests, latency, cost and the
+//! ratings its responses were given, and "Experiment: Show Report" puts
+//! the two side by side (`e` there exports it as Markdown) to back a
+//! routing change with numbers.
+
+use anyhow::{Context, Result};
+use chrono::Local;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::app::{
+    api::ExecuteResponse,
+    overlay::Overlay,
+    thinking::{Level, ThinkingEntry},
+    AppState,
+};
+
+/// One model's side of an experiment
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Arm {
+    pub model_id: String,
+    pub sent: u32,
+    pub completed: u32,
+    pub failed: u32,
+    /// Completed without the response cache, so latency and cost count
+    pub measured: u32,
+    /// Summed over measured responses
+    pub latency_ms: f64,
+    pub cost: f64,
+    pub tokens: u64,
+    pub positive: u32,
+    pub negative: u32,
+}
+
+impl Arm {
+    fn new(model_id: &str) -> Self {
+        Self {
+            model_id: model_id.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn avg_latency_ms(&self) -> Option<f64> {
+        (self.measured > 0).then(|| self.latency_ms / f64::from(self.measured))
+    }
+
+    pub fn avg_cost(&self) -> Option<f64> {
+        (self.measured > 0).then(|| self.cost / f64::from(self.measured))
+    }
+
+    pub fn avg_tokens(&self) -> Option<u64> {
+        (self.measured > 0).then(|| self.tokens / u64::from(self.measured))
+    }
+
+    /// Share of ratings that were thumbs-up, 0 to 100
+    pub fn satisfaction(&self) -> Option<u32> {
+        let rated = self.positive + self.negative;
+        (self.positive * 100 + rated / 2).checked_div(rated)
+    }
+
+    /// Share of requests sent that failed, 0 to 100
+    pub fn failure_rate(&self) -> Option<u32> {
+        (self.failed * 100 + self.sent / 2).checked_div(self.sent)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Experiment {
+    pub arms: [Arm; 2],
+    /// Share of prompts sent to the second arm
+    pub share: f64,
+    /// Unix seconds
+    pub started: i64,
+    /// Stopped experiments keep their results for the report
+    pub running: bool,
+    /// Arm each request was sent to, by request key
+    requests: HashMap<String, usize>,
+    /// Arm of each conversation turn, for ratings
+    turns: HashMap<usize, usize>,
+}
+
+impl Experiment {
+    pub fn new(a: &str, b: &str, share: f64) -> Self {
+        Self {
+            arms: [Arm::new(a), Arm::new(b)],
+            share: share.clamp(0.0, 1.0),
+            started: Local::now().timestamp(),
+            running: true,
+            requests: HashMap::new(),
+            turns: HashMap::new(),
+        }
+    }
+
+    /// Arm for the next prompt: the second whenever it has fallen behind
+    /// its share, so 0.5 alternates starting with the first
+    pub fn next_arm(&self) -> usize {
+        let total = self.arms[0].sent + self.arms[1].sent;
+        usize::from(f64::from(self.arms[1].sent + 1) <= self.share * f64::from(total + 1))
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let [a, b] = &self.arms;
+        let fmt = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+        let rows = [
+            ("Requests", a.sent.to_string(), b.sent.to_string()),
+            ("Completed", a.completed.to_string(), b.completed.to_string()),
+            (
+                "Failure rate",
+                fmt(a.failure_rate().map(|p| format!("{}%", p))),
+                fmt(b.failure_rate().map(|p| format!("{}%", p))),
+            ),
+            (
+                "Avg latency",
+                fmt(a.avg_latency_ms().map(|ms| format!("{:.0} ms", ms))),
+                fmt(b.avg_latency_ms().map(|ms| format!("{:.0} ms", ms))),
+            ),
+            (
+                "Avg cost",
+                fmt(a.avg_cost().map(|c| format!("${:.4}", c))),
+                fmt(b.avg_cost().map(|c| format!("${:.4}", c))),
+            ),
+            ("Total cost", format!("${:.4}", a.cost), format!("${:.4}", b.cost)),
+            (
+                "Avg tokens",
+                fmt(a.avg_tokens().map(|t| t.to_string())),
+                fmt(b.avg_tokens().map(|t| t.to_string())),
+            ),
+            (
+                "Satisfaction",
+                fmt(a.satisfaction().map(|p| format!("{}% of {}", p, a.positive + a.negative))),
+                fmt(b.satisfaction().map(|p| format!("{}% of {}", p, b.positive + b.negative))),
+            ),
+        ];
+        let started = chrono::DateTime::from_timestamp(self.started, 0)
+            .map(|t| t.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default();
+        let mut out = format!(
+            "# A/B experiment: {} vs {}\n\nStarted {}, {:.0}% of prompts to {}.\n\n| | {} | {} |\n|---|---|---|\n",
+            a.model_id,
+            b.model_id,
+            started,
+            self.share * 100.0,
+            b.model_id,
+            a.model_id,
+            b.model_id
+        );
+        for (metric, a, b) in rows {
+            out.push_str(&format!("| {} | {} | {} |\n", metric, a, b));
+        }
+        out
+    }
+}
+
+impl AppState {
+    /// Start splitting prompts between the `[experiment] models`
+    pub fn start_experiment(&mut self) {
+        let models = &self.config.experiment.models;
+        let entry = match models.as_slice() {
+            [a, b] if a != b => {
+                let experiment = Experiment::new(a, b, self.config.experiment.share);
+                let entry = ThinkingEntry::system(format!(
+                    "🧪 A/B test started: {} vs {} ({:.0}% to {})",
+                    a,
+                    b,
+                    experiment.share * 100.0,
+                    b
+                ));
+                self.experiment = Some(experiment);
+                entry
+            }
+            _ => ThinkingEntry::system("✗ Set two different models in [experiment] models to run an A/B test")
+                .with_level(Level::Warning),
+        };
+        self.add_thinking(entry);
+    }
+
+    /// Stop sending prompts to the arms and show the results
+    pub fn stop_experiment(&mut self) {
+        let Some(experiment) = self.experiment.as_mut().filter(|e| e.running) else {
+            self.add_debug_log("No A/B test running".to_string());
+            return;
+        };
+        experiment.running = false;
+        self.add_thinking(ThinkingEntry::system("🧪 A/B test stopped"));
+        self.open_overlay(Overlay::Experiment);
+    }
+
+    /// Model the next prompt from the prompt box goes to, while an
+    /// experiment runs
+    pub fn experiment_model(&self) -> Option<(usize, String)> {
+        let experiment = self.experiment.as_ref().filter(|e| e.running)?;
+        let arm = experiment.next_arm();
+        Some((arm, experiment.arms[arm].model_id.clone()))
+    }
+
+    /// A prompt went to `arm` as request `key`, answering the latest turn
+    pub fn experiment_sent(&mut self, arm: usize, key: String) {
+        let turn = self.conversation.len().saturating_sub(1);
+        let Some(experiment) = self.experiment.as_mut() else { return };
+        experiment.arms[arm].sent += 1;
+        experiment.requests.insert(key, arm);
+        experiment.turns.insert(turn, arm);
+        let label = if arm == 0 { "A" } else { "B" };
+        let entry = ThinkingEntry::system(format!("🧪 Arm {}: {}", label, experiment.arms[arm].model_id));
+        self.add_thinking(entry);
+    }
+
+    pub fn experiment_completed(&mut self, key: &str, response: &ExecuteResponse, cached: bool) {
+        let Some(arm) = self.experiment_arm(key) else { return };
+        arm.completed += 1;
+        if !cached {
+            arm.measured += 1;
+            arm.latency_ms += response.latency_ms;
+            arm.cost += response.cost.total;
+            arm.tokens += u64::from(response.tokens.total);
+        }
+    }
+
+    pub fn experiment_failed(&mut self, key: &str) {
+        if let Some(arm) = self.experiment_arm(key) {
+            arm.failed += 1;
+        }
+    }
+
+    fn experiment_arm(&mut self, key: &str) -> Option<&mut Arm> {
+        let experiment = self.experiment.as_mut()?;
+        let arm = experiment.requests.remove(key)?;
+        Some(&mut experiment.arms[arm])
+    }
+
+    /// Count a rating of the response to `turn` toward its arm
+    pub fn experiment_rated(&mut self, turn: usize, positive: bool) {
+        let Some(experiment) = self.experiment.as_mut() else { return };
+        let Some(&arm) = experiment.turns.get(&turn) else { return };
+        let arm = &mut experiment.arms[arm];
+        if positive {
+            arm.positive += 1;
+        } else {
+            arm.negative += 1;
+        }
+    }
+
+    pub fn open_experiment_report(&mut self) {
+        if self.experiment.is_none() {
+            self.add_debug_log("No A/B test yet; run \"Experiment: Start A/B Test\"".to_string());
+            return;
+        }
+        self.open_overlay(Overlay::Experiment);
+    }
+
+    /// Write the report to `.ims/reports/experiment-<time>.md`
+    pub fn export_experiment_report(&mut self) {
+        let Some(experiment) = &self.experiment else { return };
+        match export_markdown(experiment) {
+            Ok(path) => self.add_debug_log(format!("Exported A/B report to {}", path.display())),
+            Err(e) => self.add_debug_log(format!("Failed to export A/B report: {:#}", e)),
+        }
+    }
+}
+
+fn export_markdown(experiment: &Experiment) -> Result<PathBuf> {
+    let dir = crate::app::ims_dir().join("reports");
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let path = dir.join(format!("experiment-{}.md", Local::now().format("%Y%m%d-%H%M%S")));
+    std::fs::write(&path, experiment.to_markdown()).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{
+        api::{CostUsage, TokenUsage},
+        config::{AppConfig, ExperimentConfig},
+    };
+
+    fn response(model_id: &str, latency_ms: f64, cost: f64) -> ExecuteResponse {
+        ExecuteResponse {
+            content: String::new(),
+            model_id: model_id.to_string(),
+            tokens: TokenUsage {
+                input: 50,
+                output: 50,
+                total: 100,
+            },
+            cost: CostUsage {
+                input: 0.0,
+                output: cost,
+                total: cost,
+            },
+            latency_ms,
+            tool_calls: Vec::new(),
+            images: Vec::new(),
+            transcripts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_share_decides_the_arm() {
+        let mut experiment = Experiment::new("gpt-4o", "claude-3-haiku", 0.25);
+        let mut arms = Vec::new();
+        for _ in 0..8 {
+            let arm = experiment.next_arm();
+            experiment.arms[arm].sent += 1;
+            arms.push(arm);
+        }
+        assert_eq!(arms, [0, 0, 0, 1, 0, 0, 0, 1]);
+
+        let alternate = Experiment::new("gpt-4o", "claude-3-haiku", 0.5);
+        assert_eq!(alternate.next_arm(), 0);
+    }
+
+    #[test]
+    fn test_results_are_counted_per_arm() {
+        let mut state = AppState {
+            config: AppConfig {
+                experiment: ExperimentConfig {
+                    models: vec!["gpt-4o".to_string(), "claude-3-haiku".to_string()],
+                    share: 0.5,
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        state.start_experiment();
+
+        for (turn, key) in ["a1", "b1", "a2", "b2"].into_iter().enumerate() {
+            let (arm, model) = state.experiment_model().unwrap();
+            assert_eq!(arm, turn % 2);
+            state.conversation.push(crate::app::ConversationTurn::new("write a parser", model));
+            state.experiment_sent(arm, key.to_string());
+        }
+        state.experiment_completed("a1", &response("gpt-4o", 1_000.0, 0.02), false);
+        state.experiment_completed("a2", &response("gpt-4o", 3_000.0, 0.04), false);
+        state.experiment_completed("b1", &response("claude-3-haiku", 400.0, 0.001), true);
+        state.experiment_failed("b2");
+        state.experiment_rated(0, true);
+        state.experiment_rated(1, false);
+
+        let [a, b] = &state.experiment.as_ref().unwrap().arms;
+        assert_eq!((a.completed, a.avg_latency_ms(), a.satisfaction()), (2, Some(2_000.0), Some(100)));
+        // The cached response counts as completed but not in latency or cost
+        assert_eq!((b.completed, b.failed, b.avg_cost(), b.satisfaction()), (1, 1, None, Some(0)));
+
+        state.stop_experiment();
+        assert!(state.overlays.is_open(Overlay::Experiment));
+        assert_eq!(state.experiment_model(), None);
+        let report = state.experiment.as_ref().unwrap().to_markdown();
+        assert!(report.contains("| Avg latency | 2000 ms | - |"), "{}", report);
+        assert!(report.contains("| Failure rate | 0% | 50% |"), "{}", report);
+    }
+}
@@ -0,0 +1,127 @@
+//! External Editor
+//!
+//! Ctrl+E opens the prompt in `$VISUAL` (or `$EDITOR`, or `vi`) with the
+//! TUI suspended, and puts what was saved back in the prompt box when the
+//! editor exits; long, multi-line prompts are far easier to write there.
+//! The session's system instruction is edited the same way from the
+//! palette.
+
+use anyhow::{bail, Context, Result};
+use std::process::Command;
+
+use crate::app::{thinking::ThinkingEntry, AppState, FocusPane, InputMode};
+
+/// What is being edited
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EditTarget {
+    Prompt,
+    SystemInstruction,
+}
+
+/// The editor to run, as program then arguments (`EDITOR="code --wait"`)
+pub fn editor_command() -> Vec<String> {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .map(|value| value.split_whitespace().map(String::from).collect::<Vec<_>>())
+        .find(|command| !command.is_empty())
+        .unwrap_or_else(|| vec!["vi".to_string()])
+}
+
+/// Open `text` in the user's editor; what was saved once it exits
+pub fn edit(text: &str) -> Result<String> {
+    edit_with(&editor_command(), text)
+}
+
+fn edit_with(command: &[String], text: &str) -> Result<String> {
+    let Some((program, args)) = command.split_first() else {
+        bail!("No editor command");
+    };
+    let path = std::env::temp_dir().join(format!("ims-prompt-{}.md", uuid::Uuid::new_v4()));
+    std::fs::write(&path, text).with_context(|| format!("Failed to write {}", path.display()))?;
+
+    let status = Command::new(program).args(args).arg(&path).status();
+    let edited = std::fs::read_to_string(&path);
+    let _ = std::fs::remove_file(&path);
+
+    let status = status.with_context(|| format!("Failed to run editor {}", program))?;
+    if !status.success() {
+        bail!("Editor {} exited with {}, prompt left as it was", program, status);
+    }
+    let edited = edited.with_context(|| format!("Failed to read {}", path.display()))?;
+    // Editors end the file with a newline the prompt didn't have
+    Ok(edited.strip_suffix('\n').unwrap_or(&edited).to_string())
+}
+
+impl AppState {
+    /// Open `target` in the editor before the next frame
+    pub fn request_external_edit(&mut self, target: EditTarget) {
+        self.external_edit = Some(target);
+    }
+
+    /// The text `target` starts from in the editor
+    pub fn external_text(&self, target: EditTarget) -> String {
+        match target {
+            EditTarget::Prompt => self.input_buffer.as_str().to_string(),
+            EditTarget::SystemInstruction => self.system_instruction.clone().unwrap_or_default(),
+        }
+    }
+
+    /// Take back what was saved in the editor
+    pub fn finish_external_edit(&mut self, target: EditTarget, edited: Result<String>) {
+        let edited = match edited {
+            Ok(edited) => edited,
+            Err(e) => {
+                self.add_debug_log(format!("{:#}", e));
+                return;
+            }
+        };
+        match target {
+            EditTarget::Prompt => {
+                self.input_buffer.set(edited);
+                self.focus = FocusPane::Prompt;
+                self.input_mode = InputMode::Editing;
+            }
+            EditTarget::SystemInstruction => {
+                let instruction = edited.trim();
+                self.system_instruction = (!instruction.is_empty()).then(|| instruction.to_string());
+                self.add_thinking(ThinkingEntry::system(match &self.system_instruction {
+                    Some(instruction) => format!("System instruction set ({} chars)", instruction.chars().count()),
+                    None => "System instruction cleared".to_string(),
+                }));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A stand-in editor that saves `script`'s output over the file
+    fn editor(script: &str) -> Vec<String> {
+        vec!["sh".to_string(), "-c".to_string(), script.to_string(), "sh".to_string()]
+    }
+
+    #[test]
+    fn test_edit_round_trip() {
+        let edited = edit_with(&editor(r#"sed 's/draft/final/' "$1" > "$1.new" && mv "$1.new" "$1""#), "a draft\nprompt").unwrap();
+        assert_eq!(edited, "a final\nprompt");
+
+        let failed = edit_with(&editor("exit 1"), "kept").unwrap_err();
+        assert!(failed.to_string().contains("prompt left as it was"), "{}", failed);
+
+        let mut state = AppState::default();
+        state.input_buffer.set("kept");
+        state.finish_external_edit(EditTarget::Prompt, Err(failed));
+        assert_eq!(state.input_buffer.as_str(), "kept");
+        state.finish_external_edit(EditTarget::Prompt, Ok("line one\nline two".to_string()));
+        assert_eq!(state.external_text(EditTarget::Prompt), "line one\nline two");
+        assert_eq!(state.input_mode, InputMode::Editing);
+
+        state.finish_external_edit(EditTarget::SystemInstruction, Ok("  Answer in Rust.\n".to_string()));
+        assert_eq!(state.system_instruction.as_deref(), Some("Answer in Rust."));
+        state.finish_external_edit(EditTarget::SystemInstruction, Ok(String::new()));
+        assert_eq!(state.system_instruction, None);
+    }
+}
@@ -0,0 +1,165 @@
+//! Response Feedback
+//!
+//! `+` / `-` with the Generation pane focused rate the selected response
+//! (the latest when none is selected) and ask for an optional comment.
+//! Ratings are recorded in the metrics store, where they make up each
+//! model's satisfaction in the Inspector, and with `[feedback] send` are
+//! also POSTed to IMS Core.
+
+use crate::app::{
+    api::{ApiEvent, ApiSender},
+    overlay::Overlay,
+    thinking::ThinkingEntry,
+    AppState,
+};
+
+/// A thumbs-up or thumbs-down on one response
+#[derive(Clone, Debug, PartialEq)]
+pub struct Feedback {
+    /// Unix seconds
+    pub timestamp: i64,
+    pub model_id: String,
+    /// The prompt the response answered
+    pub prompt: String,
+    pub positive: bool,
+    pub comment: Option<String>,
+}
+
+/// A rating waiting on its optional comment
+#[derive(Clone, Debug)]
+pub struct FeedbackPrompt {
+    pub feedback: Feedback,
+    /// Conversation turn rated, shown as "response N"
+    pub turn: usize,
+    pub input: String,
+}
+
+impl AppState {
+    /// Rate the selected response (or the latest) and ask for a comment
+    pub fn start_feedback(&mut self, positive: bool) {
+        let turn = match self.selected_region.and_then(|i| self.generation_regions.get(i)) {
+            Some(region) => Some(region.turn),
+            None => self.conversation.iter().rposition(|t| t.response.is_some()),
+        };
+        let Some((turn, answered)) = turn.and_then(|i| Some((i, self.conversation.get(i)?))) else {
+            self.add_debug_log("No response to rate yet".to_string());
+            return;
+        };
+        self.feedback_prompt = Some(FeedbackPrompt {
+            feedback: Feedback {
+                timestamp: chrono::Utc::now().timestamp(),
+                model_id: answered.model_id.clone(),
+                prompt: answered.prompt.clone(),
+                positive,
+                comment: None,
+            },
+            turn,
+            input: String::new(),
+        });
+        self.open_overlay(Overlay::Feedback);
+    }
+
+    /// Record the rating with the comment typed; returns it when it is
+    /// also to be sent to IMS Core
+    pub fn submit_feedback(&mut self) -> Option<Feedback> {
+        let FeedbackPrompt { mut feedback, turn, input } = self.feedback_prompt.take()?;
+        self.close_overlay(Overlay::Feedback);
+        let comment = input.trim();
+        feedback.comment = (!comment.is_empty()).then(|| comment.to_string());
+
+        match &self.metrics_store {
+            Some(store) => match store.record_feedback(&feedback) {
+                Ok(()) => self.refresh_stored_metrics(),
+                Err(e) => self.add_debug_log(format!("{:#}", e)),
+            },
+            None => self.add_debug_log("Metrics store unavailable, rating not kept".to_string()),
+        }
+        self.experiment_rated(turn, feedback.positive);
+        self.add_thinking(ThinkingEntry::system(format!(
+            "{} Rated response {} ({})",
+            if feedback.positive { "👍" } else { "👎" },
+            turn + 1,
+            feedback.model_id
+        )));
+        self.config.feedback.send.then_some(feedback)
+    }
+}
+
+/// POST a rating to IMS Core (or `[feedback] url`) in the background
+pub fn send_feedback(state: &AppState, feedback: Feedback, api_tx: &ApiSender) {
+    let Some(client) = state.api_client.clone() else { return };
+    let url = state.config.feedback.url.clone();
+    let tx = api_tx.clone();
+    tokio::spawn(async move {
+        if let Err(e) = client.send_feedback(&feedback, url.as_deref()).await {
+            let _ = tx.send(ApiEvent::Error(format!("Sending feedback failed: {}", e)));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{
+        config::{AppConfig, FeedbackConfig},
+        metrics_store::{MetricsStore, Satisfaction},
+        ConversationTurn,
+    };
+
+    #[test]
+    fn test_ratings_feed_satisfaction() {
+        let mut state = AppState {
+            metrics_store: Some(MetricsStore::open_in_memory().unwrap()),
+            config: AppConfig {
+                feedback: FeedbackConfig {
+                    send: true,
+                    url: None,
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        state.start_feedback(true);
+        assert!(state.feedback_prompt.is_none());
+
+        for (model_id, prompt) in [("gpt-4o", "write a parser"), ("claude-3-haiku", "add tests")] {
+            state.conversation.push(ConversationTurn {
+                response: Some(String::new()),
+                ..ConversationTurn::new(prompt, model_id)
+            });
+            state.append_generation_for(state.conversation.len() - 1, "fn parse() {}\n");
+        }
+
+        // The latest response unless one is selected
+        state.start_feedback(false);
+        state.feedback_prompt.as_mut().unwrap().input = "  missed the edge cases ".to_string();
+        let sent = state.submit_feedback().unwrap();
+        assert_eq!(
+            (sent.model_id.as_str(), sent.positive, sent.comment.as_deref()),
+            ("claude-3-haiku", false, Some("missed the edge cases"))
+        );
+
+        state.select_region(0);
+        for positive in [true, true, false] {
+            state.start_feedback(positive);
+            assert_eq!(state.feedback_prompt.as_ref().unwrap().feedback.prompt, "write a parser");
+            state.submit_feedback();
+        }
+        assert_eq!(
+            state.satisfaction,
+            [
+                Satisfaction {
+                    model_id: "gpt-4o".to_string(),
+                    positive: 2,
+                    negative: 1
+                },
+                Satisfaction {
+                    model_id: "claude-3-haiku".to_string(),
+                    positive: 0,
+                    negative: 1
+                },
+            ]
+        );
+        assert_eq!(state.satisfaction[0].percent(), 67);
+    }
+}
@@ -0,0 +1,79 @@
+//! GraphQL Metrics
+//!
+//! Some IMS Core deployments serve the registry metrics over GraphQL instead
+//! of `GET /metrics`. With `[metrics] source = "graphql"` the client posts
+//! the configured query and picks each `MetricsResponse` figure out of the
+//! result's `data` by a dotted path, so any schema can be mapped.
+
+use anyhow::{bail, Context, Result};
+use serde_json::{json, Value};
+
+use crate::app::{api::MetricsResponse, config::GraphqlMetricsConfig};
+
+/// The POST body for `config`'s query
+pub fn request_body(config: &GraphqlMetricsConfig) -> Value {
+    json!({ "query": config.query, "variables": config.variables })
+}
+
+/// Map a GraphQL response onto `MetricsResponse`. Errors reported by the
+/// server fail the fetch; a figure missing from `data` is left unknown
+pub fn parse_metrics(config: &GraphqlMetricsConfig, body: &str) -> Result<MetricsResponse> {
+    let response: Value = serde_json::from_str(body).context("Invalid GraphQL response")?;
+    if let Some(errors) = response["errors"].as_array().filter(|e| !e.is_empty()) {
+        let messages: Vec<&str> = errors.iter().filter_map(|e| e["message"].as_str()).collect();
+        bail!("GraphQL errors: {}", messages.join("; "));
+    }
+    let data = response.get("data").filter(|d| !d.is_null()).context("GraphQL response has no data")?;
+    let fields = &config.fields;
+    Ok(MetricsResponse {
+        total_models_registered: count(data, &fields.total_models_registered),
+        total_model_queries: count(data, &fields.total_model_queries),
+        total_filter_queries: count(data, &fields.total_filter_queries),
+    })
+}
+
+/// The number at dotted `path` in `data`; numeric segments index arrays and
+/// numbers sent as strings (GraphQL's `BigInt` often is) are accepted
+fn count(data: &Value, path: &str) -> Option<u64> {
+    let value = path.split('.').try_fold(data, |value, segment| match value {
+        Value::Array(items) => items.get(segment.parse::<usize>().ok()?),
+        _ => value.get(segment),
+    })?;
+    match value {
+        Value::Number(n) => n.as_u64().or_else(|| n.as_f64().map(|f| f as u64)),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::config::MetricsFields;
+
+    #[test]
+    fn test_results_are_mapped_by_path() {
+        let config = GraphqlMetricsConfig {
+            fields: MetricsFields {
+                total_models_registered: "registry.models.count".to_string(),
+                total_model_queries: "usage.0.queries".to_string(),
+                total_filter_queries: "usage.0.filters".to_string(),
+            },
+            ..Default::default()
+        };
+        let body = r#"{"data": {"registry": {"models": {"count": 12}}, "usage": [{"queries": "1234", "filters": null}]}}"#;
+        let metrics = parse_metrics(&config, body).unwrap();
+        assert_eq!(metrics.total_models_registered, Some(12));
+        assert_eq!(metrics.total_model_queries, Some(1234));
+        assert_eq!(metrics.total_filter_queries, None);
+
+        let defaults = GraphqlMetricsConfig::default();
+        assert!(request_body(&defaults)["query"].as_str().unwrap().contains("totalModelQueries"));
+        let body = r#"{"data": {"metrics": {"totalModelsRegistered": 8, "totalModelQueries": 5.0, "totalFilterQueries": 2}}}"#;
+        assert_eq!(parse_metrics(&defaults, body).unwrap().total_model_queries, Some(5));
+
+        let failed = r#"{"data": null, "errors": [{"message": "Cannot query field \"metrics\""}]}"#;
+        let error = parse_metrics(&defaults, failed).unwrap_err().to_string();
+        assert!(error.contains("Cannot query field"), "{}", error);
+    }
+}
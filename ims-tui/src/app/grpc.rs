@@ -0,0 +1,310 @@
+//! gRPC Execution Path
+//!
+//! For backends that serve the execute and metrics APIs over gRPC
+//! (`ims.v1.Gateway`, see `proto/ims_gateway.proto`) instead of JSON. Built
+//! with `--features grpc` and selected with `[api] protocol = "grpc"`. The
+//! prost messages below mirror the proto file and convert to and from the
+//! `api` types, so everything past the client is the same either way.
+//! Failed calls map onto `ApiError` like HTTP ones.
+
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use tonic::codegen::http::uri::PathAndQuery;
+use tonic::transport::{Channel, ClientTlsConfig, Endpoint};
+
+use crate::app::api;
+use crate::app::api_error::{self, ApiError};
+
+const EXECUTE: &str = "/ims.v1.Gateway/Execute";
+const GET_METRICS: &str = "/ims.v1.Gateway/GetMetrics";
+
+/// Matches the HTTP client
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ExecuteRequest {
+    #[prost(string, tag = "1")]
+    pub prompt: String,
+    #[prost(string, tag = "2")]
+    pub model_id: String,
+    #[prost(uint32, optional, tag = "3")]
+    pub max_tokens: Option<u32>,
+    #[prost(double, tag = "4")]
+    pub temperature: f64,
+    #[prost(string, optional, tag = "5")]
+    pub system_instruction: Option<String>,
+    #[prost(string, optional, tag = "6")]
+    pub user_id: Option<String>,
+    #[prost(bool, tag = "7")]
+    pub bypass_policies: bool,
+    #[prost(string, tag = "8")]
+    pub idempotency_key: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct TokenUsage {
+    #[prost(uint32, tag = "1")]
+    pub input: u32,
+    #[prost(uint32, tag = "2")]
+    pub output: u32,
+    #[prost(uint32, tag = "3")]
+    pub total: u32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct CostUsage {
+    #[prost(double, tag = "1")]
+    pub input: f64,
+    #[prost(double, tag = "2")]
+    pub output: f64,
+    #[prost(double, tag = "3")]
+    pub total: f64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ToolCall {
+    #[prost(string, optional, tag = "1")]
+    pub id: Option<String>,
+    #[prost(string, tag = "2")]
+    pub name: String,
+    /// JSON
+    #[prost(string, tag = "3")]
+    pub arguments: String,
+    /// JSON
+    #[prost(string, optional, tag = "4")]
+    pub result: Option<String>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ExecuteResponse {
+    #[prost(string, tag = "1")]
+    pub content: String,
+    #[prost(string, tag = "2")]
+    pub model_id: String,
+    #[prost(message, optional, tag = "3")]
+    pub tokens: Option<TokenUsage>,
+    #[prost(message, optional, tag = "4")]
+    pub cost: Option<CostUsage>,
+    #[prost(double, tag = "5")]
+    pub latency_ms: f64,
+    #[prost(message, repeated, tag = "6")]
+    pub tool_calls: Vec<ToolCall>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MetricsRequest {}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MetricsResponse {
+    #[prost(uint64, optional, tag = "1")]
+    pub total_models_registered: Option<u64>,
+    #[prost(uint64, optional, tag = "2")]
+    pub total_model_queries: Option<u64>,
+    #[prost(uint64, optional, tag = "3")]
+    pub total_filter_queries: Option<u64>,
+}
+
+impl From<&api::ExecuteRequest> for ExecuteRequest {
+    fn from(req: &api::ExecuteRequest) -> Self {
+        Self {
+            prompt: req.prompt.clone(),
+            model_id: req.model_id.clone(),
+            max_tokens: req.max_tokens,
+            temperature: req.temperature,
+            system_instruction: req.system_instruction.clone(),
+            user_id: req.user_id.clone(),
+            bypass_policies: req.bypass_policies,
+            idempotency_key: req
+                .idempotency_key
+                .clone()
+                .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+        }
+    }
+}
+
+impl From<ExecuteResponse> for api::ExecuteResponse {
+    fn from(response: ExecuteResponse) -> Self {
+        let tokens = response.tokens.unwrap_or_default();
+        let cost = response.cost.unwrap_or_default();
+        Self {
+            content: response.content,
+            model_id: response.model_id,
+            tokens: api::TokenUsage {
+                input: tokens.input,
+                output: tokens.output,
+                total: tokens.total,
+            },
+            cost: api::CostUsage {
+                input: cost.input,
+                output: cost.output,
+                total: cost.total,
+            },
+            latency_ms: response.latency_ms,
+            tool_calls: response.tool_calls.into_iter().map(api::ToolCall::from).collect(),
+            // Not in the gRPC contract yet; images go over REST
+            images: Vec::new(),
+            transcripts: Vec::new(),
+        }
+    }
+}
+
+impl From<ToolCall> for api::ToolCall {
+    fn from(call: ToolCall) -> Self {
+        // Kept as a string when it isn't JSON, as vendors sometimes send it
+        let json = |text: String| serde_json::from_str(&text).unwrap_or(serde_json::Value::String(text));
+        Self {
+            id: call.id,
+            name: call.name,
+            arguments: json(call.arguments),
+            result: call.result.map(json),
+        }
+    }
+}
+
+impl From<MetricsResponse> for api::MetricsResponse {
+    fn from(metrics: MetricsResponse) -> Self {
+        Self {
+            total_models_registered: metrics.total_models_registered,
+            total_model_queries: metrics.total_model_queries,
+            total_filter_queries: metrics.total_filter_queries,
+        }
+    }
+}
+
+/// Classify a failed call like the HTTP status it corresponds to
+pub fn status_error(status: &tonic::Status) -> ApiError {
+    use tonic::Code;
+    let detail = status.message().to_string();
+    match status.code() {
+        Code::Unauthenticated => ApiError::Unauthorized { status: 401, detail },
+        Code::PermissionDenied => ApiError::Unauthorized { status: 403, detail },
+        Code::ResourceExhausted => ApiError::RateLimited {
+            retry_after: status
+                .metadata()
+                .get("retry-after")
+                .and_then(|v| v.to_str().ok())
+                .and_then(api_error::parse_retry_after),
+        },
+        Code::NotFound => ApiError::ModelNotFound { detail },
+        Code::InvalidArgument => ApiError::Validation { detail },
+        Code::Unavailable => ApiError::Network(detail),
+        Code::DeadlineExceeded => ApiError::Timeout,
+        code => ApiError::Server {
+            status: 500,
+            detail: format!("{:?}: {}", code, detail),
+        },
+    }
+}
+
+/// `ims.v1.Gateway` client; connects on first use
+#[derive(Clone)]
+pub struct GrpcClient {
+    channel: Channel,
+}
+
+impl GrpcClient {
+    pub fn new(url: &str) -> Result<Self> {
+        let mut endpoint = Endpoint::from_shared(url.to_string())
+            .with_context(|| format!("Invalid gRPC URL {:?}", url))?
+            .timeout(TIMEOUT);
+        if url.starts_with("https://") {
+            endpoint = endpoint
+                .tls_config(ClientTlsConfig::new().with_webpki_roots())
+                .context("Failed to configure gRPC TLS")?;
+        }
+        Ok(Self {
+            channel: endpoint.connect_lazy(),
+        })
+    }
+
+    async fn unary<Req, Resp>(&self, path: &'static str, message: Req, admin_key: Option<&str>) -> Result<Resp>
+    where
+        Req: prost::Message + 'static,
+        Resp: prost::Message + Default + 'static,
+    {
+        let mut request = tonic::Request::new(message);
+        if let Some(key) = admin_key {
+            request
+                .metadata_mut()
+                .insert("x-admin-key", key.parse().context("Admin key is not a valid header value")?);
+        }
+        let mut grpc = tonic::client::Grpc::new(self.channel.clone());
+        grpc.ready()
+            .await
+            .map_err(|e| ApiError::Network(e.to_string()))?;
+        let codec = tonic::codec::ProstCodec::default();
+        let response = grpc
+            .unary(request, PathAndQuery::from_static(path), codec)
+            .await
+            .map_err(|status| status_error(&status))?;
+        Ok(response.into_inner())
+    }
+
+    pub async fn execute(&self, req: &api::ExecuteRequest, admin_key: Option<&str>) -> Result<api::ExecuteResponse> {
+        let response: ExecuteResponse = self.unary(EXECUTE, ExecuteRequest::from(req), admin_key).await?;
+        Ok(response.into())
+    }
+
+    pub async fn metrics(&self, admin_key: Option<&str>) -> Result<api::MetricsResponse> {
+        let response: MetricsResponse = self.unary(GET_METRICS, MetricsRequest {}, admin_key).await?;
+        Ok(response.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost::Message;
+
+    #[test]
+    fn test_messages_round_trip_and_convert() {
+        let req = api::ExecuteRequest {
+            prompt: "Explain lifetimes".to_string(),
+            model_id: "gpt-4o".to_string(),
+            max_tokens: Some(512),
+            temperature: 0.2,
+            system_instruction: None,
+            user_id: Some("dana".to_string()),
+            bypass_policies: false,
+            idempotency_key: Some("key-1".to_string()),
+            images: Vec::new(),
+            audio: Vec::new(),
+        };
+        let message = ExecuteRequest::from(&req);
+        let decoded = ExecuteRequest::decode(message.encode_to_vec().as_slice()).unwrap();
+        assert_eq!(decoded, message);
+        assert_eq!(decoded.idempotency_key, "key-1");
+
+        let response = ExecuteResponse {
+            content: "Borrows end.".to_string(),
+            model_id: "gpt-4o".to_string(),
+            tokens: Some(TokenUsage { input: 12, output: 4, total: 16 }),
+            cost: None,
+            latency_ms: 120.0,
+            tool_calls: vec![ToolCall {
+                id: None,
+                name: "read_file".to_string(),
+                arguments: r#"{"path": "src/main.rs"}"#.to_string(),
+                result: None,
+            }],
+        };
+        let response = api::ExecuteResponse::from(response);
+        assert_eq!(response.tokens.total, 16);
+        assert_eq!(response.cost.total, 0.0);
+        assert_eq!(response.tool_calls[0].arguments["path"], "src/main.rs");
+
+        let mut limited = tonic::Status::resource_exhausted("slow down");
+        limited.metadata_mut().insert("retry-after", "7".parse().unwrap());
+        assert_eq!(
+            status_error(&limited),
+            ApiError::RateLimited {
+                retry_after: Some(Duration::from_secs(7))
+            }
+        );
+        assert!(matches!(
+            status_error(&tonic::Status::unauthenticated("no key")),
+            ApiError::Unauthorized { status: 401, .. }
+        ));
+    }
+}
@@ -0,0 +1,297 @@
+//! Guardrails
+//!
+//! Local checks run on a prompt before it is sent, as a client-side
+//! complement to IMS Core's policies: a length limit, banned terms,
+//! personal data (emails, SSNs, card numbers and `[guardrails]
+//! pii_patterns`) in the prompt, attached files or attached search
+//! results, and attachments that are missing. Each check blocks the prompt, warns and sends it, or is
+//! off, as `[guardrails]` says; every finding is reported in the Thinking
+//! pane with what it found and where. Attached files are read and checked
+//! in the background as the prompt is sent, failing it if they block. The
+//! API client checks every request again as it is sent, so batches,
+//! pipelines, replays and compaction can't send what a typed prompt
+//! couldn't.
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::app::{
+    config::{GuardAction, GuardrailConfig},
+    embeddings::SearchHit,
+    images,
+    thinking::{Level, ThinkingEntry},
+    AppState,
+};
+
+/// Personal data found out of the box, by what it is called in findings
+const BUILT_IN_PII: &[(&str, &str)] = &[
+    ("an email address", r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}\b"),
+    ("a US social security number", r"\b\d{3}-\d{2}-\d{4}\b"),
+    ("a card number", r"\b(?:\d{4}[ -]?){3}\d{4}\b"),
+];
+
+/// Wording that refers to attached files
+const ATTACHMENT_MENTION: &str = r"(?i)\b(?:attached|attachments?|these files|this file)\b";
+
+/// A failed check
+#[derive(Clone, Debug, PartialEq)]
+pub struct Finding {
+    pub action: GuardAction,
+    pub message: String,
+}
+
+/// Compiled `[guardrails]` checks; cheap to clone
+#[derive(Clone, Debug)]
+pub struct Guardrails {
+    config: GuardrailConfig,
+    /// Each banned term and its case-insensitive pattern
+    banned: Arc<Vec<(String, Regex)>>,
+    /// What each pattern finds, and the pattern
+    pii: Arc<Vec<(String, Regex)>>,
+    mention: Regex,
+}
+
+impl Guardrails {
+    pub fn new(config: &GuardrailConfig) -> Result<Self> {
+        let mut banned = Vec::new();
+        for term in config.banned_terms.iter().filter(|t| !t.trim().is_empty()) {
+            let term = term.trim();
+            // Whole words only where the term starts or ends with one
+            let edge = |c: Option<char>| if c.is_some_and(char::is_alphanumeric) { r"\b" } else { "" };
+            let pattern = format!(
+                "(?i){}{}{}",
+                edge(term.chars().next()),
+                regex::escape(term),
+                edge(term.chars().last())
+            );
+            banned.push((term.to_string(), Regex::new(&pattern).expect("escaped term is a valid pattern")));
+        }
+        let mut pii: Vec<(String, Regex)> = BUILT_IN_PII
+            .iter()
+            .map(|(name, pattern)| (name.to_string(), Regex::new(pattern).expect("built-in PII pattern is valid")))
+            .collect();
+        for pattern in &config.pii_patterns {
+            let regex = Regex::new(pattern).with_context(|| format!("Invalid PII pattern {:?}", pattern))?;
+            pii.push((format!("a match for {:?}", pattern), regex));
+        }
+        Ok(Self {
+            config: config.clone(),
+            banned: Arc::new(banned),
+            pii: Arc::new(pii),
+            mention: Regex::new(ATTACHMENT_MENTION).expect("attachment pattern is valid"),
+        })
+    }
+
+    /// The checks of the default config
+    pub fn built_in() -> Self {
+        Self::new(&GuardrailConfig::default()).expect("built-in guardrail patterns are valid")
+    }
+
+    /// Findings for `prompt` as typed, sent with `attachments` and the
+    /// search results in `hits`; the attached files themselves are left to
+    /// `check_attachments`
+    pub fn check(&self, prompt: &str, attachments: &[PathBuf], hits: &[SearchHit]) -> Vec<Finding> {
+        let config = &self.config;
+        let mut findings = Vec::new();
+        let mut found = |action: GuardAction, message: String| {
+            if action != GuardAction::Off {
+                findings.push(Finding { action, message });
+            }
+        };
+
+        let length = prompt.chars().count();
+        if let Some(max) = config.max_length.filter(|max| length > *max) {
+            found(config.length, format!("Prompt is {} characters, over the {} allowed", length, max));
+        }
+
+        let mut sources = vec![("the prompt".to_string(), prompt.to_string())];
+        sources.extend(hits.iter().map(|hit| (format!("search result {}", hit.location()), hit.text.clone())));
+        // Watches send the file inline, as a `File:` block
+        let inline_file = prompt.starts_with("File: ");
+        if attachments.is_empty() && !inline_file && self.mention.is_match(prompt) {
+            found(
+                config.attachments,
+                "The prompt mentions an attachment but no file is attached".to_string(),
+            );
+        }
+
+        for (source, text) in &sources {
+            self.scan(source, text, &mut found);
+        }
+        findings
+    }
+
+    /// Findings in `attachments`: files that can't be read, and what the
+    /// text ones contain. Reads every file, so it is run off the UI thread
+    pub fn check_attachments(&self, attachments: &[PathBuf]) -> Vec<Finding> {
+        let action = self.config.attachments;
+        let mut findings = Vec::new();
+        let mut found = |action: GuardAction, message: String| {
+            if action != GuardAction::Off {
+                findings.push(Finding { action, message });
+            }
+        };
+        for path in attachments {
+            // Images and audio are sent as they are, not scanned
+            if images::is_media(path) {
+                if !path.is_file() {
+                    found(action, format!("Attached file {} can't be read", path.display()));
+                }
+                continue;
+            }
+            match std::fs::read_to_string(path) {
+                Ok(content) => self.scan(&path.display().to_string(), &content, &mut found),
+                Err(_) => found(action, format!("Attached file {} can't be read", path.display())),
+            }
+        }
+        findings
+    }
+
+    /// The first finding that blocks `text` from being sent: a banned term
+    /// or personal data, where either is set to block. Checked on every
+    /// request as it is sent, whatever sent it.
+    pub fn blocking(&self, text: &str) -> Option<Finding> {
+        let mut blocked = None;
+        self.scan("the request", text, &mut |action, message| {
+            if action == GuardAction::Block && blocked.is_none() {
+                blocked = Some(Finding { action, message });
+            }
+        });
+        blocked
+    }
+
+    /// Banned terms and personal data in `text`, read from `source`
+    fn scan(&self, source: &str, text: &str, found: &mut impl FnMut(GuardAction, String)) {
+        for (term, pattern) in self.banned.iter() {
+            if pattern.is_match(text) {
+                found(self.config.banned, format!("Banned term {:?} in {}", term, source));
+            }
+        }
+        for (name, pattern) in self.pii.iter() {
+            let matches = pattern.find_iter(text).count();
+            if matches > 0 {
+                found(
+                    self.config.pii,
+                    format!("Possible personal data in {}: {} ({} match(es))", source, name, matches),
+                );
+            }
+        }
+    }
+}
+
+impl AppState {
+    /// Run the guardrails on `prompt` and report what they found; whether
+    /// it may be sent
+    pub fn preflight(&mut self, prompt: &str) -> bool {
+        let findings = self.guardrails.check(prompt, &self.attachments, &self.search_context);
+        !self.report_findings(findings)
+    }
+
+    /// Add `findings` to the Thinking pane; whether any of them blocks
+    pub fn report_findings(&mut self, findings: Vec<Finding>) -> bool {
+        let blocked = findings.iter().any(|f| f.action == GuardAction::Block);
+        for finding in findings {
+            let entry = match finding.action {
+                GuardAction::Block => {
+                    ThinkingEntry::system(format!("⛔ Not sent: {}", finding.message)).with_level(Level::Error)
+                }
+                _ => ThinkingEntry::system(format!("⚠ {}", finding.message)).with_level(Level::Warning),
+            };
+            self.add_thinking(entry);
+        }
+        blocked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checks_block_or_warn_as_configured() {
+        let guardrails = Guardrails::new(&GuardrailConfig {
+            max_length: Some(80),
+            banned_terms: vec!["Project Falcon".to_string(), "c++".to_string()],
+            pii_patterns: vec!["EMP-[0-9]{6}".to_string()],
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(guardrails.check("Refactor the parser", &[], &[]).is_empty());
+
+        let findings = guardrails.check("Email jane@corp.example about project falcon, ticket EMP-123456", &[], &[]);
+        let messages: Vec<_> = findings.iter().map(|f| (f.action, f.message.as_str())).collect();
+        assert_eq!(
+            messages,
+            [
+                (GuardAction::Block, "Banned term \"Project Falcon\" in the prompt"),
+                (GuardAction::Warn, "Possible personal data in the prompt: an email address (1 match(es))"),
+                (
+                    GuardAction::Warn,
+                    "Possible personal data in the prompt: a match for \"EMP-[0-9]{6}\" (1 match(es))"
+                ),
+            ]
+        );
+        // Not a word inside another one
+        assert!(guardrails.check("Port it from falconry", &[], &[]).is_empty());
+        assert_eq!(guardrails.check("Port it to C++ 20", &[], &[]).len(), 1);
+
+        let missing = std::env::temp_dir().join(format!("ims-missing-{}.rs", uuid::Uuid::new_v4()));
+        let findings = guardrails.check(&"x".repeat(100), std::slice::from_ref(&missing), &[]);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].message, "Prompt is 100 characters, over the 80 allowed");
+        let findings = guardrails.check_attachments(&[missing]);
+        assert!(findings[0].message.ends_with("can't be read"));
+
+        let attached = std::env::temp_dir().join(format!("ims-attached-{}.md", uuid::Uuid::new_v4()));
+        std::fs::write(&attached, "Owner: jane@corp.example").unwrap();
+        let findings = guardrails.check_attachments(std::slice::from_ref(&attached));
+        assert_eq!(
+            findings[0].message,
+            format!("Possible personal data in {}: an email address (1 match(es))", attached.display())
+        );
+        let _ = std::fs::remove_file(attached);
+        assert_eq!(
+            guardrails.check("Review this file", &[], &[])[0].message,
+            "The prompt mentions an attachment but no file is attached"
+        );
+
+        assert!(Guardrails::new(&GuardrailConfig {
+            pii_patterns: vec!["(".to_string()],
+            ..Default::default()
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_blocked_prompts_are_reported() {
+        let mut state = AppState {
+            guardrails: Guardrails::new(&GuardrailConfig {
+                banned_terms: vec!["secret-project".to_string()],
+                ..Default::default()
+            })
+            .unwrap(),
+            ..Default::default()
+        };
+        assert!(state.preflight("Summarize the changes"));
+        assert!(!state.preflight("Summarize secret-project for me"));
+        let last = state.thinking_log.last().unwrap();
+        assert_eq!(last.text, "⛔ Not sent: Banned term \"secret-project\" in the prompt");
+        assert_eq!(last.level, Level::Error);
+
+        // Attached search results are checked with the prompt
+        state.search_context.push(SearchHit {
+            path: PathBuf::from("docs/roadmap.md"),
+            start_line: 1,
+            end_line: 40,
+            text: "Next quarter: secret-project launch".to_string(),
+            score: 0.8,
+        });
+        assert!(!state.preflight("Summarize the changes"));
+        assert_eq!(
+            state.thinking_log.last().unwrap().text,
+            "⛔ Not sent: Banned term \"secret-project\" in search result docs/roadmap.md:1-40"
+        );
+    }
+}
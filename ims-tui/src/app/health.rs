@@ -0,0 +1,192 @@
+//! Backend Health Detail
+//!
+//! Each `/health` poll reports the API status plus its database, cache and
+//! (optionally) RabbitMQ subsystems. Their statuses are kept with the time
+//! each last changed for the Health popup ("Health: Show Details", a click
+//! on the status bar, or `H` in Settings), and a notification is raised
+//! when a subsystem gets worse or recovers.
+
+use chrono::{DateTime, Local};
+
+use crate::app::{api::HealthResponse, overlay::Overlay, AppState};
+use crate::core::{effects::NotificationLevel, events::Event, reduce::reduce};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HealthLevel {
+    Healthy,
+    Unknown,
+    Degraded,
+    Down,
+}
+
+impl HealthLevel {
+    /// Classify a status string as the backend reports it ("healthy",
+    /// "degraded", "unhealthy", "connected", "error: ...")
+    pub fn from_status(status: &str) -> Self {
+        let status = status.to_ascii_lowercase();
+        let any = |words: &[&str]| words.iter().any(|w| status.contains(w));
+        if any(&["unhealthy", "down", "error", "fail", "disconnected", "unavailable", "unreachable"]) {
+            HealthLevel::Down
+        } else if any(&["degraded", "slow", "warn", "partial"]) {
+            HealthLevel::Degraded
+        } else if any(&["healthy", "ok", "up", "connected", "ready"]) {
+            HealthLevel::Healthy
+        } else {
+            HealthLevel::Unknown
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SubsystemHealth {
+    pub name: &'static str,
+    pub status: String,
+    pub level: HealthLevel,
+    /// When `level` last changed
+    pub since: DateTime<Local>,
+}
+
+/// Latest known health of each subsystem
+#[derive(Clone, Debug, Default)]
+pub struct HealthDetail {
+    pub subsystems: Vec<SubsystemHealth>,
+    pub checked: Option<DateTime<Local>>,
+}
+
+impl HealthDetail {
+    /// Record one poll, returning the subsystems whose level changed as
+    /// (name, old level, new subsystem state)
+    pub fn update(&mut self, health: &HealthResponse) -> Vec<(&'static str, HealthLevel, SubsystemHealth)> {
+        let now = Local::now();
+        self.checked = Some(now);
+        let mut reported = vec![
+            ("API", health.status.clone()),
+            ("Database", health.database.clone()),
+            ("Cache", health.cache.clone()),
+        ];
+        if let Some(rabbitmq) = &health.rabbitmq {
+            reported.push(("RabbitMQ", rabbitmq.clone()));
+        }
+        reported.into_iter().filter_map(|(name, status)| self.set(name, status, now)).collect()
+    }
+
+    /// The backend could not be reached: only the API's status is known
+    pub fn unreachable(&mut self, error: &str) -> Vec<(&'static str, HealthLevel, SubsystemHealth)> {
+        let now = Local::now();
+        self.checked = Some(now);
+        self.set("API", format!("unreachable: {}", error), now).into_iter().collect()
+    }
+
+    fn set(
+        &mut self,
+        name: &'static str,
+        status: String,
+        now: DateTime<Local>,
+    ) -> Option<(&'static str, HealthLevel, SubsystemHealth)> {
+        let level = HealthLevel::from_status(&status);
+        match self.subsystems.iter_mut().find(|s| s.name == name) {
+            Some(existing) => {
+                let previous = existing.level;
+                existing.status = status;
+                if previous == level {
+                    return None;
+                }
+                existing.level = level;
+                existing.since = now;
+                Some((name, previous, existing.clone()))
+            }
+            None => {
+                self.subsystems.push(SubsystemHealth {
+                    name,
+                    status,
+                    level,
+                    since: now,
+                });
+                None
+            }
+        }
+    }
+
+    /// Subsystems that are not healthy
+    pub fn degraded(&self) -> usize {
+        self.subsystems.iter().filter(|s| s.level > HealthLevel::Unknown).count()
+    }
+}
+
+impl AppState {
+    pub fn apply_health(&mut self, health: &HealthResponse) {
+        let changes = self.health.update(health);
+        self.notify_health_changes(changes);
+    }
+
+    pub fn health_unreachable(&mut self, error: &str) {
+        let changes = self.health.unreachable(error);
+        self.notify_health_changes(changes);
+    }
+
+    fn notify_health_changes(&mut self, changes: Vec<(&'static str, HealthLevel, SubsystemHealth)>) {
+        for (name, previous, now) in changes {
+            let (level, verb) = match now.level {
+                HealthLevel::Down => (NotificationLevel::Error, "is down"),
+                HealthLevel::Degraded => (NotificationLevel::Warning, "is degraded"),
+                _ if previous > now.level => (NotificationLevel::Info, "recovered"),
+                _ => continue,
+            };
+            let message = format!("{} {} ({})", name, verb, now.status);
+            reduce(self, Event::NotificationShown { level, message });
+        }
+    }
+
+    pub fn open_health(&mut self) {
+        if self.health.subsystems.is_empty() {
+            self.add_debug_log("No health check has completed yet".to_string());
+        } else {
+            self.open_overlay(Overlay::Health);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn health(database: &str) -> HealthResponse {
+        HealthResponse {
+            status: "healthy".to_string(),
+            database: database.to_string(),
+            cache: "connected".to_string(),
+            rabbitmq: None,
+        }
+    }
+
+    #[test]
+    fn test_levels() {
+        assert_eq!(HealthLevel::from_status("healthy"), HealthLevel::Healthy);
+        assert_eq!(HealthLevel::from_status("Unhealthy"), HealthLevel::Down);
+        assert_eq!(HealthLevel::from_status("degraded: replica lag"), HealthLevel::Degraded);
+        assert_eq!(HealthLevel::from_status("error: connection refused"), HealthLevel::Down);
+        assert_eq!(HealthLevel::from_status("n/a"), HealthLevel::Unknown);
+    }
+
+    #[test]
+    fn test_degradation_is_reported_once() {
+        let mut detail = HealthDetail::default();
+        assert!(detail.update(&health("connected")).is_empty());
+        assert_eq!(detail.subsystems.len(), 3);
+
+        let changes = detail.update(&health("degraded: slow queries"));
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].0, "Database");
+        assert_eq!(changes[0].1, HealthLevel::Healthy);
+        assert_eq!(detail.degraded(), 1);
+        assert!(detail.update(&health("degraded: still slow")).is_empty());
+        assert_eq!(detail.subsystems[1].status, "degraded: still slow");
+
+        let mut state = AppState::default();
+        state.apply_health(&health("connected"));
+        state.apply_health(&health("error: connection refused"));
+        assert!(state.debug_logs.iter().any(|l| l.contains("Database is down")));
+        state.apply_health(&health("connected"));
+        assert!(state.debug_logs.iter().any(|l| l.contains("Database recovered")));
+    }
+}
@@ -0,0 +1,345 @@
+//! Generation History
+//!
+//! Every completed generation is written to
+//! `.ims/generations/<timestamp>-<model>.md` in the workspace: a small
+//! front-matter header (prompt, model, usage) followed by the response
+//! as-is, so the files read fine in any Markdown viewer.
+//!
+//! A generation can be replayed against the same or another model; the
+//! new file names the original in `replay_of` so the two can be compared
+//! side by side.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use std::path::{Path, PathBuf};
+
+use crate::app::{
+    annotations::GenerationRegion, api::ExecuteResponse, blocks, checkpoints::SessionSnapshot, overlay::Overlay,
+    thinking::ThinkingEntry, ActiveSession, AppState, ConversationTurn,
+};
+
+/// `.ims/generations` under the current working directory
+pub fn generations_dir() -> PathBuf {
+    crate::app::ims_dir().join("generations")
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GenerationRecord {
+    pub path: PathBuf,
+    pub created: DateTime<Local>,
+    pub prompt: String,
+    pub model_id: String,
+    pub tokens: u64,
+    pub cost: f64,
+    pub latency_ms: f64,
+    pub content: String,
+    /// File name of the generation this one replays
+    pub replay_of: Option<String>,
+}
+
+impl GenerationRecord {
+    pub fn from_response(prompt: &str, response: &ExecuteResponse) -> Self {
+        Self {
+            path: PathBuf::new(),
+            created: Local::now(),
+            prompt: prompt.to_string(),
+            model_id: response.model_id.clone(),
+            tokens: u64::from(response.tokens.total),
+            cost: response.cost.total,
+            latency_ms: response.latency_ms,
+            content: response.content.clone(),
+            replay_of: None,
+        }
+    }
+
+    /// File name: `20261018-142501-gpt-4o.md`
+    pub fn file_name(&self) -> String {
+        let model: String = self
+            .model_id
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '.' { c } else { '_' })
+            .collect();
+        format!("{}-{}.md", self.created.format("%Y%m%d-%H%M%S"), model)
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let replay_of = self
+            .replay_of
+            .as_ref()
+            .map(|name| format!("replay_of: {}\n", name))
+            .unwrap_or_default();
+        // JSON strings are valid YAML scalars, which keeps multi-line prompts on one line
+        format!(
+            "---\nprompt: {}\nmodel: {}\ntokens: {}\ncost: {}\nlatency_ms: {}\ncreated: {}\n{}---\n\n{}\n",
+            serde_json::Value::String(self.prompt.clone()),
+            self.model_id,
+            self.tokens,
+            self.cost,
+            self.latency_ms,
+            self.created.to_rfc3339(),
+            replay_of,
+            self.content
+        )
+    }
+
+    /// Name of the saved file, used to link replays to their original
+    pub fn saved_name(&self) -> Option<String> {
+        self.path.file_name().map(|n| n.to_string_lossy().into_owned())
+    }
+
+    /// Parse a file written by `to_markdown`
+    pub fn parse(path: &Path, text: &str) -> Option<Self> {
+        let rest = text.strip_prefix("---\n")?;
+        let (header, body) = rest.split_once("\n---\n")?;
+
+        let mut record = Self {
+            path: path.to_path_buf(),
+            created: Local::now(),
+            prompt: String::new(),
+            model_id: String::new(),
+            tokens: 0,
+            cost: 0.0,
+            latency_ms: 0.0,
+            content: body.strip_prefix('\n').unwrap_or(body).trim_end_matches('\n').to_string(),
+            replay_of: None,
+        };
+        for line in header.lines() {
+            let Some((key, value)) = line.split_once(": ") else { continue };
+            match key {
+                "prompt" => record.prompt = serde_json::from_str(value).unwrap_or_else(|_| value.to_string()),
+                "model" => record.model_id = value.to_string(),
+                "tokens" => record.tokens = value.parse().unwrap_or(0),
+                "cost" => record.cost = value.parse().unwrap_or(0.0),
+                "latency_ms" => record.latency_ms = value.parse().unwrap_or(0.0),
+                "replay_of" => record.replay_of = Some(value.to_string()),
+                "created" => {
+                    if let Ok(created) = DateTime::parse_from_rfc3339(value) {
+                        record.created = created.with_timezone(&Local);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Some(record)
+    }
+}
+
+/// Write `record` into `dir`, returning the file's path
+pub fn save(dir: &Path, record: &GenerationRecord) -> Result<PathBuf> {
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let mut path = dir.join(record.file_name());
+    // Two generations from the same model within a second
+    let mut n = 2;
+    while path.exists() {
+        path = dir.join(record.file_name().replace(".md", &format!("-{}.md", n)));
+        n += 1;
+    }
+    std::fs::write(&path, record.to_markdown()).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+/// Saved generations in `dir`, newest first; unreadable files are skipped
+pub fn list(dir: &Path) -> Result<Vec<GenerationRecord>> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", dir.display())),
+    };
+    let mut records: Vec<GenerationRecord> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "md"))
+        .filter_map(|path| {
+            let text = std::fs::read_to_string(&path).ok()?;
+            GenerationRecord::parse(&path, &text)
+        })
+        .collect();
+    records.sort_by_key(|r| std::cmp::Reverse(r.created));
+    Ok(records)
+}
+
+/// Generation history browser
+#[derive(Clone, Debug, Default)]
+pub struct HistoryView {
+    pub records: Vec<GenerationRecord>,
+    pub selected: usize,
+}
+
+impl HistoryView {
+    pub fn selected_record(&self) -> Option<&GenerationRecord> {
+        self.records.get(self.selected)
+    }
+
+    /// The record to show beside `record`: its original if it is a replay,
+    /// otherwise its newest replay
+    pub fn counterpart(&self, record: &GenerationRecord) -> Option<&GenerationRecord> {
+        match &record.replay_of {
+            Some(original) => self
+                .records
+                .iter()
+                .find(|r| r.saved_name().as_deref() == Some(original.as_str())),
+            None => {
+                let name = record.saved_name()?;
+                self.records.iter().find(|r| r.replay_of.as_deref() == Some(name.as_str()))
+            }
+        }
+    }
+}
+
+impl AppState {
+    /// Load saved generations and show the browser
+    pub fn open_history(&mut self) {
+        match list(&generations_dir()) {
+            Ok(records) => {
+                self.history_view = HistoryView {
+                    records,
+                    ..Default::default()
+                };
+                self.open_overlay(Overlay::History);
+            }
+            Err(e) => self.add_debug_log(format!("Failed to load generation history: {:#}", e)),
+        }
+    }
+
+    /// Reopen a saved generation in a new session tab
+    pub fn reopen_generation(&mut self, record: &GenerationRecord) -> Result<()> {
+        let mut session = ActiveSession::new(
+            record.path.clone(),
+            self.vendors.for_model(&record.model_id),
+            record.model_id.clone(),
+        );
+        session.reset_scroll();
+        let snapshot = SessionSnapshot {
+            session: Some(session),
+            thinking_log: vec![
+                ThinkingEntry::user(record.prompt.clone()),
+                ThinkingEntry::agent(format!(
+                    "◀ {} ({}):",
+                    record.model_id,
+                    record.created.format("%Y-%m-%d %H:%M")
+                )),
+            ],
+            generated_code: record.content.clone(),
+            generation_regions: vec![GenerationRegion {
+                bytes: 0..record.content.len(),
+                turn: 0,
+                changes: None,
+            }],
+            conversation: vec![ConversationTurn {
+                response: Some(record.content.clone()),
+                ..ConversationTurn::new(record.prompt.clone(), record.model_id.clone())
+            }],
+            code_blocks: blocks::extract_blocks(&record.content),
+            tokens_used: record.tokens,
+            cost: record.cost,
+        };
+        let title = format!("{} {}", record.created.format("%H:%M"), record.model_id);
+        self.open_in_new_tab(title, snapshot)
+    }
+
+    /// Models offered by "Retry with…": the original's model first, then the
+    /// registry's active models
+    pub fn replay_models(&self, original: &str) -> Vec<String> {
+        let mut models = vec![original.to_string()];
+        for model in self.model_registry.models.iter().filter(|m| m.is_active) {
+            if !models.contains(&model.model_id) {
+                models.push(model.model_id.clone());
+            }
+        }
+        models
+    }
+
+    /// Open an empty tab for replaying `record` on `model_id`; the caller sends the prompt
+    pub fn open_replay_tab(&mut self, record: &GenerationRecord, model_id: &str) -> Result<()> {
+        let snapshot = SessionSnapshot {
+            session: Some(ActiveSession::new(
+                record.path.clone(),
+                self.vendors.for_model(model_id),
+                model_id.to_string(),
+            )),
+            thinking_log: vec![ThinkingEntry::system(format!(
+                "↻ Replaying {} from {} on {}",
+                record.model_id,
+                record.created.format("%Y-%m-%d %H:%M"),
+                model_id
+            ))],
+            ..Default::default()
+        };
+        self.open_in_new_tab(format!("↻ {}", model_id), snapshot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(prompt: &str) -> GenerationRecord {
+        GenerationRecord {
+            path: PathBuf::new(),
+            created: Local::now(),
+            prompt: prompt.to_string(),
+            model_id: "claude-3.5/sonnet".to_string(),
+            tokens: 1200,
+            cost: 0.0042,
+            latency_ms: 812.5,
+            content: "Here:\n\n```rust\nfn main() {}\n```".to_string(),
+            replay_of: None,
+        }
+    }
+
+    #[test]
+    fn test_markdown_round_trip() {
+        let original = record("Refactor \"parse\"\nand add tests");
+        let text = original.to_markdown();
+        let parsed = GenerationRecord::parse(Path::new("x.md"), &text).unwrap();
+
+        assert_eq!(parsed.prompt, original.prompt);
+        assert_eq!(parsed.model_id, original.model_id);
+        assert_eq!(parsed.tokens, 1200);
+        assert_eq!(parsed.content, original.content);
+        assert_eq!(parsed.created.timestamp(), original.created.timestamp());
+        assert!(original.file_name().ends_with("-claude-3.5_sonnet.md"));
+    }
+
+    #[test]
+    fn test_save_and_list_newest_first() {
+        let dir = std::env::temp_dir().join(format!("ims-tui-history-{}", uuid::Uuid::new_v4()));
+        let mut older = record("first");
+        older.created -= chrono::Duration::minutes(5);
+        save(&dir, &older).unwrap();
+        let newer = record("second");
+        save(&dir, &newer).unwrap();
+        save(&dir, &newer).unwrap();
+
+        let listed = list(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(listed.len(), 3);
+        assert_eq!(listed[2].prompt, "first");
+    }
+
+    #[test]
+    fn test_replay_links_to_original() {
+        let dir = std::env::temp_dir().join(format!("ims-tui-history-{}", uuid::Uuid::new_v4()));
+        let mut original = record("explain this");
+        original.created -= chrono::Duration::minutes(1);
+        let original_path = save(&dir, &original).unwrap();
+        let mut replay = record("explain this");
+        replay.model_id = "gpt-4o".to_string();
+        replay.replay_of = original_path.file_name().map(|n| n.to_string_lossy().into_owned());
+        save(&dir, &replay).unwrap();
+        save(&dir, &record("unrelated")).unwrap();
+
+        let view = HistoryView {
+            records: list(&dir).unwrap(),
+            ..Default::default()
+        };
+        std::fs::remove_dir_all(&dir).ok();
+
+        let replay = view.records.iter().find(|r| r.model_id == "gpt-4o").unwrap();
+        let original = view.counterpart(replay).unwrap();
+        assert_eq!(original.model_id, "claude-3.5/sonnet");
+        assert_eq!(view.counterpart(original).unwrap().model_id, "gpt-4o");
+        let unrelated = view.records.iter().find(|r| r.prompt == "unrelated").unwrap();
+        assert!(view.counterpart(unrelated).is_none());
+    }
+}
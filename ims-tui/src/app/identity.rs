@@ -0,0 +1,83 @@
+//! User Attribution
+//!
+//! The `user_id` sent with every request, written to the audit trail and
+//! to exports. The first of these wins:
+//! - `[[user.workspaces]]`, the entry whose `path` is the workspace root or
+//!   the closest directory above it
+//! - `IMS_USER_ID`
+//! - `[user] id`
+//! - The OS username (`USER`, or `USERNAME` on Windows)
+
+use std::path::Path;
+
+use crate::app::config::UserConfig;
+
+/// When nothing else names the user
+pub const FALLBACK_USER: &str = "ims-tui-user";
+
+/// The user id for requests made from `workspace`
+pub fn user_id(config: &UserConfig, workspace: &Path) -> String {
+    resolve(config, workspace, |name| std::env::var(name).ok())
+}
+
+/// `user_id` with environment variables looked up through `env`
+pub fn resolve(config: &UserConfig, workspace: &Path, env: impl Fn(&str) -> Option<String>) -> String {
+    let canonical = |path: &Path| path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let workspace = canonical(workspace);
+    let override_id = config
+        .workspaces
+        .iter()
+        .map(|entry| (canonical(&entry.path), &entry.id))
+        .filter(|(path, _)| workspace.starts_with(path))
+        .max_by_key(|(path, _)| path.components().count())
+        .map(|(_, id)| id.clone());
+
+    let non_empty = |id: String| Some(id.trim().to_string()).filter(|id| !id.is_empty());
+    override_id
+        .and_then(non_empty)
+        .or_else(|| env("IMS_USER_ID").and_then(non_empty))
+        .or_else(|| config.id.clone().and_then(non_empty))
+        .or_else(|| env("USER").or_else(|| env("USERNAME")).and_then(non_empty))
+        .unwrap_or_else(|| FALLBACK_USER.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::config::WorkspaceUser;
+
+    #[test]
+    fn test_resolution_order() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| vars.iter().find(|(k, _)| *k == name).map(|(_, v)| v.to_string())
+        };
+        let mut config = UserConfig::default();
+        let workspace = Path::new("/home/dana/projects/api/src");
+
+        assert_eq!(resolve(&config, workspace, env(&[])), FALLBACK_USER);
+        assert_eq!(resolve(&config, workspace, env(&[("USER", "dana")])), "dana");
+
+        config.id = Some("dana.k".to_string());
+        assert_eq!(resolve(&config, workspace, env(&[("USER", "dana")])), "dana.k");
+        assert_eq!(resolve(&config, workspace, env(&[("IMS_USER_ID", "ci-bot")])), "ci-bot");
+
+        config.workspaces = vec![
+            WorkspaceUser {
+                path: "/home/dana/projects".into(),
+                id: "dana@projects".to_string(),
+            },
+            WorkspaceUser {
+                path: "/home/dana/projects/api".into(),
+                id: "dana@api-team".to_string(),
+            },
+            WorkspaceUser {
+                path: "/srv/shared".into(),
+                id: "shared".to_string(),
+            },
+        ];
+        // The closest workspace entry wins over everything else
+        assert_eq!(resolve(&config, workspace, env(&[("IMS_USER_ID", "ci-bot")])), "dana@api-team");
+        assert_eq!(resolve(&config, Path::new("/home/dana/projects/web"), env(&[])), "dana@projects");
+        assert_eq!(resolve(&config, Path::new("/tmp"), env(&[])), "dana.k");
+    }
+}
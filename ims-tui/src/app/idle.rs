@@ -0,0 +1,132 @@
+//! Idle Detection
+//!
+//! With no key or mouse input for `[idle] after_mins` the user counts as
+//! away. The session's focus time (shown in the Inspector) only grows
+//! while they are not, and its idle time while they are; with
+//! `pause_polling` background polling stops until the next input, so an
+//! unattended TUI makes no API calls.
+
+use std::time::{Duration, Instant};
+
+use crate::app::AppState;
+
+/// When input last came in, and whether the user has been away since
+#[derive(Clone, Debug, Default)]
+pub struct Activity {
+    last_input: Option<Instant>,
+    last_tick: Option<Instant>,
+    idle: bool,
+    /// Polling was paused for being idle, and resumes with the next input
+    paused_polling: bool,
+}
+
+impl Activity {
+    pub fn is_idle(&self) -> bool {
+        self.idle
+    }
+}
+
+/// "45s", "12m 04s", "1h 05m"
+pub fn format_focus(time: Duration) -> String {
+    match time.as_secs() {
+        s if s < 60 => format!("{}s", s),
+        s if s < 3600 => format!("{}m {:02}s", s / 60, s % 60),
+        s => format!("{}h {:02}m", s / 3600, s / 60 % 60),
+    }
+}
+
+impl AppState {
+    /// A key or mouse event came in at `now`
+    pub fn note_input(&mut self, now: Instant) {
+        self.activity.last_input = Some(now);
+        if !std::mem::take(&mut self.activity.idle) {
+            return;
+        }
+        // Time away is not focus time
+        let away = self
+            .activity
+            .last_tick
+            .replace(now)
+            .map_or(Duration::ZERO, |tick| now.saturating_duration_since(tick));
+        if let Some(session) = &mut self.session {
+            session.idle_time += away;
+        }
+        if std::mem::take(&mut self.activity.paused_polling) {
+            self.polling_paused = false;
+        }
+        self.add_debug_log("Back from idle".to_string());
+    }
+
+    /// Count the time since the last tick toward the session's focus time,
+    /// or go idle if input has been away long enough
+    pub fn tick_activity(&mut self, now: Instant) {
+        let last_input = *self.activity.last_input.get_or_insert(now);
+        let elapsed = self
+            .activity
+            .last_tick
+            .replace(now)
+            .map_or(Duration::ZERO, |tick| now.saturating_duration_since(tick));
+
+        let away = self
+            .config
+            .idle
+            .threshold()
+            .is_some_and(|after| now.saturating_duration_since(last_input) >= after);
+        if away && !self.activity.idle {
+            self.activity.idle = true;
+            if self.config.idle.pause_polling && !self.polling_paused {
+                self.polling_paused = true;
+                self.activity.paused_polling = true;
+            }
+            self.add_debug_log(format!("Idle after {} min without input", self.config.idle.after_mins));
+        }
+        if let Some(session) = &mut self.session {
+            if self.activity.idle {
+                session.idle_time += elapsed;
+            } else {
+                session.focus_time += elapsed;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{vendors::Vendor, ActiveSession};
+
+    #[test]
+    fn test_idle_stops_focus_time_and_polling() {
+        let mut state = AppState {
+            session: Some(ActiveSession::new("src/lib.rs".into(), &Vendor::unknown(), "gpt-4o".to_string())),
+            ..Default::default()
+        };
+        state.config.idle.after_mins = 1;
+        state.config.idle.pause_polling = true;
+        let focus = |state: &AppState| state.session.as_ref().unwrap().focus_time;
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        state.tick_activity(at(0));
+        state.tick_activity(at(30));
+        assert_eq!(focus(&state), Duration::from_secs(30));
+
+        state.tick_activity(at(60));
+        assert!(state.activity.is_idle() && state.polling_paused);
+        state.tick_activity(at(600));
+        assert_eq!(focus(&state), Duration::from_secs(30));
+
+        state.note_input(at(610));
+        assert_eq!(state.session.as_ref().unwrap().idle_time, Duration::from_secs(580));
+        assert!(!state.activity.is_idle() && !state.polling_paused);
+        state.tick_activity(at(615));
+        assert_eq!(format_focus(focus(&state)), "35s");
+
+        // Polling paused by hand stays paused after coming back
+        state.polling_paused = true;
+        state.tick_activity(at(700));
+        assert!(state.activity.is_idle());
+        state.note_input(at(710));
+        assert!(state.polling_paused);
+    }
+}
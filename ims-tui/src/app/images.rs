@@ -0,0 +1,220 @@
+//! Images
+//!
+//! Image files attached from the Explorer (`+`) are sent to multimodal
+//! models with the prompt, base64-encoded in the request's `images` array
+//! as IMS Core's execute contract takes them, instead of being inlined as
+//! text like other attachments. Images a model returns are described in
+//! the Thinking pane and saved under `.ims/images` from the palette.
+
+use anyhow::{bail, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::app::{thinking::ThinkingEntry, AppState};
+
+/// Largest image sent; vendors reject bigger ones anyway
+pub const MAX_IMAGE_BYTES: u64 = 20 * 1024 * 1024;
+
+/// Media type by file extension
+const MEDIA_TYPES: &[(&str, &str)] = &[
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("webp", "image/webp"),
+];
+
+/// The media type of an image file, by extension
+pub fn media_type(path: &Path) -> Option<&'static str> {
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    MEDIA_TYPES.iter().find(|(e, _)| *e == ext).map(|(_, media)| *media)
+}
+
+pub fn is_image(path: &Path) -> bool {
+    media_type(path).is_some()
+}
+
+/// Sent as an image or audio, not inlined as text
+pub fn is_media(path: &Path) -> bool {
+    is_image(path) || crate::app::audio::is_audio(path)
+}
+
+/// Where saved images go
+pub fn images_dir() -> PathBuf {
+    crate::app::ims_dir().join("images")
+}
+
+/// An attached image as sent with a prompt
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImageInput {
+    /// File name, for the model's reference
+    pub name: String,
+    pub media_type: String,
+    /// Base64 of the file
+    pub data: String,
+}
+
+impl ImageInput {
+    pub fn load(path: &Path) -> Result<Self> {
+        let media_type = media_type(path).with_context(|| format!("{} is not an image", path.display()))?;
+        let size = std::fs::metadata(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?
+            .len();
+        if size > MAX_IMAGE_BYTES {
+            bail!(
+                "{} is {} MB, over the {} MB limit",
+                path.display(),
+                size / (1024 * 1024),
+                MAX_IMAGE_BYTES / (1024 * 1024)
+            );
+        }
+        let bytes = std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        Ok(Self {
+            name: path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default(),
+            media_type: media_type.to_string(),
+            data: STANDARD.encode(bytes),
+        })
+    }
+}
+
+/// An image in a response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImageOutput {
+    pub media_type: String,
+    /// Base64 of the image
+    pub data: String,
+    /// What the model says the image shows
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+impl ImageOutput {
+    pub fn bytes(&self) -> Result<Vec<u8>> {
+        STANDARD.decode(self.data.trim()).context("Image data is not valid base64")
+    }
+
+    /// File extension for the media type (`bin` when unknown)
+    pub fn extension(&self) -> &'static str {
+        MEDIA_TYPES
+            .iter()
+            .find(|(_, media)| *media == self.media_type)
+            .map_or("bin", |(ext, _)| ext)
+    }
+
+    /// `🖼 Image 2 (image/png, 14 KB): a bar chart of latency by model`
+    pub fn summary(&self, number: usize) -> String {
+        let size = self.data.trim().len() / 4 * 3;
+        let description = self.description.as_deref().unwrap_or("no description");
+        format!(
+            "🖼 Image {} ({}, {} KB): {}",
+            number,
+            self.media_type,
+            size.div_ceil(1024),
+            description
+        )
+    }
+}
+
+/// A returned image and where it was saved, if it has been
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReturnedImage {
+    pub image: ImageOutput,
+    pub saved: Option<PathBuf>,
+}
+
+impl AppState {
+    /// Attached files sent as images or audio rather than text
+    pub fn attached_media(&self) -> Vec<PathBuf> {
+        self.attachments.iter().filter(|p| is_media(p)).cloned().collect()
+    }
+
+    /// Keep the images of a response and describe each one
+    pub fn record_image_outputs(&mut self, images: &[ImageOutput]) {
+        for image in images {
+            self.returned_images.push(ReturnedImage {
+                image: image.clone(),
+                saved: None,
+            });
+            let summary = image.summary(self.returned_images.len());
+            self.add_thinking(ThinkingEntry::agent(summary));
+        }
+        if !images.is_empty() {
+            self.add_thinking(ThinkingEntry::system(
+                "Save returned images with \"Images: Save Returned Images\"".to_string(),
+            ));
+        }
+    }
+
+    /// Write the returned images not saved yet to `dir`
+    pub fn save_returned_images(&mut self, dir: &Path) {
+        let unsaved = self.returned_images.iter().filter(|r| r.saved.is_none()).count();
+        if unsaved == 0 {
+            self.add_debug_log("No unsaved images".to_string());
+            return;
+        }
+        if let Err(e) = std::fs::create_dir_all(dir) {
+            self.add_debug_log(format!("Failed to create {}: {}", dir.display(), e));
+            return;
+        }
+        let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+        let mut messages = Vec::new();
+        for (i, returned) in self.returned_images.iter_mut().enumerate() {
+            if returned.saved.is_some() {
+                continue;
+            }
+            let path = dir.join(format!("image-{}-{}.{}", stamp, i + 1, returned.image.extension()));
+            let written = returned
+                .image
+                .bytes()
+                .and_then(|bytes| std::fs::write(&path, bytes).with_context(|| format!("Failed to write {}", path.display())));
+            match written {
+                Ok(()) => {
+                    messages.push(format!("Saved image {} to {}", i + 1, path.display()));
+                    returned.saved = Some(path);
+                }
+                Err(e) => messages.push(format!("Image {}: {:#}", i + 1, e)),
+            }
+        }
+        for message in messages {
+            self.add_thinking(ThinkingEntry::system(message));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_images_round_trip() {
+        let dir = std::env::temp_dir().join(format!("ims-images-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let png = dir.join("Diagram.PNG");
+        std::fs::write(&png, b"\x89PNG fake").unwrap();
+
+        let mut state = AppState {
+            attachments: vec![dir.join("main.rs"), png.clone()],
+            ..Default::default()
+        };
+        assert_eq!(state.attached_media(), std::slice::from_ref(&png));
+        let input = ImageInput::load(&png).unwrap();
+        assert_eq!((input.name.as_str(), input.media_type.as_str()), ("Diagram.PNG", "image/png"));
+        assert!(ImageInput::load(&dir.join("main.rs")).is_err());
+
+        let output = ImageOutput {
+            media_type: "image/png".to_string(),
+            data: input.data.clone(),
+            description: Some("a flow chart".to_string()),
+        };
+        state.record_image_outputs(std::slice::from_ref(&output));
+        assert_eq!(state.thinking_log[0].text, "🖼 Image 1 (image/png, 1 KB): a flow chart");
+
+        let saved = dir.join("saved");
+        state.save_returned_images(&saved);
+        let path = state.returned_images[0].saved.clone().unwrap();
+        assert_eq!(path.extension().unwrap(), "png");
+        assert_eq!(std::fs::read(&path).unwrap(), b"\x89PNG fake");
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,140 @@
+//! Text Input
+//!
+//! Single-line editable text with a cursor that moves by grapheme
+//! cluster, so combining marks and emoji sequences edit as one unit and
+//! wide characters place the terminal cursor in the right column.
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TextInput {
+    text: String,
+    /// Byte offset of the cursor, always on a grapheme boundary
+    cursor: usize,
+}
+
+impl TextInput {
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.cursor = 0;
+    }
+
+    /// Replace the contents, leaving the cursor at the end
+    pub fn set(&mut self, text: impl Into<String>) {
+        self.text = text.into();
+        self.cursor = self.text.len();
+    }
+
+    pub fn insert(&mut self, c: char) {
+        self.text.insert(self.cursor, c);
+        self.cursor += c.len_utf8();
+    }
+
+    pub fn insert_str(&mut self, s: &str) {
+        self.text.insert_str(self.cursor, s);
+        self.cursor += s.len();
+    }
+
+    fn prev_boundary(&self) -> Option<usize> {
+        self.text[..self.cursor]
+            .grapheme_indices(true)
+            .next_back()
+            .map(|(i, _)| i)
+    }
+
+    fn next_boundary(&self) -> Option<usize> {
+        self.text[self.cursor..]
+            .graphemes(true)
+            .next()
+            .map(|g| self.cursor + g.len())
+    }
+
+    /// Delete the grapheme before the cursor
+    pub fn backspace(&mut self) {
+        if let Some(start) = self.prev_boundary() {
+            self.text.replace_range(start..self.cursor, "");
+            self.cursor = start;
+        }
+    }
+
+    /// Delete the grapheme under the cursor
+    pub fn delete(&mut self) {
+        if let Some(end) = self.next_boundary() {
+            self.text.replace_range(self.cursor..end, "");
+        }
+    }
+
+    pub fn move_left(&mut self) {
+        if let Some(start) = self.prev_boundary() {
+            self.cursor = start;
+        }
+    }
+
+    pub fn move_right(&mut self) {
+        if let Some(end) = self.next_boundary() {
+            self.cursor = end;
+        }
+    }
+
+    pub fn move_home(&mut self) {
+        self.cursor = 0;
+    }
+
+    pub fn move_end(&mut self) {
+        self.cursor = self.text.len();
+    }
+
+    /// Display column of the cursor (terminal cells before it)
+    pub fn cursor_column(&self) -> u16 {
+        self.text[..self.cursor].width().min(u16::MAX as usize) as u16
+    }
+}
+
+impl std::fmt::Display for TextInput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_moves_by_grapheme() {
+        let mut input = TextInput::default();
+        // "e" + combining acute, then a family emoji (ZWJ sequence)
+        input.insert_str("ae\u{301}👨\u{200d}👩\u{200d}👧");
+        input.move_left();
+        input.move_left();
+        assert_eq!(input.cursor_column(), 1);
+
+        input.delete();
+        assert_eq!(input.as_str(), "a👨\u{200d}👩\u{200d}👧");
+
+        input.move_end();
+        input.backspace();
+        assert_eq!(input.as_str(), "a");
+    }
+
+    #[test]
+    fn test_cursor_column_counts_wide_chars() {
+        let mut input = TextInput::default();
+        input.set("日本x");
+        assert_eq!(input.cursor_column(), 5);
+
+        input.move_home();
+        input.insert('é');
+        assert_eq!(input.cursor_column(), 1);
+        assert_eq!(input.as_str(), "é日本x");
+    }
+}
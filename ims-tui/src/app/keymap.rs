@@ -0,0 +1,376 @@
+//! Keymap Registry
+//!
+//! Single table of keybindings. Normal-mode keys are dispatched through
+//! `lookup`, and the help overlay lists the whole table, so the reference
+//! can't drift from what the keys actually do.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::app::FocusPane;
+
+/// Input context a binding belongs to (help overlay sections, in order)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyContext {
+    Global,
+    Normal,
+    Editing,
+    Palette,
+    Settings,
+}
+
+impl KeyContext {
+    pub const ALL: [KeyContext; 5] = [
+        KeyContext::Global,
+        KeyContext::Normal,
+        KeyContext::Editing,
+        KeyContext::Palette,
+        KeyContext::Settings,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            KeyContext::Global => "Global",
+            KeyContext::Normal => "Normal",
+            KeyContext::Editing => "Editing Prompt",
+            KeyContext::Palette => "Command Palette",
+            KeyContext::Settings => "Settings",
+        }
+    }
+}
+
+/// Normal-mode actions dispatched by the key handler
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    Help,
+    ToggleSettings,
+    CommandPalette,
+    ModelRegistry,
+    Telemetry,
+    NetworkLog,
+    CodeBlocks,
+    Checkpoints,
+    PrevTab,
+    NextTab,
+    CloseTab,
+    ToggleZen,
+    ToggleMaximize,
+    CycleFocus,
+    Up,
+    Down,
+    Collapse,
+    Expand,
+    Activate,
+    NewFile,
+    ToggleAttachment,
+    ToggleMark,
+    ToggleToolCalls,
+    CycleThinkingFilter,
+    PrevRegion,
+    NextRegion,
+    RateUp,
+    RateDown,
+    DeleteFile,
+    RenameFile,
+    ContextMenu,
+    CopySelection,
+    ClearSelection,
+    ToggleAutoScroll,
+    ResetScroll,
+    EditExternally,
+    Suspend,
+    Citations,
+}
+
+/// A key plus the Ctrl/Alt/Shift modifiers it needs
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Chord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+const fn key(code: KeyCode) -> Chord {
+    Chord {
+        code,
+        modifiers: KeyModifiers::NONE,
+    }
+}
+
+const fn ch(c: char) -> Chord {
+    key(KeyCode::Char(c))
+}
+
+const fn ctrl(c: char) -> Chord {
+    Chord {
+        code: KeyCode::Char(c),
+        modifiers: KeyModifiers::CONTROL,
+    }
+}
+
+const fn alt(c: char) -> Chord {
+    Chord {
+        code: KeyCode::Char(c),
+        modifiers: KeyModifiers::ALT,
+    }
+}
+
+const fn shift(code: KeyCode) -> Chord {
+    Chord {
+        code,
+        modifiers: KeyModifiers::SHIFT,
+    }
+}
+
+impl Chord {
+    /// Letters match either case; Ctrl and Alt must match exactly, Shift
+    /// only when the chord asks for it (it's implied by the character)
+    pub fn matches(&self, event: &KeyEvent) -> bool {
+        let code_matches = match (self.code, event.code) {
+            (KeyCode::Char(a), KeyCode::Char(b)) => a.eq_ignore_ascii_case(&b),
+            (a, b) => a == b,
+        };
+        let exact = KeyModifiers::CONTROL | KeyModifiers::ALT;
+        code_matches
+            && event.modifiers & exact == self.modifiers & exact
+            && (!self.modifiers.contains(KeyModifiers::SHIFT) || event.modifiers.contains(KeyModifiers::SHIFT))
+    }
+
+    pub fn label(&self) -> String {
+        let mut label = String::new();
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            label.push_str("Ctrl+");
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            label.push_str("Alt+");
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            label.push_str("Shift+");
+        }
+        let key = match self.code {
+            KeyCode::Char(' ') => "Space".to_string(),
+            KeyCode::Char(c) if self.modifiers.is_empty() => c.to_string(),
+            KeyCode::Char(c) => c.to_ascii_uppercase().to_string(),
+            KeyCode::Up => "↑".to_string(),
+            KeyCode::Down => "↓".to_string(),
+            KeyCode::Left => "←".to_string(),
+            KeyCode::Right => "→".to_string(),
+            KeyCode::Esc => "Esc".to_string(),
+            KeyCode::Menu => "Menu".to_string(),
+            KeyCode::F(n) => format!("F{}", n),
+            other => format!("{:?}", other),
+        };
+        label.push_str(&key);
+        label
+    }
+}
+
+pub struct Binding {
+    pub context: KeyContext,
+    pub chords: &'static [Chord],
+    pub description: &'static str,
+    /// Dispatched action; `None` for keys handled by their mode's own input loop
+    pub action: Option<Action>,
+    /// Only active while this pane has focus
+    pub focus: Option<FocusPane>,
+}
+
+impl Binding {
+    /// Keys joined for display, e.g. "Menu / Shift+F10"
+    pub fn keys_label(&self) -> String {
+        self.chords.iter().map(Chord::label).collect::<Vec<_>>().join(" / ")
+    }
+
+    fn matches_query(&self, query: &str) -> bool {
+        let mut scope = String::new();
+        if let Some(pane) = self.focus {
+            scope = format!("{:?}", pane);
+        }
+        [self.description, self.context.label(), &scope, &self.keys_label()]
+            .iter()
+            .any(|field| field.to_lowercase().contains(query))
+    }
+}
+
+const fn bind(context: KeyContext, chords: &'static [Chord], description: &'static str) -> Binding {
+    Binding {
+        context,
+        chords,
+        description,
+        action: None,
+        focus: None,
+    }
+}
+
+const fn normal(chords: &'static [Chord], description: &'static str, action: Action) -> Binding {
+    Binding {
+        context: KeyContext::Normal,
+        chords,
+        description,
+        action: Some(action),
+        focus: None,
+    }
+}
+
+const fn sidebar(chords: &'static [Chord], description: &'static str, action: Action) -> Binding {
+    Binding {
+        context: KeyContext::Normal,
+        chords,
+        description,
+        action: Some(action),
+        focus: Some(FocusPane::Sidebar),
+    }
+}
+
+const fn thinking(chords: &'static [Chord], description: &'static str, action: Action) -> Binding {
+    Binding {
+        context: KeyContext::Normal,
+        chords,
+        description,
+        action: Some(action),
+        focus: Some(FocusPane::Thinking),
+    }
+}
+
+const fn generation(chords: &'static [Chord], description: &'static str, action: Action) -> Binding {
+    Binding {
+        context: KeyContext::Normal,
+        chords,
+        description,
+        action: Some(action),
+        focus: Some(FocusPane::Generation),
+    }
+}
+
+/// All bindings; earlier entries win, so focus-specific keys come first
+pub const BINDINGS: &[Binding] = &[
+    bind(KeyContext::Global, &[key(KeyCode::F(12))], "Toggle profiler overlay"),
+    bind(
+        KeyContext::Global,
+        &[alt('1'), alt('2'), alt('3'), alt('4'), alt('5')],
+        "Jump to Sidebar / Thinking / Generation / Inspector / Prompt",
+    ),
+    sidebar(&[key(KeyCode::Left)], "Collapse directory", Action::Collapse),
+    sidebar(&[key(KeyCode::Right)], "Expand directory", Action::Expand),
+    sidebar(&[ch('n')], "New file", Action::NewFile),
+    sidebar(&[ch('+')], "Attach/detach file to prompt", Action::ToggleAttachment),
+    sidebar(&[ch(' ')], "Mark/unmark file (or directory) for batch runs", Action::ToggleMark),
+    sidebar(&[key(KeyCode::Delete)], "Delete file", Action::DeleteFile),
+    sidebar(&[key(KeyCode::F(2))], "Rename file", Action::RenameFile),
+    thinking(&[key(KeyCode::Enter)], "Expand/collapse tool calls (or click one)", Action::ToggleToolCalls),
+    thinking(&[ch('v')], "Filter by source: all / user / agent / system", Action::CycleThinkingFilter),
+    thinking(&[ch('o')], "Sources of the last response, opened in $EDITOR (or click one)", Action::Citations),
+    generation(&[ch('{')], "Previous response and its prompt (or click the gutter)", Action::PrevRegion),
+    generation(&[ch('}')], "Next response and its prompt", Action::NextRegion),
+    generation(&[ch('+')], "Rate the selected response up, with a comment", Action::RateUp),
+    generation(&[ch('-')], "Rate the selected response down, with a comment", Action::RateDown),
+    normal(&[ch('?')], "Keybinding help", Action::Help),
+    normal(&[ch('q')], "Quit", Action::Quit),
+    normal(&[ch('s')], "Toggle settings", Action::ToggleSettings),
+    normal(&[ctrl('p')], "Command palette", Action::CommandPalette),
+    normal(&[ch('m')], "Model registry browser", Action::ModelRegistry),
+    normal(&[ch('t')], "Telemetry pane", Action::Telemetry),
+    normal(&[ch('n')], "Network log", Action::NetworkLog),
+    normal(&[ch('b')], "Code blocks from responses", Action::CodeBlocks),
+    normal(&[ch('c')], "Checkpoints (save / restore into a tab)", Action::Checkpoints),
+    normal(&[ch('[')], "Previous session tab", Action::PrevTab),
+    normal(&[ch(']')], "Next session tab", Action::NextTab),
+    normal(&[ctrl('w')], "Close session tab", Action::CloseTab),
+    normal(&[ch('z')], "Zen mode (Generation fills the screen while generating)", Action::ToggleZen),
+    normal(&[ctrl('m'), ch('f')], "Maximize / restore focused pane", Action::ToggleMaximize),
+    normal(&[key(KeyCode::Tab)], "Cycle focus", Action::CycleFocus),
+    normal(&[key(KeyCode::Up)], "Navigate / scroll up", Action::Up),
+    normal(&[key(KeyCode::Down)], "Navigate / scroll down", Action::Down),
+    normal(&[key(KeyCode::Enter)], "Open file / edit prompt", Action::Activate),
+    normal(
+        &[key(KeyCode::Menu), shift(KeyCode::F(10))],
+        "Context menu for the focused pane",
+        Action::ContextMenu,
+    ),
+    normal(&[ch('y')], "Copy mouse selection", Action::CopySelection),
+    normal(&[key(KeyCode::Esc)], "Clear mouse selection", Action::ClearSelection),
+    normal(&[ch('a')], "Toggle auto-scroll", Action::ToggleAutoScroll),
+    normal(&[ctrl('r')], "Reset scroll positions", Action::ResetScroll),
+    normal(&[ctrl('e')], "Edit the prompt in $EDITOR", Action::EditExternally),
+    normal(&[ctrl('z')], "Suspend to the shell (fg to return)", Action::Suspend),
+    bind(KeyContext::Editing, &[key(KeyCode::Enter)], "Send prompt"),
+    bind(KeyContext::Editing, &[key(KeyCode::Esc)], "Stop editing"),
+    bind(KeyContext::Editing, &[alt('p')], "Priority of the next prompt: high / normal / low"),
+    bind(KeyContext::Editing, &[ctrl('e')], "Edit the prompt in $EDITOR (multi-line)"),
+    bind(
+        KeyContext::Editing,
+        &[key(KeyCode::Left), key(KeyCode::Right), key(KeyCode::Home), key(KeyCode::End)],
+        "Move cursor",
+    ),
+    bind(
+        KeyContext::Editing,
+        &[key(KeyCode::Backspace), key(KeyCode::Delete)],
+        "Delete before / under cursor",
+    ),
+    bind(KeyContext::Palette, &[key(KeyCode::Esc)], "Close palette"),
+    bind(KeyContext::Palette, &[key(KeyCode::Up), key(KeyCode::Down)], "Select command"),
+    bind(KeyContext::Palette, &[key(KeyCode::Enter)], "Run selected command (or pick its argument)"),
+    bind(KeyContext::Palette, &[key(KeyCode::Backspace)], "Edit filter (type to search)"),
+    bind(KeyContext::Settings, &[key(KeyCode::Up), key(KeyCode::Down)], "Select option"),
+    bind(
+        KeyContext::Settings,
+        &[key(KeyCode::Left), key(KeyCode::Right), key(KeyCode::Enter)],
+        "Change option (saved to the config file)",
+    ),
+    bind(KeyContext::Settings, &[ch('r')], "Reconnect to IMS Core"),
+    bind(KeyContext::Settings, &[key(KeyCode::Esc)], "Close settings"),
+    bind(KeyContext::Settings, &[ch('q')], "Quit"),
+];
+
+/// Normal-mode action bound to `event` with `focus` focused
+pub fn lookup(event: &KeyEvent, focus: FocusPane) -> Option<Action> {
+    BINDINGS
+        .iter()
+        .filter(|b| b.context == KeyContext::Normal)
+        .filter(|b| b.focus.is_none_or(|pane| pane == focus))
+        .find(|b| b.chords.iter().any(|c| c.matches(event)))
+        .and_then(|b| b.action)
+}
+
+/// Bindings whose keys, description, or context contain `query` (case-insensitive)
+pub fn search(query: &str) -> Vec<&'static Binding> {
+    let query = query.trim().to_lowercase();
+    BINDINGS.iter().filter(|b| b.matches_query(&query)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn test_lookup_respects_focus_and_modifiers() {
+        let n = event(KeyCode::Char('n'), KeyModifiers::NONE);
+        assert_eq!(lookup(&n, FocusPane::Sidebar), Some(Action::NewFile));
+        assert_eq!(lookup(&n, FocusPane::Thinking), Some(Action::NetworkLog));
+
+        let shift_q = event(KeyCode::Char('Q'), KeyModifiers::SHIFT);
+        assert_eq!(lookup(&shift_q, FocusPane::Prompt), Some(Action::Quit));
+
+        // Ctrl changes the binding, it isn't ignored
+        assert_eq!(lookup(&event(KeyCode::Char('p'), KeyModifiers::NONE), FocusPane::Prompt), None);
+        assert_eq!(
+            lookup(&event(KeyCode::Char('p'), KeyModifiers::CONTROL), FocusPane::Prompt),
+            Some(Action::CommandPalette)
+        );
+        assert_eq!(lookup(&event(KeyCode::F(10), KeyModifiers::NONE), FocusPane::Prompt), None);
+        assert_eq!(
+            lookup(&event(KeyCode::Char('m'), KeyModifiers::CONTROL), FocusPane::Generation),
+            Some(Action::ToggleMaximize)
+        );
+    }
+
+    #[test]
+    fn test_search_matches_keys_and_descriptions() {
+        assert!(search("palette").iter().any(|b| b.action == Some(Action::CommandPalette)));
+        assert!(search("ctrl+r").iter().any(|b| b.action == Some(Action::ResetScroll)));
+        assert_eq!(search("").len(), BINDINGS.len());
+    }
+}
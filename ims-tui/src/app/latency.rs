@@ -0,0 +1,140 @@
+//! Latency Budgets
+//!
+//! `[latency]` sets how long a response from each model may take. One that
+//! takes longer is flagged in the Thinking pane where it arrives and counted
+//! in the Inspector; with `suggest` on, the recommendations API is asked for
+//! a replacement and the first model not itself over budget is suggested in
+//! a notification. Cached responses are never checked: their latency is the
+//! original request's.
+
+use crate::app::{
+    api::{ApiEvent, ApiSender, ImsApiClient, ModelResponse, RecommendationRequest},
+    config::LatencyConfig,
+    AppState,
+};
+use crate::core::{effects::NotificationLevel, events::Event, reduce::reduce};
+
+impl LatencyConfig {
+    /// Budget for `model_id` in milliseconds, if it has one
+    pub fn budget(&self, model_id: &str) -> Option<u64> {
+        self.models
+            .iter()
+            .find(|b| b.model == model_id)
+            .map(|b| b.max_ms)
+            .or(self.default_ms)
+    }
+}
+
+impl AppState {
+    /// Check a response's latency against its model's budget. Over budget,
+    /// it is counted and a faster model is looked up if `[latency] suggest`
+    /// is on; returns the budget it went over
+    pub fn check_latency(&mut self, model_id: &str, latency_ms: f64) -> Option<u64> {
+        let budget = self.config.latency.budget(model_id)?;
+        if latency_ms <= budget as f64 {
+            return None;
+        }
+        *self.slow_requests.entry(model_id.to_string()).or_default() += 1;
+        if self.config.latency.suggest {
+            self.suggest_faster_for = Some(model_id.to_string());
+        }
+        Some(budget)
+    }
+
+    /// Responses over budget this session, across all models
+    pub fn slow_request_count(&self) -> u32 {
+        self.slow_requests.values().sum()
+    }
+
+    /// Suggest the first of `models` that isn't `slow_model` and hasn't
+    /// been over budget itself
+    pub fn suggest_faster_model(&mut self, slow_model: &str, models: Vec<ModelResponse>) {
+        let suggestion = models
+            .into_iter()
+            .find(|m| m.model_id != slow_model && !self.slow_requests.contains_key(&m.model_id));
+        let message = match suggestion {
+            Some(model) => format!(
+                "{} is over its latency budget; try {} ({})",
+                slow_model, model.model_id, model.vendor_id
+            ),
+            None => format!("{} is over its latency budget; no faster model recommended", slow_model),
+        };
+        reduce(self, Event::NotificationShown { level: NotificationLevel::Warning, message });
+    }
+}
+
+/// Ask the recommendations API for models that could replace `slow_model`
+pub fn request_suggestion(state: &AppState, slow_model: String, api_tx: &ApiSender) {
+    let Some(client) = state.api_client.clone() else { return };
+    // Only models that fit the same context
+    let min_context_window = state
+        .model_catalog
+        .get(&slow_model)
+        .map(|m| m.context_window)
+        .unwrap_or(0);
+    tokio::spawn(suggest(client, api_tx.clone(), slow_model, min_context_window));
+}
+
+async fn suggest(client: ImsApiClient, tx: ApiSender, slow_model: String, min_context_window: u32) {
+    let criteria = RecommendationRequest {
+        min_capability_tier: None,
+        min_context_window,
+        max_cost_per_mil: None,
+        // Cheaper models are the smaller, faster ones
+        strategy: "cost".to_string(),
+    };
+    let event = match client.recommend_models(&criteria).await {
+        Ok(models) => ApiEvent::Recommendations { slow_model, models },
+        Err(e) => ApiEvent::Error(format!("Faster model lookup failed: {}", e)),
+    };
+    let _ = tx.send(event);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::config::{AppConfig, ModelBudget};
+
+    #[test]
+    fn test_responses_over_budget_are_flagged_and_counted() {
+        let mut state = AppState {
+            config: AppConfig {
+                latency: LatencyConfig {
+                    default_ms: Some(5_000),
+                    suggest: true,
+                    models: vec![ModelBudget {
+                        model: "gpt-4o".to_string(),
+                        max_ms: 2_000,
+                    }],
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        assert_eq!(state.check_latency("gpt-4o", 1_800.0), None);
+        assert_eq!(state.check_latency("gpt-4o", 2_400.0), Some(2_000));
+        // Models without an entry fall back to the default budget
+        assert_eq!(state.check_latency("claude-3-haiku", 2_400.0), None);
+        assert_eq!(state.check_latency("claude-3-haiku", 6_000.0), Some(5_000));
+        assert_eq!(state.slow_request_count(), 2);
+        assert_eq!(state.suggest_faster_for.as_deref(), Some("claude-3-haiku"));
+
+        // Models already over budget aren't suggested
+        let models = ["gpt-4o", "claude-3-haiku", "gemini-1.5-flash"]
+            .into_iter()
+            .map(|id| ModelResponse {
+                model_id: id.to_string(),
+                vendor_id: "Google".to_string(),
+                capability_tier: "Tier_2".to_string(),
+                context_window: 1_000_000,
+                cost_in_per_mil: 0.075,
+                cost_out_per_mil: 0.3,
+                function_call_support: true,
+                is_active: true,
+            })
+            .collect();
+        state.suggest_faster_model("gpt-4o", models);
+        assert!(state.debug_logs.last().unwrap().contains("try gemini-1.5-flash (Google)"));
+    }
+}
@@ -0,0 +1,200 @@
+//! Local Model Wire Formats
+//!
+//! Request bodies and response parsing for the local model servers
+//! `ImsApiClient` can talk to directly (see `[local]`): Ollama's own API,
+//! and the OpenAI-compatible one served by llama.cpp, vLLM and LM Studio.
+//! Local models cost nothing, so responses report $0.00.
+
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+
+use crate::app::{
+    api::{CostUsage, ExecuteRequest, ModelResponse, TokenUsage},
+    config::LocalKind,
+};
+
+/// `vendor_id` and tier reported for local models
+pub const VENDOR: &str = "local";
+
+/// Path of the chat endpoint
+pub fn chat_path(kind: LocalKind) -> &'static str {
+    match kind {
+        LocalKind::Ollama => "/api/chat",
+        LocalKind::OpenAi => "/v1/chat/completions",
+    }
+}
+
+/// Path of the model listing endpoint
+pub fn models_path(kind: LocalKind) -> &'static str {
+    match kind {
+        LocalKind::Ollama => "/api/tags",
+        LocalKind::OpenAi => "/v1/models",
+    }
+}
+
+/// Chat request body for `req` sent to the server's model `name`
+pub fn chat_body(kind: LocalKind, name: &str, req: &ExecuteRequest) -> Value {
+    let mut messages = Vec::new();
+    if let Some(system) = &req.system_instruction {
+        messages.push(json!({ "role": "system", "content": system }));
+    }
+    let mut user = json!({ "role": "user", "content": req.prompt });
+    if !req.images.is_empty() {
+        match kind {
+            LocalKind::Ollama => {
+                user["images"] = req.images.iter().map(|image| json!(image.data)).collect();
+            }
+            LocalKind::OpenAi => {
+                let mut parts = vec![json!({ "type": "text", "text": req.prompt })];
+                parts.extend(req.images.iter().map(|image| {
+                    json!({
+                        "type": "image_url",
+                        "image_url": { "url": format!("data:{};base64,{}", image.media_type, image.data) },
+                    })
+                }));
+                user["content"] = Value::Array(parts);
+            }
+        }
+    }
+    messages.push(user);
+    match kind {
+        LocalKind::Ollama => json!({
+            "model": name,
+            "messages": messages,
+            "stream": false,
+            "options": { "temperature": req.temperature, "num_predict": req.max_tokens },
+        }),
+        LocalKind::OpenAi => json!({
+            "model": name,
+            "messages": messages,
+            "temperature": req.temperature,
+            "max_tokens": req.max_tokens,
+        }),
+    }
+}
+
+/// The answer and token counts from a chat response
+pub fn parse_chat(kind: LocalKind, body: &str) -> Result<(String, TokenUsage)> {
+    let value: Value = serde_json::from_str(body).context("Invalid chat response")?;
+    let count = |v: &Value| v.as_u64().unwrap_or(0) as u32;
+    let (content, input, output) = match kind {
+        LocalKind::Ollama => (
+            value.pointer("/message/content"),
+            count(&value["prompt_eval_count"]),
+            count(&value["eval_count"]),
+        ),
+        LocalKind::OpenAi => (
+            value.pointer("/choices/0/message/content"),
+            count(&value["usage"]["prompt_tokens"]),
+            count(&value["usage"]["completion_tokens"]),
+        ),
+    };
+    let content = content
+        .and_then(Value::as_str)
+        .context("Chat response has no message content")?;
+    Ok((
+        content.to_string(),
+        TokenUsage {
+            input,
+            output,
+            total: input + output,
+        },
+    ))
+}
+
+/// Model names from a listing response
+pub fn parse_models(kind: LocalKind, body: &str) -> Result<Vec<String>> {
+    let value: Value = serde_json::from_str(body).context("Invalid model list")?;
+    let (list, field) = match kind {
+        LocalKind::Ollama => (&value["models"], "name"),
+        LocalKind::OpenAi => (&value["data"], "id"),
+    };
+    Ok(list
+        .as_array()
+        .context("Model list has no models")?
+        .iter()
+        .filter_map(|m| m[field].as_str().map(str::to_string))
+        .collect())
+}
+
+/// Registry entry for the local model `model_id`
+pub fn model_entry(model_id: &str, context_window: u32) -> ModelResponse {
+    ModelResponse {
+        model_id: model_id.to_string(),
+        vendor_id: VENDOR.to_string(),
+        capability_tier: "Local".to_string(),
+        context_window,
+        cost_in_per_mil: 0.0,
+        cost_out_per_mil: 0.0,
+        function_call_support: false,
+        is_active: true,
+    }
+}
+
+pub fn free() -> CostUsage {
+    CostUsage {
+        input: 0.0,
+        output: 0.0,
+        total: 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::images::ImageInput;
+
+    #[test]
+    fn test_ollama_and_openai_formats() {
+        let req = ExecuteRequest {
+            prompt: "Explain lifetimes".to_string(),
+            model_id: "local/llama3.1".to_string(),
+            max_tokens: Some(512),
+            temperature: 0.2,
+            system_instruction: Some("Be brief".to_string()),
+            user_id: None,
+            bypass_policies: false,
+            idempotency_key: None,
+            images: Vec::new(),
+            audio: Vec::new(),
+        };
+        let body = chat_body(LocalKind::Ollama, "llama3.1", &req);
+        assert_eq!(body["model"], "llama3.1");
+        assert_eq!(body["messages"][1]["content"], "Explain lifetimes");
+        assert_eq!(body["options"]["num_predict"], 512);
+        assert_eq!(chat_body(LocalKind::OpenAi, "llama3.1", &req)["max_tokens"], 512);
+
+        let req = ExecuteRequest {
+            images: vec![ImageInput {
+                name: "flow.png".to_string(),
+                media_type: "image/png".to_string(),
+                data: "iVBO".to_string(),
+            }],
+            ..req
+        };
+        assert_eq!(chat_body(LocalKind::Ollama, "llava", &req)["messages"][1]["images"][0], "iVBO");
+        let parts = &chat_body(LocalKind::OpenAi, "llava", &req)["messages"][1]["content"];
+        assert_eq!(parts[0]["text"], "Explain lifetimes");
+        assert_eq!(parts[1]["image_url"]["url"], "data:image/png;base64,iVBO");
+
+        let (content, tokens) = parse_chat(
+            LocalKind::Ollama,
+            r#"{"message": {"role": "assistant", "content": "Borrows end."}, "prompt_eval_count": 12, "eval_count": 4}"#,
+        )
+        .unwrap();
+        assert_eq!(content, "Borrows end.");
+        assert_eq!(tokens.total, 16);
+        let (content, tokens) = parse_chat(
+            LocalKind::OpenAi,
+            r#"{"choices": [{"message": {"content": "Borrows end."}}], "usage": {"prompt_tokens": 12, "completion_tokens": 5}}"#,
+        )
+        .unwrap();
+        assert_eq!((content.as_str(), tokens.output), ("Borrows end.", 5));
+        assert!(parse_chat(LocalKind::OpenAi, r#"{"choices": []}"#).is_err());
+
+        let names = parse_models(LocalKind::Ollama, r#"{"models": [{"name": "llama3.1:8b"}, {"name": "qwen2.5-coder"}]}"#);
+        assert_eq!(names.unwrap(), vec!["llama3.1:8b", "qwen2.5-coder"]);
+        let names = parse_models(LocalKind::OpenAi, r#"{"object": "list", "data": [{"id": "model.gguf"}]}"#);
+        assert_eq!(names.unwrap(), vec!["model.gguf"]);
+    }
+}
@@ -0,0 +1,512 @@
+//! Local Metrics Store
+//!
+//! Every prompt sent to IMS Core is recorded in `.ims/metrics.db` (SQLite):
+//! model, latency, tokens, cost and whether it succeeded. The Inspector's
+//! request count and latency chart and the Reports view are queried from
+//! here, so they survive restarts instead of starting from zero.
+//!
+//! Ratings given to responses are kept in the same database, and each
+//! model's share of thumbs-up is the Inspector's satisfaction figure.
+//!
+//! IMS Core's own counters (`/metrics`) are kept alongside as
+//! `BackendMetrics`, with the previous poll for deltas, and every successful
+//! request updates the session's `ActiveModel` list.
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+use std::path::{Path, PathBuf};
+
+use crate::app::{
+    api::{ExecuteResponse, MetricsResponse},
+    feedback::Feedback,
+    usage::UsageRecord,
+    AppState,
+};
+
+/// Latency points kept for the Inspector chart
+pub const LATENCY_HISTORY: usize = 60;
+
+/// `.ims/metrics.db` under the current working directory
+pub fn store_path() -> PathBuf {
+    crate::app::ims_dir().join("metrics.db")
+}
+
+/// Latest `/metrics` poll from IMS Core and the one before it
+#[derive(Clone, Debug, Default)]
+pub struct BackendMetrics {
+    pub latest: Option<MetricsResponse>,
+    pub previous: Option<MetricsResponse>,
+}
+
+impl BackendMetrics {
+    pub fn update(&mut self, metrics: MetricsResponse) {
+        self.previous = self.latest.replace(metrics);
+    }
+
+    /// A counter's latest value and its change since the previous poll
+    /// (0 on the first poll)
+    pub fn counter(&self, field: fn(&MetricsResponse) -> Option<u64>) -> Option<(u64, i64)> {
+        let latest = field(self.latest.as_ref()?)?;
+        let delta = self
+            .previous
+            .as_ref()
+            .and_then(field)
+            .map_or(0, |previous| latest as i64 - previous as i64);
+        Some((latest, delta))
+    }
+}
+
+/// A model used this session, for the Inspector's Active Models list
+#[derive(Clone, Debug, PartialEq)]
+pub struct ActiveModel {
+    pub model_id: String,
+    /// Unix seconds
+    pub last_used: i64,
+    pub requests: u32,
+    pub tokens: u64,
+    pub cost: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Outcome {
+    Success,
+    Failed(String),
+}
+
+/// One request to the Action Gateway
+#[derive(Clone, Debug, PartialEq)]
+pub struct RequestRecord {
+    /// Unix seconds
+    pub timestamp: i64,
+    pub model_id: String,
+    pub latency_ms: f64,
+    pub input_tokens: u32,
+    pub output_tokens: u32,
+    pub total_tokens: u32,
+    pub cost: f64,
+    pub outcome: Outcome,
+}
+
+impl RequestRecord {
+    pub fn from_response(response: &ExecuteResponse) -> Self {
+        Self {
+            timestamp: chrono::Local::now().timestamp(),
+            model_id: response.model_id.clone(),
+            latency_ms: response.latency_ms,
+            input_tokens: response.tokens.input,
+            output_tokens: response.tokens.output,
+            total_tokens: response.tokens.total,
+            cost: response.cost.total,
+            outcome: Outcome::Success,
+        }
+    }
+
+    pub fn failed(model_id: String, latency_ms: f64, error: String) -> Self {
+        Self {
+            timestamp: chrono::Local::now().timestamp(),
+            model_id,
+            latency_ms,
+            input_tokens: 0,
+            output_tokens: 0,
+            total_tokens: 0,
+            cost: 0.0,
+            outcome: Outcome::Failed(error),
+        }
+    }
+}
+
+/// Aggregate over a time range
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Totals {
+    pub requests: u32,
+    pub failures: u32,
+    pub tokens: u64,
+    pub cost: f64,
+}
+
+/// Ratings of one model's responses
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Satisfaction {
+    pub model_id: String,
+    pub positive: u32,
+    pub negative: u32,
+}
+
+impl Satisfaction {
+    /// Share of ratings that were thumbs-up, 0 to 100
+    pub fn percent(&self) -> u32 {
+        let total = self.positive + self.negative;
+        (self.positive * 100 + total / 2).checked_div(total).unwrap_or(0)
+    }
+}
+
+pub struct MetricsStore {
+    conn: Connection,
+}
+
+impl MetricsStore {
+    /// Open (or create) the database at `path`
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let conn = Connection::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+        Self::init(conn)
+    }
+
+    #[cfg(test)]
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS requests (
+                id            INTEGER PRIMARY KEY,
+                timestamp     INTEGER NOT NULL,
+                model_id      TEXT NOT NULL,
+                latency_ms    REAL NOT NULL,
+                input_tokens  INTEGER NOT NULL,
+                output_tokens INTEGER NOT NULL,
+                total_tokens  INTEGER NOT NULL,
+                cost          REAL NOT NULL,
+                error         TEXT
+            );
+            CREATE INDEX IF NOT EXISTS requests_timestamp ON requests (timestamp);
+            CREATE TABLE IF NOT EXISTS feedback (
+                id        INTEGER PRIMARY KEY,
+                timestamp INTEGER NOT NULL,
+                model_id  TEXT NOT NULL,
+                prompt    TEXT NOT NULL,
+                positive  INTEGER NOT NULL,
+                comment   TEXT
+            );",
+        )
+        .context("Failed to create metrics schema")?;
+        Ok(Self { conn })
+    }
+
+    pub fn record(&self, record: &RequestRecord) -> Result<()> {
+        let error = match &record.outcome {
+            Outcome::Success => None,
+            Outcome::Failed(error) => Some(error.as_str()),
+        };
+        self.conn
+            .execute(
+                "INSERT INTO requests
+                    (timestamp, model_id, latency_ms, input_tokens, output_tokens, total_tokens, cost, error)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    record.timestamp,
+                    record.model_id,
+                    record.latency_ms,
+                    record.input_tokens,
+                    record.output_tokens,
+                    record.total_tokens,
+                    record.cost,
+                    error
+                ],
+            )
+            .context("Failed to record request")?;
+        Ok(())
+    }
+
+    pub fn record_feedback(&self, feedback: &Feedback) -> Result<()> {
+        self.conn
+            .execute(
+                "INSERT INTO feedback (timestamp, model_id, prompt, positive, comment)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    feedback.timestamp,
+                    feedback.model_id,
+                    feedback.prompt,
+                    feedback.positive,
+                    feedback.comment
+                ],
+            )
+            .context("Failed to record feedback")?;
+        Ok(())
+    }
+
+    /// Ratings per model, most rated first
+    pub fn satisfaction(&self) -> Result<Vec<Satisfaction>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT model_id, SUM(positive), COUNT(*) - SUM(positive)
+             FROM feedback GROUP BY model_id ORDER BY COUNT(*) DESC, model_id",
+        )?;
+        let ratings = stmt
+            .query_map([], |row| {
+                Ok(Satisfaction {
+                    model_id: row.get(0)?,
+                    positive: row.get(1)?,
+                    negative: row.get(2)?,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()
+            .context("Failed to read feedback")?;
+        Ok(ratings)
+    }
+
+    /// Successful requests, oldest first
+    pub fn usage_records(&self) -> Result<Vec<UsageRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT timestamp, model_id, input_tokens, output_tokens, total_tokens, cost
+             FROM requests WHERE error IS NULL ORDER BY timestamp, id",
+        )?;
+        let records = stmt
+            .query_map([], |row| {
+                Ok(UsageRecord {
+                    timestamp: row.get(0)?,
+                    model_id: row.get(1)?,
+                    input_tokens: row.get(2)?,
+                    output_tokens: row.get(3)?,
+                    total_tokens: row.get(4)?,
+                    cost: row.get(5)?,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()
+            .context("Failed to read usage records")?;
+        Ok(records)
+    }
+
+    /// Requests at or after `since` (unix seconds)
+    pub fn totals_since(&self, since: i64) -> Result<Totals> {
+        self.conn
+            .query_row(
+                "SELECT COUNT(*), COUNT(error), COALESCE(SUM(total_tokens), 0), COALESCE(SUM(cost), 0.0)
+                 FROM requests WHERE timestamp >= ?1",
+                [since],
+                |row| {
+                    Ok(Totals {
+                        requests: row.get(0)?,
+                        failures: row.get(1)?,
+                        tokens: row.get(2)?,
+                        cost: row.get(3)?,
+                    })
+                },
+            )
+            .context("Failed to query totals")
+    }
+
+    /// Latencies of the last `limit` requests, oldest first
+    pub fn recent_latencies(&self, limit: usize) -> Result<Vec<f64>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT latency_ms FROM requests ORDER BY timestamp DESC, id DESC LIMIT ?1")?;
+        let mut latencies = stmt
+            .query_map([limit as i64], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<f64>>>()
+            .context("Failed to read latencies")?;
+        latencies.reverse();
+        Ok(latencies)
+    }
+
+    /// Import records from the older `usage.jsonl` file, then rename it so
+    /// they are only imported once
+    pub fn import_usage_jsonl(&mut self, path: &Path) -> Result<usize> {
+        if !path.exists() {
+            return Ok(0);
+        }
+        let records = crate::app::usage::load(path)?;
+        let tx = self.conn.transaction()?;
+        for record in &records {
+            tx.execute(
+                "INSERT INTO requests
+                    (timestamp, model_id, latency_ms, input_tokens, output_tokens, total_tokens, cost)
+                 VALUES (?1, ?2, 0, ?3, ?4, ?5, ?6)",
+                params![
+                    record.timestamp,
+                    record.model_id,
+                    record.input_tokens,
+                    record.output_tokens,
+                    record.total_tokens,
+                    record.cost
+                ],
+            )?;
+        }
+        tx.commit().context("Failed to import usage records")?;
+        std::fs::rename(path, path.with_extension("jsonl.imported"))
+            .with_context(|| format!("Failed to rename {}", path.display()))?;
+        Ok(records.len())
+    }
+}
+
+impl AppState {
+    /// Open the metrics store and load the Inspector's persisted counters
+    pub fn open_metrics_store(&mut self, path: &Path) {
+        let mut store = match MetricsStore::open(path) {
+            Ok(store) => store,
+            Err(e) => {
+                self.add_debug_log(format!("Metrics store unavailable, usage won't be recorded: {:#}", e));
+                return;
+            }
+        };
+        match store.import_usage_jsonl(&crate::app::ims_dir().join("usage.jsonl")) {
+            Ok(0) => {}
+            Ok(n) => self.add_debug_log(format!("Imported {} usage records into {}", n, path.display())),
+            Err(e) => self.add_debug_log(format!("Failed to import usage.jsonl: {:#}", e)),
+        }
+        self.metrics_store = Some(store);
+        self.refresh_stored_metrics();
+    }
+
+    /// Persist one request and update the counters derived from the store
+    pub fn record_request(&mut self, record: RequestRecord) {
+        if record.outcome == Outcome::Success {
+            self.note_model_use(&record);
+        }
+        let Some(store) = &self.metrics_store else { return };
+        if let Err(e) = store.record(&record) {
+            self.add_debug_log(format!("{:#}", e));
+            return;
+        }
+        self.refresh_stored_metrics();
+    }
+
+    /// Move the record's model to the front of `active_models`, adding its usage
+    fn note_model_use(&mut self, record: &RequestRecord) {
+        let mut model = match self.active_models.iter().position(|m| m.model_id == record.model_id) {
+            Some(index) => self.active_models.remove(index),
+            None => ActiveModel {
+                model_id: record.model_id.clone(),
+                last_used: 0,
+                requests: 0,
+                tokens: 0,
+                cost: 0.0,
+            },
+        };
+        model.last_used = record.timestamp;
+        model.requests += 1;
+        model.tokens += u64::from(record.total_tokens);
+        model.cost += record.cost;
+        self.active_models.insert(0, model);
+    }
+
+    /// Today's request count, the recent latency chart and satisfaction
+    pub(crate) fn refresh_stored_metrics(&mut self) {
+        let Some(store) = &self.metrics_store else { return };
+        let midnight = chrono::Local::now()
+            .date_naive()
+            .and_hms_opt(0, 0, 0)
+            .and_then(|t| t.and_local_timezone(chrono::Local).earliest())
+            .map(|t| t.timestamp())
+            .unwrap_or(0);
+        let refreshed = store.totals_since(midnight).and_then(|today| {
+            Ok((today, store.recent_latencies(LATENCY_HISTORY)?, store.satisfaction()?))
+        });
+        match refreshed {
+            Ok((today, latencies, satisfaction)) => {
+                self.request_count = today.requests;
+                self.latency_history = latencies;
+                self.satisfaction = satisfaction;
+            }
+            Err(e) => self.add_debug_log(format!("{:#}", e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_active_models_by_recency() {
+        let mut state = AppState::default();
+        let record = |model_id: &str, timestamp: i64, outcome: Outcome| RequestRecord {
+            timestamp,
+            model_id: model_id.to_string(),
+            latency_ms: 100.0,
+            input_tokens: 10,
+            output_tokens: 20,
+            total_tokens: 30,
+            cost: 0.5,
+            outcome,
+        };
+        state.record_request(record("gpt-4o", 100, Outcome::Success));
+        state.record_request(record("gemini-pro", 200, Outcome::Success));
+        state.record_request(record("gpt-4o", 300, Outcome::Success));
+        state.record_request(record("claude-3", 400, Outcome::Failed("timeout".to_string())));
+
+        let models: Vec<_> = state.active_models.iter().map(|m| m.model_id.as_str()).collect();
+        assert_eq!(models, vec!["gpt-4o", "gemini-pro"]);
+        let gpt = &state.active_models[0];
+        assert_eq!((gpt.requests, gpt.tokens, gpt.last_used), (2, 60, 300));
+        assert_eq!(gpt.cost, 1.0);
+    }
+
+    #[test]
+    fn test_backend_metric_deltas() {
+        let mut metrics = BackendMetrics::default();
+        assert_eq!(metrics.counter(|m| m.total_model_queries), None);
+
+        metrics.update(MetricsResponse {
+            total_models_registered: Some(12),
+            total_model_queries: Some(340),
+            total_filter_queries: None,
+        });
+        assert_eq!(metrics.counter(|m| m.total_model_queries), Some((340, 0)));
+
+        metrics.update(MetricsResponse {
+            total_models_registered: Some(11),
+            total_model_queries: Some(352),
+            total_filter_queries: Some(4),
+        });
+        assert_eq!(metrics.counter(|m| m.total_model_queries), Some((352, 12)));
+        assert_eq!(metrics.counter(|m| m.total_models_registered), Some((11, -1)));
+        assert_eq!(metrics.counter(|m| m.total_filter_queries), Some((4, 0)));
+    }
+
+    fn success(timestamp: i64, model: &str, latency_ms: f64, tokens: u32, cost: f64) -> RequestRecord {
+        RequestRecord {
+            timestamp,
+            model_id: model.to_string(),
+            latency_ms,
+            input_tokens: tokens / 2,
+            output_tokens: tokens - tokens / 2,
+            total_tokens: tokens,
+            cost,
+            outcome: Outcome::Success,
+        }
+    }
+
+    #[test]
+    fn test_record_and_query() {
+        let store = MetricsStore::open_in_memory().unwrap();
+        store.record(&success(100, "gpt-4o", 250.0, 100, 0.01)).unwrap();
+        store.record(&success(200, "gemini-pro", 400.0, 300, 0.02)).unwrap();
+        let mut failed = RequestRecord::failed("gpt-4o".to_string(), 30.0, "timeout".to_string());
+        failed.timestamp = 300;
+        store.record(&failed).unwrap();
+
+        let totals = store.totals_since(150).unwrap();
+        assert_eq!(totals, Totals { requests: 2, failures: 1, tokens: 300, cost: 0.02 });
+        assert_eq!(store.recent_latencies(2).unwrap(), vec![400.0, 30.0]);
+
+        // Failures cost nothing and stay out of the usage reports
+        let usage = store.usage_records().unwrap();
+        assert_eq!(usage.len(), 2);
+        assert_eq!(usage[0].model_id, "gpt-4o");
+        assert_eq!(usage[1].input_tokens, 150);
+    }
+
+    #[test]
+    fn test_import_usage_jsonl_once() {
+        let dir = std::env::temp_dir().join(format!("ims-tui-metrics-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let jsonl = dir.join("usage.jsonl");
+        std::fs::write(
+            &jsonl,
+            "{\"timestamp\":1,\"model_id\":\"gpt-4o\",\"input_tokens\":1,\"output_tokens\":2,\"total_tokens\":3,\"cost\":0.5}\n",
+        )
+        .unwrap();
+
+        let mut store = MetricsStore::open(&dir.join("metrics.db")).unwrap();
+        assert_eq!(store.import_usage_jsonl(&jsonl).unwrap(), 1);
+        assert_eq!(store.import_usage_jsonl(&jsonl).unwrap(), 0);
+        let usage = store.usage_records().unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(usage.len(), 1);
+        assert_eq!(usage[0].total_tokens, 3);
+    }
+}
@@ -3,11 +3,87 @@
 //! This module defines the core data structures for IMS-TUI.
 //! It maintains strict separation between UI state and business logic.
 
+pub mod alerts;
+pub mod annotations;
 pub mod api;
+pub mod api_error;
+pub mod api_key;
+pub mod audio;
+pub mod audit;
+pub mod batch;
+pub mod blocks;
+pub mod breadcrumb;
+pub mod bypass;
+pub mod cache;
+pub mod capabilities;
+pub mod checkpoints;
+pub mod citations;
+pub mod clipboard;
+pub mod compaction;
+pub mod config;
+pub mod connection;
+pub mod health;
+pub mod context_menu;
+pub mod context;
+pub mod demo;
+pub mod doctor;
+pub mod embeddings;
+pub mod endpoint;
+pub mod experiment;
+pub mod external_editor;
+pub mod feedback;
+pub mod graphql;
+pub mod guardrails;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod history;
+pub mod identity;
+pub mod idle;
+pub mod images;
+pub mod input;
+pub mod keymap;
+pub mod latency;
+pub mod local;
+pub mod metrics_store;
+pub mod modal;
+pub mod model_cache;
+pub mod network;
+pub mod offline;
+pub mod overlay;
+pub mod pipeline;
+pub mod postprocess;
+pub mod otel;
+pub mod palette;
+pub mod profiler;
+pub mod prompt_queue;
+pub mod prometheus;
+pub mod rate_limit;
+pub mod read_only;
+pub mod recovery;
+pub mod redact;
+pub mod regeneration;
+pub mod registry;
+pub mod sandbox;
+pub mod schedule;
+pub mod selection;
+pub mod settings;
+pub mod share;
+pub mod status_bar;
+pub mod tee;
+pub mod telemetry;
+pub mod thinking;
+pub mod title;
+pub mod tokens;
+pub mod transport;
+pub mod usage;
+pub mod vendors;
+pub mod watch;
+pub mod workflows;
 
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::cell::RefCell;
+use std::time::Duration;
 use serde::{Deserialize, Serialize};
 use ratatui::layout::Rect;
 use tui_tree_widget::TreeState;
@@ -22,6 +98,7 @@ pub enum AgentStatus {
 }
 
 impl AgentStatus {
+    #[allow(dead_code)]
     pub fn emoji(&self) -> &'static str {
         match self {
             AgentStatus::Working => "🟢",
@@ -70,6 +147,8 @@ impl FileNode {
         }
     }
 
+    #[cfg(any(test, feature = "testing"))]
+    #[cfg_attr(not(test), allow(dead_code))]
     pub fn new_dir(path: PathBuf) -> Self {
         let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("unknown").to_string();
         Self {
@@ -86,10 +165,16 @@ impl FileNode {
 }
 
 /// Scroll behavior for a pane
+///
+/// Offsets count visual rows (after wrapping), not source lines.
 #[derive(Clone, Debug)]
 pub struct ScrollState {
     pub auto_scroll: bool,
     pub scroll_offset: u16,
+    /// (total rows, visible rows) from the last render, for clamping
+    pub viewport: std::cell::Cell<(usize, usize)>,
+    /// Screen rect of the content (inside the borders) from the last render
+    pub area: std::cell::Cell<Rect>,
 }
 
 impl Default for ScrollState {
@@ -97,18 +182,73 @@ impl Default for ScrollState {
         Self {
             auto_scroll: true,
             scroll_offset: 0,
+            viewport: Default::default(),
+            area: Default::default(),
         }
     }
 }
 
 impl ScrollState {
+    fn max_offset(&self) -> usize {
+        let (total, visible) = self.viewport.get();
+        total.saturating_sub(visible)
+    }
+
+    /// First visible row for content of `total` rows in a `visible`-row pane
+    pub fn offset(&self, total: usize, visible: usize) -> usize {
+        self.viewport.set((total, visible));
+        if self.auto_scroll {
+            self.max_offset()
+        } else {
+            (self.scroll_offset as usize).min(self.max_offset())
+        }
+    }
+
+    /// Scroll by `delta` rows from what is on screen; reaching the bottom
+    /// resumes auto-scroll
     pub fn manual_scroll(&mut self, delta: i16) {
-        self.auto_scroll = false;
-        if delta > 0 {
-            self.scroll_offset = self.scroll_offset.saturating_add(delta as u16);
+        let max = self.max_offset();
+        let current = if self.auto_scroll {
+            max
+        } else {
+            (self.scroll_offset as usize).min(max)
+        };
+        let next = (current as i64 + delta as i64).clamp(0, max as i64) as usize;
+
+        if delta > 0 && next >= max {
+            self.enable_auto_scroll();
         } else {
-            self.scroll_offset = self.scroll_offset.saturating_sub(delta.abs() as u16);
+            self.auto_scroll = false;
+            self.scroll_offset = next.min(u16::MAX as usize) as u16;
+        }
+    }
+
+    /// Show row `row` at the top of the pane
+    pub fn scroll_to(&mut self, row: usize) {
+        self.auto_scroll = false;
+        self.scroll_offset = row.min(self.max_offset()).min(u16::MAX as usize) as u16;
+    }
+
+    /// Stop following the stream, keeping the rows currently on screen
+    pub fn freeze(&mut self) {
+        if self.auto_scroll {
+            self.auto_scroll = false;
+            self.scroll_offset = self.max_offset().min(u16::MAX as usize) as u16;
+        }
+    }
+
+    /// (visual row, column) under screen cell `(x, y)`, if inside the content area
+    pub fn row_at(&self, x: u16, y: u16) -> Option<(usize, usize)> {
+        let area = self.area.get();
+        if !area.contains(ratatui::layout::Position { x, y }) {
+            return None;
         }
+        let offset = if self.auto_scroll {
+            self.max_offset()
+        } else {
+            (self.scroll_offset as usize).min(self.max_offset())
+        };
+        Some((offset + (y - area.y) as usize, (x - area.x) as usize))
     }
 
     pub fn enable_auto_scroll(&mut self) {
@@ -122,27 +262,30 @@ impl ScrollState {
 pub struct ActiveSession {
     /// Path to file being processed
     pub file_path: PathBuf,
-    /// Vendor name (e.g., "Google Gemini", "Anthropic Claude")
-    pub vendor_name: String,
-    /// Vendor logo character (e.g., "▲" for Anthropic, "●" for OpenAI)
-    pub vendor_logo: String,
+    /// Who makes the model, and how they are shown
+    pub vendor: vendors::Vendor,
     /// Model ID
     pub model_id: String,
     /// Scroll state for thinking pane
     pub thinking: ScrollState,
     /// Scroll state for generation pane
     pub generation: ScrollState,
+    /// Time spent on the session while not idle
+    pub focus_time: Duration,
+    /// Time the session was open while idle
+    pub idle_time: Duration,
 }
 
 impl ActiveSession {
-    pub fn new(file_path: PathBuf, vendor_name: String, vendor_logo: String, model_id: String) -> Self {
+    pub fn new(file_path: PathBuf, vendor: &vendors::Vendor, model_id: String) -> Self {
         Self {
             file_path,
-            vendor_name,
-            vendor_logo,
+            vendor: vendor.clone(),
             model_id,
             thinking: ScrollState::default(),
             generation: ScrollState::default(),
+            focus_time: Duration::ZERO,
+            idle_time: Duration::ZERO,
         }
     }
 
@@ -152,6 +295,99 @@ impl ActiveSession {
     }
 }
 
+/// One prompt/response exchange in the active session
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ConversationTurn {
+    pub prompt: String,
+    pub model_id: String,
+    /// `None` while the request is in flight
+    pub response: Option<String>,
+    /// Offline queue entry this turn is waiting on (shown as Pending)
+    #[serde(skip)]
+    pub queued: Option<uuid::Uuid>,
+    /// Saved generation this turn re-runs ("Retry with…"), by file name
+    pub replay_of: Option<String>,
+    /// Numbered search results sent with the prompt
+    #[serde(default)]
+    pub citations: Vec<citations::Citation>,
+    /// Summary of this and every earlier turn, sent in their place once
+    /// the history was compacted
+    #[serde(default)]
+    pub summary: Option<String>,
+    /// Idempotency key of the request answering it, while in flight
+    #[serde(skip)]
+    pub request: Option<String>,
+    /// Thinking entry (by id) showing its prompt
+    #[serde(skip)]
+    pub prompt_entry: Option<u64>,
+}
+
+impl ConversationTurn {
+    /// A turn waiting on its response, with nothing else attached
+    pub fn new(prompt: impl Into<String>, model_id: impl Into<String>) -> Self {
+        Self {
+            prompt: prompt.into(),
+            model_id: model_id.into(),
+            response: None,
+            queued: None,
+            replay_of: None,
+            citations: Vec::new(),
+            summary: None,
+            request: None,
+            prompt_entry: None,
+        }
+    }
+}
+
+/// A prompt sent to the Action Gateway and not answered yet
+#[derive(Clone, Debug)]
+pub struct InFlightPrompt {
+    /// Sent as the `Idempotency-Key` header; retries of the same request reuse it
+    pub key: String,
+    /// What the user typed, to catch the same prompt being submitted twice
+    pub prompt: String,
+    pub model_id: String,
+    /// Sent with IMS Core's policies skipped (see `bypass`)
+    pub bypass_policies: bool,
+    /// Times it has been resent after a 429 (see `rate_limit`)
+    pub rate_limit_retries: u32,
+    /// Files attached when it was submitted: read and checked by the
+    /// guardrails when it is sent, and images and audio sent with it
+    pub attachments: Vec<PathBuf>,
+    /// File read when it is sent and put ahead of the prompt, as a batch
+    /// sends each of its files
+    pub inline_file: Option<PathBuf>,
+}
+
+impl InFlightPrompt {
+    pub fn new(prompt: String, model_id: String) -> Self {
+        Self {
+            key: uuid::Uuid::new_v4().to_string(),
+            prompt,
+            model_id,
+            bypass_policies: false,
+            rate_limit_retries: 0,
+            attachments: Vec::new(),
+            inline_file: None,
+        }
+    }
+}
+
+/// A prompt held back because its estimated cost is over `[cost] confirm_above`
+#[derive(Clone, Debug)]
+pub struct CostConfirm {
+    pub prompt: String,
+    pub estimate: f64,
+    pub threshold: f64,
+}
+
+/// `.ims` under the current working directory, where local history and usage live
+pub fn ims_dir() -> std::path::PathBuf {
+    std::env::current_dir()
+        .unwrap_or_else(|_| std::path::PathBuf::from("."))
+        .join(".ims")
+}
+
 /// Focus target for keyboard navigation
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
 pub enum FocusPane {
@@ -162,6 +398,26 @@ pub enum FocusPane {
     Prompt,
 }
 
+impl FocusPane {
+    /// In Alt+1..5 order
+    pub const ALL: [FocusPane; 5] = [
+        FocusPane::Sidebar,
+        FocusPane::Thinking,
+        FocusPane::Generation,
+        FocusPane::Inspector,
+        FocusPane::Prompt,
+    ];
+
+    /// 1-based jump number (Alt+N)
+    pub fn number(self) -> usize {
+        Self::ALL.iter().position(|p| *p == self).unwrap_or(0) + 1
+    }
+
+    pub fn from_number(n: usize) -> Option<Self> {
+        Self::ALL.get(n.checked_sub(1)?).copied()
+    }
+}
+
 /// Input mode for the prompt box
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum InputMode {
@@ -169,50 +425,271 @@ pub enum InputMode {
     Editing,
 }
 
-use crate::app::api::ImsApiClient;
+use crate::app::api::{ImsApiClient, ModelPage, ModelResponse};
+use crate::app::blocks::{BlocksView, CodeBlock};
+use crate::app::config::AppConfig;
+use crate::app::connection::ReconnectStatus;
+use crate::app::context_menu::ContextMenu;
+use crate::app::input::TextInput;
+use crate::app::offline::OfflineQueue;
+use crate::app::network::{NetworkEntry, NetworkView, TransportTotals, MAX_NETWORK_ENTRIES};
+use crate::app::profiler::Profiler;
+use crate::app::prometheus::MetricSeries;
+use crate::app::registry::ModelRegistryView;
+use crate::app::selection::Selection;
+use crate::app::telemetry::{TelemetryEntry, MAX_TELEMETRY_ENTRIES};
 
 /// Main application state
 pub struct AppState {
     // File Management (Tree)
     pub file_tree: Vec<FileNode>,
     pub tree_state: RefCell<TreeState<String>>,
+    /// Token estimate of the prompt box, kept between redraws
+    pub budget_cache: context::BudgetCache,
 
     // Active Session
     pub session: Option<ActiveSession>,
 
     // Content Buffers
-    pub thinking_log: Vec<String>,
+    pub thinking_log: Vec<thinking::ThinkingEntry>,
+    /// Thinking pane shows only this source's entries
+    pub thinking_filter: Option<thinking::Source>,
     pub generated_code: String,
+    /// Which turn produced each part of `generated_code`
+    pub generation_regions: Vec<annotations::GenerationRegion>,
+    /// Region picked in the Generation gutter
+    pub selected_region: Option<usize>,
+    /// Thinking entry (by id) of the selected region's prompt
+    pub highlighted_entry: Option<u64>,
+    /// Id given to the next Thinking entry logged
+    pub next_entry_id: u64,
+    /// Color what each regeneration changed from the previous attempt
+    pub highlight_changes: bool,
+    #[allow(dead_code)]
     pub meta_prompt: String,
+    pub conversation: Vec<ConversationTurn>,
+    /// Prompts awaiting a response, across all session tabs
+    pub in_flight: Vec<InFlightPrompt>,
+    /// Prompts waiting for one in flight to finish
+    pub prompt_queue: prompt_queue::PromptQueue,
+    /// Priority the next submitted prompt is queued with (Alt+P)
+    pub next_priority: prompt_queue::Priority,
+    /// Fenced code blocks from this session's responses
+    pub code_blocks: Vec<CodeBlock>,
+    /// Images from this session's responses
+    pub returned_images: Vec<images::ReturnedImage>,
 
     // Prompt Input
     pub input_mode: InputMode,
-    pub input_buffer: String,
+    pub input_buffer: TextInput,
     pub prompt_history: Vec<String>,
+    pub attachments: Vec<PathBuf>,
+    /// Semantic search results sent as prompt context
+    pub search_context: Vec<embeddings::SearchHit>,
+    /// Sent with every prompt of the session
+    pub system_instruction: Option<String>,
+    /// Text to open in `$EDITOR` before the next frame
+    pub external_edit: Option<external_editor::EditTarget>,
+    /// Explorer files marked (Space) for the next batch run
+    pub marked_files: Vec<PathBuf>,
+    /// Current or last batch run, shown in the Inspector's Agents block
+    pub batch: Option<batch::Batch>,
 
     // UI State
     pub global_auto_scroll: bool,
-    pub show_settings: bool,
-    pub settings_index: usize,
-    pub command_palette_visible: bool,
+    /// Style responses as markdown (off: raw text)
+    pub render_markdown: bool,
+    /// ASCII stand-ins for emoji (auto-detected from TERM/locale)
+    pub ascii_mode: bool,
+    /// Colors the terminal shows, from `[ui] colors` or detected
+    pub color_depth: crate::ui::colors::ColorDepth,
+    /// Vendor logo blinks after the generation while auto-scrolling
+    pub vendor_cursor: bool,
+    /// Lines per mouse wheel notch in the content panes
+    pub scroll_lines: u8,
+    /// Settings, palette, help and the other views open over the workspace
+    pub overlays: overlay::Overlays,
+    pub settings: settings::SettingsState,
+    /// Output of the status bar's command-backed segments
+    pub status_segments: status_bar::StatusSegments,
+    /// Last input, and whether the user has been away since
+    pub activity: idle::Activity,
+    /// A pending bell and the status bar's flash
+    pub alerts: alerts::Alerts,
     pub command_input: String,
     pub command_index: usize,
+    /// The palette is asking for a command's argument
+    pub command_argument: Option<palette::ArgumentPick>,
+    /// Palette commands run before, for ranking
+    pub command_recents: palette::Recents,
+    pub model_registry: ModelRegistryView,
+    pub blocks_view: BlocksView,
+    /// Named session snapshots and the tabs they are restored into
+    pub checkpoints: Vec<checkpoints::Checkpoint>,
+    pub session_tabs: checkpoints::SessionTabs,
+    pub checkpoints_view: checkpoints::CheckpointsView,
+    /// Saved generations browser ("History: Browse Generations")
+    pub history_view: history::HistoryView,
+    /// Usage reports ("Reports: Usage")
+    pub reports_view: usage::ReportsView,
+    /// Every prompt sent, from `.ims/audit.jsonl` ("Audit: View Trail")
+    pub audit_view: audit::AuditView,
+    /// Refactor pipeline progress ("Pipeline: Show Progress")
+    pub pipeline_view: pipeline::PipelineView,
+    pub workflows_view: workflows::WorkflowsView,
+    /// Scheduled prompts and workflows ("Schedules: Show Upcoming Runs")
+    pub schedules_view: schedule::SchedulesView,
+    /// Sources of the latest response with any (`o` in Thinking)
+    pub citations_view: citations::CitationsView,
+    /// Source to open in the editor before the next frame
+    pub citation_jump: Option<citations::Citation>,
+    /// Semantic search over the workspace ("Search: Semantic Search")
+    pub search_view: embeddings::SearchView,
+    /// Files whose saves re-run a prompt ("Watch: Show Bindings")
+    pub watch_view: watch::WatchView,
+    /// Unset in demo mode and tests; bindings are then never triggered
+    pub file_watcher: Option<watch::FileWatcher>,
+    /// A/B test splitting prompts between two models, running or stopped
+    pub experiment: Option<experiment::Experiment>,
+    /// Masked admin key input shown after a 401/403
+    pub api_key_prompt: Option<api_key::ApiKeyPrompt>,
+    /// Admin key confirmation before arming a policy bypass
+    pub bypass_prompt: Option<bypass::BypassPrompt>,
+    /// The next prompt is sent with `bypass_policies`
+    pub bypass_armed: bool,
+    /// Rating of a response waiting on its optional comment
+    pub feedback_prompt: Option<feedback::FeedbackPrompt>,
+    /// Waiting for the user to confirm an expensive prompt
+    pub cost_confirm: Option<CostConfirm>,
+    /// Unsent work left by a previous run that did not exit cleanly,
+    /// waiting for the user to restore or discard it
+    pub recovered: Option<recovery::RecoveredWork>,
+    /// Project directory that disk-writing features are confined to
+    pub workspace_root: PathBuf,
+    /// A write is waiting for the user to trust `workspace_root`
+    pub trust_prompt: Option<sandbox::TrustPrompt>,
+    /// Keybinding help overlay (`?`) and its search query
+    pub help_query: input::TextInput,
+    pub help_scroll: usize,
     pub focus: FocusPane,
+    /// Side columns can be hidden from the palette
+    pub show_sidebar: bool,
+    pub show_inspector: bool,
+    /// Generation fills the screen while a response is in flight
+    pub zen_mode: bool,
+    /// Pane temporarily expanded to fill the screen (cleared on focus change)
+    pub maximized: Option<FocusPane>,
+    /// Alt is down (only reported by terminals with keyboard enhancement)
+    pub alt_held: bool,
+    /// Pane numbers stay visible briefly after an Alt+N jump
+    pub pane_hints_until: Option<std::time::Instant>,
+    /// Mouse-drag selection in the Thinking/Generation panes
+    pub selection: Option<Selection>,
+    /// Time and cell of the last left click, for double-click detection
+    pub last_click: Option<(std::time::Instant, u16, u16)>,
+    /// Confirmation, input or pick taking all input until answered
+    pub modal: Option<modal::Modal>,
+    pub context_menu: Option<ContextMenu>,
+    /// Files dropped down from a breadcrumb
+    pub file_dropdown: Option<breadcrumb::FileDropdown>,
+    #[allow(dead_code)]
     pub pane_areas: HashMap<FocusPane, Rect>,
 
     // Metrics & Stats
     pub total_tokens_used: u64,
     pub total_cost: f64,
-    pub active_models: Vec<String>,
+    /// Models used this session, most recent first
+    pub active_models: Vec<metrics_store::ActiveModel>,
+    /// Requests sent today, from the metrics store
     pub request_count: u32,
+    /// IMS Core's `/metrics` counters
+    pub backend_metrics: metrics_store::BackendMetrics,
+    /// Per-subsystem status from `/health`
+    pub health: health::HealthDetail,
+    /// Latencies of recent requests (ms, oldest first), from the metrics store
+    pub latency_history: Vec<f64>,
+    /// Ratings per model, from the metrics store
+    pub satisfaction: Vec<metrics_store::Satisfaction>,
+    pub metrics_store: Option<metrics_store::MetricsStore>,
 
     // Debug & Logs
     pub debug_logs: Vec<String>,
+    /// Masks secrets in logs, exports and outgoing prompts (`[redaction]`)
+    pub redactor: redact::Redactor,
+    /// Checks run on prompts before they are sent (`[guardrails]`)
+    pub guardrails: guardrails::Guardrails,
+    pub show_profiler: bool,
+    pub profiler: Profiler,
+
+    // Telemetry Bus
+    pub telemetry_log: Vec<TelemetryEntry>,
+    pub telemetry_status: String,
+    pub queue_depths: HashMap<String, u32>,
+
+    // Prompts submitted while offline
+    pub offline_queue: OfflineQueue,
+
+    // Network Log
+    pub network_log: Vec<NetworkEntry>,
+    pub network_view: NetworkView,
+    pub transport: TransportTotals,
+
+    // Prometheus series (Inspector charts)
+    pub metric_series: Vec<MetricSeries>,
+
+    // Configuration
+    pub config: AppConfig,
 
     // Backend Connection
     pub api_base_url: String,
     pub api_connected: bool,
     pub api_client: Option<ImsApiClient>,
+    /// Where the current session's generation output is mirrored
+    pub tee: Option<tee::TeeFile>,
+    /// Who requests and exports are attributed to (see `identity`)
+    pub user_id: String,
+    /// Vendor branding and defaults by model (built-in plus `[[vendors]]`)
+    pub vendors: vendors::VendorRegistry,
+    /// In-progress reconnect (status bar shows attempt and countdown)
+    pub reconnect: Option<ReconnectStatus>,
+    /// Set by the Reconnect command; picked up by the event loop
+    pub reconnect_requested: bool,
+    /// Set by the reducer when the backend comes back; picked up by the
+    /// event loop
+    pub poller_request: Option<connection::PollerRequest>,
+    pub flush_queue_requested: bool,
+    /// Set by the Refresh command; the event loop refetches the registry
+    pub registry_reload_requested: bool,
+    /// Set by "Search: Index Workspace"; the event loop starts indexing
+    pub index_requested: bool,
+    /// Set after a response fills the context window past `[compaction]
+    /// threshold`, or by "Conversation: Compact History"; the event loop
+    /// summarizes older turns
+    pub compaction_requested: bool,
+    /// Older turns are being summarized
+    pub compacting: bool,
+    /// Background health and metrics polling stopped from the palette
+    pub polling_paused: bool,
+    /// Admin features unavailable (see `read_only`)
+    pub read_only: Option<read_only::ReadOnly>,
+    /// Set by commands/signals; the event loop exits on the next tick
+    pub quit_requested: bool,
+    /// Ctrl+Z or SIGTSTP: the event loop hands the terminal back to the
+    /// shell and stops
+    pub suspend_requested: bool,
+    /// SIGCONT: the terminal may have been reset while stopped, so the
+    /// event loop restores its modes and redraws in full
+    pub redraw_requested: bool,
+    pub model_catalog: HashMap<String, ModelResponse>,
+    /// Session default model per file extension
+    pub model_defaults: HashMap<String, String>,
+    /// Responses over their latency budget this session, per model
+    pub slow_requests: HashMap<String, u32>,
+    /// Prompts rejected with 429, waiting to be sent again
+    pub rate_limit_retries: Vec<rate_limit::ScheduledRetry>,
+    /// Set when a response is over budget with `[latency] suggest` on;
+    /// the event loop asks for a faster model
+    pub suggest_faster_for: Option<String>,
 }
 
 impl Default for AppState {
@@ -220,29 +697,134 @@ impl Default for AppState {
         Self {
             file_tree: Vec::new(),
             tree_state: RefCell::new(TreeState::default()),
+            budget_cache: Default::default(),
             session: None,
             thinking_log: Vec::new(),
+            thinking_filter: None,
             generated_code: String::new(),
+            generation_regions: Vec::new(),
+            selected_region: None,
+            highlighted_entry: None,
+            next_entry_id: 1,
+            highlight_changes: true,
             meta_prompt: String::new(),
+            conversation: Vec::new(),
+            in_flight: Vec::new(),
+            prompt_queue: Default::default(),
+            next_priority: Default::default(),
+            code_blocks: Vec::new(),
+            returned_images: Vec::new(),
             input_mode: InputMode::Normal,
-            input_buffer: String::new(),
+            input_buffer: TextInput::default(),
             prompt_history: Vec::new(),
+            attachments: Vec::new(),
+            search_context: Vec::new(),
+            system_instruction: None,
+            external_edit: None,
+            marked_files: Vec::new(),
+            batch: None,
             global_auto_scroll: true,
-            show_settings: false,
-            settings_index: 0,
-            command_palette_visible: false,
+            render_markdown: true,
+            ascii_mode: false,
+            color_depth: Default::default(),
+            overlays: overlay::Overlays::default(),
+            vendor_cursor: true,
+            scroll_lines: 1,
+            settings: Default::default(),
+            status_segments: Default::default(),
+            activity: Default::default(),
+            alerts: Default::default(),
             command_input: String::new(),
             command_index: 0,
+            command_argument: None,
+            command_recents: Default::default(),
+            model_registry: ModelRegistryView::default(),
+            blocks_view: BlocksView::default(),
+            checkpoints: Vec::new(),
+            session_tabs: Default::default(),
+            checkpoints_view: Default::default(),
+            history_view: Default::default(),
+            reports_view: Default::default(),
+            audit_view: Default::default(),
+            pipeline_view: Default::default(),
+            workflows_view: Default::default(),
+            schedules_view: Default::default(),
+            citations_view: Default::default(),
+            citation_jump: None,
+            search_view: Default::default(),
+            experiment: None,
+            watch_view: Default::default(),
+            file_watcher: None,
+            api_key_prompt: None,
+            bypass_prompt: None,
+            feedback_prompt: None,
+            bypass_armed: false,
+            cost_confirm: None,
+            recovered: None,
+            workspace_root: sandbox::default_root(),
+            trust_prompt: None,
+            help_query: Default::default(),
+            help_scroll: 0,
             focus: FocusPane::Sidebar,
+            show_sidebar: true,
+            show_inspector: true,
+            zen_mode: false,
+            maximized: None,
+            alt_held: false,
+            pane_hints_until: None,
+            selection: None,
+            last_click: None,
+            modal: None,
+            context_menu: None,
+            file_dropdown: None,
             pane_areas: HashMap::new(),
             total_tokens_used: 0,
             total_cost: 0.0,
             active_models: Vec::new(),
             request_count: 0,
+            backend_metrics: Default::default(),
+            health: Default::default(),
+            latency_history: Vec::new(),
+            satisfaction: Vec::new(),
+            metrics_store: None,
             debug_logs: Vec::new(),
+            redactor: redact::Redactor::built_in(),
+            guardrails: guardrails::Guardrails::built_in(),
+            show_profiler: false,
+            profiler: Profiler::default(),
+            telemetry_log: Vec::new(),
+            telemetry_status: "Disabled".to_string(),
+            queue_depths: HashMap::new(),
+            offline_queue: OfflineQueue::default(),
+            network_log: Vec::new(),
+            network_view: NetworkView::default(),
+            transport: TransportTotals::default(),
+            metric_series: Vec::new(),
+            config: AppConfig::default(),
             api_base_url: "http://localhost:8000".to_string(),
             api_connected: false,
             api_client: None,
+            tee: None,
+            user_id: identity::FALLBACK_USER.to_string(),
+            vendors: vendors::VendorRegistry::built_in(),
+            reconnect: None,
+            reconnect_requested: false,
+            poller_request: None,
+            flush_queue_requested: false,
+            registry_reload_requested: false,
+            index_requested: false,
+            compaction_requested: false,
+            compacting: false,
+            polling_paused: false,
+            read_only: None,
+            quit_requested: false,
+            suspend_requested: false,
+            redraw_requested: false,
+            model_catalog: HashMap::new(),
+            model_defaults: HashMap::new(),
+            slow_requests: HashMap::new(),
+            rate_limit_retries: Vec::new(),
+            suggest_faster_for: None,
         }
     }
 }
@@ -278,66 +860,485 @@ impl AppState {
         let selected_id = self.tree_state.borrow().selected().last().cloned();
         
         if let Some(id) = selected_id {
-            if let Some(node) = Self::find_node_recursive(&self.file_tree, &id) {
-                if !node.is_dir {
-                    let path = node.path.clone();
-                    let name = node.name.clone();
-                    let model = node.model.clone();
-
-                    let vendor = if model.contains("gemini") {
-                        ("Google Gemini".to_string(), "◆".to_string())
-                    } else if model.contains("claude") {
-                        ("Anthropic Claude".to_string(), "▲".to_string())
-                    } else if model.contains("gpt") {
-                        ("OpenAI GPT".to_string(), "●".to_string())
-                    } else {
-                        ("Unknown Vendor".to_string(), "?".to_string())
-                    };
-
-                    let mut session = ActiveSession::new(path, vendor.0, vendor.1, model);
-                    session.reset_scroll();
-                    self.session = Some(session);
-                    self.thinking_log.clear();
-                    self.generated_code.clear();
-                    self.add_debug_log(format!("Opened file: {}", name));
+            match Self::find_node_recursive(&self.file_tree, &id) {
+                Some(node) if node.is_dir => {
+                    self.tree_state.borrow_mut().toggle_selected();
+                }
+                Some(_) => self.open_file(&id),
+                None => {}
+            }
+        }
+    }
+
+    /// Start a session on the file with tree node `id` (or import it, if
+    /// it is a session archive)
+    pub fn open_file(&mut self, id: &str) {
+        let Some(node) = Self::find_node_recursive(&self.file_tree, id).filter(|n| !n.is_dir) else { return };
+        if share::is_archive(&node.path) {
+            let path = node.path.clone();
+            self.import_session(&path);
+            return;
+        }
+        let path = node.path.clone();
+        let name = node.name.clone();
+        let model = node.model.clone();
+
+        let mut session = ActiveSession::new(path, self.vendors.for_model(&model), model);
+        session.reset_scroll();
+        self.session = Some(session);
+        if self.config.tee.enabled {
+            self.start_tee();
+        }
+        self.thinking_log.clear();
+        self.generated_code.clear();
+        self.clear_generation_regions();
+        self.conversation.clear();
+        self.code_blocks.clear();
+        self.returned_images.clear();
+        self.selection = None;
+        self.add_debug_log(format!("Opened file: {}", name));
+    }
+
+    fn find_node_recursive_mut<'a>(nodes: &'a mut [FileNode], id: &str) -> Option<&'a mut FileNode> {
+        for node in nodes {
+            if node.id == id {
+                return Some(node);
+            }
+            if let Some(found) = Self::find_node_recursive_mut(&mut node.children, id) {
+                return Some(found);
+            }
+        }
+        None
+    }
+
+    /// (id, path) of every file (not directory) in the tree
+    pub fn workspace_files(&self) -> Vec<(String, PathBuf)> {
+        fn collect(nodes: &[FileNode], out: &mut Vec<(String, PathBuf)>) {
+            for node in nodes {
+                if node.is_dir {
+                    collect(&node.children, out);
                 } else {
-                     self.tree_state.borrow_mut().toggle(vec![id.clone()]);
+                    out.push((node.id.clone(), node.path.clone()));
                 }
             }
         }
+        let mut files = Vec::new();
+        collect(&self.file_tree, &mut files);
+        files
+    }
+
+    /// Apply token estimates produced by the background scanner
+    pub fn apply_token_estimates(&mut self, estimates: &[(String, u32)]) {
+        for (id, tokens) in estimates {
+            if let Some(node) = Self::find_node_recursive_mut(&mut self.file_tree, id) {
+                node.tokens = *tokens;
+            }
+        }
+    }
+
+    /// Aggregate estimated tokens across all workspace files
+    pub fn workspace_tokens(&self) -> u64 {
+        fn sum(nodes: &[FileNode]) -> u64 {
+            nodes.iter().map(|n| n.tokens as u64 + sum(&n.children)).sum()
+        }
+        sum(&self.file_tree)
+    }
+
+    /// Estimated tokens of the file in the active session
+    pub fn session_file_tokens(&self) -> Option<u32> {
+        let session = self.session.as_ref()?;
+        let id = session.file_path.to_string_lossy();
+        Self::find_node_recursive(&self.file_tree, &id).map(|n| n.tokens)
+    }
+
+    /// Attach `path`, or detach it if it already is; whether it is now
+    /// attached. Audio the backend would reject is not attached.
+    pub fn toggle_attachment(&mut self, path: PathBuf) -> anyhow::Result<bool> {
+        if let Some(pos) = self.attachments.iter().position(|p| *p == path) {
+            self.attachments.remove(pos);
+            Ok(false)
+        } else {
+            if audio::is_audio(&path) {
+                audio::validate(&path)?;
+            }
+            self.attachments.push(path);
+            Ok(true)
+        }
+    }
+
+    /// Registry entry for the active session's model, once fetched
+    pub fn session_model_info(&self) -> Option<&ModelResponse> {
+        let session = self.session.as_ref()?;
+        self.model_catalog.get(&session.model_id)
+    }
+
+    /// Estimated tokens of an attached file (from the background scan)
+    pub fn file_tokens(&self, path: &std::path::Path) -> u32 {
+        Self::find_node_recursive(&self.file_tree, &path.to_string_lossy())
+            .map(|n| n.tokens)
+            .unwrap_or(0)
+    }
+
+    /// Store a complete registry listing
+    #[cfg(test)]
+    pub fn apply_model_list(&mut self, models: Vec<ModelResponse>) {
+        self.apply_model_page(
+            ModelPage {
+                models,
+                ..Default::default()
+            },
+            false,
+        );
+    }
+
+    /// Store a fetched page of the registry listing
+    pub fn apply_model_page(&mut self, page: ModelPage, append: bool) {
+        for model in &page.models {
+            self.model_catalog.insert(model.model_id.clone(), model.clone());
+        }
+        if !append {
+            self.model_registry.checked_at = Some(chrono::Utc::now().timestamp());
+        }
+        self.model_registry.apply_page(page, append);
+    }
+
+    /// The backend confirmed the cached registry listing (304)
+    pub fn keep_cached_models(&mut self, local: Vec<ModelResponse>) {
+        for model in &local {
+            self.model_catalog.insert(model.model_id.clone(), model.clone());
+        }
+        self.model_registry.checked_at = Some(chrono::Utc::now().timestamp());
+        self.model_registry.keep_cached(local);
+    }
+
+    /// Switch the active session to another model
+    pub fn set_session_model(&mut self, model_id: &str) {
+        if let Some(session) = &mut self.session {
+            session.model_id = model_id.to_string();
+            session.vendor = self.vendors.for_model(model_id).clone();
+        }
+    }
+
+    /// Use `model_id` by default for every file with the given extension
+    pub fn set_model_default(&mut self, file_type: String, model_id: &str) {
+        fn apply(nodes: &mut [FileNode], file_type: &str, model_id: &str) {
+            for node in nodes {
+                if !node.is_dir && registry::file_type_key(&node.path) == file_type {
+                    node.model = model_id.to_string();
+                }
+                apply(&mut node.children, file_type, model_id);
+            }
+        }
+        apply(&mut self.file_tree, &file_type, model_id);
+        self.model_defaults.insert(file_type, model_id.to_string());
     }
 
     // Stub for old method signature
     pub fn add_file(&mut self, path: PathBuf) {
-        self.file_tree.push(FileNode::new_file(path));
+        let mut node = FileNode::new_file(path);
+        if let Some(model) = self.model_defaults.get(&registry::file_type_key(&node.path)) {
+            node.model = model.clone();
+        }
+        self.file_tree.push(node);
     }
 
-    pub fn cycle_focus(&mut self) {
-        self.focus = match self.focus {
-            FocusPane::Sidebar => FocusPane::Thinking,
-            FocusPane::Thinking => FocusPane::Generation,
-            FocusPane::Generation => FocusPane::Prompt,
-            FocusPane::Prompt => FocusPane::Inspector,
-            FocusPane::Inspector => FocusPane::Sidebar,
+    /// Rename a file in place (on disk too when it exists there)
+    pub fn rename_file(&mut self, path: &std::path::Path, new_name: &str) -> anyhow::Result<PathBuf> {
+        let new_name = new_name.trim();
+        if new_name.is_empty() || new_name.contains(std::path::MAIN_SEPARATOR) {
+            anyhow::bail!("Invalid file name: {:?}", new_name);
+        }
+        let new_path = path.with_file_name(new_name);
+        if new_path.exists() {
+            anyhow::bail!("{} already exists", new_path.display());
+        }
+        if path.exists() {
+            std::fs::rename(path, &new_path)?;
+        }
+
+        let old_id = path.to_string_lossy().to_string();
+        let new_id = new_path.to_string_lossy().to_string();
+        if let Some(node) = Self::find_node_recursive_mut(&mut self.file_tree, &old_id) {
+            node.id = new_id.clone();
+            node.name = new_name.to_string();
+            node.path = new_path.clone();
+        }
+
+        let mut selected = self.tree_state.borrow().selected().to_vec();
+        if selected.last() == Some(&old_id) {
+            *selected.last_mut().unwrap() = new_id;
+            self.tree_state.borrow_mut().select(selected);
+        }
+        if let Some(session) = self.session.as_mut().filter(|s| s.file_path == path) {
+            session.file_path = new_path.clone();
+        }
+        for attached in self.attachments.iter_mut().filter(|p| *p == path) {
+            *attached = new_path.clone();
+        }
+        Ok(new_path)
+    }
+
+    /// Delete a file from the tree (and from disk when it exists there)
+    pub fn remove_file(&mut self, path: &std::path::Path) -> anyhow::Result<()> {
+        fn remove(nodes: &mut Vec<FileNode>, id: &str) -> bool {
+            if let Some(pos) = nodes.iter().position(|n| n.id == id) {
+                nodes.remove(pos);
+                return true;
+            }
+            nodes.iter_mut().any(|n| remove(&mut n.children, id))
+        }
+
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+        remove(&mut self.file_tree, &path.to_string_lossy());
+        self.attachments.retain(|p| p != path);
+        if self.session.as_ref().is_some_and(|s| s.file_path == path) {
+            self.session = None;
+        }
+        Ok(())
+    }
+
+    /// Apply the `[ui]` config section (startup)
+    pub fn apply_ui_config(&mut self) {
+        let ui = self.config.ui.clone();
+        self.set_auto_scroll(ui.auto_scroll);
+        self.render_markdown = ui.render_markdown;
+        self.set_symbol_mode(ui.symbols);
+        self.set_color_mode(ui.colors);
+        self.vendor_cursor = ui.vendor_cursor;
+        self.scroll_lines = ui.scroll_lines.clamp(1, 10);
+        self.show_sidebar = ui.show_sidebar;
+        self.show_inspector = ui.show_inspector;
+    }
+
+    /// Current display preferences, for saving to the config file
+    pub fn ui_config(&self) -> config::UiConfig {
+        config::UiConfig {
+            auto_scroll: self.global_auto_scroll,
+            render_markdown: self.render_markdown,
+            symbols: self.config.ui.symbols,
+            colors: self.config.ui.colors,
+            vendor_cursor: self.vendor_cursor,
+            scroll_lines: self.scroll_lines,
+            show_sidebar: self.show_sidebar,
+            show_inspector: self.show_inspector,
+            density: self.config.ui.density,
+            bold_keywords: self.config.ui.bold_keywords,
+            dim_inactive: self.config.ui.dim_inactive,
+            window_title: self.config.ui.window_title,
+        }
+    }
+
+    pub fn set_auto_scroll(&mut self, enabled: bool) {
+        self.global_auto_scroll = enabled;
+        if let Some(session) = &mut self.session {
+            if enabled {
+                session.thinking.enable_auto_scroll();
+                session.generation.enable_auto_scroll();
+            }
+        }
+    }
+
+    pub fn set_symbol_mode(&mut self, mode: config::SymbolMode) {
+        self.config.ui.symbols = mode;
+        self.ascii_mode = match mode {
+            config::SymbolMode::Auto => crate::ui::symbols::detect_ascii_from_env(),
+            config::SymbolMode::Emoji => false,
+            config::SymbolMode::Ascii => true,
+        };
+    }
+
+    pub fn set_color_mode(&mut self, mode: config::ColorMode) {
+        use crate::ui::colors::ColorDepth;
+        self.config.ui.colors = mode;
+        self.color_depth = match mode {
+            config::ColorMode::Auto => crate::ui::colors::detect_from_env(),
+            config::ColorMode::TrueColor => ColorDepth::TrueColor,
+            config::ColorMode::Ansi256 => ColorDepth::Ansi256,
+            config::ColorMode::Ansi16 => ColorDepth::Ansi16,
         };
     }
+
+    /// Whether pane borders should show their Alt+N numbers
+    pub fn show_pane_hints(&self) -> bool {
+        self.alt_held
+            || self
+                .pane_hints_until
+                .is_some_and(|until| std::time::Instant::now() < until)
+    }
+
+    /// A prompt was sent and its response hasn't arrived yet
+    pub fn generation_in_flight(&self) -> bool {
+        self.conversation
+            .iter()
+            .any(|t| t.response.is_none() && t.queued.is_none())
+    }
+
+    /// Zen mode only takes over the screen while a generation is running
+    pub fn zen_active(&self) -> bool {
+        self.zen_mode && self.session.is_some() && self.generation_in_flight()
+    }
+
+    /// Whether `pane` is laid out this frame
+    pub fn pane_visible(&self, pane: FocusPane) -> bool {
+        match pane {
+            FocusPane::Sidebar => self.show_sidebar && !self.zen_active(),
+            FocusPane::Inspector => self.show_inspector && !self.zen_active(),
+            FocusPane::Thinking => !self.zen_active(),
+            FocusPane::Generation | FocusPane::Prompt => true,
+        }
+    }
+
+    /// Pane currently filling the screen; only while it still has focus,
+    /// and Thinking/Generation only once a session exists
+    pub fn maximized_pane(&self) -> Option<FocusPane> {
+        self.maximized.filter(|pane| {
+            *pane == self.focus
+                && (self.session.is_some() || !matches!(pane, FocusPane::Thinking | FocusPane::Generation))
+        })
+    }
+
+    /// Maximize the focused pane, or restore the layout if it already is
+    pub fn toggle_maximized(&mut self) {
+        self.maximized = if self.maximized_pane().is_some() || self.focus == FocusPane::Prompt {
+            None
+        } else {
+            Some(self.focus)
+        };
+    }
+
+    pub fn toggle_sidebar(&mut self) {
+        self.show_sidebar = !self.show_sidebar;
+        self.ensure_focus_visible();
+    }
+
+    pub fn toggle_inspector(&mut self) {
+        self.show_inspector = !self.show_inspector;
+        self.ensure_focus_visible();
+    }
+
+    /// Move focus off a pane that is no longer shown
+    pub fn ensure_focus_visible(&mut self) {
+        if !self.pane_visible(self.focus) {
+            self.cycle_focus();
+        }
+    }
+
+    /// Jump straight to `pane`, entering edit mode for the prompt.
+    /// A pane hidden by its toggle is shown again; zen mode keeps others hidden.
+    pub fn jump_to_pane(&mut self, pane: FocusPane) {
+        match pane {
+            FocusPane::Sidebar => self.show_sidebar = true,
+            FocusPane::Inspector => self.show_inspector = true,
+            _ => {}
+        }
+        if !self.pane_visible(pane) {
+            return;
+        }
+        self.maximized = None;
+        self.focus = pane;
+        self.input_mode = if pane == FocusPane::Prompt {
+            InputMode::Editing
+        } else {
+            InputMode::Normal
+        };
+        self.pane_hints_until = Some(std::time::Instant::now() + std::time::Duration::from_secs(1));
+    }
+
+    /// Next visible pane in Tab order
+    pub fn cycle_focus(&mut self) {
+        self.maximized = None;
+        loop {
+            self.focus = match self.focus {
+                FocusPane::Sidebar => FocusPane::Thinking,
+                FocusPane::Thinking => FocusPane::Generation,
+                FocusPane::Generation => FocusPane::Prompt,
+                FocusPane::Prompt => FocusPane::Inspector,
+                FocusPane::Inspector => FocusPane::Sidebar,
+            };
+            // Generation and Prompt are always visible, so this terminates
+            if self.pane_visible(self.focus) {
+                break;
+            }
+        }
+    }
     
     pub fn add_debug_log(&mut self, message: String) {
         let timestamp = chrono::Local::now().format("%H:%M:%S");
+        let message = self.redactor.redact(&message);
         self.debug_logs.push(format!("[{}] {}", timestamp, message));
         if self.debug_logs.len() > 100 {
             self.debug_logs.drain(0..10);
         }
     }
 
-    pub fn add_thinking(&mut self, line: String) {
-        self.thinking_log.push(line);
+    pub fn add_telemetry(&mut self, entry: TelemetryEntry) {
+        if let Some((queue, depth)) = &entry.queue_depth {
+            self.queue_depths.insert(queue.clone(), *depth);
+        }
+        self.telemetry_log.push(entry);
+        if self.telemetry_log.len() > MAX_TELEMETRY_ENTRIES {
+            self.telemetry_log.drain(0..50);
+        }
+    }
+
+    pub fn add_network_entry(&mut self, entry: NetworkEntry) {
+        self.transport.record(&entry);
+        self.network_log.push(entry);
+        if self.network_log.len() > MAX_NETWORK_ENTRIES {
+            self.network_log.drain(0..20);
+            self.network_view.selected = self.network_view.selected.saturating_sub(20);
+        }
+    }
+
+    /// Append one scrape's derived values, keeping `prometheus.history` points
+    pub fn record_series(&mut self, values: Vec<(String, Option<f64>)>) {
+        let history = self.config.prometheus.history.max(1);
+        for (label, value) in values {
+            let Some(value) = value else { continue };
+            let index = match self.metric_series.iter().position(|s| s.label == label) {
+                Some(index) => index,
+                None => {
+                    self.metric_series.push(MetricSeries {
+                        label,
+                        points: Default::default(),
+                    });
+                    self.metric_series.len() - 1
+                }
+            };
+            let series = &mut self.metric_series[index];
+            series.points.push_back(value);
+            while series.points.len() > history {
+                series.points.pop_front();
+            }
+        }
+    }
+
+    pub fn add_thinking(&mut self, mut entry: thinking::ThinkingEntry) {
+        entry.id = self.next_entry_id;
+        self.next_entry_id += 1;
+        self.thinking_log.push(entry);
         if self.thinking_log.len() > 1000 {
             self.thinking_log.drain(0..100);
         }
     }
 
+    /// Whether `prompt` is already waiting on a response from `model_id`
+    pub fn prompt_in_flight(&self, prompt: &str, model_id: &str) -> bool {
+        self.in_flight
+            .iter()
+            .chain(self.prompt_queue.waiting().iter().map(|w| &w.request))
+            .any(|r| r.prompt == prompt && r.model_id == model_id)
+    }
+
+    /// Forget the in-flight request with idempotency key `key`
+    pub fn finish_in_flight(&mut self, key: &str) {
+        self.in_flight.retain(|r| r.key != key);
+    }
+
     pub fn append_generation(&mut self, text: &str) {
         self.generated_code.push_str(text);
+        self.tee_generation(text);
     }
 }
\ No newline at end of file
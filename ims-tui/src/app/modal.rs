@@ -0,0 +1,253 @@
+//! Modals
+//!
+//! A centered popup asking one thing (yes or no, a line of text, or one
+//! of a list) that takes every key and click until it is answered or
+//! dismissed. Whoever opens one passes what to do with the answer, so a
+//! feature like renaming a file needs no popup or input routing of its own.
+
+use ratatui::{
+    layout::{Position, Rect},
+    text::Line,
+};
+
+use crate::app::{input::TextInput, overlay::Overlay, palette::Choice, AppState};
+use crate::ui::wrap::wrap_lines;
+
+/// What to do with the answer: the text typed, the value picked, or
+/// nothing for a confirmation
+pub type OnAnswer = Box<dyn FnOnce(&mut AppState, String) + Send>;
+
+/// Most choices listed at once; the list scrolls past these
+pub const VISIBLE_CHOICES: usize = 10;
+
+pub enum Ask {
+    Confirm,
+    Input(TextInput),
+    Select { choices: Vec<Choice>, selected: usize },
+}
+
+pub struct Modal {
+    pub title: String,
+    pub message: String,
+    pub ask: Ask,
+    /// Drawn in red, for answers that can't be taken back
+    pub destructive: bool,
+    on_answer: OnAnswer,
+}
+
+impl Modal {
+    /// "Are you sure?": `on_yes` runs only if the answer is yes
+    pub fn confirm(
+        title: impl Into<String>,
+        message: impl Into<String>,
+        on_yes: impl FnOnce(&mut AppState) + Send + 'static,
+    ) -> Self {
+        Self {
+            title: title.into(),
+            message: message.into(),
+            ask: Ask::Confirm,
+            destructive: false,
+            on_answer: Box::new(move |state, _| on_yes(state)),
+        }
+    }
+
+    /// A line of text, starting from `initial`
+    pub fn input(
+        title: impl Into<String>,
+        initial: impl Into<String>,
+        on_answer: impl FnOnce(&mut AppState, String) + Send + 'static,
+    ) -> Self {
+        let mut input = TextInput::default();
+        input.set(initial);
+        Self {
+            title: title.into(),
+            message: String::new(),
+            ask: Ask::Input(input),
+            destructive: false,
+            on_answer: Box::new(on_answer),
+        }
+    }
+
+    /// One of `choices`; the answer is its value
+    pub fn select(
+        title: impl Into<String>,
+        choices: Vec<Choice>,
+        on_answer: impl FnOnce(&mut AppState, String) + Send + 'static,
+    ) -> Self {
+        Self {
+            title: title.into(),
+            message: String::new(),
+            ask: Ask::Select { choices, selected: 0 },
+            destructive: false,
+            on_answer: Box::new(on_answer),
+        }
+    }
+
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = message.into();
+        self
+    }
+
+    pub fn destructive(mut self) -> Self {
+        self.destructive = true;
+        self
+    }
+
+    pub fn select_next(&mut self) {
+        if let Ask::Select { choices, selected } = &mut self.ask {
+            *selected = (*selected + 1).min(choices.len().saturating_sub(1));
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        if let Ask::Select { selected, .. } = &mut self.ask {
+            *selected = selected.saturating_sub(1);
+        }
+    }
+
+    /// The answer as it stands; `None` while there is none to give (empty
+    /// input, nothing to pick)
+    fn answer(&self) -> Option<String> {
+        match &self.ask {
+            Ask::Confirm => Some(String::new()),
+            Ask::Input(input) => (!input.as_str().trim().is_empty()).then(|| input.as_str().trim().to_string()),
+            Ask::Select { choices, selected } => choices.get(*selected).map(|c| c.value.clone()),
+        }
+    }
+
+    /// The message wrapped to `width` columns
+    pub fn message_rows(&self, width: u16) -> Vec<Line<'static>> {
+        let lines: Vec<Line> = self.message.lines().map(Line::from).collect();
+        wrap_lines(&lines, width)
+    }
+
+    /// Rows taken by the message and the blank row after it
+    fn message_height(&self, width: u16) -> usize {
+        match self.message_rows(width).len() {
+            0 => 0,
+            rows => rows + 1,
+        }
+    }
+
+    /// Rows between the borders, `width` wide: the message, what is asked,
+    /// the key hints
+    fn rows(&self, width: u16) -> usize {
+        let body = match &self.ask {
+            Ask::Confirm => 0,
+            Ask::Input(_) => 2,
+            Ask::Select { choices, .. } => choices.len().min(VISIBLE_CHOICES) + 1,
+        };
+        self.message_height(width) + body + 1
+    }
+
+    /// First row of the input or the choices, from the top border of a
+    /// popup `width` columns inside
+    pub fn body_offset(&self, width: u16) -> u16 {
+        1 + self.message_height(width) as u16
+    }
+
+    /// Popup rect, centered in `screen`
+    pub fn area(&self, screen: Rect) -> Rect {
+        let width = 56.min(screen.width);
+        let rows = self.rows(width.saturating_sub(2));
+        let height = u16::try_from(rows + 2).unwrap_or(u16::MAX).min(screen.height);
+        Rect {
+            x: screen.x + (screen.width - width) / 2,
+            y: screen.y + (screen.height - height) / 2,
+            width,
+            height,
+        }
+    }
+
+    /// First choice shown, keeping the selected one in view
+    pub fn scroll(&self) -> usize {
+        match &self.ask {
+            Ask::Select { selected, .. } => selected.saturating_sub(VISIBLE_CHOICES - 1),
+            _ => 0,
+        }
+    }
+
+    /// Choice whose row is at screen cell (`x`, `y`) inside `area`
+    pub fn choice_at(&self, area: Rect, x: u16, y: u16) -> Option<usize> {
+        let Ask::Select { choices, .. } = &self.ask else { return None };
+        if !area.contains(Position { x, y }) {
+            return None;
+        }
+        let row = y.checked_sub(area.y + self.body_offset(area.width.saturating_sub(2)))? as usize;
+        let index = self.scroll() + row;
+        (row < VISIBLE_CHOICES && index < choices.len()).then_some(index)
+    }
+}
+
+impl AppState {
+    /// Show `modal` on top of the overlay stack
+    pub fn open_modal(&mut self, modal: Modal) {
+        self.modal = Some(modal);
+        self.open_overlay(Overlay::Modal);
+    }
+
+    /// Close the modal, acting on its answer; does nothing while there is
+    /// no answer to give
+    pub fn answer_modal(&mut self) {
+        let Some(answer) = self.modal.as_ref().and_then(Modal::answer) else { return };
+        let Some(modal) = self.modal.take() else { return };
+        // The answer may open another modal
+        self.close_overlay(Overlay::Modal);
+        (modal.on_answer)(self, answer);
+    }
+
+    pub fn dismiss_modal(&mut self) {
+        self.close_overlay(Overlay::Modal);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_answers_reach_the_callback() {
+        let mut state = AppState::default();
+        state.open_modal(Modal::input("Rename", "", |state, name| state.command_input = name));
+        state.answer_modal();
+        assert!(state.modal.is_some(), "empty input isn't an answer");
+        if let Some(Modal { ask: Ask::Input(input), .. }) = &mut state.modal {
+            input.set(" main.rs ");
+        }
+        state.answer_modal();
+        assert!(state.modal.is_none());
+        assert_eq!(state.command_input, "main.rs");
+
+        let choice = |value: &str| Choice {
+            label: value.to_uppercase(),
+            value: value.to_string(),
+        };
+        let mut modal = Modal::select("Pick", vec![choice("a"), choice("b")], |state, value| state.command_input = value);
+        modal.select_next();
+        modal.select_next();
+        let area = modal.area(Rect::new(0, 0, 80, 24));
+        assert_eq!(modal.choice_at(area, area.x + 2, area.y + 1), Some(0));
+        assert_eq!(modal.choice_at(area, area.x + 2, area.y + 3), None);
+        state.open_modal(modal);
+        state.answer_modal();
+        assert_eq!(state.command_input, "b");
+
+        state.open_modal(Modal::confirm("Delete", "Delete it?", |state| state.command_input.clear()));
+        state.dismiss_modal();
+        assert_eq!(state.command_input, "b");
+    }
+
+    #[test]
+    fn test_long_messages_wrap() {
+        let path = "src/some/deeply/nested/module/with/a/long/name.rs";
+        let modal = Modal::select("Pick", vec![Choice { label: "A".into(), value: "a".into() }], |_, _| {})
+            .with_message(format!("Delete {}? This can't be undone.\nIt is open in another tab.", path));
+        let area = modal.area(Rect::new(0, 0, 80, 24));
+        let rows = modal.message_rows(area.width - 2).len() as u16;
+        assert!(rows >= 3, "the first line wraps");
+        // The choices start below every row of the message and a blank one
+        assert_eq!(modal.body_offset(area.width - 2), 1 + rows + 1);
+        assert_eq!(modal.choice_at(area, area.x + 2, area.y + rows + 2), Some(0));
+        assert_eq!(area.height, 2 + rows + 1 + 2 + 1);
+    }
+}
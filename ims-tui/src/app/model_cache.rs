@@ -0,0 +1,121 @@
+//! Model List Cache
+//!
+//! The registry listing is kept in `.ims/models.json` together with the
+//! ETag IMS Core sent for it, so the registry view and the model picker
+//! have models to show right away, offline included. Opening either one
+//! revalidates the first page with `If-None-Match`: a 304 keeps the cached
+//! list, anything else replaces it. `[registry] cache = false` turns this
+//! off.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::app::{
+    api::{ModelResponse, PageToken},
+    local, AppState,
+};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CachedModels {
+    /// Unix seconds the backend last confirmed the list
+    pub checked_at: i64,
+    pub etag: Option<String>,
+    pub models: Vec<ModelResponse>,
+    pub next_page: Option<PageToken>,
+    pub total: Option<u32>,
+}
+
+/// `.ims/models.json` under the current working directory
+pub fn cache_path() -> PathBuf {
+    crate::app::ims_dir().join("models.json")
+}
+
+/// The cached listing at `path`, if there is one
+pub fn load(path: &Path) -> Result<Option<CachedModels>> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => serde_json::from_str(&text)
+            .map(Some)
+            .with_context(|| format!("Corrupt model cache in {}", path.display())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    }
+}
+
+pub fn save(path: &Path, cached: &CachedModels) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    std::fs::write(path, serde_json::to_string(cached)?).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+impl AppState {
+    /// Show the cached listing until it has been revalidated
+    pub fn restore_model_cache(&mut self, path: &Path) {
+        match load(path) {
+            Ok(Some(cached)) => {
+                self.add_debug_log(format!("Restored {} cached model(s)", cached.models.len()));
+                for model in &cached.models {
+                    self.model_catalog.insert(model.model_id.clone(), model.clone());
+                }
+                let view = &mut self.model_registry;
+                view.models = cached.models;
+                view.next_page = cached.next_page;
+                view.total = cached.total;
+                view.etag = cached.etag;
+                view.checked_at = Some(cached.checked_at);
+                view.stale = true;
+            }
+            Ok(None) => {}
+            Err(e) => self.add_debug_log(format!("Ignoring model cache: {:#}", e)),
+        }
+    }
+
+    /// Write the listing loaded so far, without the local server's models
+    pub fn save_model_cache(&mut self) {
+        let view = &self.model_registry;
+        if !self.config.registry.cache || view.models.is_empty() {
+            return;
+        }
+        let cached = CachedModels {
+            checked_at: view.checked_at.unwrap_or_else(|| chrono::Utc::now().timestamp()),
+            etag: view.etag.clone(),
+            models: view.models.iter().filter(|m| m.vendor_id != local::VENDOR).cloned().collect(),
+            next_page: view.next_page.clone(),
+            total: view.total,
+        };
+        if let Err(e) = save(&cache_path(), &cached) {
+            self.add_debug_log(format!("Failed to save model cache: {:#}", e));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restore_marks_stale() {
+        let path = std::env::temp_dir().join(format!("ims-models-{}.json", uuid::Uuid::new_v4()));
+        let mut state = AppState::default();
+        state.restore_model_cache(&path);
+        assert!(state.model_registry.models.is_empty());
+
+        let cached = CachedModels {
+            checked_at: 1_700_000_000,
+            etag: Some("\"v7\"".to_string()),
+            models: vec![crate::app::local::model_entry("gpt-4o", 128_000)],
+            next_page: Some(PageToken::Offset(50)),
+            total: Some(120),
+        };
+        save(&path, &cached).unwrap();
+        state.restore_model_cache(&path);
+        let view = &state.model_registry;
+        assert_eq!(view.models.len(), 1);
+        assert_eq!(view.etag.as_deref(), Some("\"v7\""));
+        assert_eq!(view.next_page, Some(PageToken::Offset(50)));
+        assert!(view.stale && view.needs_fetch());
+        assert!(state.model_catalog.contains_key("gpt-4o"));
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -0,0 +1,231 @@
+//! Network Log
+//!
+//! Records every HTTP exchange made by the API client for the Network pane.
+//! Credentials are redacted before an entry leaves the client and bodies
+//! are truncated so a chatty backend can't balloon memory. Totals across
+//! every request (not just those still listed) show how well the
+//! connection pool and compression are doing.
+
+/// Maximum network entries kept in memory
+pub const MAX_NETWORK_ENTRIES: usize = 200;
+
+/// Bodies longer than this are cut off in the log
+pub const BODY_PREVIEW_LIMIT: usize = 4096;
+
+const REDACTED: &str = "[redacted]";
+
+/// One request/response pair
+#[derive(Clone, Debug)]
+pub struct NetworkEntry {
+    pub time: chrono::DateTime<chrono::Local>,
+    pub method: String,
+    pub url: String,
+    pub request_headers: Vec<(String, String)>,
+    pub request_body: Option<String>,
+    /// `None` when the request failed before a response arrived
+    pub status: Option<u16>,
+    pub duration_ms: u64,
+    pub response_body: Option<String>,
+    pub error: Option<String>,
+    /// HTTP version of the response ("HTTP/2.0")
+    pub version: Option<String>,
+    /// Request body size
+    pub bytes_sent: u64,
+    /// Response body size, after decompression
+    pub bytes_received: u64,
+    /// Connections the client had opened when this request finished
+    pub connections_opened: u64,
+}
+
+impl NetworkEntry {
+    pub fn is_error(&self) -> bool {
+        self.error.is_some() || self.status.is_some_and(|s| s >= 400)
+    }
+}
+
+/// Transport figures since startup
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TransportTotals {
+    pub requests: u64,
+    pub connections: u64,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+}
+
+impl TransportTotals {
+    pub fn record(&mut self, entry: &NetworkEntry) {
+        self.requests += 1;
+        // Entries can arrive out of order; the counter only grows
+        self.connections = self.connections.max(entry.connections_opened);
+        self.bytes_sent += entry.bytes_sent;
+        self.bytes_received += entry.bytes_received;
+    }
+
+    /// Requests sent over an already open connection
+    pub fn reused(&self) -> u64 {
+        self.requests.saturating_sub(self.connections)
+    }
+}
+
+/// `bytes` as "512 B", "4.2 KB" or "1.3 MB"
+pub fn format_bytes(bytes: u64) -> String {
+    match bytes {
+        0..=1023 => format!("{} B", bytes),
+        1024..=1_048_575 => format!("{:.1} KB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+/// Whether a header, query parameter or JSON field name holds a credential
+fn is_sensitive(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    ["key", "token", "secret", "password", "authorization"]
+        .iter()
+        .any(|needle| name.contains(needle))
+}
+
+pub fn redact_header(name: &str, value: &str) -> String {
+    if is_sensitive(name) {
+        REDACTED.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Mask sensitive query parameters (`?api_key=...`)
+pub fn redact_url(url: &str) -> String {
+    let Some((base, query)) = url.split_once('?') else {
+        return url.to_string();
+    };
+    let params: Vec<String> = query
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((name, _)) if is_sensitive(name) => format!("{}={}", name, REDACTED),
+            _ => pair.to_string(),
+        })
+        .collect();
+    format!("{}?{}", base, params.join("&"))
+}
+
+/// Mask sensitive fields in a JSON body; non-JSON bodies pass through
+pub fn redact_body(body: &str) -> String {
+    fn redact(value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (name, field) in map.iter_mut() {
+                    if is_sensitive(name) && !field.is_object() && !field.is_array() {
+                        *field = serde_json::Value::String(REDACTED.to_string());
+                    } else {
+                        redact(field);
+                    }
+                }
+            }
+            serde_json::Value::Array(items) => items.iter_mut().for_each(redact),
+            _ => {}
+        }
+    }
+
+    match serde_json::from_str::<serde_json::Value>(body) {
+        Ok(mut value) => {
+            redact(&mut value);
+            value.to_string()
+        }
+        Err(_) => body.to_string(),
+    }
+}
+
+/// Redact and cut a body down to `BODY_PREVIEW_LIMIT` characters
+pub fn preview_body(body: &str) -> String {
+    let body = redact_body(body);
+    match body.char_indices().nth(BODY_PREVIEW_LIMIT) {
+        Some((cut, _)) => format!("{}… ({} bytes total)", &body[..cut], body.len()),
+        None => body,
+    }
+}
+
+/// Selection state of the Network pane
+#[derive(Clone, Debug, Default)]
+pub struct NetworkView {
+    pub selected: usize,
+    /// Showing the detail view for `selected`
+    pub detail: bool,
+}
+
+impl NetworkView {
+    pub fn select_next(&mut self, len: usize) {
+        if len > 0 {
+            self.selected = (self.selected + 1).min(len - 1);
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transport_totals() {
+        let entry = |connections_opened| NetworkEntry {
+            time: chrono::Local::now(),
+            method: "GET".to_string(),
+            url: "http://h/health".to_string(),
+            request_headers: Vec::new(),
+            request_body: None,
+            status: Some(200),
+            duration_ms: 3,
+            response_body: None,
+            error: None,
+            version: Some("HTTP/1.1".to_string()),
+            bytes_sent: 100,
+            bytes_received: 2_000,
+            connections_opened,
+        };
+        let mut totals = TransportTotals::default();
+        for opened in [1, 1, 2, 1] {
+            totals.record(&entry(opened));
+        }
+        assert_eq!((totals.requests, totals.connections, totals.reused()), (4, 2, 2));
+        assert_eq!(format_bytes(totals.bytes_received), "7.8 KB");
+        assert_eq!(format_bytes(400), "400 B");
+    }
+
+    #[test]
+    fn test_redact_header() {
+        assert_eq!(redact_header("X-Admin-Key", "secret-value"), REDACTED);
+        assert_eq!(redact_header("Authorization", "Bearer abc"), REDACTED);
+        assert_eq!(redact_header("traceparent", "00-abc"), "00-abc");
+    }
+
+    #[test]
+    fn test_redact_url_query() {
+        assert_eq!(
+            redact_url("http://h/api?tier=pro&api_key=abc"),
+            "http://h/api?tier=pro&api_key=[redacted]"
+        );
+        assert_eq!(redact_url("http://h/health"), "http://h/health");
+    }
+
+    #[test]
+    fn test_redact_nested_json() {
+        let body = redact_body(r#"{"prompt":"hi","auth":{"api_key":"abc"},"tokens":{"input":1}}"#);
+        let value: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+        assert_eq!(value["auth"]["api_key"], REDACTED);
+        assert_eq!(value["prompt"], "hi");
+        // Objects named like credentials are descended into, not wiped
+        assert_eq!(value["tokens"]["input"], 1);
+    }
+
+    #[test]
+    fn test_preview_truncates() {
+        let body = "x".repeat(BODY_PREVIEW_LIMIT + 10);
+        let preview = preview_body(&body);
+
+        assert!(preview.starts_with(&"x".repeat(BODY_PREVIEW_LIMIT)));
+        assert!(preview.ends_with(&format!("({} bytes total)", BODY_PREVIEW_LIMIT + 10)));
+    }
+}
@@ -0,0 +1,118 @@
+//! Offline Prompt Queue
+//!
+//! Prompts submitted while IMS Core is unreachable are kept here and
+//! written to disk, so they survive a restart and are sent once the
+//! health check succeeds again.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// A prompt waiting for connectivity
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct QueuedPrompt {
+    pub id: uuid::Uuid,
+    /// What the user typed (shown in the conversation)
+    pub prompt: String,
+    /// Full request text including attachments and history at submit time
+    pub request_text: String,
+    pub model_id: String,
+    /// Unix timestamp (seconds)
+    pub queued_at: i64,
+}
+
+impl QueuedPrompt {
+    pub fn new(prompt: String, request_text: String, model_id: String) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4(),
+            prompt,
+            request_text,
+            model_id,
+            queued_at: chrono::Local::now().timestamp(),
+        }
+    }
+}
+
+/// Disk-backed FIFO of queued prompts
+#[derive(Clone, Debug, Default)]
+pub struct OfflineQueue {
+    pub items: Vec<QueuedPrompt>,
+    path: Option<PathBuf>,
+}
+
+impl OfflineQueue {
+    /// Load the queue persisted at `path` (missing file = empty queue)
+    pub fn load(path: &Path) -> Result<Self> {
+        let items = match std::fs::read_to_string(path) {
+            Ok(text) => serde_json::from_str(&text)
+                .with_context(|| format!("Corrupt offline queue in {}", path.display()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        Ok(Self {
+            items,
+            path: Some(path.to_path_buf()),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn push(&mut self, item: QueuedPrompt) -> Result<()> {
+        self.items.push(item);
+        self.save()
+    }
+
+    /// Remove and return everything queued, oldest first
+    pub fn take_all(&mut self) -> Result<Vec<QueuedPrompt>> {
+        let items = std::mem::take(&mut self.items);
+        self.save()?;
+        Ok(items)
+    }
+
+    fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else { return Ok(()) };
+        if self.items.is_empty() {
+            return match std::fs::remove_file(path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+                _ => Ok(()),
+            };
+        }
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(&self.items)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queue_persists_across_loads() {
+        let path = std::env::temp_dir()
+            .join(format!("ims-tui-queue-{}", uuid::Uuid::new_v4()))
+            .join("queue.json");
+
+        let mut queue = OfflineQueue::load(&path).unwrap();
+        assert!(queue.is_empty());
+        queue
+            .push(QueuedPrompt::new("hi".into(), "hi".into(), "gpt-4o".into()))
+            .unwrap();
+
+        let mut reloaded = OfflineQueue::load(&path).unwrap();
+        assert_eq!(reloaded.items, queue.items);
+
+        let drained = reloaded.take_all().unwrap();
+        assert_eq!(drained.len(), 1);
+        assert!(!path.exists());
+        std::fs::remove_dir(path.parent().unwrap()).ok();
+    }
+}
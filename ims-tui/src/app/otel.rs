@@ -0,0 +1,124 @@
+//! Trace Export
+//!
+//! Installs the `tracing` subscriber and, when built with the `otel` cargo
+//! feature and `[tracing] enabled = true`, exports spans to an OTLP
+//! collector. Outgoing API requests carry a W3C `traceparent` header so a
+//! user action can be followed from the TUI into IMS Core.
+
+use anyhow::Result;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+
+use crate::app::config::TracingConfig;
+
+/// Header carrying the trace context to the backend
+pub const TRACE_HEADER: &str = "traceparent";
+
+/// Flushes pending spans when dropped (keep alive until exit)
+pub struct TraceGuard {
+    #[cfg(feature = "otel")]
+    provider: Option<opentelemetry_sdk::trace::TracerProvider>,
+}
+
+impl Drop for TraceGuard {
+    fn drop(&mut self) {
+        #[cfg(feature = "otel")]
+        if let Some(provider) = self.provider.take() {
+            let _ = provider.shutdown();
+        }
+    }
+}
+
+/// Install the global subscriber (log output plus optional OTLP export)
+pub fn init(config: &TracingConfig) -> Result<TraceGuard> {
+    let fmt = tracing_subscriber::fmt::layer()
+        .with_target(false)
+        .with_thread_ids(false)
+        .with_file(true)
+        .with_line_number(true);
+    let registry = tracing_subscriber::registry()
+        .with(EnvFilter::new("ims_tui=debug"))
+        .with(fmt);
+
+    #[cfg(feature = "otel")]
+    {
+        use opentelemetry::trace::TracerProvider as _;
+        use opentelemetry_otlp::WithExportConfig;
+
+        if config.enabled {
+            let exporter = opentelemetry_otlp::SpanExporter::builder()
+                .with_tonic()
+                .with_endpoint(&config.otlp_endpoint)
+                .build()?;
+            let provider = opentelemetry_sdk::trace::TracerProvider::builder()
+                .with_batch_exporter(exporter, opentelemetry_sdk::runtime::Tokio)
+                .with_resource(opentelemetry_sdk::Resource::new([opentelemetry::KeyValue::new(
+                    "service.name",
+                    config.service_name.clone(),
+                )]))
+                .build();
+            let tracer = provider.tracer("ims-tui");
+
+            registry.with(tracing_opentelemetry::layer().with_tracer(tracer)).init();
+            return Ok(TraceGuard {
+                provider: Some(provider),
+            });
+        }
+
+        registry.init();
+        Ok(TraceGuard { provider: None })
+    }
+
+    #[cfg(not(feature = "otel"))]
+    {
+        registry.init();
+        if config.enabled {
+            tracing::warn!("OTLP export unavailable: rebuild with --features otel");
+        }
+        Ok(TraceGuard {})
+    }
+}
+
+/// `traceparent` value for the current span
+///
+/// Uses the exported span context when one is active; otherwise a fresh
+/// trace id is minted so the backend still receives a correlatable header.
+pub fn traceparent() -> String {
+    #[cfg(feature = "otel")]
+    {
+        use opentelemetry::trace::TraceContextExt;
+        use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+        let context = tracing::Span::current().context();
+        let span = context.span();
+        let span_context = span.span_context();
+        if span_context.is_valid() {
+            return format!(
+                "00-{}-{}-{:02x}",
+                span_context.trace_id(),
+                span_context.span_id(),
+                span_context.trace_flags().to_u8()
+            );
+        }
+    }
+
+    let trace_id = uuid::Uuid::new_v4().simple().to_string();
+    let span_id = &uuid::Uuid::new_v4().simple().to_string()[..16];
+    format!("00-{}-{}-01", trace_id, span_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_traceparent_format() {
+        let header = traceparent();
+        let parts: Vec<&str> = header.split('-').collect();
+
+        assert_eq!(parts.len(), 4);
+        assert_eq!(parts[0], "00");
+        assert_eq!(parts[1].len(), 32);
+        assert_eq!(parts[2].len(), 16);
+        assert!(parts[1].chars().all(|c| c.is_ascii_hexdigit()));
+    }
+}
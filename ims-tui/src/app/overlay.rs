@@ -0,0 +1,209 @@
+//! Overlay Stack
+//!
+//! Views opened over the workspace (settings, the command palette, help,
+//! history, ...) are kept on a stack: drawn bottom to top, with keys going
+//! to the top one only and Esc closing it. Opening Health from Settings
+//! puts it on top, and closing it goes back to Settings. Modals, context
+//! menus, breadcrumb dropdowns and the prompts asking for a key, a comment
+//! or a decision go on the stack too, their contents kept in `AppState`;
+//! mouse clicks reach only the top of the stack. Closing a prompt (with Esc
+//! or otherwise) declines it.
+
+use crate::app::{blocks::BlocksMode, AppState};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Overlay {
+    Settings,
+    CommandPalette,
+    ModelRegistry,
+    Telemetry,
+    Network,
+    Blocks,
+    Checkpoints,
+    History,
+    Reports,
+    Audit,
+    Pipeline,
+    Workflows,
+    Schedules,
+    Search,
+    Citations,
+    Watches,
+    Experiment,
+    Health,
+    Help,
+    /// `AppState::context_menu`
+    ContextMenu,
+    /// `AppState::file_dropdown`
+    FileDropdown,
+    /// `AppState::modal`
+    Modal,
+    /// `AppState::recovered`
+    Recovery,
+    /// `AppState::trust_prompt`
+    Trust,
+    /// `AppState::cost_confirm`
+    CostConfirm,
+    /// `AppState::api_key_prompt`
+    ApiKey,
+    /// `AppState::bypass_prompt`
+    Bypass,
+    /// `AppState::feedback_prompt`
+    Feedback,
+}
+
+/// Open overlays, the topmost last
+#[derive(Clone, Debug, Default)]
+pub struct Overlays {
+    stack: Vec<Overlay>,
+}
+
+impl Overlays {
+    /// The overlay taking keys
+    pub fn top(&self) -> Option<Overlay> {
+        self.stack.last().copied()
+    }
+
+    pub fn is_open(&self, overlay: Overlay) -> bool {
+        self.stack.contains(&overlay)
+    }
+
+    /// Open overlays, bottom to top (the order they are drawn in)
+    pub fn iter(&self) -> impl Iterator<Item = Overlay> + '_ {
+        self.stack.iter().copied()
+    }
+
+    /// Put `overlay` on top, moving it there if it is already open
+    fn push(&mut self, overlay: Overlay) {
+        self.remove(overlay);
+        self.stack.push(overlay);
+    }
+
+    fn remove(&mut self, overlay: Overlay) -> bool {
+        let open = self.is_open(overlay);
+        self.stack.retain(|o| *o != overlay);
+        open
+    }
+}
+
+impl AppState {
+    pub fn open_overlay(&mut self, overlay: Overlay) {
+        self.overlays.push(overlay);
+    }
+
+    pub fn close_overlay(&mut self, overlay: Overlay) {
+        if !self.overlays.remove(overlay) {
+            return;
+        }
+        match overlay {
+            Overlay::CommandPalette => {
+                self.command_input.clear();
+                self.command_index = 0;
+                self.command_argument = None;
+            }
+            Overlay::ContextMenu => self.context_menu = None,
+            Overlay::FileDropdown => self.file_dropdown = None,
+            Overlay::Modal => self.modal = None,
+            Overlay::Recovery => self.discard_recovered(),
+            Overlay::Trust => self.distrust_workspace(),
+            Overlay::CostConfirm => self.decline_cost(),
+            Overlay::ApiKey => self.cancel_api_key_prompt(),
+            Overlay::Bypass => self.bypass_prompt = None,
+            Overlay::Feedback => self.feedback_prompt = None,
+            _ => {}
+        }
+    }
+
+    /// Open `overlay`, or close it if it is already open
+    pub fn toggle_overlay(&mut self, overlay: Overlay) {
+        if self.overlays.is_open(overlay) {
+            self.close_overlay(overlay);
+        } else {
+            self.open_overlay(overlay);
+        }
+    }
+
+    /// Whether Esc backs out of something inside `overlay` (a filter being
+    /// typed, a detail view) rather than closing it
+    pub fn overlay_takes_esc(&self, overlay: Overlay) -> bool {
+        match overlay {
+            Overlay::ModelRegistry => self.model_registry.filter_editing || self.model_registry.admin_menu.is_some(),
+            Overlay::Network => self.network_view.detail,
+            Overlay::Blocks => !matches!(self.blocks_view.mode, BlocksMode::List),
+            Overlay::Checkpoints => self.checkpoints_view.naming.is_some(),
+            Overlay::Workflows => self.workflows_view.params.is_some(),
+            Overlay::Help => !self.help_query.is_empty(),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_top_overlay_closes_first() {
+        let mut state = AppState::default();
+        state.open_overlay(Overlay::Settings);
+        state.open_overlay(Overlay::Health);
+        assert_eq!(state.overlays.top(), Some(Overlay::Health));
+
+        // Reopening one already open brings it back to the top
+        state.open_overlay(Overlay::Settings);
+        assert_eq!(state.overlays.iter().collect::<Vec<_>>(), [Overlay::Health, Overlay::Settings]);
+
+        state.close_overlay(Overlay::Settings);
+        assert_eq!(state.overlays.top(), Some(Overlay::Health));
+        state.toggle_overlay(Overlay::Health);
+        assert_eq!(state.overlays.top(), None);
+
+        state.open_overlay(Overlay::Help);
+        state.help_query.set("quit");
+        assert!(state.overlay_takes_esc(Overlay::Help));
+    }
+
+    #[test]
+    fn test_popups_stack_with_overlays() {
+        use crate::app::{context_menu::{ContextMenu, MenuTarget}, modal::Modal};
+
+        let mut state = AppState::default();
+        state.open_context_menu(ContextMenu::new(MenuTarget::Generation, 0, 0));
+        state.open_overlay(Overlay::Help);
+        assert_eq!(state.overlays.top(), Some(Overlay::Help));
+
+        state.open_modal(Modal::confirm("Delete File", "Sure?", |_| {}));
+        assert_eq!(state.overlays.top(), Some(Overlay::Modal));
+        state.dismiss_modal();
+        assert_eq!(state.overlays.top(), Some(Overlay::Help));
+
+        // Closing the menu's entry drops the menu with it
+        state.close_overlay(Overlay::ContextMenu);
+        assert!(state.context_menu.is_none());
+        assert_eq!(state.overlays.iter().collect::<Vec<_>>(), [Overlay::Help]);
+    }
+
+    #[test]
+    fn test_closing_a_prompt_declines_it() {
+        use crate::app::{recovery::RecoveredWork, CostConfirm, InputMode};
+
+        let mut state = AppState::default();
+        state.open_overlay(Overlay::Settings);
+        state.ask_cost_confirm(CostConfirm {
+            prompt: "Rewrite everything".to_string(),
+            estimate: 2.0,
+            threshold: 1.0,
+        });
+        state.offer_recovered(RecoveredWork::default());
+        assert_eq!(state.overlays.top(), Some(Overlay::Recovery));
+
+        state.close_overlay(Overlay::Recovery);
+        assert!(state.recovered.is_none());
+        // The declined prompt goes back to be edited
+        state.close_overlay(Overlay::CostConfirm);
+        assert!(state.cost_confirm.is_none());
+        assert_eq!(state.input_buffer.as_str(), "Rewrite everything");
+        assert_eq!(state.input_mode, InputMode::Editing);
+        assert_eq!(state.overlays.top(), Some(Overlay::Settings));
+    }
+}
@@ -0,0 +1,229 @@
+//! Command Palette State
+//!
+//! Commands run from the palette are ranked by "frecency": how often each
+//! has been run, weighted by how recently, so the usual ones come first.
+//! The counts are kept in `.ims/palette.json` across runs, written once on
+//! exit rather than on every command run. A command that
+//! needs an argument ("Model: Switch To...") answers its first run with
+//! the choices; the palette lists them in place of the commands and runs
+//! the command again with the one picked.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::app::{overlay::Overlay, AppState};
+use crate::core::commands::{self, Command, Section};
+
+/// One value a command's argument can take
+#[derive(Clone, Debug, PartialEq)]
+pub struct Choice {
+    pub label: String,
+    pub value: String,
+}
+
+/// The palette asking for an argument of `command`
+#[derive(Clone, Debug)]
+pub struct ArgumentPick {
+    pub command: &'static str,
+    pub title: String,
+    pub choices: Vec<Choice>,
+}
+
+impl ArgumentPick {
+    /// Choices whose label contains `query` (case-insensitive)
+    pub fn matching(&self, query: &str) -> Vec<&Choice> {
+        let query = query.to_lowercase();
+        self.choices.iter().filter(|c| c.label.to_lowercase().contains(&query)).collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+struct Use {
+    count: u32,
+    /// Unix seconds
+    last_used: i64,
+}
+
+/// How often and how lately each command was run from the palette
+#[derive(Clone, Debug, Default)]
+pub struct Recents {
+    uses: HashMap<String, Use>,
+    /// Where the counts are saved; `None` keeps them in memory only
+    path: Option<PathBuf>,
+    /// Changed since loaded or last saved
+    dirty: bool,
+}
+
+impl Recents {
+    /// `.ims/palette.json` under the current working directory
+    pub fn default_path() -> PathBuf {
+        crate::app::ims_dir().join("palette.json")
+    }
+
+    /// The counts saved at `path`, saved back there as they change
+    pub fn load(path: &Path) -> Result<Self> {
+        let uses = match std::fs::read_to_string(path) {
+            Ok(text) => serde_json::from_str(&text)
+                .with_context(|| format!("Corrupt palette history in {}", path.display()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        Ok(Self {
+            uses,
+            path: Some(path.to_path_buf()),
+            dirty: false,
+        })
+    }
+
+    /// Write the counts back if a command has run since they were loaded
+    pub fn save(&mut self) -> Result<()> {
+        let Some(path) = self.path.as_ref().filter(|_| self.dirty) else { return Ok(()) };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        std::fs::write(path, serde_json::to_string(&self.uses)?)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        self.dirty = false;
+        Ok(())
+    }
+
+    /// Runs of `id`, each worth less the longer ago the latest was
+    pub fn score(&self, id: &str, now: i64) -> f64 {
+        let Some(used) = self.uses.get(id) else { return 0.0 };
+        let weight = match now - used.last_used {
+            age if age < 3_600 => 4.0,
+            age if age < 86_400 => 2.0,
+            age if age < 7 * 86_400 => 1.0,
+            _ => 0.25,
+        };
+        f64::from(used.count) * weight
+    }
+
+    fn record(&mut self, id: &str, now: i64) {
+        let used = self.uses.entry(id.to_string()).or_default();
+        used.count += 1;
+        used.last_used = now;
+        self.dirty = true;
+    }
+}
+
+impl AppState {
+    pub fn open_command_palette(&mut self) {
+        self.open_overlay(Overlay::CommandPalette);
+        self.command_input.clear();
+        self.command_index = 0;
+        self.command_argument = None;
+    }
+
+    pub fn close_command_palette(&mut self) {
+        self.close_overlay(Overlay::CommandPalette);
+    }
+
+    /// Commands listed for the current filter, by section
+    pub fn palette_sections(&self) -> Vec<(Section, Vec<&'static Command>)> {
+        let now = chrono::Utc::now().timestamp();
+        commands::sections(self, &self.command_input, &self.command_recents, now)
+    }
+
+    /// Commands listed for the current filter, in the order shown
+    pub fn palette_commands(&self) -> Vec<&'static Command> {
+        self.palette_sections().into_iter().flat_map(|(_, commands)| commands).collect()
+    }
+
+    /// Entries the palette lists: argument choices while picking one,
+    /// commands otherwise
+    pub fn palette_len(&self) -> usize {
+        match &self.command_argument {
+            Some(pick) => pick.matching(&self.command_input).len(),
+            None => self.palette_commands().len(),
+        }
+    }
+
+    pub fn restore_command_recents(&mut self, path: &Path) {
+        match Recents::load(path) {
+            Ok(recents) => self.command_recents = recents,
+            Err(e) => self.add_debug_log(format!("Ignoring palette history: {:#}", e)),
+        }
+    }
+
+    /// Count a run of command `id` toward its rank
+    pub fn record_command(&mut self, id: &str) {
+        self.command_recents.record(id, chrono::Utc::now().timestamp());
+    }
+
+    /// Reopen the palette on `choices` for `command`'s argument
+    pub fn pick_argument(&mut self, command: &'static str, title: String, choices: Vec<Choice>) {
+        self.open_command_palette();
+        self.command_argument = Some(ArgumentPick { command, title, choices });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recent_commands_rank_first() {
+        let now = 1_700_000_000;
+        let mut state = AppState::default();
+        let listed = |state: &AppState, query: &str| -> Vec<(Section, Vec<&str>)> {
+            commands::sections(state, query, &state.command_recents, now)
+                .into_iter()
+                .map(|(section, commands)| (section, commands.iter().map(|c| c.id).collect()))
+                .collect()
+        };
+        let view = Section::Category(commands::Category::View);
+        assert_eq!(
+            listed(&state, "toggle"),
+            [(view, vec!["view.toggle_sidebar", "view.toggle_inspector", "view.toggle_zen"])]
+        );
+
+        // Once last week counts for less than once just now
+        state.command_recents.record("view.toggle_inspector", now - 3 * 86_400);
+        state.command_recents.record("view.toggle_zen", now - 60);
+        assert_eq!(
+            listed(&state, "toggle"),
+            [(view, vec!["view.toggle_zen", "view.toggle_inspector", "view.toggle_sidebar"])]
+        );
+        assert_eq!(state.command_recents.score("view.toggle_zen", now), 4.0);
+        assert_eq!(state.command_recents.score("view.toggle_inspector", now), 1.0);
+
+        // With nothing typed they are also listed above the sections
+        let all = listed(&state, "");
+        assert_eq!(all[0], (Section::Recent, vec!["view.toggle_zen", "view.toggle_inspector"]));
+        assert_eq!(all[1].0, Section::Category(commands::Category::File));
+    }
+
+    #[test]
+    fn test_counts_are_written_only_when_changed() {
+        let path = std::env::temp_dir().join(format!("ims-palette-{}.json", uuid::Uuid::new_v4()));
+        let mut recents = Recents::load(&path).unwrap();
+        recents.save().unwrap();
+        assert!(!path.exists());
+
+        recents.record("view.toggle_zen", 1_700_000_000);
+        recents.save().unwrap();
+        let loaded = Recents::load(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(loaded.score("view.toggle_zen", 1_700_000_000), 4.0);
+    }
+
+    #[test]
+    fn test_argument_pick_filters_choices() {
+        let mut state = AppState::default();
+        let choice = |id: &str| Choice {
+            label: id.to_string(),
+            value: id.to_string(),
+        };
+        state.pick_argument("model.switch", "Model: Switch To...".to_string(), vec![choice("gpt-4o"), choice("claude-3")]);
+        assert!(state.overlays.is_open(Overlay::CommandPalette));
+        assert_eq!(state.palette_len(), 2);
+
+        state.command_input = "CLAUDE".to_string();
+        assert_eq!(state.palette_len(), 1);
+        state.close_command_palette();
+        assert!(state.command_argument.is_none());
+    }
+}
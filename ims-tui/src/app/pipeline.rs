@@ -0,0 +1,620 @@
+//! Refactor Pipeline
+//!
+//! Runs the `[pipeline]` steps (by default analyze → plan → generate →
+//! review) against each target: a marked file, or a module (the marked
+//! files sharing a directory). Steps run in order, each fed the previous
+//! step's output; targets run side by side up to `[batch] concurrency`.
+//! Every step's output is written to `.ims/pipelines/<run>/<target>/` and
+//! its status is shown in the Pipeline view. A failed step skips the rest
+//! of that target's steps. A paused run lets requests already sent finish
+//! but starts no further steps until it is resumed.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::Deserialize;
+
+use crate::app::{
+    api::{ExecuteRequest, ExecuteResponse, ImsApiClient},
+    config::PipelineStep,
+    history::{self, GenerationRecord},
+    metrics_store::RequestRecord,
+    overlay::Overlay,
+    thinking::{Level, ThinkingEntry},
+    AgentStatus,
+    AppState,
+};
+use crate::core::runtime::{request_mutation, EventSender};
+
+/// How marked files are grouped into targets
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Scope {
+    #[default]
+    File,
+    /// All marked files in the same directory together
+    Module,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum StepStatus {
+    Pending,
+    Running,
+    Done,
+    Failed(String),
+    Skipped,
+}
+
+impl StepStatus {
+    pub fn glyph(&self) -> &'static str {
+        match self {
+            StepStatus::Pending => "·",
+            StepStatus::Running => "▶",
+            StepStatus::Done => "✓",
+            StepStatus::Failed(_) => "✗",
+            StepStatus::Skipped => "–",
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        matches!(self, StepStatus::Done | StepStatus::Failed(_) | StepStatus::Skipped)
+    }
+}
+
+/// A file or module the steps run against
+#[derive(Clone, Debug, PartialEq)]
+pub struct Target {
+    pub label: String,
+    pub files: Vec<PathBuf>,
+    /// Used for steps without their own model
+    pub model_id: String,
+}
+
+/// Where each target's final step output goes, besides its artifacts
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Output {
+    /// Save it to History
+    pub history: bool,
+    /// Also write it here; `{target}` and `{stem}` are filled in per target
+    pub path: Option<String>,
+}
+
+/// Pause switch shared by a run and its tasks
+#[derive(Clone, Debug)]
+pub struct Pause(Arc<tokio::sync::watch::Sender<bool>>);
+
+impl Default for Pause {
+    fn default() -> Self {
+        Self(Arc::new(tokio::sync::watch::channel(false).0))
+    }
+}
+
+impl Pause {
+    pub fn set(&self, paused: bool) {
+        self.0.send_replace(paused);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        *self.0.borrow()
+    }
+
+    /// Return once the run is not paused
+    async fn wait(&self) {
+        let mut paused = self.0.subscribe();
+        let _ = paused.wait_for(|paused| !paused).await;
+    }
+}
+
+/// Everything a run needs, captured when it is started
+#[derive(Clone, Debug)]
+pub struct PipelineJob {
+    /// "Pipeline", or the workflow's name
+    pub name: String,
+    pub dir: PathBuf,
+    pub steps: Vec<PipelineStep>,
+    pub targets: Vec<Target>,
+    pub concurrency: usize,
+    pub output: Output,
+    pub pause: Pause,
+}
+
+#[derive(Clone, Debug)]
+pub struct TargetProgress {
+    pub target: Target,
+    pub steps: Vec<StepStatus>,
+    /// Output file per step, once written
+    pub artifacts: Vec<Option<PathBuf>>,
+}
+
+impl TargetProgress {
+    pub fn is_running(&self) -> bool {
+        self.steps.iter().any(|s| !s.is_finished())
+    }
+
+    /// Status shown on the target's files in the Explorer
+    pub fn agent_status(&self, paused: bool) -> AgentStatus {
+        if self.steps.iter().any(|s| matches!(s, StepStatus::Failed(_))) {
+            AgentStatus::Error
+        } else if paused && self.is_running() {
+            AgentStatus::Paused
+        } else if self.steps.contains(&StepStatus::Running) {
+            AgentStatus::Working
+        } else {
+            AgentStatus::Idle
+        }
+    }
+}
+
+/// Progress of the current (or last) run
+#[derive(Clone, Debug)]
+pub struct PipelineRun {
+    pub name: String,
+    pub dir: PathBuf,
+    pub steps: Vec<String>,
+    pub targets: Vec<TargetProgress>,
+    pub output: Output,
+    pub pause: Pause,
+}
+
+impl PipelineRun {
+    pub fn new(job: &PipelineJob) -> Self {
+        Self {
+            name: job.name.clone(),
+            dir: job.dir.clone(),
+            steps: job.steps.iter().map(|s| s.name.clone()).collect(),
+            targets: job
+                .targets
+                .iter()
+                .map(|target| TargetProgress {
+                    target: target.clone(),
+                    steps: vec![StepStatus::Pending; job.steps.len()],
+                    artifacts: vec![None; job.steps.len()],
+                })
+                .collect(),
+            output: job.output.clone(),
+            pause: job.pause.clone(),
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.targets.iter().any(TargetProgress::is_running)
+    }
+
+    /// Targets whose steps all succeeded
+    pub fn completed(&self) -> usize {
+        self.targets
+            .iter()
+            .filter(|t| t.steps.iter().all(|s| *s == StepStatus::Done))
+            .count()
+    }
+}
+
+/// Pipeline overlay ("Pipeline: Show Progress")
+#[derive(Clone, Debug, Default)]
+pub struct PipelineView {
+    pub run: Option<PipelineRun>,
+    pub selected: usize,
+}
+
+/// `.ims/pipelines/<timestamp>` for a new run
+pub fn run_dir() -> PathBuf {
+    crate::app::ims_dir()
+        .join("pipelines")
+        .join(chrono::Local::now().format("%Y%m%d-%H%M%S").to_string())
+}
+
+/// Group `files` (with their models) into targets
+pub fn targets(files: &[(PathBuf, String)], scope: Scope) -> Vec<Target> {
+    match scope {
+        Scope::File => files
+            .iter()
+            .map(|(path, model_id)| Target {
+                label: path.display().to_string(),
+                files: vec![path.clone()],
+                model_id: model_id.clone(),
+            })
+            .collect(),
+        Scope::Module => {
+            let mut modules: BTreeMap<PathBuf, Target> = BTreeMap::new();
+            for (path, model_id) in files {
+                let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+                modules
+                    .entry(dir.clone())
+                    .or_insert_with(|| Target {
+                        label: if dir.as_os_str().is_empty() {
+                            ".".to_string()
+                        } else {
+                            format!("{}/", dir.display())
+                        },
+                        files: Vec::new(),
+                        model_id: model_id.clone(),
+                    })
+                    .files
+                    .push(path.clone());
+            }
+            modules.into_values().collect()
+        }
+    }
+}
+
+/// Replace `{name}` placeholders in `template` with their values
+pub fn render_template(template: &str, vars: &[(&str, &str)]) -> String {
+    let mut out = template.to_string();
+    for (name, value) in vars {
+        out = out.replace(&format!("{{{}}}", name), value);
+    }
+    out
+}
+
+/// File-system-safe name for a target's artifact directory
+pub fn slug(label: &str) -> String {
+    let slug: String = label
+        .trim_end_matches('/')
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' { c } else { '_' })
+        .collect();
+    if slug.is_empty() {
+        "root".to_string()
+    } else {
+        slug
+    }
+}
+
+/// File stem of a single-file target, or the directory name of a module
+fn target_stem(target: &Target) -> String {
+    let path = match target.files.as_slice() {
+        [file] => file.file_stem(),
+        files => files.first().and_then(|f| f.parent()).and_then(Path::file_name),
+    };
+    path.map(|p| p.to_string_lossy().into_owned()).unwrap_or_else(|| "root".to_string())
+}
+
+/// Run every target's steps, at most `job.concurrency` targets at a time
+pub async fn run(client: ImsApiClient, tx: EventSender, job: PipelineJob) {
+    let permits = Arc::new(tokio::sync::Semaphore::new(job.concurrency.max(1)));
+    let steps = Arc::new(job.steps);
+    for (t, target) in job.targets.into_iter().enumerate() {
+        let (client, tx, permits, steps) = (client.clone(), tx.clone(), permits.clone(), steps.clone());
+        let pause = job.pause.clone();
+        let dir = job.dir.join(format!("{:02}-{}", t + 1, slug(&target.label)));
+        tokio::spawn(async move {
+            let Ok(_permit) = permits.acquire().await else { return };
+            run_target(client, tx, t, target, &steps, dir, pause).await;
+        });
+    }
+}
+
+async fn run_target(
+    client: ImsApiClient,
+    tx: EventSender,
+    t: usize,
+    target: Target,
+    steps: &[PipelineStep],
+    dir: PathBuf,
+    pause: Pause,
+) {
+    let mut code = String::new();
+    for path in &target.files {
+        match tokio::fs::read_to_string(path).await {
+            Ok(content) => code.push_str(&crate::app::context::file_block(path, &content)),
+            Err(e) => {
+                let error = format!("{}: {}", path.display(), e);
+                request_mutation(&tx, move |s| s.pipeline_step_finished(t, 0, Err(error), None));
+                return;
+            }
+        }
+    }
+
+    let mut previous = String::new();
+    for (i, step) in steps.iter().enumerate() {
+        pause.wait().await;
+        request_mutation(&tx, move |s| s.pipeline_step_started(t, i));
+        let model_id = step.model.clone().unwrap_or_else(|| target.model_id.clone());
+        let prompt = render_template(
+            &step.prompt,
+            &[("target", &target.label), ("code", code.trim_end()), ("previous", &previous)],
+        );
+
+        let started = std::time::Instant::now();
+        let result = async {
+            let response = client
+                .execute_prompt(ExecuteRequest {
+                    prompt,
+                    model_id,
+                    max_tokens: Some(crate::app::api::MAX_TOKENS),
+                    temperature: 0.7,
+                    system_instruction: None,
+                    user_id: None,
+                    bypass_policies: false,
+                    idempotency_key: None,
+                    images: Vec::new(),
+                    audio: Vec::new(),
+                })
+                .await
+                .map_err(|e| e.to_string())?;
+            let artifact = dir.join(format!("{:02}-{}.md", i + 1, slug(&step.name)));
+            tokio::fs::create_dir_all(&dir)
+                .await
+                .map_err(|e| format!("{}: {}", dir.display(), e))?;
+            tokio::fs::write(&artifact, &response.content)
+                .await
+                .map_err(|e| format!("{}: {}", artifact.display(), e))?;
+            Ok((response, artifact))
+        }
+        .await;
+        let latency_ms = started.elapsed().as_secs_f64() * 1000.0;
+
+        let output = result.as_ref().ok().map(|(response, _)| response.content.clone());
+        request_mutation(&tx, move |s| s.pipeline_step_finished(t, i, result, Some(latency_ms)));
+        match output {
+            Some(output) => previous = output,
+            None => return,
+        }
+    }
+}
+
+impl AppState {
+    /// A run over the marked files (or the selected file when none are
+    /// marked), grouped by `scope`
+    pub fn pipeline_job(&self, scope: Scope) -> Option<PipelineJob> {
+        let mut files = self.batch_files();
+        if files.is_empty() {
+            let node = self.get_selected_node().filter(|n| !n.is_dir)?;
+            files.push((node.path.clone(), node.model.clone()));
+        }
+        Some(PipelineJob {
+            name: "Pipeline".to_string(),
+            dir: run_dir(),
+            steps: self.config.pipeline.steps.clone(),
+            targets: targets(&files, scope),
+            concurrency: self.config.batch.concurrency.max(1),
+            output: Output::default(),
+            pause: Pause::default(),
+        })
+    }
+
+    pub fn start_pipeline(&mut self, job: &PipelineJob) {
+        let steps: Vec<&str> = job.steps.iter().map(|s| s.name.as_str()).collect();
+        self.add_thinking(ThinkingEntry::system(format!(
+            "{} ({}) over {} target(s); artifacts in {}",
+            job.name,
+            steps.join(" → "),
+            job.targets.len(),
+            job.dir.display()
+        )));
+        self.pipeline_view = PipelineView {
+            run: Some(PipelineRun::new(job)),
+            selected: 0,
+        };
+        self.open_overlay(Overlay::Pipeline);
+    }
+
+    pub fn pipeline_step_started(&mut self, target: usize, step: usize) {
+        let run = self.pipeline_view.run.as_mut();
+        if let Some(status) = run.and_then(|r| r.targets.get_mut(target)).and_then(|t| t.steps.get_mut(step)) {
+            *status = StepStatus::Running;
+        }
+        self.sync_agent_status();
+    }
+
+    /// Hold or release the running pipeline's remaining steps
+    pub fn set_pipeline_paused(&mut self, paused: bool) {
+        let Some(run) = self.pipeline_view.run.as_ref().filter(|r| r.is_running()) else {
+            self.add_debug_log("No pipeline is running".to_string());
+            return;
+        };
+        if run.pause.is_paused() == paused {
+            return;
+        }
+        run.pause.set(paused);
+        let message = if paused {
+            format!("{} paused: running requests will finish, later steps wait", run.name)
+        } else {
+            format!("{} resumed", run.name)
+        };
+        self.add_thinking(ThinkingEntry::system(message));
+        self.sync_agent_status();
+    }
+
+    /// Mirror each target's progress onto its files in the Explorer
+    fn sync_agent_status(&mut self) {
+        let Some(run) = self.pipeline_view.run.as_ref() else { return };
+        let paused = run.pause.is_paused();
+        let statuses: Vec<(PathBuf, AgentStatus)> = run
+            .targets
+            .iter()
+            .flat_map(|t| {
+                let status = t.agent_status(paused);
+                t.target.files.iter().map(move |f| (f.clone(), status))
+            })
+            .collect();
+        for (path, status) in statuses {
+            if let Some(node) = Self::find_node_recursive_mut(&mut self.file_tree, &path.to_string_lossy()) {
+                node.status = status;
+            }
+        }
+    }
+
+    pub fn pipeline_step_finished(
+        &mut self,
+        target: usize,
+        step: usize,
+        result: Result<(ExecuteResponse, PathBuf), String>,
+        // `None` when no request was sent (unreadable files)
+        latency_ms: Option<f64>,
+    ) {
+        let Some(run) = self.pipeline_view.run.as_mut() else { return };
+        let step_name = run.steps.get(step).cloned().unwrap_or_default();
+        let Some(progress) = run.targets.get_mut(target) else { return };
+        let label = progress.target.label.clone();
+
+        let last = step + 1 == progress.steps.len();
+        let (name, output) = (run.name.clone(), run.output.clone());
+        let record = match result {
+            Ok((response, artifact)) => {
+                progress.steps[step] = StepStatus::Done;
+                progress.artifacts[step] = Some(artifact);
+                let stem = target_stem(&progress.target);
+                if last {
+                    self.deliver_output(&output, &name, &label, &stem, &response);
+                }
+                Some(RequestRecord::from_response(&response))
+            }
+            Err(error) => {
+                progress.steps[step] = StepStatus::Failed(error.clone());
+                for later in progress.steps.iter_mut().skip(step + 1) {
+                    *later = StepStatus::Skipped;
+                }
+                let model_id = progress.target.model_id.clone();
+                self.add_thinking(
+                    ThinkingEntry::system(format!("✗ Pipeline {} failed at {}: {}", label, step_name, error))
+                        .with_level(Level::Error),
+                );
+                latency_ms.map(|latency_ms| RequestRecord::failed(model_id, latency_ms, error))
+            }
+        };
+        if let Some(record) = record {
+            self.record_request(record);
+        }
+        self.sync_agent_status();
+
+        if let Some(run) = self.pipeline_view.run.as_ref().filter(|r| !r.is_running()) {
+            let (completed, total) = (run.completed(), run.targets.len());
+            let summary = format!(
+                "{} finished: {} of {} target(s) completed, artifacts in {}",
+                run.name,
+                completed,
+                total,
+                run.dir.display()
+            );
+            self.add_debug_log(summary.clone());
+            self.add_thinking(ThinkingEntry::system(summary));
+            self.scheduled_pipeline_finished(completed, total);
+        }
+    }
+
+    /// Save or write a target's final output as `[output]` asks
+    fn deliver_output(&mut self, output: &Output, name: &str, label: &str, stem: &str, response: &ExecuteResponse) {
+        if output.history {
+            let generation = GenerationRecord::from_response(&format!("[{}] {}", label, name), response);
+            if let Err(e) = history::save(&history::generations_dir(), &generation) {
+                self.add_debug_log(format!("Failed to save generation: {:#}", e));
+            }
+        }
+        if let Some(template) = &output.path {
+            let path = PathBuf::from(render_template(template, &[("target", label.trim_end_matches('/')), ("stem", stem)]));
+            let (label, content) = (label.to_string(), response.content.clone());
+            self.write_in_workspace(&path, move |state, path| {
+                let written = path
+                    .parent()
+                    .map_or(Ok(()), std::fs::create_dir_all)
+                    .and_then(|_| std::fs::write(&path, content));
+                match written {
+                    Ok(()) => state.add_debug_log(format!("Wrote {} output to {}", label, path.display())),
+                    Err(e) => state.add_thinking(
+                        ThinkingEntry::system(format!("✗ Could not write {}: {}", path.display(), e))
+                            .with_level(Level::Error),
+                    ),
+                }
+            });
+        }
+    }
+
+    /// Show the Pipeline view, if there has been a run this session
+    pub fn open_pipeline(&mut self) {
+        if self.pipeline_view.run.is_some() {
+            self.open_overlay(Overlay::Pipeline);
+        } else {
+            self.add_debug_log("No pipeline has run yet".to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn files() -> Vec<(PathBuf, String)> {
+        vec![
+            (PathBuf::from("src/app/a.rs"), "gpt-4o".to_string()),
+            (PathBuf::from("src/main.rs"), "gemini-pro".to_string()),
+            (PathBuf::from("src/app/b.rs"), "gpt-4o".to_string()),
+        ]
+    }
+
+    #[test]
+    fn test_targets_per_file_and_module() {
+        assert_eq!(targets(&files(), Scope::File).len(), 3);
+
+        let modules = targets(&files(), Scope::Module);
+        assert_eq!(modules.len(), 2);
+        assert_eq!(modules[0].label, "src/");
+        assert_eq!(modules[0].model_id, "gemini-pro");
+        assert_eq!(modules[1].label, "src/app/");
+        assert_eq!(modules[1].files, vec![PathBuf::from("src/app/a.rs"), PathBuf::from("src/app/b.rs")]);
+        assert_eq!(slug(&modules[1].label), "src_app");
+    }
+
+    #[test]
+    fn test_render_template() {
+        let prompt = render_template(
+            "Plan for {target}:\n{previous}\n{unknown}",
+            &[("target", "src/a.rs"), ("previous", "analysis")],
+        );
+        assert_eq!(prompt, "Plan for src/a.rs:\nanalysis\n{unknown}");
+    }
+
+    #[test]
+    fn test_failed_step_skips_the_rest() {
+        let mut state = AppState::default();
+        let job = PipelineJob {
+            name: "Pipeline".to_string(),
+            dir: PathBuf::from("/tmp/run"),
+            steps: crate::app::config::PipelineConfig::default().steps,
+            targets: targets(&files()[..1], Scope::File),
+            concurrency: 1,
+            output: Output::default(),
+            pause: Pause::default(),
+        };
+        state.start_pipeline(&job);
+        state.pipeline_step_started(0, 0);
+        state.pipeline_step_finished(0, 0, Err("unreadable".to_string()), None);
+
+        let run = state.pipeline_view.run.as_ref().unwrap();
+        assert_eq!(run.steps, vec!["analyze", "plan", "generate", "review"]);
+        assert_eq!(run.targets[0].steps[0], StepStatus::Failed("unreadable".to_string()));
+        assert!(run.targets[0].steps[1..].iter().all(|s| *s == StepStatus::Skipped));
+        assert!(!run.is_running());
+        assert_eq!(run.completed(), 0);
+    }
+
+    #[test]
+    fn test_pause_is_reflected_on_files() {
+        let mut state = AppState {
+            file_tree: vec![crate::app::FileNode::new_file(PathBuf::from("src/app/a.rs"))],
+            ..Default::default()
+        };
+        let job = PipelineJob {
+            name: "Pipeline".to_string(),
+            dir: PathBuf::from("/tmp/run"),
+            steps: crate::app::config::PipelineConfig::default().steps,
+            targets: targets(&files()[..1], Scope::File),
+            concurrency: 1,
+            output: Output::default(),
+            pause: Pause::default(),
+        };
+        state.start_pipeline(&job);
+        state.pipeline_step_started(0, 0);
+        assert_eq!(state.file_tree[0].status, AgentStatus::Working);
+
+        state.set_pipeline_paused(true);
+        assert!(job.pause.is_paused());
+        assert_eq!(state.file_tree[0].status, AgentStatus::Paused);
+
+        state.set_pipeline_paused(false);
+        assert!(!job.pause.is_paused());
+        assert_eq!(state.file_tree[0].status, AgentStatus::Working);
+    }
+}
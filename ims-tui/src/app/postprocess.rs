@@ -0,0 +1,211 @@
+//! Response Post-processing
+//!
+//! `[postprocess]` steps run in order on each response, in the request's
+//! task rather than on the UI thread, before it is appended to the
+//! Generation pane: keeping only the fenced code, normalizing line
+//! endings, piping it through a formatter and adding a license header. A step that fails leaves the response as it was and is
+//! reported in the Thinking pane; the raw response is still what goes to
+//! history and the Blocks popup.
+
+use anyhow::{anyhow, bail, Context, Result};
+use std::io::{Read, Write};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use crate::app::{
+    blocks::extract_blocks,
+    config::PostStep,
+    thinking::{Level, ThinkingEntry},
+    AppState,
+};
+
+/// Run `steps` over `content`; the result, and why any steps were skipped
+pub fn apply(steps: &[PostStep], content: &str) -> (String, Vec<String>) {
+    let mut text = content.to_string();
+    let mut failures = Vec::new();
+    for step in steps {
+        match step {
+            PostStep::StripFences => text = strip_fences(&text),
+            PostStep::NormalizeLineEndings => text = text.replace("\r\n", "\n").replace('\r', "\n"),
+            PostStep::Format { command, timeout_secs } => {
+                match format(command, &text, Duration::from_secs(*timeout_secs)) {
+                    Ok(formatted) => text = formatted,
+                    Err(e) => failures.push(format!("{:#}", e)),
+                }
+            }
+            PostStep::LicenseHeader { text: header } => text = license_header(header, &text),
+        }
+    }
+    (text, failures)
+}
+
+/// The code of every fenced block, or `content` when it has none
+fn strip_fences(content: &str) -> String {
+    let blocks = extract_blocks(content);
+    if blocks.is_empty() {
+        return content.to_string();
+    }
+    blocks.iter().map(|b| b.code.as_str()).collect::<Vec<_>>().join("\n")
+}
+
+fn license_header(header: &str, content: &str) -> String {
+    let header = header.trim_end();
+    if header.is_empty() || content.trim_start().starts_with(header) {
+        return content.to_string();
+    }
+    format!("{}\n{}", header, content)
+}
+
+/// What `command` prints for `input` on stdin, if it succeeds in time
+fn format(command: &[String], input: &str, timeout: Duration) -> Result<String> {
+    let Some((program, args)) = command.split_first() else {
+        bail!("Formatter has no command");
+    };
+    let mut cmd = Command::new(program);
+    cmd.args(args).stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped());
+    // Its own process group, so a timeout stops anything it started too
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut cmd, 0);
+    let mut child = cmd.spawn().with_context(|| format!("Failed to run formatter {}", program))?;
+
+    // Written and read on their own threads so a full pipe can't stall it
+    let mut stdin = child.stdin.take().context("Formatter stdin is not piped")?;
+    let input = input.to_string();
+    let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+    let mut stdout = child.stdout.take().context("Formatter stdout is not piped")?;
+    let (output_tx, output) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut out = String::new();
+        let _ = output_tx.send(stdout.read_to_string(&mut out).map(|_| out));
+    });
+
+    let timed_out = || anyhow!("Formatter {} timed out after {}s", program, timeout.as_secs());
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            kill_group(&mut child);
+            return Err(timed_out());
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    };
+    let _ = writer.join();
+    // Something it started may still hold stdout open after it exits
+    let output = match output.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+        Ok(output) => output.context("Failed to read formatter output")?,
+        Err(_) => {
+            kill_group(&mut child);
+            return Err(timed_out());
+        }
+    };
+    if !status.success() {
+        let mut stderr = String::new();
+        if let Some(mut pipe) = child.stderr.take() {
+            let _ = pipe.read_to_string(&mut stderr);
+        }
+        bail!("Formatter {} failed ({}): {}", program, status, stderr.trim());
+    }
+    Ok(output)
+}
+
+/// Stop the formatter and everything in its process group
+fn kill_group(child: &mut Child) {
+    #[cfg(unix)]
+    if let Ok(pid) = libc::pid_t::try_from(child.id()) {
+        // SAFETY: signals only the group the formatter was spawned into
+        unsafe {
+            libc::kill(-pid, libc::SIGKILL);
+        }
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// Run `steps` over `content` off the async runtime: a formatter may take
+/// up to its timeout
+pub async fn apply_blocking(steps: Vec<PostStep>, content: String) -> (String, Vec<String>) {
+    if steps.is_empty() {
+        return (content, Vec::new());
+    }
+    let fallback = content.clone();
+    tokio::task::spawn_blocking(move || apply(&steps, &content))
+        .await
+        .unwrap_or_else(|e| (fallback, vec![format!("Post-processing stopped: {}", e)]))
+}
+
+impl AppState {
+    /// Report the `[postprocess]` steps that failed on a response
+    pub fn postprocess_failed(&mut self, failures: Vec<String>) {
+        for failure in failures {
+            self.add_thinking(
+                ThinkingEntry::system(format!("⚠ Post-processing step skipped: {}", failure)).with_level(Level::Warning),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sh(script: &str) -> PostStep {
+        PostStep::Format {
+            command: vec!["sh".to_string(), "-c".to_string(), script.to_string()],
+            timeout_secs: 1,
+        }
+    }
+
+    #[test]
+    fn test_steps_run_in_order() {
+        let response = "Here you go:\r\n```rust\r\nfn main() {}\r\n```\r\nHope it helps.";
+        let steps = [
+            PostStep::NormalizeLineEndings,
+            PostStep::StripFences,
+            sh("tr a-z A-Z"),
+            PostStep::LicenseHeader {
+                text: "// SPDX-License-Identifier: MIT\n".to_string(),
+            },
+        ];
+        let (text, failures) = apply(&steps, response);
+        assert!(failures.is_empty(), "{:?}", failures);
+        assert_eq!(text, "// SPDX-License-Identifier: MIT\nFN MAIN() {}\n");
+
+        // A header already there isn't added again, nor is prose stripped
+        let (again, _) = apply(&steps[3..], &text);
+        assert_eq!(again, text);
+        assert_eq!(apply(&[PostStep::StripFences], "No code here.").0, "No code here.");
+    }
+
+    #[test]
+    fn test_failed_formatter_keeps_response() {
+        let steps = [sh("echo bad input >&2; exit 3"), sh("sleep 5"), PostStep::NormalizeLineEndings];
+        let (text, failures) = apply(&steps, "a\r\nb");
+        assert_eq!(text, "a\nb");
+        assert_eq!(failures.len(), 2);
+        assert!(failures[0].contains("bad input"), "{}", failures[0]);
+        assert_eq!(failures[1], "Formatter sh timed out after 1s");
+
+        let missing = PostStep::Format {
+            command: vec!["/nonexistent/formatter".to_string()],
+            timeout_secs: 1,
+        };
+        let (text, failures) = apply(&[missing], "fn x() {}");
+        assert_eq!(text, "fn x() {}");
+        let mut state = AppState::default();
+        state.postprocess_failed(failures);
+        assert_eq!(state.thinking_log.last().unwrap().level, Level::Warning);
+    }
+
+    #[test]
+    fn test_timeout_covers_what_the_formatter_started() {
+        // The background sleep keeps stdout open after sh exits
+        let started = Instant::now();
+        let (text, failures) = apply(&[sh("sleep 5 & echo done")], "x");
+        assert_eq!(text, "x");
+        assert_eq!(failures, ["Formatter sh timed out after 1s"]);
+        assert!(started.elapsed() < Duration::from_secs(3));
+    }
+}
@@ -0,0 +1,128 @@
+//! Render Profiler
+//!
+//! Frame timing, event-loop lag and buffer sizes for the F12 overlay.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::app::AppState;
+use crate::core::bus::OverflowStats;
+
+/// Window over which FPS is computed
+const FPS_WINDOW: Duration = Duration::from_secs(1);
+
+/// Samples kept for the render-time sparkline
+pub const RENDER_HISTORY: usize = 120;
+
+#[derive(Clone, Debug, Default)]
+pub struct Profiler {
+    /// Frame start times within the last `FPS_WINDOW`
+    frames: VecDeque<Instant>,
+    /// Recent render durations in microseconds, oldest first
+    pub render_history: VecDeque<u64>,
+    pub last_render: Duration,
+    pub max_render: Duration,
+    /// How late the last tick fired relative to the tick rate
+    pub loop_lag: Duration,
+    pub max_loop_lag: Duration,
+    /// Messages waiting when the loop last drained each channel
+    pub api_backlog: usize,
+    pub core_backlog: usize,
+    /// Events coalesced or dropped because a channel was full
+    pub api_overflow: OverflowStats,
+    pub core_overflow: OverflowStats,
+}
+
+impl Profiler {
+    pub fn record_frame(&mut self, started: Instant, render: Duration) {
+        self.frames.push_back(started);
+        while self
+            .frames
+            .front()
+            .is_some_and(|t| started.duration_since(*t) > FPS_WINDOW)
+        {
+            self.frames.pop_front();
+        }
+
+        self.last_render = render;
+        self.max_render = self.max_render.max(render);
+        self.render_history.push_back(render.as_micros() as u64);
+        if self.render_history.len() > RENDER_HISTORY {
+            self.render_history.pop_front();
+        }
+    }
+
+    pub fn record_lag(&mut self, lag: Duration) {
+        self.loop_lag = lag;
+        self.max_loop_lag = self.max_loop_lag.max(lag);
+    }
+
+    pub fn fps(&self) -> usize {
+        self.frames.len()
+    }
+}
+
+/// `(name, items, approx. heap bytes)` for the largest state buffers
+pub fn buffer_sizes(state: &AppState) -> Vec<(&'static str, usize, usize)> {
+    let strings = |v: &[String]| v.iter().map(String::capacity).sum::<usize>();
+
+    vec![
+        (
+            "Thinking log",
+            state.thinking_log.len(),
+            state.thinking_log.iter().map(|e| e.text.capacity()).sum(),
+        ),
+        ("Generated code", state.generated_code.lines().count(), state.generated_code.capacity()),
+        ("Debug logs", state.debug_logs.len(), strings(&state.debug_logs)),
+        (
+            "Conversation",
+            state.conversation.len(),
+            state
+                .conversation
+                .iter()
+                .map(|t| t.prompt.capacity() + t.response.as_ref().map_or(0, String::capacity))
+                .sum(),
+        ),
+        (
+            "Telemetry",
+            state.telemetry_log.len(),
+            state
+                .telemetry_log
+                .iter()
+                .map(|e| e.summary.capacity() + e.event_type.capacity())
+                .sum(),
+        ),
+        (
+            "Network log",
+            state.network_log.len(),
+            state
+                .network_log
+                .iter()
+                .map(|e| {
+                    e.url.capacity()
+                        + e.request_body.as_ref().map_or(0, String::capacity)
+                        + e.response_body.as_ref().map_or(0, String::capacity)
+                })
+                .sum(),
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fps_counts_frames_in_window() {
+        let mut profiler = Profiler::default();
+        let start = Instant::now();
+
+        for i in 0..30 {
+            profiler.record_frame(start + Duration::from_millis(i * 50), Duration::from_millis(2));
+        }
+
+        // 50ms apart: only the last second's worth (21 frames incl. both ends) remain
+        assert_eq!(profiler.fps(), 21);
+        assert_eq!(profiler.max_render, Duration::from_millis(2));
+    }
+}
@@ -0,0 +1,300 @@
+//! Prometheus Scraping
+//!
+//! Parses the Prometheus text exposition format and derives the series
+//! configured under `[prometheus]` (rates, gauges, histogram quantiles)
+//! from consecutive scrapes of IMS Core's `/metrics` endpoint.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::time::Instant;
+
+use crate::app::config::{SeriesKind, SeriesMapping};
+
+/// A single sample line: `name{label="value",...} 1.5`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sample {
+    pub name: String,
+    pub labels: HashMap<String, String>,
+    pub value: f64,
+}
+
+/// Parse the label block between `{` and `}`
+fn parse_labels(block: &str) -> Option<HashMap<String, String>> {
+    let mut labels = HashMap::new();
+    let mut chars = block.chars().peekable();
+
+    loop {
+        while chars.peek().is_some_and(|c| *c == ',' || c.is_whitespace()) {
+            chars.next();
+        }
+        if chars.peek().is_none() {
+            return Some(labels);
+        }
+
+        let key: String = chars.by_ref().take_while(|c| *c != '=').collect();
+        if chars.next() != Some('"') {
+            return None;
+        }
+
+        let mut value = String::new();
+        loop {
+            match chars.next()? {
+                '\\' => match chars.next()? {
+                    'n' => value.push('\n'),
+                    other => value.push(other),
+                },
+                '"' => break,
+                c => value.push(c),
+            }
+        }
+        labels.insert(key.trim().to_string(), value);
+    }
+}
+
+/// Parse a text exposition document, skipping comments and malformed lines
+pub fn parse_exposition(text: &str) -> Vec<Sample> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let (name, labels, rest) = match line.find('{') {
+                Some(open) => {
+                    let close = line.rfind('}')?;
+                    (&line[..open], parse_labels(&line[open + 1..close])?, &line[close + 1..])
+                }
+                None => {
+                    let (name, rest) = line.split_once(char::is_whitespace)?;
+                    (name, HashMap::new(), rest)
+                }
+            };
+            let value = match rest.split_whitespace().next()? {
+                "+Inf" => f64::INFINITY,
+                "-Inf" => f64::NEG_INFINITY,
+                v => v.parse().ok()?,
+            };
+            Some(Sample {
+                name: name.trim().to_string(),
+                labels,
+                value,
+            })
+        })
+        .collect()
+}
+
+/// One scrape of the endpoint
+#[derive(Clone, Debug)]
+pub struct Scrape {
+    pub samples: Vec<Sample>,
+    pub at: Instant,
+}
+
+impl Scrape {
+    pub fn new(text: &str) -> Self {
+        Self {
+            samples: parse_exposition(text),
+            at: Instant::now(),
+        }
+    }
+
+    fn matching<'a>(&'a self, name: &'a str, labels: &'a HashMap<String, String>) -> impl Iterator<Item = &'a Sample> {
+        self.samples.iter().filter(move |s| {
+            s.name == name && labels.iter().all(|(k, v)| s.labels.get(k) == Some(v))
+        })
+    }
+
+    /// Sum of all series of `name` matching `labels`
+    fn sum(&self, name: &str, labels: &HashMap<String, String>) -> Option<f64> {
+        let mut found = false;
+        let total = self
+            .matching(name, labels)
+            .inspect(|_| found = true)
+            .map(|s| s.value)
+            .sum();
+        found.then_some(total)
+    }
+
+    /// Cumulative histogram buckets (`le` -> count) summed across series
+    fn buckets(&self, name: &str, labels: &HashMap<String, String>) -> BTreeMap<OrderedBound, f64> {
+        let bucket_name = format!("{}_bucket", name);
+        let mut buckets = BTreeMap::new();
+        for sample in self.matching(&bucket_name, labels) {
+            let Some(le) = sample.labels.get("le") else { continue };
+            let bound = match le.as_str() {
+                "+Inf" => f64::INFINITY,
+                v => match v.parse() {
+                    Ok(b) => b,
+                    Err(_) => continue,
+                },
+            };
+            *buckets.entry(OrderedBound(bound)).or_insert(0.0) += sample.value;
+        }
+        buckets
+    }
+}
+
+/// Bucket upper bound usable as an ordered map key
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct OrderedBound(f64);
+
+impl Eq for OrderedBound {}
+
+impl PartialOrd for OrderedBound {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OrderedBound {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+/// Prometheus-style `histogram_quantile` over cumulative buckets
+fn histogram_quantile(q: f64, buckets: &BTreeMap<OrderedBound, f64>) -> Option<f64> {
+    let total = *buckets.values().last()?;
+    if total <= 0.0 {
+        return None;
+    }
+    let rank = q * total;
+
+    let mut prev_bound = 0.0;
+    let mut prev_count = 0.0;
+    for (bound, count) in buckets {
+        if *count >= rank {
+            if bound.0.is_infinite() {
+                // Quantile falls in the overflow bucket: report the last finite bound
+                return Some(prev_bound);
+            }
+            let in_bucket = count - prev_count;
+            if in_bucket <= 0.0 {
+                return Some(bound.0);
+            }
+            return Some(prev_bound + (bound.0 - prev_bound) * (rank - prev_count) / in_bucket);
+        }
+        prev_bound = bound.0;
+        prev_count = *count;
+    }
+    None
+}
+
+/// Evaluate a configured series from the current (and previous) scrape
+pub fn evaluate(mapping: &SeriesMapping, prev: Option<&Scrape>, cur: &Scrape) -> Option<f64> {
+    let value = match mapping.kind {
+        SeriesKind::Gauge => cur.sum(&mapping.metric, &mapping.labels)?,
+        SeriesKind::Rate => {
+            let prev = prev?;
+            let elapsed = cur.at.duration_since(prev.at).as_secs_f64();
+            if elapsed <= 0.0 {
+                return None;
+            }
+            let now = cur.sum(&mapping.metric, &mapping.labels)?;
+            let before = prev.sum(&mapping.metric, &mapping.labels).unwrap_or(0.0);
+            // A counter reset (backend restart) restarts from zero
+            let delta = if now >= before { now - before } else { now };
+            delta / elapsed
+        }
+        SeriesKind::Quantile => {
+            let mut buckets = cur.buckets(&mapping.metric, &mapping.labels);
+            // Use only observations since the last scrape when possible
+            if let Some(prev) = prev {
+                let before = prev.buckets(&mapping.metric, &mapping.labels);
+                let windowed: BTreeMap<_, _> = buckets
+                    .iter()
+                    .map(|(b, c)| (*b, c - before.get(b).copied().unwrap_or(0.0)))
+                    .collect();
+                if windowed.values().all(|c| *c >= 0.0) && windowed.values().last().is_some_and(|c| *c > 0.0) {
+                    buckets = windowed;
+                }
+            }
+            histogram_quantile(mapping.quantile, &buckets)?
+        }
+    };
+    Some(value * mapping.scale)
+}
+
+/// Rolling history of one derived series for the Inspector charts
+#[derive(Clone, Debug)]
+pub struct MetricSeries {
+    pub label: String,
+    pub points: VecDeque<f64>,
+}
+
+impl MetricSeries {
+    pub fn latest(&self) -> Option<f64> {
+        self.points.back().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const TEXT: &str = r#"
+# HELP ims_requests_total Total number of requests
+# TYPE ims_requests_total counter
+ims_requests_total{service="gateway",model="gpt-4o",status="success"} 90
+ims_requests_total{service="gateway",model="gpt-4o",status="error"} 10
+ims_request_duration_seconds_bucket{model="gpt-4o",le="0.5"} 50
+ims_request_duration_seconds_bucket{model="gpt-4o",le="1.0"} 90
+ims_request_duration_seconds_bucket{model="gpt-4o",le="+Inf"} 100
+ims_queue_depth{queue_name="dlq"} 3
+"#;
+
+    fn mapping(kind: SeriesKind, metric: &str) -> SeriesMapping {
+        SeriesMapping {
+            label: "test".to_string(),
+            metric: metric.to_string(),
+            kind,
+            labels: HashMap::new(),
+            quantile: 0.99,
+            scale: 1.0,
+            unit: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_parse_exposition() {
+        let samples = parse_exposition(TEXT);
+        assert_eq!(samples.len(), 6);
+        assert_eq!(samples[1].labels.get("status").map(String::as_str), Some("error"));
+        assert_eq!(samples[4].value, 100.0);
+    }
+
+    #[test]
+    fn test_escaped_label_values() {
+        let samples = parse_exposition(r#"m{path="a\"b",x="1"} 2"#);
+        assert_eq!(samples[0].labels.get("path").map(String::as_str), Some("a\"b"));
+    }
+
+    #[test]
+    fn test_rate_with_label_filter() {
+        let prev = Scrape::new(TEXT);
+        let mut cur = Scrape::new(&TEXT.replace("} 10\n", "} 30\n"));
+        cur.at = prev.at + Duration::from_secs(10);
+
+        let mut m = mapping(SeriesKind::Rate, "ims_requests_total");
+        m.labels.insert("status".to_string(), "error".to_string());
+
+        assert_eq!(evaluate(&m, Some(&prev), &cur), Some(2.0));
+    }
+
+    #[test]
+    fn test_histogram_quantile() {
+        let cur = Scrape::new(TEXT);
+        let mut m = mapping(SeriesKind::Quantile, "ims_request_duration_seconds");
+
+        m.quantile = 0.5;
+        assert_eq!(evaluate(&m, None, &cur), Some(0.5));
+
+        // p99 lands in the +Inf bucket: clamp to the highest finite bound
+        m.quantile = 0.99;
+        assert_eq!(evaluate(&m, None, &cur), Some(1.0));
+    }
+
+    #[test]
+    fn test_gauge_sum() {
+        let cur = Scrape::new(TEXT);
+        assert_eq!(evaluate(&mapping(SeriesKind::Gauge, "ims_queue_depth"), None, &cur), Some(3.0));
+    }
+}
@@ -0,0 +1,173 @@
+//! Prompt Queue
+//!
+//! At most `[queue] max_in_flight` prompts run at once; the rest wait here
+//! and are sent as earlier ones finish, high priority first and in
+//! submission order within a priority. Alt+P while editing sets the
+//! priority of the next prompt. The Thinking pane lists what is waiting
+//! with each prompt's position and an ETA from recent latencies.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::app::{AppState, InFlightPrompt};
+
+/// Latencies averaged for the ETA
+const ETA_SAMPLES: usize = 10;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    High,
+    #[default]
+    Normal,
+    Low,
+}
+
+impl Priority {
+    pub fn label(self) -> &'static str {
+        match self {
+            Priority::High => "high",
+            Priority::Normal => "normal",
+            Priority::Low => "low",
+        }
+    }
+
+    /// high → normal → low → high
+    pub fn cycle(self) -> Self {
+        match self {
+            Priority::High => Priority::Normal,
+            Priority::Normal => Priority::Low,
+            Priority::Low => Priority::High,
+        }
+    }
+}
+
+/// A prompt waiting for a free slot
+#[derive(Clone, Debug)]
+pub struct WaitingPrompt {
+    pub request: InFlightPrompt,
+    /// Full request text, built when it was submitted
+    pub prompt_text: String,
+    pub priority: Priority,
+    /// Submission order, for FIFO within a priority
+    seq: u64,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct PromptQueue {
+    waiting: Vec<WaitingPrompt>,
+    next_seq: u64,
+}
+
+impl PromptQueue {
+    pub fn push(&mut self, request: InFlightPrompt, prompt_text: String, priority: Priority) {
+        self.waiting.push(WaitingPrompt {
+            request,
+            prompt_text,
+            priority,
+            seq: self.next_seq,
+        });
+        self.next_seq += 1;
+        self.waiting.sort_by_key(|w| (w.priority, w.seq));
+    }
+
+    /// The prompt to send next
+    #[cfg(test)]
+    pub fn pop(&mut self) -> Option<WaitingPrompt> {
+        self.pop_first(|_| true)
+    }
+
+    /// The first prompt in sending order that `ready` lets go
+    pub fn pop_first(&mut self, ready: impl Fn(&WaitingPrompt) -> bool) -> Option<WaitingPrompt> {
+        let index = self.waiting.iter().position(ready)?;
+        Some(self.waiting.remove(index))
+    }
+
+    /// Waiting prompts in the order they will be sent
+    pub fn waiting(&self) -> &[WaitingPrompt] {
+        &self.waiting
+    }
+
+    /// 1-based place in line of the request with `key`
+    pub fn position(&self, key: &str) -> Option<usize> {
+        self.waiting.iter().position(|w| w.request.key == key).map(|i| i + 1)
+    }
+
+    pub fn len(&self) -> usize {
+        self.waiting.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.waiting.is_empty()
+    }
+}
+
+/// Time until the prompt at `position` starts, when `slots` run at once and
+/// each takes `average` (the ones running now are assumed half done)
+pub fn eta(position: usize, slots: usize, average: Duration) -> Duration {
+    let rounds = position.div_ceil(slots.max(1)) as u32;
+    average.mul_f64(f64::from(rounds) - 0.5)
+}
+
+impl AppState {
+    /// Whether a prompt submitted now has to wait for a free slot
+    pub fn dispatch_slots_full(&self) -> bool {
+        let max = self.config.queue.max_in_flight;
+        max > 0 && self.in_flight.len() >= max
+    }
+
+    /// Average of recent response latencies, if there are any
+    pub fn average_latency(&self) -> Option<Duration> {
+        let recent: Vec<f64> = self.latency_history.iter().rev().take(ETA_SAMPLES).copied().collect();
+        (!recent.is_empty()).then(|| Duration::from_secs_f64(recent.iter().sum::<f64>() / recent.len() as f64 / 1000.0))
+    }
+
+    /// Estimated wait for the queued prompt at `position`
+    pub fn queue_eta(&self, position: usize) -> Option<Duration> {
+        self.average_latency()
+            .map(|average| eta(position, self.config.queue.max_in_flight, average))
+    }
+
+    /// Priority for the next prompt: high → normal → low
+    pub fn cycle_priority(&mut self) {
+        self.next_priority = self.next_priority.cycle();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(prompt: &str) -> InFlightPrompt {
+        InFlightPrompt::new(prompt.to_string(), "gpt-4o".to_string())
+    }
+
+    #[test]
+    fn test_high_priority_first_then_fifo() {
+        let mut queue = PromptQueue::default();
+        queue.push(request("a"), String::new(), Priority::Normal);
+        queue.push(request("b"), String::new(), Priority::Low);
+        queue.push(request("c"), String::new(), Priority::Normal);
+        queue.push(request("d"), String::new(), Priority::High);
+
+        let d = queue.waiting()[0].request.key.clone();
+        assert_eq!(queue.position(&d), Some(1));
+        let order: Vec<String> = std::iter::from_fn(|| queue.pop()).map(|w| w.request.prompt).collect();
+        assert_eq!(order, ["d", "a", "c", "b"]);
+    }
+
+    #[test]
+    fn test_eta_counts_rounds_of_slots() {
+        let average = Duration::from_secs(10);
+        assert_eq!(eta(1, 2, average), Duration::from_secs(5));
+        assert_eq!(eta(3, 2, average), Duration::from_secs(15));
+        assert_eq!(eta(3, 1, average), Duration::from_secs(25));
+
+        let state = AppState {
+            latency_history: vec![4000.0, 6000.0],
+            ..Default::default()
+        };
+        assert_eq!(state.average_latency(), Some(Duration::from_secs(5)));
+    }
+}
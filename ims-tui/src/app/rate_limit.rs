@@ -0,0 +1,134 @@
+//! Rate Limit Retries
+//!
+//! A prompt IMS Core answers with 429 isn't reported as failed straight
+//! away: it is sent again (with the same idempotency key) once the wait the
+//! backend asked for is up, or after a doubling fallback delay when it gave
+//! none. The status bar counts down to the next retry. After `MAX_RETRIES`
+//! the prompt fails like any other error.
+
+use std::time::{Duration, Instant};
+
+use crate::app::{
+    api_error::MAX_RETRY_AFTER,
+    thinking::{Level, ThinkingEntry},
+    AppState, InFlightPrompt,
+};
+
+/// Retries of one prompt before giving up
+pub const MAX_RETRIES: u32 = 3;
+
+/// First wait when the backend doesn't say how long
+const FALLBACK_WAIT: Duration = Duration::from_secs(5);
+
+/// A rate-limited prompt waiting to be sent again
+#[derive(Clone, Debug)]
+pub struct ScheduledRetry {
+    pub request: InFlightPrompt,
+    pub prompt_text: String,
+    pub at: Instant,
+}
+
+impl ScheduledRetry {
+    /// Time left until it is sent
+    pub fn countdown(&self) -> Duration {
+        self.at.saturating_duration_since(Instant::now())
+    }
+}
+
+/// Wait before retry `attempt` (1-based) when the backend gave none
+pub fn fallback_wait(attempt: u32) -> Duration {
+    FALLBACK_WAIT * 2u32.pow(attempt.saturating_sub(1))
+}
+
+impl AppState {
+    /// Schedule another try of a prompt rejected with 429 after
+    /// `retry_after` (or the fallback wait); `false` once its retries are
+    /// used up
+    pub fn schedule_rate_limit_retry(
+        &mut self,
+        mut request: InFlightPrompt,
+        prompt_text: String,
+        retry_after: Option<Duration>,
+    ) -> bool {
+        if request.rate_limit_retries >= MAX_RETRIES {
+            return false;
+        }
+        request.rate_limit_retries += 1;
+        let wait = retry_after
+            .unwrap_or_else(|| fallback_wait(request.rate_limit_retries))
+            .min(MAX_RETRY_AFTER);
+        let now = Instant::now();
+        self.add_thinking(
+            ThinkingEntry::system(format!(
+                "⏳ Rate limited ({}): retrying in {}s ({}/{})",
+                request.model_id,
+                wait.as_secs_f64().ceil(),
+                request.rate_limit_retries,
+                MAX_RETRIES
+            ))
+            .with_level(Level::Warning),
+        );
+        self.rate_limit_retries.push(ScheduledRetry {
+            request,
+            prompt_text,
+            at: now.checked_add(wait).unwrap_or(now),
+        });
+        true
+    }
+
+    /// Take the retries due at `now`
+    pub fn due_rate_limit_retries(&mut self, now: Instant) -> Vec<ScheduledRetry> {
+        let (due, waiting) = std::mem::take(&mut self.rate_limit_retries)
+            .into_iter()
+            .partition(|retry| retry.at <= now);
+        self.rate_limit_retries = waiting;
+        due
+    }
+
+    /// The retry that will be sent first
+    pub fn next_rate_limit_retry(&self) -> Option<&ScheduledRetry> {
+        self.rate_limit_retries.iter().min_by_key(|retry| retry.at)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retries_are_scheduled_until_exhausted() {
+        let mut state = AppState::default();
+        let request = InFlightPrompt::new("Explain lifetimes".to_string(), "gpt-4o".to_string());
+
+        assert!(state.schedule_rate_limit_retry(request.clone(), "Explain lifetimes".to_string(), Some(Duration::from_secs(12))));
+        assert!(state.thinking_log.last().unwrap().text.contains("retrying in 12s (1/3)"));
+        let next = state.next_rate_limit_retry().unwrap();
+        assert_eq!(next.request.rate_limit_retries, 1);
+        assert!(next.countdown() > Duration::from_secs(11));
+
+        // Nothing is due until the wait is up
+        assert!(state.due_rate_limit_retries(Instant::now()).is_empty());
+        let due = state.due_rate_limit_retries(Instant::now() + Duration::from_secs(13));
+        assert_eq!(due.len(), 1);
+        assert!(state.next_rate_limit_retry().is_none());
+
+        let mut retried = due[0].request.clone();
+        retried.rate_limit_retries = MAX_RETRIES;
+        assert!(!state.schedule_rate_limit_retry(retried, String::new(), None));
+        assert_eq!(fallback_wait(1), Duration::from_secs(5));
+        assert_eq!(fallback_wait(3), Duration::from_secs(20));
+    }
+
+    #[test]
+    fn test_huge_server_wait_is_capped() {
+        use crate::app::api_error::ApiError;
+        use reqwest::StatusCode;
+
+        let err = ApiError::from_response(StatusCode::TOO_MANY_REQUESTS, None, r#"{"retry_after": 1e30}"#, true);
+        let ApiError::RateLimited { retry_after } = err else { panic!("expected a rate limit, got {:?}", err) };
+        let mut state = AppState::default();
+        let request = InFlightPrompt::new("Explain lifetimes".to_string(), "gpt-4o".to_string());
+        assert!(state.schedule_rate_limit_retry(request, "Explain lifetimes".to_string(), retry_after));
+        assert!(state.next_rate_limit_retry().unwrap().countdown() <= MAX_RETRY_AFTER);
+    }
+}
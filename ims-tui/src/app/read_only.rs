@@ -0,0 +1,83 @@
+//! Read-Only Mode
+//!
+//! Without an admin key, or once IMS Core refuses the metrics endpoint with
+//! 401/403, the TUI stops offering what it can't do instead of failing at
+//! it: admin actions are hidden, the metrics line says why it is empty, and
+//! the metrics poller stops asking rather than logging an error every tick.
+//! Prompts work as before. Entering an admin key leaves the mode.
+
+use crate::app::{connection::PollerRequest, AppState};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReadOnly {
+    /// No admin key was configured
+    NoAdminKey,
+    /// The metrics endpoint rejected the request
+    Forbidden { status: u16 },
+}
+
+impl ReadOnly {
+    pub fn reason(&self) -> String {
+        match self {
+            ReadOnly::NoAdminKey => "no admin key".to_string(),
+            ReadOnly::Forbidden { status } => format!("metrics returned {}", status),
+        }
+    }
+
+    /// Whether the metrics poller has stopped
+    pub fn metrics_unavailable(&self) -> bool {
+        matches!(self, ReadOnly::Forbidden { .. })
+    }
+}
+
+impl AppState {
+    pub fn enter_read_only(&mut self, mode: ReadOnly) {
+        if self.read_only.as_ref() == Some(&mode) {
+            return;
+        }
+        self.add_debug_log(format!(
+            "Read-only mode ({}): admin actions hidden, prompts still work",
+            mode.reason()
+        ));
+        self.read_only = Some(mode);
+    }
+
+    /// An admin key was entered; restart the metrics poller if it stopped
+    pub fn leave_read_only(&mut self) {
+        let Some(mode) = self.read_only.take() else { return };
+        self.add_debug_log("Admin key set, leaving read-only mode".to_string());
+        if mode.metrics_unavailable() && self.api_connected {
+            self.poller_request = Some(PollerRequest::Restart);
+        }
+    }
+
+    #[cfg(test)]
+    pub fn is_read_only(&self) -> bool {
+        self.read_only.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enter_once_and_leave_restarts_metrics() {
+        let mut state = AppState {
+            api_connected: true,
+            ..Default::default()
+        };
+        state.enter_read_only(ReadOnly::NoAdminKey);
+        state.enter_read_only(ReadOnly::NoAdminKey);
+        let logged = state.debug_logs.iter().filter(|l| l.contains("Read-only")).count();
+        assert_eq!(logged, 1);
+
+        state.leave_read_only();
+        assert!(!state.is_read_only());
+        assert!(state.poller_request.is_none());
+
+        state.enter_read_only(ReadOnly::Forbidden { status: 403 });
+        state.leave_read_only();
+        assert_eq!(state.poller_request, Some(PollerRequest::Restart));
+    }
+}
@@ -0,0 +1,284 @@
+//! Crash Recovery
+//!
+//! While the app runs, `.ims/session.lock` marks the session as live and
+//! the unsent prompt and generation output are written to
+//! `.ims/recovery.json` whenever they change (at most every
+//! `SAVE_INTERVAL`). A clean exit removes both. Finding the lock at
+//! startup means either another instance is running here, if the process
+//! it names is alive, or the last run did not exit cleanly, so whatever it
+//! saved is offered back. A recovery file that can't be parsed is logged
+//! and ignored.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::{overlay::Overlay, ActiveSession, AppState, InputMode};
+
+pub const SAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+const LOCK_FILE: &str = "session.lock";
+const RECOVERY_FILE: &str = "recovery.json";
+
+/// Work that had not been sent or saved when the app last stopped
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RecoveredWork {
+    pub prompt: String,
+    pub generation: String,
+    pub file: Option<PathBuf>,
+    pub model_id: Option<String>,
+    /// Unix seconds
+    pub saved_at: i64,
+}
+
+impl RecoveredWork {
+    fn capture(state: &AppState) -> Self {
+        Self {
+            prompt: state.input_buffer.as_str().to_string(),
+            generation: state.generated_code.clone(),
+            file: state.session.as_ref().map(|s| s.file_path.clone()),
+            model_id: state.session.as_ref().map(|s| s.model_id.clone()),
+            saved_at: chrono::Local::now().timestamp(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.prompt.trim().is_empty() && self.generation.trim().is_empty()
+    }
+
+    /// Same content, whenever it was saved
+    fn same_work(&self, other: &Self) -> bool {
+        Self { saved_at: 0, ..self.clone() } == Self { saved_at: 0, ..other.clone() }
+    }
+}
+
+/// Owns the lock and recovery files for one run
+#[derive(Debug)]
+pub struct Recovery {
+    dir: PathBuf,
+    saved: RecoveredWork,
+    last_check: Instant,
+    failing: bool,
+}
+
+/// Where the lock and recovery files live
+pub fn recovery_dir() -> PathBuf {
+    super::ims_dir()
+}
+
+impl Recovery {
+    /// Take the lock in `dir`, returning what an unclean previous exit
+    /// left behind
+    pub fn start(dir: &Path) -> Result<(Self, Option<RecoveredWork>)> {
+        let lock = dir.join(LOCK_FILE);
+        let unclean = lock.exists();
+        if let Some(pid) = lock_holder(&lock).filter(|pid| *pid != std::process::id() && is_running(*pid)) {
+            bail!("another IMS-TUI (pid {}) is running in {}", pid, dir.display());
+        }
+        let found = match std::fs::read_to_string(dir.join(RECOVERY_FILE)) {
+            Ok(text) if unclean => match serde_json::from_str::<RecoveredWork>(&text) {
+                Ok(work) => Some(work),
+                Err(e) => {
+                    tracing::warn!("Ignoring corrupt recovery file in {}: {}", dir.display(), e);
+                    None
+                }
+            },
+            Ok(_) => None,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", dir.join(RECOVERY_FILE).display())),
+        };
+
+        std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        std::fs::write(&lock, std::process::id().to_string())
+            .with_context(|| format!("Failed to write {}", lock.display()))?;
+        let recovery = Self {
+            dir: dir.to_path_buf(),
+            saved: found.clone().unwrap_or_default(),
+            last_check: Instant::now(),
+            failing: false,
+        };
+        Ok((recovery, found.filter(|work| !work.is_empty())))
+    }
+
+    /// Write the current prompt and generation if they changed since the
+    /// last save and `SAVE_INTERVAL` has passed
+    pub fn tick(&mut self, state: &mut AppState) {
+        // Until the user answers, the file still holds the previous run's work
+        if state.recovered.is_some() || self.last_check.elapsed() < SAVE_INTERVAL {
+            return;
+        }
+        self.last_check = Instant::now();
+        match self.save(RecoveredWork::capture(state)) {
+            Ok(()) => self.failing = false,
+            Err(e) => {
+                tracing::warn!("Failed to save recovery file: {:#}", e);
+                if !std::mem::replace(&mut self.failing, true) {
+                    state.add_debug_log(format!("Failed to save recovery file: {:#}", e));
+                }
+            }
+        }
+    }
+
+    fn save(&mut self, work: RecoveredWork) -> Result<()> {
+        if work.same_work(&self.saved) {
+            return Ok(());
+        }
+        let path = self.dir.join(RECOVERY_FILE);
+        if work.is_empty() {
+            remove(&path)?;
+        } else {
+            std::fs::write(&path, serde_json::to_string_pretty(&work)?)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+        self.saved = work;
+        Ok(())
+    }
+
+    /// Clean exit: nothing to recover next time
+    pub fn finish(self) -> Result<()> {
+        remove(&self.dir.join(RECOVERY_FILE))?;
+        remove(&self.dir.join(LOCK_FILE))
+    }
+}
+
+/// The process id written into the lock at `path`, if it holds one
+fn lock_holder(path: &Path) -> Option<u32> {
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// Whether process `pid` is still running
+#[cfg(unix)]
+fn is_running(pid: u32) -> bool {
+    let Some(pid) = libc::pid_t::try_from(pid).ok().filter(|pid| *pid > 0) else { return false };
+    // SAFETY: signal 0 only checks that the process exists
+    let alive = unsafe { libc::kill(pid, 0) } == 0;
+    alive || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+/// Elsewhere a lock is always taken as left behind
+#[cfg(not(unix))]
+fn is_running(_pid: u32) -> bool {
+    false
+}
+
+fn remove(path: &Path) -> Result<()> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(e).with_context(|| format!("Failed to remove {}", path.display()))
+        }
+        _ => Ok(()),
+    }
+}
+
+impl AppState {
+    /// Ask whether to restore `work`
+    pub fn offer_recovered(&mut self, work: RecoveredWork) {
+        self.recovered = Some(work);
+        self.open_overlay(Overlay::Recovery);
+    }
+
+    /// Put recovered work back: the prompt into the prompt box, the
+    /// output into the Generation pane, reopening its file if needed
+    pub fn restore_recovered(&mut self) {
+        let Some(work) = self.recovered.take() else { return };
+        self.close_overlay(Overlay::Recovery);
+        if let (Some(file), Some(model_id)) = (&work.file, &work.model_id) {
+            if self.session.as_ref().map(|s| &s.file_path) != Some(file) {
+                self.session = Some(ActiveSession::new(file.clone(), self.vendors.for_model(model_id), model_id.clone()));
+            }
+        }
+        if !work.generation.is_empty() {
+            self.generated_code = work.generation;
+            self.clear_generation_regions();
+        }
+        if !work.prompt.is_empty() {
+            self.input_buffer.set(work.prompt);
+            self.input_mode = InputMode::Editing;
+        }
+        self.add_debug_log("Restored unsent work from the last session".to_string());
+    }
+
+    /// Run when the recovery prompt is closed without restoring
+    pub(super) fn discard_recovered(&mut self) {
+        if self.recovered.take().is_some() {
+            self.add_debug_log("Discarded unsent work from the last session".to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("ims-tui-recovery-{}", uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn test_unclean_exit_offers_saved_work() {
+        let dir = temp_dir();
+        let mut state = AppState::default();
+        state.input_buffer.set("Refactor the parser");
+        state.generated_code = "fn parse() {}".to_string();
+
+        let (mut recovery, found) = Recovery::start(&dir).unwrap();
+        assert!(found.is_none());
+        recovery.last_check -= SAVE_INTERVAL;
+        recovery.tick(&mut state);
+        // Crash: the lock is left behind
+        drop(recovery);
+
+        let (recovery, found) = Recovery::start(&dir).unwrap();
+        let found = found.unwrap();
+        assert_eq!(found.prompt, "Refactor the parser");
+        assert_eq!(found.generation, "fn parse() {}");
+
+        let mut restored = AppState::default();
+        restored.offer_recovered(found);
+        restored.restore_recovered();
+        assert_eq!(restored.overlays.top(), None);
+        assert_eq!(restored.input_buffer.as_str(), "Refactor the parser");
+        assert_eq!(restored.generated_code, "fn parse() {}");
+
+        // Clean exit: nothing offered next time
+        recovery.finish().unwrap();
+        let (recovery, found) = Recovery::start(&dir).unwrap();
+        assert!(found.is_none());
+        recovery.finish().unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_corrupt_file_is_ignored() {
+        let dir = temp_dir();
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(LOCK_FILE), "not a pid").unwrap();
+        std::fs::write(dir.join(RECOVERY_FILE), "{\"prompt\": ").unwrap();
+
+        let (recovery, found) = Recovery::start(&dir).unwrap();
+        assert!(found.is_none());
+        recovery.finish().unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_live_lock_is_not_taken() {
+        let dir = temp_dir();
+        std::fs::create_dir_all(&dir).unwrap();
+        // The test runner's parent is alive, so this lock is another instance's
+        let parent = std::os::unix::process::parent_id();
+        std::fs::write(dir.join(LOCK_FILE), parent.to_string()).unwrap();
+        let err = Recovery::start(&dir).unwrap_err();
+        assert!(err.to_string().starts_with(&format!("another IMS-TUI (pid {}) is running", parent)));
+        assert_eq!(lock_holder(&dir.join(LOCK_FILE)), Some(parent));
+
+        // A process that has exited left it behind
+        std::fs::write(dir.join(LOCK_FILE), u32::MAX.to_string()).unwrap();
+        let (recovery, _) = Recovery::start(&dir).unwrap();
+        recovery.finish().unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
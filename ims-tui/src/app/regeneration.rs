@@ -0,0 +1,162 @@
+//! Regeneration Diffs
+//!
+//! A response that closely resembles the one before it is taken to be a
+//! fresh attempt at the same code, and each of its lines is compared with
+//! that attempt. The Generation pane colors the lines the model added,
+//! picks out the tokens it rewrote within a line and marks where it
+//! dropped lines, so a retry shows what actually changed.
+
+use similar::{ChangeTag, DiffTag, TextDiff};
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::app::AppState;
+
+/// How alike two responses must be (by line, 0 to 1) for the second to
+/// count as a regeneration of the first rather than a new answer
+const REGENERATION_RATIO: f32 = 0.5;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LineChange {
+    Same,
+    Added,
+    /// Rewritten; byte ranges of the tokens that differ
+    Changed(Vec<Range<usize>>),
+}
+
+/// How a response differs from the previous one
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegenerationDiff {
+    /// One per line of the response
+    pub lines: Vec<LineChange>,
+    /// Previous lines dropped just before each line; those dropped after
+    /// the last line count on the last
+    pub removed: Vec<usize>,
+}
+
+impl RegenerationDiff {
+    pub fn added(&self) -> usize {
+        self.lines.iter().filter(|l| **l == LineChange::Added).count()
+    }
+
+    pub fn changed(&self) -> usize {
+        self.lines.iter().filter(|l| matches!(l, LineChange::Changed(_))).count()
+    }
+
+    pub fn removed_total(&self) -> usize {
+        self.removed.iter().sum()
+    }
+}
+
+/// Compare `current` with `previous` line by line, or `None` when it is
+/// not a regeneration of it
+pub fn diff(previous: &str, current: &str) -> Option<RegenerationDiff> {
+    let count = current.lines().count();
+    if count == 0 {
+        return None;
+    }
+    let diff = TextDiff::from_lines(previous, current);
+    if diff.ratio() < REGENERATION_RATIO {
+        return None;
+    }
+
+    let mut lines = vec![LineChange::Same; count];
+    let mut removed = vec![0; count];
+    let mut drop = |at: usize, n: usize| removed[at.min(count - 1)] += n;
+    for op in diff.ops() {
+        let (tag, old, new) = op.as_tag_tuple();
+        match tag {
+            DiffTag::Equal => {}
+            DiffTag::Delete => drop(new.start, old.len()),
+            DiffTag::Insert => new.clone().for_each(|i| lines[i] = LineChange::Added),
+            DiffTag::Replace => {
+                drop(new.end, old.len().saturating_sub(new.len()));
+                for change in diff.iter_inline_changes(op) {
+                    let (ChangeTag::Insert, Some(index)) = (change.tag(), change.new_index()) else { continue };
+                    // Lines past those replaced are new
+                    if index >= new.start + old.len() {
+                        lines[index] = LineChange::Added;
+                        continue;
+                    }
+                    let mut tokens = Vec::new();
+                    let mut at = 0;
+                    for (emphasized, text) in change.iter_strings_lossy() {
+                        let text = text.trim_end_matches('\n');
+                        if emphasized && !text.trim().is_empty() {
+                            tokens.push(at..at + text.len());
+                        }
+                        at += text.len();
+                    }
+                    lines[index] = LineChange::Changed(tokens);
+                }
+            }
+        }
+    }
+    Some(RegenerationDiff { lines, removed })
+}
+
+/// How one line of the Generation pane changed from the previous attempt
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LineMark<'a> {
+    pub change: &'a LineChange,
+    pub removed: usize,
+    /// Bytes of the line before the response starts, when it continued
+    /// the previous one's last line
+    pub offset: usize,
+}
+
+impl AppState {
+    /// Marks for the source lines of `generated_code` that belong to a
+    /// regeneration
+    pub fn line_marks(&self) -> HashMap<usize, LineMark<'_>> {
+        let mut marks = HashMap::new();
+        for (region, lines) in self.generation_regions.iter().zip(self.region_lines()) {
+            let Some(changes) = &region.changes else { continue };
+            let line_start = self.generated_code[..region.bytes.start].rfind('\n').map_or(0, |i| i + 1);
+            for (k, (change, removed)) in changes.lines.iter().zip(&changes.removed).enumerate() {
+                let offset = if k == 0 { region.bytes.start - line_start } else { 0 };
+                marks.insert(lines.start + k, LineMark { change, removed: *removed, offset });
+            }
+        }
+        marks
+    }
+
+    /// Changes of the latest response, if it was a regeneration
+    pub fn latest_changes(&self) -> Option<&RegenerationDiff> {
+        self.generation_regions.last()?.changes.as_ref()
+    }
+
+    pub fn toggle_change_highlight(&mut self) {
+        self.highlight_changes = !self.highlight_changes;
+        self.add_debug_log(format!(
+            "Regeneration highlighting {}",
+            if self.highlight_changes { "on" } else { "off" }
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_marks_added_changed_and_removed_lines() {
+        let previous = "fn main() {\n    let x = 1;\n    println!(\"{}\", x);\n    dbg!(x);\n}\n";
+        let current = "fn main() {\n    let x = 2;\n    println!(\"{}\", x);\n    eprintln!(\"done\");\n}\n";
+        let changes = diff(previous, current).unwrap();
+
+        let LineChange::Changed(tokens) = &changes.lines[1] else {
+            panic!("expected a changed line, got {:?}", changes.lines[1]);
+        };
+        assert_eq!(tokens.iter().map(|t| &"    let x = 2;"[t.clone()]).collect::<Vec<_>>(), ["2;"]);
+        assert_eq!(changes.lines[2], LineChange::Same);
+        assert!(matches!(changes.lines[3], LineChange::Changed(_)));
+        assert_eq!((changes.added(), changes.changed(), changes.removed_total()), (0, 2, 0));
+
+        let trimmed = diff(current, "fn main() {\n    let x = 2;\n").unwrap();
+        assert_eq!(trimmed.removed, [0, 3]);
+
+        // An unrelated answer is not a regeneration
+        assert_eq!(diff(previous, "The parser looks fine.\n"), None);
+    }
+}
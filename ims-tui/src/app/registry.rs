@@ -0,0 +1,408 @@
+//! Model Registry Browser State
+//!
+//! Backing state for the full-screen registry view: the fetched model list,
+//! the incremental filter, sort order and selection. The list arrives a
+//! page at a time; the next page is requested as the selection nears the
+//! end of what has loaded.
+
+use crate::app::api::{ModelPage, ModelResponse, PageToken};
+
+/// Rows from the end of the loaded list at which the next page is fetched
+const PREFETCH_ROWS: usize = 10;
+
+/// Sortable columns of the registry table
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SortColumn {
+    Model,
+    Vendor,
+    Tier,
+    Context,
+    CostIn,
+    CostOut,
+    FunctionCalls,
+    Active,
+}
+
+impl SortColumn {
+    pub const ALL: [SortColumn; 8] = [
+        SortColumn::Model,
+        SortColumn::Vendor,
+        SortColumn::Tier,
+        SortColumn::Context,
+        SortColumn::CostIn,
+        SortColumn::CostOut,
+        SortColumn::FunctionCalls,
+        SortColumn::Active,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            SortColumn::Model => "Model",
+            SortColumn::Vendor => "Vendor",
+            SortColumn::Tier => "Tier",
+            SortColumn::Context => "Context",
+            SortColumn::CostIn => "$In/M",
+            SortColumn::CostOut => "$Out/M",
+            SortColumn::FunctionCalls => "Fn",
+            SortColumn::Active => "Active",
+        }
+    }
+
+    fn index(&self) -> usize {
+        Self::ALL.iter().position(|c| c == self).unwrap_or(0)
+    }
+
+    pub fn next(&self) -> Self {
+        Self::ALL[(self.index() + 1) % Self::ALL.len()]
+    }
+
+    pub fn prev(&self) -> Self {
+        Self::ALL[(self.index() + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+}
+
+/// Admin write actions available from the registry view
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AdminAction {
+    Activate,
+    Deactivate,
+    UpdatePricing,
+}
+
+impl AdminAction {
+    pub const ALL: [AdminAction; 3] = [
+        AdminAction::Activate,
+        AdminAction::Deactivate,
+        AdminAction::UpdatePricing,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AdminAction::Activate => "Activate model",
+            AdminAction::Deactivate => "Deactivate model",
+            AdminAction::UpdatePricing => "Update pricing...",
+        }
+    }
+}
+
+/// Admin submenu popup over the registry table
+#[derive(Clone, Debug, Default)]
+pub struct AdminMenu {
+    pub selected: usize,
+    /// "in,out" price entry, once "Update pricing" is chosen
+    pub pricing_input: Option<String>,
+}
+
+/// Parse "1.25, 5" into (cost_in_per_mil, cost_out_per_mil)
+pub fn parse_pricing(input: &str) -> Option<(f64, f64)> {
+    let (cost_in, cost_out) = input.split_once(',')?;
+    let cost_in: f64 = cost_in.trim().parse().ok()?;
+    let cost_out: f64 = cost_out.trim().parse().ok()?;
+    (cost_in >= 0.0 && cost_out >= 0.0).then_some((cost_in, cost_out))
+}
+
+/// Full-screen model registry view
+#[derive(Clone, Debug)]
+pub struct ModelRegistryView {
+    pub models: Vec<ModelResponse>,
+    pub loading: bool,
+    pub filter: String,
+    /// Keystrokes go to the filter instead of actions
+    pub filter_editing: bool,
+    pub sort: SortColumn,
+    pub descending: bool,
+    pub selected: usize,
+    pub admin_menu: Option<AdminMenu>,
+    /// Where the next page starts; `None` once everything has loaded
+    pub next_page: Option<PageToken>,
+    /// Models in the registry, when the backend reports it
+    pub total: Option<u32>,
+    /// Sent back as `If-None-Match` to revalidate the first page
+    pub etag: Option<String>,
+    /// Unix seconds the backend last confirmed the list
+    pub checked_at: Option<i64>,
+    /// Restored from the model cache and not yet revalidated
+    pub stale: bool,
+}
+
+impl Default for ModelRegistryView {
+    fn default() -> Self {
+        Self {
+            models: Vec::new(),
+            loading: false,
+            filter: String::new(),
+            filter_editing: false,
+            sort: SortColumn::Model,
+            descending: false,
+            selected: 0,
+            admin_menu: None,
+            next_page: None,
+            total: None,
+            etag: None,
+            checked_at: None,
+            stale: false,
+        }
+    }
+}
+
+impl ModelRegistryView {
+    /// Models matching the filter, in display order
+    pub fn visible(&self) -> Vec<&ModelResponse> {
+        let needle = self.filter.to_lowercase();
+        let mut rows: Vec<&ModelResponse> = self
+            .models
+            .iter()
+            .filter(|m| {
+                needle.is_empty()
+                    || m.model_id.to_lowercase().contains(&needle)
+                    || m.vendor_id.to_lowercase().contains(&needle)
+                    || m.capability_tier.to_lowercase().contains(&needle)
+            })
+            .collect();
+
+        rows.sort_by(|a, b| {
+            let ord = match self.sort {
+                SortColumn::Model => a.model_id.cmp(&b.model_id),
+                SortColumn::Vendor => a.vendor_id.cmp(&b.vendor_id),
+                SortColumn::Tier => a.capability_tier.cmp(&b.capability_tier),
+                SortColumn::Context => a.context_window.cmp(&b.context_window),
+                SortColumn::CostIn => a.cost_in_per_mil.total_cmp(&b.cost_in_per_mil),
+                SortColumn::CostOut => a.cost_out_per_mil.total_cmp(&b.cost_out_per_mil),
+                SortColumn::FunctionCalls => a.function_call_support.cmp(&b.function_call_support),
+                SortColumn::Active => a.is_active.cmp(&b.is_active),
+            };
+            // Stable tie-break on id keeps rows from jumping between polls
+            let ord = ord.then_with(|| a.model_id.cmp(&b.model_id));
+            if self.descending {
+                ord.reverse()
+            } else {
+                ord
+            }
+        });
+        rows
+    }
+
+    pub fn selected_model(&self) -> Option<&ModelResponse> {
+        self.visible().get(self.selected).copied()
+    }
+
+    pub fn select_next(&mut self) {
+        let len = self.visible().len();
+        if len > 0 {
+            self.selected = (self.selected + 1).min(len - 1);
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    /// Keep the selection inside the (possibly shrunk) filtered list
+    pub fn clamp_selection(&mut self) {
+        let len = self.visible().len();
+        self.selected = self.selected.min(len.saturating_sub(1));
+    }
+
+    /// Take in a fetched page: the first replaces the list, later ones add
+    /// the models not already listed
+    pub fn apply_page(&mut self, page: ModelPage, append: bool) {
+        if append {
+            for model in page.models {
+                if !self.models.iter().any(|m| m.model_id == model.model_id) {
+                    self.models.push(model);
+                }
+            }
+        } else {
+            self.models = page.models;
+            self.etag = page.etag;
+            self.stale = false;
+        }
+        self.next_page = page.next;
+        self.total = page.total;
+        self.loading = false;
+        self.clamp_selection();
+    }
+
+    /// The backend confirmed the cached list; add the (uncached) local models
+    pub fn keep_cached(&mut self, local: Vec<ModelResponse>) {
+        for model in local {
+            if !self.models.iter().any(|m| m.model_id == model.model_id) {
+                self.models.push(model);
+            }
+        }
+        self.stale = false;
+        self.loading = false;
+    }
+
+    /// Whether opening the view or picker should fetch the list
+    pub fn needs_fetch(&self) -> bool {
+        !self.loading && (self.models.is_empty() || self.stale)
+    }
+
+    /// Whether the selection is close enough to the end of the loaded list
+    /// to fetch the next page
+    pub fn wants_more(&self) -> bool {
+        !self.loading && self.next_page.is_some() && self.selected + PREFETCH_ROWS >= self.visible().len()
+    }
+
+    /// Forget the loaded list so the next fetch starts from the first page
+    pub fn invalidate(&mut self) {
+        self.models.clear();
+        self.next_page = None;
+        self.total = None;
+        self.etag = None;
+        self.checked_at = None;
+        self.stale = false;
+        self.selected = 0;
+    }
+
+    /// Replace a model entry in place, returning the previous entry
+    pub fn replace_model(&mut self, model: ModelResponse) -> Option<ModelResponse> {
+        let slot = self.models.iter_mut().find(|m| m.model_id == model.model_id)?;
+        Some(std::mem::replace(slot, model))
+    }
+
+    /// Sort by `column`, toggling direction when it is already active
+    pub fn sort_by(&mut self, column: SortColumn) {
+        if self.sort == column {
+            self.descending = !self.descending;
+        } else {
+            self.sort = column;
+            self.descending = false;
+        }
+    }
+}
+
+/// File-type key used for per-extension model defaults ("rs", "md", ...)
+pub fn file_type_key(path: &std::path::Path) -> String {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_else(|| "(none)".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model(id: &str, vendor: &str, context: u32, cost_in: f64) -> ModelResponse {
+        ModelResponse {
+            model_id: id.to_string(),
+            vendor_id: vendor.to_string(),
+            capability_tier: "Tier_1".to_string(),
+            context_window: context,
+            cost_in_per_mil: cost_in,
+            cost_out_per_mil: cost_in * 4.0,
+            function_call_support: true,
+            is_active: true,
+        }
+    }
+
+    fn view() -> ModelRegistryView {
+        ModelRegistryView {
+            models: vec![
+                model("gpt-4o", "OpenAI", 128_000, 2.5),
+                model("claude-3-5-sonnet", "Anthropic", 200_000, 3.0),
+                model("gemini-1.5-flash", "Google", 1_000_000, 0.075),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_filter_matches_vendor_and_id() {
+        let mut v = view();
+        v.filter = "anthro".to_string();
+        assert_eq!(v.visible().len(), 1);
+
+        v.filter = "GEMINI".to_string();
+        assert_eq!(v.visible()[0].model_id, "gemini-1.5-flash");
+    }
+
+    #[test]
+    fn test_sort_toggles_direction() {
+        let mut v = view();
+        v.sort_by(SortColumn::CostIn);
+        assert_eq!(v.visible()[0].model_id, "gemini-1.5-flash");
+
+        v.sort_by(SortColumn::CostIn);
+        assert_eq!(v.visible()[0].model_id, "claude-3-5-sonnet");
+    }
+
+    #[test]
+    fn test_selection_clamped_to_filtered_list() {
+        let mut v = view();
+        v.selected = 2;
+        v.filter = "gpt".to_string();
+        v.clamp_selection();
+        assert_eq!(v.selected, 0);
+    }
+
+    #[test]
+    fn test_replace_model_returns_previous_for_rollback() {
+        let mut v = view();
+        let mut updated = v.models[0].clone();
+        updated.is_active = false;
+
+        let previous = v.replace_model(updated).unwrap();
+        assert!(previous.is_active);
+        assert!(!v.models[0].is_active);
+
+        v.replace_model(previous);
+        assert!(v.models[0].is_active);
+    }
+
+    #[test]
+    fn test_pages_append_and_prefetch() {
+        let mut v = view();
+        v.apply_page(
+            ModelPage {
+                models: vec![model("gpt-4o", "OpenAI", 128_000, 2.5), model("o1", "OpenAI", 200_000, 15.0)],
+                next: Some(PageToken::Offset(20)),
+                total: Some(40),
+                ..Default::default()
+            },
+            true,
+        );
+        // The overlapping model isn't listed twice
+        assert_eq!(v.models.len(), 4);
+        assert!(v.wants_more());
+
+        v.loading = true;
+        assert!(!v.wants_more());
+
+        v.apply_page(ModelPage::default(), false);
+        assert!(v.models.is_empty() && !v.loading && !v.wants_more());
+    }
+
+    #[test]
+    fn test_cached_list_kept_when_unchanged() {
+        let mut v = ModelRegistryView {
+            stale: true,
+            etag: Some("\"v1\"".to_string()),
+            ..view()
+        };
+        assert!(v.needs_fetch());
+        v.loading = true;
+        v.keep_cached(vec![model("local/llama3.1", "local", 8_192, 0.0)]);
+        assert_eq!(v.models.len(), 4);
+        assert!(!v.needs_fetch());
+        assert_eq!(v.etag.as_deref(), Some("\"v1\""));
+
+        v.invalidate();
+        assert!(v.etag.is_none() && v.needs_fetch());
+    }
+
+    #[test]
+    fn test_parse_pricing() {
+        assert_eq!(parse_pricing("1.25, 5"), Some((1.25, 5.0)));
+        assert_eq!(parse_pricing("1.25"), None);
+        assert_eq!(parse_pricing("-1,2"), None);
+    }
+
+    #[test]
+    fn test_file_type_key() {
+        assert_eq!(file_type_key(std::path::Path::new("src/Main.RS")), "rs");
+        assert_eq!(file_type_key(std::path::Path::new("Makefile")), "(none)");
+    }
+}
@@ -0,0 +1,214 @@
+//! Workspace Sandbox
+//!
+//! Everything that writes into the user's project (applying a
+//! generation, saving or inserting code blocks, pipeline outputs,
+//! deleting files) goes through `AppState::write_in_workspace`:
+//! - The path is resolved against the workspace root with symlinks and
+//!   `..` followed, and refused if it ends up outside the root
+//! - The first write into a workspace asks the user to trust it; the
+//!   answer is kept in config (`[trust] workspaces`) so it is asked once
+//!
+//! IMS-TUI's own files (`.ims/`, the config, caches) are not workspace
+//! writes and don't go through here.
+
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use crate::app::{overlay::Overlay, AppState};
+use crate::core::{effects::NotificationLevel, events::Event, reduce::reduce};
+
+/// A write waiting for the workspace to be trusted
+pub type PendingWrite = Box<dyn FnOnce(&mut AppState) + Send>;
+
+/// "Trust this workspace?" and the writes that asked
+pub struct TrustPrompt {
+    pub root: PathBuf,
+    pub pending: Vec<PendingWrite>,
+}
+
+/// `path` (relative to `root` unless absolute) with symlinks and `..`
+/// resolved. Parts that don't exist yet must be plain names, so a write
+/// can't climb out through a directory it is about to create.
+pub fn resolve(root: &Path, path: &Path) -> Result<PathBuf> {
+    let joined = root.join(path);
+    let mut existing = joined.as_path();
+    let mut missing = Vec::new();
+    // `symlink_metadata` so a dangling symlink counts as existing and
+    // fails to canonicalize instead of being written through
+    while std::fs::symlink_metadata(existing).is_err() {
+        let name = match existing.components().next_back() {
+            Some(Component::Normal(name)) => name,
+            _ => anyhow::bail!("{} is not a plain path", path.display()),
+        };
+        missing.push(name);
+        existing = existing.parent().context("path has no existing parent")?;
+    }
+    let mut resolved = existing
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve {}", existing.display()))?;
+    resolved.extend(missing.iter().rev());
+    Ok(resolved)
+}
+
+/// `path` resolved, if it is inside `root`
+pub fn contain(root: &Path, path: &Path) -> Result<PathBuf> {
+    let root = root
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve workspace {}", root.display()))?;
+    let resolved = resolve(&root, path)?;
+    anyhow::ensure!(
+        resolved.starts_with(&root),
+        "{} is outside the workspace {}",
+        resolved.display(),
+        root.display()
+    );
+    Ok(resolved)
+}
+
+/// The directory IMS-TUI was started in
+pub fn default_root() -> PathBuf {
+    std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."))
+}
+
+impl AppState {
+    fn workspace_trusted(&self) -> bool {
+        let root = self.workspace_root.canonicalize().unwrap_or_else(|_| self.workspace_root.clone());
+        self.config.trust.workspaces.contains(&root)
+    }
+
+    /// Run `write` on `path` once it is known to be inside the workspace,
+    /// asking to trust the workspace first if it isn't yet
+    pub fn write_in_workspace(
+        &mut self,
+        path: &Path,
+        write: impl FnOnce(&mut AppState, PathBuf) + Send + 'static,
+    ) {
+        let resolved = match contain(&self.workspace_root, path) {
+            Ok(resolved) => resolved,
+            Err(e) => {
+                let message = format!("Refused to write {}: {:#}", path.display(), e);
+                reduce(self, Event::NotificationShown { level: NotificationLevel::Warning, message });
+                return;
+            }
+        };
+        if self.workspace_trusted() {
+            write(self, resolved);
+            return;
+        }
+        let pending: PendingWrite = Box::new(move |state| write(state, resolved));
+        match &mut self.trust_prompt {
+            Some(prompt) => prompt.pending.push(pending),
+            None => {
+                self.trust_prompt = Some(TrustPrompt {
+                    root: self.workspace_root.canonicalize().unwrap_or_else(|_| self.workspace_root.clone()),
+                    pending: vec![pending],
+                });
+                self.open_overlay(Overlay::Trust);
+            }
+        }
+    }
+
+    /// Trust the workspace, saving it to the config file at `config_path`,
+    /// and run the waiting writes
+    pub fn trust_workspace(&mut self, config_path: &Path) {
+        let Some(prompt) = self.trust_prompt.take() else { return };
+        self.close_overlay(Overlay::Trust);
+        if !self.config.trust.workspaces.contains(&prompt.root) {
+            self.config.trust.workspaces.push(prompt.root.clone());
+        }
+        match self.config.save_trust(config_path) {
+            Ok(()) => self.add_debug_log(format!("Trusted workspace {}", prompt.root.display())),
+            Err(e) => self.add_debug_log(format!("Trusted {} for this session only: {:#}", prompt.root.display(), e)),
+        }
+        for write in prompt.pending {
+            write(self);
+        }
+    }
+
+    /// Overwrite the session's file with the generated code
+    pub fn apply_generation(&mut self) {
+        let Some(path) = self.session.as_ref().map(|s| s.file_path.clone()) else { return };
+        let code = self.generated_code.clone();
+        self.write_in_workspace(&path, move |state, path| match std::fs::write(&path, code) {
+            Ok(()) => state.add_debug_log(format!("Applied generation to {}", path.display())),
+            Err(e) => state.add_debug_log(format!("Failed to write {}: {}", path.display(), e)),
+        });
+    }
+
+    /// Run when the trust prompt is closed without trusting
+    pub(super) fn distrust_workspace(&mut self) {
+        if let Some(prompt) = self.trust_prompt.take() {
+            self.add_debug_log(format!(
+                "Workspace not trusted: skipped {} write(s)",
+                prompt.pending.len()
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workspace() -> PathBuf {
+        let root = std::env::temp_dir().join(format!("ims-tui-sandbox-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        root
+    }
+
+    #[test]
+    fn test_writes_stay_inside_the_workspace() {
+        let root = workspace();
+        let canonical = root.canonicalize().unwrap();
+
+        assert_eq!(contain(&root, Path::new("src/new/mod.rs")).unwrap(), canonical.join("src/new/mod.rs"));
+        assert_eq!(contain(&root, &root.join("src/../lib.rs")).unwrap(), canonical.join("lib.rs"));
+        assert!(contain(&root, Path::new("../outside.rs")).is_err());
+        assert!(contain(&root, Path::new("/etc/passwd")).is_err());
+        assert!(contain(&root, Path::new("missing/../../outside.rs")).is_err());
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(std::env::temp_dir(), root.join("escape")).unwrap();
+            assert!(contain(&root, Path::new("escape/file.rs")).is_err());
+            std::os::unix::fs::symlink(root.join("nowhere"), root.join("dangling")).unwrap();
+            assert!(contain(&root, Path::new("dangling")).is_err());
+        }
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_untrusted_workspace_asks_before_writing() {
+        let root = workspace();
+        let mut state = AppState {
+            workspace_root: root.clone(),
+            ..Default::default()
+        };
+        let write = |state: &mut AppState, path: PathBuf| {
+            std::fs::write(&path, "fn main() {}").unwrap();
+            state.add_debug_log(format!("wrote {}", path.display()));
+        };
+
+        state.write_in_workspace(Path::new("src/main.rs"), write);
+        assert!(!root.join("src/main.rs").exists());
+        assert_eq!(state.overlays.top(), Some(Overlay::Trust));
+        state.close_overlay(Overlay::Trust);
+        assert!(state.trust_prompt.is_none());
+        assert!(!root.join("src/main.rs").exists());
+
+        state.write_in_workspace(Path::new("src/main.rs"), write);
+        // Trusting also runs the waiting write
+        state.trust_workspace(&root.join("config.toml"));
+        assert!(root.join("src/main.rs").exists());
+        assert_eq!(state.config.trust.workspaces, vec![root.canonicalize().unwrap()]);
+        let saved = crate::app::config::AppConfig::load_from(&root.join("config.toml")).unwrap();
+        assert_eq!(saved.trust, state.config.trust);
+
+        // Trusted from now on: no prompt
+        state.write_in_workspace(Path::new("src/lib.rs"), write);
+        assert!(state.trust_prompt.is_none());
+        assert!(root.join("src/lib.rs").exists());
+        std::fs::remove_dir_all(&root).ok();
+    }
+}
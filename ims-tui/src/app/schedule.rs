@@ -0,0 +1,457 @@
+//! Scheduled Prompts
+//!
+//! `[[schedules]]` entries run a saved prompt or workflow daily at a local
+//! time (`at = "02:00"`) or every `every_mins` minutes while the TUI is
+//! open. The Schedules view lists the next run of each, with the outcome
+//! of the last one; the Thinking pane reports every run as it finishes.
+//! When each schedule last ran is kept in `.ims/schedules.json`, so
+//! `changed_only` workflows see only the files modified since the last
+//! successful run started. Names must be unique, since runs are kept by
+//! name.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Duration, Local, NaiveTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use crate::app::{
+    config::ScheduleEntry,
+    overlay::Overlay,
+    thinking::{Level, ThinkingEntry},
+    AppState,
+};
+use crate::core::{effects::NotificationLevel, events::Event, reduce::reduce};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cadence {
+    /// Every day at this local time
+    Daily(NaiveTime),
+    Every(Duration),
+}
+
+impl Cadence {
+    pub fn from_entry(entry: &ScheduleEntry) -> Result<Self> {
+        match (&entry.at, entry.every_mins) {
+            (Some(at), None) => NaiveTime::parse_from_str(at, "%H:%M")
+                .map(Cadence::Daily)
+                .with_context(|| format!("`at = \"{}\"` is not an HH:MM time", at)),
+            (None, Some(0)) => bail!("`every_mins` must be at least 1"),
+            (None, Some(mins)) => mins
+                .checked_mul(60)
+                .and_then(|secs| i64::try_from(secs).ok())
+                .and_then(Duration::try_seconds)
+                .filter(|every| Local::now().checked_add_signed(*every).is_some())
+                .map(Cadence::Every)
+                .with_context(|| format!("`every_mins = {}` is too large", mins)),
+            (Some(_), Some(_)) => bail!("set `at` or `every_mins`, not both"),
+            (None, None) => bail!("set `at` or `every_mins`"),
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Cadence::Daily(time) => format!("daily at {}", time.format("%H:%M")),
+            Cadence::Every(every) if every.num_minutes() % 60 == 0 => format!("every {}h", every.num_hours()),
+            Cadence::Every(every) => format!("every {}m", every.num_minutes()),
+        }
+    }
+}
+
+/// The first run strictly after `after`
+pub fn next_run<Tz: TimeZone>(cadence: Cadence, after: &DateTime<Tz>) -> DateTime<Tz> {
+    match cadence {
+        Cadence::Every(every) => after.clone() + every,
+        Cadence::Daily(time) => {
+            let tz = after.timezone();
+            let mut day = after.date_naive();
+            loop {
+                // A time skipped by a DST change runs the next day
+                if let Some(at) = tz.from_local_datetime(&day.and_time(time)).earliest() {
+                    if at > *after {
+                        return at;
+                    }
+                }
+                day = day.succ_opt().expect("date in range");
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Task {
+    Prompt { text: String, model: Option<String> },
+    Workflow(String),
+}
+
+impl Task {
+    /// The task a `[[schedules]]` or `[[watch]]` entry names; exactly one of
+    /// `prompt` and `workflow` must be set
+    pub fn new(prompt: &Option<String>, workflow: &Option<String>, model: &Option<String>) -> Result<Self> {
+        match (prompt, workflow) {
+            (Some(text), None) => Ok(Task::Prompt {
+                text: text.clone(),
+                model: model.clone(),
+            }),
+            (None, Some(name)) => Ok(Task::Workflow(name.clone())),
+            (Some(_), Some(_)) => bail!("set `prompt` or `workflow`, not both"),
+            (None, None) => bail!("set `prompt` or `workflow`"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LastRun {
+    /// Unix seconds the run started
+    pub at: i64,
+    pub ok: bool,
+    pub outcome: String,
+    /// Unix seconds the last successful run started, which `changed_only`
+    /// counts changes from
+    #[serde(default)]
+    pub ok_at: Option<i64>,
+}
+
+impl LastRun {
+    /// Files modified after this were not seen by a successful run
+    fn changes_since(&self) -> Option<i64> {
+        self.ok_at.or(self.ok.then_some(self.at))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Schedule {
+    pub name: String,
+    pub task: Task,
+    pub cadence: Cadence,
+    pub files: Vec<PathBuf>,
+    pub changed_only: bool,
+    pub next: DateTime<Local>,
+    pub last: Option<LastRun>,
+    pub paused: bool,
+    pub running: bool,
+    /// Unix seconds the running run started
+    pub started: Option<i64>,
+}
+
+/// Schedules overlay ("Schedules: Show Upcoming Runs")
+#[derive(Debug, Default)]
+pub struct SchedulesView {
+    pub schedules: Vec<Schedule>,
+    /// Entries that could not be scheduled
+    pub errors: Vec<String>,
+    pub selected: usize,
+    /// Request key of each running scheduled prompt → schedule index
+    pub prompts: HashMap<String, usize>,
+    /// Schedule that started the running pipeline
+    pub pipeline: Option<usize>,
+    /// Where last runs are kept; unset, they are not saved
+    pub runs_path: Option<PathBuf>,
+}
+
+/// `.ims/schedules.json` under the current working directory
+pub fn runs_path() -> PathBuf {
+    crate::app::ims_dir().join("schedules.json")
+}
+
+fn load_runs(path: &Path) -> Result<BTreeMap<String, LastRun>> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => serde_json::from_str(&text).with_context(|| format!("Corrupt schedule runs in {}", path.display())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+        Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    }
+}
+
+fn save_runs(path: &Path, runs: &BTreeMap<String, LastRun>) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(runs)?).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Files under `paths`, skipping hidden entries, modified after `since`
+fn collect_files(paths: &[PathBuf], since: Option<i64>) -> Vec<PathBuf> {
+    fn walk(path: &Path, out: &mut Vec<PathBuf>) {
+        if path.is_dir() {
+            let Ok(entries) = std::fs::read_dir(path) else { return };
+            let mut children: Vec<PathBuf> = entries
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|p| !p.file_name().is_some_and(|n| n.to_string_lossy().starts_with('.')))
+                .collect();
+            children.sort();
+            children.iter().for_each(|child| walk(child, out));
+        } else if path.is_file() {
+            out.push(path.to_path_buf());
+        }
+    }
+    let mut files = Vec::new();
+    paths.iter().for_each(|path| walk(path, &mut files));
+    if let Some(since) = since {
+        files.retain(|path| {
+            std::fs::metadata(path)
+                .and_then(|m| m.modified())
+                .map(|modified| DateTime::<chrono::Utc>::from(modified).timestamp() > since)
+                .unwrap_or(true)
+        });
+    }
+    files
+}
+
+impl AppState {
+    /// Schedule the `[[schedules]]` entries from now, with their last runs
+    /// from `runs_path`
+    pub fn load_schedules(&mut self, runs_path: PathBuf) {
+        let runs = load_runs(&runs_path).unwrap_or_else(|e| {
+            self.add_debug_log(format!("Ignoring schedule runs: {:#}", e));
+            BTreeMap::new()
+        });
+        let now = Local::now();
+        let (mut schedules, mut errors) = (Vec::new(), Vec::new());
+        for entry in &self.config.schedules {
+            // Last runs are kept by name
+            if schedules.iter().any(|s: &Schedule| s.name == entry.name) {
+                errors.push(format!("{}: another schedule has this name", entry.name));
+                continue;
+            }
+            let parsed = Task::new(&entry.prompt, &entry.workflow, &entry.model)
+                .and_then(|task| Ok((task, Cadence::from_entry(entry)?)));
+            match parsed {
+                Ok((task, cadence)) => schedules.push(Schedule {
+                    name: entry.name.clone(),
+                    task,
+                    cadence,
+                    files: entry.files.clone(),
+                    changed_only: entry.changed_only,
+                    next: next_run(cadence, &now),
+                    last: runs.get(&entry.name).cloned(),
+                    paused: false,
+                    running: false,
+                    started: None,
+                }),
+                Err(e) => errors.push(format!("{}: {:#}", entry.name, e)),
+            }
+        }
+        if !schedules.is_empty() {
+            self.add_debug_log(format!("{} schedule(s) loaded", schedules.len()));
+        }
+        for error in &errors {
+            self.add_debug_log(format!("Invalid schedule {}", error));
+        }
+        self.schedules_view = SchedulesView {
+            schedules,
+            errors,
+            runs_path: Some(runs_path),
+            ..Default::default()
+        };
+    }
+
+    /// Schedules whose next run is at or before `now`
+    pub fn due_schedules(&self, now: DateTime<Local>) -> Vec<usize> {
+        let schedules = &self.schedules_view.schedules;
+        (0..schedules.len())
+            .filter(|&i| !schedules[i].paused && !schedules[i].running && schedules[i].next <= now)
+            .collect()
+    }
+
+    /// Mark a schedule as running and move its next run on
+    pub fn begin_schedule(&mut self, index: usize, now: DateTime<Local>) {
+        let Some(schedule) = self.schedules_view.schedules.get_mut(index) else { return };
+        schedule.running = true;
+        schedule.started = Some(now.timestamp());
+        schedule.next = next_run(schedule.cadence, &now);
+        let name = schedule.name.clone();
+        self.add_thinking(ThinkingEntry::system(format!("⏰ Running schedule '{}'", name)));
+    }
+
+    /// Files a scheduled workflow runs on, with the model to use for each
+    pub fn schedule_files(&self, index: usize) -> Vec<(PathBuf, String)> {
+        let Some(schedule) = self.schedules_view.schedules.get(index) else { return Vec::new() };
+        let since = schedule.last.as_ref().filter(|_| schedule.changed_only).and_then(LastRun::changes_since);
+        let fallback = self.session.as_ref().map(|s| s.model_id.clone()).unwrap_or("gpt-4o".to_string());
+        collect_files(&schedule.files, since)
+            .into_iter()
+            .map(|path| {
+                let model = Self::find_node_recursive(&self.file_tree, &path.to_string_lossy())
+                    .map(|n| n.model.clone())
+                    .filter(|m| !m.is_empty())
+                    .unwrap_or_else(|| fallback.clone());
+                (path, model)
+            })
+            .collect()
+    }
+
+    /// Record how a run went and report it
+    pub fn finish_schedule(&mut self, index: usize, ok: bool, outcome: String) {
+        let Some(schedule) = self.schedules_view.schedules.get_mut(index) else { return };
+        schedule.running = false;
+        let name = schedule.name.clone();
+        let at = schedule.started.take().unwrap_or_else(|| chrono::Utc::now().timestamp());
+        let ok_at = if ok { Some(at) } else { schedule.last.as_ref().and_then(LastRun::changes_since) };
+        schedule.last = Some(LastRun {
+            at,
+            ok,
+            outcome: outcome.clone(),
+            ok_at,
+        });
+
+        let (entry, level, message) = if ok {
+            (
+                ThinkingEntry::system(format!("⏰ Schedule '{}' finished: {}", name, outcome)),
+                NotificationLevel::Info,
+                format!("Schedule '{}' finished", name),
+            )
+        } else {
+            (
+                ThinkingEntry::system(format!("✗ Schedule '{}' failed: {}", name, outcome)).with_level(Level::Error),
+                NotificationLevel::Error,
+                format!("Schedule '{}' failed: {}", name, outcome),
+            )
+        };
+        self.add_thinking(entry);
+        reduce(self, Event::NotificationShown { level, message });
+
+        let Some(path) = self.schedules_view.runs_path.clone() else { return };
+        let mut runs = load_runs(&path).unwrap_or_default();
+        for schedule in &self.schedules_view.schedules {
+            if let Some(last) = &schedule.last {
+                runs.insert(schedule.name.clone(), last.clone());
+            }
+        }
+        if let Err(e) = save_runs(&path, &runs) {
+            self.add_debug_log(format!("Failed to save schedule runs: {:#}", e));
+        }
+    }
+
+    /// A prompt finished; report it if a schedule sent it
+    pub fn scheduled_prompt_finished(&mut self, key: &str, error: Option<&str>) {
+        let Some(index) = self.schedules_view.prompts.remove(key) else { return };
+        match error {
+            None => self.finish_schedule(index, true, "response received".to_string()),
+            Some(error) => self.finish_schedule(index, false, error.to_string()),
+        }
+    }
+
+    /// The pipeline finished; report it if a schedule started it
+    pub fn scheduled_pipeline_finished(&mut self, completed: usize, total: usize) {
+        let Some(index) = self.schedules_view.pipeline.take() else { return };
+        let outcome = format!("{} of {} target(s) completed", completed, total);
+        self.finish_schedule(index, completed == total, outcome);
+    }
+
+    pub fn open_schedules(&mut self) {
+        let view = &mut self.schedules_view;
+        view.selected = view.selected.min(view.schedules.len().saturating_sub(1));
+        self.open_overlay(Overlay::Schedules);
+    }
+
+    /// Stop or restart the selected schedule's runs for this session
+    pub fn pause_selected_schedule(&mut self) {
+        let view = &mut self.schedules_view;
+        let Some(schedule) = view.schedules.get_mut(view.selected) else { return };
+        schedule.paused = !schedule.paused;
+        if !schedule.paused {
+            schedule.next = next_run(schedule.cadence, &Local::now());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn entry(name: &str) -> ScheduleEntry {
+        ScheduleEntry {
+            name: name.to_string(),
+            prompt: Some("Summarize today's changes".to_string()),
+            every_mins: Some(30),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_next_run() {
+        let noon = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+        let daily = Cadence::Daily(NaiveTime::from_hms_opt(2, 0, 0).unwrap());
+        assert_eq!(next_run(daily, &noon), Utc.with_ymd_and_hms(2024, 5, 2, 2, 0, 0).unwrap());
+        let early = Utc.with_ymd_and_hms(2024, 5, 1, 1, 0, 0).unwrap();
+        assert_eq!(next_run(daily, &early), Utc.with_ymd_and_hms(2024, 5, 1, 2, 0, 0).unwrap());
+        assert_eq!(next_run(Cadence::Every(Duration::minutes(90)), &noon), noon + Duration::minutes(90));
+        assert_eq!(daily.label(), "daily at 02:00");
+        assert_eq!(Cadence::Every(Duration::minutes(120)).label(), "every 2h");
+    }
+
+    #[test]
+    fn test_invalid_entries_are_listed() {
+        let both = ScheduleEntry {
+            at: Some("02:00".to_string()),
+            ..entry("both")
+        };
+        let bad_time = ScheduleEntry {
+            at: Some("2am".to_string()),
+            every_mins: None,
+            ..entry("bad-time")
+        };
+        let mut state = AppState::default();
+        let huge = ScheduleEntry {
+            every_mins: Some(u64::MAX),
+            ..entry("huge")
+        };
+        state.config.schedules = vec![entry("ok"), both, bad_time, huge, entry("ok")];
+        let path = std::env::temp_dir().join(format!("ims-schedules-{}.json", uuid::Uuid::new_v4()));
+        state.load_schedules(path.clone());
+        assert_eq!(state.schedules_view.schedules.len(), 1);
+        assert_eq!(state.schedules_view.errors.len(), 4);
+        assert!(state.schedules_view.errors[0].starts_with("both: "));
+        assert!(state.schedules_view.errors[2].contains("too large"));
+        assert!(state.schedules_view.errors[3].contains("another schedule has this name"));
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_due_run_is_reported_and_saved() {
+        let mut state = AppState::default();
+        state.config.schedules = vec![entry("summary")];
+        let path = std::env::temp_dir().join(format!("ims-schedules-{}.json", uuid::Uuid::new_v4()));
+        state.load_schedules(path.clone());
+
+        let later = Local::now() + Duration::minutes(31);
+        assert_eq!(state.due_schedules(later), [0]);
+        state.begin_schedule(0, later);
+        assert!(state.due_schedules(later).is_empty());
+
+        state.schedules_view.prompts.insert("k1".to_string(), 0);
+        state.scheduled_prompt_finished("k1", Some("HTTP 500"));
+        let schedule = &state.schedules_view.schedules[0];
+        assert!(!schedule.running);
+        assert!(!schedule.last.as_ref().unwrap().ok);
+        assert!(state.thinking_log.iter().any(|e| e.text.contains("Schedule 'summary' failed: HTTP 500")));
+
+        // The next session sees the last run
+        state.load_schedules(path.clone());
+        assert_eq!(state.schedules_view.schedules[0].last.as_ref().unwrap().outcome, "HTTP 500");
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_changes_count_from_the_last_successful_start() {
+        let mut state = AppState::default();
+        state.config.schedules = vec![entry("summary")];
+        state.load_schedules(std::env::temp_dir().join(format!("ims-schedules-{}.json", uuid::Uuid::new_v4())));
+        state.schedules_view.runs_path = None;
+
+        let first = Local::now() - Duration::hours(2);
+        state.begin_schedule(0, first);
+        state.finish_schedule(0, true, "ok".to_string());
+        let last = state.schedules_view.schedules[0].last.clone().unwrap();
+        assert_eq!(last.at, first.timestamp());
+
+        // A failed run doesn't move where changes are counted from
+        state.begin_schedule(0, first + Duration::hours(1));
+        state.finish_schedule(0, false, "HTTP 500".to_string());
+        let last = state.schedules_view.schedules[0].last.clone().unwrap();
+        assert_eq!(last.at, (first + Duration::hours(1)).timestamp());
+        assert_eq!(last.changes_since(), Some(first.timestamp()));
+        assert!(state.debug_logs.iter().any(|l| l.contains("Schedule 'summary' failed: HTTP 500")));
+    }
+}
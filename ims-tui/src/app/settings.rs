@@ -1,40 +1,314 @@
-pub enum SettingValue {
-    Toggle(bool),
-    Slider(u8, u8, u8), // Current, Min, Max
-    Select(Vec<String>, usize), // Options, Selected Index
-}
-
-pub struct SettingItem {
-    pub key: String,
-    pub label: String,
-    pub description: String,
-    pub value: SettingValue,
-}
-
-pub struct SettingsState {
-    pub items: Vec<SettingItem>,
-    pub selected_index: usize,
-}
-
-impl SettingsState {
-    pub fn new() -> Self {
-        Self {
-            items: vec![
-                SettingItem {
-                    key: "global_auto_scroll".to_string(),
-                    label: "Global Auto-Scroll".to_string(),
-                    description: "Automatically scroll to the bottom of live streams.".to_string(),
-                    value: SettingValue::Toggle(true),
-                },
-                SettingItem {
-                    key: "theme_cursor".to_string(),
-                    label: "Vendor Cursor".to_string(),
-                    description: "Use the model's logo as the active typing cursor.".to_string(),
-                    value: SettingValue::Toggle(true),
-                },
-                // ADD NEW SETTINGS HERE: Claude can just append to this list
-            ],
-            selected_index: 0,
-        }
-    }
-}
\ No newline at end of file
+//! Settings Model
+//!
+//! Editable preferences shown in the Settings overlay. Items are built
+//! from the live `AppState` when the overlay opens, edited generically by
+//! value kind, and written back through `apply`.
+
+use crate::app::{
+    config::{ColorMode, Density, SymbolMode},
+    AppState,
+};
+
+pub enum SettingValue {
+    Toggle(bool),
+    Slider(u8, u8, u8), // Current, Min, Max
+    Select(Vec<String>, usize), // Options, Selected Index
+}
+
+impl SettingValue {
+    /// Left/Right: flip a toggle, step a slider, or move through a select
+    pub fn step(&mut self, forward: bool) {
+        match self {
+            SettingValue::Toggle(on) => *on = !*on,
+            SettingValue::Slider(current, min, max) => {
+                *current = if forward {
+                    current.saturating_add(1).min(*max)
+                } else {
+                    current.saturating_sub(1).max(*min)
+                };
+            }
+            SettingValue::Select(options, selected) => {
+                let len = options.len().max(1);
+                *selected = if forward {
+                    (*selected + 1) % len
+                } else {
+                    (*selected + len - 1) % len
+                };
+            }
+        }
+    }
+
+    /// Enter: like `step` forward, but sliders are left alone
+    pub fn activate(&mut self) {
+        if !matches!(self, SettingValue::Slider(..)) {
+            self.step(true);
+        }
+    }
+
+    pub fn display(&self) -> String {
+        match self {
+            SettingValue::Toggle(true) => "[x] On".to_string(),
+            SettingValue::Toggle(false) => "[ ] Off".to_string(),
+            SettingValue::Slider(current, min, max) => {
+                let filled = (current - min) as usize;
+                let empty = (max - current) as usize;
+                format!("◀ {}{} ▶ {}", "█".repeat(filled), "░".repeat(empty), current)
+            }
+            SettingValue::Select(options, selected) => {
+                format!("◀ {} ▶", options.get(*selected).map(String::as_str).unwrap_or("-"))
+            }
+        }
+    }
+}
+
+pub struct SettingItem {
+    pub key: String,
+    pub label: String,
+    pub description: String,
+    pub value: SettingValue,
+}
+
+#[derive(Default)]
+pub struct SettingsState {
+    pub items: Vec<SettingItem>,
+    pub selected_index: usize,
+}
+
+/// Poll interval choices offered in Settings, in seconds
+const METRICS_INTERVALS: [u64; 5] = [2, 5, 10, 30, 60];
+const HEALTH_INTERVALS: [u64; 5] = [10, 15, 30, 60, 120];
+
+/// `presets` plus `current` (if configured to something else), as a select
+fn interval_select(current: u64, presets: &[u64]) -> SettingValue {
+    let mut options = presets.to_vec();
+    if !options.contains(&current) {
+        options.push(current);
+        options.sort_unstable();
+    }
+    let selected = options.iter().position(|s| *s == current).unwrap_or(0);
+    SettingValue::Select(options.iter().map(|s| format!("{}s", s)).collect(), selected)
+}
+
+/// Seconds from an `interval_select` option
+fn interval_secs(options: &[String], selected: usize) -> Option<u64> {
+    options.get(selected)?.trim_end_matches('s').parse().ok()
+}
+
+fn item(key: &str, label: &str, description: &str, value: SettingValue) -> SettingItem {
+    SettingItem {
+        key: key.to_string(),
+        label: label.to_string(),
+        description: description.to_string(),
+        value,
+    }
+}
+
+impl SettingsState {
+    /// Current values from `state`
+    pub fn from_state(state: &AppState) -> Self {
+        let ui = &state.config.ui;
+        let symbols = SymbolMode::ALL.iter().position(|m| *m == ui.symbols).unwrap_or(0);
+        let colors = ColorMode::ALL.iter().position(|m| *m == ui.colors).unwrap_or(0);
+        let density = Density::ALL.iter().position(|d| *d == ui.density).unwrap_or(0);
+        Self {
+            items: vec![
+                item(
+                    "global_auto_scroll",
+                    "Global Auto-Scroll",
+                    "Automatically scroll to the bottom of live streams.",
+                    SettingValue::Toggle(state.global_auto_scroll),
+                ),
+                item(
+                    "theme_cursor",
+                    "Vendor Cursor",
+                    "Use the model's logo as the active typing cursor.",
+                    SettingValue::Toggle(state.vendor_cursor),
+                ),
+                item(
+                    "render_markdown",
+                    "Markdown",
+                    "Render responses as formatted Markdown instead of plain text.",
+                    SettingValue::Toggle(state.render_markdown),
+                ),
+                item(
+                    "symbols",
+                    "Symbols",
+                    "Emoji or ASCII status symbols; Auto decides from TERM and the locale.",
+                    SettingValue::Select(
+                        SymbolMode::ALL.iter().map(|m| m.label().to_string()).collect(),
+                        symbols,
+                    ),
+                ),
+                item(
+                    "colors",
+                    "Colors",
+                    "Colors the terminal can show; fewer than 24-bit maps to the nearest. Auto decides from COLORTERM and TERM.",
+                    SettingValue::Select(ColorMode::ALL.iter().map(|m| m.label().to_string()).collect(), colors),
+                ),
+                item(
+                    "density",
+                    "Density",
+                    "Compact fits more on screen: a one-row model header and no age column in Thinking.",
+                    SettingValue::Select(Density::ALL.iter().map(|d| d.label().to_string()).collect(), density),
+                ),
+                item(
+                    "bold_keywords",
+                    "Bold Keywords",
+                    "Set language keywords in bold in the Generation pane.",
+                    SettingValue::Toggle(ui.bold_keywords),
+                ),
+                item(
+                    "dim_inactive",
+                    "Dim Inactive Panes",
+                    "Dim every pane but the focused one.",
+                    SettingValue::Toggle(ui.dim_inactive),
+                ),
+                item(
+                    "window_title",
+                    "Window Title",
+                    "Show the file, model and whether a generation is running in the terminal's title (tmux pane title).",
+                    SettingValue::Toggle(ui.window_title),
+                ),
+                item(
+                    "scroll_lines",
+                    "Scroll Speed",
+                    "Lines per mouse wheel notch in the Thinking and Generation panes.",
+                    SettingValue::Slider(state.scroll_lines, 1, 10),
+                ),
+                item(
+                    "show_sidebar",
+                    "Show Sidebar",
+                    "Show the file explorer column.",
+                    SettingValue::Toggle(state.show_sidebar),
+                ),
+                item(
+                    "show_inspector",
+                    "Show Inspector",
+                    "Show the session inspector column.",
+                    SettingValue::Toggle(state.show_inspector),
+                ),
+                item(
+                    "metrics_secs",
+                    "Metrics Poll",
+                    "How often IMS Core metrics are fetched.",
+                    interval_select(state.config.polling.metrics_secs, &METRICS_INTERVALS),
+                ),
+                item(
+                    "health_secs",
+                    "Health Poll",
+                    "How often IMS Core's health is checked.",
+                    interval_select(state.config.polling.health_secs, &HEALTH_INTERVALS),
+                ),
+            ],
+            selected_index: 0,
+        }
+    }
+
+    pub fn selected(&self) -> Option<&SettingItem> {
+        self.items.get(self.selected_index)
+    }
+
+    pub fn selected_mut(&mut self) -> Option<&mut SettingItem> {
+        self.items.get_mut(self.selected_index)
+    }
+
+    pub fn select_next(&mut self) {
+        if !self.items.is_empty() {
+            self.selected_index = (self.selected_index + 1) % self.items.len();
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        if !self.items.is_empty() {
+            self.selected_index = (self.selected_index + self.items.len() - 1) % self.items.len();
+        }
+    }
+}
+
+/// Write `item` into `state`, then refresh its `[ui]` config section
+pub fn apply(state: &mut AppState, item: &SettingItem) {
+    match (item.key.as_str(), &item.value) {
+        ("global_auto_scroll", SettingValue::Toggle(on)) => state.set_auto_scroll(*on),
+        ("theme_cursor", SettingValue::Toggle(on)) => state.vendor_cursor = *on,
+        ("render_markdown", SettingValue::Toggle(on)) => state.render_markdown = *on,
+        ("symbols", SettingValue::Select(_, selected)) => {
+            state.set_symbol_mode(SymbolMode::ALL.get(*selected).copied().unwrap_or_default());
+        }
+        ("colors", SettingValue::Select(_, selected)) => {
+            state.set_color_mode(ColorMode::ALL.get(*selected).copied().unwrap_or_default());
+        }
+        ("density", SettingValue::Select(_, selected)) => {
+            state.config.ui.density = Density::ALL.get(*selected).copied().unwrap_or_default();
+        }
+        ("bold_keywords", SettingValue::Toggle(on)) => state.config.ui.bold_keywords = *on,
+        ("dim_inactive", SettingValue::Toggle(on)) => state.config.ui.dim_inactive = *on,
+        ("window_title", SettingValue::Toggle(on)) => state.config.ui.window_title = *on,
+        ("scroll_lines", SettingValue::Slider(lines, _, _)) => state.scroll_lines = *lines,
+        ("show_sidebar", SettingValue::Toggle(on)) => {
+            state.show_sidebar = *on;
+            state.ensure_focus_visible();
+        }
+        ("show_inspector", SettingValue::Toggle(on)) => {
+            state.show_inspector = *on;
+            state.ensure_focus_visible();
+        }
+        ("metrics_secs", SettingValue::Select(options, selected)) => {
+            if let Some(secs) = interval_secs(options, *selected) {
+                state.config.polling.metrics_secs = secs;
+            }
+        }
+        ("health_secs", SettingValue::Select(options, selected)) => {
+            if let Some(secs) = interval_secs(options, *selected) {
+                state.config.polling.health_secs = secs;
+            }
+        }
+        _ => return,
+    }
+    state.config.ui = state.ui_config();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_value_stepping() {
+        let mut slider = SettingValue::Slider(9, 1, 10);
+        slider.step(true);
+        slider.step(true);
+        assert!(matches!(slider, SettingValue::Slider(10, 1, 10)));
+        slider.activate();
+        assert!(matches!(slider, SettingValue::Slider(10, 1, 10)));
+
+        let mut select = SettingValue::Select(vec!["a".into(), "b".into()], 0);
+        select.step(false);
+        assert!(matches!(select, SettingValue::Select(_, 1)));
+    }
+
+    #[test]
+    fn test_apply_updates_state_and_config() {
+        let mut state = AppState::default();
+        let mut settings = SettingsState::from_state(&state);
+        settings.selected_index = settings.items.iter().position(|i| i.key == "scroll_lines").unwrap();
+        settings.selected_mut().unwrap().value.step(true);
+
+        apply(&mut state, settings.selected().unwrap());
+
+        assert_eq!(state.scroll_lines, 2);
+        assert_eq!(state.config.ui.scroll_lines, 2);
+    }
+
+    #[test]
+    fn test_poll_interval_select() {
+        let mut state = AppState::default();
+        state.config.polling.health_secs = 45;
+        let mut settings = SettingsState::from_state(&state);
+        settings.selected_index = settings.items.iter().position(|i| i.key == "health_secs").unwrap();
+        assert_eq!(settings.selected().unwrap().value.display(), "◀ 45s ▶");
+        settings.selected_mut().unwrap().value.step(true);
+
+        apply(&mut state, settings.selected().unwrap());
+
+        assert_eq!(state.config.polling.health_secs, 60);
+    }
+}
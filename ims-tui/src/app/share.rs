@@ -0,0 +1,319 @@
+//! Session Sharing
+//!
+//! "Session: Export Archive" packs the conversation, the Generation pane,
+//! display settings and model choices into a `.imssession` file: a zip
+//! holding `manifest.json` plus one JSON (or text) file per part. A
+//! teammate opens it from the Explorer (or "Session: Import Selected
+//! Archive") to pick up the same session.
+//!
+//! `manifest.json` carries the format version; archives from a newer
+//! IMS-TUI are refused instead of being half-read. Secrets are masked in
+//! everything written, as in other exports.
+
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::Local;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use super::{
+    config::UiConfig,
+    redact::Redactor,
+    thinking::ThinkingEntry,
+    ActiveSession, AppState, ConversationTurn, FileNode,
+};
+
+/// File extension of session archives
+pub const EXTENSION: &str = "imssession";
+
+/// Written into every manifest; bumped when the layout changes
+pub const FORMAT_VERSION: u32 = 1;
+
+const FORMAT: &str = "imssession";
+const MANIFEST: &str = "manifest.json";
+const SESSION: &str = "session.json";
+const CONVERSATION: &str = "conversation.json";
+const GENERATION: &str = "generation.txt";
+const SETTINGS: &str = "settings.json";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub format: String,
+    pub version: u32,
+    /// Unix seconds
+    pub created: i64,
+    pub user_id: String,
+    pub app_version: String,
+}
+
+/// The open file and the model chosen for each file
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelChoices {
+    pub file: Option<PathBuf>,
+    pub model_id: Option<String>,
+    /// Explorer files and their models
+    pub files: Vec<(PathBuf, String)>,
+    /// File type → model, from "Set as Default for File Type"
+    pub defaults: HashMap<String, String>,
+}
+
+/// Everything in a `.imssession` archive
+#[derive(Clone, Debug)]
+pub struct SessionArchive {
+    pub manifest: Manifest,
+    pub models: ModelChoices,
+    pub conversation: Vec<ConversationTurn>,
+    pub generation: String,
+    pub settings: UiConfig,
+}
+
+/// Whether `path` looks like a session archive
+pub fn is_archive(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == EXTENSION)
+}
+
+fn files(nodes: &[FileNode], out: &mut Vec<(PathBuf, String)>) {
+    for node in nodes {
+        if node.is_dir {
+            files(&node.children, out);
+        } else {
+            out.push((node.path.clone(), node.model.clone()));
+        }
+    }
+}
+
+impl SessionArchive {
+    pub fn capture(state: &AppState) -> Self {
+        let mut models = ModelChoices {
+            file: state.session.as_ref().map(|s| s.file_path.clone()),
+            model_id: state.session.as_ref().map(|s| s.model_id.clone()),
+            defaults: state.model_defaults.clone(),
+            ..Default::default()
+        };
+        files(&state.file_tree, &mut models.files);
+        Self {
+            manifest: Manifest {
+                format: FORMAT.to_string(),
+                version: FORMAT_VERSION,
+                created: Local::now().timestamp(),
+                user_id: state.user_id.clone(),
+                app_version: env!("CARGO_PKG_VERSION").to_string(),
+            },
+            models,
+            conversation: state.conversation.iter().filter(|t| t.response.is_some()).cloned().collect(),
+            generation: state.generated_code.clone(),
+            settings: state.ui_config(),
+        }
+    }
+
+    /// A copy with secrets in the free text masked. Done before
+    /// serializing: JSON escaping of quotes and newlines would hide
+    /// `password = "..."` and `.env` lines from the patterns.
+    fn redacted(&self, redactor: &Redactor) -> Self {
+        let mask = |text: &str| redactor.redact(text).into_owned();
+        let mut archive = self.clone();
+        for turn in &mut archive.conversation {
+            turn.prompt = mask(&turn.prompt);
+            turn.response = turn.response.as_deref().map(mask);
+            turn.summary = turn.summary.as_deref().map(mask);
+        }
+        archive.generation = mask(&archive.generation);
+        archive
+    }
+
+    /// Write the archive to `path`, masking secrets with `redactor`
+    pub fn write(&self, path: &Path, redactor: &Redactor) -> Result<()> {
+        let archive = self.redacted(redactor);
+        let file = std::fs::File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+        let mut zip = zip::ZipWriter::new(file);
+        let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        let parts = [
+            (MANIFEST, serde_json::to_string_pretty(&archive.manifest)?),
+            (SESSION, serde_json::to_string_pretty(&archive.models)?),
+            (CONVERSATION, serde_json::to_string_pretty(&archive.conversation)?),
+            (GENERATION, archive.generation),
+            (SETTINGS, serde_json::to_string_pretty(&archive.settings)?),
+        ];
+        for (name, text) in parts {
+            zip.start_file(name, options)?;
+            zip.write_all(text.as_bytes())?;
+        }
+        zip.finish().with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(())
+    }
+
+    pub fn read(path: &Path) -> Result<Self> {
+        let file = std::fs::File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+        let mut zip = zip::ZipArchive::new(file).with_context(|| format!("{} is not a session archive", path.display()))?;
+        let mut text = |name: &str| -> Result<String> {
+            let mut text = String::new();
+            zip.by_name(name)
+                .with_context(|| format!("{} is missing from the archive", name))?
+                .read_to_string(&mut text)?;
+            Ok(text)
+        };
+        fn json<T: DeserializeOwned>(name: &str, text: String) -> Result<T> {
+            serde_json::from_str(&text).with_context(|| format!("Invalid {}", name))
+        }
+
+        let manifest: Manifest = json(MANIFEST, text(MANIFEST)?)?;
+        anyhow::ensure!(manifest.format == FORMAT, "Not a session archive (format {:?})", manifest.format);
+        anyhow::ensure!(
+            manifest.version <= FORMAT_VERSION,
+            "Archive format v{} needs a newer IMS-TUI (this one reads up to v{})",
+            manifest.version,
+            FORMAT_VERSION
+        );
+        Ok(Self {
+            manifest,
+            models: json(SESSION, text(SESSION)?)?,
+            conversation: json(CONVERSATION, text(CONVERSATION)?)?,
+            generation: text(GENERATION)?,
+            settings: json(SETTINGS, text(SETTINGS)?)?,
+        })
+    }
+
+    /// Replace the current session with the archived one
+    pub fn apply(self, state: &mut AppState) {
+        for (path, model) in &self.models.files {
+            let id = path.to_string_lossy();
+            if AppState::find_node_recursive(&state.file_tree, &id).is_none() {
+                state.add_file(path.clone());
+            }
+            if let Some(node) = AppState::find_node_recursive_mut(&mut state.file_tree, &id) {
+                node.model = model.clone();
+            }
+        }
+        state.model_defaults.extend(self.models.defaults);
+
+        state.session = match (self.models.file, self.models.model_id) {
+            (Some(file), Some(model_id)) => Some(ActiveSession::new(file, state.vendors.for_model(&model_id), model_id)),
+            _ => None,
+        };
+        state.thinking_log.clear();
+        state.code_blocks.clear();
+        state.selection = None;
+        for turn in &self.conversation {
+            state.add_thinking(ThinkingEntry::user(turn.prompt.clone()));
+            if let Some(response) = &turn.response {
+                state.add_thinking(ThinkingEntry::agent(response.clone()));
+            }
+        }
+        state.conversation = self.conversation;
+        state.generated_code = self.generation;
+        state.clear_generation_regions();
+        state.config.ui = self.settings;
+        state.apply_ui_config();
+    }
+}
+
+/// Where exported archives go
+pub fn exports_dir() -> PathBuf {
+    crate::app::ims_dir().join("exports")
+}
+
+impl AppState {
+    /// Export the session to `.ims/exports`
+    pub fn export_session(&mut self) {
+        let dir = exports_dir();
+        let path = dir.join(format!("session-{}.{}", Local::now().format("%Y%m%d-%H%M%S"), EXTENSION));
+        let written = std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))
+            .and_then(|_| SessionArchive::capture(self).write(&path, &self.redactor));
+        match written {
+            Ok(()) => self.add_debug_log(format!("Exported session to {}", path.display())),
+            Err(e) => self.add_debug_log(format!("Failed to export session: {:#}", e)),
+        }
+    }
+
+    /// Open the session in the archive at `path`
+    pub fn import_session(&mut self, path: &Path) {
+        match SessionArchive::read(path) {
+            Ok(archive) => {
+                let from = format!("{} ({})", archive.manifest.user_id, path.display());
+                archive.apply(self);
+                self.add_debug_log(format!("Imported session from {}", from));
+            }
+            Err(e) => self.add_debug_log(format!("Failed to import {}: {:#}", path.display(), e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_archive_round_trip() {
+        let dir = std::env::temp_dir().join(format!("ims-tui-share-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("review.imssession");
+
+        let mut state = AppState {
+            user_id: "dana".to_string(),
+            generated_code: "fn main() {}".to_string(),
+            ..Default::default()
+        };
+        let mut file = FileNode::new_file(PathBuf::from("/workspace/src/main.rs"));
+        file.model = "claude-3-5-sonnet".to_string();
+        state.file_tree.push(file);
+        state.session = Some(ActiveSession::new(
+            "/workspace/src/main.rs".into(),
+            state.vendors.for_model("claude-3-5-sonnet"),
+            "claude-3-5-sonnet".to_string(),
+        ));
+        state.conversation.push(ConversationTurn {
+            response: Some("Done".to_string()),
+            ..ConversationTurn::new("Use key sk-proj-abcdefghijklmnopqrstuvwx", "claude-3-5-sonnet")
+        });
+        state.render_markdown = false;
+        SessionArchive::capture(&state).write(&path, &state.redactor).unwrap();
+
+        let mut teammate = AppState::default();
+        teammate.import_session(&path);
+        assert_eq!(teammate.session.as_ref().unwrap().model_id, "claude-3-5-sonnet");
+        assert_eq!(teammate.generated_code, "fn main() {}");
+        assert_eq!(teammate.conversation.len(), 1);
+        assert_eq!(teammate.conversation[0].prompt, "Use key [REDACTED]");
+        assert_eq!(teammate.thinking_log.len(), 2);
+        assert!(!teammate.render_markdown);
+        let node = AppState::find_node_recursive(&teammate.file_tree, "/workspace/src/main.rs").unwrap();
+        assert_eq!(node.model, "claude-3-5-sonnet");
+
+        // Archives from a newer format are refused
+        let mut newer = SessionArchive::capture(&state);
+        newer.manifest.version = FORMAT_VERSION + 1;
+        newer.write(&path, &state.redactor).unwrap();
+        assert!(SessionArchive::read(&path).is_err());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_quoted_and_env_secrets_are_masked() {
+        let dir = std::env::temp_dir().join(format!("ims-tui-share-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("secrets.imssession");
+
+        let mut state = AppState {
+            generated_code: "cfg.password = \"hunter2hunter2\";\n".to_string(),
+            ..Default::default()
+        };
+        state.conversation.push(ConversationTurn {
+            response: Some("Moved it:\nexport API_KEY=abcd1234efgh5678\nDone".to_string()),
+            ..ConversationTurn::new(
+                "Why does password = \"hunter2hunter2\" fail?\nexport API_KEY=abcd1234efgh5678\n",
+                "gpt-4o",
+            )
+        });
+        SessionArchive::capture(&state).write(&path, &state.redactor).unwrap();
+
+        let archive = SessionArchive::read(&path).unwrap();
+        let turn = &archive.conversation[0];
+        assert_eq!(turn.prompt, "Why does password = \"[REDACTED]\" fail?\nexport API_KEY=[REDACTED]\n");
+        assert_eq!(turn.response.as_deref(), Some("Moved it:\nexport API_KEY=[REDACTED]\nDone"));
+        assert_eq!(archive.generation, "cfg.password = \"[REDACTED]\";\n");
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,135 @@
+//! Status Bar Segments
+//!
+//! The status bar is built from `[[status_bar.segments]]` in the order
+//! listed. Segments read from the session are current on every frame;
+//! `git_branch` and `script` run a command in the background every
+//! `interval_secs` and show what it last printed.
+
+use anyhow::{bail, Context, Result};
+use std::time::{Duration, Instant};
+
+use crate::app::{config::Segment, AppState};
+use crate::core::runtime::{request_mutation, EventSender};
+
+/// A segment command that runs longer is stopped and counts as failed
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Last output of a command-backed segment
+#[derive(Clone, Debug, Default)]
+pub struct SegmentSlot {
+    value: Option<String>,
+    next: Option<Instant>,
+    running: bool,
+}
+
+/// Command-backed segments' output, by position in the segment list
+#[derive(Clone, Debug, Default)]
+pub struct StatusSegments {
+    slots: Vec<SegmentSlot>,
+}
+
+impl StatusSegments {
+    pub fn value(&self, index: usize) -> Option<&str> {
+        self.slots.get(index)?.value.as_deref()
+    }
+}
+
+/// What `segment` runs and how often, if it runs anything
+fn command(segment: &Segment) -> Option<(Vec<String>, u64)> {
+    match segment {
+        Segment::GitBranch { interval_secs } => Some((
+            ["git", "rev-parse", "--abbrev-ref", "HEAD"].map(String::from).to_vec(),
+            *interval_secs,
+        )),
+        Segment::Script { command, interval_secs, .. } => Some((command.clone(), *interval_secs)),
+        _ => None,
+    }
+}
+
+/// Start the command of every segment that is due
+pub fn refresh_due(state: &mut AppState, tx: &EventSender) {
+    let now = Instant::now();
+    let segments = &state.config.status_bar.segments;
+    let slots = &mut state.status_segments.slots;
+    slots.resize_with(segments.len(), SegmentSlot::default);
+    for (index, (segment, slot)) in segments.iter().zip(slots.iter_mut()).enumerate() {
+        let Some((command, interval_secs)) = command(segment) else { continue };
+        if slot.running || slot.next.is_some_and(|next| now < next) {
+            continue;
+        }
+        slot.running = true;
+        slot.next = Some(now + Duration::from_secs(interval_secs.max(1)));
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            let output = run(&command).await;
+            request_mutation(&tx, move |s| s.segment_updated(index, output));
+        });
+    }
+}
+
+/// First line `command` prints
+async fn run(command: &[String]) -> Result<String> {
+    let Some((program, args)) = command.split_first() else {
+        bail!("Empty segment command");
+    };
+    let output = tokio::process::Command::new(program)
+        .args(args)
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true)
+        .output();
+    let output = tokio::time::timeout(TIMEOUT, output)
+        .await
+        .with_context(|| format!("{} timed out after {}s", program, TIMEOUT.as_secs()))?
+        .with_context(|| format!("Failed to run {}", program))?;
+    if !output.status.success() {
+        bail!("{} exited with {}", program, output.status);
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).lines().next().unwrap_or_default().trim().to_string())
+}
+
+impl AppState {
+    /// Show what segment `index`'s command printed; a failure clears it
+    pub fn segment_updated(&mut self, index: usize, output: Result<String>) {
+        let value = match output {
+            Ok(line) => Some(line).filter(|l| !l.is_empty()),
+            Err(e) => {
+                self.add_debug_log(format!("Status bar segment: {:#}", e));
+                None
+            }
+        };
+        if let Some(slot) = self.status_segments.slots.get_mut(index) {
+            slot.running = false;
+            slot.value = value;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_script_segment_shows_first_line() {
+        let mut state = AppState::default();
+        state.config.status_bar.segments = vec![
+            Segment::Focus,
+            Segment::Script {
+                command: ["sh", "-c", "printf 'load 0.42\\nmore'"].map(String::from).to_vec(),
+                label: None,
+                interval_secs: 30,
+            },
+        ];
+        let (tx, mut rx) = crate::core::bus::channel(8);
+        refresh_due(&mut state, &tx);
+        // Not due again until the interval has passed
+        refresh_due(&mut state, &tx);
+
+        let Some(crate::core::events::Event::StateMutationRequested(mutation)) = rx.recv().await else {
+            panic!("segment output not reported");
+        };
+        mutation(&mut state);
+        assert_eq!(state.status_segments.value(1), Some("load 0.42"));
+        assert_eq!(state.status_segments.value(0), None);
+        assert!(rx.try_recv().is_err());
+    }
+}
@@ -0,0 +1,140 @@
+//! Generation Tee
+//!
+//! Like `tee`: while on, everything streamed into the Generation pane is
+//! also appended to a file as it arrives, so a long generation survives a
+//! crash and can be followed with `tail -f` from another terminal. It is
+//! switched per session with "Generation: Tee to File On/Off"; `[tee] enabled`
+//! turns it on for every session opened.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::Local;
+
+use crate::app::AppState;
+
+/// The file one session's generation output is mirrored to
+#[derive(Debug)]
+pub struct TeeFile {
+    /// The session file this tee belongs to
+    pub session: PathBuf,
+    pub path: PathBuf,
+    file: std::fs::File,
+}
+
+impl TeeFile {
+    /// Start `<dir>/<file name>-<timestamp>.txt` for the session on `session`
+    pub fn create(dir: &Path, session: &Path) -> Result<Self> {
+        std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        let name = session.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        let path = dir.join(format!("{}-{}.txt", name, Local::now().format("%Y%m%d-%H%M%S")));
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        Ok(Self {
+            session: session.to_path_buf(),
+            path,
+            file,
+        })
+    }
+
+    /// Append `text` and flush it, so readers see it straight away
+    pub fn write(&mut self, text: &str) -> Result<()> {
+        self.file
+            .write_all(text.as_bytes())
+            .and_then(|_| self.file.flush())
+            .with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+}
+
+impl AppState {
+    /// Whether the current session's output is being mirrored
+    pub fn tee_active(&self) -> bool {
+        match (&self.tee, &self.session) {
+            (Some(tee), Some(session)) => tee.session == session.file_path,
+            _ => false,
+        }
+    }
+
+    /// Start mirroring the current session's output to a file
+    pub fn start_tee(&mut self) {
+        let Some(session) = self.session.as_ref().map(|s| s.file_path.clone()) else { return };
+        match TeeFile::create(&self.config.tee.dir, &session) {
+            Ok(tee) => {
+                self.add_debug_log(format!("Teeing generation output to {}", tee.path.display()));
+                self.tee = Some(tee);
+            }
+            Err(e) => self.add_debug_log(format!("Failed to start tee: {:#}", e)),
+        }
+    }
+
+    /// Turn the tee on or off for the current session
+    pub fn toggle_tee(&mut self) {
+        if self.tee_active() {
+            if let Some(tee) = self.tee.take() {
+                self.add_debug_log(format!("Stopped teeing to {}", tee.path.display()));
+            }
+        } else {
+            self.start_tee();
+        }
+    }
+
+    /// Mirror streamed output to the session's tee, if it has one
+    pub(crate) fn tee_generation(&mut self, text: &str) {
+        if !self.tee_active() {
+            return;
+        }
+        let Some(tee) = self.tee.as_mut() else { return };
+        if let Err(e) = tee.write(text) {
+            self.tee = None;
+            self.add_debug_log(format!("Tee stopped: {:#}", e));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{
+        config::{AppConfig, TeeConfig},
+        vendors::Vendor,
+        ActiveSession,
+    };
+
+    #[test]
+    fn test_streamed_output_is_mirrored_per_session() {
+        let dir = std::env::temp_dir().join(format!("ims-tui-tee-{}", uuid::Uuid::new_v4()));
+        let session = |file: &str| {
+            ActiveSession::new(file.into(), &Vendor::unknown(), "gpt-4o".to_string())
+        };
+        let mut state = AppState {
+            session: Some(session("src/main.rs")),
+            config: AppConfig {
+                tee: TeeConfig {
+                    enabled: false,
+                    dir: dir.clone(),
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        state.append_generation("before ");
+        state.toggle_tee();
+        assert!(state.tee_active());
+        state.append_generation("fn main() ");
+        state.append_generation("{}\n");
+        let path = state.tee.as_ref().unwrap().path.clone();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "fn main() {}\n");
+
+        // Another session doesn't write into this one's file
+        state.session = Some(session("src/lib.rs"));
+        assert!(!state.tee_active());
+        state.append_generation("pub mod app;");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "fn main() {}\n");
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
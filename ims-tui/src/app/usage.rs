@@ -0,0 +1,256 @@
+//! Usage Reports
+//!
+//! Successful requests from the metrics store are rolled up by day or ISO
+//! week, ranked by model spend, and exported as CSV for expense tracking.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, Local, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::app::{overlay::Overlay, AppState};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct UsageRecord {
+    /// Unix seconds
+    pub timestamp: i64,
+    pub model_id: String,
+    pub input_tokens: u32,
+    pub output_tokens: u32,
+    pub total_tokens: u32,
+    pub cost: f64,
+}
+
+impl UsageRecord {
+    pub fn time(&self) -> DateTime<Local> {
+        Local
+            .timestamp_opt(self.timestamp, 0)
+            .single()
+            .unwrap_or_else(Local::now)
+    }
+}
+
+/// Records from a JSON-lines file (the pre-SQLite format); malformed lines are skipped
+pub fn load(path: &Path) -> Result<Vec<UsageRecord>> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    };
+    Ok(text
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Period {
+    #[default]
+    Daily,
+    Weekly,
+}
+
+impl Period {
+    pub fn label(self) -> &'static str {
+        match self {
+            Period::Daily => "Daily",
+            Period::Weekly => "Weekly",
+        }
+    }
+
+    pub fn toggle(self) -> Self {
+        match self {
+            Period::Daily => Period::Weekly,
+            Period::Weekly => Period::Daily,
+        }
+    }
+
+    /// Bucket key for `time`: "2026-10-18" or "2026-W42" (sorts chronologically)
+    fn bucket(self, time: DateTime<Local>) -> String {
+        match self {
+            Period::Daily => time.format("%Y-%m-%d").to_string(),
+            Period::Weekly => {
+                let week = time.iso_week();
+                format!("{}-W{:02}", week.year(), week.week())
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Rollup {
+    /// Day, week, or model id depending on the grouping
+    pub label: String,
+    pub requests: u32,
+    pub tokens: u64,
+    pub cost: f64,
+}
+
+impl Rollup {
+    fn add(&mut self, record: &UsageRecord) {
+        self.requests += 1;
+        self.tokens += u64::from(record.total_tokens);
+        self.cost += record.cost;
+    }
+}
+
+fn group_by(records: &[UsageRecord], key: impl Fn(&UsageRecord) -> String) -> Vec<Rollup> {
+    let mut groups: HashMap<String, Rollup> = HashMap::new();
+    for record in records {
+        let label = key(record);
+        groups
+            .entry(label.clone())
+            .or_insert_with(|| Rollup {
+                label,
+                ..Default::default()
+            })
+            .add(record);
+    }
+    groups.into_values().collect()
+}
+
+/// Totals per day or week, newest first
+pub fn rollups(records: &[UsageRecord], period: Period) -> Vec<Rollup> {
+    let mut rollups = group_by(records, |r| period.bucket(r.time()));
+    rollups.sort_by(|a, b| b.label.cmp(&a.label));
+    rollups
+}
+
+/// Totals per model, highest spend first
+pub fn top_models(records: &[UsageRecord]) -> Vec<Rollup> {
+    let mut models = group_by(records, |r| r.model_id.clone());
+    models.sort_by(|a, b| b.cost.total_cmp(&a.cost).then_with(|| a.label.cmp(&b.label)));
+    models
+}
+
+/// One CSV row per record, with a header
+pub fn to_csv(records: &[UsageRecord]) -> String {
+    let mut csv = String::from("timestamp,model,input_tokens,output_tokens,total_tokens,cost\n");
+    for record in records {
+        let model = if record.model_id.contains([',', '"', '\n']) {
+            format!("\"{}\"", record.model_id.replace('"', "\"\""))
+        } else {
+            record.model_id.clone()
+        };
+        csv.push_str(&format!(
+            "{},{},{},{},{},{:.6}\n",
+            record.time().to_rfc3339(),
+            model,
+            record.input_tokens,
+            record.output_tokens,
+            record.total_tokens,
+            record.cost
+        ));
+    }
+    csv
+}
+
+/// Write all usage records to `.ims/reports/usage-<timestamp>.csv`
+pub fn export_csv(records: &[UsageRecord]) -> Result<PathBuf> {
+    let dir = crate::app::ims_dir().join("reports");
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let path = dir.join(format!("usage-{}.csv", Local::now().format("%Y%m%d-%H%M%S")));
+    std::fs::write(&path, to_csv(records)).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+/// Reports view
+#[derive(Clone, Debug, Default)]
+pub struct ReportsView {
+    pub records: Vec<UsageRecord>,
+    pub period: Period,
+    pub scroll: usize,
+}
+
+impl AppState {
+    fn usage_records(&self) -> Result<Vec<UsageRecord>> {
+        match &self.metrics_store {
+            Some(store) => store.usage_records(),
+            None => anyhow::bail!("The metrics store is not available"),
+        }
+    }
+
+    /// Load usage records and show the reports view
+    pub fn open_reports(&mut self) {
+        match self.usage_records() {
+            Ok(records) => {
+                self.reports_view = ReportsView {
+                    records,
+                    ..Default::default()
+                };
+                self.open_overlay(Overlay::Reports);
+            }
+            Err(e) => self.add_debug_log(format!("Failed to load usage records: {:#}", e)),
+        }
+    }
+
+    /// Export every usage record to CSV, logging where it went
+    pub fn export_usage_csv(&mut self) {
+        let exported = self.usage_records().and_then(|records| export_csv(&records).map(|path| (path, records.len())));
+        match exported {
+            Ok((path, count)) => self.add_debug_log(format!("Exported {} usage records to {}", count, path.display())),
+            Err(e) => self.add_debug_log(format!("Failed to export usage: {:#}", e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(day: u32, hour: u32, model: &str, tokens: u32, cost: f64) -> UsageRecord {
+        UsageRecord {
+            timestamp: Local.with_ymd_and_hms(2026, 10, day, hour, 0, 0).unwrap().timestamp(),
+            model_id: model.to_string(),
+            input_tokens: tokens / 2,
+            output_tokens: tokens - tokens / 2,
+            total_tokens: tokens,
+            cost,
+        }
+    }
+
+    #[test]
+    fn test_daily_and_weekly_rollups() {
+        // Oct 18 2026 is a Sunday: 12th-18th is one ISO week, the 19th starts the next
+        let records = vec![
+            record(12, 9, "gpt-4o", 100, 0.01),
+            record(18, 9, "gpt-4o", 200, 0.02),
+            record(18, 15, "claude-3-5-sonnet", 300, 0.05),
+            record(19, 9, "gpt-4o", 400, 0.04),
+        ];
+
+        let daily = rollups(&records, Period::Daily);
+        assert_eq!(daily.len(), 3);
+        assert_eq!(daily[0].label, "2026-10-19");
+        assert_eq!((daily[1].requests, daily[1].tokens), (2, 500));
+
+        let weekly = rollups(&records, Period::Weekly);
+        assert_eq!(weekly.len(), 2);
+        assert_eq!(weekly[1].label, "2026-W42");
+        assert_eq!(weekly[1].requests, 3);
+
+        let models = top_models(&records);
+        assert_eq!(models[0].label, "gpt-4o");
+        assert!((models[0].cost - 0.07).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_load_jsonl_and_csv() {
+        let path = std::env::temp_dir().join(format!("ims-tui-usage-{}.jsonl", uuid::Uuid::new_v4()));
+        let lines = [
+            serde_json::to_string(&record(18, 9, "gpt-4o", 100, 0.01)).unwrap(),
+            serde_json::to_string(&record(18, 10, "a,b", 50, 0.002)).unwrap(),
+            "not json".to_string(),
+        ];
+        std::fs::write(&path, lines.join("\n")).unwrap();
+
+        let records = load(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(records.len(), 2);
+        let csv = to_csv(&records);
+        assert_eq!(csv.lines().count(), 3);
+        assert!(csv.lines().nth(2).unwrap().contains(",\"a,b\",25,25,50,0.002000"));
+    }
+}
@@ -0,0 +1,204 @@
+//! Vendor Registry
+//!
+//! Who makes a model, and how that vendor is shown: display name, logo
+//! glyph and brand color, used by the session header, the Generation
+//! cursor and the model registry. Each vendor also carries the sampling
+//! defaults prompts to its models are sent with.
+//!
+//! A model belongs to the vendor with the longest matching model id prefix.
+//! `[[vendors]]` entries add vendors, or change the built-in ones
+//! (`openai`, `anthropic`, `google`, `local`) by id. The `local` vendor
+//! claims the model ids under `[local] prefix`.
+
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use ratatui::style::Color;
+
+use crate::app::{
+    api::MAX_TOKENS,
+    config::{LocalConfig, VendorEntry},
+};
+
+/// Sampling temperature when a vendor sets none
+const DEFAULT_TEMPERATURE: f64 = 0.7;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Vendor {
+    pub id: String,
+    pub name: String,
+    pub glyph: String,
+    pub color: Color,
+    /// Model ids starting with one of these (case-insensitively) are this vendor's
+    pub model_prefixes: Vec<String>,
+    pub temperature: f64,
+    pub max_tokens: u32,
+}
+
+impl Vendor {
+    fn new(id: &str, name: &str, glyph: &str, color: Color, model_prefixes: &[&str]) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            glyph: glyph.to_string(),
+            color,
+            model_prefixes: model_prefixes.iter().map(|p| p.to_string()).collect(),
+            temperature: DEFAULT_TEMPERATURE,
+            max_tokens: MAX_TOKENS,
+        }
+    }
+
+    /// For models no vendor claims
+    pub fn unknown() -> Self {
+        Self::new("unknown", "Unknown Vendor", "?", Color::Cyan, &[])
+    }
+
+    /// Length of the longest prefix of `model_id` this vendor claims
+    fn claim(&self, model_id: &str) -> Option<usize> {
+        let model_id = model_id.to_lowercase();
+        self.model_prefixes
+            .iter()
+            .filter(|prefix| model_id.starts_with(&prefix.to_lowercase()))
+            .map(|prefix| prefix.len())
+            .max()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct VendorRegistry {
+    vendors: Vec<Vendor>,
+    unknown: Vendor,
+}
+
+impl Default for VendorRegistry {
+    fn default() -> Self {
+        Self::built_in()
+    }
+}
+
+impl VendorRegistry {
+    pub fn built_in() -> Self {
+        Self {
+            vendors: vec![
+                Vendor::new("openai", "OpenAI GPT", "●", Color::Green, &["gpt", "chatgpt", "o1", "o3", "o4"]),
+                Vendor::new("anthropic", "Anthropic Claude", "▲", Color::LightRed, &["claude"]),
+                Vendor::new("google", "Google Gemini", "◆", Color::LightBlue, &["gemini"]),
+                Vendor::new("local", "Local Model", "⌂", Color::Gray, &["local/"]),
+            ],
+            unknown: Vendor::unknown(),
+        }
+    }
+
+    /// The built-in vendors with `[[vendors]]` applied
+    pub fn new(entries: &[VendorEntry], local: &LocalConfig) -> Result<Self> {
+        let mut registry = Self::built_in();
+        if let Some(vendor) = registry.vendors.iter_mut().find(|v| v.id == "local") {
+            vendor.model_prefixes = vec![local.prefix.clone()];
+        }
+        for entry in entries {
+            let color = entry
+                .color
+                .as_deref()
+                .map(|c| Color::from_str(c).map_err(|_| anyhow::anyhow!("unknown color {:?}", c)))
+                .transpose()
+                .with_context(|| format!("Invalid vendor {:?}", entry.id))?;
+            let vendor = match registry.vendors.iter_mut().position(|v| v.id == entry.id) {
+                Some(index) => &mut registry.vendors[index],
+                None => {
+                    registry.vendors.push(Vendor::new(&entry.id, &entry.id, "?", Color::Cyan, &[]));
+                    registry.vendors.last_mut().expect("just pushed")
+                }
+            };
+            if let Some(name) = &entry.name {
+                vendor.name = name.clone();
+            }
+            if let Some(glyph) = &entry.glyph {
+                vendor.glyph = glyph.clone();
+            }
+            if let Some(color) = color {
+                vendor.color = color;
+            }
+            vendor.model_prefixes.extend(entry.models.iter().cloned());
+            if let Some(temperature) = entry.temperature {
+                vendor.temperature = temperature;
+            }
+            if let Some(max_tokens) = entry.max_tokens {
+                vendor.max_tokens = max_tokens;
+            }
+        }
+        Ok(registry)
+    }
+
+    /// The vendor of `model_id`
+    pub fn for_model(&self, model_id: &str) -> &Vendor {
+        self.vendors
+            .iter()
+            .filter_map(|vendor| Some((vendor.claim(model_id)?, vendor)))
+            .max_by_key(|(len, _)| *len)
+            .map(|(_, vendor)| vendor)
+            .unwrap_or(&self.unknown)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup_and_config_overrides() {
+        let registry = VendorRegistry::built_in();
+        assert_eq!(registry.for_model("gpt-4o").id, "openai");
+        assert_eq!(registry.for_model("local/llama3.1").id, "local");
+        assert_eq!(registry.for_model("Claude-3-Haiku").glyph, "▲");
+        assert_eq!(registry.for_model("o1-preview").name, "OpenAI GPT");
+        // Contained, not prefixed: no longer mistaken for the vendor
+        assert_eq!(registry.for_model("my-gpt-proxy").id, "unknown");
+
+        let entries = vec![
+            VendorEntry {
+                id: "anthropic".to_string(),
+                color: Some("#d97757".to_string()),
+                max_tokens: Some(4096),
+                ..Default::default()
+            },
+            VendorEntry {
+                id: "mistral".to_string(),
+                name: Some("Mistral".to_string()),
+                glyph: Some("≋".to_string()),
+                models: vec!["mistral".to_string(), "codestral".to_string()],
+                temperature: Some(0.2),
+                ..Default::default()
+            },
+            // More specific than the built-in "gpt" prefix
+            VendorEntry {
+                id: "azure".to_string(),
+                name: Some("Azure OpenAI".to_string()),
+                models: vec!["gpt-4o-azure".to_string()],
+                ..Default::default()
+            },
+        ];
+        let local = LocalConfig {
+            prefix: "ollama:".to_string(),
+            ..Default::default()
+        };
+        let registry = VendorRegistry::new(&entries, &local).unwrap();
+        let anthropic = registry.for_model("claude-3-5-sonnet");
+        assert_eq!(anthropic.color, Color::Rgb(0xd9, 0x77, 0x57));
+        assert_eq!(anthropic.max_tokens, 4096);
+        assert_eq!(anthropic.name, "Anthropic Claude");
+        let mistral = registry.for_model("codestral-latest");
+        assert_eq!((mistral.name.as_str(), mistral.glyph.as_str()), ("Mistral", "≋"));
+        assert_eq!(mistral.temperature, 0.2);
+        assert_eq!(registry.for_model("gpt-4o-azure-eu").id, "azure");
+        assert_eq!(registry.for_model("gpt-4o").id, "openai");
+        assert_eq!(registry.for_model("ollama:llama3.1").id, "local");
+        assert_eq!(registry.for_model("local/llama3.1").id, "unknown");
+
+        let bad = VendorEntry {
+            id: "x".to_string(),
+            color: Some("not-a-color".to_string()),
+            ..Default::default()
+        };
+        assert!(VendorRegistry::new(&[bad], &local).is_err());
+    }
+}
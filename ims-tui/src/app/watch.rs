@@ -0,0 +1,278 @@
+//! Watch Mode
+//!
+//! A watch binds a file to a prompt or workflow: each time the file is
+//! saved, the prompt is sent again with the new contents (or the workflow
+//! re-runs on the file). Saves are debounced per binding so an editor's
+//! burst of writes triggers one run, and saving unchanged contents does
+//! nothing. Bindings come from `[[watch]]` or "Watch: Re-run Last Prompt
+//! When Selected File Changes", and are switched on and off in the Watches
+//! view.
+//!
+//! The parent directory of each file is watched rather than the file, so
+//! editors that save by renaming a temporary file over it are still seen.
+
+use anyhow::{Context, Result};
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::app::{
+    api::{ApiEvent, ApiSender},
+    overlay::Overlay,
+    schedule::Task,
+    thinking::{Level, ThinkingEntry},
+    AppState,
+};
+
+/// Reports saves of the watched files as `ApiEvent::FileChanged`
+pub struct FileWatcher {
+    inner: notify::RecommendedWatcher,
+    files: Arc<Mutex<HashSet<PathBuf>>>,
+    dirs: HashSet<PathBuf>,
+}
+
+impl FileWatcher {
+    pub fn new(tx: ApiSender) -> Result<Self> {
+        let files: Arc<Mutex<HashSet<PathBuf>>> = Arc::default();
+        let watched = files.clone();
+        let inner = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            let Ok(event) = event else { return };
+            if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                return;
+            }
+            let watched = watched.lock().unwrap_or_else(|e| e.into_inner());
+            for path in event.paths.into_iter().filter(|p| watched.contains(p)) {
+                let _ = tx.send(ApiEvent::FileChanged(path));
+            }
+        })
+        .context("Failed to start the file watcher")?;
+        Ok(Self {
+            inner,
+            files,
+            dirs: HashSet::new(),
+        })
+    }
+
+    pub fn watch(&mut self, file: &Path) -> Result<()> {
+        let dir = file.parent().context("File has no parent directory")?.to_path_buf();
+        if !self.dirs.contains(&dir) {
+            self.inner
+                .watch(&dir, RecursiveMode::NonRecursive)
+                .with_context(|| format!("Failed to watch {}", dir.display()))?;
+            self.dirs.insert(dir);
+        }
+        self.files.lock().unwrap_or_else(|e| e.into_inner()).insert(file.to_path_buf());
+        Ok(())
+    }
+
+    pub fn unwatch(&mut self, file: &Path) {
+        let mut files = self.files.lock().unwrap_or_else(|e| e.into_inner());
+        files.remove(file);
+        let Some(dir) = file.parent() else { return };
+        if !files.iter().any(|f| f.parent() == Some(dir)) && self.dirs.remove(dir) {
+            let _ = self.inner.unwatch(dir);
+        }
+    }
+}
+
+impl std::fmt::Debug for FileWatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FileWatcher").field("dirs", &self.dirs).finish()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct WatchBinding {
+    /// Absolute path, as the watcher reports it
+    pub file: PathBuf,
+    /// Path as configured or shown in the Explorer
+    pub label: String,
+    pub task: Task,
+    pub debounce: Duration,
+    pub enabled: bool,
+    /// When the debounce after the latest save runs out
+    pub due: Option<Instant>,
+    /// Hash of the contents last sent, so unchanged saves are skipped
+    pub last_hash: Option<u64>,
+    pub runs: u32,
+}
+
+/// Watches overlay ("Watch: Show Bindings")
+#[derive(Debug, Default)]
+pub struct WatchView {
+    pub bindings: Vec<WatchBinding>,
+    /// `[[watch]]` entries that could not be bound
+    pub errors: Vec<String>,
+    pub selected: usize,
+}
+
+pub fn content_hash(content: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// `file` made absolute without requiring it to exist yet
+fn absolute(file: &Path) -> PathBuf {
+    std::fs::canonicalize(file).unwrap_or_else(|_| std::env::current_dir().unwrap_or_default().join(file))
+}
+
+impl AppState {
+    /// Bind the `[[watch]]` entries
+    pub fn load_watches(&mut self) {
+        for entry in self.config.watch.clone() {
+            let bound = Task::new(&entry.prompt, &entry.workflow, &entry.model).and_then(|task| {
+                self.add_watch(&entry.file, task, Duration::from_millis(entry.debounce_ms), entry.enabled)
+            });
+            if let Err(e) = bound {
+                let error = format!("{}: {:#}", entry.file.display(), e);
+                self.add_debug_log(format!("Invalid watch {}", error));
+                self.watch_view.errors.push(error);
+            }
+        }
+    }
+
+    pub fn add_watch(&mut self, file: &Path, task: Task, debounce: Duration, enabled: bool) -> Result<()> {
+        let absolute = absolute(file);
+        if let Some(watcher) = self.file_watcher.as_mut() {
+            watcher.watch(&absolute)?;
+        }
+        self.watch_view.bindings.push(WatchBinding {
+            file: absolute,
+            label: file.display().to_string(),
+            task,
+            debounce,
+            enabled,
+            due: None,
+            last_hash: None,
+            runs: 0,
+        });
+        Ok(())
+    }
+
+    /// Bind the file selected in the Explorer to the last prompt sent
+    pub fn watch_selected_file(&mut self) {
+        let entry = match self.bind_selected_file() {
+            Ok(path) => ThinkingEntry::system(format!(
+                "👁 Watching {}: saving it re-runs the last prompt",
+                path.display()
+            )),
+            Err(e) => ThinkingEntry::system(format!("✗ {:#}", e)).with_level(Level::Warning),
+        };
+        self.add_thinking(entry);
+    }
+
+    fn bind_selected_file(&mut self) -> Result<PathBuf> {
+        let node = self
+            .get_selected_node()
+            .filter(|n| !n.is_dir)
+            .context("Select a file in the Explorer first")?;
+        let path = node.path.clone();
+        let turn = self.conversation.last().context("Send a prompt first; it is the one re-run")?;
+        let task = Task::Prompt {
+            text: turn.prompt.clone(),
+            model: Some(turn.model_id.clone()),
+        };
+        self.add_watch(&path, task, Duration::from_millis(500), true)?;
+        self.watch_view.selected = self.watch_view.bindings.len() - 1;
+        Ok(path)
+    }
+
+    /// A watched file was saved; restart the debounce of its bindings
+    pub fn file_changed(&mut self, file: &Path, now: Instant) {
+        for binding in self.watch_view.bindings.iter_mut().filter(|b| b.enabled && b.file == file) {
+            binding.due = Some(now + binding.debounce);
+        }
+    }
+
+    /// Bindings whose debounce has run out, with the file's new contents;
+    /// saves that left the contents as last sent are dropped
+    pub fn due_watches(&mut self, now: Instant) -> Vec<(usize, String)> {
+        let mut due = Vec::new();
+        for (index, binding) in self.watch_view.bindings.iter_mut().enumerate() {
+            if binding.due.is_none_or(|at| at > now) {
+                continue;
+            }
+            binding.due = None;
+            let Ok(content) = std::fs::read_to_string(&binding.file) else { continue };
+            let hash = content_hash(&content);
+            if binding.last_hash == Some(hash) {
+                continue;
+            }
+            binding.last_hash = Some(hash);
+            binding.runs += 1;
+            due.push((index, content));
+        }
+        due
+    }
+
+    pub fn open_watches(&mut self) {
+        let view = &mut self.watch_view;
+        view.selected = view.selected.min(view.bindings.len().saturating_sub(1));
+        self.open_overlay(Overlay::Watches);
+    }
+
+    /// Switch the selected binding on or off
+    pub fn toggle_selected_watch(&mut self) {
+        let view = &mut self.watch_view;
+        let Some(binding) = view.bindings.get_mut(view.selected) else { return };
+        binding.enabled = !binding.enabled;
+        binding.due = None;
+    }
+
+    pub fn remove_selected_watch(&mut self) {
+        let view = &mut self.watch_view;
+        if view.selected >= view.bindings.len() {
+            return;
+        }
+        let binding = view.bindings.remove(view.selected);
+        view.selected = view.selected.min(view.bindings.len().saturating_sub(1));
+        let still_bound = view.bindings.iter().any(|b| b.file == binding.file);
+        if let (false, Some(watcher)) = (still_bound, self.file_watcher.as_mut()) {
+            watcher.unwatch(&binding.file);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prompt() -> Task {
+        Task::Prompt {
+            text: "Review this".to_string(),
+            model: None,
+        }
+    }
+
+    #[test]
+    fn test_saves_debounce_and_skip_unchanged() {
+        let path = std::env::temp_dir().join(format!("ims-watch-{}.rs", uuid::Uuid::new_v4()));
+        std::fs::write(&path, "fn main() {}").unwrap();
+        let mut state = AppState::default();
+        state.add_watch(&path, prompt(), Duration::from_millis(500), true).unwrap();
+        let file = state.watch_view.bindings[0].file.clone();
+
+        let start = Instant::now();
+        state.file_changed(&file, start);
+        state.file_changed(&file, start + Duration::from_millis(300));
+        assert!(state.due_watches(start + Duration::from_millis(600)).is_empty());
+        let due = state.due_watches(start + Duration::from_millis(800));
+        assert_eq!(due, [(0, "fn main() {}".to_string())]);
+
+        // Saved again without changes
+        state.file_changed(&file, start);
+        assert!(state.due_watches(start + Duration::from_secs(1)).is_empty());
+
+        state.toggle_selected_watch();
+        std::fs::write(&path, "fn main() { todo!() }").unwrap();
+        state.file_changed(&file, start);
+        assert!(state.due_watches(start + Duration::from_secs(1)).is_empty());
+        assert_eq!(state.watch_view.bindings[0].runs, 1);
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -0,0 +1,371 @@
+//! Workflows
+//!
+//! Reusable pipelines defined in `.ims/workflows/*.toml`: a list of steps
+//! with their prompts, how each target's model is picked, how files are
+//! grouped and where the final output goes. Any `{name}` in a prompt (or
+//! the output path) other than the ones the pipeline fills in is a
+//! parameter, asked for when the workflow is run from the palette
+//! ("Workflow: Run..."), pre-filled from `[params]`.
+//!
+//! ```toml
+//! name = "Document"
+//! description = "Write module docs"
+//! scope = "module"          # or "file"
+//! model = "cheapest"        # "file", "session", "cheapest" or a model id
+//!
+//! [params]
+//! audience = "new contributors"
+//!
+//! [[steps]]
+//! name = "draft"
+//! prompt = "Document {target} for {audience}.\n\n{code}"
+//!
+//! [output]
+//! history = true
+//! path = "docs/{stem}.md"
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::app::{
+    config::PipelineStep,
+    overlay::Overlay,
+    pipeline::{self, Output, PipelineJob, Scope},
+    AppState,
+};
+
+/// Placeholders the pipeline fills in itself
+const BUILTIN: &[&str] = &["target", "code", "previous", "stem"];
+
+/// How each target's model is chosen (steps may still name their own)
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(from = "String")]
+pub enum ModelStrategy {
+    /// The model assigned to the file in the Explorer
+    #[default]
+    File,
+    /// The current session's model
+    Session,
+    /// The active registry model with the lowest per-token price
+    Cheapest,
+    Fixed(String),
+}
+
+impl From<String> for ModelStrategy {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "" | "file" => ModelStrategy::File,
+            "session" => ModelStrategy::Session,
+            "cheapest" => ModelStrategy::Cheapest,
+            _ => ModelStrategy::Fixed(value),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Workflow {
+    /// Defaults to the file name
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub scope: Scope,
+    #[serde(default)]
+    pub model: ModelStrategy,
+    /// Parameter defaults
+    #[serde(default)]
+    pub params: BTreeMap<String, String>,
+    pub steps: Vec<PipelineStep>,
+    #[serde(default)]
+    pub output: Output,
+}
+
+impl Workflow {
+    pub fn parse(text: &str) -> Result<Self> {
+        let workflow: Workflow = config::Config::builder()
+            .add_source(config::File::from_str(text, config::FileFormat::Toml))
+            .build()?
+            .try_deserialize()?;
+        anyhow::ensure!(!workflow.steps.is_empty(), "no [[steps]] defined");
+        Ok(workflow)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let mut workflow = Self::parse(&text).with_context(|| format!("Invalid workflow {}", path.display()))?;
+        if workflow.name.is_empty() {
+            workflow.name = path.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+        }
+        Ok(workflow)
+    }
+
+    /// Parameters to ask for, in order of first use
+    pub fn parameters(&self) -> Vec<String> {
+        let templates = self.steps.iter().map(|s| s.prompt.as_str()).chain(self.output.path.as_deref());
+        let mut names: Vec<String> = Vec::new();
+        for name in templates.flat_map(placeholders) {
+            if !BUILTIN.contains(&name.as_str()) && !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        names
+    }
+
+    /// Steps and output path with the parameter values filled in
+    fn render(&self, values: &[(String, String)]) -> (Vec<PipelineStep>, Output) {
+        let vars: Vec<(&str, &str)> = values.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+        let steps = self
+            .steps
+            .iter()
+            .map(|step| PipelineStep {
+                prompt: pipeline::render_template(&step.prompt, &vars),
+                ..step.clone()
+            })
+            .collect();
+        let output = Output {
+            path: self.output.path.as_deref().map(|p| pipeline::render_template(p, &vars)),
+            ..self.output.clone()
+        };
+        (steps, output)
+    }
+}
+
+/// `{name}` placeholders in `template`
+pub fn placeholders(template: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        rest = &rest[start + 1..];
+        let Some(end) = rest.find('}') else { break };
+        let name = &rest[..end];
+        if !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            names.push(name.to_string());
+            rest = &rest[end + 1..];
+        }
+    }
+    names
+}
+
+pub fn workflows_dir() -> PathBuf {
+    crate::app::ims_dir().join("workflows")
+}
+
+/// Every `*.toml` in `dir`, sorted by file name; files that fail to parse
+/// are returned as errors so the overlay can show them
+pub fn list(dir: &Path) -> Vec<Result<Workflow>> {
+    let Ok(entries) = std::fs::read_dir(dir) else { return Vec::new() };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "toml"))
+        .collect();
+    paths.sort();
+    paths.iter().map(|p| Workflow::load(p)).collect()
+}
+
+/// Values being entered for a workflow's parameters
+#[derive(Clone, Debug, Default)]
+pub struct ParamPrompt {
+    /// (name, value) in the order they are asked for
+    pub values: Vec<(String, String)>,
+    pub current: usize,
+}
+
+/// Workflows overlay ("Workflow: Run...")
+#[derive(Debug, Default)]
+pub struct WorkflowsView {
+    pub workflows: Vec<Workflow>,
+    /// Files that failed to load
+    pub errors: Vec<String>,
+    pub selected: usize,
+    pub params: Option<ParamPrompt>,
+}
+
+impl WorkflowsView {
+    pub fn selected_workflow(&self) -> Option<&Workflow> {
+        self.workflows.get(self.selected)
+    }
+}
+
+impl AppState {
+    pub fn open_workflows(&mut self) {
+        let (mut workflows, mut errors) = (Vec::new(), Vec::new());
+        for workflow in list(&workflows_dir()) {
+            match workflow {
+                Ok(workflow) => workflows.push(workflow),
+                Err(e) => errors.push(format!("{:#}", e)),
+            }
+        }
+        self.workflows_view = WorkflowsView {
+            workflows,
+            errors,
+            selected: 0,
+            params: None,
+        };
+        self.open_overlay(Overlay::Workflows);
+    }
+
+    /// Begin running the selected workflow: returns the job straight away
+    /// when it has no parameters, otherwise starts asking for them
+    pub fn choose_workflow(&mut self) -> Option<PipelineJob> {
+        let workflow = self.workflows_view.selected_workflow()?;
+        let names = workflow.parameters();
+        if names.is_empty() {
+            return self.finish_workflow(Vec::new());
+        }
+        let values = names
+            .into_iter()
+            .map(|name| {
+                let default = workflow.params.get(&name).cloned().unwrap_or_default();
+                (name, default)
+            })
+            .collect();
+        self.workflows_view.params = Some(ParamPrompt { values, current: 0 });
+        None
+    }
+
+    /// Accept the current parameter; the job once the last one is entered
+    pub fn next_workflow_param(&mut self) -> Option<PipelineJob> {
+        let params = self.workflows_view.params.as_mut()?;
+        params.current += 1;
+        if params.current < params.values.len() {
+            return None;
+        }
+        let values = self.workflows_view.params.take()?.values;
+        self.finish_workflow(values)
+    }
+
+    fn finish_workflow(&mut self, values: Vec<(String, String)>) -> Option<PipelineJob> {
+        let workflow = self.workflows_view.selected_workflow()?;
+        let job = self.workflow_job(workflow, &values);
+        if job.is_none() {
+            self.add_debug_log("Mark files in the Explorer with Space (or select one) first".to_string());
+        }
+        self.close_overlay(Overlay::Workflows);
+        job
+    }
+
+    /// A run of `workflow` over the marked (or selected) files
+    pub fn workflow_job(&self, workflow: &Workflow, values: &[(String, String)]) -> Option<PipelineJob> {
+        let mut files = self.batch_files();
+        if files.is_empty() {
+            let node = self.get_selected_node().filter(|n| !n.is_dir)?;
+            files.push((node.path.clone(), node.model.clone()));
+        }
+        Some(self.workflow_job_for(workflow, values, files))
+    }
+
+    /// A run of `workflow` over `files`, each with its model
+    pub fn workflow_job_for(
+        &self,
+        workflow: &Workflow,
+        values: &[(String, String)],
+        mut files: Vec<(PathBuf, String)>,
+    ) -> PipelineJob {
+        let strategy_model = match &workflow.model {
+            ModelStrategy::File => None,
+            ModelStrategy::Session => self.session.as_ref().map(|s| s.model_id.clone()),
+            ModelStrategy::Cheapest => self.cheapest_model(),
+            ModelStrategy::Fixed(model_id) => Some(model_id.clone()),
+        };
+        if let Some(model_id) = strategy_model {
+            files.iter_mut().for_each(|(_, model)| *model = model_id.clone());
+        }
+
+        let (steps, output) = workflow.render(values);
+        let dir = pipeline::run_dir();
+        let stamp = dir.file_name().unwrap_or_default().to_string_lossy().into_owned();
+        PipelineJob {
+            name: workflow.name.clone(),
+            dir: dir.with_file_name(format!("{}-{}", stamp, pipeline::slug(&workflow.name))),
+            steps,
+            targets: pipeline::targets(&files, workflow.scope),
+            concurrency: self.config.batch.concurrency.max(1),
+            output,
+            pause: pipeline::Pause::default(),
+        }
+    }
+
+    fn cheapest_model(&self) -> Option<String> {
+        self.model_catalog
+            .values()
+            .filter(|m| m.is_active)
+            .min_by(|a, b| {
+                (a.cost_in_per_mil + a.cost_out_per_mil).total_cmp(&(b.cost_in_per_mil + b.cost_out_per_mil))
+            })
+            .map(|m| m.model_id.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOCUMENT: &str = r#"
+        name = "Document"
+        scope = "module"
+        model = "claude-3-haiku"
+
+        [params]
+        audience = "new contributors"
+
+        [[steps]]
+        name = "draft"
+        prompt = "Document {target} for {audience} in {language}.\n\n{code}"
+
+        [[steps]]
+        name = "polish"
+        model = "gpt-4o"
+        prompt = "Tighten this:\n\n{previous}"
+
+        [output]
+        history = true
+        path = "docs/{language}/{stem}.md"
+    "#;
+
+    #[test]
+    fn test_parse_workflow() {
+        let workflow = Workflow::parse(DOCUMENT).unwrap();
+        assert_eq!(workflow.name, "Document");
+        assert_eq!(workflow.scope, Scope::Module);
+        assert_eq!(workflow.model, ModelStrategy::Fixed("claude-3-haiku".to_string()));
+        assert_eq!(workflow.steps[1].model.as_deref(), Some("gpt-4o"));
+        assert!(workflow.output.history);
+        assert_eq!(workflow.parameters(), vec!["audience", "language"]);
+
+        assert!(Workflow::parse("name = \"empty\"").is_err());
+        assert_eq!(Workflow::parse("[[steps]]\nname = \"a\"\nprompt = \"{code}\"").unwrap().model, ModelStrategy::File);
+    }
+
+    #[test]
+    fn test_placeholders() {
+        assert_eq!(placeholders("{a} {b_2} {not valid} {} {a}"), vec!["a", "b_2", "a"]);
+        assert_eq!(placeholders("fn main() { {x} }"), vec!["x"]);
+    }
+
+    #[test]
+    fn test_params_fill_in_the_job() {
+        let mut state = AppState {
+            marked_files: vec![PathBuf::from("src/app/a.rs"), PathBuf::from("src/app/b.rs")],
+            ..Default::default()
+        };
+        state.workflows_view.workflows = vec![Workflow::parse(DOCUMENT).unwrap()];
+
+        assert!(state.choose_workflow().is_none());
+        let params = state.workflows_view.params.as_mut().unwrap();
+        assert_eq!(params.values[0], ("audience".to_string(), "new contributors".to_string()));
+        assert!(state.next_workflow_param().is_none());
+        state.workflows_view.params.as_mut().unwrap().values[1].1 = "en".to_string();
+
+        let job = state.next_workflow_param().unwrap();
+        assert_eq!(job.targets.len(), 1);
+        assert_eq!(job.targets[0].model_id, "claude-3-haiku");
+        assert_eq!(job.steps[0].prompt, "Document {target} for new contributors in en.\n\n{code}");
+        assert_eq!(job.output.path.as_deref(), Some("docs/en/{stem}.md"));
+        assert!(job.dir.to_string_lossy().ends_with("-Document"));
+    }
+}
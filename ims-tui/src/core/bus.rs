@@ -0,0 +1,254 @@
+//! Bounded Event Channels
+//!
+//! The API and core channels are bounded so a flooding producer can't grow
+//! memory without limit or keep the event loop draining instead of
+//! rendering. Sending never blocks: once a channel is full, each event's
+//! `Overflow` policy decides what happens to it until the loop catches up.
+//! - `Keep`: parked in order behind the channel and still delivered, up to
+//!   `SPILL_FACTOR` times the channel's capacity; past that, dropped too
+//! - `Coalesce`: only the latest event of that kind is delivered
+//! - `Drop`: discarded and counted
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::sync::mpsc::{
+    self,
+    error::{SendError, TryRecvError, TrySendError},
+};
+
+/// Messages each channel holds before its overflow policy applies
+pub const CAPACITY: usize = 1024;
+
+/// `Keep` events parked behind a full channel, as a multiple of its capacity
+pub const SPILL_FACTOR: usize = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Overflow {
+    Keep,
+    /// Snapshots where only the newest matters, grouped by kind
+    Coalesce(&'static str),
+    Drop,
+}
+
+/// How an event type behaves when its channel is full
+pub trait Prioritized {
+    fn overflow(&self) -> Overflow;
+}
+
+struct Spill<T> {
+    queue: VecDeque<T>,
+    /// Most `Keep` events parked in `queue`
+    limit: usize,
+    latest: Vec<(&'static str, T)>,
+    coalesced: u64,
+    dropped: u64,
+}
+
+impl<T> Spill<T> {
+    fn is_empty(&self) -> bool {
+        self.queue.is_empty() && self.latest.is_empty()
+    }
+}
+
+type Shared<T> = Arc<Mutex<Spill<T>>>;
+
+fn lock<T>(spill: &Shared<T>) -> MutexGuard<'_, Spill<T>> {
+    spill.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+pub struct Sender<T> {
+    tx: mpsc::Sender<T>,
+    spill: Shared<T>,
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            tx: self.tx.clone(),
+            spill: self.spill.clone(),
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Sender").field("capacity", &self.tx.max_capacity()).finish()
+    }
+}
+
+pub struct Receiver<T> {
+    rx: mpsc::Receiver<T>,
+    spill: Shared<T>,
+}
+
+/// Counters for the F12 profiler
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OverflowStats {
+    pub coalesced: u64,
+    pub dropped: u64,
+}
+
+pub fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    let (tx, rx) = mpsc::channel(capacity);
+    let spill = Arc::new(Mutex::new(Spill {
+        queue: VecDeque::new(),
+        limit: capacity.saturating_mul(SPILL_FACTOR),
+        latest: Vec::new(),
+        coalesced: 0,
+        dropped: 0,
+    }));
+    (Sender { tx, spill: spill.clone() }, Receiver { rx, spill })
+}
+
+impl<T> Sender<T> {
+    /// Whether the receiver is gone
+    pub fn is_closed(&self) -> bool {
+        self.tx.is_closed()
+    }
+}
+
+impl<T: Prioritized> Sender<T> {
+    /// Queue `event` without waiting; fails only once the receiver is gone
+    pub fn send(&self, event: T) -> Result<(), SendError<T>> {
+        let mut spill = lock(&self.spill);
+        // Anything already spilled goes first, so later events follow it
+        let event = if spill.is_empty() {
+            match self.tx.try_send(event) {
+                Ok(()) => return Ok(()),
+                Err(TrySendError::Closed(event)) => return Err(SendError(event)),
+                Err(TrySendError::Full(event)) => event,
+            }
+        } else if self.tx.is_closed() {
+            return Err(SendError(event));
+        } else {
+            event
+        };
+
+        match event.overflow() {
+            Overflow::Keep if spill.queue.len() < spill.limit => spill.queue.push_back(event),
+            Overflow::Keep => spill.dropped += 1,
+            Overflow::Coalesce(kind) => match spill.latest.iter_mut().find(|(k, _)| *k == kind) {
+                Some(slot) => {
+                    slot.1 = event;
+                    spill.coalesced += 1;
+                }
+                None => spill.latest.push((kind, event)),
+            },
+            Overflow::Drop => spill.dropped += 1,
+        }
+        Ok(())
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Next event: the channel first, then whatever overflowed
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        match self.rx.try_recv() {
+            Err(TryRecvError::Empty) => {
+                let mut spill = lock(&self.spill);
+                if let Some(event) = spill.queue.pop_front() {
+                    return Ok(event);
+                }
+                if !spill.latest.is_empty() {
+                    return Ok(spill.latest.remove(0).1);
+                }
+                Err(TryRecvError::Empty)
+            }
+            result => result,
+        }
+    }
+
+    /// Wait for the next event; `None` once every sender is gone
+    pub async fn recv(&mut self) -> Option<T> {
+        if let Ok(event) = self.try_recv() {
+            return Some(event);
+        }
+        // Overflow only happens while the channel is full, so anything
+        // spilled from here on arrives behind a channel message
+        self.rx.recv().await
+    }
+
+    /// Waiting events, including overflow
+    pub fn len(&self) -> usize {
+        let spill = lock(&self.spill);
+        self.rx.len() + spill.queue.len() + spill.latest.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn stats(&self) -> OverflowStats {
+        let spill = lock(&self.spill);
+        OverflowStats {
+            coalesced: spill.coalesced,
+            dropped: spill.dropped,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    enum Msg {
+        Important(u32),
+        Snapshot(u32),
+        Debug,
+    }
+
+    impl Prioritized for Msg {
+        fn overflow(&self) -> Overflow {
+            match self {
+                Msg::Important(_) => Overflow::Keep,
+                Msg::Snapshot(_) => Overflow::Coalesce("snapshot"),
+                Msg::Debug => Overflow::Drop,
+            }
+        }
+    }
+
+    #[test]
+    fn test_overflow_policies() {
+        let (tx, mut rx) = channel(2);
+        tx.send(Msg::Important(1)).unwrap();
+        tx.send(Msg::Important(2)).unwrap();
+        // Full from here on
+        tx.send(Msg::Snapshot(1)).unwrap();
+        tx.send(Msg::Important(3)).unwrap();
+        tx.send(Msg::Debug).unwrap();
+        tx.send(Msg::Snapshot(2)).unwrap();
+        assert_eq!(rx.len(), 4);
+
+        let mut received = Vec::new();
+        while let Ok(msg) = rx.try_recv() {
+            received.push(msg);
+        }
+        assert_eq!(
+            received,
+            vec![Msg::Important(1), Msg::Important(2), Msg::Important(3), Msg::Snapshot(2)]
+        );
+        assert_eq!(rx.stats(), OverflowStats { coalesced: 1, dropped: 1 });
+
+        // Drained: back to the channel
+        tx.send(Msg::Debug).unwrap();
+        assert_eq!(rx.try_recv(), Ok(Msg::Debug));
+        drop(rx);
+        assert!(tx.send(Msg::Important(4)).is_err());
+    }
+
+    #[test]
+    fn test_kept_events_are_capped() {
+        let (tx, mut rx) = channel(2);
+        for i in 0..100 {
+            tx.send(Msg::Important(i)).unwrap();
+        }
+        assert_eq!(rx.len(), 2 + 2 * SPILL_FACTOR);
+        assert_eq!(rx.stats().dropped, 100 - 2 - 2 * SPILL_FACTOR as u64);
+
+        // The oldest are the ones delivered
+        let received: Vec<_> = std::iter::from_fn(|| rx.try_recv().ok()).collect();
+        assert_eq!(received.first(), Some(&Msg::Important(0)));
+        assert_eq!(received.last(), Some(&Msg::Important(2 + 2 * SPILL_FACTOR as u32 - 1)));
+    }
+}
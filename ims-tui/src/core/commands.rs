@@ -1,24 +1,60 @@
-use crate::app::AppState;
-use super::effects::CommandEffect;
+use crate::app::{
+    api::ApiEvent, external_editor::EditTarget, keymap, palette::{Choice, Recents}, pipeline::Scope, AppState, FocusPane,
+};
+use super::effects::{CommandEffect, NotificationLevel, Task, TaskResult};
+use super::events::Event;
 
 pub struct CommandContext {
+    #[allow(dead_code)]
     pub selected_vendor: String,
+    /// Picked in the palette, for a command that asked for one
+    pub argument: Option<String>,
 }
 
 impl Default for CommandContext {
     fn default() -> Self {
         Self {
             selected_vendor: "google".to_string(),
+            argument: None,
         }
     }
 }
 
+/// Pure function: no side effects, no async
+pub type CommandHandler = Box<dyn Fn(&AppState, CommandContext) -> Vec<CommandEffect> + Send + Sync>;
+
 pub struct Command {
     pub id: &'static str,
     pub title: &'static str,
-    
-    /// Pure function: no side effects, no async
-    pub handler: Box<dyn Fn(&AppState, CommandContext) -> Vec<CommandEffect> + Send + Sync>,
+    pub category: Category,
+    /// Whether the command has anything to act on now; without one it
+    /// always does
+    pub when: Option<fn(&AppState) -> bool>,
+    /// Normal-mode action that does the same, whose keys the palette shows
+    pub action: Option<keymap::Action>,
+    pub handler: CommandHandler,
+}
+
+/// Palette section a command is listed under
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Category {
+    File,
+    View,
+    Agent,
+    Workspace,
+    System,
+}
+
+impl Category {
+    pub fn label(self) -> &'static str {
+        match self {
+            Category::File => "File",
+            Category::View => "View",
+            Category::Agent => "Agent",
+            Category::Workspace => "Workspace",
+            Category::System => "System",
+        }
+    }
 }
 
 impl Command {
@@ -26,4 +62,786 @@ impl Command {
     pub fn execute(&self, state: &AppState, ctx: CommandContext) -> Vec<CommandEffect> {
         (self.handler)(state, ctx)
     }
+
+    /// Whether the command has anything to act on now; others are left out
+    /// of the palette
+    pub fn available(&self, state: &AppState) -> bool {
+        self.when.is_none_or(|when| when(state))
+    }
+}
+
+/// The pipeline run in progress, if any
+fn running_pipeline(state: &AppState) -> Option<&crate::app::pipeline::PipelineRun> {
+    state.pipeline_view.run.as_ref().filter(|r| r.is_running())
+}
+
+fn notify(level: NotificationLevel, message: &str) -> CommandEffect {
+    CommandEffect::ShowNotification {
+        level,
+        message: message.to_string(),
+    }
+}
+
+/// Start the refactor pipeline over the marked (or selected) files
+fn run_pipeline(state: &AppState, scope: Scope) -> Vec<CommandEffect> {
+    if state.pipeline_view.run.as_ref().is_some_and(|r| r.is_running()) {
+        return vec![notify(NotificationLevel::Warning, "A pipeline is already running")];
+    }
+    if state.config.pipeline.steps.is_empty() {
+        return vec![notify(NotificationLevel::Warning, "No [pipeline] steps configured")];
+    }
+    let Some(job) = state.pipeline_job(scope) else {
+        return vec![notify(NotificationLevel::Info, "Mark files in the Explorer with Space (or select one) first")];
+    };
+    let started = job.clone();
+    vec![
+        CommandEffect::StateMutation(Box::new(move |s| s.start_pipeline(&started))),
+        CommandEffect::RunPipeline(job),
+    ]
+}
+
+/// Models the session can switch to: the registry's active ones, the
+/// current one marked
+fn model_choices(state: &AppState) -> Vec<Choice> {
+    let current = state.session.as_ref().map(|s| s.model_id.as_str());
+    state
+        .model_registry
+        .models
+        .iter()
+        .filter(|m| m.is_active)
+        .map(|m| Choice {
+            label: if Some(m.model_id.as_str()) == current {
+                format!("{} (current)", m.model_id)
+            } else {
+                m.model_id.clone()
+            },
+            value: m.model_id.clone(),
+        })
+        .collect()
+}
+
+/// All palette commands, in display order
+pub fn registry() -> &'static [Command] {
+    static REGISTRY: std::sync::OnceLock<Vec<Command>> = std::sync::OnceLock::new();
+    REGISTRY.get_or_init(build_registry)
+}
+
+fn build_registry() -> Vec<Command> {
+    vec![
+        Command {
+            id: "file.new",
+            title: "File: New File",
+            category: Category::File,
+            when: None,
+            action: Some(keymap::Action::NewFile),
+            handler: Box::new(|state, _| {
+                let path = std::path::PathBuf::from(format!("new_file_{}.rs", state.file_tree.len() + 1));
+                vec![
+                    CommandEffect::StateMutation(Box::new(move |s| s.add_file(path))),
+                    CommandEffect::FocusPane(FocusPane::Sidebar),
+                ]
+            }),
+        },
+        Command {
+            id: "file.open",
+            title: "File: Open...",
+            category: Category::File,
+            when: None,
+            action: None,
+            handler: Box::new(|_, _| {
+                vec![
+                    CommandEffect::FocusPane(FocusPane::Sidebar),
+                    notify(NotificationLevel::Info, "Select a file in the Explorer and press Enter"),
+                ]
+            }),
+        },
+        Command {
+            id: "file.save",
+            title: "File: Save",
+            category: Category::File,
+            when: None,
+            action: None,
+            handler: Box::new(|_, _| vec![notify(NotificationLevel::Warning, "Saving is not supported yet")]),
+        },
+        Command {
+            id: "view.toggle_sidebar",
+            title: "View: Toggle Sidebar",
+            category: Category::View,
+            when: None,
+            action: None,
+            handler: Box::new(|_, _| vec![CommandEffect::StateMutation(Box::new(|s| s.toggle_sidebar()))]),
+        },
+        Command {
+            id: "view.toggle_inspector",
+            title: "View: Toggle Inspector",
+            category: Category::View,
+            when: None,
+            action: None,
+            handler: Box::new(|_, _| vec![CommandEffect::StateMutation(Box::new(|s| s.toggle_inspector()))]),
+        },
+        Command {
+            id: "view.toggle_zen",
+            title: "View: Toggle Zen Mode",
+            category: Category::View,
+            when: None,
+            action: Some(keymap::Action::ToggleZen),
+            handler: Box::new(|state, _| {
+                let message = if state.zen_mode {
+                    "Zen mode off"
+                } else {
+                    "Zen mode on: Generation fills the screen while a response is generating"
+                };
+                vec![
+                    CommandEffect::StateMutation(Box::new(|s| {
+                        s.zen_mode = !s.zen_mode;
+                        s.ensure_focus_visible();
+                    })),
+                    notify(NotificationLevel::Info, message),
+                ]
+            }),
+        },
+        Command {
+            id: "history.browse",
+            title: "History: Browse Generations",
+            category: Category::File,
+            when: None,
+            action: None,
+            handler: Box::new(|_, _| vec![CommandEffect::StateMutation(Box::new(|s| s.open_history()))]),
+        },
+        Command {
+            id: "thinking.export",
+            title: "Thinking: Export Log (JSON Lines)",
+            category: Category::File,
+            when: Some(|s| !s.thinking_log.is_empty()),
+            action: None,
+            handler: Box::new(|_, _| vec![CommandEffect::StateMutation(Box::new(|s| s.export_thinking_log()))]),
+        },
+        Command {
+            id: "session.export",
+            title: "Session: Export Archive (.imssession)",
+            category: Category::File,
+            when: None,
+            action: None,
+            handler: Box::new(|_, _| vec![CommandEffect::StateMutation(Box::new(|s| s.export_session()))]),
+        },
+        Command {
+            id: "session.import",
+            title: "Session: Import Selected Archive",
+            category: Category::File,
+            when: None,
+            action: None,
+            handler: Box::new(|state, _| {
+                let selected = state.get_selected_node().map(|n| n.path.clone());
+                match selected.filter(|path| crate::app::share::is_archive(path)) {
+                    Some(path) => vec![CommandEffect::StateMutation(Box::new(move |s| s.import_session(&path)))],
+                    None => vec![notify(NotificationLevel::Info, "Select a .imssession file in the Explorer first")],
+                }
+            }),
+        },
+        Command {
+            id: "generation.tee",
+            title: "Generation: Tee to File On/Off",
+            category: Category::Agent,
+            when: Some(|s| s.session.is_some()),
+            action: None,
+            handler: Box::new(|state, _| {
+                if state.session.is_none() {
+                    return vec![notify(NotificationLevel::Info, "Open a file first")];
+                }
+                vec![CommandEffect::StateMutation(Box::new(|s| s.toggle_tee()))]
+            }),
+        },
+        Command {
+            id: "generation.apply",
+            title: "Generation: Apply to Open File",
+            category: Category::Agent,
+            when: Some(|s| s.session.is_some() && !s.generated_code.is_empty()),
+            action: None,
+            handler: Box::new(|_, _| vec![CommandEffect::StateMutation(Box::new(|s| s.apply_generation()))]),
+        },
+        Command {
+            id: "generation.highlight_changes",
+            title: "Generation: Highlight Regeneration Changes On/Off",
+            category: Category::Agent,
+            when: None,
+            action: None,
+            handler: Box::new(|_, _| vec![CommandEffect::StateMutation(Box::new(|s| s.toggle_change_highlight()))]),
+        },
+        Command {
+            id: "images.save",
+            title: "Images: Save Returned Images",
+            category: Category::File,
+            when: Some(|s| s.returned_images.iter().any(|r| r.saved.is_none())),
+            action: None,
+            handler: Box::new(|_, _| {
+                vec![CommandEffect::StateMutation(Box::new(|s| {
+                    s.save_returned_images(&crate::app::images::images_dir())
+                }))]
+            }),
+        },
+        Command {
+            id: "reports.usage",
+            title: "Reports: Usage",
+            category: Category::View,
+            when: None,
+            action: None,
+            handler: Box::new(|_, _| vec![CommandEffect::StateMutation(Box::new(|s| s.open_reports()))]),
+        },
+        Command {
+            id: "reports.export_csv",
+            title: "Reports: Export Usage CSV",
+            category: Category::View,
+            when: None,
+            action: None,
+            handler: Box::new(|_, _| vec![CommandEffect::StateMutation(Box::new(|s| s.export_usage_csv()))]),
+        },
+        Command {
+            id: "experiment.start",
+            title: "Experiment: Start A/B Test",
+            category: Category::Agent,
+            when: None,
+            action: None,
+            handler: Box::new(|_, _| vec![CommandEffect::StateMutation(Box::new(|s| s.start_experiment()))]),
+        },
+        Command {
+            id: "experiment.stop",
+            title: "Experiment: Stop",
+            category: Category::Agent,
+            when: Some(|s| s.experiment.as_ref().is_some_and(|e| e.running)),
+            action: None,
+            handler: Box::new(|_, _| vec![CommandEffect::StateMutation(Box::new(|s| s.stop_experiment()))]),
+        },
+        Command {
+            id: "experiment.report",
+            title: "Experiment: Show Report",
+            category: Category::Agent,
+            when: Some(|s| s.experiment.is_some()),
+            action: None,
+            handler: Box::new(|_, _| vec![CommandEffect::StateMutation(Box::new(|s| s.open_experiment_report()))]),
+        },
+        Command {
+            id: "prompt.edit_externally",
+            title: "Prompt: Edit in $EDITOR",
+            category: Category::Agent,
+            when: None,
+            action: Some(keymap::Action::EditExternally),
+            handler: Box::new(|_, _| {
+                vec![CommandEffect::StateMutation(Box::new(|s| s.request_external_edit(EditTarget::Prompt)))]
+            }),
+        },
+        Command {
+            id: "prompt.edit_system_instruction",
+            title: "Prompt: Edit System Instruction in $EDITOR",
+            category: Category::Agent,
+            when: None,
+            action: None,
+            handler: Box::new(|_, _| {
+                vec![CommandEffect::StateMutation(Box::new(|s| {
+                    s.request_external_edit(EditTarget::SystemInstruction)
+                }))]
+            }),
+        },
+        Command {
+            id: "prompt.bypass_policies",
+            title: "Prompt: Bypass Policies for Next Request",
+            category: Category::Agent,
+            when: None,
+            action: None,
+            handler: Box::new(|state, _| {
+                if state.bypass_armed {
+                    return vec![
+                        CommandEffect::StateMutation(Box::new(|s| s.toggle_bypass())),
+                        notify(NotificationLevel::Info, "Policy bypass disarmed"),
+                    ];
+                }
+                vec![CommandEffect::StateMutation(Box::new(|s| s.toggle_bypass()))]
+            }),
+        },
+        Command {
+            id: "audit.view",
+            title: "Audit: View Trail",
+            category: Category::View,
+            when: None,
+            action: None,
+            handler: Box::new(|_, _| vec![CommandEffect::StateMutation(Box::new(|s| s.open_audit()))]),
+        },
+        Command {
+            id: "batch.run",
+            title: "Batch: Run Prompt on Marked Files",
+            category: Category::Workspace,
+            when: Some(|s| !s.marked_files.is_empty()),
+            action: None,
+            handler: Box::new(|state, _| {
+                let prompt = state.input_buffer.to_string();
+                if state.marked_files.is_empty() {
+                    return vec![notify(NotificationLevel::Info, "Mark files in the Explorer with Space first")];
+                }
+                if prompt.trim().is_empty() {
+                    return vec![notify(NotificationLevel::Info, "Type the prompt to run in the prompt box first")];
+                }
+                if state.batch.as_ref().is_some_and(|b| b.is_running()) {
+                    return vec![notify(NotificationLevel::Warning, "A batch is already running")];
+                }
+                let job = crate::app::batch::BatchJob {
+                    prompt,
+                    files: state.batch_files(),
+                    concurrency: state.config.batch.concurrency.max(1),
+                };
+                vec![CommandEffect::StateMutation(Box::new(move |s| s.start_batch(&job)))]
+            }),
+        },
+        Command {
+            id: "pipeline.run_file",
+            title: "Pipeline: Run per File",
+            category: Category::Workspace,
+            when: None,
+            action: None,
+            handler: Box::new(|state, _| run_pipeline(state, Scope::File)),
+        },
+        Command {
+            id: "pipeline.run_module",
+            title: "Pipeline: Run per Module",
+            category: Category::Workspace,
+            when: None,
+            action: None,
+            handler: Box::new(|state, _| run_pipeline(state, Scope::Module)),
+        },
+        Command {
+            id: "pipeline.show",
+            title: "Pipeline: Show Progress",
+            category: Category::Workspace,
+            when: None,
+            action: None,
+            handler: Box::new(|_, _| vec![CommandEffect::StateMutation(Box::new(|s| s.open_pipeline()))]),
+        },
+        Command {
+            id: "pipeline.pause",
+            title: "Pipeline: Pause",
+            category: Category::Workspace,
+            when: Some(|s| running_pipeline(s).is_some_and(|r| !r.pause.is_paused())),
+            action: None,
+            handler: Box::new(|_, _| vec![CommandEffect::StateMutation(Box::new(|s| s.set_pipeline_paused(true)))]),
+        },
+        Command {
+            id: "pipeline.resume",
+            title: "Pipeline: Resume",
+            category: Category::Workspace,
+            when: Some(|s| running_pipeline(s).is_some_and(|r| r.pause.is_paused())),
+            action: None,
+            handler: Box::new(|_, _| vec![CommandEffect::StateMutation(Box::new(|s| s.set_pipeline_paused(false)))]),
+        },
+        Command {
+            id: "cache.clear",
+            title: "Cache: Clear Responses",
+            category: Category::System,
+            when: None,
+            action: None,
+            handler: Box::new(|_, _| vec![CommandEffect::StateMutation(Box::new(|s| s.clear_response_cache()))]),
+        },
+        Command {
+            id: "polling.pause",
+            title: "Polling: Pause",
+            category: Category::System,
+            when: Some(|s| !s.polling_paused),
+            action: None,
+            handler: Box::new(|_, _| {
+                vec![
+                    CommandEffect::StateMutation(Box::new(|s| s.polling_paused = true)),
+                    notify(NotificationLevel::Info, "Background polling paused"),
+                ]
+            }),
+        },
+        Command {
+            id: "polling.resume",
+            title: "Polling: Resume",
+            category: Category::System,
+            when: Some(|s| s.polling_paused),
+            action: None,
+            handler: Box::new(|_, _| {
+                vec![
+                    CommandEffect::StateMutation(Box::new(|s| s.polling_paused = false)),
+                    notify(NotificationLevel::Info, "Background polling resumed"),
+                ]
+            }),
+        },
+        Command {
+            id: "health.show",
+            title: "Health: Show Details",
+            category: Category::View,
+            when: None,
+            action: None,
+            handler: Box::new(|_, _| vec![CommandEffect::StateMutation(Box::new(|s| s.open_health()))]),
+        },
+        Command {
+            id: "workflow.run",
+            title: "Workflow: Run...",
+            category: Category::Workspace,
+            when: None,
+            action: None,
+            handler: Box::new(|_, _| vec![CommandEffect::StateMutation(Box::new(|s| s.open_workflows()))]),
+        },
+        Command {
+            id: "schedules.show",
+            title: "Schedules: Show Upcoming Runs",
+            category: Category::Workspace,
+            when: None,
+            action: None,
+            handler: Box::new(|_, _| vec![CommandEffect::StateMutation(Box::new(|s| s.open_schedules()))]),
+        },
+        Command {
+            id: "watch.selected_file",
+            title: "Watch: Re-run Last Prompt When Selected File Changes",
+            category: Category::Workspace,
+            when: None,
+            action: None,
+            handler: Box::new(|_, _| vec![CommandEffect::StateMutation(Box::new(|s| s.watch_selected_file()))]),
+        },
+        Command {
+            id: "watch.show",
+            title: "Watch: Show Bindings",
+            category: Category::Workspace,
+            when: None,
+            action: None,
+            handler: Box::new(|_, _| vec![CommandEffect::StateMutation(Box::new(|s| s.open_watches()))]),
+        },
+        Command {
+            id: "search.index",
+            title: "Search: Index Workspace",
+            category: Category::Workspace,
+            when: None,
+            action: None,
+            handler: Box::new(|_, _| vec![CommandEffect::StateMutation(Box::new(|s| s.index_requested = true))]),
+        },
+        Command {
+            id: "search.open",
+            title: "Search: Semantic Search",
+            category: Category::Workspace,
+            when: None,
+            action: None,
+            handler: Box::new(|_, _| vec![CommandEffect::StateMutation(Box::new(|s| s.open_search()))]),
+        },
+        Command {
+            id: "citations.show",
+            title: "Citations: Show Sources of Last Response",
+            category: Category::Agent,
+            when: Some(|s| !s.latest_citations().is_empty()),
+            action: Some(keymap::Action::Citations),
+            handler: Box::new(|_, _| vec![CommandEffect::StateMutation(Box::new(|s| s.open_citations()))]),
+        },
+        Command {
+            id: "search.detach",
+            title: "Search: Detach Results from Prompt",
+            category: Category::Workspace,
+            when: Some(|s| !s.search_context.is_empty()),
+            action: None,
+            handler: Box::new(|_, _| vec![CommandEffect::StateMutation(Box::new(|s| s.detach_search_results()))]),
+        },
+        Command {
+            id: "conversation.compact",
+            title: "Conversation: Compact History",
+            category: Category::Agent,
+            when: Some(|s| !s.conversation.is_empty()),
+            action: None,
+            handler: Box::new(|_, _| vec![CommandEffect::StateMutation(Box::new(|s| s.compaction_requested = true))]),
+        },
+        Command {
+            id: "model.switch",
+            title: "Model: Switch To...",
+            category: Category::Agent,
+            when: Some(|s| s.session.is_some()),
+            action: None,
+            handler: Box::new(|state, ctx| {
+                if state.session.is_none() {
+                    return vec![notify(NotificationLevel::Info, "Open a file first")];
+                }
+                let Some(model_id) = ctx.argument else {
+                    let choices = model_choices(state);
+                    if choices.is_empty() {
+                        return vec![
+                            CommandEffect::StateMutation(Box::new(|s| s.registry_reload_requested = true)),
+                            notify(NotificationLevel::Info, "Loading the model list; try again in a moment"),
+                        ];
+                    }
+                    return vec![CommandEffect::PickArgument {
+                        title: "Model: Switch To...".to_string(),
+                        choices,
+                    }];
+                };
+                let message = format!("Session model set to {}", model_id);
+                vec![
+                    CommandEffect::StateMutation(Box::new(move |s| s.set_session_model(&model_id))),
+                    notify(NotificationLevel::Info, &message),
+                ]
+            }),
+        },
+        Command {
+            id: "agent.reset_session",
+            title: "Agent: Reset Session",
+            category: Category::Agent,
+            when: Some(|s| s.session.is_some()),
+            action: None,
+            handler: Box::new(|state, _| {
+                if state.session.is_none() {
+                    return vec![notify(NotificationLevel::Info, "No active session")];
+                }
+                vec![
+                    CommandEffect::StateMutation(Box::new(|s| {
+                        s.session = None;
+                        s.thinking_log.clear();
+                        s.generated_code.clear();
+                        s.clear_generation_regions();
+                        s.conversation.clear();
+                    })),
+                    notify(NotificationLevel::Info, "Session reset"),
+                ]
+            }),
+        },
+        Command {
+            id: "api.health_check",
+            title: "API: Check Health",
+            category: Category::System,
+            when: None,
+            action: None,
+            handler: Box::new(|_, _| {
+                vec![CommandEffect::SpawnTask {
+                    task: Task::HealthCheck,
+                    on_success: Some(Box::new(|result| match result {
+                        TaskResult::HealthChecked(health) => ApiEvent::HealthUpdate(health).into(),
+                        _ => Event::NotificationShown {
+                            level: NotificationLevel::Warning,
+                            message: "Unexpected health check result".to_string(),
+                        },
+                    })),
+                    on_error: Some(Box::new(|error| ApiEvent::HealthFailed(error).into())),
+                }]
+            }),
+        },
+        Command {
+            id: "doctor.run",
+            title: "System: Run Doctor",
+            category: Category::System,
+            when: None,
+            action: None,
+            handler: Box::new(|_, _| {
+                vec![
+                    notify(NotificationLevel::Info, "Checking the environment..."),
+                    CommandEffect::SpawnTask {
+                        task: Task::Doctor,
+                        on_success: Some(Box::new(|result| match result {
+                            TaskResult::DoctorReport(report) => ApiEvent::DoctorReport(report).into(),
+                            _ => Event::NotificationShown {
+                                level: NotificationLevel::Warning,
+                                message: "Unexpected doctor result".to_string(),
+                            },
+                        })),
+                        on_error: None,
+                    },
+                ]
+            }),
+        },
+        Command {
+            id: "api.refresh_metrics",
+            title: "API: Refresh Metrics",
+            category: Category::System,
+            when: None,
+            action: None,
+            handler: Box::new(|_, _| {
+                vec![CommandEffect::SpawnTask {
+                    task: Task::FetchMetrics,
+                    on_success: Some(Box::new(|result| match result {
+                        TaskResult::MetricsFetched(metrics) => ApiEvent::MetricsUpdate(metrics).into(),
+                        _ => Event::NotificationShown {
+                            level: NotificationLevel::Warning,
+                            message: "Unexpected metrics result".to_string(),
+                        },
+                    })),
+                    on_error: None,
+                }]
+            }),
+        },
+        Command {
+            id: "registry.refresh",
+            title: "Registry: Refresh Models",
+            category: Category::System,
+            when: None,
+            action: None,
+            handler: Box::new(|_, _| {
+                vec![
+                    CommandEffect::StateMutation(Box::new(|s| {
+                        s.model_registry.invalidate();
+                        s.registry_reload_requested = true;
+                    })),
+                    notify(NotificationLevel::Info, "Reloading the model list"),
+                ]
+            }),
+        },
+        Command {
+            id: "system.quit",
+            title: "System: Quit",
+            category: Category::System,
+            when: None,
+            action: Some(keymap::Action::Quit),
+            handler: Box::new(|_, _| vec![CommandEffect::StateMutation(Box::new(|s| s.quit_requested = true))]),
+        },
+    ]
+}
+
+/// Commands whose title contains `query` (case-insensitive)
+pub fn filter<'a>(commands: &'a [Command], query: &str) -> Vec<&'a Command> {
+    let query = query.to_lowercase();
+    commands
+        .iter()
+        .filter(|cmd| cmd.title.to_lowercase().contains(&query))
+        .collect()
+}
+
+/// Heading of a group of palette entries
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Section {
+    /// The most frecent commands, above the rest while nothing is typed
+    Recent,
+    Category(Category),
+}
+
+/// Commands recently run, listed first while the filter is empty
+const RECENT: usize = 3;
+
+/// Available commands matching `query`, grouped by category (in
+/// `Category` order), the most frecent first within each
+pub fn sections(state: &AppState, query: &str, recents: &Recents, now: i64) -> Vec<(Section, Vec<&'static Command>)> {
+    let mut matching: Vec<&Command> = filter(registry(), query).into_iter().filter(|c| c.available(state)).collect();
+    matching.sort_by(|a, b| recents.score(b.id, now).total_cmp(&recents.score(a.id, now)));
+
+    let mut sections = Vec::new();
+    if query.is_empty() {
+        let recent: Vec<_> = matching.iter().copied().filter(|c| recents.score(c.id, now) > 0.0).take(RECENT).collect();
+        if !recent.is_empty() {
+            sections.push((Section::Recent, recent));
+        }
+    }
+    let mut categories: Vec<Category> = matching.iter().map(|c| c.category).collect();
+    categories.sort();
+    categories.dedup();
+    for category in categories {
+        let commands = matching.iter().copied().filter(|c| c.category == category).collect();
+        sections.push((Section::Category(category), commands));
+    }
+    sections
+}
+
+/// Keys that run `command` outside the palette, e.g. "Ctrl+E"
+pub fn key_hint(command: &Command) -> Option<String> {
+    let action = command.action?;
+    let binding = keymap::BINDINGS.iter().find(|b| b.action == Some(action))?;
+    Some(match binding.focus {
+        Some(pane) => format!("{} ({:?})", binding.keys_label(), pane),
+        None => binding.keys_label(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_is_case_insensitive() {
+        let ids: Vec<&str> = filter(registry(), "TOGGLE").iter().map(|c| c.id).collect();
+        assert_eq!(ids, ["view.toggle_sidebar", "view.toggle_inspector", "view.toggle_zen"]);
+    }
+
+    #[test]
+    fn test_toggle_sidebar_hides_it_and_moves_focus() {
+        // Focus starts on the Sidebar
+        let mut state = AppState::default();
+        let toggle = registry().iter().find(|c| c.id == "view.toggle_sidebar").unwrap();
+        for effect in toggle.execute(&state, CommandContext::default()) {
+            if let CommandEffect::StateMutation(mutate) = effect {
+                mutate(&mut state);
+            }
+        }
+
+        assert!(!state.show_sidebar);
+        assert!(!state.pane_visible(FocusPane::Sidebar));
+        assert_eq!(state.focus, FocusPane::Thinking);
+    }
+
+    #[test]
+    fn test_switch_model_asks_for_the_model() {
+        let mut state = AppState {
+            session: Some(crate::app::ActiveSession::new(
+                "src/lib.rs".into(),
+                &crate::app::vendors::Vendor::unknown(),
+                "gpt-4o".to_string(),
+            )),
+            ..Default::default()
+        };
+        state.model_registry.models = ["gpt-4o", "claude-3-5-sonnet"]
+            .map(|id| crate::app::api::ModelResponse {
+                model_id: id.to_string(),
+                vendor_id: String::new(),
+                capability_tier: String::new(),
+                context_window: 128_000,
+                cost_in_per_mil: 0.0,
+                cost_out_per_mil: 0.0,
+                function_call_support: false,
+                is_active: true,
+            })
+            .to_vec();
+        let switch = registry().iter().find(|c| c.id == "model.switch").unwrap();
+
+        let effects = switch.execute(&state, CommandContext::default());
+        let [CommandEffect::PickArgument { choices, .. }] = effects.as_slice() else {
+            panic!("no follow-up picker");
+        };
+        assert_eq!(choices[0].label, "gpt-4o (current)");
+
+        let ctx = CommandContext {
+            argument: Some("claude-3-5-sonnet".to_string()),
+            ..Default::default()
+        };
+        for effect in switch.execute(&state, ctx) {
+            if let CommandEffect::StateMutation(mutate) = effect {
+                mutate(&mut state);
+            }
+        }
+        assert_eq!(state.session.unwrap().model_id, "claude-3-5-sonnet");
+    }
+
+    #[test]
+    fn test_key_hints() {
+        let hint = |id: &str| key_hint(registry().iter().find(|c| c.id == id).unwrap());
+        assert_eq!(hint("prompt.edit_externally").as_deref(), Some("Ctrl+E"));
+        assert_eq!(hint("file.new").as_deref(), Some("n (Sidebar)"));
+        assert_eq!(hint("cache.clear"), None);
+    }
+
+    #[test]
+    fn test_apply_is_only_offered_with_a_generation() {
+        let mut state = AppState {
+            session: Some(crate::app::ActiveSession::new(
+                "src/lib.rs".into(),
+                &crate::app::vendors::Vendor::unknown(),
+                "gpt-4o".to_string(),
+            )),
+            ..Default::default()
+        };
+        let listed = |state: &AppState| -> Vec<Section> {
+            sections(state, "apply", &Recents::default(), 0).into_iter().map(|(section, _)| section).collect()
+        };
+        assert!(listed(&state).is_empty());
+
+        state.generated_code = "fn main() {}".to_string();
+        assert_eq!(listed(&state), [Section::Category(Category::Agent)]);
+        let apply = registry().iter().find(|c| c.id == "generation.apply").unwrap();
+        assert_eq!(apply.category, Category::Agent);
+    }
+
+    #[test]
+    fn test_reset_session_without_session_only_notifies() {
+        let reset = registry()
+            .iter().find(|c| c.id == "agent.reset_session").unwrap();
+        let effects = reset.execute(&AppState::default(), CommandContext::default());
+
+        assert!(matches!(effects.as_slice(), [CommandEffect::ShowNotification { .. }]));
+    }
 }
@@ -0,0 +1,384 @@
+//! API Event Dispatch
+//!
+//! Background API tasks report over the API channel; the event loop wraps
+//! each `ApiEvent` in `Event::Api` and hands it to `reduce` like any other
+//! event, which routes it here. Adding an API event kind only needs a new
+//! arm below.
+//!
+//! Effects the reducer cannot perform itself (restarting pollers, sending
+//! the offline queue, which needs the API sender) are requested through
+//! `AppState` flags the event loop picks up after reducing.
+
+use crate::app::{
+    self,
+    alerts::AlertEvent,
+    api::ApiEvent,
+    connection::PollerRequest,
+    read_only::ReadOnly,
+    thinking::{Level, ThinkingEntry},
+    AppState,
+};
+use super::events::Event;
+
+impl From<ApiEvent> for Event {
+    fn from(event: ApiEvent) -> Self {
+        Event::Api(event)
+    }
+}
+
+/// Apply one API event to state
+pub fn reduce_api(state: &mut AppState, event: ApiEvent) {
+    match event {
+        ApiEvent::MetricsUpdate(metrics) => {
+            state.backend_metrics.update(metrics);
+        }
+        ApiEvent::MetricsForbidden { status, detail } => {
+            state.add_debug_log(format!("Metrics refused ({}): {}", status, detail));
+            state.enter_read_only(ReadOnly::Forbidden { status });
+        }
+        ApiEvent::HealthUpdate(health) => {
+            let was_connected = state.api_connected;
+            state.api_connected = health.status.contains("healthy");
+            state.add_debug_log(format!("Health: {}", health.status));
+            state.apply_health(&health);
+            if state.api_connected && !was_connected {
+                state.reconnect = None;
+                state.poller_request = Some(PollerRequest::Resume);
+                state.flush_queue_requested = true;
+            }
+        }
+        ApiEvent::ReconnectAttempt(attempt) => {
+            let status = state.reconnect.get_or_insert_with(Default::default);
+            status.attempt = attempt;
+            status.next_retry = None;
+        }
+        ApiEvent::ReconnectFailed { attempt, error, retry_in } => match retry_in {
+            Some(delay) => {
+                let status = state.reconnect.get_or_insert_with(Default::default);
+                status.attempt = attempt;
+                status.next_retry = Some(std::time::Instant::now() + delay);
+                status.last_error = Some(error);
+            }
+            None => {
+                state.reconnect = None;
+                state.add_debug_log(format!("Reconnect gave up after {} attempts: {}", attempt, error));
+            }
+        },
+        ApiEvent::Reconnected(health) => {
+            let attempts = state.reconnect.take().map(|r| r.attempt).unwrap_or(1);
+            state.api_connected = true;
+            state.add_debug_log(format!("Reconnected after {} attempt(s): {}", attempts, health.status));
+            state.apply_health(&health);
+            state.poller_request = Some(PollerRequest::Restart);
+            state.flush_queue_requested = true;
+        }
+        ApiEvent::HealthFailed(error) => {
+            if state.api_connected {
+                state.add_debug_log(format!("IMS Core unreachable, prompts will be queued: {}", error));
+            }
+            state.api_connected = false;
+            state.health_unreachable(&error);
+        }
+        ApiEvent::GenerationComplete { key, response, cached, content, postprocess_failures } => {
+            state.finish_in_flight(&key);
+            // Batch responses go to History, not the conversation
+            if state.batch_prompt_finished(&key, Ok(&response), cached) {
+                return;
+            }
+            state.scheduled_prompt_finished(&key, None);
+            state.experiment_completed(&key, &response, cached);
+            state.postprocess_failed(postprocess_failures);
+            let mut prompt = String::new();
+            let mut replay_of = None;
+            let mut citations = Vec::new();
+            let answered = state.conversation.iter().position(|t| t.request.as_deref() == Some(key.as_str()));
+            if let Some(turn) = answered.map(|i| &mut state.conversation[i]) {
+                turn.request = None;
+                turn.response = Some(content.clone());
+                prompt = turn.prompt.clone();
+                replay_of = turn.replay_of.clone();
+                citations = turn.citations.clone();
+            }
+            // A cached answer was already saved and paid for when first generated
+            if !cached {
+                let mut record = app::history::GenerationRecord::from_response(&prompt, &response);
+                record.replay_of = replay_of;
+                if let Err(e) = app::history::save(&app::history::generations_dir(), &record) {
+                    state.add_debug_log(format!("Failed to save generation: {:#}", e));
+                }
+                state.record_request(app::metrics_store::RequestRecord::from_response(&response));
+            }
+            state
+                .code_blocks
+                .extend(app::blocks::extract_blocks(&response.content));
+            match answered {
+                Some(turn) => state.append_generation_for(turn, &content),
+                None => state.append_generation(&content),
+            }
+            // A cached answer's latency is the original request's
+            let over_budget = if cached { None } else { state.check_latency(&response.model_id, response.latency_ms) };
+            let level = if over_budget.is_some() { Level::Warning } else { Level::Info };
+            let badge = match (cached, over_budget) {
+                (true, _) => " (cached)",
+                (false, Some(_)) => " ⏱ slow",
+                (false, None) => "",
+            };
+            // Answered by the local model server, not IMS Core
+            let local = if state.config.local.local_name(&response.model_id).is_some() { " (local, $0.00)" } else { "" };
+            state.add_thinking(
+                ThinkingEntry::agent(format!("◀ {}{}{}:", response.model_id, local, badge)).with_level(level),
+            );
+            for call in response.tool_calls.iter().cloned() {
+                state.add_thinking(ThinkingEntry::tool_call(call));
+            }
+            state.add_thinking(ThinkingEntry::agent(response.content.clone()));
+            state.record_image_outputs(&response.images);
+            state.record_transcripts(&response.transcripts);
+            state.record_citations(&citations, &response.content);
+            if cached {
+                state.add_thinking(ThinkingEntry::system(format!(
+                    "Served from cache: no tokens spent (originally {} tokens, ${:.6})",
+                    response.tokens.total, response.cost.total
+                )));
+            } else {
+                let budget = over_budget.map(|ms| format!(", over the {}ms budget", ms)).unwrap_or_default();
+                state.add_thinking(
+                    ThinkingEntry::system(format!(
+                        "Finished in {:.2}ms{}. Tokens: {} (Cost: ${:.6})",
+                        response.latency_ms, budget, response.tokens.total, response.cost.total
+                    ))
+                    .with_level(level),
+                );
+                state.total_tokens_used += response.tokens.total as u64;
+                state.add_cost(response.cost.total);
+            }
+            if app::compaction::needed(state) {
+                state.compaction_requested = true;
+            }
+            state.alert(AlertEvent::GenerationComplete);
+        }
+        ApiEvent::IndexProgress { done, total } => state.search_view.indexing = Some((done, total)),
+        ApiEvent::IndexComplete(summary) => state.finish_index(summary),
+        ApiEvent::SearchResults { query, hits } => state.receive_search_results(query, hits),
+        ApiEvent::SearchFailed(error) => state.search_failed(error),
+        ApiEvent::HistoryCompacted { through, prompt, summary, model_id, tokens, cost } => {
+            // Paid for whether or not the summary is still wanted
+            state.total_tokens_used += tokens as u64;
+            state.add_cost(cost);
+            state.finish_compaction(through, &prompt, summary, &model_id)
+        }
+        ApiEvent::CompactionFailed(error) => state.compaction_failed(error),
+        ApiEvent::AttachmentsRead { findings, skipped, uploading } => {
+            state.report_findings(findings);
+            for reason in skipped {
+                state.add_thinking(ThinkingEntry::system(format!("⚠ Not sent: {}", reason)).with_level(Level::Warning));
+            }
+            if let Some(uploading) = uploading {
+                state.add_thinking(ThinkingEntry::system(format!("⬆ Uploading {}", uploading)));
+            }
+        }
+        ApiEvent::UploadProgress { key, percent } => {
+            if let Some(request) = state.in_flight.iter().find(|r| r.key == key) {
+                let message = format!("⬆ Uploading to {}: {}%", request.model_id, percent);
+                state.add_thinking(ThinkingEntry::system(message));
+            }
+        }
+        ApiEvent::GenerationFailed { key, model_id, latency_ms, error } => {
+            state.finish_in_flight(&key);
+            state.record_request(app::metrics_store::RequestRecord::failed(model_id.clone(), latency_ms, error.clone()));
+            if state.batch_prompt_finished(&key, Err(error.clone()), false) {
+                return;
+            }
+            state.scheduled_prompt_finished(&key, Some(&error));
+            state.experiment_failed(&key);
+            tracing::error!("Prompt failed: {}", error);
+            state.add_debug_log(format!("API Error: Prompt failed: {}", error));
+            state.add_thinking(
+                ThinkingEntry::system(format!("✗ Prompt failed: {}", error)).with_level(Level::Error),
+            );
+            state.alert(AlertEvent::Error);
+        }
+        ApiEvent::RateLimited { request, prompt_text, retry_after, latency_ms, error } => {
+            let (key, model_id, retries) = (request.key.clone(), request.model_id.clone(), request.rate_limit_retries);
+            state.record_request(app::metrics_store::RequestRecord::failed(model_id, latency_ms, error.clone()));
+            // Still in flight until the retry is answered
+            if !state.schedule_rate_limit_retry(request, prompt_text, retry_after) {
+                state.finish_in_flight(&key);
+                let gave_up = format!("{} (gave up after {} retries)", error, retries);
+                if state.batch_prompt_finished(&key, Err(gave_up), false) {
+                    return;
+                }
+                state.scheduled_prompt_finished(&key, Some(&error));
+                state.experiment_failed(&key);
+                state.add_debug_log(format!("API Error: Prompt failed: {}", error));
+                state.add_thinking(
+                    ThinkingEntry::system(format!("✗ Prompt failed: {} (gave up after {} retries)", error, retries))
+                        .with_level(Level::Error),
+                );
+                state.alert(AlertEvent::Error);
+            }
+        }
+        ApiEvent::ModelInfo(model) => {
+            state.add_debug_log(format!(
+                "Model {}: {} token context",
+                model.model_id, model.context_window
+            ));
+            state.model_catalog.insert(model.model_id.clone(), model);
+        }
+        ApiEvent::ModelsLoaded { page, append } => {
+            state.add_debug_log(format!("Loaded {} models from registry", page.models.len()));
+            state.apply_model_page(page, append);
+            state.save_model_cache();
+        }
+        ApiEvent::ModelsUnchanged { local } => {
+            state.add_debug_log("Model registry unchanged since cached".to_string());
+            state.keep_cached_models(local);
+            state.save_model_cache();
+        }
+        ApiEvent::ModelsFailed(err) => {
+            state.add_debug_log(format!("Model registry fetch failed: {}", err));
+            state.model_registry.loading = false;
+        }
+        ApiEvent::ModelUpdated(model) => {
+            state.add_debug_log(format!("Registry updated: {}", model.model_id));
+            state.model_catalog.insert(model.model_id.clone(), model.clone());
+            state.model_registry.replace_model(model);
+        }
+        ApiEvent::ModelUpdateFailed { previous, error } => {
+            state.add_debug_log(format!("Admin action on {} failed, rolled back: {}", previous.model_id, error));
+            state.model_registry.replace_model(previous);
+        }
+        ApiEvent::AuthRequired { error, retry } => {
+            state.request_api_key(error, retry);
+        }
+        ApiEvent::Telemetry(entry) => {
+            state.add_telemetry(entry);
+        }
+        ApiEvent::TelemetryStatus(status) => {
+            state.add_debug_log(format!("Telemetry: {}", status));
+            state.telemetry_status = status;
+        }
+        ApiEvent::Network(entry) => {
+            state.add_network_entry(entry);
+        }
+        ApiEvent::SeriesUpdate(values) => {
+            state.record_series(values);
+        }
+        ApiEvent::TokensEstimated(estimates) => {
+            state.apply_token_estimates(&estimates);
+            state.add_debug_log(format!(
+                "Estimated tokens for {} files ({} total)",
+                estimates.len(),
+                app::tokens::format_token_count(state.workspace_tokens())
+            ));
+        }
+        ApiEvent::FileChanged(path) => {
+            state.file_changed(&path, std::time::Instant::now());
+        }
+        ApiEvent::DoctorReport(report) => {
+            state.show_doctor_report(&report);
+        }
+        ApiEvent::Recommendations { slow_model, models } => {
+            state.suggest_faster_model(&slow_model, models);
+        }
+        ApiEvent::Error(err) => {
+            tracing::error!("API Error: {}", err);
+            state.add_debug_log(format!("API Error: {}", err));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::api::HealthResponse;
+    use crate::core::reduce::reduce;
+
+    fn health(status: &str) -> HealthResponse {
+        HealthResponse {
+            status: status.to_string(),
+            database: "connected".to_string(),
+            cache: "connected".to_string(),
+            rabbitmq: None,
+        }
+    }
+
+    #[test]
+    fn test_recovery_requests_poller_resume_and_flush() {
+        let mut state = AppState::default();
+        reduce(&mut state, ApiEvent::HealthFailed("refused".to_string()).into());
+        assert!(!state.api_connected);
+        assert!(state.poller_request.is_none());
+
+        reduce(&mut state, ApiEvent::HealthUpdate(health("healthy")).into());
+        assert!(state.api_connected);
+        assert_eq!(state.poller_request, Some(PollerRequest::Resume));
+        assert!(state.flush_queue_requested);
+    }
+
+    #[test]
+    fn test_forbidden_metrics_enter_read_only() {
+        let mut state = AppState::default();
+        let forbidden = || ApiEvent::MetricsForbidden {
+            status: 403,
+            detail: "admin key required".to_string(),
+        };
+        reduce(&mut state, forbidden().into());
+        reduce(&mut state, forbidden().into());
+        assert_eq!(state.read_only, Some(ReadOnly::Forbidden { status: 403 }));
+        assert_eq!(state.debug_logs.iter().filter(|l| l.contains("Read-only")).count(), 1);
+    }
+
+    #[test]
+    fn test_responses_reach_the_turn_that_asked() {
+        let mut state = AppState::default();
+        for (prompt, key) in [("first", "k1"), ("second", "k2")] {
+            state.conversation.push(app::ConversationTurn {
+                request: Some(key.to_string()),
+                ..app::ConversationTurn::new(prompt, "gpt-4o")
+            });
+        }
+        // The second request finishes first; cached, so nothing is saved
+        for (key, answer) in [("k2", "answer 2"), ("k1", "answer 1")] {
+            let event = ApiEvent::GenerationComplete {
+                key: key.to_string(),
+                response: Box::new(crate::testing::response("gpt-4o", answer)),
+                cached: true,
+                content: answer.to_string(),
+                postprocess_failures: Vec::new(),
+            };
+            reduce(&mut state, event.into());
+        }
+        assert_eq!(state.conversation[0].response.as_deref(), Some("answer 1"));
+        assert_eq!(state.conversation[1].response.as_deref(), Some("answer 2"));
+        let turns: Vec<_> = state.generation_regions.iter().map(|r| r.turn).collect();
+        assert_eq!(turns, [1, 0]);
+    }
+
+    #[test]
+    fn test_upload_progress_names_the_model() {
+        let mut state = AppState::default();
+        let request = app::InFlightPrompt::new("Transcribe this".to_string(), "whisper-1".to_string());
+        let key = request.key.clone();
+        state.in_flight.push(request);
+        let read = ApiEvent::AttachmentsRead {
+            findings: Vec::new(),
+            skipped: vec!["standup.flac is not a supported audio format".to_string()],
+            uploading: Some("standup.mp3 (2.0 MB)".to_string()),
+        };
+        reduce(&mut state, read.into());
+        reduce(&mut state, ApiEvent::UploadProgress { key, percent: 50 }.into());
+        // Progress for a request no longer in flight is ignored
+        reduce(&mut state, ApiEvent::UploadProgress { key: "gone".to_string(), percent: 75 }.into());
+
+        let texts: Vec<_> = state.thinking_log.iter().map(|e| e.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            [
+                "⚠ Not sent: standup.flac is not a supported audio format",
+                "⬆ Uploading standup.mp3 (2.0 MB)",
+                "⬆ Uploading to whisper-1: 50%",
+            ]
+        );
+    }
+}
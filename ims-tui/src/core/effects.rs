@@ -14,6 +14,7 @@ pub enum CommandEffect {
     },
     
     /// Emit telemetry
+    #[allow(dead_code)]
     EmitEvent(TelemetryEvent),
     
     /// Show notification
@@ -24,39 +25,62 @@ pub enum CommandEffect {
     
     /// Navigate to pane
     FocusPane(FocusPane),
+
+    /// Run pipeline steps per target, reporting progress as state mutations
+    RunPipeline(crate::app::pipeline::PipelineJob),
+
+    /// Ask for the command's argument in the palette, then run it again
+    /// with the choice
+    PickArgument {
+        title: String,
+        choices: Vec<crate::app::palette::Choice>,
+    },
 }
 
 #[derive(Debug, Clone)]
 pub enum Task {
+    // Not spawned by any command yet
+    #[allow(dead_code)]
     GenerateCode {
         file_path: std::path::PathBuf,
         vendor: String,
     },
     FetchMetrics,
     HealthCheck,
+    #[allow(dead_code)]
     ReadFile {
         path: std::path::PathBuf,
     },
+    #[allow(dead_code)]
     CopyToClipboard {
         text: String,
     },
+    #[allow(dead_code)]
     PasteFromClipboard,
+    /// Check the environment as `ims-tui doctor` does
+    Doctor,
 }
 
 #[derive(Debug, Clone)]
 pub enum TaskResult {
+    // Results of the tasks above that nothing spawns yet
+    #[allow(dead_code)]
     CodeGenerated {
         file_path: std::path::PathBuf,
         code: String,
     },
     MetricsFetched(crate::app::api::MetricsResponse),
     HealthChecked(crate::app::api::HealthResponse),
+    DoctorReport(crate::app::doctor::Report),
+    #[allow(dead_code)]
     FileContentLoaded {
         content: String,
     },
+    #[allow(dead_code)]
     ClipboardContentPasted {
         text: String,
     },
+    #[allow(dead_code)]
     Success,
 }
 
@@ -65,6 +89,9 @@ pub enum TelemetryEvent {
     CommandExecuted {
         id: &'static str,
     },
+    /// Raised by a watch or schedule firing rather than a command
+    Triggered,
+    #[allow(dead_code)]
     AgentToken {
         token: String,
         usage: u32,
@@ -1,44 +1,50 @@
 use crate::app::FocusPane;
-use super::effects::TelemetryEvent;
 
 /// Events are facts that have occurred
-#[derive(Debug, Clone)]
 pub enum Event {
-    // Agent Events
+    // Agent Events (agent streaming is not wired up yet)
+    #[allow(dead_code)]
     AgentToken {
         token: String,
         usage: u32,
     },
+    #[allow(dead_code)]
     AgentCompleted {
         result: String,
     },
+    #[allow(dead_code)]
     AgentFailed {
         error: String,
     },
     
-    // API Events
-    MetricsUpdated(crate::app::api::MetricsResponse),
-    HealthStatusChanged(String),
+    // API Events, routed through `dispatch::reduce_api`
+    Api(crate::app::api::ApiEvent),
     
     // UI Events
+    #[allow(dead_code)]
     FileSelected(usize),
     PaneFocused(FocusPane),
     
-    // File Events
+    // File Events (for `Task::ReadFile`, which no command spawns yet)
+    #[allow(dead_code)]
     FileContentLoaded {
         content: String,
     },
+    #[allow(dead_code)]
     FileLoadFailed {
         error: String,
     },
     
-    // Clipboard Events
+    // Clipboard Events (clipboard tasks are not wired up yet)
+    #[allow(dead_code)]
     ClipboardUpdated {
         action: String,
     },
+    #[allow(dead_code)]
     ClipboardContentPasted {
         text: String,
     },
+    #[allow(dead_code)]
     ClipboardError {
         error: String,
     },
@@ -61,8 +67,7 @@ impl std::fmt::Debug for Event {
             Event::AgentToken { token, usage } => f.debug_struct("AgentToken").field("token", token).field("usage", usage).finish(),
             Event::AgentCompleted { result } => f.debug_struct("AgentCompleted").field("result", result).finish(),
             Event::AgentFailed { error } => f.debug_struct("AgentFailed").field("error", error).finish(),
-            Event::MetricsUpdated(m) => f.debug_tuple("MetricsUpdated").field(m).finish(),
-            Event::HealthStatusChanged(s) => f.debug_tuple("HealthStatusChanged").field(s).finish(),
+            Event::Api(e) => f.debug_tuple("Api").field(e).finish(),
             Event::FileSelected(i) => f.debug_tuple("FileSelected").field(i).finish(),
             Event::PaneFocused(p) => f.debug_tuple("PaneFocused").field(p).finish(),
             Event::FileContentLoaded { content } => f.debug_struct("FileContentLoaded").field("content", content).finish(),
@@ -77,9 +82,31 @@ impl std::fmt::Debug for Event {
     }
 }
 
+impl super::bus::Prioritized for Event {
+    /// Informational log lines and streamed tokens are dropped under load;
+    /// the rest are kept
+    fn overflow(&self) -> super::bus::Overflow {
+        use super::{bus::Overflow, effects::NotificationLevel};
+        match self {
+            Event::NotificationShown { level: NotificationLevel::Info, .. }
+            | Event::ClipboardUpdated { .. }
+            | Event::AgentToken { .. } => Overflow::Drop,
+            _ => Overflow::Keep,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum Signal {
+    // Only SIGTSTP and SIGCONT are listened for so far
+    #[allow(dead_code)]
     Interrupt,
+    #[allow(dead_code)]
     Terminate,
+    #[allow(dead_code)]
     Quit,
+    /// SIGTSTP
+    Suspend,
+    /// SIGCONT
+    Continue,
 }
@@ -1,4 +1,22 @@
+//! Core Runtime
+//!
+//! `AppState` is owned by the event loop, which acts as the single writer.
+//! Everything else talks to it through messages:
+//! - Commands are pure and return `CommandEffect`s, which `runtime` applies
+//! - Background tasks send `Event`s (including `StateMutationRequested`
+//!   closures) over the core channel, and the loop feeds them to `reduce`
+//! - API tasks send `ApiEvent`s over their own channel; the loop wraps them
+//!   in `Event::Api` and they take the same path through `reduce`
+//! - Terminal input is read on its own thread (`terminal`) and drained
+//!   before each frame, so drawing never holds up key handling
+//!
+//! This keeps rendering lock-free while letting any task request a mutation.
+
+pub mod bus;
 pub mod commands;
+pub mod dispatch;
 pub mod effects;
 pub mod events;
 pub mod reduce;
+pub mod runtime;
+pub mod terminal;
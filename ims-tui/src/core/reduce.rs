@@ -1,40 +1,82 @@
-use crate::app::AppState;
+use crate::app::{
+    thinking::{Level, ThinkingEntry},
+    AppState,
+};
+use super::effects::NotificationLevel;
 use super::events::{Event, Signal};
 
 /// Central reducer: applies events to state
 pub fn reduce(state: &mut AppState, event: Event) {
     match event {
         Event::AgentToken { token, usage } => {
-            state.thinking_log.push(format!("Token: {}", token));
+            state.add_thinking(ThinkingEntry::agent(format!("Token: {}", token)));
             state.total_tokens_used += usage as u64;
         }
-        
-        Event::FileSelected(index) => {
-            state.selected_file_index = index;
+
+        Event::AgentCompleted { result } => {
+            state.append_generation(&result);
+        }
+
+        Event::AgentFailed { error } => {
+            state.add_thinking(ThinkingEntry::agent(format!("✗ Agent failed: {}", error)).with_level(Level::Error));
         }
-        
-        Event::MetricsUpdated(metrics) => {
-            if let Some(total) = metrics.total_models_registered {
-                state.add_debug_log(format!("Models registered: {}", total));
+
+        Event::FileSelected(index) => {
+            if let Some(node) = state.file_tree.get(index) {
+                state.tree_state.borrow_mut().select(vec![node.id.clone()]);
             }
         }
-        
-        Event::HealthStatusChanged(status) => {
-            state.api_connected = status == "healthy";
-            state.add_debug_log(format!("Health: {}", status));
+
+        Event::PaneFocused(pane) => {
+            state.focus = pane;
         }
-        
+
+        Event::Api(event) => super::dispatch::reduce_api(state, event),
+
+        Event::FileContentLoaded { content } => {
+            state.add_debug_log(format!("Loaded {} bytes", content.len()));
+        }
+
+        Event::FileLoadFailed { error } | Event::ClipboardError { error } => {
+            state.add_debug_log(format!("Error: {}", error));
+        }
+
+        Event::ClipboardUpdated { action } => {
+            state.add_debug_log(format!("Clipboard: {}", action));
+        }
+
+        Event::ClipboardContentPasted { text } => {
+            state.input_buffer.insert_str(&text);
+        }
+
         Event::StateMutationRequested(mutation) => {
             mutation(state);
         }
-        
+
+        Event::NotificationShown { level, message } => {
+            let icon = match level {
+                NotificationLevel::Info => "ℹ",
+                NotificationLevel::Warning => "⚠",
+                NotificationLevel::Error => "✗",
+            };
+            state.add_debug_log(format!("{} {}", icon, message));
+        }
+
         Event::SignalReceived(Signal::Interrupt) => {
             state.add_debug_log("Signal Interrupt received".to_string());
             // Logic to cancel agent or exit would go here
         }
-        
-        _ => {
-            // Unhandled events
+
+        Event::SignalReceived(Signal::Terminate | Signal::Quit) => {
+            state.quit_requested = true;
+        }
+
+        Event::SignalReceived(Signal::Suspend) => {
+            state.suspend_requested = true;
+        }
+
+        Event::SignalReceived(Signal::Continue) => {
+            state.redraw_requested = true;
         }
     }
 }
@@ -0,0 +1,196 @@
+//! Effect Runtime
+//!
+//! Applies `CommandEffect`s against the state owned by the event loop and
+//! runs `Task`s in the background, reporting back as `Event`s.
+
+use super::commands::{Command, CommandContext};
+use super::effects::{CommandEffect, NotificationLevel, Task, TaskResult, TelemetryEvent};
+use super::events::Event;
+use crate::app::{api::ExecuteRequest, AppState};
+
+/// Channel into the event loop's reducer
+pub type EventSender = super::bus::Sender<Event>;
+
+/// Ask the event loop to apply `mutation` on its next tick
+pub fn request_mutation(tx: &EventSender, mutation: impl FnOnce(&mut AppState) + Send + 'static) {
+    let _ = tx.send(Event::StateMutationRequested(Box::new(mutation)));
+}
+
+pub struct Runtime {
+    tx: EventSender,
+}
+
+impl Runtime {
+    pub fn new(tx: EventSender) -> Self {
+        Self { tx }
+    }
+
+    /// Sender handed to background tasks
+    pub fn sender(&self) -> EventSender {
+        self.tx.clone()
+    }
+
+    /// Run a command and apply the effects it returns
+    pub fn execute(&self, state: &mut AppState, command: &Command) {
+        let effects = command.execute(state, CommandContext::default());
+        self.apply(state, TelemetryEvent::CommandExecuted { id: command.id }, effects);
+    }
+
+    /// Run a command with the argument picked for it
+    pub fn execute_with(&self, state: &mut AppState, command: &Command, argument: String) {
+        let ctx = CommandContext {
+            argument: Some(argument),
+            ..Default::default()
+        };
+        let effects = command.execute(state, ctx);
+        self.apply(state, TelemetryEvent::CommandExecuted { id: command.id }, effects);
+    }
+
+    /// Apply effects that something other than a command raised, e.g. a
+    /// watch re-running a workflow
+    pub fn perform(&self, state: &mut AppState, effects: Vec<CommandEffect>) {
+        self.apply(state, TelemetryEvent::Triggered, effects);
+    }
+
+    fn apply(&self, state: &mut AppState, origin: TelemetryEvent, effects: Vec<CommandEffect>) {
+        tracing::debug!(?origin, effects = effects.len(), "applying effects");
+
+        for effect in effects {
+            match effect {
+                CommandEffect::StateMutation(mutation) => mutation(state),
+                CommandEffect::SpawnTask {
+                    task,
+                    on_success,
+                    on_error,
+                } => {
+                    let tx = self.tx.clone();
+                    let client = state.api_client.clone();
+                    tokio::spawn(async move {
+                        match run_task(client, task).await {
+                            Ok(result) => {
+                                if let Some(on_success) = on_success {
+                                    let _ = tx.send(on_success(result));
+                                }
+                            }
+                            Err(error) => {
+                                let event = match on_error {
+                                    Some(on_error) => on_error(error),
+                                    None => Event::NotificationShown {
+                                        level: NotificationLevel::Error,
+                                        message: error,
+                                    },
+                                };
+                                let _ = tx.send(event);
+                            }
+                        }
+                    });
+                }
+                CommandEffect::EmitEvent(event) => {
+                    tracing::info!(?event, "telemetry");
+                }
+                CommandEffect::ShowNotification { level, message } => {
+                    super::reduce::reduce(state, Event::NotificationShown { level, message });
+                }
+                CommandEffect::FocusPane(pane) => {
+                    super::reduce::reduce(state, Event::PaneFocused(pane));
+                }
+                CommandEffect::RunPipeline(job) => match state.api_client.clone() {
+                    Some(client) => {
+                        tokio::spawn(crate::app::pipeline::run(client, self.tx.clone(), job));
+                    }
+                    None => state.add_debug_log("Error: API Client not initialized".to_string()),
+                },
+                CommandEffect::PickArgument { title, choices } => {
+                    if let TelemetryEvent::CommandExecuted { id } = origin {
+                        state.pick_argument(id, title, choices);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Execute a background task
+async fn run_task(client: Option<crate::app::api::ImsApiClient>, task: Task) -> Result<TaskResult, String> {
+    let client = || client.clone().ok_or_else(|| "API client not initialized".to_string());
+
+    match task {
+        Task::FetchMetrics => client()?
+            .get_metrics()
+            .await
+            .map(TaskResult::MetricsFetched)
+            .map_err(|e| e.to_string()),
+        Task::HealthCheck => client()?
+            .health_check()
+            .await
+            .map(TaskResult::HealthChecked)
+            .map_err(|e| e.to_string()),
+        Task::ReadFile { path } => tokio::fs::read_to_string(&path)
+            .await
+            .map(|content| TaskResult::FileContentLoaded { content })
+            .map_err(|e| format!("{}: {}", path.display(), e)),
+        Task::GenerateCode { file_path, vendor } => {
+            let req = ExecuteRequest {
+                prompt: format!("Generate the contents of {}", file_path.display()),
+                model_id: vendor,
+                max_tokens: Some(crate::app::api::MAX_TOKENS),
+                temperature: 0.7,
+                system_instruction: None,
+                user_id: None,
+                bypass_policies: false,
+                idempotency_key: None,
+                images: Vec::new(),
+                audio: Vec::new(),
+            };
+            client()?
+                .execute_prompt(req)
+                .await
+                .map(|response| TaskResult::CodeGenerated {
+                    file_path,
+                    code: response.content,
+                })
+                .map_err(|e| e.to_string())
+        }
+        Task::Doctor => Ok(TaskResult::DoctorReport(
+            crate::app::doctor::run(&crate::app::config::config_path()).await,
+        )),
+        Task::CopyToClipboard { .. } | Task::PasteFromClipboard => {
+            Err("Clipboard access is not available".to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_mutation_effect_applies_immediately() {
+        let (tx, _rx) = super::super::bus::channel(8);
+        let runtime = Runtime::new(tx);
+        let mut state = AppState::default();
+
+        runtime.apply(
+            &mut state,
+            TelemetryEvent::CommandExecuted { id: "test" },
+            vec![
+                CommandEffect::StateMutation(Box::new(|s| s.request_count = 7)),
+                CommandEffect::FocusPane(crate::app::FocusPane::Prompt),
+            ],
+        );
+
+        assert_eq!(state.request_count, 7);
+        assert_eq!(state.focus, crate::app::FocusPane::Prompt);
+    }
+
+    #[test]
+    fn test_requested_mutation_is_reduced() {
+        let (tx, mut rx) = super::super::bus::channel(8);
+        let mut state = AppState::default();
+
+        request_mutation(&tx, |s| s.total_cost = 1.5);
+        super::super::reduce::reduce(&mut state, rx.try_recv().unwrap());
+
+        assert_eq!(state.total_cost, 1.5);
+    }
+}
@@ -0,0 +1,241 @@
+//! Terminal Input Reader
+//!
+//! Reads crossterm events on a dedicated thread so keys keep being
+//! collected while the event loop is busy drawing; the loop drains them
+//! all before the next frame instead of handling one per draw.
+//!
+//! Also sets up and restores the terminal modes the TUI runs in, so it can
+//! step aside for a program that needs the terminal (an external editor)
+//! or for the shell when suspended with Ctrl+Z, and take it back
+//! afterwards.
+
+use std::io::{self, Stdout, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use crossterm::{
+    event::{self, Event, KeyboardEnhancementFlags, MouseEventKind},
+    execute,
+    terminal::{self as term, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{backend::CrosstermBackend, Terminal};
+
+use super::bus::{self, Overflow, Prioritized};
+use super::events::{Event as CoreEvent, Signal};
+use super::runtime::EventSender;
+
+/// How long a read waits before checking whether the loop has exited
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+impl Prioritized for Event {
+    /// Pointer motion only matters at its latest position; keys, clicks
+    /// and pastes are never lost
+    fn overflow(&self) -> Overflow {
+        match self {
+            Event::Mouse(mouse) if matches!(mouse.kind, MouseEventKind::Moved | MouseEventKind::Drag(_)) => {
+                Overflow::Coalesce("mouse_motion")
+            }
+            Event::Resize(..) => Overflow::Coalesce("resize"),
+            Event::FocusGained | Event::FocusLost => Overflow::Drop,
+            _ => Overflow::Keep,
+        }
+    }
+}
+
+/// XTWINOPS: save the window title on the terminal's title stack, and
+/// restore it (terminals without a stack ignore both)
+const PUSH_TITLE: &[u8] = b"\x1b[22;0t";
+const POP_TITLE: &[u8] = b"\x1b[23;0t";
+
+/// Whether keyboard enhancement was turned on, so it is turned off again
+static KEYBOARD_ENHANCED: AtomicBool = AtomicBool::new(false);
+
+/// Raw mode, the alternate screen, mouse capture and, where supported,
+/// modifier reporting (so pane numbers show while Alt is held)
+pub fn enter() -> Result<()> {
+    term::enable_raw_mode().context("Failed to enable raw mode")?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen, event::EnableMouseCapture).context("Failed to enter alternate screen")?;
+    let enhanced = term::supports_keyboard_enhancement().unwrap_or(false);
+    if enhanced {
+        execute!(
+            stdout,
+            event::PushKeyboardEnhancementFlags(
+                KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+                    | KeyboardEnhancementFlags::REPORT_EVENT_TYPES
+                    | KeyboardEnhancementFlags::REPORT_ALTERNATE_KEYS
+                    | KeyboardEnhancementFlags::REPORT_ALL_KEYS_AS_ESCAPE_CODES
+            )
+        )
+        .context("Failed to enable keyboard enhancement")?;
+    }
+    KEYBOARD_ENHANCED.store(enhanced, Ordering::SeqCst);
+    // Save the window title, for `leave` to put back
+    stdout.write_all(PUSH_TITLE).and_then(|()| stdout.flush()).context("Failed to save the window title")?;
+    Ok(())
+}
+
+/// Put the terminal back the way `enter` found it
+pub fn leave() -> Result<()> {
+    let mut stdout = io::stdout();
+    if KEYBOARD_ENHANCED.swap(false, Ordering::SeqCst) {
+        let _ = execute!(stdout, event::PopKeyboardEnhancementFlags);
+    }
+    let _ = stdout.write_all(POP_TITLE);
+    term::disable_raw_mode().context("Failed to disable raw mode")?;
+    execute!(stdout, LeaveAlternateScreen, event::DisableMouseCapture, crossterm::cursor::Show)
+        .context("Failed to leave alternate screen")
+}
+
+/// Run `f` with the terminal handed back to the shell and input reading
+/// paused, then restore the TUI and redraw it in full
+pub fn suspended<T>(
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    reader: &InputReader,
+    f: impl FnOnce() -> T,
+) -> Result<T> {
+    reader.pause();
+    leave()?;
+    let result = f();
+    enter()?;
+    reader.resume();
+    terminal.clear().context("Failed to redraw the terminal")?;
+    Ok(result)
+}
+
+/// Re-apply the TUI's modes after the process was stopped and continued
+/// behind its back (the shell may have reset them), and redraw in full
+pub fn refresh(terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> Result<()> {
+    term::enable_raw_mode().context("Failed to enable raw mode")?;
+    execute!(io::stdout(), EnterAlternateScreen, event::EnableMouseCapture)
+        .context("Failed to enter alternate screen")?;
+    terminal.clear().context("Failed to redraw the terminal")
+}
+
+/// Set the window title (OSC 0), which tmux takes as the pane's title
+pub fn set_title(title: &str) -> Result<()> {
+    execute!(io::stdout(), term::SetTitle(title)).context("Failed to set the window title")
+}
+
+/// Put back the window title `enter` saved, keeping it saved for `leave`
+pub fn restore_title() -> Result<()> {
+    let mut stdout = io::stdout();
+    stdout
+        .write_all(POP_TITLE)
+        .and_then(|()| stdout.write_all(PUSH_TITLE))
+        .and_then(|()| stdout.flush())
+        .context("Failed to restore the window title")
+}
+
+/// Ring the terminal bell; in a background tmux pane this flags the window
+pub fn bell() -> Result<()> {
+    let mut stdout = io::stdout();
+    stdout.write_all(b"\x07").and_then(|()| stdout.flush()).context("Failed to ring the bell")
+}
+
+/// Stop the process group as Ctrl+Z would in a cooked terminal, returning
+/// once the shell continues it (`fg`)
+pub fn stop() {
+    // SIGTSTP is handled by `spawn_signal_listener`, so SIGSTOP it is;
+    // the whole group, so a `cargo run` parent gives the shell back too
+    #[cfg(unix)]
+    // SAFETY: kill has no memory-safety preconditions
+    unsafe {
+        libc::kill(0, libc::SIGSTOP);
+    }
+}
+
+/// Turn SIGTSTP and SIGCONT into `Signal::Suspend` and `Signal::Continue`
+/// events; the terminal has to be restored before the process stops, so
+/// the default stop is left to the event loop
+pub fn spawn_signal_listener(tx: EventSender) {
+    #[cfg(unix)]
+    tokio::spawn(async move {
+        use tokio::signal::unix::{signal, SignalKind};
+        let (Ok(mut tstp), Ok(mut cont)) = (
+            signal(SignalKind::from_raw(libc::SIGTSTP)),
+            signal(SignalKind::from_raw(libc::SIGCONT)),
+        ) else {
+            tracing::warn!("Failed to listen for job control signals");
+            return;
+        };
+        loop {
+            let signal = tokio::select! {
+                Some(()) = tstp.recv() => Signal::Suspend,
+                Some(()) = cont.recv() => Signal::Continue,
+                else => break,
+            };
+            if tx.send(CoreEvent::SignalReceived(signal)).is_err() {
+                break;
+            }
+        }
+    });
+    #[cfg(not(unix))]
+    drop(tx);
+}
+
+/// The input thread, which can be paused while another program reads the
+/// terminal
+pub struct InputReader {
+    handle: JoinHandle<()>,
+    paused: Arc<AtomicBool>,
+    /// Set while the thread may be waiting on the terminal
+    reading: Arc<AtomicBool>,
+}
+
+impl InputReader {
+    /// Stop reading, returning once any read in progress has finished
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::SeqCst);
+        while self.reading.load(Ordering::SeqCst) {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::SeqCst);
+    }
+
+    pub fn join(self) -> std::thread::Result<()> {
+        self.handle.join()
+    }
+}
+
+/// Forward terminal events to `tx` until its receiver is dropped
+pub fn spawn_reader(tx: bus::Sender<Event>) -> io::Result<InputReader> {
+    let paused = Arc::new(AtomicBool::new(false));
+    let reading = Arc::new(AtomicBool::new(false));
+    let (thread_paused, thread_reading) = (paused.clone(), reading.clone());
+    let handle = std::thread::Builder::new().name("input".to_string()).spawn(move || {
+        while !tx.is_closed() {
+            // Flagged before checking for a pause, so `pause` either sees
+            // the read or the read sees the pause
+            thread_reading.store(true, Ordering::SeqCst);
+            if thread_paused.load(Ordering::SeqCst) {
+                thread_reading.store(false, Ordering::SeqCst);
+                std::thread::sleep(POLL_INTERVAL);
+                continue;
+            }
+            match event::poll(POLL_INTERVAL) {
+                Ok(true) => match event::read() {
+                    Ok(event) => {
+                        if tx.send(event).is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) => tracing::warn!("Failed to read terminal event: {}", e),
+                },
+                Ok(false) => {}
+                Err(e) => {
+                    tracing::error!("Terminal input failed: {}", e);
+                    break;
+                }
+            }
+        }
+        thread_reading.store(false, Ordering::SeqCst);
+    })?;
+    Ok(InputReader { handle, paused, reading })
+}
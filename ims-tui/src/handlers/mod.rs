@@ -1,45 +1,138 @@
 pub mod scroll;
 
-use crate::app::{api::{ApiEvent, ExecuteRequest}, AppState, FocusPane, InputMode, FileNode};
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind};
+use crate::app::{
+    api::{ApiEvent, ApiSender, ExecuteRequest, FilterParams, ImsApiClient, ModelResponse, PageToken, PricingUpdate},
+    api_error::ApiError,
+    api_key::RetryRequest,
+    blocks::{self, BlocksMode},
+    citations,
+    embeddings,
+    external_editor::EditTarget,
+    config::GuardAction,
+    guardrails::{Finding, Guardrails},
+    images::{self, ImageInput},
+    audio::{self, AudioInput},
+    context_menu::{ContextMenu, MenuAction, MenuTarget},
+    history::GenerationRecord,
+    input::TextInput,
+    modal::{Ask, Modal},
+    overlay::Overlay,
+    keymap::{self, Action},
+    cache::{self, ResponseCache},
+    clipboard, context,
+    offline::QueuedPrompt,
+    palette::Choice,
+    postprocess,
+    prompt_queue::Priority,
+    schedule,
+    registry::{file_type_key, parse_pricing, AdminAction, AdminMenu, SortColumn},
+    selection::Selection,
+    settings,
+    thinking::{Level, Source, ThinkingEntry},
+    workflows,
+    AppState, ConversationTurn, FocusPane, InFlightPrompt, InputMode,
+};
+use crate::core::{commands, effects::CommandEffect, runtime::Runtime};
+use crossterm::event::{
+    KeyCode, KeyEvent, KeyEventKind, KeyModifiers, ModifierKeyCode, MouseButton, MouseEvent, MouseEventKind,
+};
 use ratatui::layout::Rect;
-use tokio::sync::mpsc;
-use tui_tree_widget::TreeItem;
+use tracing::Instrument;
+
+/// Two clicks on the same cell within this interval make a double click
+const DOUBLE_CLICK_INTERVAL: std::time::Duration = std::time::Duration::from_millis(400);
 
 /// Handle mouse input
-pub fn handle_mouse_event(state: &mut AppState, mouse: MouseEvent, terminal_size: Rect) -> bool {
+pub fn handle_mouse_event(
+    state: &mut AppState,
+    mouse: MouseEvent,
+    terminal_size: Rect,
+    api_tx: &ApiSender,
+) -> bool {
     let col = mouse.column;
     let row = mouse.row;
-    
-    let sidebar_width = (terminal_size.width as f32 * 0.2) as u16;
-    let inspector_start = (terminal_size.width as f32 * 0.8) as u16;
-    
+
+    // Only the top of the overlay stack takes clicks
+    if let Some(top) = state.overlays.top() {
+        if let MouseEventKind::Down(button) = mouse.kind {
+            click_overlay(state, top, button, terminal_size, col, row, api_tx);
+        }
+        return true;
+    }
+
+    let layout = crate::ui::pane_layout(state, terminal_size);
+    if mouse.kind == MouseEventKind::Down(MouseButton::Left) {
+        let bar = layout.status_bar;
+        if row == bar.y && col >= bar.x && col < bar.x + bar.width {
+            state.open_health();
+            return true;
+        }
+        if let Some(bar) = layout.breadcrumb.filter(|bar| bar.contains(ratatui::layout::Position::new(col, row))) {
+            if let Some(index) = crate::ui::breadcrumb::crumb_at(state, bar, col) {
+                state.open_file_dropdown(index, col, row + 1);
+            }
+            return true;
+        }
+    }
+    let clicked = layout.pane_at(col, row);
+
     // Check click-to-focus
-    if mouse.kind == MouseEventKind::Down(crossterm::event::MouseButton::Left) {
-        if col < sidebar_width {
-            state.focus = FocusPane::Sidebar;
-        } else if col >= inspector_start {
-            state.focus = FocusPane::Inspector;
-        } else {
-            let prompt_start_y = terminal_size.height.saturating_sub(3);
-            
-            if row >= prompt_start_y {
-                state.focus = FocusPane::Prompt;
-                state.input_mode = InputMode::Editing;
-            } else {
-                let workspace_height = prompt_start_y;
-                let mid_point = workspace_height / 2;
-                
-                if row < mid_point {
-                    state.focus = FocusPane::Thinking;
-                } else {
-                    state.focus = FocusPane::Generation;
-                }
+    if mouse.kind == MouseEventKind::Down(MouseButton::Left) {
+        if let Some(pane) = clicked {
+            if pane != state.focus {
+                state.maximized = None;
+            }
+            state.focus = pane;
+            match pane {
+                FocusPane::Sidebar => click_tree_item(state, col, row, api_tx),
+                FocusPane::Prompt => state.input_mode = InputMode::Editing,
+                _ => {}
             }
         }
     }
 
+    let lines = i16::from(state.scroll_lines);
     match mouse.kind {
+        MouseEventKind::Down(MouseButton::Right) => {
+            if clicked == Some(FocusPane::Sidebar) {
+                state.focus = FocusPane::Sidebar;
+                if let Some(id) = state
+                    .tree_state
+                    .borrow()
+                    .rendered_at(ratatui::layout::Position::new(col, row))
+                    .map(<[String]>::to_vec)
+                {
+                    state.tree_state.borrow_mut().select(id);
+                }
+                open_context_menu(state, FocusPane::Sidebar, col, row);
+            } else if state
+                .session
+                .as_ref()
+                .is_some_and(|s| s.generation.area.get().contains(ratatui::layout::Position::new(col, row)))
+            {
+                state.focus = FocusPane::Generation;
+                open_context_menu(state, FocusPane::Generation, col, row);
+            }
+        }
+        MouseEventKind::Down(MouseButton::Left) => {
+            state.selection = None;
+            start_selection(state, col, row);
+            if clicked == Some(FocusPane::Thinking) {
+                click_tool_call(state, col, row);
+            }
+            if clicked == Some(FocusPane::Generation) {
+                click_generation_gutter(state, col, row);
+            }
+        }
+        MouseEventKind::Drag(MouseButton::Left) => extend_selection(state, col, row),
+        MouseEventKind::Up(MouseButton::Left) => {
+            if let Some(selection) = &mut state.selection {
+                selection.dragging = false;
+                if !selection.is_empty() {
+                    copy_selection(state);
+                }
+            }
+        }
         MouseEventKind::ScrollDown => {
             match state.focus {
                 FocusPane::Sidebar => {
@@ -47,12 +140,12 @@ pub fn handle_mouse_event(state: &mut AppState, mouse: MouseEvent, terminal_size
                 }
                 FocusPane::Thinking => {
                     if let Some(session) = &mut state.session {
-                        session.thinking.manual_scroll(1);
+                        session.thinking.manual_scroll(lines);
                     }
                 }
                 FocusPane::Generation => {
                     if let Some(session) = &mut state.session {
-                        session.generation.manual_scroll(1);
+                        session.generation.manual_scroll(lines);
                     }
                 }
                 _ => {}
@@ -65,12 +158,12 @@ pub fn handle_mouse_event(state: &mut AppState, mouse: MouseEvent, terminal_size
                 }
                 FocusPane::Thinking => {
                     if let Some(session) = &mut state.session {
-                        session.thinking.manual_scroll(-1);
+                        session.thinking.manual_scroll(-lines);
                     }
                 }
                 FocusPane::Generation => {
                     if let Some(session) = &mut state.session {
-                        session.generation.manual_scroll(-1);
+                        session.generation.manual_scroll(-lines);
                     }
                 }
                 _ => {}
@@ -81,18 +174,411 @@ pub fn handle_mouse_event(state: &mut AppState, mouse: MouseEvent, terminal_size
     true
 }
 
+/// A click while `overlay` is on top: popups act on it or close when it
+/// lands outside them; the full-screen overlays ignore the mouse
+fn click_overlay(
+    state: &mut AppState,
+    overlay: Overlay,
+    button: MouseButton,
+    terminal_size: Rect,
+    col: u16,
+    row: u16,
+    api_tx: &ApiSender,
+) {
+    match overlay {
+        Overlay::Modal => {
+            let Some(modal) = &mut state.modal else { return };
+            if button != MouseButton::Left {
+                return;
+            }
+            let area = modal.area(terminal_size);
+            if let Some(index) = modal.choice_at(area, col, row) {
+                if let Ask::Select { selected, .. } = &mut modal.ask {
+                    *selected = index;
+                }
+                state.answer_modal();
+            } else if !area.contains(ratatui::layout::Position { x: col, y: row }) {
+                state.dismiss_modal();
+            }
+        }
+        Overlay::ContextMenu => {
+            let Some(menu) = &mut state.context_menu else { return };
+            match menu.action_at(menu.area(terminal_size), col, row) {
+                Some(index) if button == MouseButton::Left => {
+                    menu.selected = index;
+                    run_menu_action(state, api_tx);
+                }
+                Some(_) => {}
+                None => state.close_overlay(Overlay::ContextMenu),
+            }
+        }
+        Overlay::FileDropdown => {
+            let Some(dropdown) = &mut state.file_dropdown else { return };
+            match dropdown.file_at(dropdown.area(terminal_size), col, row) {
+                Some(index) if button == MouseButton::Left => {
+                    dropdown.selected = index;
+                    open_dropdown_file(state, api_tx);
+                }
+                Some(_) => {}
+                None => state.close_overlay(Overlay::FileDropdown),
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Switch the session to the file picked from a breadcrumb's dropdown
+fn open_dropdown_file(state: &mut AppState, api_tx: &ApiSender) {
+    if state.open_dropdown_file() {
+        request_model_info(state, api_tx);
+    }
+}
+
+/// Breadcrumb dropdown: navigate, open the selected file or close
+fn handle_file_dropdown_input(state: &mut AppState, key: KeyEvent, api_tx: &ApiSender) -> bool {
+    let Some(dropdown) = &mut state.file_dropdown else { return true };
+    match key.code {
+        KeyCode::Up => dropdown.select_prev(),
+        KeyCode::Down => dropdown.select_next(),
+        KeyCode::Enter => open_dropdown_file(state, api_tx),
+        _ => {}
+    }
+    true
+}
+
+/// Open the context menu for `pane` anchored at screen cell `(x, y)`
+fn open_context_menu(state: &mut AppState, pane: FocusPane, x: u16, y: u16) {
+    let target = match pane {
+        FocusPane::Sidebar => match state.get_selected_node() {
+            Some(node) if !node.is_dir => MenuTarget::File(node.path.clone()),
+            _ => return,
+        },
+        FocusPane::Generation if state.session.is_some() => MenuTarget::Generation,
+        _ => return,
+    };
+    state.open_context_menu(ContextMenu::new(target, x, y));
+}
+
+/// Context menu: navigate or run the selected action
+fn handle_context_menu_input(state: &mut AppState, key: KeyEvent, api_tx: &ApiSender) -> bool {
+    let Some(menu) = &mut state.context_menu else { return true };
+
+    match key.code {
+        KeyCode::Up => menu.select_prev(),
+        KeyCode::Down => menu.select_next(),
+        KeyCode::Enter => run_menu_action(state, api_tx),
+        _ => {}
+    }
+    true
+}
+
+/// Modal: answer it, dismiss it, or edit the input / move the selection
+fn handle_modal_input(state: &mut AppState, key: KeyEvent) -> bool {
+    let Some(modal) = &mut state.modal else { return true };
+    match (&mut modal.ask, key.code) {
+        (Ask::Confirm, KeyCode::Char('n' | 'N')) => state.dismiss_modal(),
+        (_, KeyCode::Enter) | (Ask::Confirm, KeyCode::Char('y' | 'Y')) => state.answer_modal(),
+        (Ask::Select { .. }, KeyCode::Up) => modal.select_prev(),
+        (Ask::Select { .. }, KeyCode::Down) => modal.select_next(),
+        (Ask::Input(input), code) => match code {
+            KeyCode::Backspace => input.backspace(),
+            KeyCode::Delete => input.delete(),
+            KeyCode::Left => input.move_left(),
+            KeyCode::Right => input.move_right(),
+            KeyCode::Home => input.move_home(),
+            KeyCode::End => input.move_end(),
+            KeyCode::Char(c) => input.insert(c),
+            _ => {}
+        },
+        _ => {}
+    }
+    true
+}
+
+fn open_context_menu_for_focus(state: &mut AppState) {
+    let (x, y) = match state.focus {
+        FocusPane::Sidebar => (1, 1),
+        FocusPane::Generation => match &state.session {
+            Some(session) => {
+                let area = session.generation.area.get();
+                (area.x, area.y)
+            }
+            None => return,
+        },
+        _ => return,
+    };
+    open_context_menu(state, state.focus, x, y);
+}
+
+/// Ask for a new name for the file at `path`
+fn ask_rename(state: &mut AppState, path: std::path::PathBuf) {
+    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let message = format!("New name for {}", path.display());
+    let modal = Modal::input("Rename", name, move |state, name| {
+        let on_disk = path.clone();
+        let target = path.with_file_name(name.trim());
+        let rename = move |state: &mut AppState| match state.rename_file(&path, &name) {
+            Ok(new_path) => state.add_debug_log(format!("Renamed {} to {}", path.display(), new_path.display())),
+            Err(e) => state.add_debug_log(format!("Rename failed: {}", e)),
+        };
+        // Both ends of the move must be inside the workspace; entries
+        // without a file on disk only change in the tree
+        if on_disk.exists() {
+            state.write_in_workspace(&on_disk, move |state, _| {
+                state.write_in_workspace(&target, move |state, _| rename(state));
+            });
+        } else {
+            rename(state);
+        }
+    });
+    state.open_modal(modal.with_message(message));
+}
+
+/// Delete the file at `path` once the user confirms
+fn confirm_delete(state: &mut AppState, path: std::path::PathBuf) {
+    let message = format!("Delete {}? This can't be undone.", path.display());
+    let modal = Modal::confirm("Delete File", message, move |state| {
+        let on_disk = path.clone();
+        let delete = move |state: &mut AppState| match state.remove_file(&path) {
+            Ok(()) => state.add_debug_log(format!("Deleted {}", path.display())),
+            Err(e) => state.add_debug_log(format!("Delete failed: {}", e)),
+        };
+        // Entries without a file on disk only leave the tree
+        if on_disk.exists() {
+            state.write_in_workspace(&on_disk, move |state, _| delete(state));
+        } else {
+            delete(state);
+        }
+    });
+    state.open_modal(modal.destructive());
+}
+
+/// The file selected in the sidebar, if a file (not a directory) is
+fn selected_file(state: &AppState) -> Option<std::path::PathBuf> {
+    state.get_selected_node().filter(|node| !node.is_dir).map(|node| node.path.clone())
+}
+
+/// Run the context menu's selected action
+fn run_menu_action(state: &mut AppState, api_tx: &ApiSender) {
+    let Some(menu) = state.take_context_menu() else { return };
+    let Some(action) = menu.selected_action() else { return };
+    match (action, menu.target) {
+        (MenuAction::Open, MenuTarget::File(_)) => {
+            state.open_selected_file();
+            request_model_info(state, api_tx);
+        }
+        (MenuAction::Rename, MenuTarget::File(path)) => ask_rename(state, path),
+        (MenuAction::Delete, MenuTarget::File(path)) => confirm_delete(state, path),
+        (MenuAction::Attach, MenuTarget::File(path)) => {
+            let name = path.display().to_string();
+            match state.toggle_attachment(path) {
+                Ok(true) => state.add_debug_log(format!("Attached {} to prompt", name)),
+                Ok(false) => state.add_debug_log(format!("Detached {} from prompt", name)),
+                Err(e) => state.add_thinking(
+                    ThinkingEntry::system(format!("⚠ Not attached: {:#}", e)).with_level(Level::Warning),
+                ),
+            }
+        }
+        (MenuAction::Copy, MenuTarget::Generation) => match clipboard::copy(&state.generated_code) {
+            Ok(()) => state.add_debug_log(format!("Copied {} lines of generated code", state.generated_code.lines().count())),
+            Err(e) => state.add_debug_log(format!("Copy failed: {}", e)),
+        },
+        (MenuAction::Export, MenuTarget::Generation) => {
+            let extension = state
+                .session
+                .as_ref()
+                .and_then(|s| s.file_path.extension())
+                .map(|e| e.to_string_lossy().to_string())
+                .unwrap_or_else(|| "txt".to_string());
+            let path = unused_path_near_session(state, "generation", &extension);
+            let code = state.generated_code.clone();
+            state.write_in_workspace(&path, move |state, path| match std::fs::write(&path, code) {
+                Ok(()) => {
+                    state.add_debug_log(format!("Exported generation to {}", path.display()));
+                    state.add_file(path);
+                }
+                Err(e) => state.add_debug_log(format!("Failed to export {}: {}", path.display(), e)),
+            });
+        }
+        (MenuAction::Apply, MenuTarget::Generation) => state.apply_generation(),
+        _ => {}
+    }
+}
+
+/// Select the tree item under the pointer; a double click opens a file or
+/// toggles a directory
+fn click_tree_item(state: &mut AppState, col: u16, row: u16, api_tx: &ApiSender) {
+    let now = std::time::Instant::now();
+    let double = state
+        .last_click
+        .take()
+        .is_some_and(|(at, c, r)| (c, r) == (col, row) && now.duration_since(at) <= DOUBLE_CLICK_INTERVAL);
+
+    // Resolved against the last render, so the tree's scroll offset is accounted for
+    let Some(id) = state
+        .tree_state
+        .borrow()
+        .rendered_at(ratatui::layout::Position::new(col, row))
+        .map(<[String]>::to_vec)
+    else {
+        return;
+    };
+    state.tree_state.borrow_mut().select(id);
+
+    if double {
+        // Opens files and toggles directories
+        state.open_selected_file();
+        request_model_info(state, api_tx);
+    } else {
+        state.last_click = Some((now, col, row));
+    }
+}
+
+/// Expand or collapse a tool call by clicking its header row
+fn click_tool_call(state: &mut AppState, col: u16, row: u16) {
+    let Some(scroll) = pane_scroll(state, FocusPane::Thinking) else { return };
+    let width = scroll.area.get().width;
+    let Some((at, _)) = scroll.row_at(col, row) else { return };
+    if let Some((index, true)) = crate::ui::editor::thinking_entry_at(state, width, at) {
+        if let Some(citation) = state.thinking_log.get(index).and_then(|e| e.citation.clone()) {
+            state.jump_to_citation(citation);
+        }
+        state.toggle_tool_call(index);
+    }
+}
+
+/// Select the response whose gutter marker was clicked
+fn click_generation_gutter(state: &mut AppState, col: u16, row: u16) {
+    let Some(session) = &state.session else { return };
+    let area = session.generation.area.get();
+    let gutter = crate::ui::editor::generation_gutter(state);
+    if gutter == 0 || col >= area.x || col + gutter < area.x {
+        return;
+    }
+    let Some((at, _)) = session.generation.row_at(area.x, row) else { return };
+    let lines = crate::ui::editor::generation_row_lines(state, area.width);
+    if let Some(index) = lines.get(at).and_then(|&line| state.region_at_line(line)) {
+        state.select_region(index);
+        reveal_region(state, index);
+    }
+}
+
+/// Scroll the Thinking pane to the prompt of the selected region `index`
+fn reveal_region(state: &mut AppState, index: usize) {
+    let Some(entry) = state.highlighted_index() else {
+        let turn = state.generation_regions[index].turn;
+        state.add_debug_log(format!("The prompt for response #{} is no longer in the Thinking log", turn + 1));
+        return;
+    };
+    // A source filter could be hiding the prompt
+    if state.thinking_filter.is_some_and(|source| source != Source::User) {
+        state.thinking_filter = None;
+    }
+    let Some(width) = state.session.as_ref().map(|s| s.thinking.area.get().width) else { return };
+    let row = crate::ui::editor::thinking_entry_row(state, width, entry);
+    if let (Some(row), Some(session)) = (row, state.session.as_mut()) {
+        session.thinking.scroll_to(row);
+    }
+}
+
+/// Scroll the Generation pane to the first line of region `index`
+fn scroll_to_region(state: &mut AppState, index: usize) {
+    let Some(first_line) = state.region_lines().get(index).map(|lines| lines.start) else { return };
+    let Some(width) = state.session.as_ref().map(|s| s.generation.area.get().width) else { return };
+    let lines = crate::ui::editor::generation_row_lines(state, width);
+    if let (Some(row), Some(session)) = (lines.iter().position(|&line| line == first_line), state.session.as_mut()) {
+        session.generation.scroll_to(row);
+    }
+}
+
+/// Scroll state of a content pane that supports selection
+fn pane_scroll(state: &mut AppState, pane: FocusPane) -> Option<&mut crate::app::ScrollState> {
+    let session = state.session.as_mut()?;
+    match pane {
+        FocusPane::Thinking => Some(&mut session.thinking),
+        FocusPane::Generation => Some(&mut session.generation),
+        _ => None,
+    }
+}
+
+/// Begin a drag selection if the click landed on pane content
+fn start_selection(state: &mut AppState, col: u16, row: u16) {
+    let pane = state.focus;
+    let Some(scroll) = pane_scroll(state, pane) else { return };
+    let Some(at) = scroll.row_at(col, row) else { return };
+    // Hold the content still while selecting
+    scroll.freeze();
+    state.selection = Some(Selection::new(pane, at));
+}
+
+fn extend_selection(state: &mut AppState, col: u16, row: u16) {
+    let Some(pane) = state.selection.as_ref().filter(|s| s.dragging).map(|s| s.pane) else {
+        return;
+    };
+    let Some(scroll) = pane_scroll(state, pane) else { return };
+    // Dragging past the edges selects up to the edge
+    let area = scroll.area.get();
+    if area.is_empty() {
+        return;
+    }
+    let col = col.clamp(area.x, area.right() - 1);
+    let row = row.clamp(area.y, area.bottom() - 1);
+    let Some(at) = scroll.row_at(col, row) else { return };
+    if let Some(selection) = &mut state.selection {
+        selection.head = at;
+    }
+}
+
+/// Copy the selected text to the clipboard
+fn copy_selection(state: &mut AppState) {
+    let Some(selection) = state.selection.clone().filter(|s| !s.is_empty()) else { return };
+    let Some(width) = pane_scroll(state, selection.pane).map(|s| s.area.get().width) else { return };
+
+    let rows: Vec<String> = crate::ui::editor::pane_rows(state, selection.pane, width)
+        .iter()
+        .map(|line| line.to_string())
+        .collect();
+    let text = selection.text(&rows);
+    match clipboard::copy(&text) {
+        Ok(()) => state.add_debug_log(format!("Copied {} characters", text.chars().count())),
+        Err(e) => state.add_debug_log(format!("Copy failed: {}", e)),
+    }
+}
+
 /// Handle keyboard input
 pub fn handle_key_event(
     state: &mut AppState, 
     key: KeyEvent,
-    api_tx: &mpsc::UnboundedSender<ApiEvent>
+    api_tx: &ApiSender,
+    runtime: &Runtime,
 ) -> bool {
-    if state.show_settings {
-        return handle_settings_input(state, key);
+    // Modifier presses and key releases only arrive with keyboard enhancement
+    if let KeyCode::Modifier(ModifierKeyCode::LeftAlt | ModifierKeyCode::RightAlt) = key.code {
+        state.alt_held = key.kind != KeyEventKind::Release;
+        return true;
+    }
+    if key.kind == KeyEventKind::Release {
+        return true;
+    }
+
+    // Profiler overlay is non-modal and available everywhere
+    if key.code == KeyCode::F(12) {
+        state.show_profiler = !state.show_profiler;
+        return true;
+    }
+
+    if let Some(top) = state.overlays.top() {
+        return handle_overlay_input(state, top, key, api_tx, runtime);
     }
-    
-    if state.command_palette_visible {
-        return handle_command_palette_input(state, key);
+
+    // Alt+1..5 jump straight to a pane, even while editing the prompt
+    if let KeyCode::Char(c @ '1'..='5') = key.code {
+        if key.modifiers.contains(KeyModifiers::ALT) {
+            if let Some(pane) = c.to_digit(10).and_then(|n| FocusPane::from_number(n as usize)) {
+                state.jump_to_pane(pane);
+            }
+            return true;
+        }
     }
 
     if state.input_mode == InputMode::Editing {
@@ -101,250 +587,1778 @@ pub fn handle_key_event(
                 state.input_mode = InputMode::Normal;
             }
             KeyCode::Enter => {
-                let prompt = state.input_buffer.clone();
+                let prompt = state.input_buffer.to_string();
                 if !prompt.trim().is_empty() {
                     state.prompt_history.push(prompt.clone());
-                    state.add_thinking(format!("> User: {}", prompt));
-                    state.add_thinking("Dispatching to IMS Core...".to_string());
-                    
-                    // Dispatch API call
-                    if let Some(client) = state.api_client.clone() {
-                        let tx = api_tx.clone();
-                        let prompt_text = prompt.clone();
-                        let model = state.session.as_ref().map(|s| s.model_id.clone()).unwrap_or("gpt-4o".to_string());
-                        
-                        tokio::spawn(async move {
-                            let req = ExecuteRequest {
-                                prompt: prompt_text,
-                                model_id: model, // Should come from selection
-                                max_tokens: Some(1024),
-                                temperature: 0.7,
-                                system_instruction: None,
-                                user_id: Some("ims-tui-user".to_string()),
-                                bypass_policies: false,
-                            };
-                            
-                            match client.execute_prompt(req).await {
-                                Ok(response) => {
-                                    let _ = tx.send(ApiEvent::GenerationComplete(response));
-                                }
-                                Err(e) => {
-                                    let _ = tx.send(ApiEvent::Error(format!("Prompt failed: {}", e)));
-                                }
-                            }
-                        });
-                    } else {
-                        state.add_debug_log("Error: API Client not initialized".to_string());
+                    // Blocked prompts stay in the box to be edited
+                    if !state.preflight(&prompt) {
+                        return true;
+                    }
+                    match context::confirmation_needed(state, &prompt) {
+                        Some(confirm) => state.ask_cost_confirm(confirm),
+                        None => submit_prompt(state, api_tx, prompt, None),
                     }
-                    
                     state.input_buffer.clear();
                 }
                 state.input_mode = InputMode::Normal;
             }
-            KeyCode::Backspace => {
-                state.input_buffer.pop();
-            }
-            KeyCode::Char(c) => {
-                state.input_buffer.push(c);
+            KeyCode::Backspace => state.input_buffer.backspace(),
+            KeyCode::Delete => state.input_buffer.delete(),
+            KeyCode::Left => state.input_buffer.move_left(),
+            KeyCode::Right => state.input_buffer.move_right(),
+            KeyCode::Home => state.input_buffer.move_home(),
+            KeyCode::End => state.input_buffer.move_end(),
+            KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::ALT) => state.cycle_priority(),
+            KeyCode::Char('e') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                state.request_external_edit(EditTarget::Prompt)
             }
+            KeyCode::Char('z') if key.modifiers.contains(KeyModifiers::CONTROL) => state.suspend_requested = true,
+            KeyCode::Char(c) => state.input_buffer.insert(c),
             _ => {}
         }
         return true;
     }
 
-    match key.code {
-        KeyCode::Char('q') | KeyCode::Char('Q') => {
-            return false;
+    let Some(action) = keymap::lookup(&key, state.focus) else {
+        return true;
+    };
+    match action {
+        Action::Quit => return false,
+
+        Action::Help => {
+            state.open_overlay(Overlay::Help);
+            state.help_query.clear();
+            state.help_scroll = 0;
         }
 
-        KeyCode::Char('s') | KeyCode::Char('S') => {
-            state.show_settings = !state.show_settings;
+        Action::ToggleSettings => {
+            state.toggle_overlay(Overlay::Settings);
+            state.settings = settings::SettingsState::from_state(state);
         }
-        
-        KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-            state.command_palette_visible = !state.command_palette_visible;
-            state.command_input.clear();
-            state.command_index = 0;
+
+        Action::CommandPalette => {
+            if state.overlays.is_open(Overlay::CommandPalette) {
+                state.close_command_palette();
+            } else {
+                state.open_command_palette();
+            }
+        }
+
+        Action::ModelRegistry => {
+            state.open_overlay(Overlay::ModelRegistry);
+            state.model_registry.filter_editing = false;
+            if state.model_registry.needs_fetch() {
+                load_model_registry(state, api_tx);
+            }
+        }
+
+        Action::Telemetry => {
+            state.open_overlay(Overlay::Telemetry);
+        }
+
+        Action::NetworkLog => {
+            state.open_overlay(Overlay::Network);
+        }
+
+        Action::Checkpoints => {
+            state.open_overlay(Overlay::Checkpoints);
+            state.checkpoints_view = Default::default();
+            state.checkpoints_view.selected = state.checkpoints.len().saturating_sub(1);
+        }
+
+        Action::PrevTab | Action::NextTab | Action::CloseTab => {
+            let result = match action {
+                Action::CloseTab => state.close_tab(),
+                _ => state.cycle_tab(action == Action::NextTab),
+            };
+            if let Err(e) = result {
+                state.add_debug_log(e.to_string());
+            }
+        }
+
+        Action::ToggleZen => {
+            state.zen_mode = !state.zen_mode;
+            state.ensure_focus_visible();
+            state.add_debug_log(format!("Zen mode {}", if state.zen_mode { "on" } else { "off" }));
+        }
+
+        Action::ToggleMaximize => {
+            state.toggle_maximized();
         }
 
-        KeyCode::Tab => {
+        Action::CycleFocus => {
             state.cycle_focus();
         }
 
-        KeyCode::Up => {
+        Action::Up => {
             handle_up(state);
         }
 
-        KeyCode::Down => {
+        Action::Down => {
             handle_down(state);
         }
-        
-        KeyCode::Left => {
-            if state.focus == FocusPane::Sidebar {
-                state.tree_state.borrow_mut().key_left();
-            }
+
+        Action::Collapse => {
+            state.tree_state.borrow_mut().key_left();
         }
-        
-        KeyCode::Right => {
-            if state.focus == FocusPane::Sidebar {
-                state.tree_state.borrow_mut().key_right();
-            }
+
+        Action::Expand => {
+            state.tree_state.borrow_mut().key_right();
         }
 
-        KeyCode::Enter => {
+        Action::Activate => {
             match state.focus {
-                FocusPane::Sidebar => state.open_selected_file(),
+                FocusPane::Sidebar => {
+                    state.open_selected_file();
+                    request_model_info(state, api_tx);
+                }
                 FocusPane::Prompt => state.input_mode = InputMode::Editing,
                 _ => {}
             }
         }
-        
+
         // File Management Shortcuts
-        KeyCode::Char('n') => {
-            if state.focus == FocusPane::Sidebar {
-                state.add_debug_log("Creating new file...".to_string());
-                let new_path = std::path::PathBuf::from(format!("new_file_{}.rs", state.file_tree.len() + 1));
-                state.add_file(new_path);
+        Action::NewFile => {
+            state.add_debug_log("Creating new file...".to_string());
+            let new_path = std::path::PathBuf::from(format!("new_file_{}.rs", state.file_tree.len() + 1));
+            state.add_file(new_path);
+        }
+
+        // Keyboard equivalent of a right click, anchored at the pane's corner
+        Action::ContextMenu => open_context_menu_for_focus(state),
+
+        Action::CopySelection => {
+            if state.selection.is_some() {
+                copy_selection(state);
             }
         }
-        
-        KeyCode::Delete => {
-             if state.focus == FocusPane::Sidebar {
-                 // Mock delete logic
-                 state.add_debug_log("Mock: Deleted selected file".to_string());
-             }
+
+        Action::ClearSelection => {
+            state.selection = None;
         }
 
-        KeyCode::Char('a') | KeyCode::Char('A') => {
-            state.global_auto_scroll = !state.global_auto_scroll;
-            if let Some(session) = &mut state.session {
-                if state.global_auto_scroll {
-                    session.thinking.enable_auto_scroll();
-                    session.generation.enable_auto_scroll();
+        Action::CodeBlocks => {
+            if state.code_blocks.is_empty() {
+                state.add_debug_log("No code blocks in this session's responses".to_string());
+            } else {
+                state.open_overlay(Overlay::Blocks);
+                state.blocks_view = Default::default();
+                state.blocks_view.selected = state.code_blocks.len() - 1;
+            }
+        }
+
+        Action::ToggleAttachment => {
+            if let Some(node) = state.get_selected_node().filter(|n| !n.is_dir) {
+                let (path, name) = (node.path.clone(), node.name.clone());
+                match state.toggle_attachment(path) {
+                    Ok(true) => state.add_debug_log(format!("Attached {} to prompt", name)),
+                    Ok(false) => state.add_debug_log(format!("Detached {} from prompt", name)),
+                    Err(e) => state.add_thinking(
+                        ThinkingEntry::system(format!("⚠ Not attached: {:#}", e)).with_level(Level::Warning),
+                    ),
                 }
             }
         }
 
-        KeyCode::Char('r') | KeyCode::Char('R') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-            if let Some(session) = &mut state.session {
-                session.reset_scroll();
-                state.add_debug_log("Reset scroll states".to_string());
+        Action::ToggleMark => {
+            if let Some(message) = state.toggle_mark_selected() {
+                state.add_debug_log(message);
             }
         }
 
-        _ => {}
-    }
+        Action::ToggleToolCalls => {
+            if state.toggle_tool_calls() == 0 {
+                state.add_debug_log("No tool calls in this session".to_string());
+            }
+        }
 
-    true
-}
+        Action::PrevRegion | Action::NextRegion => match state.cycle_region(action == Action::NextRegion) {
+            Some(index) => {
+                reveal_region(state, index);
+                scroll_to_region(state, index);
+            }
+            None => state.add_debug_log("No responses in the Generation pane".to_string()),
+        },
 
-fn handle_up(state: &mut AppState) {
-    match state.focus {
-        FocusPane::Sidebar => {
-            state.tree_state.borrow_mut().key_up();
-        }
-        FocusPane::Thinking => {
+        Action::RateUp | Action::RateDown => state.start_feedback(action == Action::RateUp),
+
+        Action::EditExternally => state.request_external_edit(EditTarget::Prompt),
+
+        Action::Suspend => state.suspend_requested = true,
+
+        Action::Citations => state.open_citations(),
+
+        Action::CycleThinkingFilter => {
+            state.thinking_filter = Source::cycle_filter(state.thinking_filter);
             if let Some(session) = &mut state.session {
-                session.thinking.manual_scroll(-1);
+                session.thinking.enable_auto_scroll();
             }
         }
-        FocusPane::Generation => {
+
+        Action::DeleteFile => {
+            if let Some(path) = selected_file(state) {
+                confirm_delete(state, path);
+            }
+        }
+
+        Action::RenameFile => {
+            if let Some(path) = selected_file(state) {
+                ask_rename(state, path);
+            }
+        }
+
+        Action::ToggleAutoScroll => {
+            state.set_auto_scroll(!state.global_auto_scroll);
+        }
+
+        Action::ResetScroll => {
             if let Some(session) = &mut state.session {
-                session.generation.manual_scroll(-1);
+                session.reset_scroll();
+                state.add_debug_log("Reset scroll states".to_string());
             }
         }
-        FocusPane::Inspector | FocusPane::Prompt => {}
     }
+
+    true
 }
 
-fn handle_down(state: &mut AppState) {
-    match state.focus {
-        FocusPane::Sidebar => {
-            state.tree_state.borrow_mut().key_down();
+/// Add `prompt` to the conversation and send it with the session's model,
+/// queue it behind the prompts already running, or hold it while offline
+fn submit_prompt(
+    state: &mut AppState,
+    api_tx: &ApiSender,
+    prompt: String,
+    replay_of: Option<String>,
+) {
+    let arm = state.experiment_model();
+    let model = match &arm {
+        Some((_, model)) => model.clone(),
+        None => state.session.as_ref().map(|s| s.model_id.clone()).unwrap_or("gpt-4o".to_string()),
+    };
+    let priority = std::mem::take(&mut state.next_priority);
+    let key = send_prompt(state, api_tx, prompt, replay_of, model, priority);
+    if let (Some((arm, _)), Some(key)) = (arm, key) {
+        state.experiment_sent(arm, key);
+    }
+}
+
+/// Send `prompt` to `model` as `submit_prompt` does; the request key if it
+/// was sent or queued for a free slot
+fn send_prompt(
+    state: &mut AppState,
+    api_tx: &ApiSender,
+    prompt: String,
+    replay_of: Option<String>,
+    model: String,
+    priority: Priority,
+) -> Option<String> {
+    if state.prompt_in_flight(&prompt, &model) {
+        state.add_thinking(
+            ThinkingEntry::system(format!("⏳ Request already running on {}, not sending it twice", model))
+                .with_level(Level::Warning),
+        );
+        return None;
+    }
+    state.add_thinking(ThinkingEntry::user(prompt.clone()));
+    let prompt_entry = state.thinking_log.last().map(|e| e.id);
+
+    let budget = context::budget(state, &prompt);
+    if let Some(advice) = budget.suggestion() {
+        state.add_thinking(
+            ThinkingEntry::system(format!("⚠ Request exceeds the model's context window ({})", advice))
+                .with_level(Level::Warning),
+        );
+    }
+    let prompt_text = context::build_prompt(state, &prompt);
+
+    if !state.api_connected {
+        // Offline: keep the prompt until the backend is reachable again
+        if !state.attached_media().is_empty() {
+            state.add_thinking(
+                ThinkingEntry::system("⚠ Attached images and audio are not kept with prompts queued offline".to_string())
+                    .with_level(Level::Warning),
+            );
         }
-        FocusPane::Thinking => {
-            if let Some(session) = &mut state.session {
-                session.thinking.manual_scroll(1);
-            }
+        let item = QueuedPrompt::new(prompt.clone(), prompt_text, model.clone());
+        state.conversation.push(ConversationTurn {
+            queued: Some(item.id),
+            replay_of,
+            citations: citations::number(&state.search_context),
+            // The queue id is the idempotency key it is sent with
+            request: Some(item.id.to_string()),
+            prompt_entry,
+            ..ConversationTurn::new(prompt, model)
+        });
+        if let Err(e) = state.offline_queue.push(item) {
+            state.add_debug_log(format!("Failed to persist offline queue: {}", e));
         }
-        FocusPane::Generation => {
-            if let Some(session) = &mut state.session {
-                session.generation.manual_scroll(1);
-            }
+        state.add_thinking(
+            ThinkingEntry::system(format!(
+                "⏸ Pending: IMS Core is offline, prompt queued ({} waiting)",
+                state.offline_queue.len()
+            ))
+            .with_level(Level::Warning),
+        );
+    } else if state.api_client.is_some() {
+        let mut request = InFlightPrompt::new(prompt.clone(), model.clone());
+        state.conversation.push(ConversationTurn {
+            replay_of,
+            citations: citations::number(&state.search_context),
+            request: Some(request.key.clone()),
+            prompt_entry,
+            ..ConversationTurn::new(prompt, model)
+        });
+        request.attachments = state.attachments.clone();
+        state.take_bypass(&mut request);
+        let key = request.key.clone();
+        if state.dispatch_slots_full() {
+            state.prompt_queue.push(request, prompt_text, priority);
+            let position = state.prompt_queue.position(&key).unwrap_or(state.prompt_queue.len());
+            state.add_thinking(ThinkingEntry::system(format!(
+                "⏳ Queued at {} priority: #{} in line, {} running",
+                priority.label(),
+                position,
+                state.in_flight.len()
+            )));
+        } else {
+            state.add_thinking(ThinkingEntry::system("Dispatching to IMS Core..."));
+            dispatch_prompt(state, api_tx, request, prompt_text);
         }
-        FocusPane::Inspector | FocusPane::Prompt => {}
+        return Some(key);
+    } else {
+        state.add_debug_log("Error: API Client not initialized".to_string());
     }
+    None
 }
 
-fn handle_settings_input(state: &mut AppState, key: KeyEvent) -> bool {
-    let option_count = 6; 
+/// Send a prompt to the Action Gateway in the background, tracking it as
+/// in flight until a response or failure arrives
+fn dispatch_prompt(
+    state: &mut AppState,
+    api_tx: &ApiSender,
+    request: InFlightPrompt,
+    prompt_text: String,
+) {
+    let Some(mut client) = state.api_client.clone() else { return };
+    state.in_flight.push(request.clone());
+    let tx = api_tx.clone();
+    let span = tracing::info_span!("prompt_dispatch", model_id = %request.model_id);
 
-    match key.code {
-        KeyCode::Esc => {
-            state.show_settings = false;
+    let vendor = state.vendors.for_model(&request.model_id);
+    let mut req = ExecuteRequest {
+        prompt: prompt_text.clone(),
+        model_id: request.model_id.clone(), // Should come from selection
+        max_tokens: Some(vendor.max_tokens),
+        temperature: vendor.temperature,
+        system_instruction: state.system_instruction.clone(),
+        user_id: None,
+        bypass_policies: request.bypass_policies,
+        idempotency_key: Some(request.key.clone()),
+        images: Vec::new(),
+        audio: Vec::new(),
+    };
+    let cache = ResponseCache::from_config(&state.config.cache);
+    let steps = state.config.postprocess.steps.clone();
+    let guardrails = state.guardrails.clone();
+
+    tokio::spawn(async move {
+        let started = std::time::Instant::now();
+        let paths = request.attachments.clone();
+        let Ok(read) = tokio::task::spawn_blocking(move || read_attachments(&guardrails, &paths)).await else { return };
+        let blocked = read.findings.iter().any(|f| f.action == GuardAction::Block);
+        if !read.audio.is_empty() {
+            let (tx, key) = (tx.clone(), request.key.clone());
+            client = client.with_upload_progress(std::sync::Arc::new(move |percent| {
+                let _ = tx.send(ApiEvent::UploadProgress { key: key.clone(), percent });
+            }));
         }
-        KeyCode::Char('q') | KeyCode::Char('Q') => {
-            return false; 
+        let _ = tx.send(ApiEvent::AttachmentsRead {
+            findings: read.findings,
+            skipped: read.skipped,
+            uploading: read.uploading,
+        });
+        if blocked {
+            let _ = tx.send(ApiEvent::GenerationFailed {
+                key: request.key,
+                model_id: request.model_id,
+                latency_ms: 0.0,
+                error: "Not sent: an attached file is blocked by the guardrails".to_string(),
+            });
+            return;
         }
-        KeyCode::Up => {
-            if state.settings_index > 0 {
-                state.settings_index -= 1;
-            } else {
-                state.settings_index = option_count - 1;
+        req.images = read.images;
+        req.audio = read.audio;
+        if let Some(path) = &request.inline_file {
+            match tokio::fs::read_to_string(path).await {
+                Ok(content) => req.prompt = crate::app::batch::request_text(&prompt_text, path, &content),
+                Err(e) => {
+                    let _ = tx.send(ApiEvent::GenerationFailed {
+                        key: request.key,
+                        model_id: request.model_id,
+                        latency_ms: 0.0,
+                        error: format!("{}: {}", path.display(), e),
+                    });
+                    return;
+                }
             }
         }
-        KeyCode::Down => {
-            state.settings_index = (state.settings_index + 1) % option_count;
+
+        // Read and written off the async workers; the entry is a file
+        let cache_key = cache::key(&req);
+        let hit = match &cache {
+            Some(cache) => cache.fetch(&cache_key).await,
+            None => None,
+        };
+        if let Some(response) = hit {
+            let (content, postprocess_failures) = postprocess::apply_blocking(steps, response.content.clone()).await;
+            let _ = tx.send(ApiEvent::GenerationComplete {
+                key: request.key,
+                response: Box::new(response),
+                cached: true,
+                content,
+                postprocess_failures,
+            });
+            return;
         }
-        KeyCode::Enter => {
-            match state.settings_index {
-                0 => { // Auto-scroll
-                    state.global_auto_scroll = !state.global_auto_scroll;
-                    if let Some(session) = &mut state.session {
-                        if state.global_auto_scroll {
-                            session.thinking.enable_auto_scroll();
-                            session.generation.enable_auto_scroll();
-                        }
+
+        match client.execute_prompt(req).await {
+            Ok(response) => {
+                if let Some(cache) = &cache {
+                    if let Err(e) = cache.store(&cache_key, &response).await {
+                        tracing::warn!("failed to cache response: {:#}", e);
                     }
                 }
-                2 => { // API Status (Reconnect)
-                    state.api_connected = false;
+                let (content, postprocess_failures) = postprocess::apply_blocking(steps, response.content.clone()).await;
+                let _ = tx.send(ApiEvent::GenerationComplete {
+                    key: request.key,
+                    response: Box::new(response),
+                    cached: false,
+                    content,
+                    postprocess_failures,
+                });
+            }
+            Err(e) => {
+                if let Some(ApiError::RateLimited { retry_after }) = e.downcast_ref::<ApiError>() {
+                    let _ = tx.send(ApiEvent::RateLimited {
+                        retry_after: *retry_after,
+                        latency_ms: started.elapsed().as_secs_f64() * 1000.0,
+                        error: e.to_string(),
+                        request,
+                        prompt_text,
+                    });
+                    return;
+                }
+                let _ = tx.send(ApiEvent::GenerationFailed {
+                    key: request.key.clone(),
+                    model_id: request.model_id.clone(),
+                    latency_ms: started.elapsed().as_secs_f64() * 1000.0,
+                    error: e.to_string(),
+                });
+                if is_unauthorized(&e) {
+                    let _ = tx.send(ApiEvent::AuthRequired {
+                        error: e.to_string(),
+                        retry: RetryRequest::Prompt { request, prompt_text },
+                    });
                 }
-                _ => {}
             }
         }
+    }.instrument(span));
+}
+
+/// A prompt's attachments as read when it is sent
+struct ReadAttachments {
+    images: Vec<ImageInput>,
+    audio: Vec<AudioInput>,
+    findings: Vec<Finding>,
+    /// Why media that couldn't be loaded was left out
+    skipped: Vec<String>,
+    /// The audio about to be uploaded, and its size
+    uploading: Option<String>,
+}
+
+/// Check `paths` with the guardrails and load the images and audio among
+/// them, base64-encoded; blocks on file reads
+fn read_attachments(guardrails: &Guardrails, paths: &[std::path::PathBuf]) -> ReadAttachments {
+    let (mut images, mut audio, mut skipped) = (Vec::new(), Vec::new(), Vec::new());
+    for path in paths.iter().filter(|p| images::is_media(p)) {
+        let loaded = if audio::is_audio(path) {
+            AudioInput::load(path).map(|a| audio.push(a))
+        } else {
+            ImageInput::load(path).map(|i| images.push(i))
+        };
+        if let Err(e) = loaded {
+            skipped.push(format!("{:#}", e));
+        }
+    }
+    let uploading = (!audio.is_empty()).then(|| {
+        let size: usize = audio.iter().map(AudioInput::size).sum();
+        let names: Vec<_> = audio.iter().map(|a| a.name.as_str()).collect();
+        format!("{} ({})", names.join(", "), audio::format_size(size))
+    });
+    ReadAttachments {
+        images,
+        audio,
+        findings: guardrails.check_attachments(paths),
+        skipped,
+        uploading,
+    }
+}
+
+fn is_unauthorized(error: &anyhow::Error) -> bool {
+    matches!(error.downcast_ref::<ApiError>(), Some(ApiError::Unauthorized { .. }))
+}
+
+/// Admin key confirmation for a policy bypass
+fn handle_bypass_input(state: &mut AppState, key: KeyEvent) -> bool {
+    let Some(prompt) = state.bypass_prompt.as_mut() else { return true };
+    match key.code {
+        KeyCode::Backspace => {
+            prompt.input.pop();
+        }
+        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => prompt.input.clear(),
+        KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => prompt.input.push(c),
+        KeyCode::Enter => state.confirm_bypass(),
         _ => {}
     }
+    true
+}
 
+/// Optional comment on a rating; Enter records it (Esc drops the rating)
+fn handle_feedback_input(state: &mut AppState, key: KeyEvent, api_tx: &ApiSender) -> bool {
+    let Some(prompt) = state.feedback_prompt.as_mut() else { return true };
+    match key.code {
+        KeyCode::Backspace => {
+            prompt.input.pop();
+        }
+        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => prompt.input.clear(),
+        KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => prompt.input.push(c),
+        KeyCode::Enter => {
+            if let Some(feedback) = state.submit_feedback() {
+                crate::app::feedback::send_feedback(state, feedback, api_tx);
+            }
+        }
+        _ => {}
+    }
     true
 }
 
-fn handle_command_palette_input(state: &mut AppState, key: KeyEvent) -> bool {
+/// Masked admin key entry; Enter stores the key and resends the rejected requests
+fn handle_api_key_input(state: &mut AppState, key: KeyEvent, api_tx: &ApiSender) -> bool {
+    let Some(prompt) = state.api_key_prompt.as_mut() else { return true };
     match key.code {
-        KeyCode::Esc => {
-            state.command_palette_visible = false;
+        KeyCode::Backspace => {
+            prompt.input.pop();
         }
-        KeyCode::Up => {
-            if state.command_index > 0 {
-                state.command_index -= 1;
+        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => prompt.input.clear(),
+        KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => prompt.input.push(c),
+        KeyCode::Enter => {
+            let Some((admin_key, retries)) = state.submit_api_key() else { return true };
+            let Some(client) = state.api_client.clone() else { return true };
+            client.set_admin_key(admin_key);
+            state.leave_read_only();
+            state.add_debug_log(format!("Admin API key updated, retrying {} request(s)", retries.len()));
+            for retry in retries {
+                match retry {
+                    RetryRequest::Prompt { request, prompt_text } => {
+                        state.add_thinking(ThinkingEntry::system(format!(
+                            "↻ Retrying with new API key ({})",
+                            request.model_id
+                        )));
+                        dispatch_prompt(state, api_tx, request, prompt_text);
+                    }
+                    RetryRequest::Admin { action, pricing, previous } => {
+                        send_admin_action(client.clone(), action, pricing, previous, api_tx.clone());
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+    true
+}
+
+/// Send queued prompts, highest priority first, while there are free slots
+/// and the API is reachable. Batch items past `[batch] concurrency` wait
+/// behind the rest
+pub fn send_queued_prompts(state: &mut AppState, api_tx: &ApiSender) {
+    if !state.api_connected {
+        return;
+    }
+    while !state.dispatch_slots_full() {
+        let batch = state.batch.as_ref();
+        let batch_full = batch.is_some_and(|b| b.slots_full());
+        let Some(waiting) = state
+            .prompt_queue
+            .pop_first(|w| !(batch_full && batch.is_some_and(|b| b.sent(&w.request.key))))
+        else {
+            return;
+        };
+        if state.batch_prompt_started(&waiting.request.key) {
+            dispatch_prompt(state, api_tx, waiting.request, waiting.prompt_text);
+            continue;
+        }
+        state.add_thinking(ThinkingEntry::system(format!(
+            "▶ Dispatching queued {} priority prompt ({})",
+            waiting.priority.label(),
+            waiting.request.model_id
+        )));
+        dispatch_prompt(state, api_tx, waiting.request, waiting.prompt_text);
+    }
+}
+
+/// Resend rate-limited prompts whose wait is up
+pub fn send_due_retries(state: &mut AppState, api_tx: &ApiSender) {
+    for retry in state.due_rate_limit_retries(std::time::Instant::now()) {
+        state.finish_in_flight(&retry.request.key);
+        state.add_thinking(ThinkingEntry::system(format!(
+            "↻ Retrying after rate limit ({})",
+            retry.request.model_id
+        )));
+        dispatch_prompt(state, api_tx, retry.request, retry.prompt_text);
+    }
+}
+
+/// Send everything queued while offline, oldest first
+pub fn flush_offline_queue(state: &mut AppState, api_tx: &ApiSender) {
+    if state.offline_queue.is_empty() || !state.api_connected {
+        return;
+    }
+    let items = match state.offline_queue.take_all() {
+        Ok(items) => items,
+        Err(e) => {
+            state.add_debug_log(format!("Failed to flush offline queue: {}", e));
+            return;
+        }
+    };
+
+    state.add_thinking(ThinkingEntry::system(format!(
+        "▶ Back online: sending {} queued prompt(s)",
+        items.len()
+    )));
+    for item in items {
+        state.add_thinking(ThinkingEntry::user(format!("(queued) {}", item.prompt)));
+        match state.conversation.iter_mut().find(|t| t.queued == Some(item.id)) {
+            Some(turn) => turn.queued = None,
+            // Restored from disk after a restart
+            None => state.conversation.push(ConversationTurn {
+                request: Some(item.id.to_string()),
+                prompt_entry: state.thinking_log.last().map(|e| e.id),
+                ..ConversationTurn::new(item.prompt.clone(), item.model_id.clone())
+            }),
+        }
+        // The queue id doubles as the idempotency key, so a prompt restored
+        // after a crash mid-send is not executed twice
+        let request = InFlightPrompt {
+            key: item.id.to_string(),
+            prompt: item.prompt,
+            model_id: item.model_id,
+            bypass_policies: false,
+            rate_limit_retries: 0,
+            attachments: Vec::new(),
+            inline_file: None,
+        };
+        dispatch_prompt(state, api_tx, request, item.request_text);
+    }
+    // Zen mode may have just hidden the focused pane
+    state.ensure_focus_visible();
+}
+
+/// Keys for the overlay on top: Esc closes it, unless it is in the middle
+/// of something Esc backs out of first
+fn handle_overlay_input(
+    state: &mut AppState,
+    overlay: Overlay,
+    key: KeyEvent,
+    api_tx: &ApiSender,
+    runtime: &Runtime,
+) -> bool {
+    if key.code == KeyCode::Esc && !state.overlay_takes_esc(overlay) {
+        state.close_overlay(overlay);
+        return true;
+    }
+    match overlay {
+        Overlay::Settings => handle_settings_input(state, key),
+        Overlay::CommandPalette => handle_command_palette_input(state, key, runtime),
+        Overlay::ModelRegistry => handle_model_registry_input(state, key, api_tx),
+        Overlay::Telemetry => handle_telemetry_input(state, key),
+        Overlay::Network => handle_network_input(state, key),
+        Overlay::Blocks => handle_blocks_input(state, key),
+        Overlay::Checkpoints => handle_checkpoints_input(state, key),
+        Overlay::History => handle_history_input(state, key, api_tx),
+        Overlay::Reports => handle_reports_input(state, key),
+        Overlay::Audit => handle_audit_input(state, key),
+        Overlay::Pipeline => handle_pipeline_input(state, key),
+        Overlay::Workflows => handle_workflows_input(state, key, runtime),
+        Overlay::Schedules => handle_schedules_input(state, key, api_tx, runtime),
+        Overlay::Search => handle_search_input(state, key, api_tx),
+        Overlay::Citations => handle_citations_input(state, key),
+        Overlay::Watches => handle_watches_input(state, key),
+        Overlay::Experiment => handle_experiment_input(state, key),
+        Overlay::Health => handle_health_input(state, key),
+        Overlay::Help => handle_help_input(state, key),
+        Overlay::ContextMenu => handle_context_menu_input(state, key, api_tx),
+        Overlay::FileDropdown => handle_file_dropdown_input(state, key, api_tx),
+        Overlay::Modal => handle_modal_input(state, key),
+        Overlay::Recovery => handle_recovery_input(state, key),
+        Overlay::Trust => handle_trust_input(state, key),
+        Overlay::CostConfirm => handle_cost_confirm_input(state, key, api_tx),
+        Overlay::ApiKey => handle_api_key_input(state, key, api_tx),
+        Overlay::Bypass => handle_bypass_input(state, key),
+        Overlay::Feedback => handle_feedback_input(state, key, api_tx),
+    }
+}
+
+fn handle_telemetry_input(state: &mut AppState, key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Char('t') | KeyCode::Char('T') => state.close_overlay(Overlay::Telemetry),
+        KeyCode::Char('c') => state.telemetry_log.clear(),
+        KeyCode::Char('q') | KeyCode::Char('Q') => return false,
+        _ => {}
+    }
+    true
+}
+
+/// Network pane: browse recorded requests
+fn handle_network_input(state: &mut AppState, key: KeyEvent) -> bool {
+    let view = &mut state.network_view;
+    match key.code {
+        KeyCode::Esc if view.detail => view.detail = false,
+        KeyCode::Char('n') | KeyCode::Char('N') => state.close_overlay(Overlay::Network),
+        KeyCode::Up => view.select_prev(),
+        KeyCode::Down => view.select_next(state.network_log.len()),
+        KeyCode::Enter if !state.network_log.is_empty() => view.detail = !view.detail,
+        KeyCode::Char('c') => {
+            state.network_log.clear();
+            state.network_view = Default::default();
+        }
+        KeyCode::Char('q') | KeyCode::Char('Q') => return false,
+        _ => {}
+    }
+    true
+}
+
+/// Generation history: browse saved generations, reopen one in a tab or
+/// replay its prompt ("Retry with…")
+fn handle_history_input(state: &mut AppState, key: KeyEvent, api_tx: &ApiSender) -> bool {
+    let Some(record) = state.history_view.selected_record().cloned() else {
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Char('Q') => return false,
+            _ => {}
+        }
+        return true;
+    };
+
+    let view = &mut state.history_view;
+    match key.code {
+        KeyCode::Up => view.selected = view.selected.saturating_sub(1),
+        KeyCode::Down => view.selected = (view.selected + 1).min(view.records.len().saturating_sub(1)),
+        KeyCode::Enter => match state.reopen_generation(&record) {
+            Ok(()) => state.close_overlay(Overlay::History),
+            Err(e) => state.add_debug_log(e.to_string()),
+        },
+        KeyCode::Char('r') | KeyCode::Char('R') => replay_generation(state, api_tx, &record, &record.model_id),
+        KeyCode::Char('m') | KeyCode::Char('M') => pick_replay_model(state, api_tx, record),
+        KeyCode::Char('q') | KeyCode::Char('Q') => return false,
+        _ => {}
+    }
+    true
+}
+
+/// "Retry with…": pick the model to replay `record` on
+fn pick_replay_model(state: &mut AppState, api_tx: &ApiSender, record: GenerationRecord) {
+    if state.model_registry.needs_fetch() {
+        load_model_registry(state, api_tx);
+    }
+    let choices = state
+        .replay_models(&record.model_id)
+        .into_iter()
+        .map(|model| Choice {
+            label: format!("{} {}", state.vendors.for_model(&model).glyph, model),
+            value: model,
+        })
+        .collect();
+    let tx = api_tx.clone();
+    let mut modal = Modal::select("Retry with…", choices, move |state, model| {
+        replay_generation(state, &tx, &record, &model);
+    });
+    if state.model_registry.loading {
+        modal = modal.with_message("Still loading the model registry; models it adds are listed next time");
+    }
+    state.open_modal(modal);
+}
+
+/// Re-run a saved generation's prompt on `model_id` in a new tab
+fn replay_generation(
+    state: &mut AppState,
+    api_tx: &ApiSender,
+    record: &GenerationRecord,
+    model_id: &str,
+) {
+    if let Err(e) = state.open_replay_tab(record, model_id) {
+        state.add_debug_log(e.to_string());
+        return;
+    }
+    state.close_overlay(Overlay::History);
+    if state.preflight(&record.prompt) {
+        submit_prompt(state, api_tx, record.prompt.clone(), record.saved_name());
+    }
+}
+
+/// Usage reports: switch between daily and weekly rollups, export CSV
+fn handle_reports_input(state: &mut AppState, key: KeyEvent) -> bool {
+    let view = &mut state.reports_view;
+    match key.code {
+        KeyCode::Tab | KeyCode::Char('w') | KeyCode::Char('W') => {
+            view.period = view.period.toggle();
+            view.scroll = 0;
+        }
+        KeyCode::Up => view.scroll = view.scroll.saturating_sub(1),
+        KeyCode::Down => view.scroll = view.scroll.saturating_add(1),
+        KeyCode::Char('e') | KeyCode::Char('E') => state.export_usage_csv(),
+        KeyCode::Char('q') | KeyCode::Char('Q') => return false,
+        _ => {}
+    }
+    true
+}
+
+/// Audit trail: scroll through recorded requests
+fn handle_audit_input(state: &mut AppState, key: KeyEvent) -> bool {
+    let view = &mut state.audit_view;
+    match key.code {
+        KeyCode::Up => view.scroll = view.scroll.saturating_sub(1),
+        KeyCode::Down => view.scroll = (view.scroll + 1).min(view.records.len().saturating_sub(1)),
+        KeyCode::Char('q') | KeyCode::Char('Q') => return false,
+        _ => {}
+    }
+    true
+}
+
+/// Expensive prompt confirmation: send it, or put it back in the prompt box
+fn handle_cost_confirm_input(state: &mut AppState, key: KeyEvent, api_tx: &ApiSender) -> bool {
+    match key.code {
+        KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => {
+            if let Some(confirm) = state.take_cost_confirm() {
+                submit_prompt(state, api_tx, confirm.prompt, None);
+            }
+        }
+        KeyCode::Char('n') | KeyCode::Char('N') => state.close_overlay(Overlay::CostConfirm),
+        _ => {}
+    }
+    true
+}
+
+/// Recovery prompt: restore the last session's unsent work, or drop it
+fn handle_recovery_input(state: &mut AppState, key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => state.restore_recovered(),
+        KeyCode::Char('n') | KeyCode::Char('N') => state.close_overlay(Overlay::Recovery),
+        _ => {}
+    }
+    true
+}
+
+/// Workspace trust prompt: allow writes here from now on, or skip them
+fn handle_trust_input(state: &mut AppState, key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Char('y') | KeyCode::Char('Y') => state.trust_workspace(&crate::app::config::config_path()),
+        KeyCode::Char('n') | KeyCode::Char('N') => state.close_overlay(Overlay::Trust),
+        _ => {}
+    }
+    true
+}
+
+/// Pipeline view: browse targets of the current run
+fn handle_pipeline_input(state: &mut AppState, key: KeyEvent) -> bool {
+    let view = &mut state.pipeline_view;
+    let targets = view.run.as_ref().map_or(0, |r| r.targets.len());
+    let paused = view.run.as_ref().is_some_and(|r| r.pause.is_paused());
+    match key.code {
+        KeyCode::Up => view.selected = view.selected.saturating_sub(1),
+        KeyCode::Down => view.selected = (view.selected + 1).min(targets.saturating_sub(1)),
+        KeyCode::Char('p') | KeyCode::Char('P') => state.set_pipeline_paused(!paused),
+        KeyCode::Char('q') | KeyCode::Char('Q') => return false,
+        _ => {}
+    }
+    true
+}
+
+fn handle_health_input(state: &mut AppState, key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Enter => state.close_overlay(Overlay::Health),
+        KeyCode::Char('q') | KeyCode::Char('Q') => return false,
+        _ => {}
+    }
+    true
+}
+
+/// Workflows overlay: pick a workflow, then fill in its parameters
+fn handle_workflows_input(state: &mut AppState, key: KeyEvent, runtime: &Runtime) -> bool {
+    if let Some(params) = &mut state.workflows_view.params {
+        let value = &mut params.values[params.current].1;
+        match key.code {
+            KeyCode::Esc => state.workflows_view.params = None,
+            KeyCode::Enter => return start_workflow(state, runtime),
+            KeyCode::Backspace => {
+                value.pop();
+            }
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => value.clear(),
+            KeyCode::Char(c) => value.push(c),
+            _ => {}
+        }
+        return true;
+    }
+
+    let view = &mut state.workflows_view;
+    match key.code {
+        KeyCode::Up => view.selected = view.selected.saturating_sub(1),
+        KeyCode::Down => view.selected = (view.selected + 1).min(view.workflows.len().saturating_sub(1)),
+        KeyCode::Enter => return start_workflow(state, runtime),
+        KeyCode::Char('q') | KeyCode::Char('Q') => return false,
+        _ => {}
+    }
+    true
+}
+
+/// Advance the workflow overlay on Enter and run the workflow once its
+/// parameters are all entered
+fn start_workflow(state: &mut AppState, runtime: &Runtime) -> bool {
+    if state.pipeline_view.run.as_ref().is_some_and(|r| r.is_running()) {
+        state.close_overlay(Overlay::Workflows);
+        state.add_debug_log("A pipeline is already running".to_string());
+        return true;
+    }
+    let job = if state.workflows_view.params.is_some() {
+        state.next_workflow_param()
+    } else {
+        state.choose_workflow()
+    };
+    let Some(job) = job else { return true };
+    match state.api_client.clone() {
+        Some(client) => {
+            state.start_pipeline(&job);
+            tokio::spawn(crate::app::pipeline::run(client, runtime.sender(), job));
+        }
+        None => state.add_debug_log("Error: API Client not initialized".to_string()),
+    }
+    true
+}
+
+/// Start every schedule whose time has come
+pub fn run_due_schedules(state: &mut AppState, api_tx: &ApiSender, runtime: &Runtime) {
+    let now = chrono::Local::now();
+    for index in state.due_schedules(now) {
+        run_schedule(state, api_tx, runtime, index, now);
+    }
+}
+
+/// Send a scheduled prompt or start a scheduled workflow; runs that can't
+/// start now are recorded as skipped
+fn run_schedule(
+    state: &mut AppState,
+    api_tx: &ApiSender,
+    runtime: &Runtime,
+    index: usize,
+    now: chrono::DateTime<chrono::Local>,
+) {
+    let Some(task) = state.schedules_view.schedules.get(index).map(|s| s.task.clone()) else { return };
+    state.begin_schedule(index, now);
+    if !state.api_connected {
+        state.finish_schedule(index, false, "skipped, IMS Core is offline".to_string());
+        return;
+    }
+    match task {
+        schedule::Task::Prompt { text, model } => {
+            let model = model
+                .or_else(|| state.session.as_ref().map(|s| s.model_id.clone()))
+                .unwrap_or("gpt-4o".to_string());
+            if !state.preflight(&text) {
+                state.finish_schedule(index, false, "blocked by guardrails".to_string());
+                return;
+            }
+            // Scheduled work waits behind prompts typed in the meantime
+            match send_prompt(state, api_tx, text, None, model, Priority::Low) {
+                Some(key) => {
+                    state.schedules_view.prompts.insert(key, index);
+                }
+                None => state.finish_schedule(index, false, "skipped, not sent".to_string()),
+            }
+        }
+        schedule::Task::Workflow(name) => {
+            let files = state.schedule_files(index);
+            if files.is_empty() {
+                state.finish_schedule(index, true, "no files to run on".to_string());
+                return;
+            }
+            match start_named_workflow(state, runtime, &name, files) {
+                Ok(()) => state.schedules_view.pipeline = Some(index),
+                Err(reason) => state.finish_schedule(index, false, reason),
+            }
+        }
+    }
+}
+
+/// Run the workflow called `name` on `files` in the background, with its
+/// parameters at their defaults; why it could not start otherwise
+fn start_named_workflow(
+    state: &mut AppState,
+    runtime: &Runtime,
+    name: &str,
+    files: Vec<(std::path::PathBuf, String)>,
+) -> Result<(), String> {
+    if state.pipeline_view.run.as_ref().is_some_and(|r| r.is_running()) {
+        return Err("skipped, a pipeline is already running".to_string());
+    }
+    let workflow = workflows::list(&workflows::workflows_dir())
+        .into_iter()
+        .flatten()
+        .find(|w| w.name == name)
+        .ok_or_else(|| format!("no workflow named '{}'", name))?;
+    let values: Vec<(String, String)> = workflow
+        .parameters()
+        .into_iter()
+        .map(|p| {
+            let value = workflow.params.get(&p).cloned().unwrap_or_default();
+            (p, value)
+        })
+        .collect();
+    let job = state.workflow_job_for(&workflow, &values, files);
+    if state.api_client.is_none() {
+        return Err("API client not initialized".to_string());
+    }
+    // Progress shows in the Pipeline view when opened, not over what the user is doing
+    let shown = state.overlays.is_open(Overlay::Pipeline);
+    state.start_pipeline(&job);
+    if !shown {
+        state.close_overlay(Overlay::Pipeline);
+    }
+    runtime.perform(state, vec![CommandEffect::RunPipeline(job)]);
+    Ok(())
+}
+
+/// Re-run the prompts and workflows bound to files saved since the last check
+pub fn run_due_watches(state: &mut AppState, api_tx: &ApiSender, runtime: &Runtime) {
+    for (index, content) in state.due_watches(std::time::Instant::now()) {
+        let binding = state.watch_view.bindings[index].clone();
+        state.add_thinking(ThinkingEntry::system(format!("👁 {} saved, re-running", binding.label)));
+        match binding.task {
+            schedule::Task::Prompt { text, model } => {
+                let model = model
+                    .or_else(|| state.session.as_ref().map(|s| s.model_id.clone()))
+                    .unwrap_or("gpt-4o".to_string());
+                let prompt = format!("{}{}", context::file_block(std::path::Path::new(&binding.label), &content), text);
+                if state.preflight(&prompt) {
+                    send_prompt(state, api_tx, prompt, None, model, Priority::Normal);
+                }
+            }
+            schedule::Task::Workflow(name) => {
+                let fallback = state.session.as_ref().map(|s| s.model_id.clone()).unwrap_or("gpt-4o".to_string());
+                if let Err(reason) = start_named_workflow(state, runtime, &name, vec![(binding.file, fallback)]) {
+                    state.add_thinking(
+                        ThinkingEntry::system(format!("✗ Watch on {}: {}", binding.label, reason))
+                            .with_level(Level::Warning),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Up/Down through a list of `len` entries; whether `code` was either
+fn move_selection(selected: &mut usize, len: usize, code: KeyCode) -> bool {
+    match code {
+        KeyCode::Up => *selected = selected.saturating_sub(1),
+        KeyCode::Down => *selected = (*selected + 1).min(len.saturating_sub(1)),
+        _ => return false,
+    }
+    true
+}
+
+/// Schedules overlay: Enter runs the selected schedule now, Space pauses it
+fn handle_schedules_input(state: &mut AppState, key: KeyEvent, api_tx: &ApiSender, runtime: &Runtime) -> bool {
+    let view = &mut state.schedules_view;
+    if move_selection(&mut view.selected, view.schedules.len(), key.code) {
+        return true;
+    }
+    match key.code {
+        KeyCode::Enter => {
+            let index = view.selected;
+            if view.schedules.get(index).is_some_and(|s| !s.running) {
+                run_schedule(state, api_tx, runtime, index, chrono::Local::now());
+            }
+        }
+        KeyCode::Char(' ') => state.pause_selected_schedule(),
+        KeyCode::Char('q') | KeyCode::Char('Q') => return false,
+        _ => {}
+    }
+    true
+}
+
+/// Watches overlay: Space switches the selected binding on or off, d removes it
+fn handle_watches_input(state: &mut AppState, key: KeyEvent) -> bool {
+    let view = &mut state.watch_view;
+    if move_selection(&mut view.selected, view.bindings.len(), key.code) {
+        return true;
+    }
+    match key.code {
+        KeyCode::Char(' ') => state.toggle_selected_watch(),
+        KeyCode::Char('d') | KeyCode::Delete => state.remove_selected_watch(),
+        KeyCode::Char('q') | KeyCode::Char('Q') => return false,
+        _ => {}
+    }
+    true
+}
+
+/// Sources view: open the selected source in the editor
+fn handle_citations_input(state: &mut AppState, key: KeyEvent) -> bool {
+    let count = state.latest_citations().len();
+    let view = &mut state.citations_view;
+    match key.code {
+        KeyCode::Char('o') => state.close_overlay(Overlay::Citations),
+        KeyCode::Up => view.selected = view.selected.saturating_sub(1),
+        KeyCode::Down => view.selected = (view.selected + 1).min(count.saturating_sub(1)),
+        KeyCode::Enter => {
+            if let Some(citation) = state.latest_citations().get(state.citations_view.selected).cloned() {
+                state.jump_to_citation(citation);
             }
         }
+        KeyCode::Char('q') | KeyCode::Char('Q') => return false,
+        _ => {}
+    }
+    true
+}
+
+/// Search view: type a query, run it, attach results as prompt context
+fn handle_search_input(state: &mut AppState, key: KeyEvent, api_tx: &ApiSender) -> bool {
+    let view = &mut state.search_view;
+    match key.code {
+        KeyCode::Enter => embeddings::start_search(state, api_tx),
+        KeyCode::Up => view.selected = view.selected.saturating_sub(1),
+        KeyCode::Down => view.selected = (view.selected + 1).min(view.hits.len().saturating_sub(1)),
+        KeyCode::Tab => state.toggle_selected_hit(),
+        KeyCode::Char('a') if key.modifiers.contains(KeyModifiers::CONTROL) => state.attach_all_hits(),
+        KeyCode::Backspace => view.query.backspace(),
+        KeyCode::Delete => view.query.delete(),
+        KeyCode::Left => view.query.move_left(),
+        KeyCode::Right => view.query.move_right(),
+        KeyCode::Home => view.query.move_home(),
+        KeyCode::End => view.query.move_end(),
+        KeyCode::Char(c) => view.query.insert(c),
+        _ => {}
+    }
+    true
+}
+
+/// A/B test report: export it or close it
+fn handle_experiment_input(state: &mut AppState, key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Char('e') => state.export_experiment_report(),
+        KeyCode::Char('q') | KeyCode::Char('Q') => return false,
+        _ => {}
+    }
+    true
+}
+
+/// Checkpoints modal: name new checkpoints, restore or delete saved ones
+fn handle_checkpoints_input(state: &mut AppState, key: KeyEvent) -> bool {
+    let view = &mut state.checkpoints_view;
+    if let Some(input) = &mut view.naming {
+        match key.code {
+            KeyCode::Esc => view.naming = None,
+            KeyCode::Enter => {
+                let name = input.to_string();
+                view.naming = None;
+                state.create_checkpoint(&name);
+                state.checkpoints_view.selected = state.checkpoints.len() - 1;
+            }
+            KeyCode::Backspace => input.backspace(),
+            KeyCode::Delete => input.delete(),
+            KeyCode::Left => input.move_left(),
+            KeyCode::Right => input.move_right(),
+            KeyCode::Home => input.move_home(),
+            KeyCode::End => input.move_end(),
+            KeyCode::Char(c) => input.insert(c),
+            _ => {}
+        }
+        return true;
+    }
+
+    match key.code {
+        KeyCode::Char('c') | KeyCode::Char('C') => state.close_overlay(Overlay::Checkpoints),
+        KeyCode::Up => view.selected = view.selected.saturating_sub(1),
+        KeyCode::Down => view.selected = (view.selected + 1).min(state.checkpoints.len().saturating_sub(1)),
+        KeyCode::Char('n') => view.naming = Some(TextInput::default()),
+        KeyCode::Enter if !state.checkpoints.is_empty() => {
+            let index = view.selected;
+            match state.restore_checkpoint(index) {
+                Ok(()) => {
+                    state.close_overlay(Overlay::Checkpoints);
+                    state.add_debug_log(format!("Opened checkpoint \"{}\" in a new tab", state.checkpoints[index].name));
+                }
+                Err(e) => state.add_debug_log(e.to_string()),
+            }
+        }
+        KeyCode::Char('d') if !state.checkpoints.is_empty() => {
+            let removed = state.checkpoints.remove(view.selected);
+            view.selected = view.selected.min(state.checkpoints.len().saturating_sub(1));
+            state.add_debug_log(format!("Deleted checkpoint \"{}\"", removed.name));
+        }
+        KeyCode::Char('q') | KeyCode::Char('Q') => return false,
+        _ => {}
+    }
+    true
+}
+
+fn handle_help_input(state: &mut AppState, key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Esc if !state.help_query.is_empty() => state.help_query.clear(),
+        KeyCode::Char('?') if state.help_query.is_empty() => state.close_overlay(Overlay::Help),
+        KeyCode::Up => state.help_scroll = state.help_scroll.saturating_sub(1),
+        KeyCode::Down => state.help_scroll = state.help_scroll.saturating_add(1),
+        KeyCode::PageUp => state.help_scroll = state.help_scroll.saturating_sub(10),
+        KeyCode::PageDown => state.help_scroll = state.help_scroll.saturating_add(10),
+        KeyCode::Backspace => {
+            state.help_query.backspace();
+            state.help_scroll = 0;
+        }
+        KeyCode::Char(c) => {
+            state.help_query.insert(c);
+            state.help_scroll = 0;
+        }
+        _ => {}
+    }
+    true
+}
+
+/// Blocks popup: act on code blocks extracted from responses
+fn handle_blocks_input(state: &mut AppState, key: KeyEvent) -> bool {
+    if let BlocksMode::InsertLine(input) = &mut state.blocks_view.mode {
+        match key.code {
+            KeyCode::Esc => state.blocks_view.mode = BlocksMode::List,
+            KeyCode::Enter => {
+                let line = input.parse().unwrap_or(usize::MAX);
+                state.blocks_view.mode = BlocksMode::List;
+                insert_block(state, line);
+            }
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Char(c) if c.is_ascii_digit() => input.push(c),
+            _ => {}
+        }
+        return true;
+    }
+
+    if let BlocksMode::Diff(_) = state.blocks_view.mode {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('d') => state.blocks_view.mode = BlocksMode::List,
+            KeyCode::Up => state.blocks_view.diff_scroll = state.blocks_view.diff_scroll.saturating_sub(1),
+            KeyCode::Down => state.blocks_view.diff_scroll = state.blocks_view.diff_scroll.saturating_add(1),
+            KeyCode::Char('q') | KeyCode::Char('Q') => return false,
+            _ => {}
+        }
+        return true;
+    }
+
+    let Some(block) = state.code_blocks.get(state.blocks_view.selected).cloned() else {
+        state.close_overlay(Overlay::Blocks);
+        return true;
+    };
+    match key.code {
+        KeyCode::Char('b') | KeyCode::Char('B') => state.close_overlay(Overlay::Blocks),
+        KeyCode::Up => state.blocks_view.select_prev(),
+        KeyCode::Down => state.blocks_view.select_next(state.code_blocks.len()),
+        KeyCode::Char('c') => match clipboard::copy(&block.code) {
+            Ok(()) => state.add_debug_log(format!("Copied {} block ({} lines)", block.label(), block.line_count())),
+            Err(e) => state.add_debug_log(format!("Copy failed: {}", e)),
+        },
+        KeyCode::Char('s') => save_block(state, &block),
+        KeyCode::Char('i') if state.session.is_some() => {
+            state.blocks_view.mode = BlocksMode::InsertLine(String::new());
+        }
+        KeyCode::Char('d') => {
+            let Some(session) = &state.session else {
+                state.add_debug_log("Open a file to diff against".to_string());
+                return true;
+            };
+            // A file that doesn't exist yet diffs as empty
+            let existing = std::fs::read_to_string(&session.file_path).unwrap_or_default();
+            state.blocks_view.diff_scroll = 0;
+            state.blocks_view.mode = BlocksMode::Diff(blocks::line_diff(&existing, &block.code));
+        }
+        KeyCode::Char('q') | KeyCode::Char('Q') => return false,
+        _ => {}
+    }
+    true
+}
+
+/// First free `<stem>_<n>.<extension>` next to the open file (or in the cwd)
+fn unused_path_near_session(state: &AppState, stem: &str, extension: &str) -> std::path::PathBuf {
+    let dir = state
+        .session
+        .as_ref()
+        .and_then(|s| s.file_path.parent().map(|p| p.to_path_buf()))
+        .unwrap_or_default();
+    (1..)
+        .map(|n| dir.join(format!("{}_{}.{}", stem, n, extension)))
+        .find(|p| !p.exists())
+        .expect("unbounded range")
+}
+
+/// Write a block to a fresh file next to the open file and add it to the tree
+fn save_block(state: &mut AppState, block: &blocks::CodeBlock) {
+    let extension = blocks::extension_for(block.language.as_deref());
+    let path = unused_path_near_session(state, "block", extension);
+
+    let (label, code) = (block.label().to_string(), block.code.clone());
+    state.write_in_workspace(&path, move |state, path| match std::fs::write(&path, code) {
+        Ok(()) => {
+            state.add_debug_log(format!("Saved {} block to {}", label, path.display()));
+            state.add_file(path);
+        }
+        Err(e) => state.add_debug_log(format!("Failed to save {}: {}", path.display(), e)),
+    });
+}
+
+/// Insert the selected block into the open file before `line` (1-based)
+fn insert_block(state: &mut AppState, line: usize) {
+    let (Some(session), Some(block)) = (&state.session, state.code_blocks.get(state.blocks_view.selected)) else {
+        return;
+    };
+    let path = session.file_path.clone();
+    let (code, lines) = (block.code.clone(), block.line_count());
+    // Read once the write is allowed, so a wait for trust can't insert into stale text
+    state.write_in_workspace(&path, move |state, path| {
+        let original = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => {
+                state.add_debug_log(format!("Failed to read {}: {}", path.display(), e));
+                return;
+            }
+        };
+        let updated = blocks::insert_at_line(&original, line, &code);
+        match std::fs::write(&path, updated) {
+            Ok(()) => {
+                let at = line.min(original.lines().count() + 1);
+                state.add_debug_log(format!("Inserted {} lines into {} at line {}", lines, path.display(), at));
+            }
+            Err(e) => state.add_debug_log(format!("Failed to write {}: {}", path.display(), e)),
+        }
+    });
+}
+
+/// Fetch the session model's registry entry (context window, pricing) if not cached
+fn request_model_info(state: &AppState, api_tx: &ApiSender) {
+    let Some(session) = &state.session else { return };
+    if state.model_catalog.contains_key(&session.model_id) {
+        return;
+    }
+    if let Some(client) = state.api_client.clone() {
+        let tx = api_tx.clone();
+        let model_id = session.model_id.clone();
+        tokio::spawn(async move {
+            match client.get_model(&model_id).await {
+                Ok(model) => {
+                    let _ = tx.send(ApiEvent::ModelInfo(model));
+                }
+                Err(e) => {
+                    let _ = tx.send(ApiEvent::Error(format!("Model lookup failed: {}", e)));
+                }
+            }
+        });
+    }
+}
+
+/// Fetch the first page of the model list (including inactive models) for
+/// the registry view, revalidating the cached list if there is one
+pub fn load_model_registry(state: &mut AppState, api_tx: &ApiSender) {
+    fetch_model_page(state, api_tx, None);
+}
+
+/// Fetch the next page once the selection nears the end of the loaded list
+fn load_more_models(state: &mut AppState, api_tx: &ApiSender) {
+    if state.model_registry.wants_more() {
+        let next = state.model_registry.next_page.clone();
+        fetch_model_page(state, api_tx, next);
+    }
+}
+
+fn fetch_model_page(state: &mut AppState, api_tx: &ApiSender, page: Option<PageToken>) {
+    let Some(client) = state.api_client.clone() else {
+        state.add_debug_log("Error: API Client not initialized".to_string());
+        return;
+    };
+    state.model_registry.loading = true;
+    let tx = api_tx.clone();
+    let params = FilterParams {
+        capability_tier: None,
+        vendor_id: None,
+        function_call_support: None,
+        min_context: None,
+        max_cost_in: None,
+        include_inactive: Some(true),
+        limit: Some(state.config.registry.page_size.max(1)),
+        offset: None,
+        cursor: None,
+    }
+    .at(page.as_ref());
+    let append = !params.is_first_page();
+    let etag = if append || state.model_registry.models.is_empty() {
+        None
+    } else {
+        state.model_registry.etag.clone()
+    };
+    tokio::spawn(async move {
+        match client.filter_models(&params, etag.as_deref()).await {
+            Ok(None) => {
+                let local = local_models(&client, &tx).await;
+                let _ = tx.send(ApiEvent::ModelsUnchanged { local });
+            }
+            Ok(Some(mut page)) => {
+                // Local models join the first page
+                if !append {
+                    page.models.extend(local_models(&client, &tx).await);
+                }
+                let _ = tx.send(ApiEvent::ModelsLoaded { page, append });
+            }
+            Err(e) => {
+                let _ = tx.send(ApiEvent::ModelsFailed(e.to_string()));
+            }
+        }
+    });
+}
+
+/// The local server's models; one that isn't running shouldn't hide the
+/// registry
+async fn local_models(client: &ImsApiClient, tx: &ApiSender) -> Vec<ModelResponse> {
+    client.list_local_models().await.unwrap_or_else(|e| {
+        let _ = tx.send(ApiEvent::Error(format!("Local model listing failed: {}", e)));
+        Vec::new()
+    })
+}
+
+fn handle_up(state: &mut AppState) {
+    match state.focus {
+        FocusPane::Sidebar => {
+            state.tree_state.borrow_mut().key_up();
+        }
+        FocusPane::Thinking => {
+            if let Some(session) = &mut state.session {
+                session.thinking.manual_scroll(-1);
+            }
+        }
+        FocusPane::Generation => {
+            if let Some(session) = &mut state.session {
+                session.generation.manual_scroll(-1);
+            }
+        }
+        FocusPane::Inspector | FocusPane::Prompt => {}
+    }
+}
+
+fn handle_down(state: &mut AppState) {
+    match state.focus {
+        FocusPane::Sidebar => {
+            state.tree_state.borrow_mut().key_down();
+        }
+        FocusPane::Thinking => {
+            if let Some(session) = &mut state.session {
+                session.thinking.manual_scroll(1);
+            }
+        }
+        FocusPane::Generation => {
+            if let Some(session) = &mut state.session {
+                session.generation.manual_scroll(1);
+            }
+        }
+        FocusPane::Inspector | FocusPane::Prompt => {}
+    }
+}
+
+fn handle_settings_input(state: &mut AppState, key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Char('q') | KeyCode::Char('Q') => {
+            return false; 
+        }
+        KeyCode::Char('r') | KeyCode::Char('R') => {
+            state.reconnect_requested = true;
+            state.close_overlay(Overlay::Settings);
+        }
+        KeyCode::Char('h') | KeyCode::Char('H') => state.open_health(),
+        KeyCode::Up => state.settings.select_prev(),
+        KeyCode::Down => state.settings.select_next(),
+        KeyCode::Left | KeyCode::Right | KeyCode::Enter => {
+            let Some(item) = state.settings.selected_mut() else {
+                return true;
+            };
+            match key.code {
+                KeyCode::Left => item.value.step(false),
+                KeyCode::Right => item.value.step(true),
+                _ => item.value.activate(),
+            }
+            // Applied and saved on every change
+            let settings = std::mem::take(&mut state.settings);
+            if let Some(item) = settings.selected() {
+                settings::apply(state, item);
+            }
+            state.settings = settings;
+            let path = crate::app::config::config_path();
+            if let Err(e) = state.config.save_settings(&path) {
+                state.add_debug_log(format!("Failed to save settings: {:#}", e));
+            }
+        }
+        _ => {}
+    }
+
+    true
+}
+
+fn handle_model_registry_input(
+    state: &mut AppState,
+    key: KeyEvent,
+    api_tx: &ApiSender,
+) -> bool {
+    if state.model_registry.admin_menu.is_some() {
+        handle_admin_menu_input(state, key, api_tx);
+        return true;
+    }
+
+    let view = &mut state.model_registry;
+
+    if view.filter_editing {
+        match key.code {
+            KeyCode::Esc | KeyCode::Enter => view.filter_editing = false,
+            KeyCode::Backspace => {
+                view.filter.pop();
+                view.clamp_selection();
+            }
+            KeyCode::Char(c) => {
+                view.filter.push(c);
+                view.clamp_selection();
+            }
+            _ => {}
+        }
+        return true;
+    }
+
+    match key.code {
+        KeyCode::Up => view.select_prev(),
         KeyCode::Down => {
-            state.command_index += 1; // Simplified bounds check
+            view.select_next();
+            load_more_models(state, api_tx);
+        }
+        KeyCode::Left => {
+            let column = view.sort.prev();
+            view.sort_by(column);
+        }
+        KeyCode::Right => {
+            let column = view.sort.next();
+            view.sort_by(column);
+        }
+        KeyCode::Char('/') => view.filter_editing = true,
+        KeyCode::Char('o') => view.descending = !view.descending,
+        KeyCode::Char(c @ '1'..='8') => {
+            let column = SortColumn::ALL[c as usize - '1' as usize];
+            view.sort_by(column);
+        }
+        KeyCode::Char('r') => {
+            view.invalidate();
+            load_model_registry(state, api_tx);
+        }
+        KeyCode::Char('a') => {
+            let is_admin = state.api_client.as_ref().is_some_and(|c| c.has_admin_key());
+            if let Some(mode) = state.read_only.as_ref().filter(|_| is_admin) {
+                let error = format!("Read-only mode ({}): enter an admin key IMS Core accepts", mode.reason());
+                state.ask_api_key(error);
+            } else if !is_admin {
+                state.ask_api_key("Admin actions require ADMIN_API_KEY".to_string());
+            } else if view.selected_model().is_some() {
+                view.admin_menu = Some(AdminMenu::default());
+            }
         }
         KeyCode::Enter => {
-            // Execute selected command (Mock)
-            state.command_palette_visible = false;
-            state.add_debug_log(format!("Executed command index: {}", state.command_index));
-            // Actual dispatch logic would go here
+            if let Some(model_id) = view.selected_model().map(|m| m.model_id.clone()) {
+                if state.session.is_some() {
+                    state.set_session_model(&model_id);
+                    state.add_debug_log(format!("Session model set to {}", model_id));
+                    request_model_info(state, api_tx);
+                } else {
+                    state.add_debug_log("Open a file to switch its session model".to_string());
+                }
+            }
+        }
+        KeyCode::Char('d') => {
+            let model_id = view.selected_model().map(|m| m.model_id.clone());
+            let file_path = state
+                .session
+                .as_ref()
+                .map(|s| s.file_path.clone())
+                .or_else(|| state.get_selected_node().filter(|n| !n.is_dir).map(|n| n.path.clone()));
+            if let (Some(model_id), Some(path)) = (model_id, file_path) {
+                let file_type = file_type_key(&path);
+                state.add_debug_log(format!("Default model for .{} files: {}", file_type, model_id));
+                state.set_model_default(file_type, &model_id);
+            }
+        }
+        KeyCode::Char('q') | KeyCode::Char('Q') => {
+            return false;
+        }
+        _ => {}
+    }
+    true
+}
+
+fn handle_admin_menu_input(state: &mut AppState, key: KeyEvent, api_tx: &ApiSender) {
+    let Some(menu) = state.model_registry.admin_menu.as_mut() else { return };
+
+    if let Some(input) = menu.pricing_input.as_mut() {
+        match key.code {
+            KeyCode::Esc => menu.pricing_input = None,
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Char(c) if c.is_ascii_digit() || c == '.' || c == ',' || c == ' ' => input.push(c),
+            KeyCode::Enter => match parse_pricing(input) {
+                Some((cost_in, cost_out)) => {
+                    state.model_registry.admin_menu = None;
+                    let pricing = PricingUpdate {
+                        cost_in_per_mil: cost_in,
+                        cost_out_per_mil: cost_out,
+                    };
+                    dispatch_admin_action(state, AdminAction::UpdatePricing, Some(pricing), api_tx);
+                }
+                None => {
+                    state.add_debug_log("Pricing must be \"<in>,<out>\" per million tokens".to_string());
+                }
+            },
+            _ => {}
+        }
+        return;
+    }
+
+    match key.code {
+        KeyCode::Esc => state.model_registry.admin_menu = None,
+        KeyCode::Up => menu.selected = menu.selected.saturating_sub(1),
+        KeyCode::Down => menu.selected = (menu.selected + 1).min(AdminAction::ALL.len() - 1),
+        KeyCode::Enter => match AdminAction::ALL[menu.selected] {
+            AdminAction::UpdatePricing => {
+                let current = state
+                    .model_registry
+                    .selected_model()
+                    .map(|m| format!("{},{}", m.cost_in_per_mil, m.cost_out_per_mil))
+                    .unwrap_or_default();
+                if let Some(menu) = state.model_registry.admin_menu.as_mut() {
+                    menu.pricing_input = Some(current);
+                }
+            }
+            action => {
+                state.model_registry.admin_menu = None;
+                dispatch_admin_action(state, action, None, api_tx);
+            }
+        },
+        _ => {}
+    }
+}
+
+/// Apply an admin change optimistically and confirm (or roll back) in the background
+fn dispatch_admin_action(
+    state: &mut AppState,
+    action: AdminAction,
+    pricing: Option<PricingUpdate>,
+    api_tx: &ApiSender,
+) {
+    let Some(client) = state.api_client.clone() else { return };
+    let Some(mut optimistic) = state.model_registry.selected_model().cloned() else { return };
+
+    match (action, &pricing) {
+        (AdminAction::Activate, _) => optimistic.is_active = true,
+        (AdminAction::Deactivate, _) => optimistic.is_active = false,
+        (AdminAction::UpdatePricing, Some(p)) => {
+            optimistic.cost_in_per_mil = p.cost_in_per_mil;
+            optimistic.cost_out_per_mil = p.cost_out_per_mil;
+        }
+        (AdminAction::UpdatePricing, None) => return,
+    }
+
+    let model_id = optimistic.model_id.clone();
+    let Some(previous) = state.model_registry.replace_model(optimistic) else { return };
+    state.add_debug_log(format!("{}: {} (pending)", action.label(), model_id));
+
+    send_admin_action(client, action, pricing, previous, api_tx.clone());
+}
+
+/// Send an admin change for `previous.model_id`, reporting the outcome on `tx`
+fn send_admin_action(
+    client: ImsApiClient,
+    action: AdminAction,
+    pricing: Option<PricingUpdate>,
+    previous: ModelResponse,
+    tx: ApiSender,
+) {
+    tokio::spawn(async move {
+        let model_id = previous.model_id.clone();
+        let result = match (action, &pricing) {
+            (AdminAction::Activate, _) => client.set_model_active(&model_id, true).await,
+            (AdminAction::Deactivate, _) => client.set_model_active(&model_id, false).await,
+            (AdminAction::UpdatePricing, Some(p)) => client.update_model_pricing(&model_id, p).await,
+            (AdminAction::UpdatePricing, None) => unreachable!("pricing checked before dispatch"),
+        };
+        let event = match result {
+            Ok(model) => ApiEvent::ModelUpdated(model),
+            // Keep the optimistic entry until the key prompt is answered
+            Err(e) if is_unauthorized(&e) => ApiEvent::AuthRequired {
+                error: e.to_string(),
+                retry: RetryRequest::Admin { action, pricing, previous },
+            },
+            Err(e) => ApiEvent::ModelUpdateFailed {
+                previous,
+                error: e.to_string(),
+            },
+        };
+        let _ = tx.send(event);
+    });
+}
+
+fn handle_command_palette_input(state: &mut AppState, key: KeyEvent, runtime: &Runtime) -> bool {
+    match key.code {
+        KeyCode::Up => {
+            state.command_index = state.command_index.saturating_sub(1);
+        }
+        KeyCode::Down => {
+            state.command_index = (state.command_index + 1).min(state.palette_len().saturating_sub(1));
+        }
+        KeyCode::Enter => {
+            let index = state.command_index;
+            match state.command_argument.take() {
+                Some(pick) => {
+                    let value = pick.matching(&state.command_input).get(index).map(|c| c.value.clone());
+                    let command = commands::registry().iter().find(|c| c.id == pick.command);
+                    state.close_command_palette();
+                    if let (Some(command), Some(value)) = (command, value) {
+                        state.add_debug_log(format!("Executed command: {} {}", command.id, value));
+                        runtime.execute_with(state, command, value);
+                    }
+                }
+                None => {
+                    let selected = state.palette_commands().get(index).copied();
+                    state.close_command_palette();
+                    if let Some(command) = selected {
+                        state.add_debug_log(format!("Executed command: {}", command.id));
+                        state.record_command(command.id);
+                        runtime.execute(state, command);
+                    }
+                }
+            }
         }
         KeyCode::Backspace => {
             state.command_input.pop();
+            state.command_index = 0;
         }
         KeyCode::Char(c) => {
             state.command_input.push(c);
+            state.command_index = 0;
         }
         _ => {}
     }
     true
-}
\ No newline at end of file
+}
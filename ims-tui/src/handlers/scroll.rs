@@ -6,11 +6,14 @@
 //! 3. Persistence: Other pane remains in auto_scroll if not interacted with
 //! 4. Reset: Opening new file resets both panes to auto_scroll = true
 
-use crate::app::{ActiveSession, ScrollState};
+use crate::app::ScrollState;
 
-/// Scroll logic manager
+/// Scroll logic manager; the panes scroll through `AppState` methods, so
+/// only the tests use it for now
+#[allow(dead_code)]
 pub struct ScrollManager;
 
+#[allow(dead_code)]
 impl ScrollManager {
     /// Handle scroll up event
     pub fn scroll_up(scroll_state: &mut ScrollState, lines: usize) {
@@ -74,9 +77,11 @@ mod tests {
 
     #[test]
     fn test_scroll_up() {
-        let mut scroll = ScrollState::default();
-        scroll.scroll_offset = 10;
-        scroll.auto_scroll = true;
+        let mut scroll = ScrollState {
+            auto_scroll: true,
+            scroll_offset: 10,
+            ..Default::default()
+        };
 
         ScrollManager::scroll_up(&mut scroll, 3);
 
@@ -87,7 +92,6 @@ mod tests {
     #[test]
     fn test_scroll_down() {
         let mut scroll = ScrollState::default();
-        scroll.auto_scroll = true;
 
         ScrollManager::scroll_down(&mut scroll, 5, 100);
 
@@ -100,6 +104,7 @@ mod tests {
         let scroll = ScrollState {
             auto_scroll: false,
             scroll_offset: 50,
+            ..Default::default()
         };
 
         let content_lines = 100;
@@ -114,6 +119,7 @@ mod tests {
         let mut scroll = ScrollState {
             auto_scroll: false,
             scroll_offset: 50,
+            ..Default::default()
         };
 
         ScrollManager::maybe_re_enable_auto_scroll(&mut scroll, 100, 50);
@@ -126,6 +132,7 @@ mod tests {
         let scroll = ScrollState {
             auto_scroll: true,
             scroll_offset: 0,
+            ..Default::default()
         };
 
         let (start, end) = ScrollManager::calculate_visible_range(&scroll, 100, 20);
@@ -139,6 +146,7 @@ mod tests {
         let scroll = ScrollState {
             auto_scroll: false,
             scroll_offset: 10,
+            ..Default::default()
         };
 
         let (start, end) = ScrollManager::calculate_visible_range(&scroll, 100, 20);
@@ -151,7 +159,7 @@ mod tests {
     fn test_scroll_independence() {
         // Simulate two panes with independent scroll states
         let mut thinking = ScrollState::default();
-        let mut generation = ScrollState::default();
+        let generation = ScrollState::default();
 
         // User scrolls thinking pane
         ScrollManager::scroll_up(&mut thinking, 5);
@@ -160,4 +168,19 @@ mod tests {
         assert!(!thinking.auto_scroll);
         assert!(generation.auto_scroll);
     }
+
+    #[test]
+    fn test_manual_scroll_starts_from_rendered_bottom() {
+        let mut scroll = ScrollState::default();
+        // 40 wrapped rows rendered into a 10-row pane
+        assert_eq!(scroll.offset(40, 10), 30);
+
+        scroll.manual_scroll(-1);
+        assert!(!scroll.auto_scroll);
+        assert_eq!(scroll.scroll_offset, 29);
+
+        // Scrolling past the bottom resumes following the stream
+        scroll.manual_scroll(5);
+        assert!(scroll.auto_scroll);
+    }
 }
@@ -4,237 +4,467 @@
 //! monitoring metrics, and orchestrating multi-agent workflows.
 
 mod app;
+mod core;
 mod handlers;
+#[cfg(any(test, feature = "testing"))]
+// Only the tests call it from this crate; the feature exports it for forks
+#[cfg_attr(not(test), allow(dead_code))]
+mod testing;
 mod ui;
 
 use anyhow::{Context, Result};
-use app::{api::ImsApiClient, AppState};
-use crossterm::{
-    event::{self, Event, MouseEvent},
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+use app::{
+    api::ImsApiClient,
+    config::AppConfig,
+    connection::Pollers,
+    AppState,
 };
+use crossterm::event::Event;
 use ratatui::{backend::CrosstermBackend, layout::Rect, Terminal};
 use std::{
     io,
     path::PathBuf,
     time::{Duration, Instant},
 };
-use tokio::sync::mpsc;
-use tracing::{error, info, warn};
+use tracing::{info, warn};
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize logging
-    tracing_subscriber::fmt()
-        .with_env_filter("ims_tui=debug")
-        .with_target(false)
-        .with_thread_ids(false)
-        .with_file(true)
-        .with_line_number(true)
-        .init();
-
-    info!("Starting IMS-TUI v1.0.0");
-
     // Load configuration
     dotenv::dotenv().ok();
-    let api_base_url = std::env::var("IMS_API_URL").unwrap_or_else(|_| "http://localhost:8000".to_string());
-    let admin_api_key = std::env::var("ADMIN_API_KEY").ok();
+    // `doctor` checks the environment, reporting a broken config itself
+    if std::env::args().nth(1).as_deref() == Some("doctor") {
+        let report = app::doctor::run(&app::config::config_path()).await;
+        print!("{}", report.render());
+        std::process::exit(if report.passed() { 0 } else { 1 });
+    }
+    let config = AppConfig::load()?;
+    // A gateway profile may move IMS Core and change how the key is sent
+    let endpoint = config.api.endpoint().context("Invalid [api] config")?;
+    let api_base_url = endpoint.base_url();
+    let admin_api_key = std::env::var(endpoint.key_var()).ok();
+    // `--demo` plays a scripted session instead of talking to a backend
+    let demo = app::demo::DemoScript::from_args(std::env::args().skip(1))?;
+    let redactor = app::redact::Redactor::new(&config.redaction).context("Invalid [redaction] config")?;
+    let vendors = app::vendors::VendorRegistry::new(&config.vendors, &config.local).context("Invalid [[vendors]] config")?;
+    let guardrails = app::guardrails::Guardrails::new(&config.guardrails).context("Invalid [guardrails] config")?;
+
+    // Initialize logging (and OTLP export if configured); flushes on drop
+    let _trace_guard = app::otel::init(&config.tracing)?;
+
+    info!("Starting IMS-TUI v1.0.0");
 
     info!("API URL: {}", api_base_url);
 
     // Setup terminal
-    enable_raw_mode().context("Failed to enable raw mode")?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, event::EnableMouseCapture).context("Failed to enter alternate screen")?;
-    let backend = CrosstermBackend::new(stdout);
+    core::terminal::enter()?;
+    let backend = CrosstermBackend::new(io::stdout());
     let mut terminal = Terminal::new(backend).context("Failed to create terminal")?;
 
     // Initialize application state
     let mut app_state = AppState::new(api_base_url.clone());
+    app_state.config = config.clone();
+    app_state.redactor = redactor.clone();
+    app_state.vendors = vendors;
+    app_state.guardrails = guardrails.clone();
+    app_state.user_id = app::identity::user_id(&config.user, &app_state.workspace_root);
+    app_state.apply_ui_config();
+    // A demo leaves the local metrics store and offline queue untouched
+    if demo.is_none() {
+        app_state.open_metrics_store(&app::metrics_store::store_path());
+    }
 
     // Add demo files for testing
     app_state.add_file(PathBuf::from("/workspace/src/main.rs"));
     app_state.add_file(PathBuf::from("/workspace/src/app.rs"));
     app_state.add_file(PathBuf::from("/workspace/README.md"));
 
+    // Prompts queued during a previous offline session
+    if demo.is_none() {
+        match app::offline::OfflineQueue::load(&config.offline.queue_path) {
+            Ok(queue) => {
+                if !queue.is_empty() {
+                    app_state.add_debug_log(format!("Restored {} queued prompt(s)", queue.len()));
+                }
+                app_state.offline_queue = queue;
+            }
+            Err(e) => {
+                warn!("Failed to load offline queue: {}", e);
+                app_state.add_debug_log(format!("Failed to load offline queue: {}", e));
+            }
+        }
+    }
+
+    // Models from the last run, until the registry is revalidated
+    if demo.is_none() && config.registry.cache {
+        app_state.restore_model_cache(&app::model_cache::cache_path());
+    }
+
+    if demo.is_none() {
+        app_state.load_schedules(app::schedule::runs_path());
+        app_state.restore_command_recents(&app::palette::Recents::default_path());
+    }
+
+    // Take the session lock; a lock left behind means the last run crashed
+    let mut recovery = None;
+    if demo.is_none() {
+        match app::recovery::Recovery::start(&app::recovery::recovery_dir()) {
+            Ok((lock, found)) => {
+                if let Some(work) = found {
+                    app_state.offer_recovered(work);
+                }
+                recovery = Some(lock);
+            }
+            Err(e) => {
+                warn!("Crash recovery unavailable: {:#}", e);
+                app_state.add_debug_log(format!("Crash recovery unavailable: {:#}", e));
+            }
+        }
+    }
+
+    // Background tasks report back over this channel
+    let (api_tx, mut api_rx) = core::bus::channel(core::bus::CAPACITY);
+
     // Initialize API client (Mock Mode = true)
-    let api_client = ImsApiClient::new(api_base_url.clone(), admin_api_key.clone(), true)
-        .context("Failed to create API client")?;
-    
+    let api_client = ImsApiClient::new(api_base_url.clone(), admin_api_key.clone(), true, &config.http)
+        .context("Failed to create API client")?
+        .with_network_log(api_tx.clone())
+        .with_redactor(redactor)
+        .with_guardrails(guardrails)
+        .with_audit(app::audit::AuditLog::new(app::audit::audit_path()))
+        .with_user_id(app_state.user_id.clone())
+        .with_local(app_state.config.local.clone())
+        .with_endpoint(endpoint)
+        .with_metrics(config.metrics.clone())
+        .with_protocol(&config.api)
+        .context("Invalid [api] config")?;
+
     app_state.api_client = Some(api_client.clone());
+    if demo.is_none() {
+        match app::watch::FileWatcher::new(api_tx.clone()) {
+            Ok(watcher) => app_state.file_watcher = Some(watcher),
+            Err(e) => {
+                warn!("Watch mode unavailable: {:#}", e);
+                app_state.add_debug_log(format!("Watch mode unavailable: {:#}", e));
+            }
+        }
+        app_state.load_watches();
+    }
+    if !api_client.has_admin_key() && demo.is_none() {
+        app_state.enter_read_only(app::read_only::ReadOnly::NoAdminKey);
+    }
 
     // Test API connection
-    match api_client.health_check().await {
-        Ok(health) => {
-            info!("API Health: {:?}", health);
-            app_state.api_connected = true;
-            app_state.add_debug_log("API connected successfully (Mock)".to_string());
-        }
-        Err(e) => {
-            warn!("API connection failed: {}", e);
-            app_state.add_debug_log(format!("API connection failed: {}", e));
+    if demo.is_some() {
+        app_state.api_connected = true;
+        app_state.add_debug_log("Demo mode: playing a scripted session, no backend".to_string());
+    } else {
+        match api_client.health_check().await {
+            Ok(health) => {
+                info!("API Health: {:?}", health);
+                app_state.api_connected = true;
+                app_state.add_debug_log("API connected successfully (Mock)".to_string());
+            }
+            Err(e) => {
+                warn!("API connection failed: {}", e);
+                app_state.add_debug_log(format!("API connection failed: {}", e));
+            }
         }
     }
 
     // Setup background tasks
-    let (api_tx, mut api_rx) = mpsc::unbounded_channel();
     let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
 
-    // Spawn metrics poller
-    if app_state.api_connected {
-        let client_clone = ImsApiClient::new(api_base_url.clone(), admin_api_key.clone(), true)?;
+    // Count workspace tokens in the background (this also loads the
+    // tokenizer vocabularies before the first keystroke needs them)
+    let files = app_state.workspace_files();
+    let scan_tx = api_tx.clone();
+    tokio::spawn(async move {
+        let _ = tokio::task::spawn_blocking(app::tokens::warm_up).await;
+        app::tokens::workspace_token_scanner(files, scan_tx).await;
+    });
+
+    // Spawn telemetry bus consumer
+    if config.telemetry.enabled && demo.is_none() {
+        app_state.telemetry_status = "Connecting...".to_string();
         let tx_clone = api_tx.clone();
         let rx_clone = shutdown_rx.clone();
-        
+        let telemetry_config = config.telemetry.clone();
+
         tokio::spawn(async move {
-            app::api::metrics_poller(client_clone, tx_clone, rx_clone).await;
+            app::telemetry::telemetry_consumer(telemetry_config, tx_clone, rx_clone).await;
         });
 
-        info!("Started metrics poller");
+        info!("Started telemetry consumer");
+    }
+
+    // Send anything queued last time if the backend is already up
+    handlers::flush_offline_queue(&mut app_state, &api_tx);
+
+    // Spawn health checker (drives offline mode) and metrics poller
+    let mut pollers = Pollers::new(api_client.clone(), api_tx.clone(), shutdown_rx.clone(), config.clone());
+    if demo.is_none() {
+        pollers.spawn(app_state.api_connected);
     }
 
     // Main event loop
-    let result = run_event_loop(&mut terminal, &mut app_state, &mut api_rx, api_tx.clone()).await;
+    let result = run_event_loop(&mut terminal, &mut app_state, &mut api_rx, api_tx.clone(), &mut pollers, demo, recovery.as_mut()).await;
 
     // Cleanup
     info!("Shutting down...");
     let _ = shutdown_tx.send(true);
-    
-    disable_raw_mode().context("Failed to disable raw mode")?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen, event::DisableMouseCapture)
-        .context("Failed to leave alternate screen")?;
-    terminal.show_cursor().context("Failed to show cursor")?;
+    if let Err(e) = app_state.command_recents.save() {
+        warn!("Failed to save palette history: {:#}", e);
+    }
+    // Only a clean exit gives up the lock and the saved work
+    if let (Ok(()), Some(recovery)) = (&result, recovery) {
+        if let Err(e) = recovery.finish() {
+            warn!("Failed to clear recovery files: {:#}", e);
+        }
+    }
+
+    core::terminal::leave()?;
 
     info!("IMS-TUI exited");
-    
+
     result
 }
 
+/// Longest the loop reduces queued events before it gets back to input
+/// and drawing
+const EVENT_BUDGET: Duration = Duration::from_millis(8);
+/// Shortest time between frames (~60 FPS)
+const FRAME_INTERVAL: Duration = Duration::from_millis(16);
+/// Redraw at least this often, for countdowns and elapsed times
+const TICK_RATE: Duration = Duration::from_millis(100);
+
 /// Main event loop
+///
+/// Terminal input is read on its own thread, and each pass runs in stages:
+/// all pending input, then queued core and API events up to
+/// `EVENT_BUDGET`, then a draw if anything changed and the frame interval
+/// has passed. A slow draw therefore delays the next frame, not keys.
 async fn run_event_loop(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
     state: &mut AppState,
-    api_rx: &mut mpsc::UnboundedReceiver<app::api::ApiEvent>,
-    api_tx: mpsc::UnboundedSender<app::api::ApiEvent>,
+    api_rx: &mut core::bus::Receiver<app::api::ApiEvent>,
+    api_tx: app::api::ApiSender,
+    pollers: &mut Pollers,
+    demo: Option<app::demo::DemoScript>,
+    mut recovery: Option<&mut app::recovery::Recovery>,
 ) -> Result<()> {
-    let tick_rate = Duration::from_millis(100);
     let mut last_tick = Instant::now();
+    let mut next_frame = Instant::now();
+    let mut dirty = true;
+    let mut window_title = app::title::WindowTitle::default();
+
+    // Core runtime: commands apply effects here, tasks report back via `core_rx`
+    let (core_tx, mut core_rx) = core::bus::channel(core::bus::CAPACITY);
+    let runtime = core::runtime::Runtime::new(core_tx);
+    if let Some(script) = demo {
+        tokio::spawn(app::demo::play(script, runtime.sender()));
+    }
+
+    let (input_tx, mut input_rx) = core::bus::channel(core::bus::CAPACITY);
+    let reader = core::terminal::spawn_reader(input_tx).context("Failed to start the input reader")?;
+    core::terminal::spawn_signal_listener(runtime.sender());
 
     loop {
-        // Render UI
-        terminal.draw(|f| {
-            ui::render(f, state);
-        })?;
-
-        // Handle events
-        let timeout = tick_rate
-            .checked_sub(last_tick.elapsed())
-            .unwrap_or_else(|| Duration::from_secs(0));
-
-        if event::poll(timeout)? {
-            match event::read()? {
-                Event::Key(key) => {
-                    if !handlers::handle_key_event(state, key, &api_tx) {
-                        break; // User quit
-                    }
-                }
-                Event::Mouse(mouse) => {
-                    if let Ok(size) = terminal.size() {
-                        let rect = Rect {
-                            x: 0,
-                            y: 0,
-                            width: size.width,
-                            height: size.height,
-                        };
-                        handlers::handle_mouse_event(state, mouse, rect);
-                    }
-                }
-                _ => {}
+        // Input: everything pending, before any more drawing
+        let mut quit = false;
+        while let Ok(event) = input_rx.try_recv() {
+            dirty = true;
+            if !handle_terminal_event(terminal, state, event, &api_tx, &runtime) {
+                quit = true;
+                break;
             }
         }
+        if quit {
+            break;
+        }
 
-        // Handle API events
-        while let Ok(api_event) = api_rx.try_recv() {
-            match api_event {
-                app::api::ApiEvent::MetricsUpdate(metrics) => {
-                    if let Some(total) = metrics.total_models_registered {
-                        state.add_debug_log(format!("Models registered: {}", total));
-                    }
-                }
-                app::api::ApiEvent::HealthUpdate(health) => {
-                    state.api_connected = health.status.contains("healthy");
-                    state.add_debug_log(format!("Health: {}", health.status));
-                }
-                app::api::ApiEvent::GenerationComplete(response) => {
-                    state.append_generation(&response.content);
-                    state.add_thinking(format!("Finished in {:.2}ms. Tokens: {} (Cost: ${:.6})", 
-                        response.latency_ms, 
-                        response.tokens.total, 
-                        response.cost.total
-                    ));
-                    state.total_tokens_used += response.tokens.total as u64;
-                    state.total_cost += response.cost.total;
-                }
-                app::api::ApiEvent::Error(err) => {
-                    error!("API Error: {}", err);
-                    state.add_debug_log(format!("API Error: {}", err));
-                }
-            }
+        // Events from commands and background tasks
+        let budget_end = Instant::now() + EVENT_BUDGET;
+        state.profiler.core_backlog = core_rx.len();
+        state.profiler.core_overflow = core_rx.stats();
+        while Instant::now() < budget_end {
+            let Ok(event) = core_rx.try_recv() else { break };
+            core::reduce::reduce(state, event);
+            dirty = true;
+        }
+
+        if state.quit_requested {
+            break;
+        }
+
+        // Reconnect requested from Settings
+        if std::mem::take(&mut state.reconnect_requested) {
+            state.add_debug_log("Reconnecting to IMS Core...".to_string());
+            state.reconnect = Some(Default::default());
+            pollers.reconnect();
+        }
+
+        // Polling paused from the palette, or its intervals changed in Settings
+        if state.polling_paused != pollers.is_paused() {
+            pollers.set_paused(state.polling_paused, state.api_connected);
+        }
+        pollers.set_intervals(state.config.polling.clone(), state.api_connected);
+
+        // API events take the same path through the reducer
+        state.profiler.api_backlog = api_rx.len();
+        state.profiler.api_overflow = api_rx.stats();
+        while Instant::now() < budget_end {
+            let Ok(api_event) = api_rx.try_recv() else { break };
+            core::reduce::reduce(state, api_event.into());
+            dirty = true;
+        }
+        if let Some(request) = state.poller_request.take() {
+            pollers.apply(request);
+        }
+        if std::mem::take(&mut state.flush_queue_requested) {
+            handlers::flush_offline_queue(state, &api_tx);
+        }
+        if std::mem::take(&mut state.registry_reload_requested) {
+            handlers::load_model_registry(state, &api_tx);
+        }
+        if std::mem::take(&mut state.index_requested) {
+            app::embeddings::start_index(state, &api_tx);
+        }
+        if std::mem::take(&mut state.compaction_requested) {
+            app::compaction::start(state, &api_tx);
+        }
+        handlers::send_due_retries(state, &api_tx);
+        handlers::send_queued_prompts(state, &api_tx);
+        handlers::run_due_schedules(state, &api_tx, &runtime);
+        handlers::run_due_watches(state, &api_tx, &runtime);
+        if let Some(slow_model) = state.suggest_faster_for.take() {
+            app::latency::request_suggestion(state, slow_model, &api_tx);
+        }
+        if std::mem::take(&mut state.suspend_requested) {
+            core::terminal::suspended(terminal, &reader, core::terminal::stop)?;
+            dirty = true;
+            window_title.invalidate();
+        }
+        if std::mem::take(&mut state.alerts.bell) {
+            core::terminal::bell()?;
+        }
+        if std::mem::take(&mut state.redraw_requested) {
+            core::terminal::refresh(terminal)?;
+            dirty = true;
+        }
+        if let Some(citation) = state.citation_jump.take() {
+            let opened = core::terminal::suspended(terminal, &reader, || app::citations::open(&citation))?;
+            state.finish_citation_jump(&citation, opened);
+            dirty = true;
+            window_title.invalidate();
+        }
+        if let Some(target) = state.external_edit.take() {
+            let text = state.external_text(target);
+            let edited = core::terminal::suspended(terminal, &reader, || app::external_editor::edit(&text))?;
+            state.finish_external_edit(target, edited);
+            dirty = true;
+            window_title.invalidate();
         }
 
         // Periodic tick
-        if last_tick.elapsed() >= tick_rate {
-            // Update state (e.g., simulate agent activity)
-            // simulate_agent_activity(state); // Disabled to stop spam
-            last_tick = Instant::now();
+        let now = Instant::now();
+        if now.duration_since(last_tick) >= TICK_RATE {
+            state.profiler.record_lag(now.duration_since(last_tick) - TICK_RATE);
+            if let Some(recovery) = recovery.as_deref_mut() {
+                recovery.tick(state);
+            }
+            app::status_bar::refresh_due(state, &runtime.sender());
+            state.tick_activity(now);
+            match window_title.changed(state) {
+                Some(app::title::Update::Set(title)) => core::terminal::set_title(&title)?,
+                Some(app::title::Update::Restore) => core::terminal::restore_title()?,
+                None => {}
+            }
+            last_tick = now;
+            dirty = true;
         }
-    }
 
-    Ok(())
-}
+        // Render UI
+        if dirty && now >= next_frame {
+            let frame_start = Instant::now();
+            tracing::debug_span!("render").in_scope(|| {
+                terminal.draw(|f| {
+                    ui::render(f, state);
+                })
+            })?;
+            let render = frame_start.elapsed();
+            state.profiler.record_frame(frame_start, render);
+            // A slow frame leaves at least as long again for input
+            next_frame = Instant::now() + FRAME_INTERVAL.max(render);
+            dirty = false;
+        }
 
-/// Simulate agent activity for demo purposes (Disabled)
-fn simulate_agent_activity(state: &mut AppState) {
-    if let Some(session) = &mut state.session {
-        // Simulate thinking logs
-        if state.thinking_log.len() < 50 {
-            let timestamp = chrono::Local::now().format("%H:%M:%S");
-            state.add_thinking(format!("[{}] Analyzing code structure...", timestamp));
+        // Out of budget with events still queued: go round again
+        if !core_rx.is_empty() || !api_rx.is_empty() || !input_rx.is_empty() {
+            continue;
         }
 
-        // Simulate code generation
-        if state.generated_code.len() < 1000 {
-            state.append_generation("// Generated code\n");
-            state.append_generation("fn example() {\n");
-            state.append_generation("    println!(\"Hello, IMS!\");\n");
-            state.append_generation("}\n");
+        // Otherwise sleep until something arrives or a frame or tick is due
+        let wake = if dirty { next_frame } else { last_tick + TICK_RATE };
+        tokio::select! {
+            Some(event) = input_rx.recv() => {
+                dirty = true;
+                if !handle_terminal_event(terminal, state, event, &api_tx, &runtime) {
+                    break;
+                }
+            }
+            Some(event) = core_rx.recv() => {
+                core::reduce::reduce(state, event);
+                dirty = true;
+            }
+            Some(api_event) = api_rx.recv() => {
+                core::reduce::reduce(state, api_event.into());
+                dirty = true;
+            }
+            _ = tokio::time::sleep_until(wake.into()) => {}
         }
+    }
 
-        // Update token usage
-        state.total_tokens_used += 10;
-        state.total_cost = state.total_tokens_used as f64 * 0.000001;
+    drop(input_rx);
+    let _ = reader.join();
+    Ok(())
+}
+
+/// Apply one terminal event; `false` once the user quits
+fn handle_terminal_event(
+    terminal: &Terminal<CrosstermBackend<io::Stdout>>,
+    state: &mut AppState,
+    event: Event,
+    api_tx: &app::api::ApiSender,
+    runtime: &core::runtime::Runtime,
+) -> bool {
+    if matches!(event, Event::Key(_) | Event::Mouse(_)) {
+        state.note_input(Instant::now());
+    }
+    match event {
+        Event::Key(key) => handlers::handle_key_event(state, key, api_tx, runtime),
+        Event::Mouse(mouse) => {
+            if let Ok(size) = terminal.size() {
+                let rect = Rect {
+                    x: 0,
+                    y: 0,
+                    width: size.width,
+                    height: size.height,
+                };
+                handlers::handle_mouse_event(state, mouse, rect, api_tx);
+            }
+            true
+        }
+        _ => true,
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-
     #[test]
     fn test_api_url_default() {
         std::env::remove_var("IMS_API_URL");
         let url = std::env::var("IMS_API_URL").unwrap_or_else(|_| "http://localhost:8000".to_string());
         assert_eq!(url, "http://localhost:8000");
     }
-
-    #[test]
-    fn test_simulation() {
-        let mut state = AppState::default();
-        state.open_selected_file(); // No files, should be safe
-
-        simulate_agent_activity(&mut state);
-        
-        // Should not crash
-    }
 }
@@ -0,0 +1,214 @@
+//! UI Test Harness
+//!
+//! Renders into ratatui's `TestBackend` and snapshots the resulting text
+//! with `insta`, plus a builder for representative `AppState`s. Compiled
+//! for tests, and with the `testing` feature for forks that want to reuse
+//! it. Snapshots live in `snapshots/` next to the module under test; run
+//! `cargo insta review` (or `INSTA_UPDATE=always cargo test`) after an
+//! intended layout change.
+
+use std::path::PathBuf;
+
+use ratatui::{backend::TestBackend, buffer::Buffer, Frame, Terminal};
+
+use crate::app::{
+    api::{CostUsage, ExecuteResponse, TokenUsage},
+    metrics_store::ActiveModel,
+    thinking::ThinkingEntry,
+    ActiveSession, AppState, ConversationTurn, FileNode, FocusPane,
+};
+
+/// Screen size used unless a test needs another
+pub const WIDTH: u16 = 120;
+pub const HEIGHT: u16 = 36;
+
+/// Text of every cell, one line per row with trailing spaces trimmed
+pub fn buffer_text(buffer: &Buffer) -> String {
+    let area = buffer.area;
+    (area.top()..area.bottom())
+        .map(|y| {
+            let row: String = (area.left()..area.right())
+                .map(|x| buffer[(x, y)].symbol())
+                .collect();
+            row.trim_end().to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Draw one frame of `width`x`height` with `render` and return its text
+pub fn render_with(width: u16, height: u16, render: impl FnOnce(&mut Frame)) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).expect("test backend");
+    let frame = terminal.draw(render).expect("draw");
+    buffer_text(frame.buffer)
+}
+
+/// The whole screen as `ui::render` draws it
+pub fn render_app(state: &AppState, width: u16, height: u16) -> String {
+    render_with(width, height, |f| crate::ui::render(f, state))
+}
+
+/// Snapshot settings that mask what changes between runs: wall-clock
+/// times, dates and ages
+pub fn settings() -> insta::Settings {
+    let mut settings = insta::Settings::clone_current();
+    settings.add_filter(r"\d{4}-\d{2}-\d{2}", "[date]");
+    settings.add_filter(r"\d{2}:\d{2}:\d{2}(\.\d+)?", "[time]");
+    settings.add_filter(r"\d{2}:\d{2}", "[time]");
+    settings.add_filter(r"\b\d+s ago", "[age] ago");
+    settings.set_prepend_module_to_snapshot(false);
+    settings
+}
+
+/// `insta::assert_snapshot!` under `testing::settings()`
+#[macro_export]
+macro_rules! assert_ui_snapshot {
+    ($name:expr, $text:expr) => {
+        $crate::testing::settings().bind(|| insta::assert_snapshot!($name, $text))
+    };
+}
+
+/// A response as the Action Gateway returns it
+pub fn response(model_id: &str, content: &str) -> ExecuteResponse {
+    ExecuteResponse {
+        content: content.to_string(),
+        model_id: model_id.to_string(),
+        tokens: TokenUsage {
+            input: 120,
+            output: 80,
+            total: 200,
+        },
+        cost: CostUsage {
+            input: 0.0006,
+            output: 0.0012,
+            total: 0.0018,
+        },
+        latency_ms: 850.0,
+        tool_calls: Vec::new(),
+        images: Vec::new(),
+        transcripts: Vec::new(),
+    }
+}
+
+/// Builds `AppState`s for rendering tests, starting from a connected
+/// backend and no workspace
+pub struct StateBuilder {
+    state: AppState,
+}
+
+impl Default for StateBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StateBuilder {
+    pub fn new() -> Self {
+        Self {
+            state: AppState {
+                api_connected: true,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// A small Rust project: `src/` with two files, plus a README
+    pub fn workspace(mut self) -> Self {
+        self.state.workspace_root = PathBuf::from("/home/dev/demo");
+        let mut src = FileNode::new_dir(PathBuf::from("src"));
+        for (name, tokens) in [("main.rs", 1_840), ("lib.rs", 420)] {
+            let mut file = FileNode::new_file(PathBuf::from("src").join(name));
+            file.tokens = tokens;
+            src.children.push(file);
+        }
+        let mut readme = FileNode::new_file(PathBuf::from("README.md"));
+        readme.tokens = 310;
+        readme.model = "claude-3-5-sonnet".to_string();
+        self.state.file_tree = vec![src, readme];
+        self.state.tree_state.borrow_mut().open(vec!["src".to_string()]);
+        self.state.tree_state.borrow_mut().select(vec!["src".to_string(), "src/main.rs".to_string()]);
+        self
+    }
+
+    /// An open session on `file` with `model_id`
+    pub fn session(mut self, file: &str, model_id: &str) -> Self {
+        let vendor = self.state.vendors.for_model(model_id);
+        self.state.session = Some(ActiveSession::new(PathBuf::from(file), vendor, model_id.to_string()));
+        self
+    }
+
+    /// One answered prompt, as it appears after `GenerationComplete`
+    pub fn exchange(mut self, prompt: &str, answer: &str) -> Self {
+        let model_id = self
+            .state
+            .session
+            .as_ref()
+            .map_or("gpt-4o".to_string(), |s| s.model_id.clone());
+        let response = response(&model_id, answer);
+        self.state.add_thinking(ThinkingEntry::user(prompt));
+        self.state.conversation.push(ConversationTurn {
+            response: Some(answer.to_string()),
+            prompt_entry: self.state.thinking_log.last().map(|e| e.id),
+            ..ConversationTurn::new(prompt, model_id.clone())
+        });
+        self.state.add_thinking(ThinkingEntry::agent(format!("◀ {}:", model_id)));
+        self.state.add_thinking(ThinkingEntry::agent(answer));
+        self.state.add_thinking(ThinkingEntry::system(format!(
+            "Finished in {:.2}ms. Tokens: {} (Cost: ${:.6})",
+            response.latency_ms, response.tokens.total, response.cost.total
+        )));
+        self.state.code_blocks.extend(crate::app::blocks::extract_blocks(answer));
+        self.state.append_generation(answer);
+        self.state.total_tokens_used += response.tokens.total as u64;
+        self.state.total_cost += response.cost.total;
+        self.state.request_count += 1;
+        match self.state.active_models.iter_mut().find(|m| m.model_id == model_id) {
+            Some(model) => {
+                model.requests += 1;
+                model.tokens += response.tokens.total as u64;
+                model.cost += response.cost.total;
+            }
+            None => self.state.active_models.push(ActiveModel {
+                model_id,
+                last_used: chrono::Utc::now().timestamp(),
+                requests: 1,
+                tokens: response.tokens.total as u64,
+                cost: response.cost.total,
+            }),
+        }
+        self
+    }
+
+    pub fn disconnected(mut self) -> Self {
+        self.state.api_connected = false;
+        self
+    }
+
+    pub fn focus(mut self, pane: FocusPane) -> Self {
+        self.state.focus = pane;
+        self
+    }
+
+    /// Anything the other methods don't cover
+    pub fn with(mut self, edit: impl FnOnce(&mut AppState)) -> Self {
+        edit(&mut self.state);
+        self
+    }
+
+    pub fn build(self) -> AppState {
+        self.state
+    }
+
+    /// Workspace, an open session and one exchange with a code block
+    pub fn representative() -> AppState {
+        Self::new()
+            .workspace()
+            .session("src/main.rs", "gpt-4o")
+            .exchange(
+                "Add a greeting",
+                "Here is the change:\n\n```rust\nfn main() {\n    println!(\"Hello, IMS!\");\n}\n```\n",
+            )
+            .focus(FocusPane::Prompt)
+            .build()
+    }
+}
@@ -0,0 +1,72 @@
+//! Admin API Key Prompt - masked input shown after a 401/403
+
+use crate::app::api_key::ApiKeyPrompt;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+pub fn render(f: &mut Frame, prompt: &ApiKeyPrompt, area: Rect) {
+    let width = 60.min(area.width);
+    // The error wraps inside the borders; the key input sits below it
+    let error_rows = (prompt.error.chars().count() as u16).div_ceil(width.saturating_sub(2).max(1)).max(1);
+    let height = (6 + error_rows + prompt.retries.len() as u16).min(area.height);
+    let popup = Rect {
+        x: area.x + area.width.saturating_sub(width) / 2,
+        y: area.y + area.height.saturating_sub(height) / 2,
+        width,
+        height,
+    };
+    f.render_widget(Clear, popup);
+
+    let mut lines = vec![
+        Line::from(Span::styled(prompt.error.as_str(), Style::default().fg(Color::Red))),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("Admin API key: ", Style::default().fg(Color::Gray)),
+            Span::styled(prompt.masked(), Style::default().fg(Color::Yellow)),
+        ]),
+        Line::from(""),
+    ];
+    for retry in &prompt.retries {
+        lines.push(Line::from(Span::styled(
+            format!("↻ {}", retry.label()),
+            Style::default().fg(Color::Cyan),
+        )));
+    }
+    let hint = if prompt.retries.is_empty() {
+        "Enter: Save for this session | Esc: Cancel"
+    } else {
+        "Enter: Save and retry | Esc: Cancel"
+    };
+    lines.push(Line::from(Span::styled(hint, Style::default().fg(Color::DarkGray))));
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("API Key Required")
+        .border_style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD));
+    f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }).block(block), popup);
+
+    let cursor_x = popup.x + 1 + "Admin API key: ".len() as u16 + prompt.input.chars().count() as u16;
+    f.set_cursor_position((cursor_x.min(popup.right().saturating_sub(2)), popup.y + 2 + error_rows));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{render_with, HEIGHT, WIDTH};
+
+    #[test]
+    fn test_snapshot() {
+        let prompt = ApiKeyPrompt {
+            input: "secret".to_string(),
+            error: "401 Unauthorized: admin key required".to_string(),
+            retries: Vec::new(),
+        };
+        let text = render_with(WIDTH, HEIGHT, |f| render(f, &prompt, f.area()));
+        crate::assert_ui_snapshot!("api_key", text);
+    }
+}
@@ -0,0 +1,126 @@
+//! Audit Trail View - every prompt sent, from `.ims/audit.jsonl`
+
+use crate::app::AppState;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Row, Table},
+    Frame,
+};
+
+/// Leading characters of the prompt hash shown per row
+const HASH_CHARS: usize = 12;
+
+pub fn render(f: &mut Frame, state: &AppState, area: Rect) {
+    f.render_widget(Clear, area);
+    let view = &state.audit_view;
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Totals
+            Constraint::Min(0),    // Records
+            Constraint::Length(1), // Hints
+        ])
+        .split(area);
+
+    let bypassed = view.records.iter().filter(|r| r.bypass_policies).count();
+    let failed = view.records.iter().filter(|r| r.error.is_some()).count();
+    let cost: f64 = view.records.iter().map(|r| r.cost).sum();
+    let totals = Paragraph::new(Line::from(vec![
+        Span::styled(format!(" {} requests", view.records.len()), Style::default().fg(Color::White)),
+        Span::styled(format!(" | {} failed", failed), Style::default().fg(Color::Gray)),
+        Span::styled(
+            format!(" | {} policy bypass", bypassed),
+            if bypassed > 0 {
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::Gray)
+            },
+        ),
+        Span::styled(format!(" | ${:.4}", cost), Style::default().fg(Color::Yellow)),
+    ]))
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Audit Trail")
+            .border_style(Style::default().fg(Color::Cyan)),
+    );
+    f.render_widget(totals, chunks[0]);
+
+    let header = Row::new(["Time", "User", "Model", "Prompt SHA-256", "Policies", "Cost", "Result"])
+        .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD));
+    // Newest first
+    let rows = view.records.iter().rev().skip(view.scroll).map(|record| {
+        let style = if record.bypass_policies {
+            Style::default().fg(Color::Red)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        Row::new(vec![
+            record.time().format("%Y-%m-%d %H:%M:%S").to_string(),
+            record.user_id.clone(),
+            record.model_id.clone(),
+            record.prompt_sha256.chars().take(HASH_CHARS).collect(),
+            if record.bypass_policies { "BYPASSED" } else { "enforced" }.to_string(),
+            format!("${:.4}", record.cost),
+            record.error.clone().unwrap_or_else(|| "ok".to_string()),
+        ])
+        .style(style)
+    });
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(19),
+            Constraint::Length(14),
+            Constraint::Length(18),
+            Constraint::Length(14),
+            Constraint::Length(9),
+            Constraint::Length(9),
+            Constraint::Min(6),
+        ],
+    )
+    .header(header)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Requests ({})", view.records.len()))
+            .border_style(Style::default().fg(Color::DarkGray)),
+    );
+    f.render_widget(table, chunks[1]);
+
+    let hints = Paragraph::new(" ↑/↓: Scroll | Esc: Close").style(Style::default().fg(Color::DarkGray));
+    f.render_widget(hints, chunks[2]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::audit::AuditRecord;
+    use crate::testing::{render_with, StateBuilder, HEIGHT, WIDTH};
+
+    #[test]
+    fn test_snapshot() {
+        let record = |timestamp, model_id: &str, bypass_policies, cost, error: Option<&str>| AuditRecord {
+            timestamp,
+            user_id: "dana".to_string(),
+            model_id: model_id.to_string(),
+            prompt_sha256: "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08".to_string(),
+            bypass_policies,
+            cost,
+            error: error.map(str::to_string),
+        };
+        let state = StateBuilder::new()
+            .with(|s| {
+                s.audit_view.records = vec![
+                    record(1_768_478_400, "gpt-4o", false, 0.012, None),
+                    record(1_768_478_460, "claude-3-haiku", true, 0.001, None),
+                    record(1_768_478_520, "gpt-4o", false, 0.0, Some("timed out")),
+                ];
+            })
+            .build();
+        let text = render_with(WIDTH, HEIGHT, |f| render(f, &state, f.area()));
+        crate::assert_ui_snapshot!("audit", text);
+    }
+}
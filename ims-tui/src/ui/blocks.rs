@@ -0,0 +1,166 @@
+//! Blocks Popup - code blocks extracted from responses
+
+use crate::app::{blocks::BlocksMode, AppState};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+use similar::ChangeTag;
+
+pub fn render(f: &mut Frame, state: &AppState, area: Rect) {
+    let area = centered_rect(80, 70, area);
+    f.render_widget(Clear, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),    // List + preview
+            Constraint::Length(1), // Hints
+        ])
+        .split(area);
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(30), Constraint::Percentage(70)])
+        .split(chunks[0]);
+
+    render_list(f, state, columns[0]);
+
+    let view = &state.blocks_view;
+    let hints = match &view.mode {
+        BlocksMode::List => {
+            render_preview(f, state, columns[1]);
+            " ↑/↓: Select | c: Copy | s: Save as new file | i: Insert into open file | d: Diff | Esc: Close".to_string()
+        }
+        BlocksMode::InsertLine(input) => {
+            render_preview(f, state, columns[1]);
+            let path = state
+                .session
+                .as_ref()
+                .map(|s| s.file_path.display().to_string())
+                .unwrap_or_default();
+            format!(" Insert into {} at line: {}█ (empty = end of file) | Enter: Insert | Esc: Cancel", path, input)
+        }
+        BlocksMode::Diff(diff) => {
+            render_diff(f, diff, view.diff_scroll, columns[1]);
+            " ↑/↓: Scroll | Esc: Back".to_string()
+        }
+    };
+
+    f.render_widget(
+        Paragraph::new(hints).style(Style::default().fg(Color::DarkGray)),
+        chunks[1],
+    );
+}
+
+fn render_list(f: &mut Frame, state: &AppState, area: Rect) {
+    let items: Vec<ListItem> = state
+        .code_blocks
+        .iter()
+        .enumerate()
+        .map(|(i, block)| {
+            ListItem::new(Line::from(vec![
+                Span::raw(format!("{:>2}. ", i + 1)),
+                Span::styled(
+                    format!("[{}]", block.label()),
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::raw(format!(" {} lines", block.line_count())),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Blocks ({})", state.code_blocks.len()))
+                .border_style(Style::default().fg(Color::Cyan)),
+        )
+        .highlight_style(
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        );
+
+    let mut list_state = ListState::default();
+    list_state.select(Some(state.blocks_view.selected));
+    f.render_stateful_widget(list, area, &mut list_state);
+}
+
+fn render_preview(f: &mut Frame, state: &AppState, area: Rect) {
+    let Some(block) = state.code_blocks.get(state.blocks_view.selected) else { return };
+
+    let lines: Vec<Line> = block.code.lines().map(|l| Line::from(l.to_string())).collect();
+    let preview = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Preview ({})", block.label()))
+            .border_style(Style::default().fg(Color::Cyan)),
+    );
+    f.render_widget(preview, area);
+}
+
+fn render_diff(f: &mut Frame, diff: &[crate::app::blocks::DiffLine], scroll: u16, area: Rect) {
+    let lines: Vec<Line> = diff
+        .iter()
+        .map(|line| {
+            let (sign, style) = match line.tag {
+                ChangeTag::Insert => ("+", Style::default().fg(Color::Green)),
+                ChangeTag::Delete => ("-", Style::default().fg(Color::Red)),
+                ChangeTag::Equal => (" ", Style::default().fg(Color::Gray)),
+            };
+            Line::from(Span::styled(format!("{} {}", sign, line.text), style))
+        })
+        .collect();
+
+    let added = diff.iter().filter(|l| l.tag == ChangeTag::Insert).count();
+    let removed = diff.iter().filter(|l| l.tag == ChangeTag::Delete).count();
+
+    let paragraph = Paragraph::new(lines).scroll((scroll, 0)).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Diff vs open file (+{} -{})", added, removed))
+            .border_style(Style::default().fg(Color::Cyan)),
+    );
+    f.render_widget(paragraph, area);
+}
+
+fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
+    let popup_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(r);
+
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(popup_layout[1])[1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::overlay::Overlay;
+    use crate::testing::{render_with, StateBuilder, HEIGHT, WIDTH};
+
+    #[test]
+    fn test_snapshot() {
+        let mut state = StateBuilder::representative();
+        state.open_overlay(Overlay::Blocks);
+        let text = render_with(WIDTH, HEIGHT, |f| render(f, &state, f.area()));
+        crate::assert_ui_snapshot!("blocks", text);
+    }
+}
@@ -0,0 +1,100 @@
+//! Breadcrumb Bar - the session file's path above the center workspace,
+//! and the file list each crumb drops down
+
+use crate::app::{breadcrumb, AppState};
+use crate::ui::symbols;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+use unicode_width::UnicodeWidthStr;
+
+/// Column span of each crumb on a bar starting at `left`
+fn crumb_columns(state: &AppState, left: u16) -> Vec<(u16, u16)> {
+    let separator = format!(" {} ", symbols::symbols(state).separator).width() as u16;
+    let mut x = left + 1;
+    breadcrumb::crumbs(state)
+        .iter()
+        .map(|crumb| {
+            let span = (x, x + crumb.label.width() as u16);
+            x = span.1 + separator;
+            span
+        })
+        .collect()
+}
+
+/// Crumb under column `x` of the bar in `area`
+pub fn crumb_at(state: &AppState, area: Rect, x: u16) -> Option<usize> {
+    crumb_columns(state, area.x).iter().position(|(from, to)| (*from..*to).contains(&x))
+}
+
+pub fn render(f: &mut Frame, state: &AppState, area: Rect) {
+    let crumbs = breadcrumb::crumbs(state);
+    let separator = Span::styled(
+        format!(" {} ", symbols::symbols(state).separator),
+        Style::default().fg(Color::DarkGray),
+    );
+    let mut spans = vec![Span::raw(" ")];
+    for (i, crumb) in crumbs.iter().enumerate() {
+        if i > 0 {
+            spans.push(separator.clone());
+        }
+        let style = if i + 1 == crumbs.len() {
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Gray)
+        };
+        spans.push(Span::styled(crumb.label.clone(), style));
+    }
+    f.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+pub fn render_dropdown(f: &mut Frame, state: &AppState, screen: Rect) {
+    let Some(dropdown) = &state.file_dropdown else { return };
+    let area = dropdown.area(screen);
+    f.render_widget(Clear, area);
+
+    let current = state.session.as_ref().map(|s| s.file_path.to_string_lossy().into_owned());
+    let width = area.width.saturating_sub(2) as usize;
+    let lines: Vec<Line> = dropdown
+        .files
+        .iter()
+        .enumerate()
+        .map(|(i, (id, name))| {
+            let style = if i == dropdown.selected {
+                Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)
+            } else if Some(id) == current.as_ref() {
+                Style::default().fg(Color::Yellow)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            Line::from(Span::styled(format!(" {:<width$}", name), style))
+        })
+        .collect();
+
+    let popup = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan)),
+    );
+    f.render_widget(popup, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{render_app, StateBuilder, HEIGHT, WIDTH};
+
+    #[test]
+    fn test_snapshot() {
+        let mut state = StateBuilder::representative();
+        let bar = crate::ui::pane_layout(&state, Rect::new(0, 0, WIDTH, HEIGHT)).breadcrumb.unwrap();
+        let src = crumb_at(&state, bar, bar.x + 10).unwrap();
+        state.open_file_dropdown(src, bar.x + 10, bar.y + 1);
+        let text = render_app(&state, WIDTH, HEIGHT);
+        crate::assert_ui_snapshot!("breadcrumb", text);
+    }
+}
@@ -0,0 +1,65 @@
+//! Policy Bypass Prompt - admin key confirmation before arming a bypass
+
+use crate::app::bypass::BypassPrompt;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+pub fn render(f: &mut Frame, prompt: &BypassPrompt, area: Rect) {
+    let width = 64.min(area.width);
+    let height = 9.min(area.height);
+    let popup = Rect {
+        x: area.x + area.width.saturating_sub(width) / 2,
+        y: area.y + area.height.saturating_sub(height) / 2,
+        width,
+        height,
+    };
+    f.render_widget(Clear, popup);
+
+    let lines = vec![
+        Line::from("The next prompt will be sent with IMS Core's routing and"),
+        Line::from("safety policies skipped. Its use is logged as a policy bypass."),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("Admin API key: ", Style::default().fg(Color::Gray)),
+            Span::styled(prompt.masked(), Style::default().fg(Color::Yellow)),
+        ]),
+        Line::from(Span::styled(
+            prompt.error.clone().unwrap_or_default(),
+            Style::default().fg(Color::Red),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Enter: Bypass next request | Esc: Cancel",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Bypass Policies?")
+        .border_style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD));
+    f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }).block(block), popup);
+
+    let cursor_x = popup.x + 1 + "Admin API key: ".len() as u16 + prompt.input.chars().count() as u16;
+    f.set_cursor_position((cursor_x.min(popup.right().saturating_sub(2)), popup.y + 4));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{render_with, HEIGHT, WIDTH};
+
+    #[test]
+    fn test_snapshot() {
+        let prompt = BypassPrompt {
+            input: "guess".to_string(),
+            error: Some("Admin key does not match".to_string()),
+        };
+        let text = render_with(WIDTH, HEIGHT, |f| render(f, &prompt, f.area()));
+        crate::assert_ui_snapshot!("bypass", text);
+    }
+}
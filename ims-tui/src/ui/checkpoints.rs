@@ -0,0 +1,145 @@
+//! Checkpoints Modal and Session Tab Strip
+
+use crate::app::AppState;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Tabs},
+    Frame,
+};
+
+pub fn render_tab_bar(f: &mut Frame, state: &AppState, area: Rect) {
+    let titles: Vec<Line> = state
+        .session_tabs
+        .tabs
+        .iter()
+        .enumerate()
+        .map(|(i, tab)| Line::from(format!("{}: {}", i + 1, tab.title)))
+        .collect();
+
+    let tabs = Tabs::new(titles)
+        .select(state.session_tabs.active)
+        .style(Style::default().fg(Color::DarkGray))
+        .highlight_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+        .divider("│");
+    f.render_widget(tabs, area);
+}
+
+pub fn render(f: &mut Frame, state: &AppState, area: Rect) {
+    let area = centered_rect(60, 60, area);
+    f.render_widget(Clear, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),    // Checkpoints
+            Constraint::Length(3), // Name input
+            Constraint::Length(1), // Hints
+        ])
+        .split(area);
+
+    let items: Vec<ListItem> = state
+        .checkpoints
+        .iter()
+        .map(|checkpoint| {
+            ListItem::new(vec![
+                Line::from(vec![
+                    Span::styled(
+                        checkpoint.created.format("%H:%M:%S ").to_string(),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::styled(
+                        checkpoint.name.clone(),
+                        Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                    ),
+                ]),
+                Line::from(Span::styled(
+                    format!("         {}", checkpoint.summary()),
+                    Style::default().fg(Color::Gray),
+                )),
+            ])
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Checkpoints ({})", state.checkpoints.len()))
+                .border_style(Style::default().fg(Color::Cyan)),
+        )
+        .highlight_style(Style::default().bg(Color::DarkGray));
+    let mut list_state = ListState::default();
+    if !state.checkpoints.is_empty() {
+        list_state.select(Some(state.checkpoints_view.selected));
+    }
+    f.render_stateful_widget(list, chunks[0], &mut list_state);
+
+    let naming = state.checkpoints_view.naming.as_ref();
+    let input = Paragraph::new(naming.map(|i| i.as_str()).unwrap_or("Press n to save the current session"))
+        .style(Style::default().fg(if naming.is_some() { Color::White } else { Color::DarkGray }))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("New checkpoint name")
+                .border_style(Style::default().fg(if naming.is_some() { Color::Yellow } else { Color::DarkGray })),
+        );
+    f.render_widget(input, chunks[1]);
+    if let Some(input) = naming {
+        let x = (chunks[1].x + 1 + input.cursor_column()).min(chunks[1].right().saturating_sub(2));
+        f.set_cursor_position((x, chunks[1].y + 1));
+    }
+
+    let hints = if naming.is_some() {
+        " Enter: Save | Esc: Cancel"
+    } else {
+        " ↑/↓: Select | n: New | Enter: Open in new tab | d: Delete | Esc: Close"
+    };
+    f.render_widget(
+        Paragraph::new(hints).style(Style::default().fg(Color::DarkGray)),
+        chunks[2],
+    );
+}
+
+/// Helper to create a centered rect
+fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
+    let popup_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(r);
+
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(popup_layout[1])[1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{render_with, StateBuilder, HEIGHT, WIDTH};
+
+    #[test]
+    fn test_snapshot() {
+        let mut state = StateBuilder::representative();
+        state.create_checkpoint("Greeting");
+        state.create_checkpoint("");
+        let snapshot = crate::app::checkpoints::SessionSnapshot::capture(&state);
+        state.open_in_new_tab("Greeting".to_string(), snapshot).unwrap();
+        let text = render_with(WIDTH, HEIGHT, |f| {
+            let area = f.area();
+            render_tab_bar(f, &state, Rect { height: 1, ..area });
+            render(f, &state, area);
+        });
+        crate::assert_ui_snapshot!("checkpoints", text);
+    }
+}
@@ -0,0 +1,89 @@
+//! Sources View - the search results the latest response was given, each
+//! opened in the editor at its lines
+
+use crate::app::{citations, AppState};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState},
+    Frame,
+};
+
+pub fn render(f: &mut Frame, state: &AppState, area: Rect) {
+    let sources = state.latest_citations();
+    let width = 72.min(area.width);
+    let height = (sources.len() as u16 + 2).min(area.height);
+    let popup = Rect {
+        x: area.x + area.width.saturating_sub(width) / 2,
+        y: area.y + area.height.saturating_sub(height) / 2,
+        width,
+        height,
+    };
+    f.render_widget(Clear, popup);
+
+    let response = state
+        .conversation
+        .iter()
+        .rev()
+        .find(|t| t.response.is_some() && !t.citations.is_empty())
+        .and_then(|t| t.response.as_deref())
+        .unwrap_or_default();
+    let cited = citations::cited(response);
+    let items: Vec<ListItem> = sources
+        .iter()
+        .map(|citation| {
+            let mut spans = vec![Span::styled(citation.label(), Style::default().fg(Color::Blue))];
+            if cited.contains(&citation.number) {
+                spans.push(Span::styled("  cited", Style::default().fg(Color::Green)));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Sources")
+        .title_bottom(Span::styled(
+            " ↑/↓: Select | Enter: Open in $EDITOR | Esc: Close ",
+            Style::default().fg(Color::DarkGray),
+        ))
+        .border_style(Style::default().fg(Color::Cyan));
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD));
+    let mut list_state = ListState::default();
+    if !sources.is_empty() {
+        list_state.select(Some(state.citations_view.selected));
+    }
+    f.render_stateful_widget(list, popup, &mut list_state);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{citations::Citation, ConversationTurn};
+    use crate::testing::{render_with, StateBuilder, HEIGHT, WIDTH};
+
+    #[test]
+    fn test_snapshot() {
+        let citation = |number, path: &str, start_line| Citation {
+            number,
+            path: path.into(),
+            start_line,
+            end_line: start_line + 39,
+        };
+        let state = StateBuilder::new()
+            .with(|s| {
+                s.conversation.push(ConversationTurn {
+                    response: Some("In `Parser::parse` [2].".to_string()),
+                    citations: vec![citation(1, "src/lexer.rs", 1), citation(2, "src/parser.rs", 41)],
+                    ..ConversationTurn::new("Where are tokens parsed?", "gpt-4o")
+                });
+                s.citations_view.selected = 1;
+            })
+            .build();
+        let text = render_with(WIDTH, HEIGHT, |f| render(f, &state, f.area()));
+        crate::assert_ui_snapshot!("citations", text);
+    }
+}
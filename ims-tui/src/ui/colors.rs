@@ -0,0 +1,194 @@
+//! Color Depth
+//!
+//! Panes are styled for a 24-bit terminal. On one with 256 or 16 colors
+//! the finished frame is mapped to the nearest colors it has, and on 16
+//! colors the pairs that come out unreadable there (dark gray on black,
+//! the bright backgrounds the Linux console shows as dark ones) are
+//! swapped for legible ones.
+
+use ratatui::{buffer::Buffer, style::Color};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorDepth {
+    #[default]
+    TrueColor,
+    Ansi256,
+    Ansi16,
+}
+
+/// Color depth judged from `COLORTERM` and `TERM`
+pub fn detect(colorterm: Option<&str>, term: Option<&str>) -> ColorDepth {
+    if matches!(colorterm, Some("truecolor" | "24bit")) {
+        return ColorDepth::TrueColor;
+    }
+    match term {
+        Some(term) if term.ends_with("-direct") => ColorDepth::TrueColor,
+        Some(term) if term.contains("256color") => ColorDepth::Ansi256,
+        _ => ColorDepth::Ansi16,
+    }
+}
+
+/// `detect` for the current process environment
+pub fn detect_from_env() -> ColorDepth {
+    let colorterm = std::env::var("COLORTERM").ok();
+    let term = std::env::var("TERM").ok();
+    detect(colorterm.as_deref(), term.as_deref())
+}
+
+/// The 16 ANSI colors, in index order, with xterm's RGB for them
+const ANSI: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+/// Channel values of the 6×6×6 cube in the 256-color palette
+const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (i32::from(x) - i32::from(y)).pow(2) as u32;
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+/// RGB of a 256-color palette index
+fn indexed_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => ANSI[index as usize].1,
+        16..=231 => {
+            let i = index - 16;
+            (CUBE[(i / 36) as usize], CUBE[(i / 6 % 6) as usize], CUBE[(i % 6) as usize])
+        }
+        _ => {
+            let v = 8 + (index - 232) * 10;
+            (v, v, v)
+        }
+    }
+}
+
+/// Nearest 256-color index: the closer of the cube and the gray ramp
+fn nearest_256(rgb: (u8, u8, u8)) -> u8 {
+    let level = |v: u8| CUBE.iter().enumerate().min_by_key(|(_, c)| v.abs_diff(**c)).map_or(0, |(i, _)| i as u8);
+    let cube = 16 + 36 * level(rgb.0) + 6 * level(rgb.1) + level(rgb.2);
+    let mean = ((u16::from(rgb.0) + u16::from(rgb.1) + u16::from(rgb.2)) / 3) as u8;
+    let gray = 232 + (mean.saturating_sub(3) / 10).min(23);
+    if distance(rgb, indexed_rgb(gray)) < distance(rgb, indexed_rgb(cube)) {
+        gray
+    } else {
+        cube
+    }
+}
+
+/// Nearest ANSI color; a clearly colored value is kept off the grays,
+/// which plain distance favors for muted hues
+fn nearest_16(rgb: (u8, u8, u8)) -> Color {
+    let (max, min) = (rgb.0.max(rgb.1).max(rgb.2), rgb.0.min(rgb.1).min(rgb.2));
+    let chromatic = max - min > 64;
+    ANSI.iter()
+        .filter(|(color, _)| {
+            !chromatic || !matches!(color, Color::Black | Color::DarkGray | Color::Gray | Color::White)
+        })
+        .min_by_key(|(_, ansi)| distance(rgb, *ansi))
+        .map_or(Color::Reset, |(color, _)| *color)
+}
+
+/// `color` as a terminal of `depth` can show it
+fn fit_color(color: Color, depth: ColorDepth) -> Color {
+    match (depth, color) {
+        (ColorDepth::Ansi256, Color::Rgb(r, g, b)) => Color::Indexed(nearest_256((r, g, b))),
+        (ColorDepth::Ansi16, Color::Rgb(r, g, b)) => nearest_16((r, g, b)),
+        (ColorDepth::Ansi16, Color::Indexed(i)) if i < 16 => ANSI[i as usize].0,
+        (ColorDepth::Ansi16, Color::Indexed(i)) => nearest_16(indexed_rgb(i)),
+        _ => color,
+    }
+}
+
+/// A foreground and background a 16-color terminal shows legibly
+fn legible_16(fg: Color, bg: Color) -> (Color, Color) {
+    let bg = match bg {
+        Color::DarkGray => Color::Blue,
+        Color::LightRed => Color::Red,
+        Color::LightGreen => Color::Green,
+        Color::LightYellow => Color::Yellow,
+        Color::LightBlue => Color::Blue,
+        Color::LightMagenta => Color::Magenta,
+        Color::LightCyan => Color::Cyan,
+        Color::White => Color::Gray,
+        bg => bg,
+    };
+    let fg = match fg {
+        // Bright black is plain black in many 16-color themes
+        Color::DarkGray if bg != Color::Gray => Color::Gray,
+        fg if fg == bg && fg != Color::Reset => {
+            if matches!(bg, Color::Gray | Color::Yellow | Color::Cyan | Color::Green) {
+                Color::Black
+            } else {
+                Color::White
+            }
+        }
+        fg => fg,
+    };
+    (fg, bg)
+}
+
+/// Map every cell of a finished frame to colors the terminal has
+pub fn fit(buffer: &mut Buffer, depth: ColorDepth) {
+    if depth == ColorDepth::TrueColor {
+        return;
+    }
+    for cell in buffer.content.iter_mut() {
+        let (mut fg, mut bg) = (fit_color(cell.fg, depth), fit_color(cell.bg, depth));
+        if depth == ColorDepth::Ansi16 {
+            (fg, bg) = legible_16(fg, bg);
+        }
+        cell.fg = fg;
+        cell.bg = bg;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{layout::Rect, style::Style};
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(detect(Some("truecolor"), Some("xterm-256color")), ColorDepth::TrueColor);
+        assert_eq!(detect(None, Some("xterm-direct")), ColorDepth::TrueColor);
+        assert_eq!(detect(None, Some("tmux-256color")), ColorDepth::Ansi256);
+        assert_eq!(detect(None, Some("linux")), ColorDepth::Ansi16);
+        assert_eq!(detect(None, None), ColorDepth::Ansi16);
+    }
+
+    #[test]
+    fn test_fit_maps_to_nearest_and_keeps_text_legible() {
+        let orange = Color::Rgb(0xd9, 0x77, 0x57);
+        assert_eq!(fit_color(orange, ColorDepth::Ansi256), Color::Indexed(173));
+        assert_eq!(fit_color(Color::Rgb(0x80, 0x80, 0x80), ColorDepth::Ansi256), Color::Indexed(244));
+        assert_eq!(fit_color(orange, ColorDepth::Ansi16), Color::Yellow);
+        assert_eq!(fit_color(Color::Indexed(196), ColorDepth::Ansi16), Color::LightRed);
+
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 3, 1));
+        buffer.set_string(0, 0, "a", Style::default().fg(Color::DarkGray).bg(Color::Black));
+        buffer.set_string(1, 0, "b", Style::default().fg(Color::White).bg(Color::DarkGray));
+        buffer.set_string(2, 0, "c", Style::default().fg(Color::Blue).bg(Color::LightBlue));
+        fit(&mut buffer, ColorDepth::Ansi16);
+        let colors: Vec<_> = buffer.content.iter().map(|c| (c.fg, c.bg)).collect();
+        assert_eq!(
+            colors,
+            [(Color::Gray, Color::Black), (Color::White, Color::Blue), (Color::White, Color::Blue)]
+        );
+    }
+}
@@ -1,11 +1,13 @@
 use crate::app::AppState;
+use crate::core::commands::{self, Category, Section};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
     Frame,
 };
+use unicode_width::UnicodeWidthStr;
 
 pub fn render(f: &mut Frame, state: &AppState, area: Rect) {
     let area = centered_rect(60, 40, area);
@@ -19,46 +21,73 @@ pub fn render(f: &mut Frame, state: &AppState, area: Rect) {
         ])
         .split(area);
 
-    // Input Box
+    // Input Box, titled with the command while picking its argument
+    let title = match &state.command_argument {
+        Some(pick) => pick.title.as_str(),
+        None => "Command Palette",
+    };
     let input = Paragraph::new(state.command_input.as_str())
         .style(Style::default().fg(Color::Yellow))
-        .block(Block::default().borders(Borders::ALL).title("Command Palette"));
+        .block(Block::default().borders(Borders::ALL).title(title));
     f.render_widget(input, chunks[0]);
-    
-    // Commands List
-    // For now, hardcode list. Later, use a registry.
-    let commands = vec![
-        "File: New File",
-        "File: Open...",
-        "File: Save",
-        "View: Toggle Sidebar",
-        "View: Toggle Inspector",
-        "Agent: Reset Session",
-        "System: Quit",
-    ];
 
-    let filtered_commands: Vec<&str> = commands
-        .into_iter()
-        .filter(|cmd| cmd.to_lowercase().contains(&state.command_input.to_lowercase()))
-        .collect();
+    // Commands under a heading per section (or argument choices), each
+    // command with its keys and colored by its category
+    let width = chunks[1].width.saturating_sub(2) as usize;
+    let mut rows: Vec<ListItem> = Vec::new();
+    let mut entries = 0;
+    let mut selected = None;
+    let mut entry = |rows: &mut Vec<ListItem>, label: &str, hint: Option<String>, color: Color| {
+        let style = if entries == state.command_index {
+            selected = Some(rows.len());
+            Style::default().fg(Color::Black).bg(color).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        entries += 1;
+        let mut spans = vec![Span::styled(label.to_string(), style)];
+        if let Some(hint) = hint {
+            let gap = width.saturating_sub(label.width() + hint.width());
+            spans.push(Span::raw(" ".repeat(gap)));
+            spans.push(Span::styled(hint, Style::default().fg(Color::DarkGray)));
+        }
+        rows.push(ListItem::new(Line::from(spans)));
+    };
+    match &state.command_argument {
+        Some(pick) => {
+            for choice in pick.matching(&state.command_input) {
+                entry(&mut rows, &choice.label, None, Color::Cyan);
+            }
+        }
+        None => {
+            for (section, commands) in state.palette_sections() {
+                let (heading, color) = match section {
+                    Section::Recent => ("Recent", Color::Gray),
+                    Section::Category(category) => (category.label(), category_color(category)),
+                };
+                let heading = Span::styled(heading, Style::default().fg(color).add_modifier(Modifier::BOLD));
+                rows.push(ListItem::new(Line::from(heading)));
+                for cmd in commands {
+                    entry(&mut rows, cmd.title, commands::key_hint(cmd), category_color(cmd.category));
+                }
+            }
+        }
+    }
 
-    let items: Vec<ListItem> = filtered_commands
-        .iter()
-        .enumerate()
-        .map(|(i, cmd)| {
-            let style = if i == state.command_index {
-                Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)
-            } else {
-                Style::default().fg(Color::White)
-            };
-            ListItem::new(Line::from(vec![Span::styled(*cmd, style)]))
-        })
-        .collect();
+    let list = List::new(rows).block(Block::default().borders(Borders::ALL));
+    // Headings aren't selectable, so the selected row is counted past them
+    let mut list_state = ListState::default().with_selected(selected);
+    f.render_stateful_widget(list, chunks[1], &mut list_state);
+}
 
-    let list = List::new(items)
-        .block(Block::default().borders(Borders::ALL));
-    
-    f.render_widget(list, chunks[1]);
+fn category_color(category: Category) -> Color {
+    match category {
+        Category::File => Color::Cyan,
+        Category::View => Color::Blue,
+        Category::Agent => Color::Magenta,
+        Category::Workspace => Color::Green,
+        Category::System => Color::Yellow,
+    }
 }
 
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
@@ -80,3 +109,42 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
         ])
         .split(popup_layout[1])[1]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{render_with, StateBuilder, HEIGHT, WIDTH};
+
+    #[test]
+    fn test_snapshot() {
+        // Filtered so new commands don't churn the snapshot
+        let state = StateBuilder::new()
+            .with(|s| {
+                s.command_input = "pipeline".to_string();
+                s.command_index = 1;
+            })
+            .build();
+        let text = render_with(WIDTH, HEIGHT, |f| render(f, &state, f.area()));
+        crate::assert_ui_snapshot!("command_palette", text);
+    }
+
+    #[test]
+    fn test_snapshot_argument_pick() {
+        let state = StateBuilder::new()
+            .with(|s| {
+                let choice = |id: &str| crate::app::palette::Choice {
+                    label: id.to_string(),
+                    value: id.to_string(),
+                };
+                s.pick_argument(
+                    "model.switch",
+                    "Model: Switch To...".to_string(),
+                    vec![choice("gpt-4o (current)"), choice("claude-3-5-sonnet"), choice("gemini-1.5-pro")],
+                );
+                s.command_index = 1;
+            })
+            .build();
+        let text = render_with(WIDTH, HEIGHT, |f| render(f, &state, f.area()));
+        crate::assert_ui_snapshot!("command_palette_argument", text);
+    }
+}
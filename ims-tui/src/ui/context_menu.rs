@@ -0,0 +1,63 @@
+//! Context Menu Popup - anchored at the right-click position
+
+use crate::app::{context_menu::MenuAction, AppState};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+pub fn render(f: &mut Frame, state: &AppState, screen: Rect) {
+    let Some(menu) = &state.context_menu else { return };
+    let area = menu.area(screen);
+    f.render_widget(Clear, area);
+
+    let lines: Vec<Line> = menu
+        .actions()
+        .iter()
+        .enumerate()
+        .map(|(i, action)| {
+            let style = if i == menu.selected {
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD)
+            } else if *action == MenuAction::Delete {
+                Style::default().fg(Color::Red)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            let width = area.width.saturating_sub(2) as usize;
+            Line::from(Span::styled(format!(" {:<width$}", action.label()), style))
+        })
+        .collect();
+
+    let popup = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan)),
+    );
+    f.render_widget(popup, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::context_menu::{ContextMenu, MenuTarget};
+    use crate::testing::{render_app, StateBuilder, HEIGHT, WIDTH};
+
+    #[test]
+    fn test_snapshot() {
+        let state = StateBuilder::new()
+            .workspace()
+            .with(|s| {
+                let mut menu = ContextMenu::new(MenuTarget::File("src/main.rs".into()), 10, 3);
+                menu.selected = 2;
+                s.open_context_menu(menu);
+            })
+            .build();
+        let text = render_app(&state, WIDTH, HEIGHT);
+        crate::assert_ui_snapshot!("context_menu", text);
+    }
+}
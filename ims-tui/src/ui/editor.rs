@@ -2,13 +2,22 @@
 //!
 //! Implements the 50/50 split center workspace with smart scroll logic
 
-use crate::app::{AppState, FocusPane, InputMode};
-use crate::ui::focus_border_style;
+use crate::app::{
+    config::Density,
+    context, images,
+    prompt_queue::Priority,
+    regeneration::{LineChange, LineMark},
+    thinking::{Level, Source, ThinkingEntry},
+    tokens::format_token_count,
+    AppState, CostConfirm, FocusPane, InputMode,
+};
+use crate::ui::{focus_border_style, keywords, markdown, pane_hint, symbols, wrap};
+use std::collections::HashMap;
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Constraint, Direction, Layout, Margin, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph, Wrap},
+    widgets::{Block, Borders, Clear, Gauge, Paragraph, Wrap},
     Frame,
 };
 
@@ -21,35 +30,393 @@ pub fn render_thinking_pane(f: &mut Frame, state: &AppState, area: Rect) {
 
     let is_focused = state.focus == FocusPane::Thinking;
 
-    // Create header with vendor branding
+    // Create header with vendor branding, a bare row when compact
+    let header_height = match state.config.ui.density {
+        Density::Comfortable => 3,
+        Density::Compact => 1,
+    };
     let header_area = Rect {
         x: area.x,
         y: area.y,
         width: area.width,
-        height: 3,
+        height: header_height.min(area.height),
     };
 
-    let content_area = Rect {
+    let mut content_area = Rect {
         x: area.x,
-        y: area.y + 3,
+        y: area.y + header_area.height,
         width: area.width,
-        height: area.height.saturating_sub(3),
+        height: area.height.saturating_sub(header_area.height),
     };
 
+    // Prompts waiting for a free slot, below the log
+    if !state.prompt_queue.is_empty() {
+        let rows = state.prompt_queue.len().min(QUEUE_ROWS) as u16 + 2;
+        let split = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(3), Constraint::Length(rows)])
+            .split(content_area);
+        content_area = split[0];
+        render_prompt_queue(f, state, split[1]);
+    }
+
     // Render vendor header
-    render_vendor_header(f, session, header_area, is_focused);
+    let bypass = state
+        .bypass_armed
+        .then(|| format!("{} POLICY BYPASS", symbols::symbols(state).warning));
+    let badges = state
+        .session_model_info()
+        .map(crate::app::capabilities::badges)
+        .unwrap_or_default();
+    let warnings = state.capability_warnings(state.input_buffer.as_str());
+    let warning = (!warnings.is_empty())
+        .then(|| format!("{} {}", symbols::symbols(state).warning, warnings.join("; ")));
+    let border = match state.config.ui.density {
+        Density::Comfortable => Some(focus_border_style(state, is_focused)),
+        Density::Compact => None,
+    };
+    render_vendor_header(f, session, &badges, warning, bypass, header_area, border);
+
+    let mut title = "Agent Thinking".to_string();
+    if let Some(source) = state.thinking_filter {
+        title.push_str(&format!(" [{} only]", source.label()));
+    }
+    if !state.offline_queue.is_empty() {
+        title.push_str(&format!(
+            " ({} {} pending)",
+            symbols::symbols(state).pending,
+            state.offline_queue.len()
+        ));
+    }
 
     // Render thinking log
+    let rows = thinking_rows(state, content_area.width.saturating_sub(2));
     render_scrollable_content(
         f,
-        &state.thinking_log,
+        state,
+        rows,
         content_area,
         &session.thinking,
-        is_focused,
-        "Agent Thinking",
+        FocusPane::Thinking,
+        &title,
     );
 }
 
+/// Queued prompts listed under the Thinking log
+const QUEUE_ROWS: usize = 4;
+
+/// Waiting prompts in dispatch order with their place in line and ETA
+fn render_prompt_queue(f: &mut Frame, state: &AppState, area: Rect) {
+    let queue = &state.prompt_queue;
+    let mut lines: Vec<Line> = queue
+        .waiting()
+        .iter()
+        .enumerate()
+        .take(QUEUE_ROWS)
+        .map(|(i, waiting)| {
+            let eta = state
+                .queue_eta(i + 1)
+                .map(|eta| format!("~{}s", eta.as_secs().max(1)))
+                .unwrap_or_else(|| "ETA unknown".to_string());
+            let style = match waiting.priority {
+                Priority::High => Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                Priority::Normal => Style::default().fg(Color::Yellow),
+                Priority::Low => Style::default().fg(Color::DarkGray),
+            };
+            Line::from(vec![
+                Span::raw(format!("{}. ", i + 1)),
+                Span::styled(format!("[{}] ", waiting.priority.label()), style),
+                Span::raw(format!("{} ", waiting.request.model_id)),
+                Span::styled(eta, Style::default().fg(Color::Cyan)),
+                Span::styled(
+                    format!("  {}", waiting.request.prompt.lines().next().unwrap_or_default()),
+                    Style::default().fg(Color::Gray),
+                ),
+            ])
+        })
+        .collect();
+    if queue.len() > QUEUE_ROWS {
+        if let Some(last) = lines.last_mut() {
+            *last = Line::from(Span::styled(
+                format!("… and {} more", queue.len() - QUEUE_ROWS + 1),
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+    }
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(
+            "{} Queued ({}, {} running)",
+            symbols::symbols(state).pending,
+            queue.len(),
+            state.in_flight.len()
+        ))
+        .border_style(Style::default().fg(Color::DarkGray));
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Thinking log (under the source filter) as visual rows `width` columns wide
+pub fn thinking_rows(state: &AppState, width: u16) -> Vec<Line<'static>> {
+    let now = chrono::Local::now();
+    let lines: Vec<Line> = state
+        .visible_thinking()
+        .flat_map(|(_, entry)| {
+            let mut lines = thinking_entry_lines(state, entry, now);
+            // The prompt of the response selected in the Generation gutter
+            if state.highlighted_entry == Some(entry.id) {
+                for span in lines.iter_mut().flat_map(|l| l.spans.iter_mut()) {
+                    span.style = span.style.bg(Color::DarkGray);
+                }
+            }
+            lines
+        })
+        .collect();
+    wrap::wrap_lines(&lines, width)
+}
+
+/// Width of the "age source " prefix on each entry's first line
+const ENTRY_PREFIX_WIDTH: usize = 8;
+
+/// Width of the age column, left out when compact
+const AGE_WIDTH: usize = 4;
+
+/// One thinking entry, its first line prefixed with its age and source;
+/// tool calls are plain text with the header highlighted, and citations
+/// look like links
+fn thinking_entry_lines(
+    state: &AppState,
+    entry: &ThinkingEntry,
+    now: chrono::DateTime<chrono::Local>,
+) -> Vec<Line<'static>> {
+    let mut lines = if entry.tool_call.is_some() {
+        entry
+            .display_lines()
+            .into_iter()
+            .enumerate()
+            .map(|(i, line)| {
+                let style = if i == 0 {
+                    Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(Color::Gray)
+                };
+                Line::from(Span::styled(line, style))
+            })
+            .collect()
+    } else if entry.citation.is_some() {
+        // Clicked to open the source
+        vec![Line::from(Span::styled(
+            entry.text.clone(),
+            Style::default().fg(Color::Blue).add_modifier(Modifier::UNDERLINED),
+        ))]
+    } else if state.render_markdown {
+        markdown::render(&entry.text)
+    } else {
+        markdown::render_plain(&entry.text)
+    };
+
+    let level_color = match entry.level {
+        Level::Info => None,
+        Level::Warning => Some(Color::Yellow),
+        Level::Error => Some(Color::Red),
+    };
+    if let Some(color) = level_color {
+        for span in lines.iter_mut().flat_map(|l| l.spans.iter_mut()) {
+            span.style = span.style.fg(color);
+        }
+    }
+
+    if lines.is_empty() {
+        lines.push(Line::default());
+    }
+    let source_color = match entry.source {
+        Source::User => Color::Yellow,
+        Source::Agent => Color::Cyan,
+        Source::System => Color::DarkGray,
+    };
+    let compact = state.config.ui.density == Density::Compact;
+    for (i, line) in lines.iter_mut().enumerate() {
+        let prefix = if i == 0 {
+            let mut prefix = vec![Span::styled(
+                format!("{} ", entry.source.tag()),
+                Style::default().fg(source_color).add_modifier(Modifier::BOLD),
+            )];
+            if !compact {
+                prefix.insert(0, Span::styled(format!("{:>3} ", entry.age(now)), Style::default().fg(Color::DarkGray)));
+            }
+            prefix
+        } else if compact {
+            vec![Span::raw(" ".repeat(ENTRY_PREFIX_WIDTH - AGE_WIDTH))]
+        } else {
+            vec![Span::raw(" ".repeat(ENTRY_PREFIX_WIDTH))]
+        };
+        line.spans.splice(0..0, prefix);
+    }
+    lines
+}
+
+/// Thinking entry (index into the log) shown on visual row `row` at
+/// `width`, and whether that row is the entry's first
+pub fn thinking_entry_at(state: &AppState, width: u16, row: usize) -> Option<(usize, bool)> {
+    let now = chrono::Local::now();
+    let mut start = 0;
+    for (index, entry) in state.visible_thinking() {
+        let rows = wrap::wrap_lines(&thinking_entry_lines(state, entry, now), width).len();
+        if row < start + rows {
+            return Some((index, row == start));
+        }
+        start += rows;
+    }
+    None
+}
+
+/// First visual row of thinking entry `index` at `width`, if it is shown
+pub fn thinking_entry_row(state: &AppState, width: u16, index: usize) -> Option<usize> {
+    let now = chrono::Local::now();
+    let mut start = 0;
+    for (i, entry) in state.visible_thinking() {
+        if i == index {
+            return Some(start);
+        }
+        start += wrap::wrap_lines(&thinking_entry_lines(state, entry, now), width).len();
+    }
+    None
+}
+
+/// Generated code as visual rows `width` columns wide
+pub fn generation_rows(state: &AppState, width: u16) -> Vec<Line<'static>> {
+    let marks = if state.highlight_changes { state.line_marks() } else { HashMap::new() };
+    let lines: Vec<Line> = state
+        .generated_code
+        .lines()
+        .enumerate()
+        .map(|(i, line)| match marks.get(&i) {
+            Some(mark) => changed_line(line, mark),
+            None if state.config.ui.bold_keywords => keywords::emphasize(line),
+            None => Line::from(line),
+        })
+        .collect();
+    wrap::wrap_lines(&lines, width)
+}
+
+/// A line of a regeneration: green if the model added it, the rewritten
+/// tokens in yellow if it changed
+fn changed_line<'a>(line: &'a str, mark: &LineMark) -> Line<'a> {
+    let (before, after) = line.split_at(mark.offset.min(line.len()));
+    let mut spans = vec![Span::raw(before)];
+    match mark.change {
+        LineChange::Same => spans.push(Span::raw(after)),
+        LineChange::Added => spans.push(Span::styled(after, Style::default().fg(Color::Green))),
+        LineChange::Changed(tokens) => {
+            let token = Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD);
+            let mut at = 0;
+            for range in tokens.iter().filter(|r| after.get((*r).clone()).is_some()) {
+                spans.push(Span::raw(&after[at.min(range.start)..range.start]));
+                spans.push(Span::styled(&after[range.clone()], token));
+                at = at.max(range.end);
+            }
+            spans.push(Span::raw(&after[at..]));
+        }
+    }
+    Line::from(spans)
+}
+
+/// Turn number, change marker and bar marking which prompt produced each
+/// line
+const GUTTER_WIDTH: u16 = 5;
+
+/// Colors the gutter cycles through, one per response
+const REGION_COLORS: [Color; 3] = [Color::Cyan, Color::Magenta, Color::Green];
+
+/// Columns of the Generation pane left of the code for the gutter
+pub fn generation_gutter(state: &AppState) -> u16 {
+    if state.generation_regions.is_empty() {
+        0
+    } else {
+        GUTTER_WIDTH
+    }
+}
+
+/// Source line of each visual row of the generated code at `width`
+pub fn generation_row_lines(state: &AppState, width: u16) -> Vec<usize> {
+    state
+        .generated_code
+        .lines()
+        .enumerate()
+        .flat_map(|(i, line)| {
+            let rows = wrap::wrap_lines(&[Line::from(line)], width).len().max(1);
+            std::iter::repeat_n(i, rows)
+        })
+        .collect()
+}
+
+/// Gutter rows `offset..` : each response's turn number on its first row
+/// and a bar in its color beside the rest; the selected one reversed.
+/// Between them, a regeneration marks lines added (`+`), changed (`~`)
+/// and where lines were dropped (`-`)
+fn generation_gutter_lines(state: &AppState, width: u16, offset: usize, visible: usize) -> Vec<Line<'static>> {
+    let regions = state.region_lines();
+    let row_lines = generation_row_lines(state, width);
+    let marks = if state.highlight_changes { state.line_marks() } else { HashMap::new() };
+    (offset..row_lines.len().min(offset + visible))
+        .map(|row| {
+            let line = row_lines[row];
+            let Some(index) = regions.iter().rposition(|lines| lines.contains(&line)) else {
+                return Line::default();
+            };
+            let color = REGION_COLORS[index % REGION_COLORS.len()];
+            let first_row = row == 0 || row_lines[row - 1] != line;
+            let first = line == regions[index].start && first_row;
+            let label = if first {
+                format!("{:>3}", state.generation_regions[index].turn + 1)
+            } else {
+                "   ".to_string()
+            };
+            let mut style = Style::default().fg(color);
+            if state.selected_region == Some(index) {
+                style = style.add_modifier(Modifier::REVERSED | Modifier::BOLD);
+            }
+            let marker = match marks.get(&line).filter(|_| first_row) {
+                Some(LineMark { change: LineChange::Added, .. }) => Span::styled("+", Style::default().fg(Color::Green)),
+                Some(LineMark { change: LineChange::Changed(_), .. }) => Span::styled("~", Style::default().fg(Color::Yellow)),
+                Some(LineMark { removed: 1.., .. }) => Span::styled("-", Style::default().fg(Color::Red)),
+                _ => Span::raw(" "),
+            };
+            Line::from(vec![
+                Span::styled(label, style),
+                marker,
+                Span::styled("▏", Style::default().fg(color)),
+            ])
+        })
+        .collect()
+}
+
+/// Visual rows of a content pane, as laid out by the last render
+pub fn pane_rows(state: &AppState, pane: FocusPane, width: u16) -> Vec<Line<'static>> {
+    match pane {
+        FocusPane::Thinking => thinking_rows(state, width),
+        FocusPane::Generation => generation_rows(state, width),
+        _ => Vec::new(),
+    }
+}
+
+/// Reverse-video the part of row `index` covered by the mouse selection
+fn highlight_selection(state: &AppState, pane: FocusPane, index: usize, row: Line<'static>) -> Line<'static> {
+    match state
+        .selection
+        .as_ref()
+        .filter(|s| s.pane == pane)
+        .and_then(|s| s.columns(index))
+    {
+        Some((from, to)) => wrap::highlight_columns(
+            &row,
+            from,
+            to,
+            Style::default().add_modifier(Modifier::REVERSED),
+        ),
+        None => row,
+    }
+}
+
 /// Render generation pane (bottom half of center workspace)
 pub fn render_generation_pane(f: &mut Frame, state: &AppState, area: Rect) {
     let session = match &state.session {
@@ -59,70 +426,119 @@ pub fn render_generation_pane(f: &mut Frame, state: &AppState, area: Rect) {
 
     let is_focused = state.focus == FocusPane::Generation;
 
-    // Calculate scroll offset for auto-scroll
-    let content_lines: Vec<&str> = state.generated_code.lines().collect();
-    let visible_lines = area.height.saturating_sub(2) as usize; // Account for borders
-
-    let scroll_offset = if session.generation.auto_scroll {
-        // Auto-scroll: show last N lines
-        content_lines.len().saturating_sub(visible_lines)
-    } else {
-        // Manual scroll: use stored offset
-        session.generation.scroll_offset as usize
+    // Wrap to the width beside the gutter so scrolling counts visual rows
+    let inner = area.inner(Margin::new(1, 1));
+    let gutter = generation_gutter(state).min(inner.width);
+    let text_area = Rect {
+        x: inner.x + gutter,
+        width: inner.width - gutter,
+        ..inner
     };
+    let rows = generation_rows(state, text_area.width);
+    let visible_lines = text_area.height as usize;
+    let scroll_offset = session.generation.offset(rows.len(), visible_lines);
+    session.generation.area.set(text_area);
+    let total_rows = rows.len();
 
     // Add virtual cursor (vendor logo)
-    let mut display_lines: Vec<Line> = content_lines
-        .iter()
+    let mut display_lines: Vec<Line> = rows
+        .into_iter()
+        .enumerate()
         .skip(scroll_offset)
         .take(visible_lines)
-        .map(|&line| Line::from(line))
+        .map(|(i, row)| highlight_selection(state, FocusPane::Generation, i, row))
         .collect();
 
     // Append vendor logo as virtual cursor on last line
-    if !display_lines.is_empty() && session.generation.auto_scroll {
+    if state.vendor_cursor && !display_lines.is_empty() && session.generation.auto_scroll {
         let last_idx = display_lines.len() - 1;
-        let current_text = display_lines[last_idx].clone();
-        
-        let mut spans = current_text.spans;
-        spans.push(Span::styled(
-            format!(" {}", session.vendor_logo),
+        display_lines[last_idx].spans.push(Span::styled(
+            format!(" {}", session.vendor.glyph),
             Style::default()
-                .fg(Color::Cyan)
+                .fg(session.vendor.color)
                 .add_modifier(Modifier::BOLD | Modifier::SLOW_BLINK),
         ));
-        
-        display_lines[last_idx] = Line::from(spans);
     }
 
+    let sym = symbols::symbols(state);
     let scroll_indicator = if session.generation.auto_scroll {
-        "🔄 Auto-scroll"
+        format!("{} Auto-scroll", sym.auto_scroll)
     } else {
-        "📌 Manual"
+        format!("{} Manual", sym.manual_scroll)
     };
 
-    let title = format!(
+    let mut title = format!(
         "File Generation ({}/{} lines) [{}]",
-        scroll_offset + visible_lines.min(content_lines.len()),
-        content_lines.len(),
+        scroll_offset + visible_lines.min(total_rows),
+        total_rows,
         scroll_indicator
     );
+    if state.tee_active() {
+        title.push_str(" [tee]");
+    }
+    if let Some(changes) = state.latest_changes().filter(|_| state.highlight_changes) {
+        title.push_str(&format!(
+            " [+{} ~{} -{}]",
+            changes.added(),
+            changes.changed(),
+            changes.removed_total()
+        ));
+    }
 
-    let paragraph = Paragraph::new(display_lines)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title(title)
-                .border_style(focus_border_style(is_focused)),
-        )
-        .wrap(Wrap { trim: false });
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .title(pane_hint(state, FocusPane::Generation))
+        .border_style(focus_border_style(state, is_focused));
+    f.render_widget(block, area);
+    f.render_widget(Paragraph::new(display_lines), text_area);
+
+    if gutter > 0 {
+        let lines = generation_gutter_lines(state, text_area.width, scroll_offset, visible_lines);
+        f.render_widget(Paragraph::new(lines), Rect { width: gutter, ..inner });
+    }
+}
 
-    f.render_widget(paragraph, area);
+/// A chip per attached file along the prompt box's bottom border, images
+/// (sent as images rather than text) picked out
+fn attachment_chips(state: &AppState) -> Line<'static> {
+    let sym = symbols::symbols(state);
+    let mut spans = vec![Span::raw(" ")];
+    for path in &state.attachments {
+        let name = path.file_name().map_or_else(|| path.display().to_string(), |n| n.to_string_lossy().into_owned());
+        let (icon, color) = if images::is_image(path) { (sym.image, Color::Magenta) } else { (sym.attachment, Color::Blue) };
+        spans.push(Span::styled(
+            format!(" {} {} ", icon, name),
+            Style::default().fg(Color::Black).bg(color),
+        ));
+        spans.push(Span::raw(" "));
+    }
+    // Search results attached as context, by file and lines
+    for hit in &state.search_context {
+        let name = hit.path.file_name().map_or_else(|| hit.path.display().to_string(), |n| n.to_string_lossy().into_owned());
+        spans.push(Span::styled(
+            format!(" {} {}:{}-{} ", sym.attachment, name, hit.start_line, hit.end_line),
+            Style::default().fg(Color::Black).bg(Color::Green),
+        ));
+        spans.push(Span::raw(" "));
+    }
+    Line::from(spans)
 }
 
 /// Render prompt input box (bottom of center workspace)
 pub fn render_prompt_box(f: &mut Frame, state: &AppState, area: Rect) {
     let is_focused = state.focus == FocusPane::Prompt;
+
+    let budget = context::budget(state, state.input_buffer.as_str());
+    let (area, gauge_area) = if state.session.is_some() {
+        let split = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(0), Constraint::Length(26)])
+            .split(area);
+        (split[0], Some(split[1]))
+    } else {
+        (area, None)
+    };
     
     let border_style = if is_focused {
         match state.input_mode {
@@ -133,10 +549,26 @@ pub fn render_prompt_box(f: &mut Frame, state: &AppState, area: Rect) {
         Style::default().fg(Color::DarkGray)
     };
 
-    let title = match state.input_mode {
+    let mut title = match state.input_mode {
         InputMode::Normal => "Prompt (Press Enter to edit)",
         InputMode::Editing => "Prompt (Editing - Press Esc to stop)",
-    };
+    }
+    .to_string();
+    let attached = state.attachments.len() + state.search_context.len();
+    if attached > 0 {
+        title.push_str(&format!(" {} {}", symbols::symbols(state).attachment, attached));
+    }
+    if !state.api_connected {
+        title.push_str(" [Offline: prompts are queued]");
+    }
+    if state.next_priority != Priority::Normal {
+        title.push_str(&format!(" [{} priority]", state.next_priority.label()));
+    }
+    // Written in $EDITOR; the box shows it run together
+    let lines = state.input_buffer.as_str().lines().count();
+    if lines > 1 {
+        title.push_str(&format!(" [{} lines, Ctrl+E to edit]", lines));
+    }
 
     let input_text = if state.input_buffer.is_empty() && state.input_mode == InputMode::Normal {
         Span::styled(
@@ -144,56 +576,149 @@ pub fn render_prompt_box(f: &mut Frame, state: &AppState, area: Rect) {
             Style::default().fg(Color::Gray).add_modifier(Modifier::ITALIC),
         )
     } else {
-        Span::raw(&state.input_buffer)
+        Span::raw(state.input_buffer.as_str())
     };
 
+    let mut block = Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .title(pane_hint(state, FocusPane::Prompt))
+        .border_style(border_style);
+    if attached > 0 {
+        block = block.title_bottom(attachment_chips(state));
+    }
+    if let Some(advice) = budget.suggestion() {
+        block = block.title_bottom(Span::styled(
+            format!(" {} {} ", symbols::symbols(state).warning, advice),
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        ));
+    }
+
+    // Keep the cursor in view once the prompt outgrows the box
+    let inner_width = area.width.saturating_sub(2);
+    let cursor_column = state.input_buffer.cursor_column();
+    let h_scroll = cursor_column.saturating_sub(inner_width.saturating_sub(1));
+
     let paragraph = Paragraph::new(Line::from(input_text))
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title(title)
-                .border_style(border_style),
-        );
+        .block(block)
+        .scroll((0, h_scroll));
 
     f.render_widget(paragraph, area);
 
+    if let Some(gauge_area) = gauge_area {
+        let cost = context::estimated_cost(state, &budget);
+        let over = cost.zip(state.config.cost.confirm_above).is_some_and(|(cost, limit)| cost > limit);
+        render_context_gauge(f, &budget, cost.map(|cost| (cost, over)), gauge_area);
+    }
+
     // Render cursor if editing
     if state.input_mode == InputMode::Editing && is_focused {
         f.set_cursor_position((
-            area.x + state.input_buffer.len() as u16 + 1,
+            area.x + 1 + cursor_column - h_scroll,
             area.y + 1,
         ));
     }
 }
 
-/// Render vendor branding header
+/// Render estimated input tokens against the model's context window, with
+/// the request's estimated cost (and whether it needs confirming) below
+fn render_context_gauge(f: &mut Frame, budget: &context::ContextBudget, cost: Option<(f64, bool)>, area: Rect) {
+    let ratio = budget.ratio();
+    let color = if budget.overflow().is_some() {
+        Color::Red
+    } else if ratio > 0.8 {
+        Color::Yellow
+    } else {
+        Color::Green
+    };
+
+    let label = match budget.window {
+        Some(window) => format!(
+            "{} / {}",
+            format_token_count(budget.total() as u64),
+            format_token_count(window as u64)
+        ),
+        None => format!("{} / ?", format_token_count(budget.total() as u64)),
+    };
+
+    let mut block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("Context ({})", budget.tokenizer.label()))
+        .border_style(Style::default().fg(Color::DarkGray));
+    if let Some((cost, over)) = cost {
+        let style = if over {
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Gray)
+        };
+        block = block.title_bottom(Line::from(Span::styled(format!(" ≈ ${:.4} ", cost), style)).right_aligned());
+    }
+
+    let gauge = Gauge::default()
+        .block(block)
+        .gauge_style(Style::default().fg(color))
+        .ratio(ratio.min(1.0))
+        .label(label);
+
+    f.render_widget(gauge, area);
+}
+
+/// Confirmation popup for a prompt estimated over `[cost] confirm_above`
+pub fn render_cost_confirm(f: &mut Frame, confirm: &CostConfirm, area: Rect) {
+    let width = 56.min(area.width);
+    let popup = Rect {
+        x: area.x + area.width.saturating_sub(width) / 2,
+        y: area.y + area.height.saturating_sub(6) / 2,
+        width,
+        height: 6.min(area.height),
+    };
+    f.render_widget(Clear, popup);
+
+    let lines = vec![
+        Line::from(vec![
+            Span::raw("Estimated cost "),
+            Span::styled(
+                format!("${:.4}", confirm.estimate),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(format!(" is over ${:.4}.", confirm.threshold)),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled(
+            "y/Enter: Send | n/Esc: Back to editing",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Send Prompt?")
+        .border_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+    f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }).block(block), popup);
+}
+
+/// Render vendor branding header with the model's capability `badges`,
+/// `warning` about features the prompt needs that it lacks, and `bypass`
+/// as a warning badge while a policy bypass is armed; bordered with
+/// `border`, or a bare row without one
 fn render_vendor_header(
     f: &mut Frame,
     session: &crate::app::ActiveSession,
+    badges: &[crate::app::capabilities::Badge],
+    warning: Option<String>,
+    bypass: Option<String>,
     area: Rect,
-    is_focused: bool,
+    border: Option<Style>,
 ) {
-    let vendor_display = format!(
-        "{} {} | File: {}",
-        session.vendor_logo,
-        session.vendor_name,
-        session
-            .file_path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("unknown")
-    );
-
-    let header = Paragraph::new(Line::from(vec![
+    let mut spans = vec![
         Span::styled(
-            session.vendor_logo.clone(),
+            session.vendor.glyph.clone(),
             Style::default()
-                .fg(Color::Cyan)
+                .fg(session.vendor.color)
                 .add_modifier(Modifier::BOLD),
         ),
         Span::raw(" "),
         Span::styled(
-            &session.vendor_name,
+            &session.vendor.name,
             Style::default()
                 .fg(Color::White)
                 .add_modifier(Modifier::BOLD),
@@ -207,62 +732,83 @@ fn render_vendor_header(
                 .unwrap_or("unknown"),
             Style::default().fg(Color::Yellow),
         ),
-    ]))
-    .block(
-        Block::default()
-            .borders(Borders::ALL)
-            .border_style(focus_border_style(is_focused)),
-    );
+    ];
+    if !badges.is_empty() {
+        spans.push(Span::raw(" "));
+    }
+    for badge in badges {
+        spans.push(Span::raw(" "));
+        spans.push(Span::styled(
+            format!("[{}]", badge.label),
+            Style::default().fg(if badge.available { Color::Green } else { Color::DarkGray }),
+        ));
+    }
+    if let Some(warning) = warning {
+        spans.push(Span::styled(
+            format!("  {}", warning),
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        ));
+    }
+    if let Some(badge) = bypass {
+        spans.push(Span::raw("  "));
+        spans.push(Span::styled(
+            format!(" {} ", badge),
+            Style::default().fg(Color::White).bg(Color::Red).add_modifier(Modifier::BOLD),
+        ));
+    }
+    let mut header = Paragraph::new(Line::from(spans));
+    if let Some(style) = border {
+        header = header.block(Block::default().borders(Borders::ALL).border_style(style));
+    }
 
     f.render_widget(header, area);
 }
 
-/// Generic scrollable content renderer
+/// Generic scrollable content renderer for pre-wrapped rows
 fn render_scrollable_content(
     f: &mut Frame,
-    lines: &[String],
+    state: &AppState,
+    rows: Vec<Line<'static>>,
     area: Rect,
     scroll_state: &crate::app::ScrollState,
-    is_focused: bool,
+    pane: FocusPane,
     title: &str,
 ) {
+    let sym = symbols::symbols(state);
     let visible_lines = area.height.saturating_sub(2) as usize;
+    let scroll_offset = scroll_state.offset(rows.len(), visible_lines);
+    scroll_state.area.set(area.inner(Margin::new(1, 1)));
 
-    let scroll_offset = if scroll_state.auto_scroll {
-        lines.len().saturating_sub(visible_lines)
-    } else {
-        scroll_state.scroll_offset as usize
-    };
-
-    let display_lines: Vec<Line> = lines
-        .iter()
+    let total_lines = rows.len();
+    let display_lines: Vec<Line> = rows
+        .into_iter()
+        .enumerate()
         .skip(scroll_offset)
         .take(visible_lines)
-        .map(|line| Line::from(line.as_str()))
+        .map(|(i, row)| highlight_selection(state, pane, i, row))
         .collect();
 
     let scroll_indicator = if scroll_state.auto_scroll {
-        "🔄 Auto-scroll"
+        format!("{} Auto-scroll", sym.auto_scroll)
     } else {
-        "📌 Manual"
+        format!("{} Manual", sym.manual_scroll)
     };
 
     let full_title = format!(
         "{} ({}/{} lines) [{}]",
         title,
-        scroll_offset + visible_lines.min(lines.len()),
-        lines.len(),
+        scroll_offset + visible_lines.min(total_lines),
+        total_lines,
         scroll_indicator
     );
 
-    let paragraph = Paragraph::new(display_lines)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title(full_title)
-                .border_style(focus_border_style(is_focused)),
-        )
-        .wrap(Wrap { trim: false });
+    let paragraph = Paragraph::new(display_lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(full_title)
+            .title(pane_hint(state, pane))
+            .border_style(focus_border_style(state, state.focus == pane)),
+    );
 
     f.render_widget(paragraph, area);
 }
@@ -270,12 +816,18 @@ fn render_scrollable_content(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::app::{ActiveSession, ScrollState};
+    use crate::app::{
+        api::{ModelResponse, ToolCall},
+        thinking::ThinkingEntry,
+        vendors::VendorRegistry,
+        ActiveSession,
+    };
+    use crate::testing::{render_with, StateBuilder, HEIGHT, WIDTH};
     use std::path::PathBuf;
 
     #[test]
     fn test_scroll_calculation() {
-        let lines = vec![
+        let lines = [
             "Line 1".to_string(),
             "Line 2".to_string(),
             "Line 3".to_string(),
@@ -298,11 +850,132 @@ mod tests {
     fn test_vendor_header_display() {
         let session = ActiveSession::new(
             PathBuf::from("/test/file.rs"),
-            "Google Gemini".to_string(),
-            "◆".to_string(),
+            VendorRegistry::built_in().for_model("gemini-1.5-pro"),
+            "gemini-1.5-pro".to_string(),
+        );
+
+        assert_eq!(session.vendor.glyph, "◆");
+        assert_eq!(session.vendor.name, "Google Gemini");
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let state = StateBuilder::representative();
+        let text = render_with(80, 20, |f| {
+            let area = f.area();
+            let half = Rect { height: area.height / 2, ..area };
+            render_thinking_pane(f, &state, half);
+            render_generation_pane(f, &state, Rect { y: half.bottom(), ..half });
+        });
+        crate::assert_ui_snapshot!("editor_panes", text);
+
+        let text = render_with(80, 6, |f| render_prompt_box(f, &state, f.area()));
+        crate::assert_ui_snapshot!("editor_prompt", text);
+
+        let confirm = CostConfirm {
+            prompt: "Rewrite the whole module".to_string(),
+            estimate: 1.25,
+            threshold: 0.5,
+        };
+        let text = render_with(WIDTH, HEIGHT, |f| render_cost_confirm(f, &confirm, f.area()));
+        crate::assert_ui_snapshot!("editor_cost_confirm", text);
+
+        let mut state = StateBuilder::representative();
+        state.bypass_armed = true;
+        let text = render_with(80, 3, |f| render_thinking_pane(f, &state, f.area()));
+        crate::assert_ui_snapshot!("editor_bypass_header", text);
+
+        let mut state = StateBuilder::representative();
+        state.model_catalog.insert(
+            "gpt-4o".to_string(),
+            ModelResponse {
+                model_id: "gpt-4o".to_string(),
+                vendor_id: "OpenAI".to_string(),
+                capability_tier: "Tier_1".to_string(),
+                context_window: 128_000,
+                cost_in_per_mil: 2.5,
+                cost_out_per_mil: 10.0,
+                function_call_support: false,
+                is_active: true,
+            },
         );
+        state.add_thinking(ThinkingEntry::tool_call(ToolCall {
+            id: None,
+            name: "read_file".to_string(),
+            arguments: serde_json::json!({}),
+            result: None,
+        }));
+        let text = render_with(100, 3, |f| render_thinking_pane(f, &state, f.area()));
+        crate::assert_ui_snapshot!("editor_capabilities_header", text);
+
+        let mut state = StateBuilder::representative();
+        state.latency_history = vec![8000.0];
+        state.in_flight.push(crate::app::InFlightPrompt::new("Document the parser".to_string(), "gpt-4o".to_string()));
+        for (prompt, priority) in [("Add tests for lexer.rs", Priority::Low), ("Fix the build", Priority::High)] {
+            let request = crate::app::InFlightPrompt::new(prompt.to_string(), "gpt-4o".to_string());
+            state.prompt_queue.push(request, String::new(), priority);
+        }
+        state.next_priority = Priority::High;
+        let text = render_with(80, 14, |f| render_thinking_pane(f, &state, f.area()));
+        crate::assert_ui_snapshot!("editor_prompt_queue", text);
+        let text = render_with(80, 6, |f| render_prompt_box(f, &state, f.area()));
+        assert!(text.contains("[high priority]"), "{}", text);
+    }
+
+    #[test]
+    fn test_snapshot_attachment_chips() {
+        let mut state = StateBuilder::representative();
+        state.attachments = vec![PathBuf::from("/workspace/src/main.rs"), PathBuf::from("/workspace/docs/flow.png")];
+        let text = render_with(80, 3, |f| render_prompt_box(f, &state, f.area()));
+        crate::assert_ui_snapshot!("editor_attachment_chips", text);
+    }
+
+    #[test]
+    fn test_snapshot_annotations() {
+        let mut state = StateBuilder::representative();
+        state.generation_regions.push(crate::app::annotations::GenerationRegion {
+            bytes: 0..state.generated_code.len(),
+            turn: 0,
+            changes: None,
+        });
+        state.add_thinking(ThinkingEntry::user("Print the version too"));
+        state.conversation.push(crate::app::ConversationTurn {
+            response: Some(String::new()),
+            prompt_entry: state.thinking_log.last().map(|e| e.id),
+            ..crate::app::ConversationTurn::new("Print the version too", "gpt-4o")
+        });
+        state.append_generation_for(1, "println!(\"v{}\", VERSION);\n");
+        assert_eq!(state.select_region(0), Some(0));
+
+        let text = render_with(80, 24, |f| {
+            let area = f.area();
+            let half = Rect { height: area.height / 2, ..area };
+            render_thinking_pane(f, &state, half);
+            render_generation_pane(f, &state, Rect { y: half.bottom(), ..half });
+        });
+        crate::assert_ui_snapshot!("editor_annotations", text);
+    }
+
+    #[test]
+    fn test_snapshot_regeneration_changes() {
+        let mut state = StateBuilder::representative();
+        state.generated_code.clear();
+        for (turn, code) in [
+            "fn version() -> &'static str {\n    \"1.0\"\n}\n\nfn main() {\n    println!(\"{}\", version());\n    dbg!(version());\n}\n",
+            "fn version() -> &'static str {\n    \"1.1\"\n}\n\nfn main() {\n    println!(\"v{}\", version());\n}\n\n#[test]\nfn test_version() {}\n",
+        ]
+        .iter()
+        .enumerate()
+        {
+            state.conversation.push(crate::app::ConversationTurn {
+                response: Some(String::new()),
+                ..crate::app::ConversationTurn::new("Print the version", "gpt-4o")
+            });
+            state.append_generation_for(turn, code);
+        }
+        assert_eq!(state.generation_regions[0].changes, None);
 
-        assert_eq!(session.vendor_logo, "◆");
-        assert_eq!(session.vendor_name, "Google Gemini");
+        let text = render_with(80, 24, |f| render_generation_pane(f, &state, f.area()));
+        crate::assert_ui_snapshot!("editor_regeneration_changes", text);
     }
 }
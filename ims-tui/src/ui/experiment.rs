@@ -0,0 +1,129 @@
+//! Experiment Report - the two arms of an A/B test side by side
+
+use crate::app::{experiment::Arm, AppState};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::Span,
+    widgets::{Block, Borders, Clear, Paragraph, Row, Table},
+    Frame,
+};
+
+/// A metric of both arms, with the better one (if they can be compared)
+/// in green
+fn metric_row<T: PartialOrd>(
+    name: &'static str,
+    arms: &[Arm; 2],
+    value: impl Fn(&Arm) -> Option<T>,
+    label: impl Fn(T) -> String,
+    lower_is_better: bool,
+) -> Row<'static> {
+    let [a, b] = [value(&arms[0]), value(&arms[1])];
+    let better = match (&a, &b) {
+        (Some(a), Some(b)) if a != b => Some(usize::from((a > b) == lower_is_better)),
+        _ => None,
+    };
+    let cell = |i: usize, v: Option<T>| match v {
+        Some(v) if better == Some(i) => {
+            Span::styled(label(v), Style::default().fg(Color::Green).add_modifier(Modifier::BOLD))
+        }
+        Some(v) => Span::raw(label(v)),
+        None => Span::styled("-", Style::default().fg(Color::DarkGray)),
+    };
+    Row::new(vec![Span::raw(name), cell(0, a), cell(1, b)])
+}
+
+pub fn render(f: &mut Frame, state: &AppState, area: Rect) {
+    f.render_widget(Clear, area);
+    let Some(experiment) = &state.experiment else { return };
+    let arms = &experiment.arms;
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(area);
+
+    let header = Row::new(vec![
+        String::new(),
+        format!("A: {}", arms[0].model_id),
+        format!("B: {}", arms[1].model_id),
+    ])
+    .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD));
+    let rows = vec![
+        Row::new(vec![
+            Span::raw("Requests"),
+            Span::raw(format!("{} ({} done)", arms[0].sent, arms[0].completed)),
+            Span::raw(format!("{} ({} done)", arms[1].sent, arms[1].completed)),
+        ]),
+        metric_row("Failure rate", arms, Arm::failure_rate, |p| format!("{}%", p), true),
+        metric_row("Avg latency", arms, Arm::avg_latency_ms, |ms| format!("{:.0} ms", ms), true),
+        metric_row("Avg cost", arms, Arm::avg_cost, |c| format!("${:.4}", c), true),
+        metric_row("Total cost", arms, |a| Some(a.cost), |c| format!("${:.4}", c), true),
+        metric_row("Avg tokens", arms, Arm::avg_tokens, |t| t.to_string(), true),
+        metric_row(
+            "Satisfaction",
+            arms,
+            |a| a.satisfaction().map(|p| (p, a.positive + a.negative)),
+            |(p, rated)| format!("{}% 👍 of {}", p, rated),
+            false,
+        ),
+    ];
+    let widths = [Constraint::Length(14), Constraint::Min(20), Constraint::Min(20)];
+
+    let status = if experiment.running { "running" } else { "stopped" };
+    let title = format!(
+        "A/B Test ({}, {:.0}% of prompts to B)",
+        status,
+        experiment.share * 100.0
+    );
+    let table = Table::new(rows, widths).header(header).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_style(Style::default().fg(Color::Cyan)),
+    );
+    f.render_widget(table, chunks[0]);
+
+    let hints = Paragraph::new(" e: Export Markdown to .ims/reports | Esc: Close")
+        .style(Style::default().fg(Color::DarkGray));
+    f.render_widget(hints, chunks[1]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::experiment::Experiment;
+    use crate::testing::{render_with, StateBuilder, HEIGHT, WIDTH};
+
+    #[test]
+    fn test_snapshot() {
+        let mut experiment = Experiment::new("gpt-4o", "claude-3-haiku", 0.5);
+        experiment.running = false;
+        experiment.arms[0] = Arm {
+            sent: 6,
+            completed: 6,
+            measured: 5,
+            latency_ms: 11_000.0,
+            cost: 0.09,
+            tokens: 4_200,
+            positive: 3,
+            negative: 1,
+            ..experiment.arms[0].clone()
+        };
+        experiment.arms[1] = Arm {
+            sent: 6,
+            completed: 5,
+            failed: 1,
+            measured: 5,
+            latency_ms: 4_500.0,
+            cost: 0.006,
+            tokens: 3_900,
+            positive: 2,
+            negative: 2,
+            ..experiment.arms[1].clone()
+        };
+        let state = StateBuilder::new().with(|s| s.experiment = Some(experiment)).build();
+        let text = render_with(WIDTH, HEIGHT, |f| render(f, &state, f.area()));
+        crate::assert_ui_snapshot!("experiment", text);
+    }
+}
@@ -0,0 +1,80 @@
+//! Feedback Prompt - optional comment on a response rating
+
+use crate::app::feedback::FeedbackPrompt;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+pub fn render(f: &mut Frame, prompt: &FeedbackPrompt, area: Rect) {
+    let width = 64.min(area.width);
+    let height = 7.min(area.height);
+    let popup = Rect {
+        x: area.x + area.width.saturating_sub(width) / 2,
+        y: area.y + area.height.saturating_sub(height) / 2,
+        width,
+        height,
+    };
+    f.render_widget(Clear, popup);
+
+    let feedback = &prompt.feedback;
+    let (rating, color) = if feedback.positive {
+        ("👍 Helpful", Color::Green)
+    } else {
+        ("👎 Not helpful", Color::Red)
+    };
+    let lines = vec![
+        Line::from(vec![
+            Span::styled(rating, Style::default().fg(color).add_modifier(Modifier::BOLD)),
+            Span::styled(
+                format!("  response {} ({})", prompt.turn + 1, feedback.model_id),
+                Style::default().fg(Color::Gray),
+            ),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("Comment: ", Style::default().fg(Color::Gray)),
+            Span::styled(prompt.input.clone(), Style::default().fg(Color::Yellow)),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Enter: Save rating (comment optional) | Esc: Cancel",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Rate Response")
+        .border_style(Style::default().fg(color));
+    f.render_widget(Paragraph::new(lines).block(block), popup);
+
+    let cursor_x = popup.x + 1 + "Comment: ".len() as u16 + prompt.input.chars().count() as u16;
+    f.set_cursor_position((cursor_x.min(popup.right().saturating_sub(2)), popup.y + 3));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::feedback::Feedback;
+    use crate::testing::{render_with, HEIGHT, WIDTH};
+
+    #[test]
+    fn test_snapshot() {
+        let prompt = FeedbackPrompt {
+            feedback: Feedback {
+                timestamp: 0,
+                model_id: "gpt-4o".to_string(),
+                prompt: "write a parser".to_string(),
+                positive: false,
+                comment: None,
+            },
+            turn: 1,
+            input: "missed the edge cases".to_string(),
+        };
+        let text = render_with(WIDTH, HEIGHT, |f| render(f, &prompt, f.area()));
+        crate::assert_ui_snapshot!("feedback", text);
+    }
+}
@@ -0,0 +1,102 @@
+//! Health Detail - status of each IMS Core subsystem and when it last changed
+
+use crate::app::{health::HealthLevel, AppState};
+use chrono::Local;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+fn level_style(level: HealthLevel) -> (Color, &'static str) {
+    match level {
+        HealthLevel::Healthy => (Color::Green, "healthy"),
+        HealthLevel::Unknown => (Color::Gray, "unknown"),
+        HealthLevel::Degraded => (Color::Yellow, "degraded"),
+        HealthLevel::Down => (Color::Red, "down"),
+    }
+}
+
+pub fn render(f: &mut Frame, state: &AppState, area: Rect) {
+    let health = &state.health;
+    let height = (health.subsystems.len() as u16 + 6).min(area.height);
+    let width = 72.min(area.width);
+    let popup = Rect {
+        x: area.x + area.width.saturating_sub(width) / 2,
+        y: area.y + area.height.saturating_sub(height) / 2,
+        width,
+        height,
+    };
+    f.render_widget(Clear, popup);
+
+    let mut lines: Vec<Line> = health
+        .subsystems
+        .iter()
+        .map(|subsystem| {
+            let (color, label) = level_style(subsystem.level);
+            Line::from(vec![
+                Span::styled(format!("{:<10}", subsystem.name), Style::default().add_modifier(Modifier::BOLD)),
+                Span::styled(format!("● {:<9}", label), Style::default().fg(color)),
+                Span::styled(
+                    format!("since {} ", subsystem.since.format("%H:%M:%S")),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::raw(subsystem.status.clone()),
+            ])
+        })
+        .collect();
+
+    lines.push(Line::from(""));
+    if let Some(checked) = health.checked {
+        lines.push(Line::from(Span::styled(
+            format!(
+                "Last checked {} ({}s ago)",
+                checked.format("%H:%M:%S"),
+                (Local::now() - checked).num_seconds().max(0)
+            ),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    lines.push(Line::from(Span::styled(
+        "Esc: Close",
+        Style::default().fg(Color::DarkGray),
+    )));
+
+    let border = match health.subsystems.iter().map(|s| s.level).max() {
+        Some(level) => level_style(level).0,
+        None => Color::Cyan,
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("IMS Core Health")
+        .border_style(Style::default().fg(border).add_modifier(Modifier::BOLD));
+    f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }).block(block), popup);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::api::HealthResponse;
+    use crate::testing::{render_with, StateBuilder, HEIGHT, WIDTH};
+
+    #[test]
+    fn test_snapshot() {
+        let mut state = StateBuilder::new().build();
+        state.apply_health(&HealthResponse {
+            status: "healthy".to_string(),
+            database: "connected".to_string(),
+            cache: "connected".to_string(),
+            rabbitmq: Some("connected".to_string()),
+        });
+        state.apply_health(&HealthResponse {
+            status: "healthy".to_string(),
+            database: "degraded: replica lag".to_string(),
+            cache: "error: connection refused".to_string(),
+            rabbitmq: Some("connected".to_string()),
+        });
+        let text = render_with(WIDTH, HEIGHT, |f| render(f, &state, f.area()));
+        crate::assert_ui_snapshot!("health", text);
+    }
+}
@@ -0,0 +1,116 @@
+//! Help Overlay - full-screen keybinding reference built from the keymap
+
+use crate::app::{
+    keymap::{self, KeyContext},
+    AppState,
+};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+pub fn render(f: &mut Frame, state: &AppState, area: Rect) {
+    f.render_widget(Clear, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Search
+            Constraint::Min(0),    // Bindings
+            Constraint::Length(1), // Hints
+        ])
+        .split(area);
+
+    let search = Paragraph::new(Line::from(vec![
+        Span::styled(" / ", Style::default().fg(Color::Yellow)),
+        Span::raw(state.help_query.as_str()),
+    ]))
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Keybindings — type to search")
+            .border_style(Style::default().fg(Color::Cyan)),
+    );
+    f.render_widget(search, chunks[0]);
+    f.set_cursor_position((
+        (chunks[0].x + 4 + state.help_query.cursor_column()).min(chunks[0].right().saturating_sub(2)),
+        chunks[0].y + 1,
+    ));
+
+    let lines = binding_lines(state.help_query.as_str());
+    let visible = chunks[1].height.saturating_sub(2) as usize;
+    let scroll = state.help_scroll.min(lines.len().saturating_sub(visible));
+    let list = Paragraph::new(lines)
+        .scroll((scroll as u16, 0))
+        .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::DarkGray)));
+    f.render_widget(list, chunks[1]);
+
+    let hints = Paragraph::new(" ↑/↓/PgUp/PgDn: Scroll | Esc: Clear search / Close | ?: Close")
+        .style(Style::default().fg(Color::DarkGray));
+    f.render_widget(hints, chunks[2]);
+}
+
+/// Matching bindings grouped under a header per context
+fn binding_lines(query: &str) -> Vec<Line<'static>> {
+    let matches = keymap::search(query);
+    if matches.is_empty() {
+        return vec![Line::from(Span::styled(
+            format!(" No keybindings match \"{}\"", query),
+            Style::default().fg(Color::DarkGray),
+        ))];
+    }
+
+    let key_width = matches.iter().map(|b| b.keys_label().chars().count()).max().unwrap_or(0);
+    let mut lines = Vec::new();
+    for context in KeyContext::ALL {
+        let group: Vec<_> = matches.iter().filter(|b| b.context == context).collect();
+        if group.is_empty() {
+            continue;
+        }
+        if !lines.is_empty() {
+            lines.push(Line::default());
+        }
+        lines.push(Line::from(Span::styled(
+            format!(" {}", context.label()),
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+        )));
+        for binding in group {
+            let mut spans = vec![
+                Span::styled(
+                    format!("   {:<key_width$}  ", binding.keys_label()),
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::raw(binding.description),
+            ];
+            if let Some(pane) = binding.focus {
+                spans.push(Span::styled(
+                    format!("  ({:?} focused)", pane),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            lines.push(Line::from(spans));
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{render_with, StateBuilder, HEIGHT, WIDTH};
+
+    #[test]
+    fn test_snapshot() {
+        let state = StateBuilder::new().build();
+        let text = render_with(WIDTH, HEIGHT, |f| render(f, &state, f.area()));
+        crate::assert_ui_snapshot!("help", text);
+
+        let mut searching = StateBuilder::new().build();
+        searching.help_query.insert_str("palette");
+        let text = render_with(WIDTH, HEIGHT, |f| render(f, &searching, f.area()));
+        crate::assert_ui_snapshot!("help_search", text);
+    }
+}
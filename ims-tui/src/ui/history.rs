@@ -0,0 +1,163 @@
+//! History View - generations saved under `.ims/generations`, with replays
+//! shown beside their original
+
+use crate::app::{history::GenerationRecord, AppState};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+
+pub fn render(f: &mut Frame, state: &AppState, area: Rect) {
+    f.render_widget(Clear, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),    // List + preview
+            Constraint::Length(1), // Hints
+        ])
+        .split(area);
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+        .split(chunks[0]);
+
+    render_list(f, state, columns[0]);
+    render_preview(f, state, columns[1]);
+
+    let hints = " ↑/↓: Select | Enter: Reopen in new tab | r: Retry | m: Retry with… | Esc: Close";
+    f.render_widget(
+        Paragraph::new(hints).style(Style::default().fg(Color::DarkGray)),
+        chunks[1],
+    );
+}
+
+fn render_list(f: &mut Frame, state: &AppState, area: Rect) {
+    let view = &state.history_view;
+    let items: Vec<ListItem> = view
+        .records
+        .iter()
+        .map(|record| {
+            let mut header = vec![
+                Span::styled(
+                    record.created.format("%Y-%m-%d %H:%M ").to_string(),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(record.model_id.clone(), Style::default().fg(Color::Yellow)),
+            ];
+            if record.replay_of.is_some() {
+                header.push(Span::styled(" ↻ replay", Style::default().fg(Color::Magenta)));
+            }
+            ListItem::new(vec![
+                Line::from(header),
+                Line::from(Span::raw(format!("  {}", record.prompt.lines().next().unwrap_or("")))),
+            ])
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Generations ({})", view.records.len()))
+                .border_style(Style::default().fg(Color::Cyan)),
+        )
+        .highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD));
+    let mut list_state = ListState::default();
+    if !view.records.is_empty() {
+        list_state.select(Some(view.selected));
+    }
+    f.render_stateful_widget(list, area, &mut list_state);
+}
+
+fn render_preview(f: &mut Frame, state: &AppState, area: Rect) {
+    let view = &state.history_view;
+    let Some(record) = view.selected_record() else {
+        let empty = Paragraph::new("No saved generations yet. Completed responses are saved to .ims/generations.")
+            .style(Style::default().fg(Color::DarkGray))
+            .wrap(Wrap { trim: true })
+            .block(preview_block("Preview"));
+        f.render_widget(empty, area);
+        return;
+    };
+
+    // Replays and their original are compared side by side, original on the left
+    let (left, right) = match view.counterpart(record) {
+        Some(other) if record.replay_of.is_some() => (other, Some(record)),
+        Some(other) => (record, Some(other)),
+        None => (record, None),
+    };
+    let Some(right) = right else {
+        render_record(f, left, "Preview", area);
+        return;
+    };
+    let halves = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+    render_record(f, left, "Original", halves[0]);
+    render_record(f, right, "Replay", halves[1]);
+}
+
+fn preview_block(title: &str) -> Block<'_> {
+    Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .border_style(Style::default().fg(Color::DarkGray))
+}
+
+fn render_record(f: &mut Frame, record: &GenerationRecord, title: &str, area: Rect) {
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("Prompt: ", Style::default().fg(Color::Yellow)),
+            Span::raw(record.prompt.clone()),
+        ]),
+        Line::from(Span::styled(
+            format!(
+                "{} | {} tokens | ${:.6} | {:.0}ms | {}",
+                record.model_id,
+                record.tokens,
+                record.cost,
+                record.latency_ms,
+                record.path.display()
+            ),
+            Style::default().fg(Color::DarkGray),
+        )),
+        Line::default(),
+    ];
+    lines.extend(record.content.lines().map(|line| Line::from(line.to_string())));
+
+    let preview = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(preview_block(title));
+    f.render_widget(preview, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{render_with, StateBuilder, HEIGHT, WIDTH};
+
+    #[test]
+    fn test_snapshot() {
+        let first = GenerationRecord {
+            path: std::path::PathBuf::from(".ims/generations/first.json"),
+            replay_of: None,
+            ..GenerationRecord::from_response("Add a greeting", &crate::testing::response("gpt-4o", "fn main() {}"))
+        };
+        let replay = GenerationRecord {
+            path: std::path::PathBuf::from(".ims/generations/replay.json"),
+            replay_of: Some("first.json".to_string()),
+            ..GenerationRecord::from_response("Add a greeting", &crate::testing::response("claude-3-haiku", "fn main() {}"))
+        };
+        let state = StateBuilder::new()
+            .with(|s| s.history_view.records = vec![first, replay])
+            .build();
+        let text = render_with(WIDTH, HEIGHT, |f| render(f, &state, f.area()));
+        crate::assert_ui_snapshot!("history", text);
+    }
+}
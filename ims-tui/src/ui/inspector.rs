@@ -1,44 +1,66 @@
 //! Inspector Panel - Metrics & Stats
 
-use crate::app::{AppState, FocusPane};
-use crate::ui::focus_border_style;
+use crate::app::{
+    batch::ItemStatus, idle::format_focus, pipeline::StepStatus, tokens::format_token_count, AgentStatus, AppState, FocusPane,
+};
+use crate::ui::{focus_border_style, pane_hint, symbols};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Gauge, List, ListItem, Paragraph},
+    widgets::{Block, Borders, Gauge, List, ListItem, Paragraph, Sparkline},
     Frame,
 };
 
 pub fn render(f: &mut Frame, state: &AppState, area: Rect) {
     let is_focused = state.focus == FocusPane::Inspector;
 
+    // Prometheus charts take 3 rows per configured series
+    let series_height = if state.config.prometheus.enabled {
+        state.config.prometheus.series.len() as u16 * 3 + 2
+    } else {
+        0
+    };
+    // Batch and pipeline progress, while there is a run to show
+    let agents = state.batch.as_ref().map_or(0, |b| b.items.len())
+        + state.pipeline_view.run.as_ref().map_or(0, |r| r.targets.len());
+    let agents_height = if agents > 0 { (agents as u16 + 2).min(8) } else { 0 };
+
     // Split inspector into sections
     let sections = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(6),  // Session info
-            Constraint::Length(8),  // Metrics
-            Constraint::Length(6),  // Active models
-            Constraint::Min(0),     // Debug logs
+            Constraint::Length(7),             // Session info
+            Constraint::Length(11),            // Metrics
+            Constraint::Length(series_height), // Prometheus series
+            Constraint::Length(agents_height), // Agents
+            Constraint::Length(6),             // Active models
+            Constraint::Min(0),                // Debug logs
         ])
         .split(area);
 
     render_session_info(f, state, sections[0], is_focused);
     render_metrics(f, state, sections[1], is_focused);
-    render_active_models(f, state, sections[2], is_focused);
-    render_debug_logs(f, state, sections[3], is_focused);
+    if series_height > 0 {
+        render_series(f, state, sections[2], is_focused);
+    }
+    if agents_height > 0 {
+        render_agents(f, state, sections[3], is_focused);
+    }
+    render_active_models(f, state, sections[4], is_focused);
+    render_debug_logs(f, state, sections[5], is_focused);
 }
 
 /// Session information
 fn render_session_info(f: &mut Frame, state: &AppState, area: Rect, is_focused: bool) {
+    let sym = symbols::symbols(state);
     let info = if let Some(session) = &state.session {
         vec![
             Line::from(vec![
                 Span::raw("Vendor: "),
                 Span::styled(
-                    format!("{} {}", session.vendor_logo, session.vendor_name),
-                    Style::default().fg(Color::Cyan),
+                    format!("{} {}", session.vendor.glyph, session.vendor.name),
+                    Style::default().fg(session.vendor.color),
                 ),
             ]),
             Line::from(vec![
@@ -52,13 +74,35 @@ fn render_session_info(f: &mut Frame, state: &AppState, area: Rect, is_focused:
                     Style::default().fg(Color::Yellow),
                 ),
             ]),
+            Line::from(vec![
+                Span::raw("File Tokens: "),
+                Span::styled(
+                    match state.session_file_tokens() {
+                        Some(tokens) if tokens > 0 => format!("~{}", format_token_count(tokens as u64)),
+                        _ => "n/a".to_string(),
+                    },
+                    Style::default().fg(Color::Magenta),
+                ),
+            ]),
+            Line::from(vec![
+                Span::raw("Focus: "),
+                Span::styled(format_focus(session.focus_time), Style::default().fg(Color::Cyan)),
+                Span::styled(
+                    format!(
+                        " · idle {}{}",
+                        format_focus(session.idle_time),
+                        if state.activity.is_idle() { " (now)" } else { "" }
+                    ),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]),
             Line::from(vec![
                 Span::raw("Status: "),
                 Span::styled(
                     if state.api_connected {
-                        "🟢 Connected"
+                        format!("{} Connected", sym.connected)
                     } else {
-                        "🔴 Disconnected"
+                        format!("{} Disconnected", sym.disconnected)
                     },
                     Style::default().fg(if state.api_connected {
                         Color::Green
@@ -86,7 +130,8 @@ fn render_session_info(f: &mut Frame, state: &AppState, area: Rect, is_focused:
         Block::default()
             .borders(Borders::ALL)
             .title("Session")
-            .border_style(focus_border_style(is_focused)),
+            .title(pane_hint(state, FocusPane::Inspector))
+            .border_style(focus_border_style(state, is_focused)),
     );
 
     f.render_widget(paragraph, area);
@@ -94,14 +139,23 @@ fn render_session_info(f: &mut Frame, state: &AppState, area: Rect, is_focused:
 
 /// Metrics panel
 fn render_metrics(f: &mut Frame, state: &AppState, area: Rect, is_focused: bool) {
+    let metrics_block = Block::default()
+        .borders(Borders::ALL)
+        .title("Metrics")
+        .border_style(focus_border_style(state, is_focused));
+
     let metrics_layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(2), // Tokens
-            Constraint::Length(2), // Cost
-            Constraint::Length(2), // Requests
+            Constraint::Length(1), // Cost
+            Constraint::Length(1), // Requests
+            Constraint::Length(1), // IMS Core counters
+            Constraint::Length(1), // Workspace
+            Constraint::Length(1), // Latency
+            Constraint::Length(2), // Latency chart
         ])
-        .split(area);
+        .split(metrics_block.inner(area));
 
     // Token usage gauge
     let token_percent = ((state.total_tokens_used as f64 / 1_000_000.0) * 100.0).min(100.0);
@@ -128,30 +182,211 @@ fn render_metrics(f: &mut Frame, state: &AppState, area: Rect, is_focused: bool)
         }));
 
     // Request count
-    let req_text = format!(
-        "Requests: {} (Limit: 1500/day)",
-        state.request_count
-    );
+    let req_text = format!("Requests: {} today", state.request_count);
     let req_para = Paragraph::new(req_text)
         .block(Block::default())
         .style(Style::default().fg(Color::Yellow));
 
-    let metrics_block = Block::default()
-        .borders(Borders::ALL)
-        .title("Metrics")
-        .border_style(focus_border_style(is_focused));
+    // IMS Core's counters from the last /metrics poll, with changes since the one before
+    let backend = &state.backend_metrics;
+    let counters = [
+        ("models", backend.counter(|m| m.total_models_registered)),
+        ("queries", backend.counter(|m| m.total_model_queries)),
+        ("filters", backend.counter(|m| m.total_filter_queries)),
+    ];
+    let mut backend_spans = vec![Span::raw("IMS:")];
+    for (label, value) in counters {
+        let Some((value, delta)) = value else { continue };
+        backend_spans.push(Span::raw(format!(" {} {}", value, label)));
+        if delta != 0 {
+            backend_spans.push(Span::styled(
+                format!(" ({:+})", delta),
+                Style::default().fg(if delta > 0 { Color::Green } else { Color::Red }),
+            ));
+        }
+    }
+    if backend_spans.len() == 1 {
+        let waiting = match &state.read_only {
+            Some(mode) if mode.metrics_unavailable() => format!(" unavailable, read-only ({})", mode.reason()),
+            _ => " waiting for metrics".to_string(),
+        };
+        backend_spans.push(Span::styled(waiting, Style::default().fg(Color::DarkGray)));
+    }
+    let backend_para = Paragraph::new(Line::from(backend_spans));
+
+    // Estimated size of the workspace if sent as context
+    let workspace_text = format!(
+        "Workspace: ~{} tokens",
+        format_token_count(state.workspace_tokens())
+    );
+    let workspace_para = Paragraph::new(workspace_text)
+        .style(Style::default().fg(Color::Magenta));
+
+    // Recent request latencies from the metrics store
+    let latency_text = match state.latency_history.last() {
+        Some(latest) => format!(
+            "Latency: {:.0}ms (last {})",
+            latest,
+            state.latency_history.len()
+        ),
+        None => "Latency: no requests yet".to_string(),
+    };
+    let mut latency_spans = vec![Span::styled(latency_text, Style::default().fg(Color::Cyan))];
+    let slow = state.slow_request_count();
+    if slow > 0 {
+        latency_spans.push(Span::styled(
+            format!(" ⏱ {} slow", slow),
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        ));
+    }
+    let latency_para = Paragraph::new(Line::from(latency_spans));
+    let skip = state
+        .latency_history
+        .len()
+        .saturating_sub(metrics_layout[6].width as usize);
+    let latencies: Vec<u64> = state
+        .latency_history
+        .iter()
+        .skip(skip)
+        .map(|ms| ms.round() as u64)
+        .collect();
+    let latency_chart = Sparkline::default()
+        .data(&latencies)
+        .style(Style::default().fg(Color::Cyan));
 
     f.render_widget(metrics_block, area);
     f.render_widget(token_gauge, metrics_layout[0]);
     f.render_widget(cost_para, metrics_layout[1]);
     f.render_widget(req_para, metrics_layout[2]);
+    f.render_widget(backend_para, metrics_layout[3]);
+    f.render_widget(workspace_para, metrics_layout[4]);
+    f.render_widget(latency_para, metrics_layout[5]);
+    f.render_widget(latency_chart, metrics_layout[6]);
 }
 
-/// Active models list
+/// Prometheus-derived series as sparklines
+fn render_series(f: &mut Frame, state: &AppState, area: Rect, is_focused: bool) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Series")
+        .border_style(focus_border_style(state, is_focused));
+
+    let mappings = &state.config.prometheus.series;
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(mappings.iter().map(|_| Constraint::Length(3)))
+        .split(block.inner(area));
+
+    f.render_widget(block, area);
+
+    for (mapping, row) in mappings.iter().zip(rows.iter()) {
+        let series = state.metric_series.iter().find(|s| s.label == mapping.label);
+
+        let parts = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Length(2)])
+            .split(*row);
+
+        let latest = match series.and_then(|s| s.latest()) {
+            Some(value) => format!("{:.2} {}", value, mapping.unit),
+            None => "waiting for data".to_string(),
+        };
+        let header = Paragraph::new(Line::from(vec![
+            Span::raw(format!("{}: ", mapping.label)),
+            Span::styled(latest, Style::default().fg(Color::Yellow)),
+        ]));
+        f.render_widget(header, parts[0]);
+
+        // Sparkline wants integers; scale so small rates still show variation
+        let points: Vec<u64> = series
+            .map(|s| {
+                let max = s.points.iter().copied().fold(0.0_f64, f64::max);
+                let factor = if max > 0.0 { 100.0 / max } else { 0.0 };
+                let skip = s.points.len().saturating_sub(parts[1].width as usize);
+                s.points.iter().skip(skip).map(|v| (v * factor).round() as u64).collect()
+            })
+            .unwrap_or_default();
+        let sparkline = Sparkline::default()
+            .data(&points)
+            .max(100)
+            .style(Style::default().fg(Color::Cyan));
+        f.render_widget(sparkline, parts[1]);
+    }
+}
+
+/// Per-file progress of the batch run and per-target progress of the
+/// pipeline, unfinished work first
+fn render_agents(f: &mut Frame, state: &AppState, area: Rect, is_focused: bool) {
+    let (mut done, mut total) = (0, 0);
+    // (sort key, line)
+    let mut rows: Vec<(u8, Line)> = Vec::new();
+
+    if let Some(batch) = &state.batch {
+        done += batch.finished();
+        total += batch.items.len();
+        for item in &batch.items {
+            let name = item
+                .path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| item.path.display().to_string());
+            let (order, glyph, detail, color) = match &item.status {
+                ItemStatus::Running => (0, "▶", format!(" {}", item.model_id), Color::Yellow),
+                ItemStatus::Pending => (2, "·", String::new(), Color::DarkGray),
+                ItemStatus::Failed(_) => (3, "✗", " failed".to_string(), Color::Red),
+                ItemStatus::Done { tokens, .. } => {
+                    (4, "✓", format!(" {} tok", format_token_count(u64::from(*tokens))), Color::Green)
+                }
+            };
+            rows.push((order, agent_line(glyph, name, detail, color)));
+        }
+    }
+
+    if let Some(run) = &state.pipeline_view.run {
+        let paused = run.pause.is_paused();
+        done += run.targets.iter().filter(|t| !t.is_running()).count();
+        total += run.targets.len();
+        for target in &run.targets {
+            let name = target.target.label.clone();
+            let next = target
+                .steps
+                .iter()
+                .position(|s| *s != StepStatus::Done)
+                .map_or("", |i| run.steps[i].as_str());
+            let (order, glyph, detail, color) = match target.agent_status(paused) {
+                AgentStatus::Error => (3, "✗", " failed".to_string(), Color::Red),
+                AgentStatus::Paused => (1, "⏸", format!(" paused before {}", next), Color::Yellow),
+                AgentStatus::Working => (0, "▶", format!(" {}", next), Color::Yellow),
+                AgentStatus::Idle if target.is_running() => (2, "·", String::new(), Color::DarkGray),
+                AgentStatus::Idle => (4, "✓", String::new(), Color::Green),
+            };
+            rows.push((order, agent_line(glyph, name, detail, color)));
+        }
+    }
+
+    rows.sort_by_key(|(order, _)| *order);
+    let list = List::new(rows.into_iter().map(|(_, line)| ListItem::new(line)).collect::<Vec<_>>()).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Agents ({}/{})", done, total))
+            .border_style(focus_border_style(state, is_focused)),
+    );
+    f.render_widget(list, area);
+}
+
+fn agent_line(glyph: &str, name: String, detail: String, color: Color) -> Line<'static> {
+    Line::from(vec![
+        Span::styled(format!("{} {}", glyph, name), Style::default().fg(color)),
+        Span::styled(detail, Style::default().fg(Color::DarkGray)),
+    ])
+}
+
+/// Models used this session, most recent first, with their usage
 fn render_active_models(f: &mut Frame, state: &AppState, area: Rect, is_focused: bool) {
+    let now = chrono::Utc::now().timestamp();
     let items: Vec<ListItem> = if state.active_models.is_empty() {
         vec![ListItem::new(Line::from(Span::styled(
-            "No active models",
+            "No requests yet",
             Style::default().fg(Color::DarkGray),
         )))]
     } else {
@@ -159,10 +394,30 @@ fn render_active_models(f: &mut Frame, state: &AppState, area: Rect, is_focused:
             .active_models
             .iter()
             .map(|model| {
-                ListItem::new(Line::from(Span::styled(
-                    format!("• {}", model),
+                let mut spans = vec![Span::styled(
+                    format!("• {}", model.model_id),
                     Style::default().fg(Color::Green),
-                )))
+                )];
+                // Share of thumbs-up over every rating of the model
+                if let Some(rated) = state.satisfaction.iter().find(|s| s.model_id == model.model_id) {
+                    let color = match rated.percent() {
+                        75.. => Color::Green,
+                        50.. => Color::Yellow,
+                        _ => Color::Red,
+                    };
+                    spans.push(Span::styled(format!(" 👍{}%", rated.percent()), Style::default().fg(color)));
+                }
+                spans.push(Span::styled(
+                    format!(
+                        " {}× {} tok ${:.4} {}",
+                        model.requests,
+                        format_token_count(model.tokens),
+                        model.cost,
+                        format_age(now - model.last_used)
+                    ),
+                    Style::default().fg(Color::DarkGray),
+                ));
+                ListItem::new(Line::from(spans))
             })
             .collect()
     };
@@ -171,12 +426,21 @@ fn render_active_models(f: &mut Frame, state: &AppState, area: Rect, is_focused:
         Block::default()
             .borders(Borders::ALL)
             .title("Active Models")
-            .border_style(focus_border_style(is_focused)),
+            .border_style(focus_border_style(state, is_focused)),
     );
 
     f.render_widget(list, area);
 }
 
+/// "12s ago", "5m ago", "2h ago"
+pub fn format_age(secs: i64) -> String {
+    match secs.max(0) {
+        s if s < 60 => format!("{}s ago", s),
+        s if s < 3600 => format!("{}m ago", s / 60),
+        s => format!("{}h ago", s / 3600),
+    }
+}
+
 /// Debug logs (last 10 entries)
 fn render_debug_logs(f: &mut Frame, state: &AppState, area: Rect, is_focused: bool) {
     let log_count = state.debug_logs.len();
@@ -200,7 +464,7 @@ fn render_debug_logs(f: &mut Frame, state: &AppState, area: Rect, is_focused: bo
         Block::default()
             .borders(Borders::ALL)
             .title(format!("Debug Logs ({})", log_count))
-            .border_style(focus_border_style(is_focused)),
+            .border_style(focus_border_style(state, is_focused)),
     );
 
     f.render_widget(paragraph, area);
@@ -209,6 +473,7 @@ fn render_debug_logs(f: &mut Frame, state: &AppState, area: Rect, is_focused: bo
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::{render_with, StateBuilder, HEIGHT};
 
     #[test]
     fn test_token_percentage_calculation() {
@@ -228,4 +493,31 @@ mod tests {
         assert_eq!(low_color, Color::Green);
         assert_eq!(high_color, Color::Red);
     }
+
+    #[test]
+    fn test_snapshot() {
+        let state = StateBuilder::representative();
+        let text = render_with(40, HEIGHT, |f| render(f, &state, f.area()));
+        crate::assert_ui_snapshot!("inspector", text);
+    }
+
+    #[test]
+    fn test_snapshot_slow_requests() {
+        let mut state = StateBuilder::representative();
+        state.slow_requests.insert("gpt-4o".to_string(), 2);
+        let text = render_with(40, HEIGHT, |f| render(f, &state, f.area()));
+        crate::assert_ui_snapshot!("inspector_slow", text);
+    }
+
+    #[test]
+    fn test_snapshot_satisfaction() {
+        let mut state = StateBuilder::representative();
+        state.satisfaction = vec![crate::app::metrics_store::Satisfaction {
+            model_id: "gpt-4o".to_string(),
+            positive: 2,
+            negative: 1,
+        }];
+        let text = render_with(48, HEIGHT, |f| render(f, &state, f.area()));
+        crate::assert_ui_snapshot!("inspector_satisfaction", text);
+    }
 }
@@ -0,0 +1,69 @@
+//! Keyword Emphasis
+//!
+//! With `[ui] bold_keywords`, generated code has the keywords of the usual
+//! languages in bold. It goes by words alone, with no idea of the language
+//! or of strings and comments; enough to give a long listing some shape.
+
+use ratatui::{
+    style::{Modifier, Style},
+    text::{Line, Span},
+};
+
+/// Keywords of Rust, Python, JavaScript/TypeScript, Go, Java and C
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "case", "class", "const", "continue", "def", "default", "elif", "else",
+    "enum", "except", "export", "extends", "finally", "fn", "for", "from", "func", "function", "if", "impl",
+    "import", "in", "interface", "lambda", "let", "loop", "match", "mod", "mut", "new", "package", "pass",
+    "pub", "raise", "return", "static", "struct", "switch", "trait", "try", "type", "use", "var", "where",
+    "while", "with", "yield",
+];
+
+/// `line` with each keyword in a bold span of its own
+pub fn emphasize(line: &str) -> Line<'_> {
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let mut spans = Vec::new();
+    let mut plain = 0;
+    let mut chars = line.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        if !(c.is_alphabetic() || c == '_') {
+            continue;
+        }
+        let mut end = start + c.len_utf8();
+        while let Some(&(i, c)) = chars.peek() {
+            if !(c.is_alphanumeric() || c == '_') {
+                break;
+            }
+            end = i + c.len_utf8();
+            chars.next();
+        }
+        if KEYWORDS.contains(&&line[start..end]) {
+            if plain < start {
+                spans.push(Span::raw(&line[plain..start]));
+            }
+            spans.push(Span::styled(&line[start..end], bold));
+            plain = end;
+        }
+    }
+    if plain < line.len() {
+        spans.push(Span::raw(&line[plain..]));
+    }
+    Line::from(spans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_whole_keywords_are_bold() {
+        let line = emphasize("pub fn format_if(ok: bool) -> Result<()> { return Ok(()) }");
+        let bold: Vec<_> = line
+            .spans
+            .iter()
+            .filter(|s| s.style.add_modifier.contains(Modifier::BOLD))
+            .map(|s| s.content.as_ref())
+            .collect();
+        assert_eq!(bold, ["pub", "fn", "return"]);
+        assert_eq!(line.to_string(), "pub fn format_if(ok: bool) -> Result<()> { return Ok(()) }");
+    }
+}
@@ -0,0 +1,264 @@
+//! Markdown Rendering
+//!
+//! Converts model responses to styled ratatui lines: headings, emphasis,
+//! lists, inline code, block quotes and fenced code.
+
+use pulldown_cmark::{CodeBlockKind, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+
+struct Renderer {
+    lines: Vec<Line<'static>>,
+    current: Vec<Span<'static>>,
+    /// Active inline styles, innermost last
+    styles: Vec<Style>,
+    /// Next item number per nested list (`None` = bulleted)
+    lists: Vec<Option<u64>>,
+    quote_depth: usize,
+    in_code_block: bool,
+}
+
+impl Renderer {
+    fn style(&self) -> Style {
+        self.styles
+            .iter()
+            .fold(Style::default(), |acc, s| acc.patch(*s))
+    }
+
+    fn prefix(&self) -> Vec<Span<'static>> {
+        let mut spans = Vec::new();
+        if self.quote_depth > 0 {
+            spans.push(Span::styled(
+                "│ ".repeat(self.quote_depth),
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+        spans
+    }
+
+    fn push_text(&mut self, text: &str) {
+        if self.current.is_empty() {
+            self.current = self.prefix();
+        }
+        self.current.push(Span::styled(text.to_string(), self.style()));
+    }
+
+    fn flush(&mut self) {
+        if !self.current.is_empty() {
+            self.lines.push(Line::from(std::mem::take(&mut self.current)));
+        }
+    }
+
+    fn blank(&mut self) {
+        self.flush();
+        if self.lines.last().is_some_and(|l| !l.spans.is_empty()) {
+            self.lines.push(Line::default());
+        }
+    }
+
+    fn start(&mut self, tag: Tag) {
+        match tag {
+            Tag::Heading { level, .. } => {
+                self.blank();
+                let color = match level {
+                    HeadingLevel::H1 => Color::Magenta,
+                    HeadingLevel::H2 => Color::Cyan,
+                    _ => Color::Blue,
+                };
+                self.styles
+                    .push(Style::default().fg(color).add_modifier(Modifier::BOLD));
+                self.push_text(&format!("{} ", "#".repeat(level as usize)));
+            }
+            Tag::Paragraph if self.lists.is_empty() => self.blank(),
+            Tag::BlockQuote(_) => {
+                self.blank();
+                self.quote_depth += 1;
+                self.styles
+                    .push(Style::default().fg(Color::Gray).add_modifier(Modifier::ITALIC));
+            }
+            Tag::CodeBlock(kind) => {
+                self.blank();
+                self.in_code_block = true;
+                if let CodeBlockKind::Fenced(lang) = kind {
+                    if !lang.is_empty() {
+                        self.lines.push(Line::from(Span::styled(
+                            format!("┌ {}", lang),
+                            Style::default().fg(Color::DarkGray),
+                        )));
+                    }
+                }
+            }
+            Tag::List(start) => {
+                if self.lists.is_empty() {
+                    self.blank();
+                } else {
+                    self.flush();
+                }
+                self.lists.push(start);
+            }
+            Tag::Item => {
+                self.flush();
+                let indent = "  ".repeat(self.lists.len().saturating_sub(1));
+                let marker = match self.lists.last_mut() {
+                    Some(Some(n)) => {
+                        *n += 1;
+                        format!("{}{}. ", indent, *n - 1)
+                    }
+                    _ => format!("{}• ", indent),
+                };
+                self.current = self.prefix();
+                self.current
+                    .push(Span::styled(marker, Style::default().fg(Color::Yellow)));
+            }
+            Tag::Emphasis => self.styles.push(Style::default().add_modifier(Modifier::ITALIC)),
+            Tag::Strong => self.styles.push(Style::default().add_modifier(Modifier::BOLD)),
+            Tag::Strikethrough => self
+                .styles
+                .push(Style::default().add_modifier(Modifier::CROSSED_OUT)),
+            Tag::Link { .. } => self.styles.push(
+                Style::default()
+                    .fg(Color::Blue)
+                    .add_modifier(Modifier::UNDERLINED),
+            ),
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Heading(_) => {
+                self.styles.pop();
+                self.flush();
+            }
+            TagEnd::Paragraph | TagEnd::Item => self.flush(),
+            TagEnd::BlockQuote(_) => {
+                self.flush();
+                self.styles.pop();
+                self.quote_depth = self.quote_depth.saturating_sub(1);
+            }
+            TagEnd::CodeBlock => {
+                self.in_code_block = false;
+                self.flush();
+            }
+            TagEnd::List(_) => {
+                self.flush();
+                self.lists.pop();
+            }
+            TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough | TagEnd::Link => {
+                self.styles.pop();
+            }
+            _ => {}
+        }
+    }
+
+    fn code_block_text(&mut self, text: &str) {
+        for line in text.lines() {
+            let mut spans = self.prefix();
+            spans.push(Span::styled("│ ", Style::default().fg(Color::DarkGray)));
+            spans.push(Span::styled(line.to_string(), Style::default().fg(Color::Cyan)));
+            self.lines.push(Line::from(spans));
+        }
+    }
+}
+
+/// Render markdown `text` as styled lines
+pub fn render(text: &str) -> Vec<Line<'static>> {
+    let mut renderer = Renderer {
+        lines: Vec::new(),
+        current: Vec::new(),
+        styles: Vec::new(),
+        lists: Vec::new(),
+        quote_depth: 0,
+        in_code_block: false,
+    };
+
+    for event in Parser::new_ext(text, Options::ENABLE_STRIKETHROUGH) {
+        match event {
+            Event::Start(tag) => renderer.start(tag),
+            Event::End(tag) => renderer.end(tag),
+            Event::Text(text) if renderer.in_code_block => renderer.code_block_text(&text),
+            Event::Text(text) => renderer.push_text(&text),
+            Event::Code(code) => {
+                if renderer.current.is_empty() {
+                    renderer.current = renderer.prefix();
+                }
+                renderer.current.push(Span::styled(
+                    code.to_string(),
+                    Style::default().fg(Color::Yellow).bg(Color::DarkGray),
+                ));
+            }
+            Event::SoftBreak => renderer.push_text(" "),
+            Event::HardBreak => renderer.flush(),
+            Event::Rule => {
+                renderer.blank();
+                renderer.lines.push(Line::from(Span::styled(
+                    "─".repeat(20),
+                    Style::default().fg(Color::DarkGray),
+                )));
+            }
+            Event::Html(html) | Event::InlineHtml(html) => renderer.push_text(&html),
+            _ => {}
+        }
+    }
+    renderer.flush();
+    renderer.lines
+}
+
+/// Render without styling: one line per source line
+pub fn render_plain(text: &str) -> Vec<Line<'static>> {
+    text.lines().map(|l| Line::from(l.to_string())).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain(lines: &[Line]) -> Vec<String> {
+        lines
+            .iter()
+            .map(|l| l.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_render_blocks_and_inlines() {
+        let lines = render("# Title\n\nSome **bold** and `code`.\n\n- one\n- two\n\n1. first\n\n> quoted\n\n```rust\nfn x() {}\n```");
+        assert_eq!(
+            plain(&lines),
+            [
+                "# Title",
+                "",
+                "Some bold and code.",
+                "",
+                "• one",
+                "• two",
+                "",
+                "1. first",
+                "",
+                "│ quoted",
+                "",
+                "┌ rust",
+                "│ fn x() {}",
+            ]
+        );
+
+        let bold = &lines[2].spans[1];
+        assert_eq!(bold.content, "bold");
+        assert!(bold.style.add_modifier.contains(Modifier::BOLD));
+    }
+
+    #[test]
+    fn test_plain_text_passes_through() {
+        assert_eq!(plain(&render("> User: hi")), ["│ User: hi"]);
+        assert_eq!(plain(&render_plain("a **b**\nc")), ["a **b**", "c"]);
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let lines = render("# Title\n\nSome **bold** and `code`.\n\n- one\n- two\n\n> quoted\n\n```rust\nfn x() {}\n```");
+        let text = crate::testing::render_with(40, 14, |f| f.render_widget(ratatui::widgets::Paragraph::new(lines), f.area()));
+        crate::assert_ui_snapshot!("markdown", text);
+    }
+}
@@ -3,86 +3,289 @@
 //! Implements VS Code-inspired 3-column layout:
 //! [Sidebar (20%) | Center Workspace (60%) | Inspector (20%)]
 
+pub mod api_key;
+pub mod audit;
+pub mod blocks;
+pub mod breadcrumb;
+pub mod bypass;
+pub mod checkpoints;
+pub mod citations;
+pub mod colors;
+pub mod context_menu;
 pub mod editor;
+pub mod experiment;
+pub mod feedback;
+pub mod health;
+pub mod help;
+pub mod history;
+pub mod reports;
 pub mod inspector;
+pub mod keywords;
+pub mod markdown;
+pub mod modal;
+pub mod model_registry;
+pub mod network;
+pub mod pipeline;
+pub mod workflows;
+pub mod profiler;
+pub mod recovery;
+pub mod schedules;
+pub mod search;
 pub mod settings;
 pub mod sidebar;
+pub mod symbols;
+pub mod task_list;
+pub mod telemetry;
+pub mod trust;
+pub mod watches;
+pub mod wrap;
 pub mod command_palette;
 
-use crate::app::{AppState, FocusPane, InputMode};
+use crate::app::{config::Segment, overlay::Overlay, AppState, FocusPane};
+use std::fmt::Write;
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Constraint, Direction, Layout, Position, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Paragraph},
     Frame,
 };
 
-/// Main render function - called every frame
-pub fn render(f: &mut Frame, state: &AppState) {
-    let size = f.area();
+/// Where each pane is drawn this frame (`None` = not shown)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PaneLayout {
+    pub sidebar: Option<Rect>,
+    pub inspector: Option<Rect>,
+    /// Session tab strip, once a checkpoint has been opened in a second tab
+    pub tab_bar: Option<Rect>,
+    /// Path of the session's file, above Thinking
+    pub breadcrumb: Option<Rect>,
+    /// Welcome screen, shown instead of Thinking/Generation without a session
+    pub welcome: Option<Rect>,
+    pub thinking: Option<Rect>,
+    pub generation: Option<Rect>,
+    pub prompt: Rect,
+    pub status_bar: Rect,
+}
 
-    // Create 3-column layout
-    let main_layout = Layout::default()
-        .direction(Direction::Horizontal)
+impl PaneLayout {
+    pub fn area(&self, pane: FocusPane) -> Option<Rect> {
+        match pane {
+            FocusPane::Sidebar => self.sidebar,
+            FocusPane::Thinking => self.thinking,
+            FocusPane::Generation => self.generation,
+            FocusPane::Inspector => self.inspector,
+            FocusPane::Prompt => Some(self.prompt),
+        }
+    }
+
+    /// Pane under screen cell `(x, y)`
+    pub fn pane_at(&self, x: u16, y: u16) -> Option<FocusPane> {
+        FocusPane::ALL
+            .into_iter()
+            .find(|pane| self.area(*pane).is_some_and(|r| r.contains(Position { x, y })))
+    }
+}
+
+/// Split the screen for the current pane visibility; shared by rendering
+/// and mouse hit-testing so both agree on where panes are
+pub fn pane_layout(state: &AppState, size: Rect) -> PaneLayout {
+    let screen = Layout::default()
+        .direction(Direction::Vertical)
         .constraints([
-            Constraint::Percentage(20), // Sidebar
-            Constraint::Percentage(60), // Center Workspace
-            Constraint::Percentage(20), // Inspector
+            Constraint::Min(0),    // Panes
+            Constraint::Length(1), // Status bar
         ])
         .split(size);
 
-    // Render each column
-    sidebar::render(f, state, main_layout[0]);
-    render_center_workspace(f, state, main_layout[1]);
-    inspector::render(f, state, main_layout[2]);
-
-    // Render overlays
-    if state.show_settings {
-        settings::render(f, state, size);
-    }
-    
-    if state.command_palette_visible {
-        command_palette::render(f, state, size);
+    // A maximized pane takes everything above the prompt
+    if let Some(pane) = state.maximized_pane() {
+        let split = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(3)])
+            .split(screen[0]);
+        let mut layout = PaneLayout {
+            prompt: split[1],
+            status_bar: screen[1],
+            ..Default::default()
+        };
+        match pane {
+            FocusPane::Sidebar => layout.sidebar = Some(split[0]),
+            FocusPane::Thinking => layout.thinking = Some(split[0]),
+            FocusPane::Generation => layout.generation = Some(split[0]),
+            FocusPane::Inspector => layout.inspector = Some(split[0]),
+            FocusPane::Prompt => {}
+        }
+        return layout;
     }
-}
 
-/// Render center workspace (thinking + generation + prompt)
-fn render_center_workspace(f: &mut Frame, state: &AppState, area: Rect) {
-    // Split center into Content (Top) and Prompt (Bottom)
-    let layout = Layout::default()
+    // 3-column layout; hidden side columns give their width to the center
+    let show_sidebar = state.pane_visible(FocusPane::Sidebar);
+    let show_inspector = state.pane_visible(FocusPane::Inspector);
+    let main_layout = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(if show_sidebar { 20 } else { 0 }),   // Sidebar
+            Constraint::Min(0),                                           // Center Workspace
+            Constraint::Percentage(if show_inspector { 20 } else { 0 }), // Inspector
+        ])
+        .split(screen[0]);
+
+    // Split center into Tabs, Content and Prompt (Bottom)
+    let show_tabs = state.session_tabs.len() > 1;
+    let show_breadcrumb = state.session.is_some();
+    let outer = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(if show_tabs { 1 } else { 0 }),       // Session tabs
+            Constraint::Length(if show_breadcrumb { 1 } else { 0 }), // Breadcrumbs
+            Constraint::Min(0),                                         // Content + Prompt
+        ])
+        .split(main_layout[1]);
+    let center = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Min(0),    // Content (Thinking/Generation or Welcome)
             Constraint::Length(3), // Prompt (Fixed height)
         ])
-        .split(area);
-
-    let content_area = layout[0];
-    let prompt_area = layout[1];
+        .split(outer[2]);
 
-    // Render Content Area
-    if state.session.is_none() {
-        render_welcome_screen(f, content_area);
+    let (thinking, generation) = if state.session.is_none() {
+        (None, None)
+    } else if !state.pane_visible(FocusPane::Thinking) {
+        (None, Some(center[0]))
     } else {
-        // Split content into Thinking and Generation
-        let workspace_layout = Layout::default()
+        let workspace = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Percentage(50),
-                Constraint::Percentage(50),
-            ])
-            .split(content_area);
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(center[0]);
+        (Some(workspace[0]), Some(workspace[1]))
+    };
+
+    PaneLayout {
+        sidebar: show_sidebar.then_some(main_layout[0]),
+        inspector: show_inspector.then_some(main_layout[2]),
+        tab_bar: show_tabs.then_some(outer[0]),
+        breadcrumb: show_breadcrumb.then_some(outer[1]),
+        welcome: state.session.is_none().then_some(center[0]),
+        thinking,
+        generation,
+        prompt: center[1],
+        status_bar: screen[1],
+    }
+}
+
+/// Main render function - called every frame
+pub fn render(f: &mut Frame, state: &AppState) {
+    let size = f.area();
+    let layout = pane_layout(state, size);
 
-        editor::render_thinking_pane(f, state, workspace_layout[0]);
-        editor::render_generation_pane(f, state, workspace_layout[1]);
+    if let Some(area) = layout.sidebar {
+        sidebar::render(f, state, area);
+    }
+    render_center_workspace(f, state, &layout);
+    if let Some(area) = layout.inspector {
+        inspector::render(f, state, area);
+    }
+    if state.config.ui.dim_inactive {
+        dim_inactive_panes(f, state, &layout);
+    }
+    render_status_bar(f, state, layout.status_bar);
+
+    // Render overlays, the one taking keys last
+    for overlay in state.overlays.iter() {
+        match overlay {
+            Overlay::Audit => audit::render(f, state, size),
+            Overlay::Blocks => blocks::render(f, state, size),
+            Overlay::Checkpoints => checkpoints::render(f, state, size),
+            Overlay::Citations => citations::render(f, state, size),
+            Overlay::CommandPalette => command_palette::render(f, state, size),
+            Overlay::Experiment => experiment::render(f, state, size),
+            Overlay::Health => health::render(f, state, size),
+            Overlay::Help => help::render(f, state, size),
+            Overlay::History => history::render(f, state, size),
+            Overlay::ModelRegistry => model_registry::render(f, state, size),
+            Overlay::Network => network::render(f, state, size),
+            Overlay::Pipeline => pipeline::render(f, state, size),
+            Overlay::Reports => reports::render(f, state, size),
+            Overlay::Schedules => schedules::render(f, state, size),
+            Overlay::Search => search::render(f, state, size),
+            Overlay::Settings => settings::render(f, state, size),
+            Overlay::Telemetry => telemetry::render(f, state, size),
+            Overlay::Watches => watches::render(f, state, size),
+            Overlay::Workflows => workflows::render(f, state, size),
+            Overlay::ContextMenu => context_menu::render(f, state, size),
+            Overlay::FileDropdown => breadcrumb::render_dropdown(f, state, size),
+            Overlay::Modal => {
+                if let Some(open) = &state.modal {
+                    modal::render(f, open, size);
+                }
+            }
+            Overlay::Recovery => {
+                if let Some(work) = &state.recovered {
+                    recovery::render(f, work, size);
+                }
+            }
+            Overlay::Trust => {
+                if let Some(prompt) = &state.trust_prompt {
+                    trust::render(f, prompt, size);
+                }
+            }
+            Overlay::CostConfirm => {
+                if let Some(confirm) = &state.cost_confirm {
+                    editor::render_cost_confirm(f, confirm, size);
+                }
+            }
+            Overlay::ApiKey => {
+                if let Some(prompt) = &state.api_key_prompt {
+                    api_key::render(f, prompt, size);
+                }
+            }
+            Overlay::Bypass => {
+                if let Some(prompt) = &state.bypass_prompt {
+                    bypass::render(f, prompt, size);
+                }
+            }
+            Overlay::Feedback => {
+                if let Some(prompt) = &state.feedback_prompt {
+                    feedback::render(f, prompt, size);
+                }
+            }
+        }
+    }
+
+    // Drawn last so it stays visible over every other overlay
+    if state.show_profiler {
+        profiler::render(f, state, size);
+    }
+
+    colors::fit(f.buffer_mut(), state.color_depth);
+}
+
+/// Render center workspace (thinking + generation + prompt)
+fn render_center_workspace(f: &mut Frame, state: &AppState, layout: &PaneLayout) {
+    if let Some(area) = layout.tab_bar {
+        checkpoints::render_tab_bar(f, state, area);
+    }
+    if let Some(area) = layout.breadcrumb {
+        breadcrumb::render(f, state, area);
+    }
+
+    // Render Content Area
+    if let Some(area) = layout.welcome {
+        render_welcome_screen(f, area);
+    }
+    if let Some(area) = layout.thinking {
+        editor::render_thinking_pane(f, state, area);
+    }
+    if let Some(area) = layout.generation {
+        editor::render_generation_pane(f, state, area);
     }
 
     // Always render Prompt Box
-    editor::render_prompt_box(f, state, prompt_area);
+    editor::render_prompt_box(f, state, layout.prompt);
 }
 
-/// Welcome screen (shown when no file is open)
 fn render_welcome_screen(f: &mut Frame, area: Rect) {
     let logo = vec![
         "██╗███╗   ███╗███████╗",
@@ -96,7 +299,7 @@ fn render_welcome_screen(f: &mut Frame, area: Rect) {
         "MULTI-VENDOR FRAMEWORK: GOOGLE • ANTHROPIC • OPENAI",
         "",
         "Press ↑/↓ to navigate files, Enter to open",
-        "Press S for settings, Q to quit",
+        "Press S for settings, ? for all keybindings, Q to quit",
     ];
 
     let lines: Vec<Line> = logo
@@ -123,84 +326,197 @@ fn render_welcome_screen(f: &mut Frame, area: Rect) {
     f.render_widget(welcome, area);
 }
 
-/// Render status bar at bottom
+/// Render status bar at bottom: the configured segments, then whatever
+/// needs attention
 pub fn render_status_bar(f: &mut Frame, state: &AppState, area: Rect) {
-    let status_text = if state.api_connected {
-        format!(
-            "🟢 API Connected | Files: {} | Tokens: {} | Cost: ${:.4} | Focus: {:?}",
-            state.file_tree.len(),
-            state.total_tokens_used,
-            state.total_cost,
-            state.focus
-        )
+    let sym = symbols::symbols(state);
+    let mut notices = Vec::new();
+    let degraded = state.health.degraded();
+    if state.api_connected && degraded > 0 {
+        notices.push(format!("{} {} subsystem(s) unhealthy (click for details)", sym.warning, degraded));
+    }
+    if !state.api_connected && !state.offline_queue.is_empty() {
+        notices.push(format!("{} {} queued", sym.pending, state.offline_queue.len()));
+    }
+    if let Some(retry) = state.next_rate_limit_retry() {
+        notices.push(format!(
+            "{} Rate limited: retrying {} in {}s",
+            sym.pending,
+            retry.request.model_id,
+            retry.countdown().as_secs() + 1
+        ));
+    }
+    if state.polling_paused {
+        notices.push(format!("{} polling paused", sym.pending));
+    }
+    if let Some(mode) = &state.read_only {
+        notices.push(format!("{} read-only: {}", sym.locked, mode.reason()));
+    }
+
+    let mut spans = Vec::new();
+    let segments = state.config.status_bar.segments.iter().enumerate();
+    for text in segments.filter_map(|(i, segment)| segment_text(state, i, segment)).chain(notices) {
+        if !spans.is_empty() {
+            spans.push(Span::raw(" | "));
+        }
+        spans.push(Span::raw(text));
+    }
+
+    // An alert's flash lights the bar up for a moment
+    let (background, foreground) = if state.alerts.flashing(std::time::Instant::now()) {
+        (Color::Yellow, Color::Black)
     } else {
-        "🔴 API Disconnected - Waiting for backend...".to_string()
+        (Color::DarkGray, Color::White)
     };
-
-    let status_bar = Paragraph::new(status_text)
-        .style(
-            Style::default()
-                .bg(Color::DarkGray)
-                .fg(Color::White)
-                .add_modifier(Modifier::BOLD),
-        )
+    let status_bar = Paragraph::new(Line::from(spans))
+        .style(Style::default().bg(background).fg(foreground).add_modifier(Modifier::BOLD))
         .block(Block::default());
 
     f.render_widget(status_bar, area);
 }
 
-/// Render keybinding hints
-pub fn render_keybindings(area: Rect) -> Paragraph<'static> {
-    let hints = vec![
-        "↑/↓: Navigate",
-        "Enter: Open",
-        "Tab: Cycle Focus",
-        "S: Settings",
-        "Q: Quit",
-    ];
-
-    let text: Vec<Line> = hints
-        .iter()
-        .map(|&hint| {
-            Line::from(Span::styled(
-                hint,
-                Style::default()
-                    .fg(Color::Gray)
-                    .add_modifier(Modifier::ITALIC),
-            ))
-        })
-        .collect();
+/// A status bar segment's text, if it has anything to show
+fn segment_text(state: &AppState, index: usize, segment: &Segment) -> Option<String> {
+    let sym = symbols::symbols(state);
+    Some(match segment {
+        Segment::Connection => match &state.reconnect {
+            Some(reconnect) => {
+                let next = match reconnect.countdown() {
+                    Some(left) => format!("next retry in {}s", left.as_secs() + 1),
+                    None => "checking...".to_string(),
+                };
+                let error = reconnect
+                    .last_error
+                    .as_deref()
+                    .map(|e| format!(" | Last error: {}", e))
+                    .unwrap_or_default();
+                format!(
+                    "{} Reconnecting: attempt {}/{}, {}{}",
+                    sym.reconnecting,
+                    reconnect.attempt,
+                    crate::app::connection::MAX_ATTEMPTS,
+                    next,
+                    error
+                )
+            }
+            None if state.api_connected => format!("{} API Connected", sym.connected),
+            None => format!(
+                "{} API Disconnected - Waiting for backend... (S: Settings > Reconnect)",
+                sym.disconnected
+            ),
+        },
+        Segment::User => format!("User: {}", state.user_id),
+        Segment::Files => format!("Files: {}", state.file_tree.len()),
+        Segment::Model => format!("Model: {}", state.session.as_ref()?.model_id),
+        Segment::Tokens => format!("Tokens: {}", state.total_tokens_used),
+        Segment::Cost => format!("Cost: ${:.4}", state.total_cost),
+        Segment::Focus => format!("Focus: {:?}", state.focus),
+        Segment::GitBranch { .. } => format!("Branch: {}", state.status_segments.value(index)?),
+        Segment::Clock { format } => {
+            // An invalid format shows nothing rather than panicking
+            let mut time = String::new();
+            write!(time, "{}", chrono::Local::now().format(format)).ok()?;
+            time
+        }
+        Segment::Script { label, .. } => {
+            let value = state.status_segments.value(index)?;
+            match label {
+                Some(label) => format!("{}: {}", label, value),
+                None => value.to_string(),
+            }
+        }
+    })
+}
 
-    Paragraph::new(text)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title("Keybindings")
-                .border_style(Style::default().fg(Color::DarkGray)),
-        )
+/// Right-aligned `[N]` badge for a pane's border while pane hints are shown
+pub fn pane_hint(state: &AppState, pane: FocusPane) -> Line<'static> {
+    if !state.show_pane_hints() {
+        return Line::default();
+    }
+    Line::from(Span::styled(
+        format!("[Alt+{}]", pane.number()),
+        Style::default().fg(Color::Black).bg(Color::Yellow).add_modifier(Modifier::BOLD),
+    ))
+    .right_aligned()
 }
 
 /// Get focus border style
-pub fn focus_border_style(is_focused: bool) -> Style {
+pub fn focus_border_style(state: &AppState, is_focused: bool) -> Style {
     if is_focused {
         Style::default()
             .fg(Color::Cyan)
             .add_modifier(Modifier::BOLD)
+    } else if state.config.ui.dim_inactive {
+        Style::default().fg(Color::DarkGray).add_modifier(Modifier::DIM)
     } else {
         Style::default().fg(Color::DarkGray)
     }
 }
 
+/// Dim the contents of every pane but the focused one (`[ui] dim_inactive`)
+fn dim_inactive_panes(f: &mut Frame, state: &AppState, layout: &PaneLayout) {
+    let panes = [
+        (FocusPane::Sidebar, layout.sidebar),
+        (FocusPane::Thinking, layout.thinking),
+        (FocusPane::Generation, layout.generation),
+        (FocusPane::Inspector, layout.inspector),
+        (FocusPane::Prompt, Some(layout.prompt)),
+    ];
+    for (pane, area) in panes {
+        if let Some(area) = area.filter(|_| pane != state.focus) {
+            f.buffer_mut().set_style(area, Style::default().add_modifier(Modifier::DIM));
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_focus_border_style() {
-        let focused = focus_border_style(true);
-        let unfocused = focus_border_style(false);
+        let mut state = AppState::default();
+        let focused = focus_border_style(&state, true);
+        let unfocused = focus_border_style(&state, false);
 
         assert_eq!(focused.fg, Some(Color::Cyan));
         assert_eq!(unfocused.fg, Some(Color::DarkGray));
+        assert!(!unfocused.add_modifier.contains(Modifier::DIM));
+
+        state.config.ui.dim_inactive = true;
+        assert!(focus_border_style(&state, false).add_modifier.contains(Modifier::DIM));
+    }
+
+    #[test]
+    fn test_status_bar_segments_in_configured_order() {
+        use crate::testing::{render_with, StateBuilder};
+
+        let mut state = StateBuilder::representative();
+        state.config.status_bar.segments = vec![
+            Segment::Model,
+            Segment::Clock { format: "%Q".to_string() },
+            Segment::GitBranch { interval_secs: 10 },
+            Segment::Cost,
+        ];
+        let text = render_with(60, 1, |f| render_status_bar(f, &state, f.area()));
+        assert_eq!(text.trim_end(), "Model: gpt-4o | Cost: $0.0018");
+    }
+
+    #[test]
+    fn test_screen_snapshots() {
+        use crate::testing::{render_app, StateBuilder, HEIGHT, WIDTH};
+
+        let state = StateBuilder::representative();
+        crate::assert_ui_snapshot!("screen", render_app(&state, WIDTH, HEIGHT));
+
+        let welcome = StateBuilder::new().workspace().disconnected().build();
+        crate::assert_ui_snapshot!("screen_welcome_offline", render_app(&welcome, WIDTH, HEIGHT));
+
+        let narrow = StateBuilder::representative();
+        crate::assert_ui_snapshot!("screen_narrow", render_app(&narrow, 80, 24));
+
+        let mut compact = StateBuilder::representative();
+        compact.config.ui.density = crate::app::config::Density::Compact;
+        crate::assert_ui_snapshot!("screen_compact", render_app(&compact, WIDTH, HEIGHT));
     }
 }
@@ -0,0 +1,92 @@
+//! Modal Popup - confirmation, input or pick over everything else
+
+use crate::app::modal::{Ask, Modal, VISIBLE_CHOICES};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+pub fn render(f: &mut Frame, modal: &Modal, screen: Rect) {
+    let area = modal.area(screen);
+    f.render_widget(Clear, area);
+    let accent = if modal.destructive { Color::Red } else { Color::Cyan };
+    let width = area.width.saturating_sub(2) as usize;
+
+    let mut lines = Vec::new();
+    let message = modal.message_rows(area.width.saturating_sub(2));
+    if !message.is_empty() {
+        lines.extend(message);
+        lines.push(Line::from(""));
+    }
+    let hints = match &modal.ask {
+        Ask::Confirm => "y/Enter: Yes | n/Esc: No",
+        Ask::Input(input) => {
+            lines.push(Line::from(vec![
+                Span::styled("> ", Style::default().fg(accent)),
+                Span::styled(input.as_str(), Style::default().fg(Color::Yellow)),
+            ]));
+            lines.push(Line::from(""));
+            "Enter: OK | Esc: Cancel"
+        }
+        Ask::Select { choices, selected } => {
+            for (i, choice) in choices.iter().enumerate().skip(modal.scroll()).take(VISIBLE_CHOICES) {
+                let style = if i == *selected {
+                    Style::default().fg(Color::Black).bg(accent).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(Color::White)
+                };
+                lines.push(Line::from(Span::styled(format!(" {:<width$}", choice.label, width = width.saturating_sub(1)), style)));
+            }
+            lines.push(Line::from(""));
+            "↑/↓: Choose | Enter: OK | Esc: Cancel"
+        }
+    };
+    lines.push(Line::from(Span::styled(hints, Style::default().fg(Color::DarkGray))));
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(modal.title.as_str())
+        .border_style(Style::default().fg(accent).add_modifier(Modifier::BOLD));
+    f.render_widget(Paragraph::new(lines).block(block), area);
+
+    if let Ask::Input(input) = &modal.ask {
+        let x = (area.x + 3 + input.cursor_column()).min(area.right().saturating_sub(2));
+        f.set_cursor_position((x, area.y + modal.body_offset(area.width.saturating_sub(2))));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::palette::Choice;
+    use crate::testing::{render_with, HEIGHT, WIDTH};
+
+    #[test]
+    fn test_snapshot_confirm() {
+        let modal = Modal::confirm("Delete File", "Delete src/main.rs? This can't be undone.", |_| {}).destructive();
+        let text = render_with(WIDTH, HEIGHT, |f| render(f, &modal, f.area()));
+        crate::assert_ui_snapshot!("modal_confirm", text);
+    }
+
+    #[test]
+    fn test_snapshot_input() {
+        let modal = Modal::input("Rename", "main.rs", |_, _| {}).with_message("New name for src/main.rs");
+        let text = render_with(WIDTH, HEIGHT, |f| render(f, &modal, f.area()));
+        crate::assert_ui_snapshot!("modal_input", text);
+    }
+
+    #[test]
+    fn test_snapshot_select() {
+        let choice = |label: &str| Choice {
+            label: label.to_string(),
+            value: label.to_string(),
+        };
+        let mut modal = Modal::select("Open With", vec![choice("Editor"), choice("Pager"), choice("Browser")], |_, _| {});
+        modal.select_next();
+        let text = render_with(WIDTH, HEIGHT, |f| render(f, &modal, f.area()));
+        crate::assert_ui_snapshot!("modal_select", text);
+    }
+}
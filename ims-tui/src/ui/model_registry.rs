@@ -0,0 +1,297 @@
+//! Model Registry Browser (full-screen view)
+
+use crate::app::{
+    registry::{AdminAction, AdminMenu, SortColumn},
+    tokens::format_token_count,
+    AppState,
+};
+use crate::ui::{inspector::format_age, symbols};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Clear, List, ListItem, Paragraph, Row, Table, TableState},
+    Frame,
+};
+
+pub fn render(f: &mut Frame, state: &AppState, area: Rect) {
+    f.render_widget(Clear, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Filter
+            Constraint::Min(0),    // Table
+            Constraint::Length(3), // Footer
+        ])
+        .split(area);
+
+    render_filter(f, state, chunks[0]);
+    render_table(f, state, chunks[1]);
+    render_footer(f, state, chunks[2]);
+
+    if let Some(menu) = &state.model_registry.admin_menu {
+        render_admin_menu(f, state, menu, area);
+    }
+}
+
+fn render_filter(f: &mut Frame, state: &AppState, area: Rect) {
+    let view = &state.model_registry;
+
+    let (text, style) = if view.filter.is_empty() && !view.filter_editing {
+        (
+            "Press / to filter by model, vendor or tier".to_string(),
+            Style::default().fg(Color::Gray).add_modifier(Modifier::ITALIC),
+        )
+    } else {
+        (view.filter.clone(), Style::default().fg(Color::Yellow))
+    };
+
+    let border = if view.filter_editing {
+        Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(Color::Cyan)
+    };
+
+    let input = Paragraph::new(Span::styled(text, style)).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Model Registry")
+            .border_style(border),
+    );
+    f.render_widget(input, area);
+
+    if view.filter_editing {
+        f.set_cursor_position((area.x + view.filter.len() as u16 + 1, area.y + 1));
+    }
+}
+
+fn render_table(f: &mut Frame, state: &AppState, area: Rect) {
+    let view = &state.model_registry;
+    let rows_data = view.visible();
+
+    let header = Row::new(SortColumn::ALL.iter().map(|column| {
+        let mut title = column.title().to_string();
+        if *column == view.sort {
+            title.push_str(if view.descending { " ▼" } else { " ▲" });
+        }
+        Cell::from(title)
+    }))
+    .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD));
+
+    let session_model = state.session.as_ref().map(|s| s.model_id.as_str());
+
+    let rows = rows_data.iter().map(|m| {
+        let vendor = state.vendors.for_model(&m.model_id);
+        let style = if !m.is_active {
+            Style::default().fg(Color::DarkGray)
+        } else if Some(m.model_id.as_str()) == session_model {
+            Style::default().fg(Color::Green)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        Row::new(vec![
+            Cell::from(m.model_id.clone()),
+            Cell::from(Line::from(vec![
+                Span::styled(vendor.glyph.clone(), Style::default().fg(vendor.color)),
+                Span::raw(format!(" {}", m.vendor_id)),
+            ])),
+            Cell::from(m.capability_tier.clone()),
+            Cell::from(format_token_count(m.context_window as u64)),
+            Cell::from(format!("{:.3}", m.cost_in_per_mil)),
+            Cell::from(format!("{:.3}", m.cost_out_per_mil)),
+            Cell::from(if m.function_call_support { "✓" } else { "-" }),
+            Cell::from(if m.is_active { "✓" } else { "✗" }),
+        ])
+        .style(style)
+    });
+
+    // Later pages load as the selection nears the end of the list
+    let loaded = match view.total {
+        Some(total) if total as usize > view.models.len() => format!("{} of {}", view.models.len(), total),
+        _ => view.models.len().to_string(),
+    };
+    let more = match (view.loading, view.stale, &view.next_page) {
+        (true, true, _) => ", checking for changes...",
+        (true, false, _) => ", loading more...",
+        (false, _, Some(_)) => ", more below",
+        (false, _, None) => "",
+    };
+    // How long since the backend confirmed the (possibly cached) list
+    let age = view
+        .checked_at
+        .map(|at| format!(", checked {}", format_age(chrono::Utc::now().timestamp() - at)))
+        .unwrap_or_default();
+    let title = if view.loading && view.models.is_empty() {
+        "Models (loading...)".to_string()
+    } else {
+        format!("Models ({}/{}{}{})", rows_data.len(), loaded, more, age)
+    };
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(20),
+            Constraint::Length(12),
+            Constraint::Length(8),
+            Constraint::Length(9),
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Length(4),
+            Constraint::Length(8),
+        ],
+    )
+    .header(header)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_style(Style::default().fg(Color::Cyan)),
+    )
+    .highlight_style(
+        Style::default()
+            .fg(Color::Black)
+            .bg(Color::Cyan)
+            .add_modifier(Modifier::BOLD),
+    );
+
+    let mut table_state = TableState::default();
+    if !rows_data.is_empty() {
+        table_state.select(Some(view.selected));
+    }
+    f.render_stateful_widget(table, area, &mut table_state);
+}
+
+/// Admin submenu popup (only reachable with an admin key)
+fn render_admin_menu(f: &mut Frame, state: &AppState, menu: &AdminMenu, area: Rect) {
+    let model_id = state
+        .model_registry
+        .selected_model()
+        .map(|m| m.model_id.as_str())
+        .unwrap_or("-");
+
+    let popup = Rect {
+        x: area.x + area.width.saturating_sub(40) / 2,
+        y: area.y + area.height.saturating_sub(8) / 2,
+        width: 40.min(area.width),
+        height: 8.min(area.height),
+    };
+    f.render_widget(Clear, popup);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("{} Admin: {}", symbols::symbols(state).locked, model_id))
+        .border_style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD));
+
+    if let Some(input) = &menu.pricing_input {
+        let lines = vec![
+            Line::from(Span::styled(
+                "Cost per million tokens as <in>,<out>",
+                Style::default().fg(Color::Gray),
+            )),
+            Line::from(""),
+            Line::from(Span::styled(input.as_str(), Style::default().fg(Color::Yellow))),
+            Line::from(""),
+            Line::from(Span::styled(
+                "Enter: Apply | Esc: Back",
+                Style::default().fg(Color::DarkGray),
+            )),
+        ];
+        f.render_widget(Paragraph::new(lines).block(block), popup);
+        f.set_cursor_position((popup.x + input.len() as u16 + 1, popup.y + 3));
+        return;
+    }
+
+    let items: Vec<ListItem> = AdminAction::ALL
+        .iter()
+        .enumerate()
+        .map(|(i, action)| {
+            let style = if i == menu.selected {
+                Style::default().fg(Color::Black).bg(Color::Red).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            ListItem::new(Line::from(Span::styled(action.label(), style)))
+        })
+        .collect();
+
+    f.render_widget(List::new(items).block(block), popup);
+}
+
+fn render_footer(f: &mut Frame, state: &AppState, area: Rect) {
+    let defaults = if state.model_defaults.is_empty() {
+        String::new()
+    } else {
+        let mut pairs: Vec<String> = state
+            .model_defaults
+            .iter()
+            .map(|(ext, model)| format!(".{}→{}", ext, model))
+            .collect();
+        pairs.sort();
+        format!(" | Defaults: {}", pairs.join(", "))
+    };
+
+    let is_admin = state.api_client.as_ref().is_some_and(|c| c.has_admin_key());
+    let admin_hint = match &state.read_only {
+        Some(mode) => format!(" | Read-only ({}) | a: Enter admin key", mode.reason()),
+        None if is_admin => " | a: Admin".to_string(),
+        None => format!(" | a: Admin {}", symbols::symbols(state).locked),
+    };
+
+    let footer = Paragraph::new(Line::from(vec![
+        Span::styled(
+            "↑/↓: Select | ←/→ or 1-8: Sort | o: Reverse | Enter: Use for session | d: Default for file type | r: Refresh | Esc: Close",
+            Style::default().fg(Color::Gray),
+        ),
+        Span::styled(admin_hint, Style::default().fg(Color::Red)),
+        Span::styled(defaults, Style::default().fg(Color::Yellow)),
+    ]))
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::DarkGray)),
+    );
+
+    f.render_widget(footer, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::api::{ModelPage, ModelResponse, PageToken};
+    use crate::testing::{render_with, StateBuilder, HEIGHT, WIDTH};
+
+    #[test]
+    fn test_snapshot() {
+        let model = |model_id: &str, vendor_id: &str, context_window, cost_in_per_mil, is_active| ModelResponse {
+            model_id: model_id.to_string(),
+            vendor_id: vendor_id.to_string(),
+            capability_tier: "standard".to_string(),
+            context_window,
+            cost_in_per_mil,
+            cost_out_per_mil: cost_in_per_mil * 4.0,
+            function_call_support: true,
+            is_active,
+        };
+        let mut state = StateBuilder::new().build();
+        state.apply_model_list(vec![
+            model("gpt-4o", "openai", 128_000, 2.5, true),
+            model("claude-3-haiku", "anthropic", 200_000, 0.25, true),
+            model("gemini-pro", "google", 32_000, 0.5, false),
+        ]);
+        let text = render_with(WIDTH, HEIGHT, |f| render(f, &state, f.area()));
+        crate::assert_ui_snapshot!("model_registry", text);
+
+        state.apply_model_page(
+            ModelPage {
+                models: vec![model("o1", "openai", 200_000, 15.0, true)],
+                next: Some(PageToken::Offset(4)),
+                total: Some(120),
+                ..Default::default()
+            },
+            true,
+        );
+        let text = render_with(WIDTH, HEIGHT, |f| render(f, &state, f.area()));
+        crate::assert_ui_snapshot!("model_registry_paged", text);
+    }
+}
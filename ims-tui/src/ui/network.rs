@@ -0,0 +1,253 @@
+//! Network Pane - recorded HTTP requests to IMS Core
+
+use crate::app::{
+    config::Http2Mode,
+    network::{format_bytes, NetworkEntry},
+    AppState,
+};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table, TableState, Wrap},
+    Frame,
+};
+
+pub fn render(f: &mut Frame, state: &AppState, area: Rect) {
+    f.render_widget(Clear, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),    // Requests or detail
+            Constraint::Length(1), // Transport stats
+            Constraint::Length(1), // Hints
+        ])
+        .split(area);
+
+    let selected = state.network_log.get(state.network_view.selected);
+    let hints = match selected {
+        Some(entry) if state.network_view.detail => {
+            render_detail(f, entry, chunks[0]);
+            " Esc: Back | ↑/↓: Previous/Next request"
+        }
+        _ => {
+            render_requests(f, state, chunks[0]);
+            " ↑/↓: Select | Enter: Details | c: Clear | Esc/N: Close"
+        }
+    };
+
+    f.render_widget(Paragraph::new(transport_line(state)), chunks[1]);
+    f.render_widget(
+        Paragraph::new(hints).style(Style::default().fg(Color::DarkGray)),
+        chunks[2],
+    );
+}
+
+/// Connection reuse and bytes moved since startup, with the `[http]` knobs
+/// that affect them
+fn transport_line(state: &AppState) -> Line<'static> {
+    let totals = &state.transport;
+    let http = &state.config.http;
+    let http2 = match http.http2 {
+        Http2Mode::Auto => "auto",
+        Http2Mode::Always => "always",
+        Http2Mode::Never => "off",
+    };
+    let label = Style::default().fg(Color::Yellow);
+    Line::from(vec![
+        Span::styled(" Connections ", label),
+        Span::raw(format!("{} reused / {} new", totals.reused(), totals.connections)),
+        Span::styled("  Bodies ", label),
+        Span::raw(format!(
+            "↑ {} ↓ {}",
+            format_bytes(totals.bytes_sent),
+            format_bytes(totals.bytes_received)
+        )),
+        Span::styled("  Compression ", label),
+        Span::raw(if http.compression { "gzip, br" } else { "off" }),
+        Span::styled("  HTTP/2 ", label),
+        Span::raw(http2),
+    ])
+}
+
+fn status_style(entry: &NetworkEntry) -> Style {
+    match entry.status {
+        Some(s) if s < 300 => Style::default().fg(Color::Green),
+        Some(s) if s < 400 => Style::default().fg(Color::Yellow),
+        _ => Style::default().fg(Color::Red),
+    }
+}
+
+fn status_text(entry: &NetworkEntry) -> String {
+    entry
+        .status
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| "ERR".to_string())
+}
+
+fn render_requests(f: &mut Frame, state: &AppState, area: Rect) {
+    let header = Row::new(["Time", "Method", "Status", "Duration", "URL"])
+        .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD));
+
+    let rows = state.network_log.iter().map(|entry| {
+        Row::new(vec![
+            Cell::from(entry.time.format("%H:%M:%S").to_string()),
+            Cell::from(entry.method.clone()),
+            Cell::from(Span::styled(status_text(entry), status_style(entry))),
+            Cell::from(format!("{} ms", entry.duration_ms)),
+            Cell::from(entry.url.clone()),
+        ])
+    });
+
+    let errors = state.network_log.iter().filter(|e| e.is_error()).count();
+    let title = if state.network_log.is_empty() {
+        "Network (no requests yet; mock mode makes none)".to_string()
+    } else {
+        format!("Network ({} requests, {} failed)", state.network_log.len(), errors)
+    };
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(8),
+            Constraint::Length(6),
+            Constraint::Length(6),
+            Constraint::Length(9),
+            Constraint::Min(20),
+        ],
+    )
+    .header(header)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_style(Style::default().fg(Color::Cyan)),
+    )
+    .highlight_style(
+        Style::default()
+            .fg(Color::Black)
+            .bg(Color::Cyan)
+            .add_modifier(Modifier::BOLD),
+    );
+
+    let mut table_state = TableState::default();
+    if !state.network_log.is_empty() {
+        table_state.select(Some(state.network_view.selected));
+    }
+    f.render_stateful_widget(table, area, &mut table_state);
+}
+
+fn render_detail(f: &mut Frame, entry: &NetworkEntry, area: Rect) {
+    let heading = |text: &str| {
+        Line::from(Span::styled(
+            text.to_string(),
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+        ))
+    };
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled(format!("{} ", entry.method), Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(entry.url.clone()),
+        ]),
+        Line::from(vec![
+            Span::raw("Status: "),
+            Span::styled(status_text(entry), status_style(entry)),
+            Span::raw(format!(
+                "   Duration: {} ms   At: {}",
+                entry.duration_ms,
+                entry.time.format("%H:%M:%S%.3f")
+            )),
+        ]),
+        Line::from(format!(
+            "Protocol: {}   Sent: {}   Received: {}",
+            entry.version.as_deref().unwrap_or("-"),
+            format_bytes(entry.bytes_sent),
+            format_bytes(entry.bytes_received)
+        )),
+    ];
+    if let Some(error) = &entry.error {
+        lines.push(Line::from(Span::styled(error.clone(), Style::default().fg(Color::Red))));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(heading("Request Headers"));
+    for (name, value) in &entry.request_headers {
+        lines.push(Line::from(vec![
+            Span::styled(format!("{}: ", name), Style::default().fg(Color::Gray)),
+            Span::raw(value.clone()),
+        ]));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(heading("Request Body"));
+    lines.extend(
+        entry
+            .request_body
+            .as_deref()
+            .unwrap_or("(empty)")
+            .lines()
+            .map(|l| Line::from(l.to_string())),
+    );
+
+    lines.push(Line::from(""));
+    lines.push(heading("Response Body"));
+    lines.extend(
+        entry
+            .response_body
+            .as_deref()
+            .unwrap_or("(none)")
+            .lines()
+            .map(|l| Line::from(l.to_string())),
+    );
+
+    let detail = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Request Detail")
+            .border_style(Style::default().fg(Color::Cyan)),
+    );
+    f.render_widget(detail, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{render_with, StateBuilder, HEIGHT, WIDTH};
+
+    fn entry(method: &str, url: &str, status: Option<u16>, error: Option<&str>) -> NetworkEntry {
+        NetworkEntry {
+            time: chrono::Local::now(),
+            method: method.to_string(),
+            url: url.to_string(),
+            request_headers: vec![("X-Admin-Key".to_string(), "[redacted]".to_string())],
+            request_body: None,
+            status,
+            duration_ms: 42,
+            response_body: status.map(|_| "{\"status\":\"healthy\"}".to_string()),
+            error: error.map(str::to_string),
+            version: status.map(|_| "HTTP/1.1".to_string()),
+            bytes_sent: 0,
+            bytes_received: if status.is_some() { 20 } else { 0 },
+            connections_opened: 1,
+        }
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let mut state = StateBuilder::new()
+            .with(|s| {
+                s.add_network_entry(entry("GET", "http://localhost:8000/health", Some(200), None));
+                s.add_network_entry(entry("GET", "http://localhost:8000/metrics", Some(401), None));
+                s.add_network_entry(entry("POST", "http://localhost:8000/api/v1/execute", None, Some("connection refused")));
+            })
+            .build();
+        let text = render_with(WIDTH, HEIGHT, |f| render(f, &state, f.area()));
+        crate::assert_ui_snapshot!("network", text);
+
+        state.network_view.detail = true;
+        let text = render_with(WIDTH, HEIGHT, |f| render(f, &state, f.area()));
+        crate::assert_ui_snapshot!("network_detail", text);
+    }
+}
@@ -0,0 +1,141 @@
+//! Pipeline View - per-target step status of the refactor pipeline
+
+use crate::app::{pipeline::StepStatus, AppState};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Row, Table, TableState, Wrap},
+    Frame,
+};
+
+pub fn render(f: &mut Frame, state: &AppState, area: Rect) {
+    f.render_widget(Clear, area);
+    let Some(run) = &state.pipeline_view.run else { return };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),    // Targets × steps
+            Constraint::Length(8), // Selected target
+            Constraint::Length(1), // Hints
+        ])
+        .split(area);
+
+    let mut header = vec!["Target".to_string()];
+    header.extend(run.steps.iter().cloned());
+    let header = Row::new(header).style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD));
+
+    let rows = run.targets.iter().map(|target| {
+        let mut cells = vec![Span::raw(target.target.label.clone())];
+        cells.extend(
+            target
+                .steps
+                .iter()
+                .map(|status| Span::styled(status.glyph(), Style::default().fg(status_color(status)))),
+        );
+        Row::new(cells)
+    });
+
+    let mut widths = vec![Constraint::Min(20)];
+    widths.extend(run.steps.iter().map(|name| Constraint::Length(name.chars().count().max(3) as u16)));
+
+    let title = format!(
+        "{}: {}/{} complete{}",
+        run.name,
+        run.completed(),
+        run.targets.len(),
+        match (run.is_running(), run.pause.is_paused()) {
+            (true, true) => " (paused)",
+            (true, false) => " (running)",
+            _ => "",
+        }
+    );
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(title)
+                .border_style(Style::default().fg(Color::Cyan)),
+        )
+        .highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD));
+    let mut table_state = TableState::default();
+    let selected = state.pipeline_view.selected.min(run.targets.len().saturating_sub(1));
+    table_state.select(Some(selected));
+    f.render_stateful_widget(table, chunks[0], &mut table_state);
+
+    // Artifacts (or the error) for the selected target
+    let mut lines = Vec::new();
+    if let Some(target) = run.targets.get(selected) {
+        for (i, status) in target.steps.iter().enumerate() {
+            let detail = match (status, &target.artifacts[i]) {
+                (StepStatus::Failed(error), _) => error.clone(),
+                (_, Some(path)) => path.display().to_string(),
+                (StepStatus::Running, None) => format!("running on {}", target.target.model_id),
+                _ => String::new(),
+            };
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("{} {}: ", status.glyph(), run.steps[i]),
+                    Style::default().fg(status_color(status)),
+                ),
+                Span::styled(detail, Style::default().fg(Color::Gray)),
+            ]));
+        }
+    }
+    let detail = Paragraph::new(lines).wrap(Wrap { trim: true }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Artifacts ({})", run.dir.display()))
+            .border_style(Style::default().fg(Color::DarkGray)),
+    );
+    f.render_widget(detail, chunks[1]);
+
+    let hints = Paragraph::new(" ↑/↓: Select target | p: Pause/Resume | Esc: Close").style(Style::default().fg(Color::DarkGray));
+    f.render_widget(hints, chunks[2]);
+}
+
+fn status_color(status: &StepStatus) -> Color {
+    match status {
+        StepStatus::Pending | StepStatus::Skipped => Color::DarkGray,
+        StepStatus::Running => Color::Yellow,
+        StepStatus::Done => Color::Green,
+        StepStatus::Failed(_) => Color::Red,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::pipeline::{targets, Output, Pause, PipelineJob, Scope};
+    use crate::testing::{render_with, StateBuilder, HEIGHT, WIDTH};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_snapshot() {
+        let files = [
+            (PathBuf::from("src/main.rs"), "gpt-4o".to_string()),
+            (PathBuf::from("src/lib.rs"), "gpt-4o".to_string()),
+        ];
+        let job = PipelineJob {
+            name: "Pipeline".to_string(),
+            dir: PathBuf::from(".ims/pipelines/run"),
+            steps: crate::app::config::PipelineConfig::default().steps,
+            targets: targets(&files, Scope::File),
+            concurrency: 1,
+            output: Output::default(),
+            pause: Pause::default(),
+        };
+        let mut state = StateBuilder::new().workspace().build();
+        state.start_pipeline(&job);
+        state.pipeline_step_started(0, 0);
+        let artifact = PathBuf::from(".ims/pipelines/run/main.rs/analyze.md");
+        state.pipeline_step_finished(0, 0, Ok((crate::testing::response("gpt-4o", "analysis"), artifact)), Some(850.0));
+        state.pipeline_step_started(0, 1);
+        state.pipeline_step_started(1, 0);
+        state.pipeline_step_finished(1, 0, Err("unreadable".to_string()), None);
+        let text = render_with(WIDTH, HEIGHT, |f| render(f, &state, f.area()));
+        crate::assert_ui_snapshot!("pipeline", text);
+    }
+}
@@ -0,0 +1,139 @@
+//! Profiler Overlay (F12) - frame timing and buffer sizes
+
+use crate::app::{profiler, AppState};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Sparkline},
+    Frame,
+};
+
+const WIDTH: u16 = 46;
+
+fn format_bytes(bytes: usize) -> String {
+    match bytes {
+        b if b >= 1 << 20 => format!("{:.1} MiB", b as f64 / (1 << 20) as f64),
+        b if b >= 1 << 10 => format!("{:.1} KiB", b as f64 / (1 << 10) as f64),
+        b => format!("{} B", b),
+    }
+}
+
+fn timing_style(millis: f64, warn: f64, bad: f64) -> Style {
+    Style::default().fg(if millis >= bad {
+        Color::Red
+    } else if millis >= warn {
+        Color::Yellow
+    } else {
+        Color::Green
+    })
+}
+
+pub fn render(f: &mut Frame, state: &AppState, area: Rect) {
+    let stats = &state.profiler;
+    let buffers = profiler::buffer_sizes(state);
+
+    let height = (10 + buffers.len() as u16 + 4).min(area.height);
+    let popup = Rect {
+        x: area.x + area.width.saturating_sub(WIDTH + 1),
+        y: area.y + 1,
+        width: WIDTH.min(area.width),
+        height,
+    };
+    f.render_widget(Clear, popup);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Profiler (F12)")
+        .border_style(Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD));
+    let inner = block.inner(popup);
+    f.render_widget(block, popup);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(3)])
+        .split(inner);
+
+    let render_ms = stats.last_render.as_secs_f64() * 1000.0;
+    let lag_ms = stats.loop_lag.as_secs_f64() * 1000.0;
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::raw("FPS:          "),
+            Span::styled(stats.fps().to_string(), Style::default().fg(Color::Cyan)),
+        ]),
+        Line::from(vec![
+            Span::raw("Render:       "),
+            Span::styled(format!("{:.2} ms", render_ms), timing_style(render_ms, 8.0, 16.0)),
+            Span::styled(
+                format!("  (max {:.2})", stats.max_render.as_secs_f64() * 1000.0),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]),
+        Line::from(vec![
+            Span::raw("Loop lag:     "),
+            Span::styled(format!("{:.1} ms", lag_ms), timing_style(lag_ms, 20.0, 100.0)),
+            Span::styled(
+                format!("  (max {:.1})", stats.max_loop_lag.as_secs_f64() * 1000.0),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]),
+        Line::from(format!(
+            "Backlog:      api {}  core {}",
+            stats.api_backlog, stats.core_backlog
+        )),
+        Line::from(Span::styled(
+            format!(
+                "Overflow:     api {}/{}  core {}/{}  (coalesced/dropped)",
+                stats.api_overflow.coalesced,
+                stats.api_overflow.dropped,
+                stats.core_overflow.coalesced,
+                stats.core_overflow.dropped
+            ),
+            if stats.api_overflow.dropped + stats.core_overflow.dropped > 0 {
+                Style::default().fg(Color::Yellow)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            },
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Buffers",
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+        )),
+    ];
+
+    let mut total = 0;
+    for (name, items, bytes) in &buffers {
+        total += bytes;
+        lines.push(Line::from(format!("{:<15}{:>7} items {:>10}", name, items, format_bytes(*bytes))));
+    }
+    lines.push(Line::from(Span::styled(
+        format!("{:<28}{:>10}", "Total", format_bytes(total)),
+        Style::default().add_modifier(Modifier::BOLD),
+    )));
+
+    f.render_widget(Paragraph::new(lines), chunks[0]);
+
+    let history: Vec<u64> = stats.render_history.iter().copied().collect();
+    let sparkline = Sparkline::default()
+        .block(Block::default().title("Render time"))
+        .data(&history)
+        .style(Style::default().fg(Color::Magenta));
+    f.render_widget(sparkline, chunks[1]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, render_with, StateBuilder};
+
+    #[test]
+    fn test_snapshot() {
+        let mut state = StateBuilder::representative();
+        state.profiler.api_backlog = 3;
+        state.profiler.api_overflow.coalesced = 2;
+        let text = render_with(testing::WIDTH, testing::HEIGHT, |f| render(f, &state, f.area()));
+        crate::assert_ui_snapshot!("profiler", text);
+    }
+}
@@ -0,0 +1,89 @@
+//! Recovery Prompt - offers back unsent work after an unclean exit
+
+use crate::app::recovery::RecoveredWork;
+use chrono::{Local, TimeZone};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+/// Longest prompt preview before it is cut short
+const PREVIEW_CHARS: usize = 120;
+
+pub fn render(f: &mut Frame, work: &RecoveredWork, area: Rect) {
+    let width = 64.min(area.width);
+    let height = 10.min(area.height);
+    let popup = Rect {
+        x: area.x + area.width.saturating_sub(width) / 2,
+        y: area.y + area.height.saturating_sub(height) / 2,
+        width,
+        height,
+    };
+    f.render_widget(Clear, popup);
+
+    let saved = Local
+        .timestamp_opt(work.saved_at, 0)
+        .single()
+        .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default();
+    let mut lines = vec![Line::from(format!("The last session ended unexpectedly (saved {}).", saved))];
+    lines.push(Line::from(""));
+    if !work.prompt.is_empty() {
+        let mut preview: String = work.prompt.chars().take(PREVIEW_CHARS).collect();
+        if work.prompt.chars().count() > PREVIEW_CHARS {
+            preview.push('…');
+        }
+        lines.push(Line::from(vec![
+            Span::styled("Prompt: ", Style::default().fg(Color::Gray)),
+            Span::styled(preview, Style::default().fg(Color::White)),
+        ]));
+    }
+    if !work.generation.is_empty() {
+        let file = work
+            .file
+            .as_ref()
+            .and_then(|p| p.file_name())
+            .map(|n| format!(" for {}", n.to_string_lossy()))
+            .unwrap_or_default();
+        lines.push(Line::from(vec![
+            Span::styled("Output: ", Style::default().fg(Color::Gray)),
+            Span::styled(
+                format!("{} lines{}", work.generation.lines().count(), file),
+                Style::default().fg(Color::White),
+            ),
+        ]));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "y/Enter: Restore | n/Esc: Discard",
+        Style::default().fg(Color::DarkGray),
+    )));
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Restore Unsent Work?")
+        .border_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+    f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }).block(block), popup);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{render_with, HEIGHT, WIDTH};
+
+    #[test]
+    fn test_snapshot() {
+        let work = RecoveredWork {
+            prompt: "Add retry with exponential backoff to fetch_models".to_string(),
+            generation: "async fn fetch_models() {\n    todo!()\n}\n".to_string(),
+            file: Some("src/api.rs".into()),
+            model_id: Some("gpt-4o".to_string()),
+            saved_at: 1_768_478_400,
+        };
+        let text = render_with(WIDTH, HEIGHT, |f| render(f, &work, f.area()));
+        crate::assert_ui_snapshot!("recovery", text);
+    }
+}
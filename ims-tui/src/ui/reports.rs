@@ -0,0 +1,128 @@
+//! Reports View - usage rollups from `.ims/usage.jsonl`
+
+use crate::app::{
+    usage::{self, Rollup},
+    AppState,
+};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Row, Table},
+    Frame,
+};
+
+pub fn render(f: &mut Frame, state: &AppState, area: Rect) {
+    f.render_widget(Clear, area);
+    let view = &state.reports_view;
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Totals
+            Constraint::Min(0),    // Rollups + models
+            Constraint::Length(1), // Hints
+        ])
+        .split(area);
+
+    let cost: f64 = view.records.iter().map(|r| r.cost).sum();
+    let tokens: u64 = view.records.iter().map(|r| u64::from(r.total_tokens)).sum();
+    let totals = Paragraph::new(Line::from(vec![
+        Span::styled(format!(" {} requests", view.records.len()), Style::default().fg(Color::White)),
+        Span::styled(
+            format!(" | {} tokens", crate::app::tokens::format_token_count(tokens)),
+            Style::default().fg(Color::Green),
+        ),
+        Span::styled(format!(" | ${:.4}", cost), Style::default().fg(Color::Yellow)),
+    ]))
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Usage Reports")
+            .border_style(Style::default().fg(Color::Cyan)),
+    );
+    f.render_widget(totals, chunks[0]);
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(chunks[1]);
+
+    let periods = usage::rollups(&view.records, view.period);
+    let scroll = view.scroll.min(periods.len().saturating_sub(1));
+    render_rollups(
+        f,
+        &periods[scroll..],
+        &format!("{} ({})", view.period.label(), periods.len()),
+        "Period",
+        columns[0],
+    );
+    render_rollups(f, &usage::top_models(&view.records), "Top Models by Spend", "Model", columns[1]);
+
+    let hints = Paragraph::new(" Tab/w: Daily/Weekly | ↑/↓: Scroll | e: Export CSV | Esc: Close")
+        .style(Style::default().fg(Color::DarkGray));
+    f.render_widget(hints, chunks[2]);
+}
+
+fn render_rollups(f: &mut Frame, rollups: &[Rollup], title: &str, label: &str, area: Rect) {
+    let header = Row::new([label, "Requests", "Tokens", "Cost"])
+        .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD));
+
+    let rows = rollups.iter().map(|rollup| {
+        Row::new(vec![
+            rollup.label.clone(),
+            rollup.requests.to_string(),
+            crate::app::tokens::format_token_count(rollup.tokens),
+            format!("${:.4}", rollup.cost),
+        ])
+    });
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(12),
+            Constraint::Length(9),
+            Constraint::Length(8),
+            Constraint::Length(10),
+        ],
+    )
+    .header(header)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(title.to_string())
+            .border_style(Style::default().fg(Color::DarkGray)),
+    );
+    f.render_widget(table, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::usage::UsageRecord;
+    use crate::testing::{render_with, StateBuilder, HEIGHT, WIDTH};
+
+    #[test]
+    fn test_snapshot() {
+        // Midday, so the rollup lands on one day in any timezone
+        let record = |timestamp, model_id: &str, total_tokens, cost| UsageRecord {
+            timestamp,
+            model_id: model_id.to_string(),
+            input_tokens: total_tokens / 2,
+            output_tokens: total_tokens / 2,
+            total_tokens,
+            cost,
+        };
+        let state = StateBuilder::new()
+            .with(|s| {
+                s.reports_view.records = vec![
+                    record(1_768_478_400, "gpt-4o", 1_200, 0.012),
+                    record(1_768_482_000, "claude-3-haiku", 800, 0.001),
+                    record(1_768_564_800, "gpt-4o", 2_000, 0.02),
+                ];
+            })
+            .build();
+        let text = render_with(WIDTH, HEIGHT, |f| render(f, &state, f.area()));
+        crate::assert_ui_snapshot!("reports", text);
+    }
+}
@@ -0,0 +1,130 @@
+//! Schedules View - `[[schedules]]` with their next run and how the last
+//! one went
+
+use super::task_list::{self, TaskList};
+use crate::app::{
+    schedule::{Schedule, Task},
+    AppState,
+};
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Color, Style},
+    text::Span,
+    widgets::Row,
+    Frame,
+};
+
+pub fn render(f: &mut Frame, state: &AppState, area: Rect) {
+    let view = &state.schedules_view;
+    let now = chrono::Utc::now().timestamp();
+    let rows = view
+        .schedules
+        .iter()
+        .map(|schedule| {
+            let task = match &schedule.task {
+                Task::Prompt { model: Some(model), .. } => format!("prompt ({})", model),
+                Task::Prompt { model: None, .. } => "prompt".to_string(),
+                Task::Workflow(name) => format!("workflow {}", name),
+            };
+            Row::new(vec![
+                Span::raw(schedule.name.clone()),
+                Span::raw(task),
+                Span::raw(schedule.cadence.label()),
+                next_cell(schedule),
+                match &schedule.last {
+                    Some(last) => Span::styled(
+                        format!(
+                            "{} {} ({})",
+                            if last.ok { "✓" } else { "✗" },
+                            last.outcome,
+                            super::inspector::format_age(now - last.at)
+                        ),
+                        Style::default().fg(if last.ok { Color::Green } else { Color::Red }),
+                    ),
+                    None => Span::styled("never", Style::default().fg(Color::DarkGray)),
+                },
+            ])
+        })
+        .collect();
+
+    let title = if view.schedules.is_empty() && view.errors.is_empty() {
+        "Schedules (none, add [[schedules]] to config.toml)".to_string()
+    } else {
+        format!("Schedules ({})", view.schedules.len())
+    };
+    let list = TaskList {
+        title,
+        header: &["Schedule", "Runs", "When", "Next", "Last"],
+        widths: &[
+            Constraint::Length(20),
+            Constraint::Length(24),
+            Constraint::Length(16),
+            Constraint::Length(18),
+            Constraint::Min(20),
+        ],
+        rows,
+        selected: view.selected,
+        errors: &view.errors,
+        hints: "↑/↓: Select | Enter: Run now | Space: Pause/Resume | Esc: Close",
+    };
+    task_list::render(f, area, list);
+}
+
+fn next_cell(schedule: &Schedule) -> Span<'static> {
+    if schedule.running {
+        Span::styled("running...", Style::default().fg(Color::Yellow))
+    } else if schedule.paused {
+        Span::styled("paused", Style::default().fg(Color::DarkGray))
+    } else {
+        Span::raw(schedule.next.format("%a %d %b %H:%M").to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::schedule::{Cadence, LastRun};
+    use crate::testing::{render_with, StateBuilder, HEIGHT, WIDTH};
+    use chrono::{Duration, Local, NaiveTime, TimeZone};
+
+    #[test]
+    fn test_snapshot() {
+        let next = Local.with_ymd_and_hms(2024, 5, 2, 2, 0, 0).unwrap();
+        let nightly = Schedule {
+            name: "nightly-summary".to_string(),
+            task: Task::Workflow("summarize".to_string()),
+            cadence: Cadence::Daily(NaiveTime::from_hms_opt(2, 0, 0).unwrap()),
+            files: vec!["src".into()],
+            changed_only: true,
+            next,
+            last: Some(LastRun {
+                at: chrono::Utc::now().timestamp(),
+                ok: true,
+                outcome: "4 of 4 target(s) completed".to_string(),
+                ok_at: None,
+            }),
+            paused: false,
+            running: false,
+            started: None,
+        };
+        let standup = Schedule {
+            name: "standup".to_string(),
+            task: Task::Prompt {
+                text: "What changed yesterday?".to_string(),
+                model: Some("gpt-4o".to_string()),
+            },
+            cadence: Cadence::Every(Duration::minutes(30)),
+            paused: true,
+            last: None,
+            ..nightly.clone()
+        };
+        let state = StateBuilder::new()
+            .with(|s| {
+                s.schedules_view.schedules = vec![nightly, standup];
+                s.schedules_view.errors = vec!["weekly: set `at` or `every_mins`".to_string()];
+            })
+            .build();
+        let text = render_with(WIDTH, HEIGHT, |f| render(f, &state, f.area()));
+        crate::assert_ui_snapshot!("schedules", text);
+    }
+}
@@ -0,0 +1,171 @@
+//! Search View - semantic search over the indexed workspace, with results
+//! attachable as prompt context
+
+use crate::app::AppState;
+use crate::ui::symbols;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+use unicode_width::UnicodeWidthStr;
+
+pub fn render(f: &mut Frame, state: &AppState, area: Rect) {
+    f.render_widget(Clear, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Query
+            Constraint::Min(0),    // Results + preview
+            Constraint::Length(1), // Hints
+        ])
+        .split(area);
+
+    render_query(f, state, chunks[0]);
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+        .split(chunks[1]);
+    render_results(f, state, columns[0]);
+    render_preview(f, state, columns[1]);
+
+    f.render_widget(
+        Paragraph::new(" Enter: Search | ↑/↓: Select | Tab: Attach/detach | Ctrl+A: Attach all | Esc: Close")
+            .style(Style::default().fg(Color::DarkGray)),
+        chunks[2],
+    );
+}
+
+fn render_query(f: &mut Frame, state: &AppState, area: Rect) {
+    let view = &state.search_view;
+    let mut title = "Semantic Search".to_string();
+    if let Some((done, total)) = view.indexing {
+        title.push_str(&format!(" [indexing {}/{} files]", done, total));
+    } else if view.searching {
+        title.push_str(" [searching…]");
+    }
+    let query = Paragraph::new(view.query.as_str()).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_style(Style::default().fg(Color::Cyan)),
+    );
+    f.render_widget(query, area);
+    f.set_cursor_position((area.x + 1 + view.query.cursor_column(), area.y + 1));
+}
+
+fn render_results(f: &mut Frame, state: &AppState, area: Rect) {
+    let view = &state.search_view;
+    let attachment = symbols::symbols(state).attachment;
+    let items: Vec<ListItem> = view
+        .hits
+        .iter()
+        .map(|hit| {
+            let marker = if state.is_hit_attached(hit) {
+                attachment.to_string()
+            } else {
+                " ".repeat(attachment.width())
+            };
+            ListItem::new(vec![
+                Line::from(vec![
+                    Span::styled(format!("{} ", marker), Style::default().fg(Color::Blue)),
+                    Span::styled(format!("{:.2} ", hit.score), Style::default().fg(Color::DarkGray)),
+                    Span::styled(hit.location(), Style::default().fg(Color::Yellow)),
+                ]),
+                Line::from(Span::raw(format!(
+                    "    {}",
+                    hit.text.lines().find(|l| !l.trim().is_empty()).unwrap_or("").trim()
+                ))),
+            ])
+        })
+        .collect();
+
+    let title = if view.searched.is_empty() {
+        "Results".to_string()
+    } else {
+        format!("Results for \"{}\" ({})", view.searched, view.hits.len())
+    };
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(title)
+                .border_style(Style::default().fg(Color::Cyan)),
+        )
+        .highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD));
+    let mut list_state = ListState::default();
+    if !view.hits.is_empty() {
+        list_state.select(Some(view.selected));
+    }
+    f.render_stateful_widget(list, area, &mut list_state);
+}
+
+fn render_preview(f: &mut Frame, state: &AppState, area: Rect) {
+    let view = &state.search_view;
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Preview")
+        .border_style(Style::default().fg(Color::DarkGray));
+    let paragraph = match (view.selected_hit(), &view.error) {
+        (_, Some(error)) => Paragraph::new(error.as_str()).style(Style::default().fg(Color::Red)),
+        (Some(hit), None) => {
+            let lines: Vec<Line> = hit
+                .text
+                .lines()
+                .enumerate()
+                .map(|(i, line)| {
+                    Line::from(vec![
+                        Span::styled(format!("{:>4} ", hit.start_line + i), Style::default().fg(Color::DarkGray)),
+                        Span::raw(line.to_string()),
+                    ])
+                })
+                .collect();
+            Paragraph::new(lines)
+        }
+        (None, None) => Paragraph::new(
+            "Type a query and press Enter. Run \"Search: Index Workspace\" first, and again after files change.",
+        )
+        .style(Style::default().fg(Color::DarkGray))
+        .wrap(Wrap { trim: true }),
+    };
+    f.render_widget(paragraph.block(block), area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::embeddings::SearchHit;
+    use crate::testing::{render_with, StateBuilder, HEIGHT, WIDTH};
+
+    #[test]
+    fn test_snapshot() {
+        let hit = SearchHit {
+            path: "src/parser.rs".into(),
+            start_line: 41,
+            end_line: 43,
+            text: "fn parse(tokens: &[Token]) -> Ast {\n    Parser::new(tokens).parse()\n}".to_string(),
+            score: 0.82,
+        };
+        let other = SearchHit {
+            path: "src/lexer.rs".into(),
+            start_line: 1,
+            end_line: 2,
+            text: "\npub fn tokenize(input: &str) -> Vec<Token> {".to_string(),
+            score: 0.61,
+        };
+        let state = StateBuilder::new()
+            .with(|s| {
+                s.search_view.query.set("where are tokens parsed");
+                s.search_view.searched = "where are tokens parsed".to_string();
+                s.search_view.hits = vec![hit.clone(), other];
+                s.search_context = vec![hit];
+            })
+            .build();
+        let text = render_with(WIDTH, HEIGHT, |f| render(f, &state, f.area()));
+        crate::assert_ui_snapshot!("search", text);
+    }
+}
@@ -1,11 +1,12 @@
 //! Settings Overlay Modal
 
 use crate::app::AppState;
+use crate::ui::symbols;
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
     Frame,
 };
 
@@ -22,17 +23,21 @@ pub fn render(f: &mut Frame, state: &AppState, area: Rect) {
         .constraints([
             Constraint::Length(3),  // Title
             Constraint::Min(0),     // Options
+            Constraint::Length(3),  // Description of the selected option
+            Constraint::Length(7),  // Status
             Constraint::Length(3),  // Footer
         ])
         .split(popup_area);
 
-    render_title(f, sections[0]);
+    render_title(f, state, sections[0]);
     render_options(f, state, sections[1]);
-    render_footer(f, sections[2]);
+    render_description(f, state, sections[2]);
+    render_status(f, state, sections[3]);
+    render_footer(f, sections[4]);
 }
 
-fn render_title(f: &mut Frame, area: Rect) {
-    let title = Paragraph::new("⚙️  IMS-TUI Settings")
+fn render_title(f: &mut Frame, state: &AppState, area: Rect) {
+    let title = Paragraph::new(format!("{}  IMS-TUI Settings", symbols::symbols(state).settings))
         .alignment(Alignment::Center)
         .block(
             Block::default()
@@ -49,37 +54,24 @@ fn render_title(f: &mut Frame, area: Rect) {
 }
 
 fn render_options(f: &mut Frame, state: &AppState, area: Rect) {
-    let token_usage = format!("{} tokens", state.total_tokens_used);
-    let total_cost = format!("${:.4}", state.total_cost);
-    let debug_logs = format!("{} entries", state.debug_logs.len());
-
-    let options = vec![
-        ("Auto-scroll", if state.global_auto_scroll { "Enabled" } else { "Disabled" }),
-        ("API Endpoint", state.api_base_url.as_str()),
-        ("API Status", if state.api_connected { "🟢 Connected" } else { "🔴 Disconnected" }),
-        ("Token Usage", token_usage.as_str()),
-        ("Total Cost", total_cost.as_str()),
-        ("Debug Logs", debug_logs.as_str()),
-    ];
-
-    let items: Vec<ListItem> = options
+    let items: Vec<ListItem> = state
+        .settings
+        .items
         .iter()
         .enumerate()
-        .map(|(i, (label, value))| {
-            let style = if i == state.settings_index {
+        .map(|(i, item)| {
+            let selected = i == state.settings.selected_index;
+            let style = if selected {
                 Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)
             } else {
                 Style::default().fg(Color::Yellow)
             };
 
             ListItem::new(Line::from(vec![
+                Span::styled(format!("{:<20}", item.label), style),
                 Span::styled(
-                    format!("{:<20}", label),
-                    style,
-                ),
-                Span::styled(
-                    *value,
-                    if i == state.settings_index { style } else { Style::default().fg(Color::White) },
+                    item.value.display(),
+                    if selected { style } else { Style::default().fg(Color::White) },
                 ),
             ]))
         })
@@ -93,11 +85,60 @@ fn render_options(f: &mut Frame, state: &AppState, area: Rect) {
                 .border_style(Style::default().fg(Color::White)),
         );
 
-    f.render_widget(list, area);
+    // Scrolled to keep the selected option in view
+    let mut list_state = ListState::default().with_selected(Some(state.settings.selected_index));
+    f.render_stateful_widget(list, area, &mut list_state);
+}
+
+fn render_description(f: &mut Frame, state: &AppState, area: Rect) {
+    let text = state.settings.selected().map(|item| item.description.as_str()).unwrap_or("");
+    let description = Paragraph::new(text)
+        .style(Style::default().fg(Color::Gray).add_modifier(Modifier::ITALIC))
+        .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::DarkGray)));
+
+    f.render_widget(description, area);
+}
+
+/// Read-only connection and usage figures
+fn render_status(f: &mut Frame, state: &AppState, area: Rect) {
+    let sym = symbols::symbols(state);
+    let api_status = if state.reconnect.is_some() {
+        format!("{} Reconnecting", sym.reconnecting)
+    } else if state.api_connected {
+        format!("{} Connected", sym.connected)
+    } else {
+        format!("{} Disconnected (R: Reconnect)", sym.disconnected)
+    };
+
+    let rows = [
+        ("API Endpoint", state.api_base_url.clone()),
+        ("API Status", api_status),
+        ("Token Usage", format!("{} tokens", state.total_tokens_used)),
+        ("Total Cost", format!("${:.4}", state.total_cost)),
+        ("Debug Logs", format!("{} entries", state.debug_logs.len())),
+    ];
+    let lines: Vec<Line> = rows
+        .into_iter()
+        .map(|(label, value)| {
+            Line::from(vec![
+                Span::styled(format!("{:<20}", label), Style::default().fg(Color::Yellow)),
+                Span::raw(value),
+            ])
+        })
+        .collect();
+
+    let status = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Status")
+            .border_style(Style::default().fg(Color::White)),
+    );
+
+    f.render_widget(status, area);
 }
 
 fn render_footer(f: &mut Frame, area: Rect) {
-    let footer = Paragraph::new("Press Esc to close | Press R to refresh API connection")
+    let footer = Paragraph::new("↑↓: Select | ←→/Enter: Change | R: Reconnect | H: Health | Esc: Close")
         .alignment(Alignment::Center)
         .block(
             Block::default()
@@ -133,6 +174,7 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::{render_with, StateBuilder, HEIGHT, WIDTH};
 
     #[test]
     fn test_centered_rect() {
@@ -142,4 +184,13 @@ mod tests {
         assert_eq!(centered.width, 60);
         assert_eq!(centered.height, 35); // 70% of 50
     }
+
+    #[test]
+    fn test_snapshot() {
+        let mut state = StateBuilder::new().build();
+        state.settings = crate::app::settings::SettingsState::from_state(&state);
+        state.settings.selected_index = 3;
+        let text = render_with(WIDTH, HEIGHT, |f| render(f, &state, f.area()));
+        crate::assert_ui_snapshot!("settings", text);
+    }
 }
@@ -1,11 +1,11 @@
 //! Sidebar - File Explorer
 
-use crate::app::{AppState, FileNode, FocusPane};
-use crate::ui::focus_border_style;
+use crate::app::{tokens::format_token_count, AppState, FileNode, FocusPane};
+use crate::ui::{focus_border_style, pane_hint, symbols::Symbols};
 use ratatui::{
     layout::Rect,
     style::{Color, Modifier, Style},
-    text::Span,
+    text::{Line, Span},
     widgets::{Block, Borders},
     Frame,
 };
@@ -15,13 +15,18 @@ pub fn render(f: &mut Frame, state: &AppState, area: Rect) {
     let is_focused = state.focus == FocusPane::Sidebar;
 
     // recursive helper to build tree items
-    fn build_tree_items(nodes: &[FileNode]) -> Vec<TreeItem<String>> {
+    fn build_tree_items<'a>(
+        nodes: &'a [FileNode],
+        attachments: &[std::path::PathBuf],
+        marked: &[std::path::PathBuf],
+        sym: &Symbols,
+    ) -> Vec<TreeItem<'a, String>> {
         nodes.iter().map(|node| {
-            let label = Span::styled(
+            let name = Span::styled(
                 if node.is_dir {
-                    format!("📁 {}", node.name)
+                    format!("{} {}", sym.folder, node.name)
                 } else {
-                    format!("📄 {}", node.name)
+                    format!("{} {}", sym.file, node.name)
                 },
                 if node.is_dir {
                     Style::default().fg(Color::Blue)
@@ -29,22 +34,57 @@ pub fn render(f: &mut Frame, state: &AppState, area: Rect) {
                     Style::default().fg(Color::White)
                 }
             );
+
+            let mut spans = Vec::new();
+            if marked.contains(&node.path) {
+                spans.push(Span::styled(format!("{} ", sym.marked), Style::default().fg(Color::Magenta)));
+            }
+            spans.push(name);
+            if !node.is_dir && node.tokens > 0 {
+                spans.push(Span::styled(
+                    format!(" {}", format_token_count(node.tokens as u64)),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            if let Some(badge) = crate::ui::symbols::agent_status(sym, node.status) {
+                spans.push(Span::styled(format!(" {}", badge), Style::default().fg(node.status.color())));
+            }
+            if attachments.contains(&node.path) {
+                spans.push(Span::styled(format!(" {}", sym.attachment), Style::default().fg(Color::Yellow)));
+            }
+            let label = Line::from(spans);
             
-            let children = build_tree_items(&node.children);
+            let children = build_tree_items(&node.children, attachments, marked, sym);
             TreeItem::new(node.id.clone(), label, children)
                 .expect("Duplicate tree item ID")
         }).collect()
     }
 
-    let items = build_tree_items(&state.file_tree);
+    let items = build_tree_items(
+        &state.file_tree,
+        &state.attachments,
+        &state.marked_files,
+        crate::ui::symbols::symbols(state),
+    );
+
+    let workspace_tokens = state.workspace_tokens();
+    let mut title = if workspace_tokens > 0 {
+        format!("Explorer ({} tok)", format_token_count(workspace_tokens))
+    } else {
+        "Explorer".to_string()
+    };
+    if !state.marked_files.is_empty() {
+        title.push_str(&format!(" [{} marked]", state.marked_files.len()));
+    }
 
     let tree = Tree::new(&items)
         .expect("Duplicate tree item ID")
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("Explorer")
-                .border_style(focus_border_style(is_focused)),
+                .title(title)
+                .title(pane_hint(state, FocusPane::Sidebar))
+                .border_style(focus_border_style(state, is_focused)),
         )
         .highlight_style(
             Style::default()
@@ -63,4 +103,27 @@ pub fn render(f: &mut Frame, state: &AppState, area: Rect) {
     let mut tree_state = state.tree_state.borrow_mut();
     
     f.render_stateful_widget(tree, area, &mut *tree_state);
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{render_with, StateBuilder};
+
+    #[test]
+    fn test_snapshot() {
+        let mut state = StateBuilder::new()
+            .workspace()
+            .with(|s| {
+                s.marked_files.push("src/lib.rs".into());
+                s.file_tree[0].children[0].status = crate::app::AgentStatus::Working;
+            })
+            .build();
+        let text = render_with(40, 10, |f| render(f, &state, f.area()));
+        crate::assert_ui_snapshot!("sidebar", text);
+
+        state.ascii_mode = true;
+        let text = render_with(40, 10, |f| render(f, &state, f.area()));
+        crate::assert_ui_snapshot!("sidebar_ascii", text);
+    }
+}
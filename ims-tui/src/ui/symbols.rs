@@ -0,0 +1,125 @@
+//! Display Symbols
+//!
+//! Emoji used across panes, with ASCII stand-ins for terminals and fonts
+//! that render emoji poorly (Linux console, non-UTF-8 locales).
+
+use crate::app::{AgentStatus, AppState};
+
+pub struct Symbols {
+    pub connected: &'static str,
+    pub disconnected: &'static str,
+    pub reconnecting: &'static str,
+    pub folder: &'static str,
+    pub file: &'static str,
+    pub attachment: &'static str,
+    pub image: &'static str,
+    pub marked: &'static str,
+    pub auto_scroll: &'static str,
+    pub manual_scroll: &'static str,
+    pub pending: &'static str,
+    pub warning: &'static str,
+    pub locked: &'static str,
+    pub settings: &'static str,
+    /// Between breadcrumbs
+    pub separator: &'static str,
+}
+
+pub const EMOJI: Symbols = Symbols {
+    connected: "🟢",
+    disconnected: "🔴",
+    reconnecting: "🟡",
+    folder: "📁",
+    file: "📄",
+    attachment: "📎",
+    image: "🖼",
+    marked: "☑",
+    auto_scroll: "🔄",
+    manual_scroll: "📌",
+    pending: "⏸",
+    warning: "⚠",
+    locked: "🔒",
+    settings: "⚙️",
+    separator: "›",
+};
+
+pub const ASCII: Symbols = Symbols {
+    connected: "[+]",
+    disconnected: "[x]",
+    reconnecting: "[~]",
+    folder: "[D]",
+    file: "[F]",
+    attachment: "@",
+    image: "[img]",
+    marked: "[*]",
+    auto_scroll: ">>",
+    manual_scroll: "||",
+    pending: "..",
+    warning: "!",
+    locked: "(admin)",
+    settings: "*",
+    separator: ">",
+};
+
+/// Explorer badge for a file's agent status (none while idle)
+pub fn agent_status(sym: &Symbols, status: AgentStatus) -> Option<&'static str> {
+    match status {
+        AgentStatus::Idle => None,
+        AgentStatus::Working => Some(sym.connected),
+        AgentStatus::Error => Some(sym.disconnected),
+        AgentStatus::Paused => Some(sym.pending),
+    }
+}
+
+/// Symbol set for the current display mode
+pub fn symbols(state: &AppState) -> &'static Symbols {
+    if state.ascii_mode {
+        &ASCII
+    } else {
+        &EMOJI
+    }
+}
+
+/// Whether the terminal likely can't show emoji, judged from `TERM` and
+/// the locale variables (first of `LC_ALL`, `LC_CTYPE`, `LANG` that is set)
+pub fn detect_ascii(term: Option<&str>, locale: Option<&str>) -> bool {
+    if matches!(term, Some("linux" | "dumb" | "vt100" | "vt220" | "ansi")) {
+        return true;
+    }
+    match locale {
+        Some(locale) => {
+            let locale = locale.to_ascii_lowercase();
+            !(locale.contains("utf-8") || locale.contains("utf8"))
+        }
+        None => false,
+    }
+}
+
+/// `detect_ascii` for the current process environment
+pub fn detect_ascii_from_env() -> bool {
+    let term = std::env::var("TERM").ok();
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .find_map(|name| std::env::var(name).ok().filter(|v| !v.is_empty()));
+    detect_ascii(term.as_deref(), locale.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_ascii() {
+        assert!(detect_ascii(Some("linux"), Some("en_US.UTF-8")));
+        assert!(detect_ascii(Some("xterm-256color"), Some("C")));
+        assert!(detect_ascii(Some("xterm-256color"), Some("POSIX")));
+        assert!(!detect_ascii(Some("xterm-256color"), Some("en_US.utf8")));
+        assert!(!detect_ascii(Some("xterm-256color"), None));
+    }
+
+    #[test]
+    fn test_ascii_symbols_are_ascii() {
+        for s in [ASCII.connected, ASCII.folder, ASCII.pending, ASCII.settings] {
+            assert!(s.is_ascii());
+        }
+    }
+}
@@ -0,0 +1,64 @@
+//! Task List - the layout the Watches and Schedules views share: a table of
+//! entries, the config entries that didn't parse, and the view's keys
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Clear, Paragraph, Row, Table, TableState, Wrap},
+    Frame,
+};
+
+/// What one view lists
+pub struct TaskList<'a> {
+    pub title: String,
+    pub header: &'a [&'a str],
+    pub widths: &'a [Constraint],
+    pub rows: Vec<Row<'a>>,
+    pub selected: usize,
+    /// Invalid config entries, the first five shown
+    pub errors: &'a [String],
+    pub hints: &'a str,
+}
+
+pub fn render(f: &mut Frame, area: Rect, list: TaskList) {
+    f.render_widget(Clear, area);
+
+    let errors = list.errors.len().min(5) as u16;
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),                                        // Entries
+            Constraint::Length(if errors > 0 { errors + 2 } else { 0 }), // Invalid entries
+            Constraint::Length(1),                                     // Hints
+        ])
+        .split(area);
+
+    let header = Row::new(list.header.iter().copied())
+        .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD));
+    let mut table_state = TableState::default();
+    if !list.rows.is_empty() {
+        table_state.select(Some(list.selected));
+    }
+    let table = Table::new(list.rows, list.widths.iter().copied())
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(list.title)
+                .border_style(Style::default().fg(Color::Cyan)),
+        )
+        .highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD));
+    f.render_stateful_widget(table, chunks[0], &mut table_state);
+
+    if errors > 0 {
+        let text: Vec<_> = list.errors.iter().map(|e| format!("✗ {}", e)).collect();
+        let errors = Paragraph::new(text.join("\n"))
+            .style(Style::default().fg(Color::Red))
+            .wrap(Wrap { trim: true })
+            .block(Block::default().borders(Borders::ALL).title("Invalid"));
+        f.render_widget(errors, chunks[1]);
+    }
+
+    let hints = Paragraph::new(format!(" {}", list.hints)).style(Style::default().fg(Color::DarkGray));
+    f.render_widget(hints, chunks[2]);
+}
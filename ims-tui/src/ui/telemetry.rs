@@ -0,0 +1,142 @@
+//! Telemetry Pane - live events from the IMS Core telemetry bus
+
+use crate::app::{telemetry::TelemetryKind, AppState};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+pub fn render(f: &mut Frame, state: &AppState, area: Rect) {
+    f.render_widget(Clear, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(4), // Connection + queue depths
+            Constraint::Min(0),    // Event stream
+            Constraint::Length(1), // Hints
+        ])
+        .split(area);
+
+    render_summary(f, state, chunks[0]);
+    render_events(f, state, chunks[1]);
+
+    let hints = Paragraph::new(" Esc/T: Close | c: Clear")
+        .style(Style::default().fg(Color::DarkGray));
+    f.render_widget(hints, chunks[2]);
+}
+
+fn render_summary(f: &mut Frame, state: &AppState, area: Rect) {
+    let count = |kind: TelemetryKind| state.telemetry_log.iter().filter(|e| e.kind == kind).count();
+
+    let mut queues: Vec<(&String, &u32)> = state.queue_depths.iter().collect();
+    queues.sort();
+    let queue_spans: Vec<Span> = if queues.is_empty() {
+        vec![Span::styled("no samples", Style::default().fg(Color::DarkGray))]
+    } else {
+        queues
+            .iter()
+            .map(|(name, depth)| {
+                Span::styled(
+                    format!("{}={}  ", name, depth),
+                    Style::default().fg(if **depth > 0 { Color::Yellow } else { Color::Green }),
+                )
+            })
+            .collect()
+    };
+
+    let mut queue_line = vec![Span::raw("Queues: ")];
+    queue_line.extend(queue_spans);
+
+    let lines = vec![
+        Line::from(vec![
+            Span::raw("Bus: "),
+            Span::styled(state.telemetry_status.as_str(), Style::default().fg(Color::Cyan)),
+            Span::raw(format!(
+                "   Routing: {}  Policy hits: {}  Executions: {}",
+                count(TelemetryKind::Routing),
+                count(TelemetryKind::Policy),
+                count(TelemetryKind::Execution)
+            )),
+        ]),
+        Line::from(queue_line),
+    ];
+
+    let summary = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Telemetry")
+            .border_style(Style::default().fg(Color::Cyan)),
+    );
+    f.render_widget(summary, area);
+}
+
+fn render_events(f: &mut Frame, state: &AppState, area: Rect) {
+    let visible = area.height.saturating_sub(2) as usize;
+
+    let lines: Vec<Line> = state
+        .telemetry_log
+        .iter()
+        .rev()
+        .take(visible)
+        .rev()
+        .map(|entry| {
+            Line::from(vec![
+                Span::styled(
+                    entry.time.format("%H:%M:%S ").to_string(),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(
+                    format!("{:<7}", entry.kind.label()),
+                    Style::default().fg(entry.kind.color()).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(format!("{} ", entry.event_type), Style::default().fg(Color::White)),
+                Span::styled(entry.summary.as_str(), Style::default().fg(Color::Gray)),
+            ])
+        })
+        .collect();
+
+    let events = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Events ({})", state.telemetry_log.len()))
+            .border_style(Style::default().fg(Color::DarkGray)),
+    );
+    f.render_widget(events, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::telemetry::TelemetryEntry;
+    use crate::testing::{render_with, StateBuilder, HEIGHT, WIDTH};
+
+    #[test]
+    fn test_snapshot() {
+        let state = StateBuilder::new()
+            .with(|s| {
+                s.telemetry_status = "Connected to models.events".to_string();
+                s.add_telemetry(TelemetryEntry {
+                    time: chrono::Local::now(),
+                    kind: TelemetryKind::Routing,
+                    event_type: "pcr.recommendation_generated".to_string(),
+                    summary: "gpt-4o for src/main.rs (cost)".to_string(),
+                    queue_depth: None,
+                });
+                s.add_telemetry(TelemetryEntry {
+                    time: chrono::Local::now(),
+                    kind: TelemetryKind::Error,
+                    event_type: "api.error".to_string(),
+                    summary: "upstream timeout".to_string(),
+                    queue_depth: None,
+                });
+                s.add_telemetry(TelemetryEntry::queue_depth("dlq.dead_letters", 3));
+            })
+            .build();
+        let text = render_with(WIDTH, HEIGHT, |f| render(f, &state, f.area()));
+        crate::assert_ui_snapshot!("telemetry", text);
+    }
+}
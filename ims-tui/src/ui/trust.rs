@@ -0,0 +1,61 @@
+//! Workspace Trust Prompt - asked before the first write into a workspace
+
+use crate::app::sandbox::TrustPrompt;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+pub fn render(f: &mut Frame, prompt: &TrustPrompt, area: Rect) {
+    let width = 64.min(area.width);
+    let height = 9.min(area.height);
+    let popup = Rect {
+        x: area.x + area.width.saturating_sub(width) / 2,
+        y: area.y + area.height.saturating_sub(height) / 2,
+        width,
+        height,
+    };
+    f.render_widget(Clear, popup);
+
+    let lines = vec![
+        Line::from("IMS-TUI wants to write files in"),
+        Line::from(Span::styled(
+            prompt.root.display().to_string(),
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Writes stay inside this directory. Trusting it is remembered in the config file.",
+            Style::default().fg(Color::Gray),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            "y: Trust and write | n/Esc: Don't write",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("Trust This Workspace? ({} pending)", prompt.pending.len()))
+        .border_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+    f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }).block(block), popup);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{render_with, HEIGHT, WIDTH};
+
+    #[test]
+    fn test_snapshot() {
+        let prompt = TrustPrompt {
+            root: "/home/dev/projects/api".into(),
+            pending: vec![Box::new(|_| {})],
+        };
+        let text = render_with(WIDTH, HEIGHT, |f| render(f, &prompt, f.area()));
+        crate::assert_ui_snapshot!("trust", text);
+    }
+}
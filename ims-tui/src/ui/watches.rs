@@ -0,0 +1,102 @@
+//! Watches View - files whose saves re-run a prompt or workflow
+
+use super::task_list::{self, TaskList};
+use crate::app::{schedule::Task, AppState};
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Color, Style},
+    text::Span,
+    widgets::Row,
+    Frame,
+};
+
+pub fn render(f: &mut Frame, state: &AppState, area: Rect) {
+    let view = &state.watch_view;
+    let rows = view
+        .bindings
+        .iter()
+        .map(|binding| {
+            let task = match &binding.task {
+                Task::Prompt { text, .. } => format!("prompt \"{}\"", text.lines().next().unwrap_or_default()),
+                Task::Workflow(name) => format!("workflow {}", name),
+            };
+            let (status, color) = match (binding.enabled, binding.due.is_some()) {
+                (false, _) => ("off", Color::DarkGray),
+                (true, true) => ("saved", Color::Yellow),
+                (true, false) => ("on", Color::Green),
+            };
+            Row::new(vec![
+                Span::styled(status, Style::default().fg(color)),
+                Span::raw(binding.label.clone()),
+                Span::raw(task),
+                Span::raw(format!("{}ms", binding.debounce.as_millis())),
+                Span::raw(binding.runs.to_string()),
+            ])
+        })
+        .collect();
+
+    let title = if view.bindings.is_empty() {
+        "Watches (none, run \"Watch: Re-run Last Prompt When Selected File Changes\")".to_string()
+    } else if state.file_watcher.is_none() {
+        format!("Watches ({}, watcher not running)", view.bindings.len())
+    } else {
+        format!("Watches ({})", view.bindings.len())
+    };
+    let list = TaskList {
+        title,
+        header: &["", "File", "Re-runs", "Debounce", "Runs"],
+        widths: &[
+            Constraint::Length(5),
+            Constraint::Length(32),
+            Constraint::Min(24),
+            Constraint::Length(8),
+            Constraint::Length(5),
+        ],
+        rows,
+        selected: view.selected,
+        errors: &view.errors,
+        hints: "↑/↓: Select | Space: On/Off | d: Remove | Esc: Close",
+    };
+    task_list::render(f, area, list);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::watch::WatchBinding;
+    use crate::testing::{render_with, StateBuilder, HEIGHT, WIDTH};
+    use std::time::Duration;
+
+    #[test]
+    fn test_snapshot() {
+        let review = WatchBinding {
+            file: "/work/src/parser.rs".into(),
+            label: "src/parser.rs".to_string(),
+            task: Task::Prompt {
+                text: "Review this for panics".to_string(),
+                model: None,
+            },
+            debounce: Duration::from_millis(500),
+            enabled: true,
+            due: None,
+            last_hash: None,
+            runs: 3,
+        };
+        let docs = WatchBinding {
+            file: "/work/src/lib.rs".into(),
+            label: "src/lib.rs".to_string(),
+            task: Task::Workflow("document".to_string()),
+            enabled: false,
+            runs: 0,
+            ..review.clone()
+        };
+        let state = StateBuilder::new()
+            .with(|s| {
+                s.watch_view.bindings = vec![review, docs];
+                s.watch_view.errors = vec!["notes.md: set `prompt` or `workflow`".to_string()];
+            })
+            .build();
+        let text = render_with(WIDTH, HEIGHT, |f| render(f, &state, f.area()));
+        crate::assert_ui_snapshot!("watches", text);
+    }
+}
@@ -0,0 +1,172 @@
+//! Workflows View - workflows under `.ims/workflows`, then a prompt for
+//! each parameter of the chosen one
+
+use crate::app::{
+    workflows::{ModelStrategy, ParamPrompt, Workflow},
+    AppState,
+};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+
+pub fn render(f: &mut Frame, state: &AppState, area: Rect) {
+    f.render_widget(Clear, area);
+    let view = &state.workflows_view;
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),    // List + details
+            Constraint::Length(1), // Hints
+        ])
+        .split(area);
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+        .split(chunks[0]);
+
+    let items: Vec<ListItem> = view
+        .workflows
+        .iter()
+        .map(|w| ListItem::new(format!("{} ({} steps)", w.name, w.steps.len())))
+        .chain(
+            view.errors
+                .iter()
+                .map(|e| ListItem::new(Span::styled(format!("✗ {}", e), Style::default().fg(Color::Red)))),
+        )
+        .collect();
+    let title = if items.is_empty() {
+        format!("Workflows (none in {})", crate::app::workflows::workflows_dir().display())
+    } else {
+        "Workflows".to_string()
+    };
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(title)
+                .border_style(Style::default().fg(Color::Cyan)),
+        )
+        .highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD));
+    let mut list_state = ListState::default();
+    if !view.workflows.is_empty() {
+        list_state.select(Some(view.selected));
+    }
+    f.render_stateful_widget(list, columns[0], &mut list_state);
+
+    if let Some(workflow) = view.selected_workflow() {
+        render_details(f, workflow, columns[1]);
+    }
+
+    let hints = if view.params.is_some() {
+        " Enter: Next | Ctrl+U: Clear | Esc: Back"
+    } else {
+        " ↑/↓: Select | Enter: Run on marked files | Esc: Close"
+    };
+    f.render_widget(
+        Paragraph::new(hints).style(Style::default().fg(Color::DarkGray)),
+        chunks[1],
+    );
+
+    if let Some(params) = &view.params {
+        render_param_prompt(f, params, area);
+    }
+}
+
+fn render_details(f: &mut Frame, workflow: &Workflow, area: Rect) {
+    let label = Style::default().fg(Color::Gray);
+    let model = match &workflow.model {
+        ModelStrategy::File => "each file's model".to_string(),
+        ModelStrategy::Session => "session model".to_string(),
+        ModelStrategy::Cheapest => "cheapest active model".to_string(),
+        ModelStrategy::Fixed(model_id) => model_id.clone(),
+    };
+    let mut lines = vec![
+        Line::from(workflow.description.as_str()),
+        Line::from(""),
+        Line::from(vec![Span::styled("Scope: ", label), Span::raw(format!("{:?}", workflow.scope))]),
+        Line::from(vec![Span::styled("Model: ", label), Span::raw(model)]),
+    ];
+    let params = workflow.parameters();
+    if !params.is_empty() {
+        lines.push(Line::from(vec![Span::styled("Parameters: ", label), Span::raw(params.join(", "))]));
+    }
+    if let Some(path) = &workflow.output.path {
+        lines.push(Line::from(vec![Span::styled("Output: ", label), Span::raw(path.as_str())]));
+    }
+    lines.push(Line::from(""));
+    for (i, step) in workflow.steps.iter().enumerate() {
+        let model = step.model.as_deref().map(|m| format!(" ({})", m)).unwrap_or_default();
+        lines.push(Line::from(Span::styled(
+            format!("{}. {}{}", i + 1, step.name, model),
+            Style::default().fg(Color::Cyan),
+        )));
+    }
+
+    let detail = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(workflow.name.as_str())
+            .border_style(Style::default().fg(Color::DarkGray)),
+    );
+    f.render_widget(detail, area);
+}
+
+fn render_param_prompt(f: &mut Frame, params: &ParamPrompt, area: Rect) {
+    let Some((name, value)) = params.values.get(params.current) else { return };
+    let width = 60.min(area.width);
+    let popup = Rect {
+        x: area.x + area.width.saturating_sub(width) / 2,
+        y: area.y + area.height.saturating_sub(3) / 2,
+        width,
+        height: 3.min(area.height),
+    };
+    f.render_widget(Clear, popup);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("{} ({}/{})", name, params.current + 1, params.values.len()))
+        .border_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+    f.render_widget(Paragraph::new(value.as_str()).block(block), popup);
+
+    let cursor_x = popup.x + 1 + value.chars().count() as u16;
+    f.set_cursor_position((cursor_x.min(popup.right().saturating_sub(2)), popup.y + 1));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::workflows::Workflow;
+    use crate::testing::{render_with, StateBuilder, HEIGHT, WIDTH};
+
+    #[test]
+    fn test_snapshot() {
+        let workflow = Workflow::parse(
+            r#"
+            name = "Document"
+            description = "Write module docs"
+            scope = "module"
+
+            [params]
+            audience = "new contributors"
+
+            [[steps]]
+            name = "draft"
+            prompt = "Document {target} for {audience}.\n\n{code}"
+            "#,
+        )
+        .unwrap();
+        let state = StateBuilder::new()
+            .with(|s| {
+                s.workflows_view.workflows = vec![workflow];
+                s.workflows_view.errors = vec!["broken.toml: missing field `steps`".to_string()];
+            })
+            .build();
+        let text = render_with(WIDTH, HEIGHT, |f| render(f, &state, f.area()));
+        crate::assert_ui_snapshot!("workflows", text);
+    }
+}
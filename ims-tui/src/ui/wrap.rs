@@ -0,0 +1,167 @@
+//! Word Wrapping
+//!
+//! Pre-wraps lines into visual rows for a given width so scroll offsets
+//! and auto-scroll count what is actually on screen. Widths come from
+//! `unicode-width`, so CJK and emoji take two columns.
+
+use ratatui::{
+    style::Style,
+    text::{Line, Span},
+};
+use unicode_width::UnicodeWidthChar;
+
+/// Styled characters of one visual row
+type Row = Vec<(char, Style)>;
+
+fn to_line(row: Row, template: &Line) -> Line<'static> {
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    let mut text = String::new();
+    let mut current: Option<Style> = None;
+
+    for (c, style) in row {
+        if current.is_some_and(|s| s != style) {
+            spans.push(Span::styled(std::mem::take(&mut text), current.unwrap()));
+        }
+        current = Some(style);
+        text.push(c);
+    }
+    if let Some(style) = current {
+        spans.push(Span::styled(text, style));
+    }
+
+    let mut line = Line::from(spans).style(template.style);
+    line.alignment = template.alignment;
+    line
+}
+
+/// Wrap one line at word boundaries; words wider than `width` are split
+fn wrap_line(line: &Line, width: usize, out: &mut Vec<Line<'static>>) {
+    let chars: Vec<(char, Style)> = line
+        .spans
+        .iter()
+        .flat_map(|span| span.content.chars().map(move |c| (c, span.style)))
+        .collect();
+
+    let start = out.len();
+    let mut row: Row = Vec::new();
+    let mut row_width = 0;
+    let mut i = 0;
+
+    // Ends the current row at a wrap point, dropping trailing whitespace
+    let mut break_row = |row: &mut Row| {
+        while row.last().is_some_and(|(c, _)| c.is_whitespace()) {
+            row.pop();
+        }
+        out.push(to_line(std::mem::take(row), line));
+    };
+
+    while i < chars.len() {
+        // Next run of whitespace or non-whitespace
+        let is_space = chars[i].0.is_whitespace();
+        let end = chars[i..]
+            .iter()
+            .position(|(c, _)| c.is_whitespace() != is_space)
+            .map_or(chars.len(), |n| i + n);
+        let word = &chars[i..end];
+        let word_width: usize = word.iter().map(|(c, _)| c.width().unwrap_or(0)).sum();
+        i = end;
+
+        if row_width + word_width <= width {
+            row.extend_from_slice(word);
+            row_width += word_width;
+            continue;
+        }
+
+        if is_space {
+            // Whitespace at a wrap point is dropped
+            if !row.is_empty() {
+                break_row(&mut row);
+                row_width = 0;
+            }
+            continue;
+        }
+
+        if !row.is_empty() && word_width <= width {
+            break_row(&mut row);
+            row_width = 0;
+        }
+        for &(c, style) in word {
+            let w = c.width().unwrap_or(0);
+            if row_width + w > width && !row.is_empty() {
+                break_row(&mut row);
+                row_width = 0;
+            }
+            row.push((c, style));
+            row_width += w;
+        }
+    }
+
+    if !row.is_empty() || out.len() == start {
+        out.push(to_line(row, line));
+    }
+}
+
+/// Patch `style` onto the cells of `row` in display columns `[from, to)`
+pub fn highlight_columns(row: &Line, from: usize, to: usize, style: Style) -> Line<'static> {
+    let mut col = 0;
+    let cells: Row = row
+        .spans
+        .iter()
+        .flat_map(|span| span.content.chars().map(move |c| (c, span.style)))
+        .map(|(c, span_style)| {
+            let inside = col >= from && col < to;
+            col += c.width().unwrap_or(0);
+            (c, if inside { span_style.patch(style) } else { span_style })
+        })
+        .collect();
+    to_line(cells, row)
+}
+
+/// Wrap `lines` into visual rows no wider than `width` columns
+pub fn wrap_lines(lines: &[Line], width: u16) -> Vec<Line<'static>> {
+    let width = width.max(1) as usize;
+    let mut rows = Vec::with_capacity(lines.len());
+    for line in lines {
+        wrap_line(line, width, &mut rows);
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(rows: &[Line]) -> Vec<String> {
+        rows.iter().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn test_wraps_at_word_boundaries() {
+        let rows = wrap_lines(&[Line::from("hello wide world"), Line::from("")], 10);
+        assert_eq!(text(&rows), ["hello wide", "world", ""]);
+    }
+
+    #[test]
+    fn test_splits_long_words_and_counts_wide_chars() {
+        let rows = wrap_lines(&[Line::from("abcdefghij")], 4);
+        assert_eq!(text(&rows), ["abcd", "efgh", "ij"]);
+
+        // Each CJK character is two columns wide
+        let rows = wrap_lines(&[Line::from("日本語テキスト")], 6);
+        assert_eq!(text(&rows), ["日本語", "テキス", "ト"]);
+    }
+
+    #[test]
+    fn test_keeps_span_styles() {
+        let line = Line::from(vec![
+            Span::raw("plain "),
+            Span::styled("bold", Style::default().add_modifier(ratatui::style::Modifier::BOLD)),
+        ]);
+        let rows = wrap_lines(&[line], 6);
+        assert_eq!(text(&rows), ["plain", "bold"]);
+        assert!(rows[1].spans[0]
+            .style
+            .add_modifier
+            .contains(ratatui::style::Modifier::BOLD));
+    }
+}